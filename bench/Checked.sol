@@ -0,0 +1,13 @@
+// SPDX-License-Identifier: MIT
+// Baselines of `bench.toml`, Solidity's checked arithmetic.
+pragma solidity ^0.8.0;
+
+contract Checked {
+    function add(uint256 a, uint256 b) external pure returns (uint256) {
+        return a + b;
+    }
+
+    function mul(uint256 a, uint256 b) external pure returns (uint256) {
+        return a * b;
+    }
+}
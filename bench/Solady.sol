@@ -0,0 +1,20 @@
+// SPDX-License-Identifier: MIT
+// Baselines of `bench.toml`, the Solady counterparts of the benchmarked macros.
+pragma solidity ^0.8.4;
+
+import {FixedPointMathLib} from "solady/utils/FixedPointMathLib.sol";
+import {SafeCastLib} from "solady/utils/SafeCastLib.sol";
+
+contract Solady {
+    function mulWad(uint256 x, uint256 y) external pure returns (uint256) {
+        return FixedPointMathLib.mulWad(x, y);
+    }
+
+    function divWad(uint256 x, uint256 y) external pure returns (uint256) {
+        return FixedPointMathLib.divWad(x, y);
+    }
+
+    function toUint8(uint256 x) external pure returns (uint8) {
+        return SafeCastLib.toUint8(x);
+    }
+}
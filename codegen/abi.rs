@@ -0,0 +1,184 @@
+//! Solidity ABI files, for generating dispatchers with `huffgen dispatcher`.
+
+use std::collections::HashMap;
+use std::io;
+
+use crate::config::invalid;
+use crate::json::{self, Json};
+use crate::libdispatch;
+use crate::profile::Profile;
+
+/// A function of an ABI.
+pub struct AbiFunction {
+    /// Canonical signature, e.g. `transfer(address,uint256)`.
+    pub signature: String,
+    /// Name of the macro implementing the function, e.g. `TRANSFER`.
+    pub target: String,
+    pub payable: bool,
+}
+
+/// The dispatched entries of an ABI.
+pub struct Abi {
+    pub functions: Vec<AbiFunction>,
+    /// Whether the ABI declares a `receive` function.
+    pub receive: bool,
+}
+
+/// Parses an ABI, or a build artifact holding one under `abi`.
+///
+/// Overloaded functions are told apart by suffixing the macro names of later overloads with their
+/// position, e.g. `SAFE_TRANSFER_FROM` then `SAFE_TRANSFER_FROM_2`.
+pub fn parse(src: &str) -> io::Result<Abi> {
+    let json = json::parse(src).map_err(invalid)?;
+    let entries = json
+        .as_array()
+        .or_else(|| json.get("abi").and_then(Json::as_array))
+        .ok_or_else(|| invalid("expected an ABI array, or an object with an `abi` array"))?;
+
+    let mut overloads = HashMap::new();
+    let mut abi = Abi {
+        functions: Vec::new(),
+        receive: false,
+    };
+
+    for entry in entries {
+        match entry.get("type").and_then(Json::as_str) {
+            Some("function") => {
+                let name = entry
+                    .get("name")
+                    .and_then(Json::as_str)
+                    .ok_or_else(|| invalid("function without a `name`"))?;
+                let inputs = match entry.get("inputs") {
+                    None => Vec::new(),
+                    Some(inputs) => inputs
+                        .as_array()
+                        .ok_or_else(|| invalid(format!("`inputs` of `{}` must be an array", name)))?
+                        .iter()
+                        .map(canonical_type)
+                        .collect::<io::Result<Vec<String>>>()?,
+                };
+                let payable = entry.get("stateMutability").and_then(Json::as_str)
+                    == Some("payable")
+                    || entry.get("payable") == Some(&Json::Boolean(true));
+
+                let count = overloads.entry(name.to_string()).or_insert(0);
+                *count += 1;
+                let target = match *count {
+                    1 => macro_name(name),
+                    n => format!("{}_{}", macro_name(name), n),
+                };
+
+                abi.functions.push(AbiFunction {
+                    signature: format!("{}({})", name, inputs.join(",")),
+                    target,
+                    payable,
+                });
+            }
+            Some("receive") => abi.receive = true,
+            _ => {}
+        }
+    }
+
+    if abi.functions.is_empty() {
+        return Err(invalid("the ABI declares no functions"));
+    }
+
+    Ok(abi)
+}
+
+/// Renders a `MAIN` macro dispatching the functions of the ABI `src` with `strategy`, one of
+/// `linear`, `binary` or `jump_table`, through a dispatcher called `name`.
+pub fn render_dispatcher(
+    src: &str,
+    name: &str,
+    strategy: &str,
+    profile: &Profile,
+) -> io::Result<String> {
+    libdispatch::render_abi(&parse(src)?, name, strategy, profile.push0())
+}
+
+/// Converts a camel case name to the upper snake case of Huff macros, e.g. `balanceOf` to
+/// `BALANCE_OF`.
+pub fn macro_name(name: &str) -> String {
+    let mut macro_name = String::new();
+    let mut previous = None::<char>;
+
+    for c in name.chars() {
+        let boundary = previous.is_some_and(|p| p.is_ascii_lowercase() || p.is_ascii_digit());
+        if c.is_ascii_uppercase() && boundary {
+            macro_name.push('_');
+        }
+        macro_name.push(c.to_ascii_uppercase());
+        previous = Some(c);
+    }
+
+    macro_name
+}
+
+/// The canonical type of a parameter, expanding tuples into their components.
+fn canonical_type(param: &Json) -> io::Result<String> {
+    let ty = param
+        .get("type")
+        .and_then(Json::as_str)
+        .ok_or_else(|| invalid("parameter without a `type`"))?;
+
+    match ty.strip_prefix("tuple") {
+        Some(suffix) => {
+            let components = param
+                .get("components")
+                .and_then(Json::as_array)
+                .ok_or_else(|| invalid("tuple parameter without `components`"))?
+                .iter()
+                .map(canonical_type)
+                .collect::<io::Result<Vec<String>>>()?;
+            Ok(format!("({}){}", components.join(","), suffix))
+        }
+        None => Ok(ty.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn macro_names() {
+        assert_eq!(macro_name("balanceOf"), "BALANCE_OF");
+        assert_eq!(macro_name("safeTransferFrom"), "SAFE_TRANSFER_FROM");
+        assert_eq!(macro_name("supportsInterface"), "SUPPORTS_INTERFACE");
+        assert_eq!(macro_name("DOMAIN_SEPARATOR"), "DOMAIN_SEPARATOR");
+        assert_eq!(macro_name("erc20Name"), "ERC20_NAME");
+    }
+
+    #[test]
+    fn signatures() {
+        let abi = parse(
+            r#"{ "abi": [
+                { "type": "function", "name": "f", "inputs": [
+                    { "type": "tuple[2]", "components": [
+                        { "type": "uint8" },
+                        { "type": "tuple", "components": [{ "type": "bytes" }] }
+                    ] },
+                    { "type": "address" }
+                ], "stateMutability": "payable" },
+                { "type": "function", "name": "f", "inputs": [] },
+                { "type": "event", "name": "E", "inputs": [] }
+            ] }"#,
+        )
+        .unwrap();
+
+        let functions = abi
+            .functions
+            .iter()
+            .map(|f| (f.signature.as_str(), f.target.as_str(), f.payable))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            functions,
+            [
+                ("f((uint8,(bytes))[2],address)", "F", true),
+                ("f()", "F_2", false)
+            ]
+        );
+        assert!(!abi.receive);
+    }
+}
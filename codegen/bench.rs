@@ -0,0 +1,413 @@
+//! Benchmarks of the generated libraries against baselines of the same operations, such as the
+//! macros of huffmate or the functions of Solady, each deployed on the embedded EVM and called on
+//! the same inputs to compare their gas and size.
+//!
+//! Operations are read from a config of `[[operation]]` tables:
+//!
+//! ```toml
+//! [[operation]]
+//! name = "checked add"
+//! inputs = [[1, 2], ["0xffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff", 1]]
+//! libhuff = { file = "src/libsafemath.huff", macro = "SAFE_ADD_U256" }
+//!
+//! [[operation.baseline]]
+//! name = "huffmate"
+//! file = "lib/huffmate/src/math/SafeMath.huff"
+//! macro = "SAFE_ADD"
+//!
+//! [[operation.baseline]]
+//! name = "solidity"
+//! solidity = "bench/Checked.sol"
+//! contract = "Checked"
+//! function = "add(uint256,uint256)"
+//! ```
+//!
+//! Macros take the inputs on the stack, top first, and Solidity functions as arguments. Every
+//! implementation of an operation must return the same words or revert, with any data, on each
+//! input.
+
+use std::path::Path;
+use std::process::Command;
+
+use crate::config::{Table, Value};
+use crate::evm::{addr, Call, Evm, Outcome};
+use crate::huff::{self, Program};
+use crate::profile::{self, Profile};
+use crate::u256::U256;
+
+const CALLER: u64 = 0xca11;
+
+const TABLE_HEADER: &str = "\
+| operation | implementation | bytes | gas | bytes delta | gas delta |
+|---|---|---:|---:|---:|---:|
+";
+
+/// The code of an implementation of an operation.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Source {
+    /// A macro of a Huff library, invoked from a `MAIN` loading the inputs from calldata.
+    Huff { file: String, invocation: String },
+    /// A function of a Solidity contract, compiled with the `solc` at `$SOLC` or on the `PATH`.
+    Solidity {
+        file: String,
+        contract: String,
+        function: String,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Implementation {
+    pub name: String,
+    pub source: Source,
+}
+
+/// An operation, implemented by the library and its baselines.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Operation {
+    pub name: String,
+    pub inputs: Vec<Vec<U256>>,
+    /// The implementations, the library's first.
+    pub implementations: Vec<Implementation>,
+}
+
+/// The cost of an implementation of an operation.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Measurement {
+    pub operation: String,
+    pub implementation: String,
+    /// Bytes of the deployed code and the mean gas of a call over the inputs, or why the
+    /// implementation was skipped.
+    pub cost: Result<(usize, u64), String>,
+}
+
+/// The operations of `config`.
+pub fn parse(config: &Table) -> Result<Vec<Operation>, String> {
+    let operations = match config.get("operation") {
+        Some(value) => value
+            .as_array()
+            .ok_or("`operation` must be an array of tables")?,
+        None => return Ok(Vec::new()),
+    };
+
+    operations
+        .iter()
+        .map(|operation| {
+            let operation = operation
+                .as_table()
+                .ok_or("`operation` must be an array of tables")?;
+            let name = string(operation, "name", "operation")?;
+            let context = format!("operation `{}`", name);
+
+            let inputs = operation
+                .get("inputs")
+                .and_then(Value::as_array)
+                .ok_or(format!("{} requires an array of `inputs`", context))?
+                .iter()
+                .map(|input| {
+                    input
+                        .as_array()
+                        .and_then(|words| words.iter().map(word).collect())
+                })
+                .collect::<Option<Vec<Vec<U256>>>>()
+                .ok_or(format!(
+                    "the `inputs` of {} must be arrays of words",
+                    context
+                ))?;
+
+            let libhuff = operation
+                .get("libhuff")
+                .and_then(Value::as_table)
+                .ok_or(format!("{} requires a `libhuff` table", context))?;
+            let mut implementations = vec![Implementation {
+                name: "libhuff".to_string(),
+                source: source(libhuff, &context)?,
+            }];
+
+            let baselines = match operation.get("baseline") {
+                Some(value) => value
+                    .as_array()
+                    .ok_or("`baseline` must be an array of tables")?,
+                None => &[],
+            };
+            for baseline in baselines {
+                let baseline = baseline
+                    .as_table()
+                    .ok_or("`baseline` must be an array of tables")?;
+                let name = string(baseline, "name", &format!("a baseline of {}", context))?;
+                let context = format!("baseline `{}` of {}", name, context);
+                implementations.push(Implementation {
+                    name,
+                    source: source(baseline, &context)?,
+                });
+            }
+
+            Ok(Operation {
+                name,
+                inputs,
+                implementations,
+            })
+        })
+        .collect()
+}
+
+fn string(table: &Table, key: &str, context: &str) -> Result<String, String> {
+    match table.get(key).map(Value::as_str) {
+        Some(Some(value)) => Ok(value.to_string()),
+        _ => Err(format!("{} requires a string `{}`", context, key)),
+    }
+}
+
+fn word(value: &Value) -> Option<U256> {
+    match value {
+        Value::Integer(i) => U256::from_dec(&i.to_string()),
+        Value::String(s) => match s.strip_prefix("0x") {
+            Some(hex) => U256::from_hex(hex),
+            None => U256::from_dec(s),
+        },
+        _ => None,
+    }
+}
+
+fn source(table: &Table, context: &str) -> Result<Source, String> {
+    match table.get("solidity") {
+        Some(_) => Ok(Source::Solidity {
+            file: string(table, "solidity", context)?,
+            contract: string(table, "contract", context)?,
+            function: string(table, "function", context)?,
+        }),
+        None => {
+            let invocation = string(table, "macro", context)?;
+            Ok(Source::Huff {
+                file: string(table, "file", context)?,
+                invocation: match invocation.contains('(') {
+                    true => invocation,
+                    false => format!("{}()", invocation),
+                },
+            })
+        }
+    }
+}
+
+/// Deploys each implementation of `operations` on the embedded EVM and calls it on the inputs of
+/// its operation, the macros compiled for `profile`, paths resolved against the working directory.
+///
+/// Baselines that cannot be built, such as those of libraries that are not installed, are skipped.
+/// Fails if the library cannot be built, or if an implementation returns other words than the
+/// library's, or reverts where it does not.
+pub fn measure(operations: &[Operation], profile: &Profile) -> Result<Vec<Measurement>, String> {
+    let mut measurements = Vec::new();
+    for operation in operations {
+        let mut expected: Option<Vec<(bool, Vec<u8>)>> = None;
+        for (i, implementation) in operation.implementations.iter().enumerate() {
+            let built = build(&implementation.source, profile);
+            let (code, selector) = match (built, i) {
+                (Ok(built), _) => built,
+                (Err(e), 0) => return Err(format!("libhuff of `{}`: {}", operation.name, e)),
+                (Err(e), _) => {
+                    measurements.push(Measurement {
+                        operation: operation.name.clone(),
+                        implementation: implementation.name.clone(),
+                        cost: Err(e),
+                    });
+                    continue;
+                }
+            };
+
+            let (size, outcomes) = execute(&code, selector, &operation.inputs)
+                .map_err(|e| format!("{} of `{}`: {}", implementation.name, operation.name, e))?;
+            let results = outcomes
+                .iter()
+                .map(|outcome| (outcome.success(), outcome.output.clone()))
+                .map(|(success, output)| (success, if success { output } else { Vec::new() }))
+                .collect::<Vec<_>>();
+            let expected = expected.get_or_insert_with(|| results.clone());
+            if let Some(case) = (0..results.len()).find(|&case| results[case] != expected[case]) {
+                let inputs = operation.inputs[case].iter().map(|word| word.to_hex());
+                let inputs = inputs.collect::<Vec<String>>();
+                return Err(format!(
+                    "{} of `{}` differs from libhuff on [{}]",
+                    implementation.name,
+                    operation.name,
+                    inputs.join(", ")
+                ));
+            }
+
+            let gas = outcomes.iter().map(|outcome| outcome.gas_used).sum::<u64>();
+            measurements.push(Measurement {
+                operation: operation.name.clone(),
+                implementation: implementation.name.clone(),
+                cost: Ok((size, gas / outcomes.len().max(1) as u64)),
+            });
+        }
+    }
+    Ok(measurements)
+}
+
+/// The deployment bytecode of `source` and the selector its calls start with.
+fn build(source: &Source, profile: &Profile) -> Result<(Vec<u8>, [u8; 4]), String> {
+    match source {
+        Source::Huff { file, invocation } => {
+            let path = Path::new(file);
+            let src = std::fs::read_to_string(path).map_err(|e| format!("{}: {}", file, e))?;
+            let name = invocation.split('(').next().unwrap_or_default();
+            let program = Program::parse_file(&src, path).map_err(|e| e.msg)?;
+            let definition = program
+                .macros
+                .get(name)
+                .ok_or(format!("{} does not define `{}`", file, name))?;
+
+            let main = main(path, invocation, definition.takes, definition.returns)?;
+            let code = profile::compile_deployment(main, "MAIN", profile).map_err(|e| e.msg)?;
+            Ok((code, [0; 4]))
+        }
+        Source::Solidity {
+            file,
+            contract,
+            function,
+        } => Ok((solc(file, contract)?, huff::selector(function))),
+    }
+}
+
+/// A `MAIN` invoking `invocation` of the library at `file` on the `takes` words of the calldata
+/// after the selector, the first on top, and returning its `returns` outputs, top first.
+fn main(file: &Path, invocation: &str, takes: usize, returns: usize) -> Result<Program, String> {
+    let offset = |i: usize, base: usize| U256::from_u64((base + 32 * i) as u64).to_hex();
+    let loads = (0..takes)
+        .rev()
+        .map(|i| format!("{} calldataload\n    ", offset(i, 4)));
+    let stores = (0..returns).map(|i| format!("{} mstore\n    ", offset(i, 0)));
+    let body = format!(
+        "{}{}\n    {}{} 0x00 return",
+        loads.collect::<String>(),
+        invocation,
+        stores.collect::<String>(),
+        offset(returns, 0),
+    );
+    let src = format!(
+        "#include \"{}\"\n\n#define macro MAIN() = takes (0) returns (0) {{\n    {}\n}}\n",
+        file.file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or_default(),
+        body,
+    );
+    Program::parse_str(&src, file.parent().unwrap_or(Path::new("."))).map_err(|e| e.msg)
+}
+
+/// The deployment bytecode of `contract` in the Solidity source `file`, compiled with the
+/// remappings of `remappings.txt`.
+fn solc(file: &str, contract: &str) -> Result<Vec<u8>, String> {
+    let solc = std::env::var("SOLC").unwrap_or_else(|_| "solc".to_string());
+    let remappings = std::fs::read_to_string("remappings.txt").unwrap_or_default();
+    let output = Command::new(&solc)
+        .args(remappings.split_whitespace())
+        .args(["--bin", "--optimize", "--base-path", "."])
+        .arg(file)
+        .output()
+        .map_err(|_| format!("{} not found", solc))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!(
+            "{} failed: {}",
+            solc,
+            stderr.lines().next().unwrap_or_default()
+        ));
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let header = format!(":{} =======", contract);
+    let hex = stdout
+        .split("======= ")
+        .find(|section| {
+            section
+                .lines()
+                .next()
+                .is_some_and(|line| line.ends_with(&header[..]))
+        })
+        .and_then(|section| section.split("Binary:").nth(1))
+        .and_then(|binary| binary.split_whitespace().next())
+        .ok_or(format!("{} does not define `{}`", file, contract))?;
+    bytes(hex).ok_or(format!(
+        "{} returned invalid bytecode for `{}`",
+        solc, contract
+    ))
+}
+
+fn bytes(hex: &str) -> Option<Vec<u8>> {
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+/// Deploys `code` on a fresh EVM and calls it with `selector` and each of `inputs`, returning the
+/// bytes of the deployed code and the outcome of each call.
+fn execute(
+    code: &[u8],
+    selector: [u8; 4],
+    inputs: &[Vec<U256>],
+) -> Result<(usize, Vec<Outcome>), String> {
+    let mut evm = Evm::new();
+    let (deployment, address) = evm.create(addr(CALLER), code, 30_000_000);
+    if !deployment.success() {
+        return Err(format!("deployment halted with {:?}", deployment.halt));
+    }
+
+    let outcomes = inputs
+        .iter()
+        .map(|input| {
+            let words = input.iter().flat_map(|word| word.to_be_bytes());
+            evm.transact(Call {
+                caller: addr(CALLER),
+                address,
+                code_address: address,
+                value: U256::ZERO,
+                data: selector.into_iter().chain(words).collect(),
+                gas: 30_000_000,
+                is_static: false,
+                transfer: false,
+            })
+        })
+        .collect();
+    Ok((deployment.output.len(), outcomes))
+}
+
+/// Renders `measurements` as a Markdown table, with the deltas of each baseline to the library,
+/// and the skipped baselines after it.
+pub fn markdown(measurements: &[Measurement]) -> String {
+    let mut out = TABLE_HEADER.to_string();
+    let mut library = (0, 0);
+    for measurement in measurements {
+        let (size, gas) = match measurement.cost {
+            Ok(cost) => cost,
+            Err(_) => continue,
+        };
+        let deltas = match measurement.implementation.as_str() {
+            "libhuff" => {
+                library = (size, gas);
+                "- | -".to_string()
+            }
+            _ => format!(
+                "{:+} | {:+}",
+                size as i64 - library.0 as i64,
+                gas as i64 - library.1 as i64
+            ),
+        };
+        out.push_str(&format!(
+            "| {} | {} | {} | {} | {} |\n",
+            measurement.operation, measurement.implementation, size, gas, deltas
+        ));
+    }
+
+    let skipped = measurements.iter().filter_map(|measurement| {
+        let reason = measurement.cost.as_ref().err()?;
+        Some(format!(
+            "- {} of `{}`: {}\n",
+            measurement.implementation, measurement.operation, reason
+        ))
+    });
+    let skipped = skipped.collect::<String>();
+    if !skipped.is_empty() {
+        out.push_str(&format!("\nSkipped:\n\n{}", skipped));
+    }
+    out
+}
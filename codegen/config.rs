@@ -0,0 +1,453 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+
+/// Path of the generation config, relative to the working directory.
+pub const CONFIG_PATH: &str = "libhuff.toml";
+
+/// A config value.
+///
+/// Only the subset of TOML the generators need is supported: strings, integers, booleans, arrays,
+/// tables, arrays of tables, and inline tables.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    String(String),
+    Integer(u128),
+    Boolean(bool),
+    Array(Vec<Value>),
+    Table(Table),
+}
+
+pub type Table = BTreeMap<String, Value>;
+
+impl Value {
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_integer(&self) -> Option<u128> {
+        match self {
+            Value::Integer(i) => Some(*i),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&[Value]> {
+        match self {
+            Value::Array(a) => Some(a),
+            _ => None,
+        }
+    }
+
+    pub fn as_table(&self) -> Option<&Table> {
+        match self {
+            Value::Table(t) => Some(t),
+            _ => None,
+        }
+    }
+}
+
+/// Renders `table` as an inline TOML table, keys in order, the same for equal tables.
+pub fn canonical(table: &Table) -> String {
+    let entries = table
+        .iter()
+        .map(|(key, value)| format!("{} = {}", quote(key), canonical_value(value)))
+        .collect::<Vec<String>>();
+
+    format!("{{{}}}", entries.join(", "))
+}
+
+fn canonical_value(value: &Value) -> String {
+    match value {
+        Value::String(s) => quote(s),
+        Value::Integer(i) => i.to_string(),
+        Value::Boolean(b) => b.to_string(),
+        Value::Array(items) => {
+            let items = items.iter().map(canonical_value).collect::<Vec<String>>();
+            format!("[{}]", items.join(", "))
+        }
+        Value::Table(table) => canonical(table),
+    }
+}
+
+fn quote(s: &str) -> String {
+    let mut quoted = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\t' => quoted.push_str("\\t"),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Loads the config at `CONFIG_PATH`, or an empty table if there is none.
+pub fn load() -> io::Result<Table> {
+    match fs::read_to_string(CONFIG_PATH) {
+        Ok(src) => parse(&src).map_err(|e| invalid(format!("{}: {}", CONFIG_PATH, e))),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Table::new()),
+        Err(e) => Err(e),
+    }
+}
+
+/// Builds an `InvalidData` error, used for malformed config throughout the generators.
+pub fn invalid(msg: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg.into())
+}
+
+/// Reads `key` of `table` as a Huff identifier, naming `context` in the error if it is missing.
+pub fn identifier(table: &Table, key: &str, context: &str) -> io::Result<String> {
+    let name = table
+        .get(key)
+        .and_then(Value::as_str)
+        .ok_or_else(|| invalid(format!("{} requires a `{}`", context, key)))?;
+
+    let valid = name.chars().next().is_some_and(|c| c.is_ascii_alphabetic())
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+
+    if !valid {
+        return Err(invalid(format!("`{}` is not a valid identifier", name)));
+    }
+
+    Ok(name.to_string())
+}
+
+/// Reads `key` of the `[generate]` table of the config, the defaults of `huffgen generate`, as a
+/// string, `None` if either is missing.
+pub fn generate_string<'a>(config: &'a Table, key: &str) -> io::Result<Option<&'a str>> {
+    match generate_value(config, key)? {
+        None => Ok(None),
+        Some(Value::String(s)) => Ok(Some(s)),
+        Some(_) => Err(invalid(format!("`generate.{}` must be a string", key))),
+    }
+}
+
+/// Reads `key` of the `[generate]` table of the config, `None` if either is missing.
+pub fn generate_value<'a>(config: &'a Table, key: &str) -> io::Result<Option<&'a Value>> {
+    match config.get("generate") {
+        None => Ok(None),
+        Some(Value::Table(generate)) => Ok(generate.get(key)),
+        Some(_) => Err(invalid("`generate` must be a table")),
+    }
+}
+
+/// Parses a config source.
+pub fn parse(src: &str) -> Result<Table, String> {
+    let mut parser = Parser {
+        chars: src.chars().collect(),
+        pos: 0,
+        line: 1,
+    };
+    parser.document()
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+    line: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += 1;
+        if c == '\n' {
+            self.line += 1;
+        }
+        Some(c)
+    }
+
+    fn error<T>(&self, msg: &str) -> Result<T, String> {
+        Err(format!("line {}: {}", self.line, msg))
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), String> {
+        match self.bump() {
+            Some(found) if found == c => Ok(()),
+            Some(found) => self.error(&format!("expected `{}`, found `{}`", c, found)),
+            None => self.error(&format!("expected `{}`, found end of file", c)),
+        }
+    }
+
+    /// Skips spaces, tabs and comments, and newlines if `newlines` is set.
+    fn skip(&mut self, newlines: bool) {
+        while let Some(c) = self.peek() {
+            match c {
+                ' ' | '\t' | '\r' => {
+                    self.bump();
+                }
+                '\n' if newlines => {
+                    self.bump();
+                }
+                '#' => {
+                    while !matches!(self.peek(), None | Some('\n')) {
+                        self.bump();
+                    }
+                }
+                _ => break,
+            }
+        }
+    }
+
+    fn end_of_line(&mut self) -> Result<(), String> {
+        self.skip(false);
+        match self.peek() {
+            None | Some('\n') => Ok(()),
+            Some(c) => self.error(&format!("unexpected `{}` after value", c)),
+        }
+    }
+
+    fn document(&mut self) -> Result<Table, String> {
+        let mut root = Table::new();
+        let mut current: Vec<String> = Vec::new();
+        loop {
+            self.skip(true);
+            match self.peek() {
+                None => return Ok(root),
+                Some('[') => {
+                    self.bump();
+                    let is_array = self.peek() == Some('[');
+                    if is_array {
+                        self.bump();
+                    }
+                    let path = self.key_path()?;
+                    self.expect(']')?;
+                    if is_array {
+                        self.expect(']')?;
+                    }
+                    self.end_of_line()?;
+                    let line = self.line;
+                    let (last, parents) = path.split_last().unwrap();
+                    let parent = table_at(&mut root, parents).map_err(|e| at(line, e))?;
+                    if is_array {
+                        let entry = parent
+                            .entry(last.clone())
+                            .or_insert_with(|| Value::Array(Vec::new()));
+                        match entry {
+                            Value::Array(a) => a.push(Value::Table(Table::new())),
+                            _ => return self.error(&format!("`{}` is not an array", last)),
+                        }
+                    } else {
+                        match parent.get(last) {
+                            None => {
+                                parent.insert(last.clone(), Value::Table(Table::new()));
+                            }
+                            Some(Value::Table(_)) => {}
+                            Some(_) => return self.error(&format!("`{}` is not a table", last)),
+                        }
+                    }
+                    current = path;
+                }
+                Some(_) => {
+                    let key = self.key()?;
+                    self.skip(false);
+                    self.expect('=')?;
+                    self.skip(false);
+                    let value = self.value()?;
+                    self.end_of_line()?;
+                    let line = self.line;
+                    let table = table_at(&mut root, &current).map_err(|e| at(line, e))?;
+                    if table.insert(key.clone(), value).is_some() {
+                        return self.error(&format!("duplicate key `{}`", key));
+                    }
+                }
+            }
+        }
+    }
+
+    fn key(&mut self) -> Result<String, String> {
+        if self.peek() == Some('"') {
+            return self.string();
+        }
+        let start = self.pos;
+        while matches!(self.peek(), Some(c) if c.is_ascii_alphanumeric() || c == '_' || c == '-') {
+            self.bump();
+        }
+        if start == self.pos {
+            return self.error("expected key");
+        }
+        Ok(self.chars[start..self.pos].iter().collect())
+    }
+
+    fn key_path(&mut self) -> Result<Vec<String>, String> {
+        let mut path = Vec::new();
+        loop {
+            self.skip(false);
+            path.push(self.key()?);
+            self.skip(false);
+            if self.peek() != Some('.') {
+                return Ok(path);
+            }
+            self.bump();
+        }
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        let quote = self.bump().unwrap();
+        let mut out = String::new();
+        loop {
+            match self.bump() {
+                None | Some('\n') => return self.error("unterminated string"),
+                Some(c) if c == quote => return Ok(out),
+                Some('\\') if quote == '"' => match self.bump() {
+                    Some('n') => out.push('\n'),
+                    Some('t') => out.push('\t'),
+                    Some('"') => out.push('"'),
+                    Some('\\') => out.push('\\'),
+                    _ => return self.error("unsupported escape sequence"),
+                },
+                Some(c) => out.push(c),
+            }
+        }
+    }
+
+    fn value(&mut self) -> Result<Value, String> {
+        match self.peek() {
+            Some('"') | Some('\'') => Ok(Value::String(self.string()?)),
+            Some('[') => {
+                self.bump();
+                let mut items = Vec::new();
+                loop {
+                    self.skip(true);
+                    if self.peek() == Some(']') {
+                        self.bump();
+                        return Ok(Value::Array(items));
+                    }
+                    items.push(self.value()?);
+                    self.skip(true);
+                    match self.peek() {
+                        Some(',') => {
+                            self.bump();
+                        }
+                        Some(']') => {}
+                        _ => return self.error("expected `,` or `]` in array"),
+                    }
+                }
+            }
+            Some('{') => {
+                self.bump();
+                let mut table = Table::new();
+                loop {
+                    self.skip(false);
+                    if self.peek() == Some('}') {
+                        self.bump();
+                        return Ok(Value::Table(table));
+                    }
+                    let key = self.key()?;
+                    self.skip(false);
+                    self.expect('=')?;
+                    self.skip(false);
+                    let value = self.value()?;
+                    if table.insert(key.clone(), value).is_some() {
+                        return self.error(&format!("duplicate key `{}`", key));
+                    }
+                    self.skip(false);
+                    match self.peek() {
+                        Some(',') => {
+                            self.bump();
+                        }
+                        Some('}') => {}
+                        _ => return self.error("expected `,` or `}` in inline table"),
+                    }
+                }
+            }
+            Some(c) if c.is_ascii_alphanumeric() || c == '+' || c == '_' => {
+                let start = self.pos;
+                while matches!(self.peek(), Some(c) if c.is_ascii_alphanumeric() || c == '_' || c == '+')
+                {
+                    self.bump();
+                }
+                let word: String = self.chars[start..self.pos].iter().collect();
+                match word.as_str() {
+                    "true" => Ok(Value::Boolean(true)),
+                    "false" => Ok(Value::Boolean(false)),
+                    _ => {
+                        let digits = word.trim_start_matches('+').replace('_', "");
+                        let parsed = match digits.strip_prefix("0x") {
+                            Some(hex) => u128::from_str_radix(hex, 16),
+                            None => digits.parse(),
+                        };
+                        match parsed {
+                            Ok(i) => Ok(Value::Integer(i)),
+                            Err(_) => self.error(&format!("invalid value `{}`", word)),
+                        }
+                    }
+                }
+            }
+            _ => self.error("expected value"),
+        }
+    }
+}
+
+fn at(line: usize, msg: String) -> String {
+    format!("line {}: {}", line, msg)
+}
+
+/// Resolves the table at `path`, creating intermediate tables and descending into the last entry
+/// of arrays of tables.
+fn table_at<'a>(root: &'a mut Table, path: &[String]) -> Result<&'a mut Table, String> {
+    let mut table = root;
+    for key in path {
+        let entry = table
+            .entry(key.clone())
+            .or_insert_with(|| Value::Table(Table::new()));
+        table = match entry {
+            Value::Table(t) => t,
+            Value::Array(a) => match a.last_mut() {
+                Some(Value::Table(t)) => t,
+                _ => return Err(format!("`{}` is not an array of tables", key)),
+            },
+            _ => return Err(format!("`{}` is not a table", key)),
+        };
+    }
+    Ok(table)
+}
+
+/// EVM hardfork targeted by the generated code.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum EvmVersion {
+    Paris,
+    Shanghai,
+    Cancun,
+}
+
+impl EvmVersion {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "paris" => Some(EvmVersion::Paris),
+            "shanghai" => Some(EvmVersion::Shanghai),
+            "cancun" => Some(EvmVersion::Cancun),
+            _ => None,
+        }
+    }
+
+    /// The name of the hardfork, as given to `evm_version`.
+    pub fn name(self) -> &'static str {
+        match self {
+            EvmVersion::Paris => "paris",
+            EvmVersion::Shanghai => "shanghai",
+            EvmVersion::Cancun => "cancun",
+        }
+    }
+}
+
+/// Reads the `evm_version` key of the config, defaulting to Shanghai.
+pub fn evm_version(config: &Table) -> io::Result<EvmVersion> {
+    match config.get("evm_version") {
+        None => Ok(EvmVersion::Shanghai),
+        Some(value) => value.as_str().and_then(EvmVersion::parse).ok_or_else(|| {
+            invalid("`evm_version` must be one of \"paris\", \"shanghai\", \"cancun\"")
+        }),
+    }
+}
@@ -0,0 +1,234 @@
+//! Warnings and errors of generation, located at the config keys they come from.
+
+use crate::config::CONFIG_PATH;
+use crate::manifest::{optional, string};
+
+/// How severe a diagnostic is.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Severity {
+    /// Generation succeeds, but likely not as intended.
+    Warning,
+    /// Generation fails.
+    Error,
+}
+
+impl Severity {
+    pub fn name(self) -> &'static str {
+        match self {
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        }
+    }
+}
+
+/// A warning or error of generation.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub message: String,
+    /// The config key the diagnostic comes from, `generate.sizes`, if any.
+    pub key: Option<String>,
+    /// The line of the key in the config, if it is set there, or of the config the diagnostic is
+    /// at.
+    pub line: Option<usize>,
+}
+
+impl Diagnostic {
+    pub fn warning(message: impl Into<String>) -> Self {
+        Diagnostic {
+            severity: Severity::Warning,
+            message: message.into(),
+            key: None,
+            line: None,
+        }
+    }
+
+    pub fn error(message: impl Into<String>) -> Self {
+        Diagnostic {
+            severity: Severity::Error,
+            message: message.into(),
+            key: None,
+            line: None,
+        }
+    }
+
+    /// The error `message` of a command, located in `config`, the source of the config, if it is
+    /// about the config: prefixed with its path, and giving the line or naming the key it is at.
+    pub fn from_message(message: &str, config: &str) -> Self {
+        let Some(message) = message.strip_prefix(&format!("{}: ", CONFIG_PATH)) else {
+            return Diagnostic::error(message);
+        };
+
+        // parse errors give their line, `line 4: expected value`
+        let parsed = message.strip_prefix("line ").and_then(|rest| {
+            let (line, message) = rest.split_once(": ")?;
+            Some((line.parse::<usize>().ok()?, message))
+        });
+        if let Some((line, message)) = parsed {
+            return Diagnostic {
+                line: Some(line),
+                ..Diagnostic::error(message)
+            };
+        }
+
+        match Diagnostic::located(message, config) {
+            diagnostic if diagnostic.key.is_some() => diagnostic,
+            _ => Diagnostic::error(format!("{}: {}", CONFIG_PATH, message)),
+        }
+    }
+
+    /// The error `message`, located at the first key of `config` it names in backticks, such as
+    /// `` `evm_version` must be one of ``, if any.
+    pub fn located(message: &str, config: &str) -> Self {
+        let key = message
+            .split('`')
+            .skip(1)
+            .step_by(2)
+            .find(|key| locate(config, key).is_some());
+        match key {
+            Some(key) => Diagnostic::error(message).with_key(key).locate(config),
+            None => Diagnostic::error(message),
+        }
+    }
+
+    /// Attributes the diagnostic to the config key `key`, `generate.sizes`.
+    pub fn with_key(mut self, key: &str) -> Self {
+        self.key = Some(key.to_string());
+        self
+    }
+
+    /// Locates the key of the diagnostic in `src`, the source of the config.
+    pub fn locate(mut self, src: &str) -> Self {
+        self.line = self.key.as_deref().and_then(|key| locate(src, key));
+        self
+    }
+
+    /// The diagnostic as printed for people, located below the message.
+    pub fn render(&self) -> String {
+        let mut rendered = format!("{}: {}\n", self.severity.name(), self.message);
+        match (&self.key, self.line) {
+            (Some(key), Some(line)) => {
+                rendered.push_str(&format!("  --> {}:{} `{}`\n", CONFIG_PATH, line, key))
+            }
+            (Some(key), None) => rendered.push_str(&format!("  --> `{}`\n", key)),
+            (None, Some(line)) => rendered.push_str(&format!("  --> {}:{}\n", CONFIG_PATH, line)),
+            _ => {}
+        }
+        rendered
+    }
+
+    /// The diagnostic as a JSON object on a line, for tools.
+    pub fn to_json(&self) -> String {
+        let key = self.key.as_deref().map(string);
+        let file = self.line.map(|_| string(CONFIG_PATH));
+        format!(
+            "{{\"severity\": {}, \"message\": {}, \"key\": {}, \"file\": {}, \"line\": {}}}\n",
+            string(self.severity.name()),
+            string(&self.message),
+            optional(key),
+            optional(file),
+            optional(self.line),
+        )
+    }
+}
+
+/// The line of `src`, a TOML document, setting the dotted `key`, from its table header or a
+/// dotted key, if any.
+fn locate(src: &str, key: &str) -> Option<usize> {
+    let mut table = String::new();
+    for (i, line) in src.lines().enumerate() {
+        let line = line.trim();
+        if let Some(header) = line.strip_prefix('[') {
+            let header = header.trim_start_matches('[');
+            table = header
+                .split(']')
+                .next()
+                .unwrap_or_default()
+                .trim()
+                .to_string();
+            // a table declared by the key itself, `[generate.naming]`
+            if table == key {
+                return Some(i + 1);
+            }
+            continue;
+        }
+
+        let Some((name, _)) = line.split_once('=') else {
+            continue;
+        };
+        let name = name
+            .split('.')
+            .map(str::trim)
+            .collect::<Vec<&str>>()
+            .join(".");
+        let name = if table.is_empty() {
+            name
+        } else {
+            format!("{}.{}", table, name)
+        };
+        if name == key {
+            return Some(i + 1);
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn locations() {
+        let src = "evm_version = \"paris\"\n\n[generate]\nsizes = [8, 12]\n\n[generate.naming]\n";
+        assert_eq!(locate(src, "evm_version"), Some(1));
+        assert_eq!(locate(src, "generate.sizes"), Some(4));
+        assert_eq!(locate(src, "generate.naming"), Some(6));
+        assert_eq!(locate(src, "generate.layout"), None);
+        assert_eq!(
+            locate("generate . sizes = [8]\n", "generate.sizes"),
+            Some(1)
+        );
+
+        let diagnostic = Diagnostic::error("invalid size `12`").with_key("generate.sizes");
+        assert_eq!(
+            diagnostic.render(),
+            "error: invalid size `12`\n  --> `generate.sizes`\n"
+        );
+        let diagnostic = diagnostic.locate(src);
+        assert_eq!(
+            diagnostic.render(),
+            "error: invalid size `12`\n  --> libhuff.toml:4 `generate.sizes`\n",
+        );
+        assert_eq!(
+            diagnostic.to_json(),
+            "{\"severity\": \"error\", \"message\": \"invalid size `12`\", \
+             \"key\": \"generate.sizes\", \"file\": \"libhuff.toml\", \"line\": 4}\n",
+        );
+
+        // the errors of commands are located if they are about the config
+        let error = |message: &str| Diagnostic::from_message(message, src).render();
+        assert_eq!(
+            error("libhuff.toml: `evm_version` must be one of \"paris\", \"shanghai\""),
+            "error: `evm_version` must be one of \"paris\", \"shanghai\"\n  \
+             --> libhuff.toml:1 `evm_version`\n",
+        );
+        assert_eq!(
+            error("libhuff.toml: line 2: expected value"),
+            "error: expected value\n  --> libhuff.toml:2\n",
+        );
+        assert_eq!(
+            error("libhuff.toml: `opcodes.deny` is unknown"),
+            "error: libhuff.toml: `opcodes.deny` is unknown\n",
+        );
+        assert_eq!(
+            error("lib.huff:3: unknown opcode"),
+            "error: lib.huff:3: unknown opcode\n"
+        );
+        let generated = "generated `libcast.huff` does not parse";
+        assert_eq!(
+            Diagnostic::located(generated, src),
+            Diagnostic::error(generated)
+        );
+    }
+}
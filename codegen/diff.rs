@@ -0,0 +1,221 @@
+//! Line-based unified diffs, for reporting generated files that are out of date.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Lines of unchanged context around each hunk.
+const CONTEXT: usize = 3;
+
+/// Largest number of line pairs compared after trimming the common prefix and suffix, beyond which
+/// the differing middle is reported as replaced outright.
+const MAX_CELLS: usize = 1 << 22;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Op {
+    Equal,
+    Delete,
+    Insert,
+}
+
+/// Renders the unified diff from `old` to `new`, labelled with `path`, or `None` if they are equal.
+pub fn unified(path: &str, old: &str, new: &str) -> Option<String> {
+    if old == new {
+        return None;
+    }
+
+    let old = old.lines().collect::<Vec<&str>>();
+    let new = new.lines().collect::<Vec<&str>>();
+    let edits = edits(&old, &new);
+
+    let mut diff = format!("--- a/{}\n+++ b/{}\n", path, path);
+    for hunk in hunks(&edits) {
+        diff.push_str(&render_hunk(&edits[hunk.0..hunk.1], &old, &new));
+    }
+
+    Some(diff)
+}
+
+/// Counts the lines inserted and deleted from `old` to `new`.
+pub fn stat(old: &str, new: &str) -> (usize, usize) {
+    let old = old.lines().collect::<Vec<&str>>();
+    let new = new.lines().collect::<Vec<&str>>();
+    let edits = edits(&old, &new);
+    let count = |op| edits.iter().filter(|(edit, _, _)| *edit == op).count();
+    (count(Op::Insert), count(Op::Delete))
+}
+
+/// Renders the unified diff from the file at `path` to `contents`, a missing file diffed as empty.
+pub fn against_file(path: &Path, contents: &str) -> io::Result<Option<String>> {
+    let old = match fs::read_to_string(path) {
+        Ok(old) => old,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e),
+    };
+
+    Ok(unified(&path.display().to_string(), &old, contents))
+}
+
+/// An edit script of `(op, old index, new index)`, the indices of the lines before the edit.
+fn edits(old: &[&str], new: &[&str]) -> Vec<(Op, usize, usize)> {
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let old_middle = &old[prefix..old.len() - suffix];
+    let new_middle = &new[prefix..new.len() - suffix];
+
+    let mut edits = (0..prefix).map(|i| (Op::Equal, i, i)).collect::<Vec<_>>();
+    for (op, i, j) in middle(old_middle, new_middle) {
+        edits.push((op, prefix + i, prefix + j));
+    }
+    for k in 0..suffix {
+        edits.push((Op::Equal, old.len() - suffix + k, new.len() - suffix + k));
+    }
+
+    edits
+}
+
+/// A shortest edit script between lines with no common prefix or suffix, from their longest
+/// common subsequence.
+fn middle(old: &[&str], new: &[&str]) -> Vec<(Op, usize, usize)> {
+    let (n, m) = (old.len(), new.len());
+
+    if n * m > MAX_CELLS {
+        let deletes = (0..n).map(|i| (Op::Delete, i, 0));
+        let inserts = (0..m).map(|j| (Op::Insert, n, j));
+        return deletes.chain(inserts).collect();
+    }
+
+    // lengths[i][j] is the length of the longest common subsequence of old[i..] and new[j..]
+    let mut lengths = vec![vec![0u32; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lengths[i][j] = if old[i] == new[j] {
+                lengths[i + 1][j + 1] + 1
+            } else {
+                lengths[i + 1][j].max(lengths[i][j + 1])
+            };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    let mut edits = Vec::new();
+    while i < n || j < m {
+        if i < n && j < m && old[i] == new[j] {
+            edits.push((Op::Equal, i, j));
+            i += 1;
+            j += 1;
+        } else if j == m || (i < n && lengths[i + 1][j] >= lengths[i][j + 1]) {
+            edits.push((Op::Delete, i, j));
+            i += 1;
+        } else {
+            edits.push((Op::Insert, i, j));
+            j += 1;
+        }
+    }
+
+    edits
+}
+
+/// Ranges of the edit script to render as hunks, each change with its surrounding context.
+fn hunks(edits: &[(Op, usize, usize)]) -> Vec<(usize, usize)> {
+    let mut hunks: Vec<(usize, usize)> = Vec::new();
+
+    for (k, edit) in edits.iter().enumerate() {
+        if edit.0 == Op::Equal {
+            continue;
+        }
+
+        let start = k.saturating_sub(CONTEXT);
+        let end = (k + 1 + CONTEXT).min(edits.len());
+        match hunks.last_mut() {
+            Some(last) if start <= last.1 => last.1 = end,
+            _ => hunks.push((start, end)),
+        }
+    }
+
+    hunks
+}
+
+fn render_hunk(edits: &[(Op, usize, usize)], old: &[&str], new: &[&str]) -> String {
+    let old_count = edits.iter().filter(|edit| edit.0 != Op::Insert).count();
+    let new_count = edits.iter().filter(|edit| edit.0 != Op::Delete).count();
+
+    // empty ranges start at the line before them
+    let (_, i, j) = edits[0];
+    let old_start = if old_count == 0 { i } else { i + 1 };
+    let new_start = if new_count == 0 { j } else { j + 1 };
+
+    let mut hunk = format!(
+        "@@ -{},{} +{},{} @@\n",
+        old_start, old_count, new_start, new_count
+    );
+    for &(op, i, j) in edits {
+        let line = match op {
+            Op::Equal => format!(" {}\n", old[i]),
+            Op::Delete => format!("-{}\n", old[i]),
+            Op::Insert => format!("+{}\n", new[j]),
+        };
+        hunk.push_str(&line);
+    }
+
+    hunk
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn equal() {
+        assert_eq!(unified("a.huff", "a\nb\n", "a\nb\n"), None);
+    }
+
+    #[test]
+    fn changed_line() {
+        let old = "1\n2\n3\n4\n5\n6\n7\n8\n9\n";
+        let new = "1\n2\n3\n4\nfive\n6\n7\n8\n9\n";
+
+        assert_eq!(
+            unified("a.huff", old, new).unwrap(),
+            "--- a/a.huff\n+++ b/a.huff\n@@ -2,7 +2,7 @@\n 2\n 3\n 4\n-5\n+five\n 6\n 7\n 8\n"
+        );
+    }
+
+    #[test]
+    fn new_file() {
+        assert_eq!(
+            unified("a.huff", "", "a\nb\n").unwrap(),
+            "--- a/a.huff\n+++ b/a.huff\n@@ -0,0 +1,2 @@\n+a\n+b\n"
+        );
+    }
+
+    #[test]
+    fn line_counts() {
+        assert_eq!(stat("a\nb\nc\n", "a\nb\nc\n"), (0, 0));
+        assert_eq!(stat("a\nb\nc\n", "a\nx\ny\nc\n"), (2, 1));
+        assert_eq!(stat("", "a\nb\n"), (2, 0));
+    }
+
+    #[test]
+    fn separate_hunks() {
+        let old = (1..=20).map(|i| format!("{}\n", i)).collect::<String>();
+        let new = (1..=20)
+            .map(|i| match i {
+                2 => "two\n".to_string(),
+                19 => "nineteen\n".to_string(),
+                _ => format!("{}\n", i),
+            })
+            .collect::<String>();
+
+        let diff = unified("a.huff", &old, &new).unwrap();
+        assert_eq!(diff.matches("@@ -").count(), 2);
+        assert!(diff.contains("@@ -1,5 +1,5 @@\n 1\n-2\n+two\n 3\n"));
+        assert!(diff.contains("@@ -16,5 +16,5 @@\n 16\n 17\n 18\n-19\n+nineteen\n 20\n"));
+    }
+}
@@ -0,0 +1,220 @@
+use crate::libcast::OverflowRevert;
+use crate::library::Options;
+use crate::templates::{self, Context};
+
+/// A harness function, calling a macro of `libcast.huff` on its `uint256` argument.
+struct Function {
+    name: String,
+    label: String,
+    invocation: String,
+    bits: u16,
+    kind: Kind,
+}
+
+/// How a cast handles values that do not fit in its type.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    /// Reverts.
+    Checked,
+    /// Truncates.
+    Unsafe,
+    /// Returns whether the value fits with the truncated value.
+    Try,
+}
+
+/// Renders the Foundry differential tests of the casts generated with `options`, as file names and
+/// contents, fuzzing the macros of `libcast.huff` at the path `library` from the tests, deployed
+/// through a Huff harness, against a Solidity mirror.
+pub fn render(options: &Options, library: &str) -> Vec<(&'static str, String)> {
+    let functions = functions(options.sizes(), &options.prefix);
+    let check = match options.overflow_revert {
+        OverflowRevert::Error => ERROR_CHECK_TEMPLATE,
+        OverflowRevert::Panic => PANIC_CHECK_TEMPLATE,
+    };
+
+    vec![
+        ("CastHarness.huff", harness(&functions, library)),
+        ("CastMirror.sol", mirror(&functions, check)),
+        ("Cast.t.sol", test(&functions)),
+    ]
+}
+
+fn functions(sizes: &[u16], prefix: &str) -> Vec<Function> {
+    let variants = [
+        ("toU", "to_u", "TO_U", 8, Kind::Checked),
+        ("unsafeToU", "unsafe_to_u", "UNSAFE_TO_U", 8, Kind::Unsafe),
+        ("tryToU", "try_to_u", "TRY_TO_U", 8, Kind::Try),
+        ("miniToU", "mini_to_u", "MINI_TO_U", 32, Kind::Checked),
+        (
+            "unsafeMiniToU",
+            "unsafe_mini_to_u",
+            "UNSAFE_MINI_TO_U",
+            32,
+            Kind::Unsafe,
+        ),
+    ];
+
+    variants
+        .iter()
+        .flat_map(move |&(name, label, invocation, min_bits, kind)| {
+            sizes
+                .iter()
+                .filter(move |&&bits| bits >= min_bits)
+                .map(move |&bits| Function {
+                    name: format!("{}{}", name, bits),
+                    label: format!("{}{}", label, bits),
+                    invocation: format!("{}{}{}()", prefix, invocation, bits),
+                    bits,
+                    kind,
+                })
+        })
+        .collect()
+}
+
+impl Function {
+    /// The Solidity return types of the function.
+    fn returns(&self) -> String {
+        match self.kind {
+            Kind::Try => format!("bool, uint{}", self.bits),
+            _ => format!("uint{}", self.bits),
+        }
+    }
+}
+
+fn harness(functions: &[Function], library: &str) -> String {
+    let functions = functions
+        .iter()
+        .map(|f| {
+            let output = match f.kind {
+                Kind::Try => "0x00 mstore 0x20 mstore 0x40 0x00 return",
+                _ => "0x00 mstore 0x20 0x00 return",
+            };
+            Context::new()
+                .text("name", f.name.as_str())
+                .text("label", f.label.as_str())
+                .text("returns", f.returns())
+                .text("invocation", f.invocation.as_str())
+                .text("output", output)
+        })
+        .collect();
+
+    let context = Context::new()
+        .text("library", library)
+        .list("functions", functions);
+    templates::render(HARNESS_TEMPLATE, &context)
+}
+
+fn mirror(functions: &[Function], check: &str) -> String {
+    let functions = functions
+        .iter()
+        .map(|f| {
+            let bits = Context::new().text("bits", f.bits.to_string());
+            let check = match f.kind {
+                Kind::Checked => templates::render(check, &bits),
+                _ => String::new(),
+            };
+            bits.text("name", f.name.as_str())
+                .text("returns", f.returns())
+                .flag("try", f.kind == Kind::Try)
+                .text("check", check)
+        })
+        .collect();
+
+    templates::render(
+        MIRROR_TEMPLATE,
+        &Context::new().list("functions", functions),
+    )
+}
+
+fn test(functions: &[Function]) -> String {
+    let functions = functions
+        .iter()
+        .map(|f| {
+            let mut name = f.name.clone();
+            name[..1].make_ascii_uppercase();
+            Context::new()
+                .text("test_name", name)
+                .text("name", f.name.as_str())
+        })
+        .collect();
+
+    templates::render(TEST_TEMPLATE, &Context::new().list("functions", functions))
+}
+
+const HARNESS_TEMPLATE: &str = r#"/// Generated by `huffgen`, exposes the casts of `libcast.huff` for differential testing.
+
+#include "{{library}}"
+
+{{#each functions}}#define function {{name}}(uint256) pure returns ({{returns}})
+{{/each}}
+#define macro MAIN() = takes (0) returns (0) {
+    0x00 calldataload 0xe0 shr
+
+{{#each functions}}    dup1 __FUNC_SIG({{name}}) eq {{label}} jumpi
+{{/each}}
+    0x00 dup1 revert
+
+{{#each functions}}    {{label}}:
+        0x04 calldataload {{invocation}} {{output}}
+{{/each}}}
+"#;
+
+const MIRROR_TEMPLATE: &str = r#"// SPDX-License-Identifier: MIT
+// Generated by `huffgen`, mirrors the casts of `libcast.huff` for differential testing.
+pragma solidity ^0.8.0;
+
+contract CastMirror {
+    error Overflow();
+{{#each functions}}
+    function {{name}}(uint256 value) external pure returns ({{returns}}) {
+{{#if try}}        return (value <= type(uint{{bits}}).max, uint{{bits}}(value));
+{{else}}{{check}}        return uint{{bits}}(value);
+{{/if}}    }
+{{/each}}}
+"#;
+
+const ERROR_CHECK_TEMPLATE: &str = r#"        if (value > type(uint{{bits}}).max) revert Overflow();
+"#;
+
+const PANIC_CHECK_TEMPLATE: &str = r#"        if (value > type(uint{{bits}}).max) {
+            assembly {
+                mstore(0x00, 0x4e487b71)
+                mstore(0x20, 0x11)
+                revert(0x1c, 0x24)
+            }
+        }
+"#;
+
+const TEST_TEMPLATE: &str = r#"// SPDX-License-Identifier: MIT
+// Generated by `huffgen`, fuzzes the casts of `libcast.huff` against their Solidity mirror.
+pragma solidity ^0.8.0;
+
+import {Test} from "forge-std/Test.sol";
+import {HuffDeployer} from "foundry-huff/HuffDeployer.sol";
+
+import {CastMirror} from "./CastMirror.sol";
+
+contract CastDifferentialTest is Test {
+    address internal huff;
+    CastMirror internal mirror;
+
+    function setUp() public {
+        huff = HuffDeployer.deploy("../differential/CastHarness");
+        mirror = new CastMirror();
+    }
+
+    /// Asserts the harness and the mirror both succeed with the same output or both revert with
+    /// the same data.
+    function assertSame(bytes memory data) internal {
+        (bool huffOk, bytes memory huffOut) = huff.call(data);
+        (bool mirrorOk, bytes memory mirrorOut) = address(mirror).call(data);
+
+        assertEq(huffOk, mirrorOk);
+        assertEq(huffOut, mirrorOut);
+    }
+{{#each functions}}
+    function test{{test_name}}(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.{{name}}, (value >> shift)));
+    }
+{{/each}}}
+"#;
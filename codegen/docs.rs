@@ -0,0 +1,237 @@
+//! Markdown reference documentation of generated libraries, laid out as an mdBook.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::huff;
+use crate::manifest::{self, Macro};
+use crate::profile::Profile;
+
+/// The configuration of the book, at its root.
+const BOOK: &str = "[book]\ntitle = \"libhuff\"\nsrc = \"src\"\n";
+
+/// Renders the book documenting the libraries at `files`, as paths relative to the root of the
+/// book with their contents: its `book.toml`, the table of contents, `src/SUMMARY.md`, and a page
+/// for each library, in the order of `files`.
+pub fn render(files: &[&Path], profile: &Profile) -> Result<Vec<(String, String)>, huff::Error> {
+    let mut summary = String::from("# Summary\n\n");
+    let mut pages = Vec::new();
+
+    for file in files {
+        let stem = file
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .unwrap_or_default();
+        let page = page(file, profile)?;
+        let title = page
+            .lines()
+            .next()
+            .and_then(|line| line.strip_prefix("# "))
+            .unwrap_or(stem);
+
+        summary.push_str(&format!("- [{}]({}.md)\n", title, stem));
+        pages.push((format!("src/{}.md", stem), page));
+    }
+
+    let mut book = vec![
+        ("book.toml".to_string(), BOOK.to_string()),
+        ("src/SUMMARY.md".to_string(), summary),
+    ];
+    book.extend(pages);
+    Ok(book)
+}
+
+/// Renders the page of the library at `file`: its module documentation, a table of the macros it
+/// defines with their `takes` and `returns` counts, size and gas for `profile`, and a reference
+/// section per macro with its signature, stack, errors and doc comments.
+pub fn page(file: &Path, profile: &Profile) -> Result<String, huff::Error> {
+    let src = std::fs::read_to_string(file).map_err(|e| huff::Error { msg: e.to_string() })?;
+    let file_name = file
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or_default();
+    let macros = manifest::macros(file, profile)?;
+    let comments = doc_comments(&src);
+
+    let module = src
+        .lines()
+        .filter_map(|line| line.strip_prefix("//!"))
+        .map(|line| line.strip_prefix(' ').unwrap_or(line))
+        .collect::<Vec<&str>>();
+    let (title, overview) = match module.split_first() {
+        Some((first, rest)) if first.starts_with("# ") => (&first[2..], rest),
+        _ => (file_name, &module[..]),
+    };
+
+    let mut out = format!("# {}\n\n", title);
+    let overview = overview.join("\n");
+    if !overview.trim().is_empty() {
+        out.push_str(&format!("{}\n\n", overview.trim()));
+    }
+
+    out.push_str("## Macros\n\n");
+    out.push_str("| Macro | Takes | Returns | Bytes | Gas |\n");
+    out.push_str("| --- | ---: | ---: | ---: | ---: |\n");
+    for definition in &macros {
+        out.push_str(&format!(
+            "| [`{}`](#{}) | {} | {} | {} | {} |\n",
+            signature(definition),
+            definition.name.to_lowercase(),
+            definition.takes,
+            definition.returns,
+            optional(definition.size),
+            optional(definition.gas),
+        ));
+    }
+
+    out.push_str("\n## Reference\n");
+    for definition in &macros {
+        out.push_str(&format!("\n### `{}`\n\n", definition.name));
+        out.push_str(&format!(
+            "```huff\n#define macro {} = takes ({}) returns ({})\n```\n\n",
+            signature(definition),
+            definition.takes,
+            definition.returns,
+        ));
+
+        if let (Some(first), Some(last)) = (definition.stack.first(), definition.stack.last()) {
+            out.push_str(&format!("- Stack: `{}` to `{}`\n", first, last));
+        }
+        if !definition.errors.is_empty() {
+            let errors = definition.errors.iter().map(|error| format!("`{}`", error));
+            out.push_str(&format!(
+                "- Reverts: {}\n",
+                errors.collect::<Vec<String>>().join(", ")
+            ));
+        }
+        if !definition.stack.is_empty() || !definition.errors.is_empty() {
+            out.push('\n');
+        }
+
+        if let Some(lines) = comments.get(definition.name.as_str()) {
+            out.push_str(&markdown(lines));
+        }
+    }
+
+    Ok(out)
+}
+
+/// The name of a macro with its template arguments.
+fn signature(definition: &Macro) -> String {
+    format!("{}({})", definition.name, definition.params.join(", "))
+}
+
+fn optional(value: Option<impl ToString>) -> String {
+    value.map_or("-".to_string(), |value| value.to_string())
+}
+
+/// The `///` comments preceding each macro definition of `src`, by macro name, without the
+/// comment markers.
+pub(crate) fn doc_comments(src: &str) -> HashMap<&str, Vec<&str>> {
+    let mut comments = HashMap::new();
+    let mut pending = Vec::new();
+
+    for line in src.lines() {
+        if let Some(comment) = line.strip_prefix("///") {
+            pending.push(comment.strip_prefix(' ').unwrap_or(comment));
+            continue;
+        }
+
+        let name = line
+            .strip_prefix("#define macro ")
+            .and_then(|rest| rest.split('(').next());
+        if let Some(name) = name {
+            comments.insert(name, std::mem::take(&mut pending));
+        }
+        pending.clear();
+    }
+
+    comments
+}
+
+/// Renders the doc comment `lines` of a macro as the body of its reference section, leaving out
+/// the title, nesting headings under the section and listing `@pre` and `@post` conditions.
+fn markdown(lines: &[&str]) -> String {
+    let mut lines = lines;
+    if lines.first().is_some_and(|line| line.starts_with("## ")) {
+        lines = &lines[1..];
+        if lines.first() == Some(&"") {
+            lines = &lines[1..];
+        }
+    }
+
+    let mut out = String::new();
+    let mut fenced = false;
+    for line in lines {
+        if line.starts_with("```") {
+            fenced = !fenced;
+        }
+
+        let condition = ["@pre ", "@post "]
+            .iter()
+            .find_map(|tag| Some((tag.trim_end(), line.strip_prefix(tag)?)));
+        match condition {
+            _ if fenced => out.push_str(line),
+            Some((tag, expression)) => out.push_str(&format!("- {} `{}`", tag, expression)),
+            None if line.starts_with('#') => out.push_str(&format!("#{}", line)),
+            None => out.push_str(line),
+        }
+        out.push('\n');
+    }
+
+    if out.is_empty() {
+        out
+    } else {
+        format!("{}\n", out.trim_end())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn macro_sections() {
+        let src = concat!(
+            "/// ## Mask\n",
+            "///\n",
+            "/// Pushes a mask.\n",
+            "///\n",
+            "/// ### Conditions\n",
+            "///\n",
+            "/// @post result == 0xff\n",
+            "///\n",
+            "/// ```huff\n",
+            "/// #define macro MAIN() = takes (0) returns (0) {\n",
+            "///     MASK()\n",
+            "/// }\n",
+            "/// ```\n",
+            "#define macro MASK() = takes (0) returns (1) {\n",
+            "    0xff\n",
+            "}\n",
+            "\n",
+            "/// Not documentation of `B`.\n",
+            "#define constant C = 0x01\n",
+            "#define macro B() = takes (0) returns (0) {}\n",
+        );
+        let comments = doc_comments(src);
+
+        assert_eq!(comments["B"], Vec::<&str>::new());
+        assert_eq!(
+            markdown(&comments["MASK"]),
+            concat!(
+                "Pushes a mask.\n",
+                "\n",
+                "#### Conditions\n",
+                "\n",
+                "- @post `result == 0xff`\n",
+                "\n",
+                "```huff\n",
+                "#define macro MAIN() = takes (0) returns (0) {\n",
+                "    MASK()\n",
+                "}\n",
+                "```\n",
+            )
+        );
+    }
+}
@@ -0,0 +1,16 @@
+/// Formats a line of macro body with its stack comment aligned to the repo's column.
+pub fn line(code: &str, comment: &str) -> String {
+    format!("    {:<23} // {}\n", code, comment)
+}
+
+/// Formats the `@pre` and `@post` conditions of a macro as doc comment lines.
+///
+/// Conditions are expressions over the stack arguments, by name, and `result`, the value left on
+/// top of the stack. A `@pre` condition is required of the arguments, and checked macros revert
+/// when it does not hold. A `@post` condition holds whenever the macro completes.
+pub fn conditions(pre: &[&str], post: &[&str]) -> String {
+    let pre = pre.iter().map(|c| format!("/// @pre {}\n", c));
+    let post = post.iter().map(|c| format!("/// @post {}\n", c));
+
+    pre.chain(post).collect()
+}
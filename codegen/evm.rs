@@ -0,0 +1,1025 @@
+//! Minimal EVM interpreter (Cancun rules, simplified gas accounting), for executing generated
+//! macros without an external node.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::keccak::keccak256;
+use crate::u256::U256;
+
+pub type Address = [u8; 20];
+
+pub fn addr(n: u64) -> Address {
+    let mut a = [0u8; 20];
+    a[12..].copy_from_slice(&n.to_be_bytes());
+    a
+}
+
+pub fn addr_to_u256(a: &Address) -> U256 {
+    U256::from_be_bytes(a)
+}
+
+pub fn u256_to_addr(v: U256) -> Address {
+    let b = v.to_be_bytes();
+    b[12..].try_into().unwrap()
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Account {
+    pub code: Vec<u8>,
+    pub storage: HashMap<U256, U256>,
+    pub balance: U256,
+    pub nonce: u64,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Block {
+    pub chain_id: U256,
+    pub timestamp: U256,
+    pub number: U256,
+    pub prevrandao: U256,
+    pub basefee: U256,
+    pub gas_limit: U256,
+    pub coinbase: Address,
+    pub blob_hashes: Vec<U256>,
+    pub blob_basefee: U256,
+    pub origin: Address,
+    pub gas_price: U256,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Log {
+    pub address: Address,
+    pub topics: Vec<U256>,
+    pub data: Vec<u8>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Halt {
+    Stop,
+    Return,
+    Revert,
+    /// Exceptional halt: invalid opcode, stack error, bad jump, out of gas, static violation.
+    Exception(String),
+}
+
+#[derive(Clone, Debug)]
+pub struct Outcome {
+    pub halt: Halt,
+    pub output: Vec<u8>,
+    pub gas_used: u64,
+    /// Final stack of the outermost frame, top first.
+    pub stack: Vec<U256>,
+    pub logs: Vec<Log>,
+}
+
+impl Outcome {
+    pub fn success(&self) -> bool {
+        matches!(self.halt, Halt::Stop | Halt::Return)
+    }
+}
+
+/// Precompile hook: given input, returns Some((success, output)) if handled.
+pub type Precompile = fn(&[u8]) -> (bool, Vec<u8>);
+
+#[derive(Clone, Default)]
+pub struct Evm {
+    pub accounts: HashMap<Address, Account>,
+    pub transient: HashMap<(Address, U256), U256>,
+    pub block: Block,
+    pub precompiles: HashMap<Address, Precompile>,
+    pub logs: Vec<Log>,
+    warm_accounts: HashSet<Address>,
+    warm_slots: HashSet<(Address, U256)>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Call {
+    pub caller: Address,
+    pub address: Address,
+    pub code_address: Address,
+    pub value: U256,
+    pub data: Vec<u8>,
+    pub gas: u64,
+    pub is_static: bool,
+    pub transfer: bool,
+}
+
+struct Frame {
+    stack: Vec<U256>,
+    memory: Vec<u8>,
+    pc: usize,
+    gas: u64,
+    returndata: Vec<u8>,
+}
+
+fn mem_cost(words: u64) -> u64 {
+    3 * words + words * words / 512
+}
+
+impl Evm {
+    pub fn new() -> Self {
+        let mut evm = Evm {
+            block: Block {
+                chain_id: U256::ONE,
+                timestamp: U256::from_u64(1_700_000_000),
+                number: U256::from_u64(19_000_000),
+                gas_limit: U256::from_u64(30_000_000),
+                basefee: U256::from_u64(7),
+                blob_basefee: U256::ONE,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        evm.precompiles.insert(addr(4), identity);
+        evm
+    }
+
+    pub fn account(&mut self, a: Address) -> &mut Account {
+        self.accounts.entry(a).or_default()
+    }
+
+    pub fn set_code(&mut self, a: Address, code: Vec<u8>) {
+        self.account(a).code = code;
+    }
+
+    pub fn sload(&self, a: Address, slot: U256) -> U256 {
+        self.accounts
+            .get(&a)
+            .and_then(|acc| acc.storage.get(&slot).copied())
+            .unwrap_or_default()
+    }
+
+    pub fn sstore(&mut self, a: Address, slot: U256, v: U256) {
+        self.account(a).storage.insert(slot, v);
+    }
+
+    pub fn balance(&self, a: Address) -> U256 {
+        self.accounts
+            .get(&a)
+            .map(|acc| acc.balance)
+            .unwrap_or_default()
+    }
+
+    /// Executes a top-level transaction-like call. Transient storage is cleared afterwards.
+    pub fn transact(&mut self, call: Call) -> Outcome {
+        self.begin(call.caller, call.address);
+        let out = self.call(call, 0);
+        self.end(out)
+    }
+
+    /// Executes a top-level contract creation from `caller`, running `initcode` at the address the
+    /// caller creates at its nonce and storing the code it returns there. Returns the outcome of
+    /// the initcode and the address, which holds no code if the creation failed.
+    pub fn create(&mut self, caller: Address, initcode: &[u8], gas: u64) -> (Outcome, Address) {
+        let nonce = self.account(caller).nonce;
+        self.account(caller).nonce += 1;
+        let address = create_address(&caller, nonce);
+        self.begin(caller, address);
+
+        let call = Call {
+            caller,
+            address,
+            code_address: address,
+            value: U256::ZERO,
+            data: vec![],
+            gas,
+            is_static: false,
+            transfer: false,
+        };
+        let snapshot = self.accounts.clone();
+        let out = self.run(&call, initcode, 0);
+        if out.success() {
+            let account = self.account(address);
+            account.code = out.output.clone();
+            account.nonce = 1;
+        } else {
+            self.accounts = snapshot;
+            self.logs.clear();
+        }
+        (self.end(out), address)
+    }
+
+    fn begin(&mut self, caller: Address, address: Address) {
+        self.warm_accounts.clear();
+        self.warm_slots.clear();
+        self.warm_accounts.insert(caller);
+        self.warm_accounts.insert(address);
+        for i in 1..=10 {
+            self.warm_accounts.insert(addr(i));
+        }
+        self.logs.clear();
+    }
+
+    fn end(&mut self, out: Outcome) -> Outcome {
+        self.transient.clear();
+        Outcome {
+            logs: std::mem::take(&mut self.logs),
+            ..out
+        }
+    }
+
+    fn call(&mut self, call: Call, depth: usize) -> Outcome {
+        if depth > 1024 {
+            return Outcome {
+                halt: Halt::Exception("call depth".into()),
+                output: vec![],
+                gas_used: 0,
+                stack: vec![],
+                logs: vec![],
+            };
+        }
+        let snapshot = (
+            self.accounts.clone(),
+            self.transient.clone(),
+            self.logs.len(),
+        );
+        if call.transfer && !call.value.is_zero() {
+            let bal = self.balance(call.caller);
+            if bal < call.value {
+                return Outcome {
+                    halt: Halt::Revert,
+                    output: vec![],
+                    gas_used: 0,
+                    stack: vec![],
+                    logs: vec![],
+                };
+            }
+            self.account(call.caller).balance = bal.wrapping_sub(call.value);
+            let to = self.account(call.address);
+            to.balance = to.balance.wrapping_add(call.value);
+        }
+        if let Some(p) = self.precompiles.get(&call.code_address).copied() {
+            let (ok, out) = p(&call.data);
+            return Outcome {
+                halt: if ok {
+                    Halt::Return
+                } else {
+                    Halt::Exception("precompile".into())
+                },
+                output: if ok { out } else { vec![] },
+                gas_used: 100,
+                stack: vec![],
+                logs: vec![],
+            };
+        }
+        let code = self
+            .accounts
+            .get(&call.code_address)
+            .map(|a| a.code.clone())
+            .unwrap_or_default();
+        let out = self.run(&call, &code, depth);
+        if !out.success() {
+            self.accounts = snapshot.0;
+            self.transient = snapshot.1;
+            self.logs.truncate(snapshot.2);
+        }
+        out
+    }
+
+    fn run(&mut self, call: &Call, code: &[u8], depth: usize) -> Outcome {
+        let jumpdests = jumpdests(code);
+        let mut f = Frame {
+            stack: Vec::new(),
+            memory: Vec::new(),
+            pc: 0,
+            gas: call.gas,
+            returndata: Vec::new(),
+        };
+        let result = self.exec(call, code, &jumpdests, &mut f, depth);
+        let mut stack = f.stack.clone();
+        stack.reverse();
+        match result {
+            Ok((halt, output)) => {
+                let gas_used = if matches!(halt, Halt::Exception(_)) {
+                    call.gas
+                } else {
+                    call.gas - f.gas
+                };
+                Outcome {
+                    halt,
+                    output,
+                    gas_used,
+                    stack,
+                    logs: vec![],
+                }
+            }
+            Err(e) => Outcome {
+                halt: Halt::Exception(e),
+                output: vec![],
+                gas_used: call.gas,
+                stack,
+                logs: vec![],
+            },
+        }
+    }
+
+    fn exec(
+        &mut self,
+        call: &Call,
+        code: &[u8],
+        jumpdests: &HashSet<usize>,
+        f: &mut Frame,
+        depth: usize,
+    ) -> Result<(Halt, Vec<u8>), String> {
+        macro_rules! pop {
+            () => {
+                f.stack
+                    .pop()
+                    .ok_or_else(|| format!("stack underflow at pc {}", f.pc))?
+            };
+        }
+        macro_rules! push {
+            ($v:expr) => {{
+                let v = $v;
+                if f.stack.len() >= 1024 {
+                    return Err("stack overflow".into());
+                }
+                f.stack.push(v);
+            }};
+        }
+        macro_rules! gas {
+            ($g:expr) => {{
+                let g: u64 = $g;
+                if f.gas < g {
+                    return Err(format!("out of gas at pc {}", f.pc));
+                }
+                f.gas -= g;
+            }};
+        }
+        fn expand(f: &mut Frame, offset: U256, size: U256) -> Result<(usize, usize), String> {
+            let size = size.as_usize().ok_or("memory size overflow")?;
+            if size == 0 {
+                return Ok((0, 0));
+            }
+            let offset = offset.as_usize().ok_or("memory offset overflow")?;
+            let end = offset.checked_add(size).ok_or("memory overflow")?;
+            if end > 1 << 26 {
+                return Err("memory limit".into());
+            }
+            let old_words = f.memory.len().div_ceil(32) as u64;
+            let new_words = end.div_ceil(32) as u64;
+            if new_words > old_words {
+                let cost = mem_cost(new_words) - mem_cost(old_words);
+                if f.gas < cost {
+                    return Err("out of gas (memory)".into());
+                }
+                f.gas -= cost;
+                f.memory.resize(new_words as usize * 32, 0);
+            }
+            Ok((offset, size))
+        }
+        fn copy_padded(src: &[u8], offset: U256, size: usize) -> Vec<u8> {
+            let mut out = vec![0u8; size];
+            if let Some(o) = offset.as_usize() {
+                for (i, b) in out.iter_mut().enumerate() {
+                    if let Some(v) = o.checked_add(i).and_then(|j| src.get(j)) {
+                        *b = *v;
+                    }
+                }
+            }
+            out
+        }
+
+        loop {
+            if f.pc >= code.len() {
+                return Ok((Halt::Stop, vec![]));
+            }
+            let op = code[f.pc];
+            match op {
+                0x00 => return Ok((Halt::Stop, vec![])),
+                0x01..=0x0b | 0x10..=0x1d => {
+                    let low = matches!(op, 0x01 | 0x03 | 0x10..=0x1d);
+                    gas!(if op == 0x0a {
+                        10
+                    } else if low {
+                        3
+                    } else if op == 0x08 || op == 0x09 {
+                        8
+                    } else {
+                        5
+                    });
+                    let a = pop!();
+                    let r = match op {
+                        0x15 => {
+                            if a.is_zero() {
+                                U256::ONE
+                            } else {
+                                U256::ZERO
+                            }
+                        }
+                        0x19 => !a,
+                        _ => {
+                            let b = pop!();
+                            match op {
+                                0x01 => a.wrapping_add(b),
+                                0x02 => a.wrapping_mul(b),
+                                0x03 => a.wrapping_sub(b),
+                                0x04 => a.div(b),
+                                0x05 => a.sdiv(b),
+                                0x06 => a.rem(b),
+                                0x07 => a.smod(b),
+                                0x08 => {
+                                    let n = pop!();
+                                    a.addmod(b, n)
+                                }
+                                0x09 => {
+                                    let n = pop!();
+                                    a.mulmod(b, n)
+                                }
+                                0x0a => {
+                                    gas!(50 * b.bits().div_ceil(8) as u64);
+                                    a.pow(b)
+                                }
+                                0x0b => b.signextend(a),
+                                0x10 => bool_u(a < b),
+                                0x11 => bool_u(a > b),
+                                0x12 => bool_u(a.slt(&b)),
+                                0x13 => bool_u(b.slt(&a)),
+                                0x14 => bool_u(a == b),
+                                0x16 => a & b,
+                                0x17 => a | b,
+                                0x18 => a ^ b,
+                                0x1a => b.byte(a),
+                                0x1b => {
+                                    b.shl(a.as_usize().map(|v| v.min(256) as u32).unwrap_or(256))
+                                }
+                                0x1c => {
+                                    b.shr(a.as_usize().map(|v| v.min(256) as u32).unwrap_or(256))
+                                }
+                                0x1d => {
+                                    b.sar(a.as_usize().map(|v| v.min(256) as u32).unwrap_or(256))
+                                }
+                                _ => unreachable!(),
+                            }
+                        }
+                    };
+                    push!(r);
+                }
+                0x20 => {
+                    let o = pop!();
+                    let s = pop!();
+                    let (o, s) = expand(f, o, s)?;
+                    gas!(30 + 6 * s.div_ceil(32) as u64);
+                    let h = keccak256(&f.memory[o..o + s]);
+                    push!(U256::from_be_bytes(&h));
+                }
+                0x30 => {
+                    gas!(2);
+                    push!(addr_to_u256(&call.address));
+                }
+                0x31 => {
+                    let a = u256_to_addr(pop!());
+                    gas!(self.access_account(a));
+                    push!(self.balance(a));
+                }
+                0x32 => {
+                    gas!(2);
+                    push!(addr_to_u256(&self.block.origin));
+                }
+                0x33 => {
+                    gas!(2);
+                    push!(addr_to_u256(&call.caller));
+                }
+                0x34 => {
+                    gas!(2);
+                    push!(call.value);
+                }
+                0x35 => {
+                    gas!(3);
+                    let o = pop!();
+                    push!(U256::from_be_bytes(&copy_padded(&call.data, o, 32)));
+                }
+                0x36 => {
+                    gas!(2);
+                    push!(U256::from_u64(call.data.len() as u64));
+                }
+                0x37 | 0x39 | 0x3e => {
+                    let d = pop!();
+                    let o = pop!();
+                    let s = pop!();
+                    if op == 0x3e {
+                        let end = o.checked_add(s);
+                        match end.and_then(|e| e.as_usize()) {
+                            Some(e) if e <= f.returndata.len() => {}
+                            _ => return Err("returndatacopy out of bounds".into()),
+                        }
+                    }
+                    let (d, s) = expand(f, d, s)?;
+                    gas!(3 + 3 * s.div_ceil(32) as u64);
+                    let src = match op {
+                        0x37 => &call.data[..],
+                        0x39 => code,
+                        _ => &f.returndata[..],
+                    };
+                    let bytes = copy_padded(src, o, s);
+                    f.memory[d..d + s].copy_from_slice(&bytes);
+                }
+                0x38 => {
+                    gas!(2);
+                    push!(U256::from_u64(code.len() as u64));
+                }
+                0x3a => {
+                    gas!(2);
+                    push!(self.block.gas_price);
+                }
+                0x3b => {
+                    let a = u256_to_addr(pop!());
+                    gas!(self.access_account(a));
+                    let len = self.accounts.get(&a).map(|x| x.code.len()).unwrap_or(0);
+                    push!(U256::from_u64(len as u64));
+                }
+                0x3c => {
+                    let a = u256_to_addr(pop!());
+                    let d = pop!();
+                    let o = pop!();
+                    let s = pop!();
+                    gas!(self.access_account(a));
+                    let (d, s) = expand(f, d, s)?;
+                    gas!(3 * s.div_ceil(32) as u64);
+                    let ext = self
+                        .accounts
+                        .get(&a)
+                        .map(|x| x.code.clone())
+                        .unwrap_or_default();
+                    let bytes = copy_padded(&ext, o, s);
+                    f.memory[d..d + s].copy_from_slice(&bytes);
+                }
+                0x3d => {
+                    gas!(2);
+                    push!(U256::from_u64(f.returndata.len() as u64));
+                }
+                0x3f => {
+                    let a = u256_to_addr(pop!());
+                    gas!(self.access_account(a));
+                    let h = match self.accounts.get(&a) {
+                        Some(acc)
+                            if !acc.code.is_empty() || !acc.balance.is_zero() || acc.nonce > 0 =>
+                        {
+                            U256::from_be_bytes(&keccak256(&acc.code))
+                        }
+                        _ => U256::ZERO,
+                    };
+                    push!(h);
+                }
+                0x40 => {
+                    gas!(20);
+                    let n = pop!();
+                    let h = if n < self.block.number
+                        && self.block.number.wrapping_sub(n) <= U256::from_u64(256)
+                    {
+                        U256::from_be_bytes(&keccak256(&n.to_be_bytes()))
+                    } else {
+                        U256::ZERO
+                    };
+                    push!(h);
+                }
+                0x41..=0x48 | 0x4a => {
+                    gas!(2);
+                    let v = match op {
+                        0x41 => addr_to_u256(&self.block.coinbase),
+                        0x42 => self.block.timestamp,
+                        0x43 => self.block.number,
+                        0x44 => self.block.prevrandao,
+                        0x45 => self.block.gas_limit,
+                        0x46 => self.block.chain_id,
+                        0x47 => {
+                            f.gas += 2;
+                            gas!(5);
+                            self.balance(call.address)
+                        }
+                        0x48 => self.block.basefee,
+                        _ => self.block.blob_basefee,
+                    };
+                    push!(v);
+                }
+                0x49 => {
+                    gas!(3);
+                    let i = pop!();
+                    let v = i
+                        .as_usize()
+                        .and_then(|i| self.block.blob_hashes.get(i).copied())
+                        .unwrap_or_default();
+                    push!(v);
+                }
+                0x50 => {
+                    gas!(2);
+                    pop!();
+                }
+                0x51 => {
+                    gas!(3);
+                    let o = pop!();
+                    let (o, _) = expand(f, o, U256::from_u64(32))?;
+                    push!(U256::from_be_bytes(&f.memory[o..o + 32]));
+                }
+                0x52 => {
+                    gas!(3);
+                    let o = pop!();
+                    let v = pop!();
+                    let (o, _) = expand(f, o, U256::from_u64(32))?;
+                    f.memory[o..o + 32].copy_from_slice(&v.to_be_bytes());
+                }
+                0x53 => {
+                    gas!(3);
+                    let o = pop!();
+                    let v = pop!();
+                    let (o, _) = expand(f, o, U256::ONE)?;
+                    f.memory[o] = v.to_be_bytes()[31];
+                }
+                0x54 => {
+                    let k = pop!();
+                    gas!(self.access_slot(call.address, k));
+                    push!(self.sload(call.address, k));
+                }
+                0x55 => {
+                    if call.is_static {
+                        return Err("sstore in static call".into());
+                    }
+                    let k = pop!();
+                    let v = pop!();
+                    let cold = self.access_slot(call.address, k) == 2100;
+                    let cur = self.sload(call.address, k);
+                    let mut g = if cur == v {
+                        100
+                    } else if cur.is_zero() {
+                        20000
+                    } else {
+                        2900
+                    };
+                    if cold {
+                        g += 2100;
+                    }
+                    gas!(g);
+                    self.sstore(call.address, k, v);
+                }
+                0x56 => {
+                    gas!(8);
+                    let d = pop!();
+                    let d = d.as_usize().filter(|d| jumpdests.contains(d));
+                    match d {
+                        Some(d) => {
+                            f.pc = d;
+                            continue;
+                        }
+                        None => return Err(format!("bad jump at pc {}", f.pc)),
+                    }
+                }
+                0x57 => {
+                    gas!(10);
+                    let d = pop!();
+                    let c = pop!();
+                    if !c.is_zero() {
+                        match d.as_usize().filter(|d| jumpdests.contains(d)) {
+                            Some(d) => {
+                                f.pc = d;
+                                continue;
+                            }
+                            None => return Err(format!("bad jumpi at pc {}", f.pc)),
+                        }
+                    }
+                }
+                0x58 => {
+                    gas!(2);
+                    push!(U256::from_u64(f.pc as u64));
+                }
+                0x59 => {
+                    gas!(2);
+                    push!(U256::from_u64(f.memory.len() as u64));
+                }
+                0x5a => {
+                    gas!(2);
+                    push!(U256::from_u64(f.gas));
+                }
+                0x5b => gas!(1),
+                0x5c => {
+                    gas!(100);
+                    let k = pop!();
+                    let v = self
+                        .transient
+                        .get(&(call.address, k))
+                        .copied()
+                        .unwrap_or_default();
+                    push!(v);
+                }
+                0x5d => {
+                    if call.is_static {
+                        return Err("tstore in static call".into());
+                    }
+                    gas!(100);
+                    let k = pop!();
+                    let v = pop!();
+                    self.transient.insert((call.address, k), v);
+                }
+                0x5e => {
+                    let d = pop!();
+                    let s_off = pop!();
+                    let s = pop!();
+                    let hi = if d > s_off { d } else { s_off };
+                    let total = hi.checked_add(s).unwrap_or(U256::MAX);
+                    expand(f, U256::ZERO, if s.is_zero() { U256::ZERO } else { total })?;
+                    let (d, sz) = expand(f, d, s)?;
+                    gas!(3 + 3 * sz.div_ceil(32) as u64);
+                    if sz > 0 {
+                        let so = s_off.as_usize().unwrap();
+                        let bytes = f.memory[so..so + sz].to_vec();
+                        f.memory[d..d + sz].copy_from_slice(&bytes);
+                    }
+                }
+                0x5f => {
+                    gas!(2);
+                    push!(U256::ZERO);
+                }
+                0x60..=0x7f => {
+                    gas!(3);
+                    let n = (op - 0x5f) as usize;
+                    let start = f.pc + 1;
+                    let mut bytes = vec![0u8; n];
+                    for (i, b) in bytes.iter_mut().enumerate() {
+                        *b = code.get(start + i).copied().unwrap_or(0);
+                    }
+                    push!(U256::from_be_bytes(&bytes));
+                    f.pc += n;
+                }
+                0x80..=0x8f => {
+                    gas!(3);
+                    let n = (op - 0x7f) as usize;
+                    if f.stack.len() < n {
+                        return Err(format!("stack underflow (dup{}) at pc {}", n, f.pc));
+                    }
+                    let v = f.stack[f.stack.len() - n];
+                    push!(v);
+                }
+                0x90..=0x9f => {
+                    gas!(3);
+                    let n = (op - 0x8f) as usize;
+                    let len = f.stack.len();
+                    if len < n + 1 {
+                        return Err(format!("stack underflow (swap{}) at pc {}", n, f.pc));
+                    }
+                    f.stack.swap(len - 1, len - 1 - n);
+                }
+                0xa0..=0xa4 => {
+                    if call.is_static {
+                        return Err("log in static call".into());
+                    }
+                    let n = (op - 0xa0) as usize;
+                    let o = pop!();
+                    let s = pop!();
+                    let mut topics = Vec::new();
+                    for _ in 0..n {
+                        topics.push(pop!());
+                    }
+                    let (o, s) = expand(f, o, s)?;
+                    gas!(375 + 375 * n as u64 + 8 * s as u64);
+                    self.logs.push(Log {
+                        address: call.address,
+                        topics,
+                        data: f.memory[o..o + s].to_vec(),
+                    });
+                }
+                0xf0 | 0xf5 => {
+                    if call.is_static {
+                        return Err("create in static call".into());
+                    }
+                    let value = pop!();
+                    let o = pop!();
+                    let s = pop!();
+                    let salt = if op == 0xf5 { Some(pop!()) } else { None };
+                    let (o, s) = expand(f, o, s)?;
+                    gas!(32000);
+                    let initcode = f.memory[o..o + s].to_vec();
+                    let nonce = self.account(call.address).nonce;
+                    self.account(call.address).nonce += 1;
+                    let new = match salt {
+                        Some(salt) => create2_address(&call.address, salt, &initcode),
+                        None => create_address(&call.address, nonce),
+                    };
+                    let gas = f.gas - f.gas / 64;
+                    let sub = Call {
+                        caller: call.address,
+                        address: new,
+                        code_address: new,
+                        value,
+                        data: vec![],
+                        gas,
+                        is_static: false,
+                        transfer: true,
+                    };
+                    // run initcode directly
+                    let snapshot = self.accounts.clone();
+                    if !value.is_zero() {
+                        let bal = self.balance(call.address);
+                        if bal < value {
+                            f.returndata.clear();
+                            push!(U256::ZERO);
+                            f.pc += 1;
+                            continue;
+                        }
+                        self.account(call.address).balance = bal.wrapping_sub(value);
+                        let to = self.account(new);
+                        to.balance = to.balance.wrapping_add(value);
+                    }
+                    let out = self.run(&sub, &initcode, depth + 1);
+                    f.gas -= out.gas_used.min(f.gas);
+                    if out.success() {
+                        self.account(new).code = out.output;
+                        self.account(new).nonce = 1;
+                        f.returndata.clear();
+                        push!(addr_to_u256(&new));
+                    } else {
+                        self.accounts = snapshot;
+                        f.returndata = out.output;
+                        push!(U256::ZERO);
+                    }
+                }
+                0xf1 | 0xf2 | 0xf4 | 0xfa => {
+                    let g = pop!();
+                    let to = u256_to_addr(pop!());
+                    let value = if op == 0xf1 || op == 0xf2 {
+                        pop!()
+                    } else {
+                        U256::ZERO
+                    };
+                    let ao = pop!();
+                    let asz = pop!();
+                    let ro = pop!();
+                    let rsz = pop!();
+                    if call.is_static && op == 0xf1 && !value.is_zero() {
+                        return Err("value call in static context".into());
+                    }
+                    let (ao, asz) = expand(f, ao, asz)?;
+                    let (ro, rsz) = expand(f, ro, rsz)?;
+                    let mut cost = self.access_account(to);
+                    if !value.is_zero() {
+                        cost += 9000;
+                        if op == 0xf1 && !self.accounts.contains_key(&to) {
+                            cost += 25000;
+                        }
+                    }
+                    gas!(cost);
+                    let available = f.gas - f.gas / 64;
+                    let mut forwarded = match g.as_usize() {
+                        Some(g) if (g as u64) < available => g as u64,
+                        _ => available,
+                    };
+                    f.gas -= forwarded;
+                    if !value.is_zero() {
+                        forwarded += 2300;
+                    }
+                    let data = f.memory[ao..ao + asz].to_vec();
+                    let sub = match op {
+                        0xf1 => Call {
+                            caller: call.address,
+                            address: to,
+                            code_address: to,
+                            value,
+                            data,
+                            gas: forwarded,
+                            is_static: call.is_static,
+                            transfer: true,
+                        },
+                        0xf2 => Call {
+                            caller: call.address,
+                            address: call.address,
+                            code_address: to,
+                            value,
+                            data,
+                            gas: forwarded,
+                            is_static: call.is_static,
+                            transfer: false,
+                        },
+                        0xf4 => Call {
+                            caller: call.caller,
+                            address: call.address,
+                            code_address: to,
+                            value: call.value,
+                            data,
+                            gas: forwarded,
+                            is_static: call.is_static,
+                            transfer: false,
+                        },
+                        _ => Call {
+                            caller: call.address,
+                            address: to,
+                            code_address: to,
+                            value: U256::ZERO,
+                            data,
+                            gas: forwarded,
+                            is_static: true,
+                            transfer: false,
+                        },
+                    };
+                    let out = self.call(sub, depth + 1);
+                    f.gas += forwarded - out.gas_used.min(forwarded);
+                    let n = rsz.min(out.output.len());
+                    f.memory[ro..ro + n].copy_from_slice(&out.output[..n]);
+                    f.returndata = out.output.clone();
+                    push!(bool_u(out.success()));
+                }
+                0xf3 | 0xfd => {
+                    let o = pop!();
+                    let s = pop!();
+                    let (o, s) = expand(f, o, s)?;
+                    let data = f.memory[o..o + s].to_vec();
+                    return Ok((
+                        if op == 0xf3 {
+                            Halt::Return
+                        } else {
+                            Halt::Revert
+                        },
+                        data,
+                    ));
+                }
+                0xff => {
+                    if call.is_static {
+                        return Err("selfdestruct in static call".into());
+                    }
+                    gas!(5000);
+                    let to = u256_to_addr(pop!());
+                    let bal = self.balance(call.address);
+                    self.account(call.address).balance = U256::ZERO;
+                    let t = self.account(to);
+                    t.balance = t.balance.wrapping_add(bal);
+                    return Ok((Halt::Stop, vec![]));
+                }
+                _ => return Err(format!("invalid opcode 0x{:02x} at pc {}", op, f.pc)),
+            }
+            f.pc += 1;
+        }
+    }
+
+    fn access_account(&mut self, a: Address) -> u64 {
+        if self.warm_accounts.insert(a) {
+            2600
+        } else {
+            100
+        }
+    }
+
+    fn access_slot(&mut self, a: Address, k: U256) -> u64 {
+        if self.warm_slots.insert((a, k)) {
+            2100
+        } else {
+            100
+        }
+    }
+}
+
+fn bool_u(b: bool) -> U256 {
+    if b {
+        U256::ONE
+    } else {
+        U256::ZERO
+    }
+}
+
+fn identity(input: &[u8]) -> (bool, Vec<u8>) {
+    (true, input.to_vec())
+}
+
+pub fn jumpdests(code: &[u8]) -> HashSet<usize> {
+    let mut out = HashSet::new();
+    let mut i = 0;
+    while i < code.len() {
+        let op = code[i];
+        if op == 0x5b {
+            out.insert(i);
+        }
+        if (0x60..=0x7f).contains(&op) {
+            i += (op - 0x5f) as usize;
+        }
+        i += 1;
+    }
+    out
+}
+
+fn rlp_bytes(b: &[u8]) -> Vec<u8> {
+    if b.len() == 1 && b[0] < 0x80 {
+        return b.to_vec();
+    }
+    let mut out = vec![0x80 + b.len() as u8];
+    out.extend(b);
+    out
+}
+
+pub fn create_address(deployer: &Address, nonce: u64) -> Address {
+    let nonce_bytes: Vec<u8> = {
+        let b = nonce.to_be_bytes();
+        let first = b.iter().position(|x| *x != 0).unwrap_or(8);
+        b[first..].to_vec()
+    };
+    let mut payload = rlp_bytes(deployer);
+    payload.extend(rlp_bytes(&nonce_bytes));
+    let mut rlp = vec![0xc0 + payload.len() as u8];
+    rlp.extend(payload);
+    let h = keccak256(&rlp);
+    h[12..].try_into().unwrap()
+}
+
+pub fn create2_address(deployer: &Address, salt: U256, initcode: &[u8]) -> Address {
+    let mut buf = vec![0xff];
+    buf.extend(deployer);
+    buf.extend(salt.to_be_bytes());
+    buf.extend(keccak256(initcode));
+    let h = keccak256(&buf);
+    h[12..].try_into().unwrap()
+}
@@ -0,0 +1,1183 @@
+//! Minimal Huff assembler, for compiling generated macros without an external compiler.
+//!
+//! Supports the subset of Huff used by the generated libraries: macros with template arguments,
+//! jump labels, constants (including `FREE_STORAGE_POINTER()`), errors, events, functions, jump
+//! tables, code tables, `#include` and the `__ERROR`, `__FUNC_SIG`, `__EVENT_HASH`, `__RIGHTPAD`,
+//! `__tablestart`, `__tablesize` and `__codesize` builtins.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use crate::keccak::keccak256;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Error {
+    pub msg: String,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.msg)
+    }
+}
+
+fn err<T>(msg: impl Into<String>) -> Result<T, Error> {
+    Err(Error { msg: msg.into() })
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Tok {
+    Ident(String),
+    Hex(String),
+    Dec(String),
+    Str(String),
+    Punct(char),
+    Directive(String),
+    Arrow,
+}
+
+fn lex(src: &str) -> Result<Vec<(Tok, usize)>, Error> {
+    let chars: Vec<char> = src.chars().collect();
+    let mut i = 0;
+    let mut line = 1;
+    let mut out = Vec::new();
+    while i < chars.len() {
+        let c = chars[i];
+        if c == '\n' {
+            line += 1;
+            i += 1;
+        } else if c.is_whitespace() {
+            i += 1;
+        } else if c == '/' && i + 1 < chars.len() && chars[i + 1] == '/' {
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+        } else if c == '/' && i + 1 < chars.len() && chars[i + 1] == '*' {
+            i += 2;
+            while i + 1 < chars.len() && !(chars[i] == '*' && chars[i + 1] == '/') {
+                if chars[i] == '\n' {
+                    line += 1;
+                }
+                i += 1;
+            }
+            i += 2;
+        } else if c == '#' {
+            let start = i + 1;
+            i += 1;
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            out.push((Tok::Directive(chars[start..i].iter().collect()), line));
+        } else if c == '"' || c == '\'' {
+            let q = c;
+            i += 1;
+            let start = i;
+            while i < chars.len() && chars[i] != q {
+                i += 1;
+            }
+            out.push((Tok::Str(chars[start..i].iter().collect()), line));
+            i += 1;
+        } else if c == '0' && i + 1 < chars.len() && (chars[i + 1] == 'x' || chars[i + 1] == 'X') {
+            i += 2;
+            let start = i;
+            while i < chars.len() && chars[i].is_ascii_hexdigit() {
+                i += 1;
+            }
+            out.push((Tok::Hex(chars[start..i].iter().collect()), line));
+        } else if c.is_ascii_digit() {
+            let start = i;
+            while i < chars.len() && chars[i].is_ascii_digit() {
+                i += 1;
+            }
+            out.push((Tok::Dec(chars[start..i].iter().collect()), line));
+        } else if c.is_alphabetic() || c == '_' || c == '$' {
+            let start = i;
+            while i < chars.len()
+                && (chars[i].is_alphanumeric() || chars[i] == '_' || chars[i] == '$')
+            {
+                i += 1;
+            }
+            out.push((Tok::Ident(chars[start..i].iter().collect()), line));
+        } else if c == '-' && i + 1 < chars.len() && chars[i + 1] == '>' {
+            out.push((Tok::Arrow, line));
+            i += 2;
+        } else {
+            out.push((Tok::Punct(c), line));
+            i += 1;
+        }
+    }
+    Ok(out)
+}
+
+/// An argument passed at a macro invocation site.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Arg {
+    Literal(Vec<u8>),
+    Constant(String),
+    Param(String),
+    Ident(String),
+    Call(String, Vec<Arg>),
+    Builtin(String, Vec<Arg>),
+    Str(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Stmt {
+    Literal(Vec<u8>),
+    Constant(String),
+    Param(String),
+    Ident(String),
+    Label(String),
+    Call(String, Vec<Arg>),
+    Builtin(String, Vec<Arg>),
+}
+
+#[derive(Debug, Clone)]
+pub struct Macro {
+    pub params: Vec<String>,
+    pub takes: usize,
+    pub returns: usize,
+    pub body: Vec<Stmt>,
+}
+
+#[derive(Debug, Clone)]
+pub enum TableKind {
+    Jump,
+    Packed,
+    Code,
+}
+
+#[derive(Debug, Clone)]
+pub struct Table {
+    pub kind: TableKind,
+    pub labels: Vec<String>,
+    pub code: Vec<u8>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Program {
+    pub macros: HashMap<String, Macro>,
+    pub constants: HashMap<String, Vec<u8>>,
+    pub errors: HashMap<String, String>,
+    pub events: HashMap<String, String>,
+    pub functions: HashMap<String, String>,
+    pub tables: HashMap<String, Table>,
+    pub table_order: Vec<String>,
+    free_storage_pointer: u64,
+}
+
+fn hex_to_bytes(hex: &str) -> Result<Vec<u8>, Error> {
+    let mut h = hex.to_string();
+    if h.len() % 2 == 1 {
+        h.insert(0, '0');
+    }
+    if h.is_empty() {
+        return err("empty hex literal");
+    }
+    (0..h.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&h[i..i + 2], 16).map_err(|e| Error { msg: e.to_string() }))
+        .collect()
+}
+
+/// The bytes of the hex literal `hex` of a word, failing if they do not fit in one.
+fn hex_to_word(hex: &str) -> Result<Vec<u8>, Error> {
+    let bytes = hex_to_bytes(hex)?;
+    if trim(&bytes).len() > 32 {
+        return err(format!("hex literal 0x{} overflows 256 bits", hex));
+    }
+    Ok(bytes)
+}
+
+fn dec_to_bytes(dec: &str) -> Result<Vec<u8>, Error> {
+    let mut word = [0u8; 32];
+    for digit in dec.chars() {
+        let mut carry = digit.to_digit(10).unwrap_or(0) as u16;
+        for byte in word.iter_mut().rev() {
+            let v = *byte as u16 * 10 + carry;
+            *byte = v as u8;
+            carry = v >> 8;
+        }
+        if carry != 0 {
+            return err(format!("decimal literal {} overflows 256 bits", dec));
+        }
+    }
+    Ok(trim(&word))
+}
+
+/// Strips the leading zero bytes of a big-endian value, keeping at least one byte.
+fn trim(bytes: &[u8]) -> Vec<u8> {
+    let first = bytes
+        .iter()
+        .position(|x| *x != 0)
+        .unwrap_or(bytes.len() - 1);
+    bytes[first..].to_vec()
+}
+
+/// Canonicalizes a parameter list such as `(address to, uint256 indexed amount)`.
+fn canonical_params(toks: &[Tok]) -> String {
+    let mut out = String::new();
+    let mut depth = 0;
+    let mut expect_type = true;
+    for t in toks {
+        match t {
+            Tok::Punct('(') => {
+                if depth > 0 {
+                    out.push('(');
+                }
+                depth += 1;
+                expect_type = true;
+            }
+            Tok::Punct(')') => {
+                depth -= 1;
+                if depth > 0 {
+                    out.push(')');
+                }
+                expect_type = false;
+            }
+            Tok::Punct(',') => {
+                out.push(',');
+                expect_type = true;
+            }
+            Tok::Punct('[') => {
+                out.push('[');
+            }
+            Tok::Punct(']') => {
+                out.push(']');
+            }
+            Tok::Dec(d) => out.push_str(d),
+            Tok::Ident(id) if expect_type => {
+                out.push_str(id);
+                expect_type = false;
+            }
+            _ => {}
+        }
+    }
+    out
+}
+
+pub fn selector(sig: &str) -> [u8; 4] {
+    let h = keccak256(sig.as_bytes());
+    [h[0], h[1], h[2], h[3]]
+}
+
+struct Parser {
+    toks: Vec<(Tok, usize)>,
+    pos: usize,
+    file: PathBuf,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Tok> {
+        self.toks.get(self.pos).map(|t| &t.0)
+    }
+
+    fn peek_at(&self, n: usize) -> Option<&Tok> {
+        self.toks.get(self.pos + n).map(|t| &t.0)
+    }
+
+    fn line(&self) -> usize {
+        self.toks
+            .get(self.pos)
+            .or(self.toks.last())
+            .map(|t| t.1)
+            .unwrap_or(0)
+    }
+
+    fn next(&mut self) -> Result<Tok, Error> {
+        let t = self.toks.get(self.pos).cloned();
+        self.pos += 1;
+        match t {
+            Some((t, _)) => Ok(t),
+            None => err(format!("{}: unexpected end of file", self.file.display())),
+        }
+    }
+
+    fn fail<T>(&self, msg: &str) -> Result<T, Error> {
+        err(format!("{}:{}: {}", self.file.display(), self.line(), msg))
+    }
+
+    fn expect_punct(&mut self, c: char) -> Result<(), Error> {
+        match self.next()? {
+            Tok::Punct(p) if p == c => Ok(()),
+            t => {
+                self.pos -= 1;
+                self.fail(&format!("expected `{}`, found {:?}", c, t))
+            }
+        }
+    }
+
+    fn expect_ident(&mut self) -> Result<String, Error> {
+        match self.next()? {
+            Tok::Ident(s) => Ok(s),
+            t => {
+                self.pos -= 1;
+                self.fail(&format!("expected identifier, found {:?}", t))
+            }
+        }
+    }
+
+    fn expect_keyword(&mut self, kw: &str) -> Result<(), Error> {
+        let id = self.expect_ident()?;
+        if id != kw {
+            self.pos -= 1;
+            return self.fail(&format!("expected `{}`, found `{}`", kw, id));
+        }
+        Ok(())
+    }
+
+    fn paren_group(&mut self) -> Result<Vec<Tok>, Error> {
+        let mut depth = 0;
+        let mut out = Vec::new();
+        loop {
+            let t = self.next()?;
+            match t {
+                Tok::Punct('(') => depth += 1,
+                Tok::Punct(')') => depth -= 1,
+                _ => {}
+            }
+            out.push(t);
+            if depth == 0 {
+                return Ok(out);
+            }
+        }
+    }
+
+    fn number(&mut self) -> Result<usize, Error> {
+        let t = self.next()?;
+        let number = match &t {
+            Tok::Dec(d) => d.parse().ok(),
+            Tok::Hex(h) => usize::from_str_radix(h, 16).ok(),
+            _ => {
+                self.pos -= 1;
+                return self.fail(&format!("expected number, found {:?}", t));
+            }
+        };
+        number.map_or_else(
+            || {
+                self.pos -= 1;
+                self.fail(&format!("number too large: {:?}", t))
+            },
+            Ok,
+        )
+    }
+
+    fn arg(&mut self) -> Result<Arg, Error> {
+        match self.next()? {
+            Tok::Hex(h) => Ok(Arg::Literal(hex_to_word(&h)?)),
+            Tok::Dec(d) => Ok(Arg::Literal(dec_to_bytes(&d)?)),
+            Tok::Str(s) => Ok(Arg::Str(s)),
+            Tok::Punct('[') => {
+                let name = self.expect_ident()?;
+                self.expect_punct(']')?;
+                Ok(Arg::Constant(name))
+            }
+            Tok::Punct('<') => {
+                let name = self.expect_ident()?;
+                self.expect_punct('>')?;
+                Ok(Arg::Param(name))
+            }
+            Tok::Ident(id) => {
+                if self.peek() == Some(&Tok::Punct('(')) {
+                    let args = self.args()?;
+                    if id.starts_with("__") && is_builtin(&id) {
+                        Ok(Arg::Builtin(id, args))
+                    } else {
+                        Ok(Arg::Call(id, args))
+                    }
+                } else {
+                    Ok(Arg::Ident(id))
+                }
+            }
+            t => {
+                self.pos -= 1;
+                self.fail(&format!("unexpected token in argument: {:?}", t))
+            }
+        }
+    }
+
+    fn args(&mut self) -> Result<Vec<Arg>, Error> {
+        self.expect_punct('(')?;
+        let mut args = Vec::new();
+        if self.peek() == Some(&Tok::Punct(')')) {
+            self.next()?;
+            return Ok(args);
+        }
+        loop {
+            args.push(self.arg()?);
+            match self.next()? {
+                Tok::Punct(',') => continue,
+                Tok::Punct(')') => break,
+                t => {
+                    self.pos -= 1;
+                    return self.fail(&format!("expected `,` or `)`, found {:?}", t));
+                }
+            }
+        }
+        Ok(args)
+    }
+
+    fn body(&mut self) -> Result<Vec<Stmt>, Error> {
+        self.expect_punct('{')?;
+        let mut body = Vec::new();
+        loop {
+            let t = self.next()?;
+            match t {
+                Tok::Punct('}') => return Ok(body),
+                Tok::Hex(h) => body.push(Stmt::Literal(hex_to_word(&h)?)),
+                Tok::Dec(d) => body.push(Stmt::Literal(dec_to_bytes(&d)?)),
+                Tok::Punct('[') => {
+                    let name = self.expect_ident()?;
+                    self.expect_punct(']')?;
+                    body.push(Stmt::Constant(name));
+                }
+                Tok::Punct('<') => {
+                    let name = self.expect_ident()?;
+                    self.expect_punct('>')?;
+                    body.push(Stmt::Param(name));
+                }
+                Tok::Ident(id) => {
+                    if self.peek() == Some(&Tok::Punct(':'))
+                        && self.peek_at(1) != Some(&Tok::Punct(':'))
+                    {
+                        self.next()?;
+                        body.push(Stmt::Label(id));
+                    } else if self.peek() == Some(&Tok::Punct('(')) {
+                        let args = self.args()?;
+                        if is_builtin(&id) {
+                            body.push(Stmt::Builtin(id, args));
+                        } else {
+                            body.push(Stmt::Call(id, args));
+                        }
+                    } else {
+                        body.push(Stmt::Ident(id));
+                    }
+                }
+                t => {
+                    self.pos -= 1;
+                    return self.fail(&format!("unexpected token in macro body: {:?}", t));
+                }
+            }
+        }
+    }
+}
+
+fn is_builtin(name: &str) -> bool {
+    matches!(
+        name,
+        "__ERROR"
+            | "__FUNC_SIG"
+            | "__EVENT_HASH"
+            | "__RIGHTPAD"
+            | "__tablestart"
+            | "__tablesize"
+            | "__codesize"
+    )
+}
+
+impl Program {
+    pub fn parse_str(src: &str, base: &Path) -> Result<Program, Error> {
+        let mut prog = Program::default();
+        let mut seen = Vec::new();
+        prog.load_src(src, &base.join("<input>"), &mut seen)?;
+        Ok(prog)
+    }
+
+    /// Parses `src` as the contents of `file`, which errors are reported against, resolving its
+    /// includes from the directory of `file`.
+    pub fn parse_file(src: &str, file: &Path) -> Result<Program, Error> {
+        let mut prog = Program::default();
+        prog.load_src(src, file, &mut Vec::new())?;
+        Ok(prog)
+    }
+
+    fn load(&mut self, path: &Path, seen: &mut Vec<PathBuf>) -> Result<(), Error> {
+        let canonical = path.canonicalize().map_err(|e| Error {
+            msg: format!("{}: {}", path.display(), e),
+        })?;
+        if seen.contains(&canonical) {
+            return Ok(());
+        }
+        seen.push(canonical.clone());
+        let src = std::fs::read_to_string(&canonical).map_err(|e| Error {
+            msg: format!("{}: {}", path.display(), e),
+        })?;
+        self.load_src(&src, &canonical, seen)
+    }
+
+    fn load_src(&mut self, src: &str, file: &Path, seen: &mut Vec<PathBuf>) -> Result<(), Error> {
+        let mut p = Parser {
+            toks: lex(src)?,
+            pos: 0,
+            file: file.to_path_buf(),
+        };
+        while let Some(t) = p.peek().cloned() {
+            match t {
+                Tok::Directive(d) if d == "include" => {
+                    p.next()?;
+                    let inc = match p.next()? {
+                        Tok::Str(s) => s,
+                        _ => return p.fail("expected include path"),
+                    };
+                    if p.peek() == Some(&Tok::Punct(';')) {
+                        p.next()?;
+                    }
+                    let dir = file.parent().unwrap_or(Path::new("."));
+                    self.load(&dir.join(inc), seen)?;
+                }
+                Tok::Directive(d) if d == "define" => {
+                    p.next()?;
+                    self.define(&mut p)?;
+                }
+                Tok::Punct(';') => {
+                    p.next()?;
+                }
+                t => return p.fail(&format!("unexpected top-level token {:?}", t)),
+            }
+        }
+        Ok(())
+    }
+
+    fn define(&mut self, p: &mut Parser) -> Result<(), Error> {
+        let kind = p.expect_ident()?;
+        match kind.as_str() {
+            "macro" | "fn" => {
+                let name = p.expect_ident()?;
+                p.expect_punct('(')?;
+                let mut params = Vec::new();
+                while p.peek() != Some(&Tok::Punct(')')) {
+                    params.push(p.expect_ident()?);
+                    if p.peek() == Some(&Tok::Punct(',')) {
+                        p.next()?;
+                    }
+                }
+                p.expect_punct(')')?;
+                p.expect_punct('=')?;
+                p.expect_keyword("takes")?;
+                p.expect_punct('(')?;
+                let takes = p.number()?;
+                p.expect_punct(')')?;
+                p.expect_keyword("returns")?;
+                p.expect_punct('(')?;
+                let returns = p.number()?;
+                p.expect_punct(')')?;
+                let body = p.body()?;
+                if self.macros.contains_key(&name) {
+                    return p.fail(&format!("duplicate macro `{}`", name));
+                }
+                self.macros.insert(
+                    name.clone(),
+                    Macro {
+                        params,
+                        takes,
+                        returns,
+                        body,
+                    },
+                );
+            }
+            "constant" => {
+                let name = p.expect_ident()?;
+                p.expect_punct('=')?;
+                let value = match p.next()? {
+                    Tok::Hex(h) => hex_to_word(&h)?,
+                    Tok::Dec(d) => dec_to_bytes(&d)?,
+                    Tok::Ident(id) if id == "FREE_STORAGE_POINTER" => {
+                        p.expect_punct('(')?;
+                        p.expect_punct(')')?;
+                        let v = self.free_storage_pointer;
+                        self.free_storage_pointer += 1;
+                        trim(&v.to_be_bytes())
+                    }
+                    _ => return p.fail("expected constant value"),
+                };
+                if self.constants.contains_key(&name) {
+                    return p.fail(&format!("duplicate constant `{}`", name));
+                }
+                self.constants.insert(name, value);
+                if p.peek() == Some(&Tok::Punct(';')) {
+                    p.next()?;
+                }
+            }
+            "error" | "event" | "function" => {
+                let name = p.expect_ident()?;
+                let params = p.paren_group()?;
+                let sig = format!("{}({})", name, canonical_params(&params));
+                // skip trailing modifiers such as `view returns (uint256)`
+                while let Some(Tok::Ident(id)) = p.peek().cloned() {
+                    p.next()?;
+                    if id == "returns" {
+                        p.paren_group()?;
+                    }
+                }
+                let map = match kind.as_str() {
+                    "error" => &mut self.errors,
+                    "event" => &mut self.events,
+                    _ => &mut self.functions,
+                };
+                // libraries included together may each declare the same error
+                if map.get(&name).is_some_and(|existing| *existing != sig) {
+                    return p.fail(&format!("duplicate {} `{}`", kind, name));
+                }
+                map.insert(name, sig);
+                if p.peek() == Some(&Tok::Punct(';')) {
+                    p.next()?;
+                }
+            }
+            "jumptable" | "jumptable__packed" | "table" => {
+                let name = p.expect_ident()?;
+                p.expect_punct('{')?;
+                let mut labels = Vec::new();
+                let mut code = Vec::new();
+                loop {
+                    match p.next()? {
+                        Tok::Punct('}') => break,
+                        Tok::Ident(l) => labels.push(l),
+                        Tok::Hex(h) => code.extend(hex_to_bytes(&h)?),
+                        _ => return p.fail("unexpected token in table"),
+                    }
+                }
+                let kind = match kind.as_str() {
+                    "jumptable" => TableKind::Jump,
+                    "jumptable__packed" => TableKind::Packed,
+                    _ => TableKind::Code,
+                };
+                self.table_order.push(name.clone());
+                self.tables.insert(name, Table { kind, labels, code });
+            }
+            k => return p.fail(&format!("unsupported definition `{}`", k)),
+        }
+        Ok(())
+    }
+}
+
+pub fn opcode(name: &str) -> Option<u8> {
+    let op = match name {
+        "stop" => 0x00,
+        "add" => 0x01,
+        "mul" => 0x02,
+        "sub" => 0x03,
+        "div" => 0x04,
+        "sdiv" => 0x05,
+        "mod" => 0x06,
+        "smod" => 0x07,
+        "addmod" => 0x08,
+        "mulmod" => 0x09,
+        "exp" => 0x0a,
+        "signextend" => 0x0b,
+        "lt" => 0x10,
+        "gt" => 0x11,
+        "slt" => 0x12,
+        "sgt" => 0x13,
+        "eq" => 0x14,
+        "iszero" => 0x15,
+        "and" => 0x16,
+        "or" => 0x17,
+        "xor" => 0x18,
+        "not" => 0x19,
+        "byte" => 0x1a,
+        "shl" => 0x1b,
+        "shr" => 0x1c,
+        "sar" => 0x1d,
+        "sha3" | "keccak256" => 0x20,
+        "address" => 0x30,
+        "balance" => 0x31,
+        "origin" => 0x32,
+        "caller" => 0x33,
+        "callvalue" => 0x34,
+        "calldataload" => 0x35,
+        "calldatasize" => 0x36,
+        "calldatacopy" => 0x37,
+        "codesize" => 0x38,
+        "codecopy" => 0x39,
+        "gasprice" => 0x3a,
+        "extcodesize" => 0x3b,
+        "extcodecopy" => 0x3c,
+        "returndatasize" => 0x3d,
+        "returndatacopy" => 0x3e,
+        "extcodehash" => 0x3f,
+        "blockhash" => 0x40,
+        "coinbase" => 0x41,
+        "timestamp" => 0x42,
+        "number" => 0x43,
+        "prevrandao" | "difficulty" => 0x44,
+        "gaslimit" => 0x45,
+        "chainid" => 0x46,
+        "selfbalance" => 0x47,
+        "basefee" => 0x48,
+        "blobhash" => 0x49,
+        "blobbasefee" => 0x4a,
+        "pop" => 0x50,
+        "mload" => 0x51,
+        "mstore" => 0x52,
+        "mstore8" => 0x53,
+        "sload" => 0x54,
+        "sstore" => 0x55,
+        "jump" => 0x56,
+        "jumpi" => 0x57,
+        "pc" => 0x58,
+        "msize" => 0x59,
+        "gas" => 0x5a,
+        "jumpdest" => 0x5b,
+        "tload" => 0x5c,
+        "tstore" => 0x5d,
+        "mcopy" => 0x5e,
+        "push0" => 0x5f,
+        "log0" => 0xa0,
+        "log1" => 0xa1,
+        "log2" => 0xa2,
+        "log3" => 0xa3,
+        "log4" => 0xa4,
+        "create" => 0xf0,
+        "call" => 0xf1,
+        "callcode" => 0xf2,
+        "return" => 0xf3,
+        "delegatecall" => 0xf4,
+        "create2" => 0xf5,
+        "staticcall" => 0xfa,
+        "revert" => 0xfd,
+        "invalid" => 0xfe,
+        "selfdestruct" => 0xff,
+        _ => {
+            if let Some(n) = name.strip_prefix("push") {
+                let n: u8 = n.parse().ok()?;
+                if (1..=32).contains(&n) {
+                    return Some(0x5f + n);
+                }
+                return None;
+            }
+            if let Some(n) = name.strip_prefix("dup") {
+                let n: u8 = n.parse().ok()?;
+                if (1..=16).contains(&n) {
+                    return Some(0x7f + n);
+                }
+                return None;
+            }
+            if let Some(n) = name.strip_prefix("swap") {
+                let n: u8 = n.parse().ok()?;
+                if (1..=16).contains(&n) {
+                    return Some(0x8f + n);
+                }
+                return None;
+            }
+            return None;
+        }
+    };
+    Some(op)
+}
+
+/// Template argument bound in a macro invocation, along with the scope it was written in.
+#[derive(Clone, Debug)]
+struct Bound {
+    arg: Arg,
+    scope: usize,
+}
+
+struct Scope {
+    parent: Option<usize>,
+    args: HashMap<String, Bound>,
+    labels: HashMap<String, usize>,
+}
+
+struct Codegen<'a> {
+    prog: &'a Program,
+    code: Vec<u8>,
+    scopes: Vec<Scope>,
+    label_refs: Vec<(usize, usize, String)>,
+    table_refs: Vec<(usize, String, bool)>,
+    depth: usize,
+    push0: bool,
+}
+
+fn push_bytes(code: &mut Vec<u8>, bytes: &[u8], push0: bool) {
+    let first = bytes.iter().position(|x| *x != 0);
+    match first {
+        None if push0 => code.push(0x5f),
+        None => code.extend([0x60, 0x00]),
+        Some(i) => {
+            let b = &bytes[i..];
+            code.push(0x5f + b.len() as u8);
+            code.extend(b);
+        }
+    }
+}
+
+impl Codegen<'_> {
+    fn new_scope(&mut self, parent: Option<usize>) -> usize {
+        self.scopes.push(Scope {
+            parent,
+            args: HashMap::new(),
+            labels: HashMap::new(),
+        });
+        self.scopes.len() - 1
+    }
+
+    fn constant(&self, name: &str) -> Result<Vec<u8>, Error> {
+        self.prog.constants.get(name).cloned().ok_or(Error {
+            msg: format!("unknown constant `{}`", name),
+        })
+    }
+
+    fn label_ref(&mut self, scope: usize, name: &str) {
+        self.code.push(0x61);
+        self.label_refs
+            .push((self.code.len(), scope, name.to_string()));
+        self.code.extend([0, 0]);
+    }
+
+    fn ident(&mut self, scope: usize, name: &str) -> Result<(), Error> {
+        if let Some(op) = opcode(name) {
+            if (0x60..=0x7f).contains(&op) {
+                return err(format!("bare `{}` is not supported", name));
+            }
+            self.code.push(op);
+            return Ok(());
+        }
+        if let Some(m) = self.prog.macros.get(name) {
+            if m.params.is_empty() {
+                return self.invoke(scope, name, &[]);
+            }
+        }
+        self.label_ref(scope, name);
+        Ok(())
+    }
+
+    fn builtin(&mut self, scope: usize, name: &str, args: &[Arg]) -> Result<(), Error> {
+        let first = match args.first() {
+            Some(Arg::Ident(s)) | Some(Arg::Str(s)) => s.clone(),
+            Some(Arg::Call(s, a)) if a.is_empty() => s.clone(),
+            Some(Arg::Literal(b)) => {
+                let hex: String = b.iter().map(|x| format!("{:02x}", x)).collect();
+                hex
+            }
+            Some(Arg::Param(a)) => {
+                let bound = self.lookup_arg(scope, a)?;
+                match bound.arg {
+                    Arg::Ident(s) | Arg::Str(s) => s,
+                    _ => return err(format!("unsupported argument to {}", name)),
+                }
+            }
+            _ => return err(format!("{} requires an argument", name)),
+        };
+        match name {
+            "__ERROR" => {
+                let sig = self.prog.errors.get(&first).ok_or(Error {
+                    msg: format!("unknown error `{}`", first),
+                })?;
+                let mut word = [0u8; 32];
+                word[..4].copy_from_slice(&selector(sig));
+                self.code.push(0x7f);
+                self.code.extend(word);
+            }
+            "__FUNC_SIG" => {
+                let sig = if first.contains('(') {
+                    first
+                } else {
+                    self.prog
+                        .functions
+                        .get(&first)
+                        .or(self.prog.errors.get(&first))
+                        .cloned()
+                        .ok_or(Error {
+                            msg: format!("unknown function `{}`", first),
+                        })?
+                };
+                self.code.push(0x63);
+                self.code.extend(selector(&sig));
+            }
+            "__EVENT_HASH" => {
+                let sig = if first.contains('(') {
+                    first
+                } else {
+                    self.prog.events.get(&first).cloned().ok_or(Error {
+                        msg: format!("unknown event `{}`", first),
+                    })?
+                };
+                self.code.push(0x7f);
+                self.code.extend(keccak256(sig.as_bytes()));
+            }
+            "__RIGHTPAD" => {
+                let bytes = hex_to_bytes(&first)?;
+                if bytes.len() > 32 {
+                    return err(format!(
+                        "`__RIGHTPAD` of {} bytes, more than a word",
+                        bytes.len()
+                    ));
+                }
+                let mut word = [0u8; 32];
+                word[..bytes.len()].copy_from_slice(&bytes);
+                self.code.push(0x7f);
+                self.code.extend(word);
+            }
+            "__tablestart" => {
+                self.code.push(0x61);
+                self.table_refs.push((self.code.len(), first, false));
+                self.code.extend([0, 0]);
+            }
+            "__tablesize" => {
+                let t = self.prog.tables.get(&first).ok_or(Error {
+                    msg: format!("unknown table `{}`", first),
+                })?;
+                let size = u16::try_from(table_size(t)).map_err(|_| Error {
+                    msg: format!(
+                        "table `{}` of {} bytes is too large for `__tablesize`",
+                        first,
+                        table_size(t)
+                    ),
+                })?;
+                self.code.push(0x61);
+                self.code.extend(size.to_be_bytes());
+            }
+            "__codesize" => {
+                let size = compile_macro_size(self.prog, &first, self.push0)?;
+                let size = u16::try_from(size).map_err(|_| Error {
+                    msg: format!(
+                        "macro `{}` of {} bytes is too large for `__codesize`",
+                        first, size
+                    ),
+                })?;
+                self.code.push(0x61);
+                self.code.extend(size.to_be_bytes());
+            }
+            _ => return err(format!("unsupported builtin {}", name)),
+        }
+        Ok(())
+    }
+
+    fn lookup_arg(&self, scope: usize, name: &str) -> Result<Bound, Error> {
+        self.scopes[scope].args.get(name).cloned().ok_or(Error {
+            msg: format!("unknown template argument `<{}>`", name),
+        })
+    }
+
+    fn emit_arg(&mut self, scope: usize, arg: &Arg) -> Result<(), Error> {
+        match arg {
+            Arg::Literal(b) => {
+                push_bytes(&mut self.code, b, self.push0);
+                Ok(())
+            }
+            Arg::Constant(c) => {
+                let v = self.constant(c)?;
+                push_bytes(&mut self.code, &v, self.push0);
+                Ok(())
+            }
+            Arg::Param(a) => {
+                let bound = self.lookup_arg(scope, a)?;
+                self.emit_arg(bound.scope, &bound.arg)
+            }
+            Arg::Ident(id) => self.ident(scope, id),
+            Arg::Call(name, args) => self.invoke(scope, name, args),
+            Arg::Builtin(name, args) => self.builtin(scope, name, args),
+            Arg::Str(_) => err("string literal cannot be emitted"),
+        }
+    }
+
+    fn invoke(&mut self, caller: usize, name: &str, args: &[Arg]) -> Result<(), Error> {
+        let m = self.prog.macros.get(name).ok_or(Error {
+            msg: format!("unknown macro `{}`", name),
+        })?;
+        if m.params.len() != args.len() {
+            return err(format!(
+                "macro `{}` expects {} arguments, found {}",
+                name,
+                m.params.len(),
+                args.len()
+            ));
+        }
+        self.depth += 1;
+        if self.depth > 64 {
+            return err(format!("macro recursion too deep at `{}`", name));
+        }
+        let scope = self.new_scope(Some(caller));
+        for (p, a) in m.params.iter().zip(args) {
+            // resolve `<x>` references eagerly so they bind to the caller's arguments
+            let bound = match a {
+                Arg::Param(r) => self.lookup_arg(caller, r)?,
+                _ => Bound {
+                    arg: a.clone(),
+                    scope: caller,
+                },
+            };
+            self.scopes[scope].args.insert(p.clone(), bound);
+        }
+        for stmt in &m.body {
+            match stmt {
+                Stmt::Literal(b) => push_bytes(&mut self.code, b, self.push0),
+                Stmt::Constant(c) => {
+                    let v = self.constant(c)?;
+                    push_bytes(&mut self.code, &v, self.push0);
+                }
+                Stmt::Param(a) => {
+                    let bound = self.lookup_arg(scope, a)?;
+                    self.emit_arg(bound.scope, &bound.arg)?;
+                }
+                Stmt::Ident(id) => self.ident(scope, id)?,
+                Stmt::Label(l) => {
+                    if self.scopes[scope].labels.contains_key(l) {
+                        return err(format!("duplicate label `{}` in `{}`", l, name));
+                    }
+                    self.scopes[scope].labels.insert(l.clone(), self.code.len());
+                    self.code.push(0x5b);
+                }
+                Stmt::Call(n, a) => self.invoke(scope, n, a)?,
+                Stmt::Builtin(n, a) => self.builtin(scope, n, a)?,
+            }
+        }
+        self.depth -= 1;
+        Ok(())
+    }
+
+    fn resolve_label(&self, scope: usize, name: &str) -> Option<usize> {
+        let mut s = Some(scope);
+        while let Some(i) = s {
+            if let Some(off) = self.scopes[i].labels.get(name) {
+                return Some(*off);
+            }
+            s = self.scopes[i].parent;
+        }
+        // fall back to a label defined in a child scope, if it is unique
+        let mut found = None;
+        for (i, sc) in self.scopes.iter().enumerate() {
+            if let Some(off) = sc.labels.get(name) {
+                let mut p = Some(i);
+                let mut descends = false;
+                while let Some(j) = p {
+                    if j == scope {
+                        descends = true;
+                        break;
+                    }
+                    p = self.scopes[j].parent;
+                }
+                if descends {
+                    if found.is_some() {
+                        return None;
+                    }
+                    found = Some(*off);
+                }
+            }
+        }
+        found
+    }
+}
+
+fn table_size(t: &Table) -> usize {
+    match t.kind {
+        TableKind::Jump => t.labels.len() * 32,
+        TableKind::Packed => t.labels.len() * 2,
+        TableKind::Code => t.code.len(),
+    }
+}
+
+fn compile_macro_size(prog: &Program, name: &str, push0: bool) -> Result<usize, Error> {
+    Ok(compile(prog, name, push0)?.len())
+}
+
+/// Compiles the macro `entry` (and any tables it references) to runtime bytecode, pushing zero
+/// with `PUSH0` if `push0` is set.
+pub fn compile(prog: &Program, entry: &str, push0: bool) -> Result<Vec<u8>, Error> {
+    let mut cg = Codegen {
+        prog,
+        code: Vec::new(),
+        scopes: Vec::new(),
+        label_refs: Vec::new(),
+        table_refs: Vec::new(),
+        depth: 0,
+        push0,
+    };
+    let root = cg.new_scope(None);
+    cg.invoke(root, entry, &[])?;
+    let mut code = std::mem::take(&mut cg.code);
+    // append referenced tables
+    let mut table_offsets = HashMap::new();
+    let mut all_labels: HashMap<String, Vec<usize>> = HashMap::new();
+    for sc in &cg.scopes {
+        for (l, off) in &sc.labels {
+            all_labels.entry(l.clone()).or_default().push(*off);
+        }
+    }
+    for name in &prog.table_order {
+        let used = cg.table_refs.iter().any(|(_, t, _)| t == name);
+        if !used {
+            continue;
+        }
+        let t = &prog.tables[name];
+        table_offsets.insert(name.clone(), code.len());
+        match t.kind {
+            TableKind::Code => code.extend(&t.code),
+            TableKind::Jump | TableKind::Packed => {
+                for l in &t.labels {
+                    let offs = all_labels.get(l).ok_or(Error {
+                        msg: format!("unknown label `{}` in table `{}`", l, name),
+                    })?;
+                    let off = u16::try_from(offs[0]).map_err(|_| Error {
+                        msg: format!(
+                            "label `{}` at offset {} does not fit table `{}`",
+                            l, offs[0], name
+                        ),
+                    })?;
+                    if let TableKind::Jump = t.kind {
+                        let mut w = [0u8; 32];
+                        w[30..].copy_from_slice(&off.to_be_bytes());
+                        code.extend(w);
+                    } else {
+                        code.extend(off.to_be_bytes());
+                    }
+                }
+            }
+        }
+    }
+    for (pos, scope, name) in &cg.label_refs {
+        let off = cg.resolve_label(*scope, name).ok_or(Error {
+            msg: format!("unknown label or opcode `{}`", name),
+        })?;
+        let off = u16::try_from(off).map_err(|_| Error {
+            msg: format!(
+                "label `{}` at offset {} is out of reach of `PUSH2`",
+                name, off
+            ),
+        })?;
+        code[*pos..*pos + 2].copy_from_slice(&off.to_be_bytes());
+    }
+    for (pos, name, _) in &cg.table_refs {
+        let off = table_offsets.get(name).ok_or(Error {
+            msg: format!("unknown table `{}`", name),
+        })?;
+        let off = u16::try_from(*off).map_err(|_| Error {
+            msg: format!(
+                "table `{}` at offset {} is out of reach of `PUSH2`",
+                name, off
+            ),
+        })?;
+        code[*pos..*pos + 2].copy_from_slice(&off.to_be_bytes());
+    }
+    Ok(code)
+}
+
+/// Wraps `runtime` in deployment bytecode: `constructor`, then a bootstrap copying the runtime
+/// code after it to memory and returning it, pushing zero with `PUSH0` if `push0` is set.
+pub fn deployment(constructor: &[u8], runtime: &[u8], push0: bool) -> Result<Vec<u8>, Error> {
+    let zero: &[u8] = if push0 { &[0x5f] } else { &[0x60, 0x00] };
+    // PUSH2 size DUP1 PUSH2 offset 0 CODECOPY 0 RETURN
+    let bootstrap = 9 + 2 * zero.len();
+    let size = u16::try_from(runtime.len());
+    let offset = u16::try_from(constructor.len() + bootstrap);
+    let (size, offset) = size.ok().zip(offset.ok()).ok_or(Error {
+        msg: format!(
+            "runtime code of {} bytes is too large to deploy",
+            runtime.len()
+        ),
+    })?;
+
+    let mut code = constructor.to_vec();
+    code.push(0x61);
+    code.extend(size.to_be_bytes());
+    code.push(0x80);
+    code.push(0x61);
+    code.extend(offset.to_be_bytes());
+    code.extend(zero);
+    code.push(0x39);
+    code.extend(zero);
+    code.push(0xf3);
+    code.extend(runtime);
+    Ok(code)
+}
@@ -0,0 +1,113 @@
+//! A description of generated definitions independent of the language they are rendered in, from
+//! which the Huff macros are lowered and document their conditions, and the Yul functions are
+//! rendered.
+
+use crate::emit;
+
+/// An expression over the arguments of a definition.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Expr {
+    /// An argument, by name.
+    Arg(&'static str),
+    /// A literal, in hex.
+    Lit(String),
+    /// `a & b`.
+    And(Box<Expr>, Box<Expr>),
+    /// `a <= b`, one if it holds, zero otherwise.
+    Le(Box<Expr>, Box<Expr>),
+}
+
+impl Expr {
+    pub fn lit(lit: impl Into<String>) -> Self {
+        Expr::Lit(lit.into())
+    }
+
+    pub fn and(self, other: Expr) -> Self {
+        Expr::And(Box::new(self), Box::new(other))
+    }
+
+    pub fn le(self, other: Expr) -> Self {
+        Expr::Le(Box::new(self), Box::new(other))
+    }
+
+    /// The expression as the `@pre` and `@post` conditions write it, with the arguments `named`,
+    /// comparisons in parentheses when `nested` in another expression.
+    pub fn infix(&self, nested: bool, named: &dyn Fn(&str) -> String) -> String {
+        let infix = |expr: &Expr| expr.infix(true, named);
+        match self {
+            Expr::Arg(name) => named(name),
+            Expr::Lit(lit) => lit.clone(),
+            Expr::And(a, b) => format!("{} & {}", infix(a), infix(b)),
+            Expr::Le(a, b) if nested => format!("({} <= {})", infix(a), infix(b)),
+            Expr::Le(a, b) => format!("{} <= {}", infix(a), infix(b)),
+        }
+    }
+}
+
+/// A definition taking its arguments from and leaving its results on the stack, or taking and
+/// returning them as a function.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Definition {
+    /// The name of the Huff macro, `TO_U8`.
+    pub name: String,
+    /// The arguments, top of the stack first.
+    pub args: Vec<&'static str>,
+    /// The conditions the arguments must meet, reverting if any does not.
+    pub checks: Vec<Expr>,
+    /// The results by name, top of the stack first.
+    pub results: Vec<(&'static str, Expr)>,
+}
+
+impl Definition {
+    /// The `@pre` and `@post` doc comment lines of the macro of the definition, leaving out the
+    /// results that are arguments of the same name.
+    pub fn conditions(&self) -> String {
+        self.conditions_named(&|name| name.to_string())
+    }
+
+    /// The `@pre` and `@post` doc comment lines of the definition, with its arguments and results
+    /// `named` as the target names them.
+    pub fn conditions_named(&self, named: &dyn Fn(&str) -> String) -> String {
+        let checks = self.checks.iter();
+        let pre = checks
+            .map(|check| check.infix(false, named))
+            .collect::<Vec<String>>();
+        let post = self
+            .results
+            .iter()
+            .filter(|(name, result)| *result != Expr::Arg(name))
+            .map(|(name, result)| format!("{} == {}", named(name), result.infix(true, named)))
+            .collect::<Vec<String>>();
+
+        let pre = pre.iter().map(String::as_str).collect::<Vec<&str>>();
+        let post = post.iter().map(String::as_str).collect::<Vec<&str>>();
+        emit::conditions(&pre, &post)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn conditions() {
+        let mask = || Expr::lit("0xff");
+        let cast = Definition {
+            name: "TRY_TO_U8".to_string(),
+            args: vec!["value"],
+            checks: vec![Expr::Arg("value").le(mask())],
+            results: vec![
+                ("result", Expr::Arg("value").le(mask())),
+                ("masked_value", Expr::Arg("value").and(mask())),
+                ("value", Expr::Arg("value")),
+            ],
+        };
+
+        assert_eq!(
+            cast.conditions(),
+            "/// @pre value <= 0xff\n\
+             /// @post result == (value <= 0xff)\n\
+             /// @post masked_value == value & 0xff\n",
+        );
+    }
+}
@@ -0,0 +1,304 @@
+//! Layouts of the generated files.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+/// How the generated libraries are laid out in files.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Layout {
+    /// A file per library, `libcast.huff`.
+    #[default]
+    Library,
+    /// A directory per sized library with a file per type, `cast/u8.huff`, and its items shared by
+    /// the types, `cast/common.huff`, included by an index in place of the library, `libcast.huff`.
+    Type,
+    /// A file per library and a bundle including every library, `libhuff.huff`.
+    Bundle,
+}
+
+impl Layout {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "library" => Some(Layout::Library),
+            "type" => Some(Layout::Type),
+            "bundle" => Some(Layout::Bundle),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Layout::Library => "library",
+            Layout::Type => "type",
+            Layout::Bundle => "bundle",
+        }
+    }
+}
+
+/// The file name of the bundle of the `bundle` layout.
+pub const BUNDLE: &str = "libhuff.huff";
+
+/// A definition of a library, with the comments and blank lines preceding it.
+struct Item<'a> {
+    text: String,
+    /// The path of an `#include`.
+    include: Option<&'a str>,
+    /// The type the definition belongs to, by its file stem, e.g. `u8` for `TO_U8`.
+    ty: Option<String>,
+}
+
+/// Renders the bundle of the library `files`, including each of them.
+pub fn bundle<'a>(files: impl IntoIterator<Item = &'a String>) -> String {
+    let includes = files
+        .into_iter()
+        .map(|name| format!("#include \"{}\"\n", name))
+        .collect::<String>();
+
+    format!(
+        "{}\n{}",
+        header("LibHuff", "Includes every generated library."),
+        includes
+    )
+}
+
+/// Splits each of the `sized` libraries of `files` by type, leaving the other libraries as they
+/// are.
+pub fn by_type(files: BTreeMap<String, String>, sized: &[String]) -> BTreeMap<String, String> {
+    let split = files
+        .iter()
+        .filter(|(name, _)| sized.contains(name))
+        .map(|(name, contents)| (name.as_str(), types(contents)))
+        .collect::<BTreeMap<&str, BTreeSet<String>>>();
+
+    let mut out = BTreeMap::new();
+    for (name, contents) in &files {
+        if !split.contains_key(name.as_str()) {
+            out.insert(name.clone(), contents.clone());
+            continue;
+        }
+        let dir = directory(name);
+        let (library_header, items) = parse(contents);
+        let title = library_header
+            .lines()
+            .find_map(|line| line.strip_prefix("//! # "))
+            .unwrap_or(name);
+
+        // includes of a split library are of its shared items, or of the same type if it has it
+        let include = |path: &str, ty: Option<&str>| match split.get(path) {
+            Some(types) => match ty.filter(|ty| types.contains(*ty)) {
+                Some(ty) => format!("#include \"../{}/{}.huff\"\n", directory(path), ty),
+                None => format!("#include \"../{}/common.huff\"\n", directory(path)),
+            },
+            None => format!("#include \"../{}\"\n", path),
+        };
+        let includes = items
+            .iter()
+            .filter_map(|item| item.include)
+            .collect::<Vec<&str>>();
+
+        let shared = items
+            .iter()
+            .filter(|item| item.include.is_none() && item.ty.is_none());
+        let common = file(
+            &header(
+                &format!("{}, Shared Items", title),
+                &format!("The items of `{}` shared by its types.", name),
+            ),
+            &includes
+                .iter()
+                .map(|path| include(path, None))
+                .collect::<String>(),
+            shared.map(|item| item.text.as_str()),
+        );
+        out.insert(format!("{}/common.huff", dir), common);
+
+        let mut index = format!("#include \"{}/common.huff\"\n", dir);
+        for ty in types_in_order(&items) {
+            let includes = includes
+                .iter()
+                .filter(|path| split.contains_key(**path))
+                .map(|path| include(path, Some(&ty)))
+                .collect::<String>();
+            let contents = file(
+                &header(
+                    &format!("{}, {}", title, ty.to_uppercase()),
+                    &format!("The `{}` items of `{}`.", ty.to_uppercase(), name),
+                ),
+                &format!("#include \"common.huff\"\n{}", includes),
+                items
+                    .iter()
+                    .filter(|item| item.ty.as_ref() == Some(&ty))
+                    .map(|item| &*item.text),
+            );
+            out.insert(format!("{}/{}.huff", dir, ty), contents);
+            index.push_str(&format!("#include \"{}/{}.huff\"\n", dir, ty));
+        }
+
+        out.insert(name.clone(), format!("{}{}", library_header, index));
+    }
+
+    out
+}
+
+/// The directory of the types of the library `file_name`, e.g. `cast` for `libcast.huff`.
+fn directory(file_name: &str) -> &str {
+    let stem = file_name.strip_suffix(".huff").unwrap_or(file_name);
+    stem.strip_prefix("lib").unwrap_or(stem)
+}
+
+fn header(title: &str, description: &str) -> String {
+    format!(
+        "\n//  {}\n//! # {}\n//!\n//! {}\n",
+        "-".repeat(96),
+        title,
+        description
+    )
+}
+
+/// Renders a file of `items` after `header` and `includes`, one blank line apart.
+fn file<'a>(header: &str, includes: &str, items: impl Iterator<Item = &'a str>) -> String {
+    let mut out = format!("{}\n{}", header, includes);
+    if !includes.is_empty() {
+        out.push('\n');
+    }
+    for (i, item) in items.enumerate() {
+        out.push_str(if i == 0 {
+            item.trim_start_matches('\n')
+        } else {
+            item
+        });
+    }
+    out
+}
+
+fn types(contents: &str) -> BTreeSet<String> {
+    parse(contents)
+        .1
+        .into_iter()
+        .filter_map(|item| item.ty)
+        .collect()
+}
+
+/// The types of `items`, in the order they are first defined.
+fn types_in_order(items: &[Item]) -> Vec<String> {
+    let mut types = Vec::new();
+    for ty in items.iter().filter_map(|item| item.ty.as_ref()) {
+        if !types.contains(ty) {
+            types.push(ty.clone());
+        }
+    }
+    types
+}
+
+/// Splits a rendered library into its header, the comments and blank lines up to its first item,
+/// and its items. Blank lines after the last item are dropped.
+fn parse(contents: &str) -> (&str, Vec<Item<'_>>) {
+    let start = contents
+        .split_inclusive('\n')
+        .take_while(|line| !line.starts_with("///") && !line.starts_with('#'))
+        .map(str::len)
+        .sum::<usize>();
+    let (header, rest) = contents.split_at(start);
+
+    let mut items = Vec::new();
+    let mut text = String::new();
+    let mut definition: Option<&str> = None;
+    for line in rest.split_inclusive('\n') {
+        text.push_str(line);
+
+        let line = line.trim_end();
+        let done = match definition {
+            // the closing brace of a multiline definition
+            Some(_) => line == "}",
+            None if line.starts_with("#include ") => {
+                let path = line.split('"').nth(1);
+                items.push(Item {
+                    text: std::mem::take(&mut text),
+                    include: path,
+                    ty: None,
+                });
+                continue;
+            }
+            None if line.starts_with("#define ") => {
+                definition = Some(line);
+                !line.ends_with('{')
+            }
+            None => false,
+        };
+
+        if let (true, Some(line)) = (done, definition) {
+            items.push(Item {
+                text: std::mem::take(&mut text),
+                include: None,
+                ty: ty(line),
+            });
+            definition = None;
+        }
+    }
+
+    (header, items)
+}
+
+/// The type of the definition `line`, the first part of its name naming a type, such as the `U8`
+/// of `SAFE_ADD_U8`, the `I8` of `TO_I8` or the `BYTES4` of `TO_BYTES4`.
+fn ty(line: &str) -> Option<String> {
+    let name = line.split_whitespace().nth(2)?;
+    let name = name
+        .split(|c: char| !c.is_alphanumeric() && c != '_')
+        .next()?;
+
+    name.split('_')
+        .find(|part| {
+            ["U", "I", "BYTES"].iter().any(|prefix| {
+                part.strip_prefix(prefix)
+                    .is_some_and(|n| !n.is_empty() && n.bytes().all(|b| b.is_ascii_digit()))
+            })
+        })
+        .map(str::to_lowercase)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn definitions_by_type() {
+        let library = concat!(
+            "\n",
+            "//! # Library\n",
+            "\n",
+            "#include \"libcast.huff\"\n",
+            "\n",
+            "/// ## Error\n",
+            "#define error Overflow()\n",
+            "\n",
+            "#define macro MINI_U8_MASK() = takes (0) returns (1) { 0xff }\n",
+            "/// ## Add\n",
+            "#define macro SAFE_ADD_U8() = takes (2) returns (1) {\n",
+            "    add\n",
+            "}\n",
+            "\n",
+        );
+        let (header, items) = parse(library);
+
+        assert_eq!(header, "\n//! # Library\n\n");
+        assert_eq!(items.len(), 4);
+        assert_eq!(items[0].include, Some("libcast.huff"));
+        assert_eq!(items[1].text, "\n/// ## Error\n#define error Overflow()\n");
+        assert_eq!(items[2].ty.as_deref(), Some("u8"));
+        assert!(items[3]
+            .text
+            .starts_with("/// ## Add\n#define macro SAFE_ADD_U8()"));
+        assert!(items[3].text.ends_with("    add\n}\n"));
+
+        let ty = |name: &str| {
+            ty(&format!(
+                "#define macro {} = takes (1) returns (1) {{",
+                name
+            ))
+        };
+        assert_eq!(ty("TO_BYTES32()"), Some("bytes32".into()));
+        assert_eq!(ty("UNSAFE_TO_I256()"), Some("i256".into()));
+        assert_eq!(ty("__MINI_MASK(bitsize)"), None);
+        assert_eq!(ty("IS_UNSAFE()"), None);
+    }
+}
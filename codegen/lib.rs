@@ -0,0 +1,108 @@
+//! Generators of the libhuff Huff libraries.
+//!
+//! The `huffgen` binary wraps this crate, which can also be called from a build script to generate
+//! a selection of the libraries.
+//!
+//! ```
+//! use huffgen::{Generator, Library};
+//!
+//! let files = Generator::new()
+//!     .with_library(Library::Cast)
+//!     .with_sizes(&[8, 256])
+//!     .render()
+//!     .unwrap();
+//!
+//! assert!(files["libcast.huff"].contains("TO_U8"));
+//! ```
+
+pub mod abi;
+pub mod bench;
+pub mod config;
+pub mod diagnostics;
+pub mod diff;
+pub mod difftest;
+pub mod docs;
+mod emit;
+pub mod evm;
+pub mod huff;
+mod ir;
+mod json;
+mod keccak;
+mod layout;
+mod libabi;
+mod libaccount;
+mod libauth;
+mod libbits;
+mod libblob;
+mod libbloom;
+mod libbytes;
+mod libbytescast;
+mod libcall;
+mod libcalldata;
+mod libcast;
+mod libchain;
+mod libchecksum;
+mod libcmp;
+mod libcollections;
+mod libconstants;
+mod libcounter;
+mod libcreate;
+mod libcursor;
+mod libdecay;
+mod libdecimal;
+mod libdecode;
+mod libdiamond;
+mod libdispatch;
+mod libescrow;
+mod libfixedpoint;
+mod libflags;
+mod libforwarder;
+mod libguard;
+mod libkeccak;
+mod libmapping;
+mod libmerkle;
+mod libmultitoken;
+mod libnamespace;
+mod libparse;
+mod libpayment;
+mod librandom;
+pub mod library;
+mod libratelimit;
+mod libreturn;
+mod librevert;
+mod librlp;
+mod libroyalty;
+mod libsafemath;
+mod libsafetransfer;
+mod libsignature;
+mod libsigned;
+mod libslots;
+mod libstruct;
+mod libtime;
+mod libtimelock;
+mod libtry;
+mod libtype;
+mod libunits;
+mod libwrapping;
+pub mod listing;
+mod lower;
+pub mod manifest;
+pub mod naming;
+mod optimize;
+pub mod pack;
+pub mod profile;
+pub mod repl;
+pub mod report;
+pub mod slots;
+mod templates;
+pub mod testgen;
+pub mod u256;
+mod validate;
+mod yul;
+
+pub use config::EvmVersion;
+pub use layout::Layout;
+pub use libcast::OverflowRevert;
+pub use library::{Generator, Library, Options};
+pub use naming::Naming;
+pub use yul::Target;
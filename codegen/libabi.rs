@@ -0,0 +1,225 @@
+use crate::emit::line;
+use crate::templates::{self, Context};
+
+/// The most static words a return or event of the library encodes, and the most topics of a log.
+const MAX_WORDS: usize = 4;
+
+pub fn render() -> String {
+    let returns = (1..=MAX_WORDS).map(return_context).collect();
+    let events = (0..=MAX_WORDS)
+        .flat_map(|topics| (0..=MAX_WORDS).map(move |words| event_context(topics, words)))
+        .collect();
+    let context = Context::new()
+        .list("returns", returns)
+        .list("events", events);
+
+    templates::render(LIBRARY_TEMPLATE, &context)
+}
+
+fn return_context(words: usize) -> Context {
+    let name = match words {
+        1 => "RETURN_U256".to_string(),
+        _ => format!("RETURN_{}_WORDS", words),
+    };
+    let values = names("value", words);
+    let values = values.iter().map(String::as_str).collect::<Vec<&str>>();
+
+    let mut body = line("// takes:", &stack(&[], &values));
+    for i in 0..words {
+        body.push_str(&line(
+            &format!("{:#04x}", i * 32),
+            &stack(&["ptr"], &values[i..]),
+        ));
+        body.push_str(&line("mstore", &stack(&[], &values[i + 1..])));
+    }
+    body.push_str(&line(&format!("{:#04x}", words * 32), "[len]"));
+    body.push_str(&line("0x00", "[ptr, len]"));
+    body.push_str(&line("return", "[]"));
+
+    Context::new()
+        .text("name", name)
+        .text("words", words.to_string())
+        .text("title", count(words, "Word"))
+        .text("count", count(words, "static word"))
+        .text("len", format!("{:#04x}", words * 32))
+        .text(
+            "arguments",
+            arguments(&values, "word of the return data", 0),
+        )
+        .text("pushes", pushes(words))
+        .text("body", body)
+}
+
+fn event_context(topics: usize, words: usize) -> Context {
+    let (topic_names, word_names) = (names("topic", topics), names("word", words));
+    let inputs = word_names
+        .iter()
+        .chain(&topic_names)
+        .map(String::as_str)
+        .collect::<Vec<&str>>();
+    let (data, topics_stack) = inputs.split_at(words);
+
+    let mut body = String::new();
+    if !inputs.is_empty() {
+        body.push_str(&line("// takes:", &stack(&[], &inputs)));
+    }
+    if words == 0 {
+        body.push_str(&line("0x00", &stack(&["len"], topics_stack)));
+        body.push_str(&line("0x00", &stack(&["ptr", "len"], topics_stack)));
+    } else {
+        body.push_str(&line("FREE_MEMORY_PTR()", &stack(&["ptr"], &inputs)));
+        for (i, word) in data.iter().enumerate() {
+            let rest = &inputs[i + 1..];
+            body.push_str(&line("swap1", &stack(&[word, "ptr"], rest)));
+            body.push_str(&line("dup2", &stack(&["ptr", word, "ptr"], rest)));
+            if i > 0 {
+                let offset = format!("{:#04x}", i * 32);
+                body.push_str(&line(
+                    &offset,
+                    &stack(&["offset", "ptr", word, "ptr"], rest),
+                ));
+                body.push_str(&line("add", &stack(&["word_ptr", word, "ptr"], rest)));
+            }
+            body.push_str(&line("mstore", &stack(&["ptr"], rest)));
+        }
+        let len = format!("{:#04x}", words * 32);
+        body.push_str(&line(&len, &stack(&["len", "ptr"], topics_stack)));
+        body.push_str(&line("swap1", &stack(&["ptr", "len"], topics_stack)));
+    }
+    body.push_str(&line(&format!("log{}", topics), "[]"));
+
+    let arguments = [
+        arguments(data, "word of the event data", 0),
+        arguments(topics_stack, "topic of the event", words),
+    ]
+    .concat();
+
+    Context::new()
+        .text("name", format!("EMIT_LOG{}_{}", topics, words))
+        .text(
+            "title",
+            format!("Log{} Of {}", topics, count(words, "Word")),
+        )
+        .text("topics", count(topics, "topic"))
+        .text("words", count(words, "static data word"))
+        .text("len", format!("{:#04x}", words * 32))
+        .text("inputs", inputs.len().to_string())
+        .text("arguments", arguments)
+        .text("pushes", pushes(inputs.len()))
+        .text("body", body)
+        .flag("memory", words > 0)
+        .flag("takes", !inputs.is_empty())
+}
+
+/// A count of `noun`s, such as `1 topic` or `2 topics`.
+fn count(n: usize, noun: &str) -> String {
+    format!("{} {}{}", n, noun, if n == 1 { "" } else { "s" })
+}
+
+/// The names of `count` stack arguments, such as `word_0` to `word_3`.
+fn names(name: &str, count: usize) -> Vec<String> {
+    (0..count).map(|i| format!("{}_{}", name, i)).collect()
+}
+
+/// A stack comment of the `top` items above the `rest`.
+fn stack(top: &[&str], rest: &[&str]) -> String {
+    format!("[{}]", [top, rest].concat().join(", "))
+}
+
+/// The doc comment lines documenting the stack arguments `names`, the first at `stack[depth]`.
+fn arguments(names: &[&str], description: &str, depth: usize) -> String {
+    names
+        .iter()
+        .enumerate()
+        .map(|(i, name)| {
+            format!(
+                "/// - `{}`: (`stack[{}]`) {}.\n",
+                name,
+                depth + i,
+                description
+            )
+        })
+        .collect()
+}
+
+/// The usage pushes of `count` stack arguments, loaded from calldata.
+fn pushes(count: usize) -> String {
+    (0..count)
+        .rev()
+        .map(|i| format!("///     {:#04x} calldataload\n", 4 + i * 32))
+        .collect()
+}
+
+const LIBRARY_TEMPLATE: &str = r#"
+//  ------------------------------------------------------------------------------------------------
+//! # ABI Library
+//!
+//! Provides ABI encoded returns of static values and events of up to four topics and four static
+//! data words.
+//!
+//! Returns encode their words from the start of memory, overwriting the scratch space, the free
+//! memory pointer and the zero slot, as nothing runs after them. Events encode their data at the
+//! free memory pointer, `0x80` if it is unset, without allocating it.
+//!
+//! ## API
+//!
+//! - `FREE_MEMORY_PTR` - Pushes the free memory pointer.
+//! - `RETURN_U256` - Returns a word.
+//! - `RETURN_N_WORDS` - Returns `N` words, for `N` from 2 to 4.
+//! - `EMIT_LOGT_D` - Emits an event of `T` topics, from 0 to 4, and `D` data words, from 0 to 4.
+
+/// ## Free Memory Pointer
+///
+/// Pushes the free memory pointer, stored at `0x40`, or `0x80`, the start of free memory in
+/// Solidity, if it is unset.
+#define macro FREE_MEMORY_PTR() = takes (0) returns (1) {
+    0x40                    // [0x40]
+    mload                   // [free_ptr]
+    dup1                    // [free_ptr, free_ptr]
+    iszero                  // [is_unset, free_ptr]
+    0x07                    // [0x07, is_unset, free_ptr]
+    shl                     // [default_ptr, free_ptr]
+    or                      // [ptr]
+}
+{{#each returns}}
+/// ## Return {{title}}
+///
+/// Returns {{count}}, ABI encoded in order from `stack[0]`.
+///
+/// > WARNING: Memory from `0x00` to `{{len}}` will be overwritten.
+///
+/// ### Stack Arguments
+///
+{{arguments}}///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+{{pushes}}///     {{name}}()
+/// }
+/// ```
+#define macro {{name}}() = takes ({{words}}) returns (0) {
+{{body}}}
+{{/each}}{{#each events}}
+/// ## Emit {{title}}
+///
+/// Emits an event of {{topics}} and {{words}}.
+{{#if memory}}///
+/// The data is ABI encoded at the free memory pointer, in order from `stack[0]`.
+///
+/// > WARNING: Memory from the free memory pointer to `{{len}}` bytes past it will be overwritten.
+{{/if}}{{#if takes}}///
+/// ### Stack Arguments
+///
+{{arguments}}{{/if}}///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+{{pushes}}///     {{name}}()
+///     stop
+/// }
+/// ```
+#define macro {{name}}() = takes ({{inputs}}) returns (0) {
+{{body}}}
+{{/each}}"#;
@@ -0,0 +1,549 @@
+use crate::keccak::keccak256;
+use crate::templates::{self, Context};
+
+/// Signature of `IAccount.validateUserOp` for the v0.7 `PackedUserOperation`.
+const VALIDATE_USER_OP: &str =
+    "validateUserOp((address,uint256,bytes,bytes,bytes32,uint256,bytes32,bytes,bytes),bytes32,uint256)";
+
+/// Signature of `INonceManager.getNonce`.
+const GET_NONCE: &str = "getNonce(address,uint192)";
+
+pub fn render() -> String {
+    let context = Context::new()
+        .text("validate_selector", selector(VALIDATE_USER_OP))
+        .text("get_nonce_selector", selector(GET_NONCE));
+    let constants = templates::render(CONSTANT_DEFINITION, &context);
+
+    format!(
+        "{}{}{}{}{}",
+        HEADER, constants, USER_OP_DEFINITION, NONCE_DEFINITION, VALIDATION_DEFINITION,
+    )
+}
+
+fn selector(signature: &str) -> String {
+    let hash = keccak256(signature.as_bytes());
+    format!(
+        "0x{:02x}{:02x}{:02x}{:02x}",
+        hash[0], hash[1], hash[2], hash[3]
+    )
+}
+
+const HEADER: &str = r#"
+//  ------------------------------------------------------------------------------------------------
+//! # Account Library
+//!
+//! Provides ERC-4337 account validation building blocks for EntryPoint v0.7.
+//!
+//! User operations are read from calldata as the ABI encoded `PackedUserOperation` tuple, referred
+//! to by the calldata offset of its head. In `validateUserOp`, the head is at
+//! `0x04 + calldataload(0x04)`.
+//!
+//! Signatures are checked against the EIP-191 signed message of the user operation hash, with
+//! `ECRECOVER` for EOA signers and `isValidSignature` for contract signers.
+//!
+//! ## API
+//!
+//! - `REQUIRE_ENTRY_POINT` - Reverts if the caller is not the entry point.
+//! - `USER_OP_HASH` - Reconstructs the hash of a user operation.
+//! - `USER_OP_NONCE` - Loads the nonce of a user operation.
+//! - `USER_OP_SIGNATURE` - Loads the calldata range of the signature of a user operation.
+//! - `NONCE_KEY` - Extracts the key of a nonce.
+//! - `NONCE_SEQUENCE` - Extracts the sequence of a nonce.
+//! - `ENTRY_POINT_NONCE` - Queries the entry point for the next nonce of a key.
+//! - `VALIDATE_USER_OP_SIGNATURE` - Checks the signature of a user operation.
+//! - `PAY_PREFUND` - Pays the entry point the funds missing for a user operation.
+//! - `VALIDATE_USER_OP` - Validates the user operation of a `validateUserOp` call.
+
+#include "libkeccak.huff"
+#include "libsignature.huff"
+"#;
+
+const CONSTANT_DEFINITION: &str = r#"
+/// ## Entry Point V07
+///
+/// Canonical address of the v0.7 entry point.
+#define constant ENTRY_POINT_V07 = 0x0000000071727de22e5e9d8baf0edac6f37da032
+
+/// ## Validate User Op Selector
+///
+/// Selector of `validateUserOp(PackedUserOperation,bytes32,uint256)`.
+#define constant VALIDATE_USER_OP_SELECTOR = {{validate_selector}}
+
+/// ## Get Nonce Selector
+///
+/// Selector of `getNonce(address,uint192)` on the entry point.
+#define constant GET_NONCE_SELECTOR = {{get_nonce_selector}}
+
+/// ## Signature Validation Failed
+///
+/// Validation data returned for an invalid signature.
+#define constant SIG_VALIDATION_FAILED = 0x01
+
+/// ## Not Entry Point Error
+///
+/// Thrown when an entry point only macro is called by another account.
+#define error NotEntryPoint()
+"#;
+
+const USER_OP_DEFINITION: &str = r#"
+/// ## Require Entry Point
+///
+/// Reverts if the caller is not the entry point.
+///
+/// ### Template Arguments
+///
+/// - `entry_point`: address of the entry point.
+///
+/// ### Panics
+///
+/// - if the caller is not `entry_point`
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     REQUIRE_ENTRY_POINT([ENTRY_POINT_V07])
+///     stop
+/// }
+/// ```
+#define macro REQUIRE_ENTRY_POINT(entry_point) = takes (0) returns (0) {
+    <entry_point>           // [entry_point]
+    caller                  // [caller, entry_point]
+    eq                      // [is_entry_point]
+    is_entry_point          // [is_entry_point_dest, is_entry_point]
+    jumpi                   // []
+        __ERROR(NotEntryPoint) // [err]
+        0x00                // [ptr, err]
+        mstore              // []
+        0x04                // [err_len]
+        0x00                // [ptr, err_len]
+        revert              // []
+    is_entry_point:         // []
+}
+
+/// ## User Op Hash
+///
+/// Reconstructs the hash of a user operation as computed by the v0.7 entry point,
+/// `keccak256(abi.encode(keccak256(packed), entry_point, chainid))`, where `packed` encodes the
+/// fields of the user operation with its dynamic fields hashed.
+///
+/// > WARNING: Memory from `ptr` to `ptr + 0x100` and the length of the longest dynamic field will
+/// > be overwritten.
+///
+/// ### Template Arguments
+///
+/// - `entry_point`: address of the entry point.
+/// - `ptr`: pointer to free memory.
+///
+/// ### Stack Arguments
+///
+/// - `user_op`: (`stack[0]`) calldata offset of the user operation.
+///
+/// ### Panics
+///
+/// - if a dynamic field exceeds calldata.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x04 calldataload 0x04 add
+///     USER_OP_HASH([ENTRY_POINT_V07], 0x00)
+///     0x00 mstore
+///     0x20 0x00 return
+/// }
+/// ```
+#define macro USER_OP_HASH(entry_point, ptr) = takes (1) returns (1) {
+    // takes:               // [user_op]
+    __USER_OP_BYTES(0xe0)   // [paymaster_data_offset, paymaster_data_len, user_op]
+    HASH_CALLDATA_RANGE(<ptr>) // [paymaster_data_hash, user_op]
+    swap1                   // [user_op, paymaster_data_hash]
+    __USER_OP_BYTES(0x60)   // [call_data_offset, call_data_len, user_op, paymaster_data_hash]
+    HASH_CALLDATA_RANGE(<ptr>) // [call_data_hash, user_op, paymaster_data_hash]
+    swap1                   // [user_op, call_data_hash, paymaster_data_hash]
+    __USER_OP_BYTES(0x40)   // [init_code_offset, init_code_len, user_op, call_data_hash, paymaster_data_hash]
+    HASH_CALLDATA_RANGE(<ptr>) // [init_code_hash, user_op, call_data_hash, paymaster_data_hash]
+    <ptr>                   // [ptr, init_code_hash, user_op, call_data_hash, paymaster_data_hash]
+    0x40                    // [0x40, ptr, init_code_hash, user_op, call_data_hash, paymaster_data_hash]
+    add                     // [init_code_ptr, init_code_hash, user_op, call_data_hash, paymaster_data_hash]
+    mstore                  // [user_op, call_data_hash, paymaster_data_hash]
+    swap1                   // [call_data_hash, user_op, paymaster_data_hash]
+    <ptr>                   // [ptr, call_data_hash, user_op, paymaster_data_hash]
+    0x60                    // [0x60, ptr, call_data_hash, user_op, paymaster_data_hash]
+    add                     // [call_data_ptr, call_data_hash, user_op, paymaster_data_hash]
+    mstore                  // [user_op, paymaster_data_hash]
+    swap1                   // [paymaster_data_hash, user_op]
+    <ptr>                   // [ptr, paymaster_data_hash, user_op]
+    0xe0                    // [0xe0, ptr, paymaster_data_hash, user_op]
+    add                     // [paymaster_data_ptr, paymaster_data_hash, user_op]
+    mstore                  // [user_op]
+    __USER_OP_WORD(0x00, <ptr>) // [user_op]
+    __USER_OP_WORD(0x20, <ptr>) // [user_op]
+    __USER_OP_WORD(0x80, <ptr>) // [user_op]
+    __USER_OP_WORD(0xa0, <ptr>) // [user_op]
+    __USER_OP_WORD(0xc0, <ptr>) // [user_op]
+    pop                     // []
+    0x100                   // [packed_len]
+    <ptr>                   // [ptr, packed_len]
+    sha3                    // [packed_hash]
+    <ptr>                   // [ptr, packed_hash]
+    mstore                  // []
+    <entry_point>           // [entry_point]
+    <ptr>                   // [ptr, entry_point]
+    0x20                    // [0x20, ptr, entry_point]
+    add                     // [entry_point_ptr, entry_point]
+    mstore                  // []
+    chainid                 // [chainid]
+    <ptr>                   // [ptr, chainid]
+    0x40                    // [0x40, ptr, chainid]
+    add                     // [chainid_ptr, chainid]
+    mstore                  // []
+    0x60                    // [len]
+    <ptr>                   // [ptr, len]
+    sha3                    // [user_op_hash]
+}
+
+/// ## User Op Nonce
+///
+/// Loads the nonce of a user operation.
+///
+/// ### Stack Arguments
+///
+/// - `user_op`: (`stack[0]`) calldata offset of the user operation.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x04 calldataload 0x04 add
+///     USER_OP_NONCE()
+///     0x00 mstore
+///     0x20 0x00 return
+/// }
+/// ```
+#define macro USER_OP_NONCE() = takes (1) returns (1) {
+    // takes:               // [user_op]
+    0x20                    // [0x20, user_op]
+    add                     // [nonce_offset]
+    calldataload            // [nonce]
+}
+
+/// ## User Op Signature
+///
+/// Loads the calldata range of the signature of a user operation.
+///
+/// ### Stack Arguments
+///
+/// - `user_op`: (`stack[0]`) calldata offset of the user operation.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x04 calldataload 0x04 add
+///     USER_OP_SIGNATURE()
+///     0x00 calldatacopy
+///     stop
+/// }
+/// ```
+#define macro USER_OP_SIGNATURE() = takes (1) returns (2) {
+    // takes:               // [user_op]
+    __USER_OP_BYTES(0x100)  // [sig_offset, sig_len, user_op]
+    swap2                   // [user_op, sig_len, sig_offset]
+    pop                     // [sig_len, sig_offset]
+    swap1                   // [sig_offset, sig_len]
+}
+
+/// ## User Op Bytes
+///
+/// Loads the calldata range of the dynamic field at `field` of a user operation.
+#define macro __USER_OP_BYTES(field) = takes (1) returns (3) {
+    // takes:               // [user_op]
+    dup1                    // [user_op, user_op]
+    <field>                 // [field, user_op, user_op]
+    add                     // [field_offset, user_op]
+    calldataload            // [relative_offset, user_op]
+    dup2                    // [user_op, relative_offset, user_op]
+    add                     // [len_offset, user_op]
+    dup1                    // [len_offset, len_offset, user_op]
+    calldataload            // [len, len_offset, user_op]
+    swap1                   // [len_offset, len, user_op]
+    0x20                    // [0x20, len_offset, len, user_op]
+    add                     // [offset, len, user_op]
+}
+
+/// ## User Op Word
+///
+/// Copies the static field at `field` of a user operation to `ptr + field`.
+#define macro __USER_OP_WORD(field, ptr) = takes (1) returns (1) {
+    // takes:               // [user_op]
+    dup1                    // [user_op, user_op]
+    <field>                 // [field, user_op, user_op]
+    add                     // [field_offset, user_op]
+    calldataload            // [value, user_op]
+    <ptr>                   // [ptr, value, user_op]
+    <field>                 // [field, ptr, value, user_op]
+    add                     // [value_ptr, value, user_op]
+    mstore                  // [user_op]
+}
+"#;
+
+const NONCE_DEFINITION: &str = r#"
+/// ## Nonce Key
+///
+/// Extracts the 192 bit key of a nonce.
+///
+/// ### Stack Arguments
+///
+/// - `nonce`: (`stack[0]`) nonce of a user operation.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x04 calldataload
+///     NONCE_KEY()
+///     0x00 mstore
+///     0x20 0x00 return
+/// }
+/// ```
+#define macro NONCE_KEY() = takes (1) returns (1) {
+    // takes:               // [nonce]
+    0x40                    // [shift, nonce]
+    shr                     // [key]
+}
+
+/// ## Nonce Sequence
+///
+/// Extracts the 64 bit sequence of a nonce.
+///
+/// ### Stack Arguments
+///
+/// - `nonce`: (`stack[0]`) nonce of a user operation.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x04 calldataload
+///     NONCE_SEQUENCE()
+///     0x00 mstore
+///     0x20 0x00 return
+/// }
+/// ```
+#define macro NONCE_SEQUENCE() = takes (1) returns (1) {
+    // takes:               // [nonce]
+    0xffffffffffffffff      // [mask, nonce]
+    and                     // [sequence]
+}
+
+/// ## Entry Point Nonce
+///
+/// Queries the entry point for the next nonce of this account for a key.
+///
+/// > WARNING: Memory from `0x00` to `0x44` will be overwritten.
+///
+/// ### Template Arguments
+///
+/// - `entry_point`: address of the entry point.
+///
+/// ### Stack Arguments
+///
+/// - `key`: (`stack[0]`) 192 bit nonce key.
+///
+/// ### Panics
+///
+/// - if the entry point call fails, with its revert data.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x00
+///     ENTRY_POINT_NONCE([ENTRY_POINT_V07])
+///     0x00 mstore
+///     0x20 0x00 return
+/// }
+/// ```
+#define macro ENTRY_POINT_NONCE(entry_point) = takes (1) returns (1) {
+    // takes:               // [key]
+    [GET_NONCE_SELECTOR]    // [selector, key]
+    0xe0                    // [shift, selector, key]
+    shl                     // [selector, key]
+    0x00                    // [ptr, selector, key]
+    mstore                  // [key]
+    address                 // [address, key]
+    0x04                    // [ptr, address, key]
+    mstore                  // [key]
+    0x24                    // [ptr, key]
+    mstore                  // []
+    0x20                    // [ret_len]
+    0x00                    // [ret_ptr, ret_len]
+    0x44                    // [arg_len, ret_ptr, ret_len]
+    0x00                    // [arg_ptr, arg_len, ret_ptr, ret_len]
+    <entry_point>           // [entry_point, arg_ptr, arg_len, ret_ptr, ret_len]
+    gas                     // [gas, entry_point, arg_ptr, arg_len, ret_ptr, ret_len]
+    staticcall              // [success]
+    success                 // [success_dest, success]
+    jumpi                   // []
+        returndatasize      // [returndata_len]
+        0x00                // [returndata_offset, returndata_len]
+        0x00                // [ptr, returndata_offset, returndata_len]
+        returndatacopy      // []
+        returndatasize      // [returndata_len]
+        0x00                // [ptr, returndata_len]
+        revert              // []
+    success:                // []
+    0x00                    // [ptr]
+    mload                   // [nonce]
+}
+"#;
+
+const VALIDATION_DEFINITION: &str = r#"
+/// ## Validate User Op Signature
+///
+/// Checks the signature of a user operation against the EIP-191 signed message of its hash,
+/// returning zero if it is valid and `SIG_VALIDATION_FAILED` otherwise.
+///
+/// > WARNING: Memory from `sig_ptr` to `sig_ptr + sig_len` and from `free_mem_ptr` to
+/// > `free_mem_ptr + 0x80 + sig_len` will be overwritten.
+///
+/// ### Template Arguments
+///
+/// - `sig_ptr`: pointer to the memory to copy the signature to.
+/// - `free_mem_ptr`: pointer to free memory (requires at least `0x80 + sig_len` bytes of memory
+///   following it).
+///
+/// ### Stack Arguments
+///
+/// - `signer`: (`stack[0]`) account the signature is expected from.
+/// - `user_op_hash`: (`stack[1]`) hash of the user operation.
+/// - `user_op`: (`stack[2]`) calldata offset of the user operation.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define constant OWNER_SLOT = FREE_STORAGE_POINTER()
+///
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x04 calldataload 0x04 add
+///     0x24 calldataload
+///     [OWNER_SLOT] sload
+///     VALIDATE_USER_OP_SIGNATURE(0x80, 0x100)
+///     0x00 mstore
+///     0x20 0x00 return
+/// }
+/// ```
+#define macro VALIDATE_USER_OP_SIGNATURE(sig_ptr, free_mem_ptr) = takes (3) returns (1) {
+    // takes:               // [signer, user_op_hash, user_op]
+    swap2                   // [user_op, user_op_hash, signer]
+    USER_OP_SIGNATURE()     // [sig_offset, sig_len, user_op_hash, signer]
+    dup2                    // [sig_len, sig_offset, sig_len, user_op_hash, signer]
+    swap1                   // [sig_offset, sig_len, sig_len, user_op_hash, signer]
+    <sig_ptr>               // [sig_ptr, sig_offset, sig_len, sig_len, user_op_hash, signer]
+    calldatacopy            // [sig_len, user_op_hash, signer]
+    swap1                   // [user_op_hash, sig_len, signer]
+    ETH_SIGNED_MESSAGE_HASH(<free_mem_ptr>) // [hash, sig_len, signer]
+    <sig_ptr>               // [sig_ptr, hash, sig_len, signer]
+    swap1                   // [hash, sig_ptr, sig_len, signer]
+    dup4                    // [signer, hash, sig_ptr, sig_len, signer]
+    IS_VALID_SIGNATURE_CALL(<free_mem_ptr>) // [is_valid, signer]
+    swap1                   // [signer, is_valid]
+    pop                     // [is_valid]
+    iszero                  // [validation_data]
+}
+
+/// ## Pay Prefund
+///
+/// Pays the caller, the entry point, the funds missing for a user operation. The result of the
+/// transfer is ignored, as the entry point verifies its own balance.
+///
+/// ### Stack Arguments
+///
+/// - `missing_account_funds`: (`stack[0]`) amount owed to the entry point.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x44 calldataload
+///     PAY_PREFUND()
+///     stop
+/// }
+/// ```
+#define macro PAY_PREFUND() = takes (1) returns (0) {
+    // takes:               // [missing_account_funds]
+    dup1                    // [missing_account_funds, missing_account_funds]
+    iszero                  // [is_funded, missing_account_funds]
+    funded                  // [funded_dest, is_funded, missing_account_funds]
+    jumpi                   // [missing_account_funds]
+        0x00                // [ret_len, missing_account_funds]
+        0x00                // [ret_ptr, ret_len, missing_account_funds]
+        0x00                // [arg_len, ret_ptr, ret_len, missing_account_funds]
+        0x00                // [arg_ptr, arg_len, ret_ptr, ret_len, missing_account_funds]
+        dup5                // [value, arg_ptr, arg_len, ret_ptr, ret_len, missing_account_funds]
+        caller              // [caller, value, arg_ptr, arg_len, ret_ptr, ret_len, missing_account_funds]
+        gas                 // [gas, caller, value, arg_ptr, arg_len, ret_ptr, ret_len, missing_account_funds]
+        call                // [success, missing_account_funds]
+        pop                 // [missing_account_funds]
+    funded:                 // [missing_account_funds]
+    pop                     // []
+}
+
+/// ## Validate User Op
+///
+/// Validates the user operation of a `validateUserOp` call made by the entry point, checking its
+/// signature and paying the missing funds, and returns the validation data.
+///
+/// The user operation hash is taken from the call, as the entry point is trusted to compute it.
+/// Nonce uniqueness is enforced by the entry point; accounts restricting nonce keys can check
+/// `USER_OP_NONCE` before validating.
+///
+/// > WARNING: Memory from `sig_ptr` to `sig_ptr + sig_len` and from `free_mem_ptr` to
+/// > `free_mem_ptr + 0x80 + sig_len` will be overwritten.
+///
+/// ### Template Arguments
+///
+/// - `entry_point`: address of the entry point.
+/// - `sig_ptr`: pointer to the memory to copy the signature to.
+/// - `free_mem_ptr`: pointer to free memory (requires at least `0x80 + sig_len` bytes of memory
+///   following it).
+///
+/// ### Stack Arguments
+///
+/// - `signer`: (`stack[0]`) account the signature is expected from.
+///
+/// ### Panics
+///
+/// - if the caller is not `entry_point`
+///
+/// ### Usage
+///
+/// ```huff
+/// #define constant OWNER_SLOT = FREE_STORAGE_POINTER()
+///
+/// #define macro MAIN() = takes (0) returns (0) {
+///     [OWNER_SLOT] sload
+///     VALIDATE_USER_OP([ENTRY_POINT_V07], 0x80, 0x100)
+///     0x00 mstore
+///     0x20 0x00 return
+/// }
+/// ```
+#define macro VALIDATE_USER_OP(entry_point, sig_ptr, free_mem_ptr) = takes (1) returns (1) {
+    // takes:               // [signer]
+    REQUIRE_ENTRY_POINT(<entry_point>) // [signer]
+    0x04                    // [0x04, signer]
+    calldataload            // [relative_offset, signer]
+    0x04                    // [0x04, relative_offset, signer]
+    add                     // [user_op, signer]
+    0x24                    // [0x24, user_op, signer]
+    calldataload            // [user_op_hash, user_op, signer]
+    dup3                    // [signer, user_op_hash, user_op, signer]
+    VALIDATE_USER_OP_SIGNATURE(<sig_ptr>, <free_mem_ptr>) // [validation_data, signer]
+    swap1                   // [signer, validation_data]
+    pop                     // [validation_data]
+    0x44                    // [0x44, validation_data]
+    calldataload            // [missing_account_funds, validation_data]
+    PAY_PREFUND()           // [validation_data]
+}
+"#;
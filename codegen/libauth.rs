@@ -0,0 +1,368 @@
+pub fn render() -> String {
+    format!(
+        "{}{}{}{}{}",
+        HEADER,
+        INTERFACE_DEFINITION,
+        GETTER_DEFINITION,
+        AUTHORIZATION_DEFINITION,
+        SETTER_DEFINITION,
+    )
+}
+
+const HEADER: &str = r#"
+//  ------------------------------------------------------------------------------------------------
+//! # Auth Library
+//!
+//! Provides owner and authority based authorization, mirroring Solmate's `Auth`.
+//!
+//! A caller is authorized if it is the stored owner, or if the stored authority is nonzero and
+//! `authority.canCall(caller, address(this), msg.sig)` returns true. The owner is checked first,
+//! so the owner never pays for the external call. An authority that reverts or returns malformed
+//! data does not authorize the caller.
+//!
+//! Storage slots are passed as template arguments so the layout is chosen by the contract.
+//!
+//! ## API
+//!
+//! - `OWNER` - Pushes the stored owner.
+//! - `AUTHORITY` - Pushes the stored authority.
+//! - `IS_AUTHORIZED` - Checks if a user may call a function.
+//! - `AUTH_REQUIRES` - Reverts if the caller may not call the current function.
+//! - `AUTH_CONSTRUCTOR` - Initializes the owner and authority.
+//! - `TRANSFER_OWNERSHIP` - Updates the owner, requiring authorization.
+//! - `SET_AUTHORITY` - Updates the authority, requiring authorization.
+"#;
+
+const INTERFACE_DEFINITION: &str = r#"
+/// ## Authority Interface
+#define function canCall(address,address,bytes4) view returns (bool)
+
+/// ## Ownership Transferred Event
+///
+/// Emitted when the owner is updated.
+#define event OwnershipTransferred(address indexed, address indexed)
+
+/// ## Authority Updated Event
+///
+/// Emitted when the authority is updated.
+#define event AuthorityUpdated(address indexed, address indexed)
+
+/// ## Unauthorized Error
+///
+/// Thrown when the caller is neither the owner nor permitted by the authority.
+#define error Unauthorized()
+"#;
+
+const GETTER_DEFINITION: &str = r#"
+/// ## Owner
+///
+/// Pushes the stored owner.
+///
+/// ### Template Arguments
+///
+/// - `owner_slot`: storage slot of the owner.
+///
+/// ### Usage
+///
+/// ```huff
+/// #include "libauth.huff"
+///
+/// #define constant OWNER_SLOT = FREE_STORAGE_POINTER()
+///
+/// #define macro MAIN() = takes (0) returns (0) {
+///     OWNER([OWNER_SLOT])
+///     0x00 mstore
+///     0x20 0x00 return
+/// }
+/// ```
+#define macro OWNER(owner_slot) = takes (0) returns (1) {
+    <owner_slot>            // [owner_slot]
+    sload                   // [owner]
+}
+
+/// ## Authority
+///
+/// Pushes the stored authority.
+///
+/// ### Template Arguments
+///
+/// - `authority_slot`: storage slot of the authority.
+///
+/// ### Usage
+///
+/// ```huff
+/// #include "libauth.huff"
+///
+/// #define constant AUTHORITY_SLOT = FREE_STORAGE_POINTER()
+///
+/// #define macro MAIN() = takes (0) returns (0) {
+///     AUTHORITY([AUTHORITY_SLOT])
+///     0x00 mstore
+///     0x20 0x00 return
+/// }
+/// ```
+#define macro AUTHORITY(authority_slot) = takes (0) returns (1) {
+    <authority_slot>        // [authority_slot]
+    sload                   // [authority]
+}
+"#;
+
+const AUTHORIZATION_DEFINITION: &str = r#"
+/// ## Is Authorized
+///
+/// Checks if `user` is the owner or is permitted by the authority to call `sig` on this contract.
+///
+/// > WARNING: Memory from `free_mem_ptr` to `free_mem_ptr + 0x80` will be overwritten.
+///
+/// ### Template Arguments
+///
+/// - `owner_slot`: storage slot of the owner.
+/// - `authority_slot`: storage slot of the authority.
+/// - `free_mem_ptr`: pointer to free memory (requires at least 128 bytes of memory following it).
+///
+/// ### Stack Arguments
+///
+/// - `user`: (`stack[0]`) address to check.
+/// - `sig`: (`stack[1]`) right-aligned function selector.
+///
+/// ### Usage
+///
+/// ```huff
+/// #include "libauth.huff"
+///
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x00 calldataload 0xe0 shr
+///     caller
+///     IS_AUTHORIZED([OWNER_SLOT], [AUTHORITY_SLOT], 0x00)
+///     0x00 mstore
+///     0x20 0x00 return
+/// }
+/// ```
+#define macro IS_AUTHORIZED(owner_slot, authority_slot, free_mem_ptr) = takes (2) returns (1) {
+    // takes:                   // [user, sig]
+    dup1                        // [user, user, sig]
+    <owner_slot> sload          // [owner, user, user, sig]
+    eq                          // [is_owner, user, sig]
+    is_owner                    // [is_owner_dest, is_owner, user, sig]
+    jumpi                       // [user, sig]
+
+    <authority_slot> sload      // [authority, user, sig]
+    dup1                        // [authority, authority, user, sig]
+    iszero                      // [no_authority, authority, user, sig]
+    no_authority                // [no_authority_dest, no_authority, authority, user, sig]
+    jumpi                       // [authority, user, sig]
+
+    __FUNC_SIG(canCall)         // [selector, authority, user, sig]
+    <free_mem_ptr> mstore       // [authority, user, sig]
+    swap1                       // [user, authority, sig]
+    <free_mem_ptr> 0x20 add     // [user_ptr, user, authority, sig]
+    mstore                      // [authority, sig]
+    address                     // [this, authority, sig]
+    <free_mem_ptr> 0x40 add     // [this_ptr, this, authority, sig]
+    mstore                      // [authority, sig]
+    swap1                       // [sig, authority]
+    0xe0 shl                    // [sig_word, authority]
+    <free_mem_ptr> 0x60 add     // [sig_ptr, sig_word, authority]
+    mstore                      // [authority]
+
+    0x20                        // [ret_size, authority]
+    <free_mem_ptr>              // [ret_ptr, ret_size, authority]
+    0x64                        // [arg_size, ret_ptr, ret_size, authority]
+    <free_mem_ptr> 0x1c add     // [arg_ptr, arg_size, ret_ptr, ret_size, authority]
+    dup5                        // [authority, arg_ptr, arg_size, ret_ptr, ret_size, authority]
+    gas                         // [gas, authority, arg_ptr, arg_size, ret_ptr, ret_size, authority]
+    staticcall                  // [call_success, authority]
+
+    returndatasize 0x1f lt      // [has_ret, call_success, authority]
+    and                         // [call_ok, authority]
+    <free_mem_ptr> mload        // [can_call, call_ok, authority]
+    0x01 eq                     // [is_true, call_ok, authority]
+    and                         // [authorized, authority]
+    swap1                       // [authority, authorized]
+    pop                         // [authorized]
+    done                        // [done_dest, authorized]
+    jump                        // [authorized]
+
+    no_authority:               // [authority, user, sig]
+        swap2                   // [sig, user, authority]
+        pop                     // [user, authority]
+        pop                     // [authorized]
+        done                    // [done_dest, authorized]
+        jump                    // [authorized]
+
+    is_owner:                   // [user, sig]
+        pop                     // [sig]
+        pop                     // []
+        0x01                    // [authorized]
+    done:                       // [authorized]
+}
+
+/// ## Auth Requires
+///
+/// Reverts if the caller is not authorized to call the current function, as identified by the
+/// selector in calldata.
+///
+/// > WARNING: Memory from `free_mem_ptr` to `free_mem_ptr + 0x80` will be overwritten.
+///
+/// ### Template Arguments
+///
+/// - `owner_slot`: storage slot of the owner.
+/// - `authority_slot`: storage slot of the authority.
+/// - `free_mem_ptr`: pointer to free memory (requires at least 128 bytes of memory following it).
+///
+/// ### Panics
+///
+/// - if the caller is not the owner and the authority does not permit the call.
+///
+/// ### Usage
+///
+/// ```huff
+/// #include "libauth.huff"
+///
+/// #define macro ADMIN_ONLY() = takes (0) returns (0) {
+///     AUTH_REQUIRES([OWNER_SLOT], [AUTHORITY_SLOT], 0x00)
+///     stop
+/// }
+/// ```
+#define macro AUTH_REQUIRES(owner_slot, authority_slot, free_mem_ptr) = takes (0) returns (0) {
+    0x00 calldataload       // [calldata_word]
+    0xe0 shr                // [sig]
+    caller                  // [user, sig]
+    IS_AUTHORIZED(<owner_slot>, <authority_slot>, <free_mem_ptr>) // [authorized]
+    authorized              // [authorized_dest, authorized]
+    jumpi                   // []
+        __ERROR(Unauthorized) // [err]
+        0x00                // [ptr, err]
+        mstore              // []
+        0x04                // [err_len]
+        0x00                // [ptr, err_len]
+        revert              // []
+    authorized:             // []
+}
+"#;
+
+const SETTER_DEFINITION: &str = r#"
+/// ## Auth Constructor
+///
+/// Initializes the owner and authority, emitting `OwnershipTransferred` and `AuthorityUpdated`.
+///
+/// ### Template Arguments
+///
+/// - `owner_slot`: storage slot of the owner.
+/// - `authority_slot`: storage slot of the authority.
+///
+/// ### Stack Arguments
+///
+/// - `owner`: (`stack[0]`) initial owner.
+/// - `authority`: (`stack[1]`) initial authority, may be zero.
+///
+/// ### Usage
+///
+/// ```huff
+/// #include "libauth.huff"
+///
+/// #define macro CONSTRUCTOR() = takes (0) returns (0) {
+///     0x00 caller
+///     AUTH_CONSTRUCTOR([OWNER_SLOT], [AUTHORITY_SLOT])
+/// }
+/// ```
+#define macro AUTH_CONSTRUCTOR(owner_slot, authority_slot) = takes (2) returns (0) {
+    // takes:                               // [owner, authority]
+    dup1                                    // [owner, owner, authority]
+    <owner_slot> sstore                     // [owner, authority]
+    caller                                  // [user, owner, authority]
+    __EVENT_HASH(OwnershipTransferred)      // [sig, user, owner, authority]
+    0x00 0x00                               // [ptr, len, sig, user, owner, authority]
+    log3                                    // [authority]
+    dup1                                    // [authority, authority]
+    <authority_slot> sstore                 // [authority]
+    caller                                  // [user, authority]
+    __EVENT_HASH(AuthorityUpdated)          // [sig, user, authority]
+    0x00 0x00                               // [ptr, len, sig, user, authority]
+    log3                                    // []
+}
+
+/// ## Transfer Ownership
+///
+/// Updates the owner, emitting `OwnershipTransferred`.
+///
+/// > WARNING: Memory from `free_mem_ptr` to `free_mem_ptr + 0x80` will be overwritten.
+///
+/// ### Template Arguments
+///
+/// - `owner_slot`: storage slot of the owner.
+/// - `authority_slot`: storage slot of the authority.
+/// - `free_mem_ptr`: pointer to free memory (requires at least 128 bytes of memory following it).
+///
+/// ### Stack Arguments
+///
+/// - `new_owner`: (`stack[0]`) new owner.
+///
+/// ### Panics
+///
+/// - if the caller is not authorized to call the current function.
+///
+/// ### Usage
+///
+/// ```huff
+/// #include "libauth.huff"
+///
+/// #define macro TRANSFER_OWNERSHIP_WRAPPER() = takes (0) returns (0) {
+///     0x04 calldataload
+///     TRANSFER_OWNERSHIP([OWNER_SLOT], [AUTHORITY_SLOT], 0x00)
+///     stop
+/// }
+/// ```
+#define macro TRANSFER_OWNERSHIP(owner_slot, authority_slot, free_mem_ptr) = takes (1) returns (0) {
+    // takes:                               // [new_owner]
+    AUTH_REQUIRES(<owner_slot>, <authority_slot>, <free_mem_ptr>) // [new_owner]
+    dup1                                    // [new_owner, new_owner]
+    <owner_slot> sstore                     // [new_owner]
+    caller                                  // [user, new_owner]
+    __EVENT_HASH(OwnershipTransferred)      // [sig, user, new_owner]
+    0x00 0x00                               // [ptr, len, sig, user, new_owner]
+    log3                                    // []
+}
+
+/// ## Set Authority
+///
+/// Updates the authority, emitting `AuthorityUpdated`.
+///
+/// > WARNING: Memory from `free_mem_ptr` to `free_mem_ptr + 0x80` will be overwritten.
+///
+/// ### Template Arguments
+///
+/// - `owner_slot`: storage slot of the owner.
+/// - `authority_slot`: storage slot of the authority.
+/// - `free_mem_ptr`: pointer to free memory (requires at least 128 bytes of memory following it).
+///
+/// ### Stack Arguments
+///
+/// - `new_authority`: (`stack[0]`) new authority, may be zero.
+///
+/// ### Panics
+///
+/// - if the caller is not authorized to call the current function.
+///
+/// ### Usage
+///
+/// ```huff
+/// #include "libauth.huff"
+///
+/// #define macro SET_AUTHORITY_WRAPPER() = takes (0) returns (0) {
+///     0x04 calldataload
+///     SET_AUTHORITY([OWNER_SLOT], [AUTHORITY_SLOT], 0x00)
+///     stop
+/// }
+/// ```
+#define macro SET_AUTHORITY(owner_slot, authority_slot, free_mem_ptr) = takes (1) returns (0) {
+    // takes:                               // [new_authority]
+    AUTH_REQUIRES(<owner_slot>, <authority_slot>, <free_mem_ptr>) // [new_authority]
+    dup1                                    // [new_authority, new_authority]
+    <authority_slot> sstore                 // [new_authority]
+    caller                                  // [user, new_authority]
+    __EVENT_HASH(AuthorityUpdated)          // [sig, user, new_authority]
+    0x00 0x00                               // [ptr, len, sig, user, new_authority]
+    log3                                    // []
+}
+"#;
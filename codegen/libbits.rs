@@ -0,0 +1,490 @@
+use crate::emit::line;
+use crate::libcast::mask;
+use crate::templates::{self, Context};
+
+/// The masks of the lanes `POPCOUNT` sums the bits of, by name and lane pattern, in the order the
+/// lanes widen.
+const POPCOUNT_MASKS: [(&str, &str); 5] = [
+    ("POPCOUNT_M2", "5"),
+    ("POPCOUNT_M4", "3"),
+    ("POPCOUNT_M8", "0f"),
+    ("POPCOUNT_M16", "00ff"),
+    ("POPCOUNT_H16", "0001"),
+];
+
+pub fn render() -> String {
+    let constants = POPCOUNT_MASKS
+        .iter()
+        .map(|(name, pattern)| {
+            let word = pattern.repeat(64 / pattern.len());
+            format!(
+                "#define constant {} = 0x{}\n",
+                name,
+                word.trim_start_matches("00")
+            )
+        })
+        .collect::<String>();
+
+    let context = Context::new()
+        .text("constants", constants)
+        .text("msb_body", msb_body());
+
+    templates::render(LIBRARY_TEMPLATE, &context)
+}
+
+/// Emits the binary search of `MSB`, turning `[word]` into `[index]`: each step shifts the word by
+/// the bits found so far and sets the next bit of the index if what remains exceeds half as many
+/// bits as the step before.
+fn msb_body() -> String {
+    let mut body = line("dup1", "[word, word]");
+    body.push_str(&line(&threshold(7), "[threshold, word, word]"));
+    body.push_str(&line("lt", "[is_above, word]"));
+    body.push_str(&line("0x07", "[0x07, is_above, word]"));
+    body.push_str(&line("shl", "[index, word]"));
+
+    for step in (0..7).rev() {
+        body.push_str(&line("dup2", "[word, index, word]"));
+        body.push_str(&line("dup2", "[index, word, index, word]"));
+        body.push_str(&line("shr", "[remaining, index, word]"));
+        body.push_str(&line(
+            &threshold(step),
+            "[threshold, remaining, index, word]",
+        ));
+        body.push_str(&line("lt", "[is_above, index, word]"));
+        if step > 0 {
+            body.push_str(&line(
+                &format!("0x{:02x}", step),
+                "[shift, is_above, index, word]",
+            ));
+            body.push_str(&line("shl", "[bit, index, word]"));
+        }
+        body.push_str(&line("or", "[index, word]"));
+    }
+
+    body.push_str(&line("swap1", "[word, index]"));
+    body.push_str(&line("iszero", "[is_zero, index]"));
+    body.push_str(&line("0x08", "[0x08, is_zero, index]"));
+    body.push_str(&line("shl", "[zero_index, index]"));
+    body.push_str(&line("or", "[index]"));
+    body
+}
+
+/// The largest value of `2 ** step` bits, which the remaining word exceeds if it has more bits.
+fn threshold(step: u16) -> String {
+    let threshold = mask(1 << step);
+    match threshold.len() % 2 {
+        0 => threshold,
+        _ => format!("0x0{}", &threshold[2..]),
+    }
+}
+
+const LIBRARY_TEMPLATE: &str = r#"
+//  ------------------------------------------------------------------------------------------------
+//! # Bits Library
+//!
+//! Provides reading and writing of single bits of a word, population counts and the indexes of the
+//! most and least significant set bits, for bitmaps such as allowlists or tick bitmaps.
+//!
+//! Bits are indexed from the least significant bit, bit 0. Each single bit operation takes its
+//! index either as a template argument or, in its `_AT` variant, on the stack.
+//!
+//! ## API
+//!
+//! - `GET_BIT` - Reads a bit of a word.
+//! - `SET_BIT` - Sets a bit of a word.
+//! - `CLEAR_BIT` - Clears a bit of a word.
+//! - `TOGGLE_BIT` - Flips a bit of a word.
+//! - `POPCOUNT` - Counts the set bits of a word.
+//! - `MSB` - Index of the most significant set bit of a word.
+//! - `LSB` - Index of the least significant set bit of a word.
+//!
+//! - `GET_BIT_AT`, `SET_BIT_AT`, `CLEAR_BIT_AT`, `TOGGLE_BIT_AT` - The same, for an index on the
+//!   stack.
+
+/// ## Popcount Masks
+///
+/// Masks of the low halves of the 2, 4, 8 and 16 bit lanes `POPCOUNT` sums, and the multiplier
+/// summing the 16 bit lanes into the highest.
+{{constants}}
+/// ## Get Bit
+///
+/// Reads bit `n` of a word.
+///
+/// ### Template Arguments
+///
+/// - `n`: index of the bit.
+///
+/// ### Stack Arguments
+///
+/// - `word`: (`stack[0]`) word to read.
+///
+/// ### Conditions
+///
+/// @pre n < 256
+/// @post result == (word >> n) & 1
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x04 calldataload
+///     GET_BIT(0x03)
+///     0x00 mstore
+///     0x20 0x00 return
+/// }
+/// ```
+#define macro GET_BIT(n) = takes (1) returns (1) {
+    // takes:               // [word]
+    <n>                     // [n, word]
+    shr                     // [shifted]
+    0x01                    // [0x01, shifted]
+    and                     // [bit]
+}
+
+/// ## Get Bit At
+///
+/// Reads bit `index` of a word.
+///
+/// ### Stack Arguments
+///
+/// - `index`: (`stack[0]`) index of the bit.
+/// - `word`: (`stack[1]`) word to read.
+///
+/// ### Conditions
+///
+/// @post result == (word >> index) & 1
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x24 calldataload
+///     0x04 calldataload
+///     GET_BIT_AT()
+///     0x00 mstore
+///     0x20 0x00 return
+/// }
+/// ```
+#define macro GET_BIT_AT() = takes (2) returns (1) {
+    // takes:               // [index, word]
+    shr                     // [shifted]
+    0x01                    // [0x01, shifted]
+    and                     // [bit]
+}
+
+/// ## Set Bit
+///
+/// Sets bit `n` of a word.
+///
+/// ### Template Arguments
+///
+/// - `n`: index of the bit.
+///
+/// ### Stack Arguments
+///
+/// - `word`: (`stack[0]`) word to write.
+///
+/// ### Conditions
+///
+/// @pre n < 256
+/// @post result == word | (1 << n)
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x00 sload
+///     SET_BIT(0x03)
+///     0x00 sstore
+/// }
+/// ```
+#define macro SET_BIT(n) = takes (1) returns (1) {
+    // takes:               // [word]
+    0x01                    // [0x01, word]
+    <n>                     // [n, 0x01, word]
+    shl                     // [mask, word]
+    or                      // [word]
+}
+
+/// ## Set Bit At
+///
+/// Sets bit `index` of a word, leaving the word as it is if `index` is not below 256.
+///
+/// ### Stack Arguments
+///
+/// - `index`: (`stack[0]`) index of the bit.
+/// - `word`: (`stack[1]`) word to write.
+///
+/// ### Conditions
+///
+/// @post result == word | (1 << index)
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x00 sload
+///     0x04 calldataload
+///     SET_BIT_AT()
+///     0x00 sstore
+/// }
+/// ```
+#define macro SET_BIT_AT() = takes (2) returns (1) {
+    // takes:               // [index, word]
+    0x01                    // [0x01, index, word]
+    swap1                   // [index, 0x01, word]
+    shl                     // [mask, word]
+    or                      // [word]
+}
+
+/// ## Clear Bit
+///
+/// Clears bit `n` of a word.
+///
+/// ### Template Arguments
+///
+/// - `n`: index of the bit.
+///
+/// ### Stack Arguments
+///
+/// - `word`: (`stack[0]`) word to write.
+///
+/// ### Conditions
+///
+/// @pre n < 256
+/// @post result == word & ~(1 << n)
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x00 sload
+///     CLEAR_BIT(0x03)
+///     0x00 sstore
+/// }
+/// ```
+#define macro CLEAR_BIT(n) = takes (1) returns (1) {
+    // takes:               // [word]
+    0x01                    // [0x01, word]
+    <n>                     // [n, 0x01, word]
+    shl                     // [mask, word]
+    not                     // [clear_mask, word]
+    and                     // [word]
+}
+
+/// ## Clear Bit At
+///
+/// Clears bit `index` of a word, leaving the word as it is if `index` is not below 256.
+///
+/// ### Stack Arguments
+///
+/// - `index`: (`stack[0]`) index of the bit.
+/// - `word`: (`stack[1]`) word to write.
+///
+/// ### Conditions
+///
+/// @post result == word & ~(1 << index)
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x00 sload
+///     0x04 calldataload
+///     CLEAR_BIT_AT()
+///     0x00 sstore
+/// }
+/// ```
+#define macro CLEAR_BIT_AT() = takes (2) returns (1) {
+    // takes:               // [index, word]
+    0x01                    // [0x01, index, word]
+    swap1                   // [index, 0x01, word]
+    shl                     // [mask, word]
+    not                     // [clear_mask, word]
+    and                     // [word]
+}
+
+/// ## Toggle Bit
+///
+/// Flips bit `n` of a word.
+///
+/// ### Template Arguments
+///
+/// - `n`: index of the bit.
+///
+/// ### Stack Arguments
+///
+/// - `word`: (`stack[0]`) word to write.
+///
+/// ### Conditions
+///
+/// @pre n < 256
+/// @post result == word ^ (1 << n)
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x00 sload
+///     TOGGLE_BIT(0x03)
+///     0x00 sstore
+/// }
+/// ```
+#define macro TOGGLE_BIT(n) = takes (1) returns (1) {
+    // takes:               // [word]
+    0x01                    // [0x01, word]
+    <n>                     // [n, 0x01, word]
+    shl                     // [mask, word]
+    xor                     // [word]
+}
+
+/// ## Toggle Bit At
+///
+/// Flips bit `index` of a word, leaving the word as it is if `index` is not below 256.
+///
+/// ### Stack Arguments
+///
+/// - `index`: (`stack[0]`) index of the bit.
+/// - `word`: (`stack[1]`) word to write.
+///
+/// ### Conditions
+///
+/// @post result == word ^ (1 << index)
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x00 sload
+///     0x04 calldataload
+///     TOGGLE_BIT_AT()
+///     0x00 sstore
+/// }
+/// ```
+#define macro TOGGLE_BIT_AT() = takes (2) returns (1) {
+    // takes:               // [index, word]
+    0x01                    // [0x01, index, word]
+    swap1                   // [index, 0x01, word]
+    shl                     // [mask, word]
+    xor                     // [word]
+}
+
+/// ## Popcount
+///
+/// Counts the set bits of a word, summing the bits of ever wider lanes of the word without
+/// branching.
+///
+/// ### Stack Arguments
+///
+/// - `word`: (`stack[0]`) word to count the bits of.
+///
+/// ### Conditions
+///
+/// @post result <= 256
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x04 calldataload
+///     POPCOUNT()
+///     0x00 mstore
+///     0x20 0x00 return
+/// }
+/// ```
+#define macro POPCOUNT() = takes (1) returns (1) {
+    // takes:               // [word]
+    dup1                    // [word, word]
+    0x01                    // [0x01, word, word]
+    shr                     // [shifted, word]
+    [POPCOUNT_M2]           // [m2, shifted, word]
+    and                     // [high_bits, word]
+    swap1                   // [word, high_bits]
+    sub                     // [counts_2]
+    dup1                    // [counts_2, counts_2]
+    0x02                    // [0x02, counts_2, counts_2]
+    shr                     // [shifted, counts_2]
+    [POPCOUNT_M4]           // [m4, shifted, counts_2]
+    and                     // [high_counts, counts_2]
+    swap1                   // [counts_2, high_counts]
+    [POPCOUNT_M4]           // [m4, counts_2, high_counts]
+    and                     // [low_counts, high_counts]
+    add                     // [counts_4]
+    dup1                    // [counts_4, counts_4]
+    0x04                    // [0x04, counts_4, counts_4]
+    shr                     // [shifted, counts_4]
+    add                     // [sums]
+    [POPCOUNT_M8]           // [m8, sums]
+    and                     // [counts_8]
+    dup1                    // [counts_8, counts_8]
+    0x08                    // [0x08, counts_8, counts_8]
+    shr                     // [shifted, counts_8]
+    add                     // [sums]
+    [POPCOUNT_M16]          // [m16, sums]
+    and                     // [counts_16]
+    [POPCOUNT_H16]          // [h16, counts_16]
+    mul                     // [total]
+    0xf0                    // [0xf0, total]
+    shr                     // [count]
+}
+
+/// ## Most Significant Bit
+///
+/// Pushes the index of the most significant set bit of a word, `floor(log2(word))`, or 256 if
+/// the word is zero, by a branchless binary search.
+///
+/// ### Stack Arguments
+///
+/// - `word`: (`stack[0]`) word to search.
+///
+/// ### Conditions
+///
+/// @post result == 256 || (word >> result) == 1
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x04 calldataload
+///     MSB()
+///     0x00 mstore
+///     0x20 0x00 return
+/// }
+/// ```
+#define macro MSB() = takes (1) returns (1) {
+    // takes:               // [word]
+{{msb_body}}}
+
+/// ## Least Significant Bit
+///
+/// Pushes the index of the least significant set bit of a word, or 256 if the word is zero, as
+/// the most significant bit of `word & -word`.
+///
+/// ### Stack Arguments
+///
+/// - `word`: (`stack[0]`) word to search.
+///
+/// ### Conditions
+///
+/// @post result == 256 || (word >> result) & 1 == 1
+/// @post result == 256 || word & ((1 << result) - 1) == 0
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x04 calldataload
+///     LSB()
+///     0x00 mstore
+///     0x20 0x00 return
+/// }
+/// ```
+#define macro LSB() = takes (1) returns (1) {
+    // takes:               // [word]
+    dup1                    // [word, word]
+    0x00                    // [0x00, word, word]
+    sub                     // [negated, word]
+    and                     // [lowest_bit]
+    MSB()                   // [index]
+}
+"#;
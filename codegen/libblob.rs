@@ -0,0 +1,243 @@
+use crate::config::Table;
+use crate::profile::Profile;
+
+/// Renders `libblob.huff` when the opcode profile of the config allows `blobhash` and
+/// `blobbasefee`, from Cancun.
+pub fn render(config: &Table) -> std::io::Result<Option<String>> {
+    let profile = Profile::load(config)?;
+
+    if !profile.allows(0x49) || !profile.allows(0x4a) {
+        return Ok(None);
+    }
+
+    let libblob = format!(
+        "{}{}{}{}",
+        HEADER, CONSTANT_DEFINITION, BLOB_DEFINITION, POINT_EVALUATION_DEFINITION,
+    );
+
+    Ok(Some(libblob))
+}
+
+const HEADER: &str = r#"
+//  ------------------------------------------------------------------------------------------------
+//! # Blob Library
+//!
+//! Provides macros for EIP-4844 blob transactions.
+//!
+//! Requires the Cancun hardfork. On earlier chains `blobhash` and `blobbasefee` are invalid
+//! opcodes and the point evaluation precompile address holds no code.
+//!
+//! ## API
+//!
+//! - `BLOBHASH_AT` - Pushes the versioned hash of a blob of the current transaction.
+//! - `REQUIRE_BLOB_COUNT` - Reverts if the current transaction does not carry exactly `n` blobs.
+//! - `BLOB_BASEFEE` - Pushes the blob base fee of the current block.
+//! - `POINT_EVALUATION` - Verifies a KZG proof against a versioned hash, reverting on failure.
+"#;
+
+const CONSTANT_DEFINITION: &str = r#"
+/// ## Point Evaluation Precompile
+///
+/// Address of the point evaluation precompile.
+#define constant POINT_EVALUATION_PRECOMPILE = 0x0a
+
+/// ## Field Elements Per Blob
+///
+/// Number of field elements in a blob, returned by the point evaluation precompile.
+#define constant FIELD_ELEMENTS_PER_BLOB = 0x1000
+
+/// ## BLS Modulus
+///
+/// Modulus of the BLS12-381 scalar field, returned by the point evaluation precompile.
+#define constant BLS_MODULUS = 0x73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000001
+
+/// ## Invalid Blob Count Error
+///
+/// Thrown when the current transaction does not carry the expected number of blobs.
+#define error InvalidBlobCount()
+
+/// ## Invalid Point Evaluation Error
+///
+/// Thrown when a KZG proof does not verify.
+#define error InvalidPointEvaluation()
+"#;
+
+const BLOB_DEFINITION: &str = r#"
+/// ## Blob Hash At
+///
+/// Pushes the versioned hash of the blob at `index` of the current transaction, or zero if the
+/// transaction carries no such blob.
+///
+/// ### Template Arguments
+///
+/// - `index`: index of the blob.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     BLOBHASH_AT(0x00)
+///     0x00 mstore
+///     0x20 0x00 return
+/// }
+/// ```
+#define macro BLOBHASH_AT(index) = takes (0) returns (1) {
+    <index>                 // [index]
+    blobhash                // [versioned_hash]
+}
+
+/// ## Require Blob Count
+///
+/// Reverts if the current transaction does not carry exactly `n` blobs.
+///
+/// ### Template Arguments
+///
+/// - `n`: expected number of blobs.
+///
+/// ### Panics
+///
+/// - if the current transaction carries fewer or more than `n` blobs.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     REQUIRE_BLOB_COUNT(0x01)
+///     stop
+/// }
+/// ```
+#define macro REQUIRE_BLOB_COUNT(n) = takes (0) returns (0) {
+    <n>                     // [n]
+    blobhash                // [extra_hash]
+    iszero                  // [no_extra]
+    0x01                    // [1, no_extra]
+    <n>                     // [n, 1, no_extra]
+    sub                     // [last, no_extra]
+    blobhash                // [last_hash, no_extra]
+    iszero                  // [no_last, no_extra]
+    iszero                  // [has_last, no_extra]
+    <n>                     // [n, has_last, no_extra]
+    iszero                  // [is_none, has_last, no_extra]
+    or                      // [has_n, no_extra]
+    and                     // [is_n]
+    is_n                    // [is_n_dest, is_n]
+    jumpi                   // []
+        __ERROR(InvalidBlobCount) // [err]
+        0x00                // [ptr, err]
+        mstore              // []
+        0x04                // [err_len]
+        0x00                // [ptr, err_len]
+        revert              // []
+    is_n:                   // []
+}
+
+/// ## Blob Base Fee
+///
+/// Pushes the blob base fee of the current block.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     BLOB_BASEFEE()
+///     0x00 mstore
+///     0x20 0x00 return
+/// }
+/// ```
+#define macro BLOB_BASEFEE() = takes (0) returns (1) {
+    blobbasefee             // [blob_basefee]
+}
+"#;
+
+const POINT_EVALUATION_DEFINITION: &str = r#"
+/// ## Point Evaluation
+///
+/// Verifies that the blob committed to by `versioned_hash` evaluates to `y` at `z`, reverting if
+/// the proof does not verify.
+///
+/// The 48 byte commitment and 48 byte proof are read, in that order, from calldata at
+/// `proof_offset`. The precompile input is laid out in memory as follows.
+///
+/// | Offset | Size | Value            |
+/// | ------ | ---- | ---------------- |
+/// | `0x00` | 32   | `versioned_hash` |
+/// | `0x20` | 32   | `z`              |
+/// | `0x40` | 32   | `y`              |
+/// | `0x60` | 48   | commitment       |
+/// | `0x90` | 48   | proof            |
+///
+/// The precompile is considered successful only if it returns the BLS modulus, so calls on chains
+/// without the precompile revert rather than silently succeeding.
+///
+/// > WARNING: Memory from `ptr` to `ptr + 0xc0` will be overwritten.
+///
+/// ### Template Arguments
+///
+/// - `ptr`: memory pointer to lay out the precompile input at.
+///
+/// ### Stack Arguments
+///
+/// - `versioned_hash`: (`stack[0]`) versioned hash of the blob.
+/// - `z`: (`stack[1]`) evaluation point.
+/// - `y`: (`stack[2]`) claimed evaluation.
+/// - `proof_offset`: (`stack[3]`) calldata offset of the commitment and proof.
+///
+/// ### Panics
+///
+/// - if the proof does not verify.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x44                // [proof_offset]
+///     0x24 calldataload   // [y, proof_offset]
+///     0x04 calldataload   // [z, y, proof_offset]
+///     BLOBHASH_AT(0x00)   // [versioned_hash, z, y, proof_offset]
+///     POINT_EVALUATION(0x00)
+///     stop
+/// }
+/// ```
+#define macro POINT_EVALUATION(ptr) = takes (4) returns (0) {
+    // takes:               // [versioned_hash, z, y, proof_offset]
+    <ptr>                   // [ptr, versioned_hash, z, y, proof_offset]
+    mstore                  // [z, y, proof_offset]
+    <ptr>                   // [ptr, z, y, proof_offset]
+    0x20                    // [0x20, ptr, z, y, proof_offset]
+    add                     // [z_ptr, z, y, proof_offset]
+    mstore                  // [y, proof_offset]
+    <ptr>                   // [ptr, y, proof_offset]
+    0x40                    // [0x40, ptr, y, proof_offset]
+    add                     // [y_ptr, y, proof_offset]
+    mstore                  // [proof_offset]
+    0x60                    // [proof_len, proof_offset]
+    swap1                   // [proof_offset, proof_len]
+    <ptr>                   // [ptr, proof_offset, proof_len]
+    0x60                    // [0x60, ptr, proof_offset, proof_len]
+    add                     // [proof_ptr, proof_offset, proof_len]
+    calldatacopy            // []
+    0x40                    // [ret_len]
+    <ptr>                   // [ret_ptr, ret_len]
+    0xc0                    // [input_len, ret_ptr, ret_len]
+    <ptr>                   // [input_ptr, input_len, ret_ptr, ret_len]
+    [POINT_EVALUATION_PRECOMPILE] // [precompile, input_ptr, input_len, ret_ptr, ret_len]
+    gas                     // [gas, precompile, input_ptr, input_len, ret_ptr, ret_len]
+    staticcall              // [success]
+    <ptr>                   // [ptr, success]
+    0x20                    // [0x20, ptr, success]
+    add                     // [modulus_ptr, success]
+    mload                   // [modulus, success]
+    [BLS_MODULUS]           // [bls_modulus, modulus, success]
+    eq                      // [is_modulus, success]
+    and                     // [verified]
+    verified                // [verified_dest, verified]
+    jumpi                   // []
+        __ERROR(InvalidPointEvaluation) // [err]
+        0x00                // [ptr, err]
+        mstore              // []
+        0x04                // [err_len]
+        0x00                // [ptr, err_len]
+        revert              // []
+    verified:               // []
+}
+"#;
@@ -0,0 +1,251 @@
+use crate::config::{invalid, Table, Value};
+use crate::emit::line;
+use crate::templates::{self, Context};
+
+/// Number of hash probes per item when the config does not set `bloom.probes`.
+const DEFAULT_PROBES: u128 = 3;
+
+/// Number of storage words when the config does not set `bloom.words`.
+const DEFAULT_WORDS: u128 = 4;
+
+/// Bits of the item hash consumed by each probe.
+const PROBE_BITS: u16 = 16;
+
+pub fn render(config: &Table) -> std::io::Result<String> {
+    let (probes, words) = parse_bloom(config)?;
+
+    let context = Context::new()
+        .text("probes", probes.to_string())
+        .text("words", words.to_string())
+        .text("bits", (words as u32 * 256).to_string())
+        .text("add_body", generate_add(probes, words))
+        .text("contains_body", generate_contains(probes, words));
+
+    let libbloom = format!(
+        "{}{}{}{}",
+        templates::render(HEADER, &context),
+        HASH_DEFINITION,
+        templates::render(ADD_TEMPLATE, &context),
+        templates::render(CONTAINS_TEMPLATE, &context),
+    );
+
+    Ok(libbloom)
+}
+
+fn parse_bloom(config: &Table) -> std::io::Result<(u16, u16)> {
+    let bloom = match config.get("bloom") {
+        None => return Ok((DEFAULT_PROBES as u16, DEFAULT_WORDS as u16)),
+        Some(Value::Table(bloom)) => bloom,
+        Some(_) => return Err(invalid("`bloom` must be a table")),
+    };
+
+    let read = |key: &str, default: u128, max: u128| match bloom.get(key) {
+        None => Ok(default as u16),
+        Some(value) => value
+            .as_integer()
+            .filter(|n| (1..=max).contains(n))
+            .map(|n| n as u16)
+            .ok_or_else(|| invalid(format!("`bloom.{}` must be between 1 and {}", key, max))),
+    };
+
+    Ok((
+        read("probes", DEFAULT_PROBES, (256 / PROBE_BITS) as u128)?,
+        read("words", DEFAULT_WORDS, 256)?,
+    ))
+}
+
+/// Emits the code turning `[hash]` into `[mask, slot, hash]` for the given probe.
+fn generate_probe(probe: u16, words: u16) -> String {
+    let mut body = line(
+        "dup1",
+        if probe > 0 {
+            "[hash, hash]"
+        } else {
+            "[probe, hash]"
+        },
+    );
+
+    if probe > 0 {
+        body.push_str(&line(
+            &format!("0x{:02x}", probe * PROBE_BITS),
+            "[shift, hash, hash]",
+        ));
+        body.push_str(&line("shr", "[probe, hash]"));
+    }
+
+    if words.is_power_of_two() {
+        body.push_str(&line(
+            &format!("0x{:x}", words as u32 * 256 - 1),
+            "[bit_mask, probe, hash]",
+        ));
+        body.push_str(&line("and", "[position, hash]"));
+    } else {
+        body.push_str(&line("0xffff", "[probe_mask, probe, hash]"));
+        body.push_str(&line("and", "[probe, hash]"));
+        body.push_str(&line(
+            &format!("0x{:x}", words as u32 * 256),
+            "[bits, probe, hash]",
+        ));
+        body.push_str(&line("swap1", "[probe, bits, hash]"));
+        body.push_str(&line("mod", "[position, hash]"));
+    }
+
+    if words == 1 {
+        body.push_str(&line("0x01", "[1, position, hash]"));
+        body.push_str(&line("swap1", "[position, 1, hash]"));
+        body.push_str(&line("shl", "[mask, hash]"));
+        body.push_str(&line("<slot_base>", "[slot, mask, hash]"));
+    } else {
+        body.push_str(&line("dup1", "[position, position, hash]"));
+        body.push_str(&line("0xff", "[0xff, position, position, hash]"));
+        body.push_str(&line("and", "[bit, position, hash]"));
+        body.push_str(&line("0x01", "[1, bit, position, hash]"));
+        body.push_str(&line("swap1", "[bit, 1, position, hash]"));
+        body.push_str(&line("shl", "[mask, position, hash]"));
+        body.push_str(&line("swap1", "[position, mask, hash]"));
+        body.push_str(&line("0x08", "[0x08, position, mask, hash]"));
+        body.push_str(&line("shr", "[word, mask, hash]"));
+        body.push_str(&line("<slot_base>", "[slot_base, word, mask, hash]"));
+        body.push_str(&line("add", "[slot, mask, hash]"));
+    }
+
+    body
+}
+
+fn generate_add(probes: u16, words: u16) -> String {
+    let mut body = line("// takes:", "[item]");
+    body.push_str(&line("__BLOOM_HASH()", "[hash]"));
+
+    for probe in 0..probes {
+        body.push_str(&generate_probe(probe, words));
+        body.push_str(&line("dup1", "[slot, slot, mask, hash]"));
+        body.push_str(&line("sload", "[bloom_word, slot, mask, hash]"));
+        body.push_str(&line("dup3", "[mask, bloom_word, slot, mask, hash]"));
+        body.push_str(&line("or", "[new_word, slot, mask, hash]"));
+        body.push_str(&line("swap1", "[slot, new_word, mask, hash]"));
+        body.push_str(&line("sstore", "[mask, hash]"));
+        body.push_str(&line("pop", "[hash]"));
+    }
+
+    body.push_str(&line("pop", "[]"));
+    body
+}
+
+fn generate_contains(probes: u16, words: u16) -> String {
+    let mut body = line("// takes:", "[item]");
+    body.push_str(&line("__BLOOM_HASH()", "[hash]"));
+
+    for probe in 0..probes {
+        body.push_str(&generate_probe(probe, words));
+        body.push_str(&line("sload", "[bloom_word, mask, hash]"));
+        body.push_str(&line("and", "[is_set, hash]"));
+        body.push_str(&line("iszero", "[is_unset, hash]"));
+        body.push_str(&line("absent", "[absent_dest, is_unset, hash]"));
+        body.push_str(&line("jumpi", "[hash]"));
+    }
+
+    body.push_str(&line("pop", "[]"));
+    body.push_str(&line("0x01", "[maybe_contains]"));
+    body.push_str(&line("done", "[done_dest, maybe_contains]"));
+    body.push_str(&line("jump", "[maybe_contains]"));
+    body.push_str("    absent:                 // [hash]\n");
+    body.push_str("        pop                 // []\n");
+    body.push_str("        0x00                // [maybe_contains]\n");
+    body.push_str("    done:                   // [maybe_contains]\n");
+    body
+}
+
+const HEADER: &str = r#"
+//  ------------------------------------------------------------------------------------------------
+//! # Bloom Library
+//!
+//! Provides a storage backed bloom filter for probabilistic membership checks.
+//!
+//! Items are hashed once with keccak256 and each probe takes the next 16 bits of the hash as a bit
+//! position in the filter. This build uses {{probes}} probes over {{words}} storage words ({{bits}} bits),
+//! configurable with `bloom.probes` and `bloom.words` in `libhuff.toml`.
+//!
+//! With `n` items, `k` probes and `m` bits, the false positive rate is about
+//! `(1 - e^(-k * n / m)) ^ k`. There are no false negatives.
+//!
+//! ## API
+//!
+//! - `BLOOM_ADD` - Adds an item to the filter.
+//! - `BLOOM_MAYBE_CONTAINS` - Checks if an item may have been added to the filter.
+"#;
+
+const HASH_DEFINITION: &str = r#"
+/// ## Bloom Hash
+///
+/// Hashes an item for probing.
+#define macro __BLOOM_HASH() = takes (1) returns (1) {
+    // takes:               // [item]
+    0x00                    // [ptr, item]
+    mstore                  // []
+    0x20                    // [len]
+    0x00                    // [ptr, len]
+    sha3                    // [hash]
+}
+"#;
+
+const ADD_TEMPLATE: &str = r#"
+/// ## Bloom Add
+///
+/// Adds an item to the filter, setting the bit of each probe.
+///
+/// > WARNING: Memory from `0x00` to `0x20` will be overwritten.
+///
+/// ### Template Arguments
+///
+/// - `slot_base`: first storage slot of the filter, followed by the remaining filter words.
+///
+/// ### Stack Arguments
+///
+/// - `item`: (`stack[0]`) item to add.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define constant BLOOM_SLOT = FREE_STORAGE_POINTER()
+///
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x04 calldataload
+///     BLOOM_ADD([BLOOM_SLOT])
+///     stop
+/// }
+/// ```
+#define macro BLOOM_ADD(slot_base) = takes (1) returns (0) {
+{{add_body}}}
+"#;
+
+const CONTAINS_TEMPLATE: &str = r#"
+/// ## Bloom Maybe Contains
+///
+/// Checks if an item may have been added to the filter. Returns `false` only if the item was
+/// definitely never added.
+///
+/// > WARNING: Memory from `0x00` to `0x20` will be overwritten.
+///
+/// ### Template Arguments
+///
+/// - `slot_base`: first storage slot of the filter, followed by the remaining filter words.
+///
+/// ### Stack Arguments
+///
+/// - `item`: (`stack[0]`) item to check.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define constant BLOOM_SLOT = FREE_STORAGE_POINTER()
+///
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x04 calldataload
+///     BLOOM_MAYBE_CONTAINS([BLOOM_SLOT])
+///     0x00 mstore
+///     0x20 0x00 return
+/// }
+/// ```
+#define macro BLOOM_MAYBE_CONTAINS(slot_base) = takes (1) returns (1) {
+{{contains_body}}}
+"#;
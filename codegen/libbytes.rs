@@ -0,0 +1,318 @@
+use crate::templates::{self, Context};
+
+/// Renders `libbytes.huff`, copying memory with `mcopy` where the opcode profile allows it, from
+/// Cancun, and with a word by word loop otherwise.
+pub fn render(mcopy: bool) -> String {
+    let context = Context::new().flag("mcopy", mcopy);
+
+    templates::render(LIBRARY_TEMPLATE, &context)
+}
+
+const LIBRARY_TEMPLATE: &str = r#"
+//  ------------------------------------------------------------------------------------------------
+//! # Bytes Library
+//!
+//! Provides copying, slicing, concatenation and hashing of byte arrays in memory.
+//!
+//! A byte array is laid out as Solidity lays out `bytes` in memory: a length word followed by the
+//! bytes of the array. Memory copies use `mcopy` when targeting Cancun or later, and copy word by
+//! word otherwise.
+//!
+//! ## API
+//!
+//! - `MEMCOPY` - Copies a range of memory.
+//! - `BYTES_COPY` - Copies a byte array.
+//! - `BYTES_SLICE` - Copies a range of a byte array into a new byte array.
+//! - `BYTES_CONCAT` - Concatenates two byte arrays into a new byte array.
+//! - `BYTES_HASH_SLICE` - Hashes a range of a byte array in place.
+
+/// ## Out Of Bounds Error
+///
+/// Thrown when a range exceeds a byte array or overflows.
+#define error OutOfBounds()
+
+/// ## Memory Copy
+///
+/// Copies `len` bytes of memory from `src` to `dest`.
+///
+{{#if mcopy}}/// Compiles to `mcopy`, which copies overlapping ranges as if through a buffer.
+{{else}}/// Copies whole words, then merges the last partial word into the word at its destination, so
+/// the bytes after the range are preserved. Ranges may only overlap if `dest <= src`.
+///
+/// > WARNING: Memory up to the word past `src + len` and `dest + len` may be expanded.
+{{/if}}///
+/// ### Stack Arguments
+///
+/// - `dest`: (`stack[0]`) memory pointer to copy to.
+/// - `src`: (`stack[1]`) memory pointer to copy from.
+/// - `len`: (`stack[2]`) number of bytes to copy.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x04 calldataload 0x00 mstore
+///     0x20 0x00 0x40
+///     MEMCOPY()
+///     0x20 0x40 return
+/// }
+/// ```
+#define macro MEMCOPY() = takes (3) returns (0) {
+    // takes:               // [dest, src, len]
+{{#if mcopy}}    mcopy                   // []
+{{else}}    0x00                    // [i, dest, src, len]
+    copy_words:             // [i, dest, src, len]
+        dup1                // [i, i, dest, src, len]
+        0x20                // [0x20, i, i, dest, src, len]
+        add                 // [next, i, dest, src, len]
+        dup5                // [len, next, i, dest, src, len]
+        lt                  // [is_partial, i, dest, src, len]
+        copy_tail           // [copy_tail_dest, is_partial, i, dest, src, len]
+        jumpi               // [i, dest, src, len]
+        dup3                // [src, i, dest, src, len]
+        dup2                // [i, src, i, dest, src, len]
+        add                 // [src_ptr, i, dest, src, len]
+        mload               // [word, i, dest, src, len]
+        dup3                // [dest, word, i, dest, src, len]
+        dup3                // [i, dest, word, i, dest, src, len]
+        add                 // [dest_ptr, word, i, dest, src, len]
+        mstore              // [i, dest, src, len]
+        0x20                // [0x20, i, dest, src, len]
+        add                 // [i, dest, src, len]
+        copy_words          // [copy_words_dest, i, dest, src, len]
+        jump                // [i, dest, src, len]
+    copy_tail:              // [i, dest, src, len]
+        dup1                // [i, i, dest, src, len]
+        dup5                // [len, i, i, dest, src, len]
+        sub                 // [remaining, i, dest, src, len]
+        dup1                // [remaining, remaining, i, dest, src, len]
+        iszero              // [is_done, remaining, i, dest, src, len]
+        copied              // [copied_dest, is_done, remaining, i, dest, src, len]
+        jumpi               // [remaining, i, dest, src, len]
+        0x03                // [0x03, remaining, i, dest, src, len]
+        shl                 // [copied_bits, i, dest, src, len]
+        0x0100              // [0x0100, copied_bits, i, dest, src, len]
+        sub                 // [kept_bits, i, dest, src, len]
+        0x01                // [0x01, kept_bits, i, dest, src, len]
+        swap1               // [kept_bits, 0x01, i, dest, src, len]
+        shl                 // [kept_bit, i, dest, src, len]
+        0x01                // [0x01, kept_bit, i, dest, src, len]
+        swap1               // [kept_bit, 0x01, i, dest, src, len]
+        sub                 // [kept_mask, i, dest, src, len]
+        dup3                // [dest, kept_mask, i, dest, src, len]
+        dup3                // [i, dest, kept_mask, i, dest, src, len]
+        add                 // [dest_ptr, kept_mask, i, dest, src, len]
+        dup1                // [dest_ptr, dest_ptr, kept_mask, i, dest, src, len]
+        mload               // [dest_word, dest_ptr, kept_mask, i, dest, src, len]
+        dup3                // [kept_mask, dest_word, dest_ptr, kept_mask, i, dest, src, len]
+        and                 // [kept, dest_ptr, kept_mask, i, dest, src, len]
+        dup6                // [src, kept, dest_ptr, kept_mask, i, dest, src, len]
+        dup5                // [i, src, kept, dest_ptr, kept_mask, i, dest, src, len]
+        add                 // [src_ptr, kept, dest_ptr, kept_mask, i, dest, src, len]
+        mload               // [src_word, kept, dest_ptr, kept_mask, i, dest, src, len]
+        dup4                // [kept_mask, src_word, kept, dest_ptr, kept_mask, i, dest, src, len]
+        not                 // [copied_mask, src_word, kept, dest_ptr, kept_mask, i, dest, src, len]
+        and                 // [copied, kept, dest_ptr, kept_mask, i, dest, src, len]
+        or                  // [merged, dest_ptr, kept_mask, i, dest, src, len]
+        swap1               // [dest_ptr, merged, kept_mask, i, dest, src, len]
+        mstore              // [kept_mask, i, dest, src, len]
+    copied:                 // [scratch, i, dest, src, len]
+        pop                 // [i, dest, src, len]
+        pop                 // [dest, src, len]
+        pop                 // [src, len]
+        pop                 // [len]
+        pop                 // []
+{{/if}}}
+
+/// ## Bytes Copy
+///
+/// Copies the byte array at `ptr`, with its length, to `dest`.
+///
+/// ### Stack Arguments
+///
+/// - `ptr`: (`stack[0]`) memory pointer of the byte array.
+/// - `dest`: (`stack[1]`) memory pointer to copy the byte array to.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x20 0x00 mstore
+///     0x04 calldataload 0x20 mstore
+///     0x80 0x00
+///     BYTES_COPY()
+///     0x40 swap1 return
+/// }
+/// ```
+#define macro BYTES_COPY() = takes (2) returns (1) {
+    // takes:               // [ptr, dest]
+    dup1                    // [ptr, ptr, dest]
+    mload                   // [len, ptr, dest]
+    0x20                    // [0x20, len, ptr, dest]
+    add                     // [size, ptr, dest]
+    swap1                   // [ptr, size, dest]
+    dup3                    // [dest, ptr, size, dest]
+    MEMCOPY()               // [dest]
+}
+
+/// ## Bytes Slice
+///
+/// Copies `len` bytes of the byte array at `ptr` from index `start` into a new byte array at
+/// `dest`.
+///
+/// ### Stack Arguments
+///
+/// - `ptr`: (`stack[0]`) memory pointer of the byte array.
+/// - `start`: (`stack[1]`) index of the first byte of the slice.
+/// - `len`: (`stack[2]`) length of the slice.
+/// - `dest`: (`stack[3]`) memory pointer of the new byte array.
+///
+/// ### Panics
+///
+/// - if the slice overflows or exceeds the byte array.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x20 0x00 mstore
+///     0x04 calldataload 0x20 mstore
+///     0x80 0x04 0x04 0x00
+///     BYTES_SLICE()
+///     0x24 swap1 return
+/// }
+/// ```
+#define macro BYTES_SLICE() = takes (4) returns (1) {
+    // takes:               // [ptr, start, len, dest]
+    __CHECK_SLICE()         // [ptr, start, len, dest]
+    add                     // [start_ptr, len, dest]
+    0x20                    // [0x20, start_ptr, len, dest]
+    add                     // [src, len, dest]
+    dup2                    // [len, src, len, dest]
+    dup4                    // [dest, len, src, len, dest]
+    mstore                  // [src, len, dest]
+    dup3                    // [dest, src, len, dest]
+    0x20                    // [0x20, dest, src, len, dest]
+    add                     // [dest_data, src, len, dest]
+    MEMCOPY()               // [dest]
+}
+
+/// ## Bytes Concatenation
+///
+/// Writes the concatenation of the byte arrays at `a` and `b` into a new byte array at `dest`,
+/// which must not overlap `b`, or `a` unless it is `a` itself.
+///
+/// ### Stack Arguments
+///
+/// - `a`: (`stack[0]`) memory pointer of the first byte array.
+/// - `b`: (`stack[1]`) memory pointer of the second byte array.
+/// - `dest`: (`stack[2]`) memory pointer of the new byte array.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x20 0x00 mstore
+///     0x04 calldataload 0x20 mstore
+///     0x80 0x00 0x00
+///     BYTES_CONCAT()
+///     0x60 swap1 return
+/// }
+/// ```
+#define macro BYTES_CONCAT() = takes (3) returns (1) {
+    // takes:               // [a, b, dest]
+    dup1                    // [a, a, b, dest]
+    mload                   // [len_a, a, b, dest]
+    dup3                    // [b, len_a, a, b, dest]
+    mload                   // [len_b, len_a, a, b, dest]
+    dup2                    // [len_a, len_b, len_a, a, b, dest]
+    add                     // [len, len_a, a, b, dest]
+    dup5                    // [dest, len, len_a, a, b, dest]
+    mstore                  // [len_a, a, b, dest]
+    dup1                    // [len_a, len_a, a, b, dest]
+    dup3                    // [a, len_a, len_a, a, b, dest]
+    0x20                    // [0x20, a, len_a, len_a, a, b, dest]
+    add                     // [a_data, len_a, len_a, a, b, dest]
+    dup6                    // [dest, a_data, len_a, len_a, a, b, dest]
+    0x20                    // [0x20, dest, a_data, len_a, len_a, a, b, dest]
+    add                     // [dest_data, a_data, len_a, len_a, a, b, dest]
+    MEMCOPY()               // [len_a, a, b, dest]
+    swap1                   // [a, len_a, b, dest]
+    pop                     // [len_a, b, dest]
+    dup3                    // [dest, len_a, b, dest]
+    add                     // [b_ptr, b, dest]
+    0x20                    // [0x20, b_ptr, b, dest]
+    add                     // [b_dest, b, dest]
+    dup2                    // [b, b_dest, b, dest]
+    mload                   // [len_b, b_dest, b, dest]
+    swap2                   // [b, b_dest, len_b, dest]
+    0x20                    // [0x20, b, b_dest, len_b, dest]
+    add                     // [b_data, b_dest, len_b, dest]
+    swap1                   // [b_dest, b_data, len_b, dest]
+    MEMCOPY()               // [dest]
+}
+
+/// ## Bytes Slice Hash
+///
+/// Hashes `len` bytes of the byte array at `ptr` from index `start`, in place.
+///
+/// ### Stack Arguments
+///
+/// - `ptr`: (`stack[0]`) memory pointer of the byte array.
+/// - `start`: (`stack[1]`) index of the first byte of the slice.
+/// - `len`: (`stack[2]`) length of the slice.
+///
+/// ### Panics
+///
+/// - if the slice overflows or exceeds the byte array.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x20 0x00 mstore
+///     0x04 calldataload 0x20 mstore
+///     0x10 0x00 0x00
+///     BYTES_HASH_SLICE()
+///     0x00 mstore
+///     0x20 0x00 return
+/// }
+/// ```
+#define macro BYTES_HASH_SLICE() = takes (3) returns (1) {
+    // takes:               // [ptr, start, len]
+    __CHECK_SLICE()         // [ptr, start, len]
+    add                     // [start_ptr, len]
+    0x20                    // [0x20, start_ptr, len]
+    add                     // [slice_ptr, len]
+    sha3                    // [hash]
+}
+
+/// ## Check Slice
+///
+/// Reverts if a slice of `len` bytes from index `start` overflows or exceeds the byte array at
+/// `ptr`.
+#define macro __CHECK_SLICE() = takes (3) returns (3) {
+    // takes:               // [ptr, start, len]
+    dup3                    // [len, ptr, start, len]
+    dup3                    // [start, len, ptr, start, len]
+    add                     // [end, ptr, start, len]
+    dup1                    // [end, end, ptr, start, len]
+    dup4                    // [start, end, end, ptr, start, len]
+    gt                      // [overflowed, end, ptr, start, len]
+    swap1                   // [end, overflowed, ptr, start, len]
+    dup3                    // [ptr, end, overflowed, ptr, start, len]
+    mload                   // [array_len, end, overflowed, ptr, start, len]
+    lt                      // [exceeds, overflowed, ptr, start, len]
+    or                      // [out_of_bounds, ptr, start, len]
+    iszero                  // [in_bounds, ptr, start, len]
+    in_bounds               // [in_bounds_dest, in_bounds, ptr, start, len]
+    jumpi                   // [ptr, start, len]
+        __ERROR(OutOfBounds) // [err, ptr, start, len]
+        0x00                // [ptr, err, ptr, start, len]
+        mstore              // [ptr, start, len]
+        0x04                // [err_len, ptr, start, len]
+        0x00                // [ptr, err_len, ptr, start, len]
+        revert              // []
+    in_bounds:              // [ptr, start, len]
+}
+"#;
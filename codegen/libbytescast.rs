@@ -0,0 +1,158 @@
+use crate::emit::{conditions, line};
+use crate::templates::{self, Context};
+
+/// Renders `libbytescast.huff` with the casts of the bit `sizes`, `bytes1` for 8 bits.
+pub fn render(sizes: &[u16]) -> String {
+    let casts = sizes
+        .iter()
+        .map(|size| generate_cast((size / 8) as u8))
+        .collect::<String>();
+
+    format!("{}{}{}", HEADER, ERROR_DEFINITION, casts)
+}
+
+/// Builds the hex literal of the left aligned mask of `bytes` bytes.
+fn mask(bytes: u8) -> String {
+    format!(
+        "0x{}{}",
+        "ff".repeat(bytes as usize),
+        "00".repeat(32 - bytes as usize)
+    )
+}
+
+fn generate_cast(bytes: u8) -> String {
+    let name = format!("BYTES{}", bytes);
+    let mask = mask(bytes);
+    // shifting out the bytes of the type leaves the bytes below it
+    let shift = format!("{:#04x}", 8 * bytes as u16);
+
+    let mask_conditions = conditions(&[], &[&format!("result == {}", mask)]);
+    let cast_conditions = conditions(&[], &[&format!("result == value & {}", mask)]);
+    let safe_conditions = conditions(&[&format!("value & ~{} == 0", mask)], &["result == value"]);
+
+    let context = Context::new()
+        .text("mask_conditions", mask_conditions)
+        .text("cast_conditions", cast_conditions)
+        .text("safe_conditions", safe_conditions)
+        // every word is a bytes32, so the widest casts are no-ops
+        .flag("truncates", bytes < 32)
+        .text("shift_line", line(&shift, "[shift, value, value]"))
+        .text(
+            "mask_line",
+            line(&format!("{}_MASK()", name), "[mask, value]"),
+        )
+        .text("name", name)
+        .text("size", bytes.to_string())
+        .text("mask", mask);
+    templates::render(CAST_TEMPLATE, &context)
+}
+
+const HEADER: &str = r#"
+//  ------------------------------------------------------------------------------------------------
+//! # Bytes Casting Library
+//!
+//! Provides macros for casting values to fixed size byte arrays.
+//!
+//! Sizes supported range from 1 to 32 bytes inclusive. Like Solidity's `bytesN`, values are left
+//! aligned, occupying the high bytes of the word, so casts mask from the high end of the word
+//! rather than the low end as the masks of `libcast.huff` do.
+//!
+//! Items prefixed with `SAFE_` will revert if any byte below the type is set.
+//!
+//! ## API
+//!
+//! For a given type, `BYTESNAME`:
+//!
+//! - `BYTESNAME_MASK` - Left aligned mask of the size of the type.
+//! - `TO_BYTESNAME` - Casts a value to the type, clearing the bytes below it.
+//! - `SAFE_TO_BYTESNAME` - Casts a value to the type, reverting if any byte below it is set.
+"#;
+
+const ERROR_DEFINITION: &str = r#"
+/// ## Dirty Bytes Error
+///
+/// Thrown when a value has bytes set below its fixed size byte array type.
+#define error DirtyBytes()
+"#;
+
+const CAST_TEMPLATE: &str = r#"
+/// ## {{name}} Mask
+///
+/// Used to cast a value to `bytes{{size}}`.
+///
+/// ### Conditions
+///
+{{mask_conditions}}///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x00 calldataload
+///     {{name}}_MASK() and
+/// }
+/// ```
+#define macro {{name}}_MASK() = takes (0) returns (1) { {{mask}} }
+
+/// ## {{name}} Cast
+///
+/// Casts a value to `bytes{{size}}`, keeping its high {{size}} bytes.
+///
+/// ### Conditions
+///
+{{cast_conditions}}#define macro TO_{{name}}() = takes (1) returns (1) {
+    // takes:               // [value]
+{{#if truncates}}{{mask_line}}    and                     // [truncated]
+{{/if}}}
+
+/// ## Safe {{name}} Cast
+///
+/// Casts a value to `bytes{{size}}`, as when validating ABI encoded calldata.
+///
+/// ### Panics
+///
+/// - if any byte below the high {{size}} bytes of the value is set.
+///
+/// ### Conditions
+///
+{{safe_conditions}}///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x04 calldataload
+///     SAFE_TO_{{name}}()
+///     0x00 sstore
+/// }
+/// ```
+#define macro SAFE_TO_{{name}}() = takes (1) returns (1) {
+    // takes:               // [value]
+{{#if truncates}}    dup1                    // [value, value]
+{{shift_line}}    shl                     // [low_bytes, value]
+    iszero                  // [is_clean, value]
+    is_clean                // [is_clean_dest, is_clean, value]
+    jumpi                   // [value]
+        __ERROR(DirtyBytes) // [err]
+        0x00                // [ptr, err]
+        mstore              // []
+        0x04                // [err_len]
+        0x00                // [ptr, err_len]
+        revert              // []
+    is_clean:               // [value]
+{{/if}}}
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shifts_out_the_type() {
+        assert_eq!(mask(2), format!("0xffff{}", "00".repeat(30)));
+        let shift = |shift: &str| line(shift, "[shift, value, value]");
+        assert!(generate_cast(1).contains(&shift("0x08")));
+        assert!(generate_cast(31).contains(&shift("0xf8")));
+        // `bytes32` takes the whole word, so neither cast has code
+        let widest = generate_cast(32);
+        assert!(!widest.contains("shl") && !widest.contains("BYTES32_MASK()           //"));
+    }
+}
@@ -0,0 +1,506 @@
+use crate::emit::line;
+use crate::templates::{self, Context};
+
+/// Renders `libcall.huff`, with the static calls returning a value checked by the casts of
+/// `libcast.huff` for each of `sizes` but 256 bits.
+pub fn render(sizes: &[u16]) -> String {
+    let casts = sizes
+        .iter()
+        .filter(|size| **size < 256)
+        .map(|size| {
+            let context = Context::new()
+                .text("cast_line", line(&format!("TO_U{}()", size), "[value]"))
+                .text("size", size.to_string());
+            templates::render(STATICCALL_CAST_TEMPLATE, &context)
+        })
+        .collect::<String>();
+
+    format!(
+        "{}{}{}{}{}",
+        HEADER, GAS_DEFINITION, STATICCALL_DEFINITION, casts, BATCH_DEFINITION
+    )
+}
+
+const HEADER: &str = r#"
+//  ------------------------------------------------------------------------------------------------
+//! # Call Library
+//!
+//! Provides external call wrappers.
+//!
+//! A call forwards at most 63/64 of the remaining gas (EIP-150), silently capping any larger gas
+//! argument. The gas limited wrappers account for this, so a callee can not be starved of the gas
+//! it was meant to receive, and the caller keeps the gas it needs after the call.
+//!
+//! ## API
+//!
+//! - `CALL_WITH_GAS_CAP` - Calls a contract with exactly a given amount of gas.
+//! - `CALL_RESERVE_GAS` - Calls a contract with all gas but a reserve.
+//! - `STATICCALL_RETURN_WORD` - Static calls a function, returning the word it returns.
+//! - `STATICCALL_ARG_RETURN_WORD` - Static calls a function with an argument, returning the word
+//!   it returns.
+//! - `STATICCALL_RETURN_UN` - Static calls a function, returning the `uintN` it returns.
+//! - `STATICCALL_ARG_RETURN_UN` - Static calls a function with an argument, returning the `uintN`
+//!   it returns.
+//! - `BATCH_CALL_ALL_OR_NOTHING` - Calls a batch of contracts, reverting if any call fails.
+//! - `BATCH_CALL_BEST_EFFORT` - Calls a batch of contracts, recording the success of each call.
+//!
+//! ## Batches
+//!
+//! A batch is an in-memory array of pointers to its calls, and each call is the address to call
+//! followed by its calldata as `bytes`:
+//!
+//! | Offset | Batch | Call |
+//! | ------ | ----- | ---- |
+//! | `0x00` | `count` | `target` |
+//! | `0x20` | `call_ptr_0` | `len` |
+//! | `0x40` | `call_ptr_1` | `data` |
+//! | ... | ... | ... |
+
+#include "libcast.huff"
+#include "librevert.huff"
+
+/// ## Insufficient Gas Error
+///
+/// Thrown when too little gas remains to give a call its gas, or keep a reserve.
+#define error InsufficientGas()
+
+/// ## Invalid Return Data Error
+///
+/// Thrown when a call returns less data than expected.
+#define error InvalidReturnData()
+"#;
+
+const GAS_DEFINITION: &str = r#"
+/// ## Call With Gas Cap
+///
+/// Calls `target` with `gas`, reverting if the 63/64 rule may have given the callee less.
+///
+/// A callee given less than `gas` leaves at most `gas / 63` to the caller when it runs out of gas,
+/// so the call reverts unless more than `gas / 63` remains after it.
+///
+/// ### Template Arguments
+///
+/// - `target`: address to call.
+/// - `gas`: gas to give the callee.
+///
+/// ### Stack Arguments
+///
+/// - `value`: (`stack[0]`) wei to send.
+/// - `args_ptr`: (`stack[1]`) memory pointer of the calldata.
+/// - `args_len`: (`stack[2]`) length of the calldata.
+/// - `ret_ptr`: (`stack[3]`) memory pointer to copy the returndata to.
+/// - `ret_len`: (`stack[4]`) length of the returndata to copy.
+///
+/// ### Panics
+///
+/// - if the callee may have received less than `gas`.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x00 0x00 0x00 0x00 0x00
+///     CALL_WITH_GAS_CAP(0x3a11e7, 0x7530)
+///     0x00 mstore
+///     0x20 0x00 return
+/// }
+/// ```
+#define macro CALL_WITH_GAS_CAP(target, gas) = takes (5) returns (1) {
+    // takes:               // [value, args_ptr, args_len, ret_ptr, ret_len]
+    <target>                // [target, value, args_ptr, args_len, ret_ptr, ret_len]
+    <gas>                   // [gas, target, value, args_ptr, args_len, ret_ptr, ret_len]
+    call                    // [success]
+    0x3f                    // [0x3f, success]
+    <gas>                   // [gas, 0x3f, success]
+    div                     // [starved_left, success]
+    gas                     // [gas_left, starved_left, success]
+    gt                      // [was_given_gas, success]
+    was_given_gas           // [was_given_gas_dest, was_given_gas, success]
+    jumpi                   // [success]
+        __ERROR(InsufficientGas) // [err, success]
+        0x00                // [ptr, err, success]
+        mstore              // [success]
+        0x04                // [err_len, success]
+        0x00                // [ptr, err_len, success]
+        revert              // []
+    was_given_gas:          // [success]
+}
+
+/// ## Call Reserve Gas
+///
+/// Calls `target` with all remaining gas but `reserve`, so at least `reserve` remains after the
+/// call, less the cost of the call itself.
+///
+/// The cost of the call itself is charged to the caller after the reserve is set aside: up to
+/// `2600` to access a cold target, `9000` to send value, and `25000` to send value to an empty
+/// account. Include the costs that apply in `reserve`.
+///
+/// ### Template Arguments
+///
+/// - `reserve`: gas to keep after the call.
+///
+/// ### Stack Arguments
+///
+/// - `target`: (`stack[0]`) address to call.
+/// - `value`: (`stack[1]`) wei to send.
+/// - `args_ptr`: (`stack[2]`) memory pointer of the calldata.
+/// - `args_len`: (`stack[3]`) length of the calldata.
+/// - `ret_ptr`: (`stack[4]`) memory pointer to copy the returndata to.
+/// - `ret_len`: (`stack[5]`) length of the returndata to copy.
+///
+/// ### Panics
+///
+/// - if less than `reserve` gas remains.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x00 0x00 0x00 0x00 0x00 0x3a11e7
+///     CALL_RESERVE_GAS(0x2710)
+///     0x00 mstore
+///     0x20 0x00 return
+/// }
+/// ```
+#define macro CALL_RESERVE_GAS(reserve) = takes (6) returns (1) {
+    // takes:               // [target, value, args_ptr, args_len, ret_ptr, ret_len]
+    gas                     // [gas_left, target, value, args_ptr, args_len, ret_ptr, ret_len]
+    <reserve>               // [reserve, gas_left, target, value, args_ptr, args_len, ret_ptr, ret_len]
+    dup2                    // [gas_left, reserve, gas_left, target, value, args_ptr, args_len, ret_ptr, ret_len]
+    dup2                    // [reserve, gas_left, reserve, gas_left, target, value, args_ptr, args_len, ret_ptr, ret_len]
+    gt                      // [is_short, reserve, gas_left, target, value, args_ptr, args_len, ret_ptr, ret_len]
+    iszero                  // [is_sufficient, reserve, gas_left, target, value, args_ptr, args_len, ret_ptr, ret_len]
+    is_sufficient           // [is_sufficient_dest, is_sufficient, reserve, gas_left, target, value, args_ptr, args_len, ret_ptr, ret_len]
+    jumpi                   // [reserve, gas_left, target, value, args_ptr, args_len, ret_ptr, ret_len]
+        __ERROR(InsufficientGas) // [err, reserve, gas_left, target, value, args_ptr, args_len, ret_ptr, ret_len]
+        0x00                // [ptr, err, reserve, gas_left, target, value, args_ptr, args_len, ret_ptr, ret_len]
+        mstore              // [reserve, gas_left, target, value, args_ptr, args_len, ret_ptr, ret_len]
+        0x04                // [err_len, reserve, gas_left, target, value, args_ptr, args_len, ret_ptr, ret_len]
+        0x00                // [ptr, err_len, reserve, gas_left, target, value, args_ptr, args_len, ret_ptr, ret_len]
+        revert              // []
+    is_sufficient:          // [reserve, gas_left, target, value, args_ptr, args_len, ret_ptr, ret_len]
+        swap1               // [gas_left, reserve, target, value, args_ptr, args_len, ret_ptr, ret_len]
+        sub                 // [forwarded, target, value, args_ptr, args_len, ret_ptr, ret_len]
+        call                // [success]
+}
+"#;
+
+const STATICCALL_DEFINITION: &str = r#"
+/// ## Staticcall Return Word
+///
+/// Static calls the function `selector` of `target` without arguments, returning the word it
+/// returns. Reverts with the revert data of the call if it fails.
+///
+/// > WARNING: Memory from `0x00` to `0x20` will be overwritten.
+///
+/// ### Template Arguments
+///
+/// - `target`: address to call.
+/// - `selector`: selector of the function.
+///
+/// ### Panics
+///
+/// - if the call fails.
+/// - if the call returns less than a word.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     // totalSupply()
+///     STATICCALL_RETURN_WORD(0x3a11e7, 0x18160ddd)
+///     0x00 mstore
+///     0x20 0x00 return
+/// }
+/// ```
+#define macro STATICCALL_RETURN_WORD(target, selector) = takes (0) returns (1) {
+    <selector>              // [selector]
+    0x00                    // [ptr, selector]
+    mstore                  // []
+    0x20                    // [ret_len]
+    0x00                    // [ret_ptr, ret_len]
+    0x04                    // [args_len, ret_ptr, ret_len]
+    0x1c                    // [args_ptr, args_len, ret_ptr, ret_len]
+    <target>                // [target, args_ptr, args_len, ret_ptr, ret_len]
+    gas                     // [gas, target, args_ptr, args_len, ret_ptr, ret_len]
+    staticcall              // [success]
+    __STATICCALL_RETURNED_WORD() // [word]
+}
+
+/// ## Staticcall Arg Return Word
+///
+/// Static calls the function `selector` of `target` with `arg`, returning the word it returns.
+/// Reverts with the revert data of the call if it fails.
+///
+/// > WARNING: Memory from `0x00` to `0x40` will be overwritten.
+///
+/// ### Template Arguments
+///
+/// - `target`: address to call.
+/// - `selector`: selector of the function.
+///
+/// ### Stack Arguments
+///
+/// - `arg`: (`stack[0]`) argument of the function.
+///
+/// ### Panics
+///
+/// - if the call fails.
+/// - if the call returns less than a word.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     // balanceOf(address)
+///     caller
+///     STATICCALL_ARG_RETURN_WORD(0x3a11e7, 0x70a08231)
+///     0x00 mstore
+///     0x20 0x00 return
+/// }
+/// ```
+#define macro STATICCALL_ARG_RETURN_WORD(target, selector) = takes (1) returns (1) {
+    // takes:               // [arg]
+    0x20                    // [arg_ptr, arg]
+    mstore                  // []
+    <selector>              // [selector]
+    0x00                    // [ptr, selector]
+    mstore                  // []
+    0x20                    // [ret_len]
+    0x00                    // [ret_ptr, ret_len]
+    0x24                    // [args_len, ret_ptr, ret_len]
+    0x1c                    // [args_ptr, args_len, ret_ptr, ret_len]
+    <target>                // [target, args_ptr, args_len, ret_ptr, ret_len]
+    gas                     // [gas, target, args_ptr, args_len, ret_ptr, ret_len]
+    staticcall              // [success]
+    __STATICCALL_RETURNED_WORD() // [word]
+}
+
+/// ## Staticcall Returned Word
+///
+/// Bubbles the revert of a failed call, or loads the word it returned to `0x00`.
+#define macro __STATICCALL_RETURNED_WORD() = takes (1) returns (1) {
+    // takes:               // [success]
+    succeeded               // [succeeded_dest, success]
+    jumpi                   // []
+        BUBBLE_REVERT()     // []
+    succeeded:              // []
+        0x20                // [0x20]
+        returndatasize      // [returndatasize, 0x20]
+        lt                  // [is_short]
+        iszero              // [has_word]
+        has_word            // [has_word_dest, has_word]
+        jumpi               // []
+            __ERROR(InvalidReturnData) // [err]
+            0x00            // [ptr, err]
+            mstore          // []
+            0x04            // [err_len]
+            0x00            // [ptr, err_len]
+            revert          // []
+    has_word:               // []
+        0x00                // [ptr]
+        mload               // [word]
+}
+"#;
+
+const STATICCALL_CAST_TEMPLATE: &str = r#"
+/// ## Staticcall Return U{{size}}
+///
+/// Static calls the function `selector` of `target` without arguments, returning the `uint{{size}}`
+/// it returns.
+///
+/// > WARNING: Memory from `0x00` to `0x20` will be overwritten.
+///
+/// ### Panics
+///
+/// - if the call fails.
+/// - if the call returns less than a word, or a word overflowing `uint{{size}}`.
+#define macro STATICCALL_RETURN_U{{size}}(target, selector) = takes (0) returns (1) {
+    STATICCALL_RETURN_WORD(<target>, <selector>) // [word]
+{{cast_line}}}
+
+/// ## Staticcall Arg Return U{{size}}
+///
+/// Static calls the function `selector` of `target` with `arg`, returning the `uint{{size}}` it
+/// returns.
+///
+/// > WARNING: Memory from `0x00` to `0x40` will be overwritten.
+///
+/// ### Panics
+///
+/// - if the call fails.
+/// - if the call returns less than a word, or a word overflowing `uint{{size}}`.
+#define macro STATICCALL_ARG_RETURN_U{{size}}(target, selector) = takes (1) returns (1) {
+    // takes:               // [arg]
+    STATICCALL_ARG_RETURN_WORD(<target>, <selector>) // [word]
+{{cast_line}}}
+"#;
+
+const BATCH_DEFINITION: &str = r#"
+/// ## Batch Call All Or Nothing
+///
+/// Calls each call of the batch at `batch_ptr` in order, reverting with the revert data of the
+/// first call to fail.
+///
+/// ### Stack Arguments
+///
+/// - `batch_ptr`: (`stack[0]`) memory pointer of the batch.
+///
+/// ### Panics
+///
+/// - if any call fails.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x01 0x00 mstore        // count
+///     0x40 0x20 mstore        // call_ptr_0
+///     0x3a11e7 0x40 mstore    // target
+///     0x00 0x60 mstore        // len
+///     0x00
+///     BATCH_CALL_ALL_OR_NOTHING()
+///     stop
+/// }
+/// ```
+#define macro BATCH_CALL_ALL_OR_NOTHING() = takes (1) returns (0) {
+    // takes:               // [batch_ptr]
+    dup1                    // [batch_ptr, batch_ptr]
+    mload                   // [count, batch_ptr]
+    0x00                    // [i, count, batch_ptr]
+    loop:                   // [i, count, batch_ptr]
+        dup2                // [count, i, count, batch_ptr]
+        dup2                // [i, count, i, count, batch_ptr]
+        lt                  // [is_pending, i, count, batch_ptr]
+        iszero              // [is_done, i, count, batch_ptr]
+        done                // [done_dest, is_done, i, count, batch_ptr]
+        jumpi               // [i, count, batch_ptr]
+        dup1                // [i, i, count, batch_ptr]
+        0x05                // [0x05, i, i, count, batch_ptr]
+        shl                 // [rel_ptr, i, count, batch_ptr]
+        0x20                // [0x20, rel_ptr, i, count, batch_ptr]
+        add                 // [rel_ptr, i, count, batch_ptr]
+        dup4                // [batch_ptr, rel_ptr, i, count, batch_ptr]
+        add                 // [call_ptr_ptr, i, count, batch_ptr]
+        mload               // [call_ptr, i, count, batch_ptr]
+        __BATCH_CALL()      // [success, i, count, batch_ptr]
+        succeeded           // [succeeded_dest, success, i, count, batch_ptr]
+        jumpi               // [i, count, batch_ptr]
+            BUBBLE_REVERT() // []
+        succeeded:          // [i, count, batch_ptr]
+        0x01                // [0x01, i, count, batch_ptr]
+        add                 // [i, count, batch_ptr]
+        loop                // [loop_dest, i, count, batch_ptr]
+        jump                // [i, count, batch_ptr]
+    done:                   // [i, count, batch_ptr]
+        pop                 // [count, batch_ptr]
+        pop                 // [batch_ptr]
+        pop                 // []
+}
+
+/// ## Batch Call Best Effort
+///
+/// Calls each call of the batch at `batch_ptr` in order, writing whether each call succeeded to
+/// an in-memory array at `results_ptr`.
+///
+/// > WARNING: Memory from `results_ptr` to `results_ptr + 0x20 + count * 0x20` will be
+/// > overwritten.
+///
+/// ### Stack Arguments
+///
+/// - `batch_ptr`: (`stack[0]`) memory pointer of the batch.
+/// - `results_ptr`: (`stack[1]`) memory pointer to write the results to.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x01 0x00 mstore        // count
+///     0x40 0x20 mstore        // call_ptr_0
+///     0x3a11e7 0x40 mstore    // target
+///     0x00 0x60 mstore        // len
+///     0x80 0x00
+///     BATCH_CALL_BEST_EFFORT()
+///     0x40 0x80 return
+/// }
+/// ```
+#define macro BATCH_CALL_BEST_EFFORT() = takes (2) returns (0) {
+    // takes:               // [batch_ptr, results_ptr]
+    dup1                    // [batch_ptr, batch_ptr, results_ptr]
+    mload                   // [count, batch_ptr, results_ptr]
+    dup1                    // [count, count, batch_ptr, results_ptr]
+    dup4                    // [results_ptr, count, count, batch_ptr, results_ptr]
+    mstore                  // [count, batch_ptr, results_ptr]
+    0x00                    // [i, count, batch_ptr, results_ptr]
+    loop:                   // [i, count, batch_ptr, results_ptr]
+        dup2                // [count, i, count, batch_ptr, results_ptr]
+        dup2                // [i, count, i, count, batch_ptr, results_ptr]
+        lt                  // [is_pending, i, count, batch_ptr, results_ptr]
+        iszero              // [is_done, i, count, batch_ptr, results_ptr]
+        done                // [done_dest, is_done, i, count, batch_ptr, results_ptr]
+        jumpi               // [i, count, batch_ptr, results_ptr]
+        dup1                // [i, i, count, batch_ptr, results_ptr]
+        0x05                // [0x05, i, i, count, batch_ptr, results_ptr]
+        shl                 // [rel_ptr, i, count, batch_ptr, results_ptr]
+        0x20                // [0x20, rel_ptr, i, count, batch_ptr, results_ptr]
+        add                 // [rel_ptr, i, count, batch_ptr, results_ptr]
+        dup1                // [rel_ptr, rel_ptr, i, count, batch_ptr, results_ptr]
+        dup5                // [batch_ptr, rel_ptr, rel_ptr, i, count, batch_ptr, results_ptr]
+        add                 // [call_ptr_ptr, rel_ptr, i, count, batch_ptr, results_ptr]
+        mload               // [call_ptr, rel_ptr, i, count, batch_ptr, results_ptr]
+        __BATCH_CALL()      // [success, rel_ptr, i, count, batch_ptr, results_ptr]
+        swap1               // [rel_ptr, success, i, count, batch_ptr, results_ptr]
+        dup6                // [results_ptr, rel_ptr, success, i, count, batch_ptr, results_ptr]
+        add                 // [result_ptr, success, i, count, batch_ptr, results_ptr]
+        mstore              // [i, count, batch_ptr, results_ptr]
+        0x01                // [0x01, i, count, batch_ptr, results_ptr]
+        add                 // [i, count, batch_ptr, results_ptr]
+        loop                // [loop_dest, i, count, batch_ptr, results_ptr]
+        jump                // [i, count, batch_ptr, results_ptr]
+    done:                   // [i, count, batch_ptr, results_ptr]
+        pop                 // [count, batch_ptr, results_ptr]
+        pop                 // [batch_ptr, results_ptr]
+        pop                 // [results_ptr]
+        pop                 // []
+}
+
+/// ## Batch Call
+///
+/// Makes a call of a batch with all remaining gas, ignoring its returndata.
+#define macro __BATCH_CALL() = takes (1) returns (1) {
+    // takes:               // [call_ptr]
+    0x00                    // [ret_len, call_ptr]
+    0x00                    // [ret_ptr, ret_len, call_ptr]
+    dup3                    // [call_ptr, ret_ptr, ret_len, call_ptr]
+    0x20                    // [0x20, call_ptr, ret_ptr, ret_len, call_ptr]
+    add                     // [len_ptr, ret_ptr, ret_len, call_ptr]
+    mload                   // [args_len, ret_ptr, ret_len, call_ptr]
+    dup4                    // [call_ptr, args_len, ret_ptr, ret_len, call_ptr]
+    0x40                    // [0x40, call_ptr, args_len, ret_ptr, ret_len, call_ptr]
+    add                     // [args_ptr, args_len, ret_ptr, ret_len, call_ptr]
+    0x00                    // [value, args_ptr, args_len, ret_ptr, ret_len, call_ptr]
+    dup6                    // [call_ptr, value, args_ptr, args_len, ret_ptr, ret_len, call_ptr]
+    mload                   // [target, value, args_ptr, args_len, ret_ptr, ret_len, call_ptr]
+    gas                     // [gas, target, value, args_ptr, args_len, ret_ptr, ret_len, call_ptr]
+    call                    // [success, call_ptr]
+    swap1                   // [call_ptr, success]
+    pop                     // [success]
+}
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn casts_of_each_size() {
+        let rendered = render(&[8, 256]);
+        assert!(rendered.contains("#define macro STATICCALL_RETURN_U8(target, selector)"));
+        assert!(rendered.contains("#define macro STATICCALL_ARG_RETURN_U8(target, selector)"));
+        assert!(rendered.contains("    TO_U8()                 // [value]\n"));
+        // the word wrappers return a `uint256` unchecked
+        assert!(!rendered.contains("STATICCALL_RETURN_U256"));
+        assert!(!rendered.contains("STATICCALL_RETURN_U16"));
+        assert!(rendered.ends_with("    pop                     // [success]\n}\n"));
+    }
+}
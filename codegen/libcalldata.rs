@@ -0,0 +1,182 @@
+use crate::emit::{conditions, line};
+use crate::libcast::mask;
+use crate::templates::{self, Context};
+
+/// Renders `libcalldata.huff` with the loads of the bit `sizes`.
+pub fn render(sizes: &[u16]) -> String {
+    let loads = sizes
+        .iter()
+        .map(|size| generate_load(&format!("U{}", size), &format!("uint{}", size), *size))
+        .collect::<String>();
+
+    format!(
+        "{}{}{}{}{}",
+        HEADER,
+        ERROR_DEFINITION,
+        generate_load("ADDRESS", "address", 160),
+        BOOL_DEFINITION,
+        loads,
+    )
+}
+
+fn generate_load(name: &str, solidity_type: &str, size: u16) -> String {
+    let mask = mask(size);
+
+    let load_conditions = conditions(
+        &[
+            "offset + 32 <= calldatasize",
+            &format!("calldataload(offset) <= {}", mask),
+        ],
+        &["result == calldataload(offset)"],
+    );
+
+    let context = Context::new()
+        .text("load_conditions", load_conditions)
+        // every word is a uint256, so the widest loads only check bounds
+        .flag("checks", size < 256)
+        .text(
+            "shift_line",
+            line(&format!("{:#04x}", size), "[shift, value, value]"),
+        )
+        .text("name", name)
+        .text("solidity_type", solidity_type);
+    templates::render(LOAD_TEMPLATE, &context)
+}
+
+const HEADER: &str = r#"
+//  ------------------------------------------------------------------------------------------------
+//! # Calldata Library
+//!
+//! Provides checked loads of ABI encoded calldata parameters.
+//!
+//! Each load reads the word at a calldata offset, reverting with `OutOfBounds` if the word extends
+//! past the end of calldata, and with `DirtyBits` if bits above the width of the declared type are
+//! set, as Solidity's ABI decoder does. Bit sizes supported range from 8 to 256 inclusive and are
+//! multiples of 8.
+//!
+//! ## API
+//!
+//! - `LOAD_ADDRESS` - Loads an `address`.
+//! - `LOAD_BOOL` - Loads a `bool`.
+//!
+//! For a given type, `TYPENAME`:
+//!
+//! - `LOAD_TYPENAME` - Loads a value of the type.
+
+#include "libkeccak.huff"
+"#;
+
+const ERROR_DEFINITION: &str = r#"
+/// ## Dirty Bits Error
+///
+/// Thrown when a calldata word has bits set above the width of its type.
+#define error DirtyBits()
+"#;
+
+const BOOL_DEFINITION: &str = r#"
+/// ## Load Bool
+///
+/// Loads the `bool` at a calldata offset.
+///
+/// ### Template Arguments
+///
+/// - `offset` - Calldata offset of the word.
+///
+/// ### Panics
+///
+/// - if the word extends past the end of calldata.
+/// - if the word is neither zero nor one.
+///
+/// ### Conditions
+///
+/// @pre offset + 32 <= calldatasize
+/// @pre calldataload(offset) <= 1
+/// @post result == calldataload(offset)
+#define macro LOAD_BOOL(offset) = takes (0) returns (1) {
+    <offset>                // [offset]
+    dup1                    // [offset, offset]
+    0x20                    // [0x20, offset, offset]
+    add                     // [end, offset]
+    __CHECK_CALLDATA_RANGE() // [offset]
+    calldataload            // [value]
+    dup1                    // [value, value]
+    0x02                    // [two, value, value]
+    gt                      // [is_clean, value]
+    is_clean                // [is_clean_dest, is_clean, value]
+    jumpi                   // [value]
+        __ERROR(DirtyBits)  // [err]
+        0x00                // [ptr, err]
+        mstore              // []
+        0x04                // [err_len]
+        0x00                // [ptr, err_len]
+        revert              // []
+    is_clean:               // [value]
+}
+"#;
+
+const LOAD_TEMPLATE: &str = r#"
+/// ## Load {{name}}
+///
+/// Loads the `{{solidity_type}}` at a calldata offset.
+///
+/// ### Template Arguments
+///
+/// - `offset` - Calldata offset of the word.
+///
+/// ### Panics
+///
+/// - if the word extends past the end of calldata.
+/// - if any bit of the word above the width of the type is set.
+///
+/// ### Conditions
+///
+{{load_conditions}}///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     LOAD_{{name}}(0x04)
+///     0x00 sstore
+/// }
+/// ```
+#define macro LOAD_{{name}}(offset) = takes (0) returns (1) {
+    <offset>                // [offset]
+    dup1                    // [offset, offset]
+    0x20                    // [0x20, offset, offset]
+    add                     // [end, offset]
+    __CHECK_CALLDATA_RANGE() // [offset]
+    calldataload            // [value]
+{{#if checks}}    dup1                    // [value, value]
+{{shift_line}}    shr                     // [high_bits, value]
+    iszero                  // [is_clean, value]
+    is_clean                // [is_clean_dest, is_clean, value]
+    jumpi                   // [value]
+        __ERROR(DirtyBits)  // [err]
+        0x00                // [ptr, err]
+        mstore              // []
+        0x04                // [err_len]
+        0x00                // [ptr, err_len]
+        revert              // []
+    is_clean:               // [value]
+{{/if}}}
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn loads_of_each_size() {
+        let rendered = render(&[8, 256]);
+        for name in ["ADDRESS", "BOOL", "U8", "U256"] {
+            let definition = format!("#define macro LOAD_{}(offset)", name);
+            assert!(rendered.contains(&definition));
+        }
+        assert!(!rendered.contains("LOAD_U16"));
+
+        // the bits above the type are shifted down to be checked, but a `uint256` has none
+        let address = generate_load("ADDRESS", "address", 160);
+        assert!(address.contains(&line("0xa0", "[shift, value, value]")));
+        assert!(!generate_load("U256", "uint256", 256).contains("DirtyBits"));
+    }
+}
@@ -0,0 +1,524 @@
+use crate::emit::conditions;
+use crate::ir::{Definition, Expr};
+use crate::lower;
+use crate::optimize::{mask_encoding, Objective};
+use crate::templates::{self, Context};
+use crate::yul;
+
+/// Widths of the casts, in bits.
+pub const INT_SIZES: [u16; 32] = [
+    8, 16, 24, 32, 40, 48, 56, 64, 72, 80, 88, 96, 104, 112, 120, 128, 136, 144, 152, 160, 168,
+    176, 184, 192, 200, 208, 216, 224, 232, 240, 248, 256,
+];
+
+/// How checked casts revert when the value does not fit in the type.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OverflowRevert {
+    /// The `Overflow()` custom error.
+    #[default]
+    Error,
+    /// Solidity's `Panic(uint256)` with the arithmetic overflow code, `0x11`, as checked
+    /// arithmetic reverts.
+    Panic,
+}
+
+impl OverflowRevert {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "error" => Some(OverflowRevert::Error),
+            "panic" => Some(OverflowRevert::Panic),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            OverflowRevert::Error => "error",
+            OverflowRevert::Panic => "panic",
+        }
+    }
+
+    /// The revert path of a checked cast, indented below its jump.
+    pub fn body(self) -> &'static str {
+        match self {
+            OverflowRevert::Error => ERROR_REVERT,
+            OverflowRevert::Panic => PANIC_REVERT,
+        }
+    }
+}
+
+/// Renders `libcast.huff` with the casts of the bit `sizes`, reverting on overflow as `revert`.
+///
+/// `push0` prices the mask encodings for targets with `PUSH0`, available from Shanghai.
+pub fn render(sizes: &[u16], revert: OverflowRevert, push0: bool) -> String {
+    let note = match revert {
+        OverflowRevert::Error => "",
+        OverflowRevert::Panic => PANIC_NOTE,
+    };
+
+    let address_mask = mask(160);
+    let address_mask_conditions = conditions(&[], &[&format!("result == {}", address_mask)]);
+    let [address, unsafe_address, bool] = other_definitions();
+    let address_masks = |lit: &str| (lit == address_mask).then(|| "ADDRESS_MASK()".to_string());
+    let body = |definition: &Definition, masks: &dyn Fn(&str) -> Option<String>| {
+        lower::body(definition, masks, revert.body())
+    };
+
+    let casts = sizes
+        .iter()
+        .map(|size| cast_context(*size, revert, push0))
+        .collect();
+    let context = Context::new()
+        .text("revert_note", note)
+        .list("casts", casts)
+        .text(
+            "address_mask_code",
+            mask_encoding(160, Objective::Gas, push0).code,
+        )
+        .text("address_mask_conditions", address_mask_conditions)
+        .text("address_conditions", address.conditions())
+        .text("address_body", body(&address, &address_masks))
+        .text("unsafe_address_conditions", unsafe_address.conditions())
+        .text("unsafe_address_body", body(&unsafe_address, &address_masks))
+        .text("bool_conditions", bool.conditions())
+        .text("bool_body", body(&bool, &|_| None));
+
+    templates::render(LIBRARY_TEMPLATE, &context)
+}
+
+/// Renders `libcast.yul`, the Yul functions of the casts of `libcast.huff` of the bit `sizes`.
+pub fn render_yul(sizes: &[u16], revert: OverflowRevert) -> String {
+    yul::render(YUL_DOC, &definitions(sizes), revert)
+}
+
+/// The casts of `libcast.huff` of the bit `sizes` that are not Huff specific, for the Huff
+/// macros and the Yul functions alike: the checked, unchecked and try casts of each size, the casts
+/// of several values, and then the address and bool casts.
+fn definitions(sizes: &[u16]) -> Vec<Definition> {
+    let mut definitions = sizes
+        .iter()
+        .flat_map(|size| size_definitions(*size))
+        .collect::<Vec<_>>();
+    definitions.extend(other_definitions());
+    definitions
+}
+
+/// The unsigned casts of the bit `size`.
+fn size_definitions(size: u16) -> [Definition; 5] {
+    let (name, mask) = (format!("U{}", size), || Expr::lit(mask(size)));
+    let value = || Expr::Arg("value");
+    // checked casts leave their arguments, the top named the result
+    let cast = |name: String, args: Vec<&'static str>| {
+        let checks = args.iter().map(|arg| Expr::Arg(arg).le(mask())).collect();
+        let results = args.iter().enumerate().map(|(i, arg)| match i {
+            0 => ("result", Expr::Arg(arg)),
+            _ => (*arg, Expr::Arg(arg)),
+        });
+        Definition {
+            name,
+            checks,
+            results: results.collect(),
+            args,
+        }
+    };
+
+    [
+        cast(format!("TO_{}", name), vec!["value"]),
+        Definition {
+            name: format!("UNSAFE_TO_{}", name),
+            args: vec!["value"],
+            checks: Vec::new(),
+            results: vec![("result", value().and(mask()))],
+        },
+        Definition {
+            name: format!("TRY_TO_{}", name),
+            args: vec!["value"],
+            checks: Vec::new(),
+            results: vec![
+                ("result", value().le(mask())),
+                ("masked_value", value().and(mask())),
+            ],
+        },
+        cast(format!("TO_{}_2", name), vec!["a", "b"]),
+        cast(format!("TO_{}_3", name), vec!["a", "b", "c"]),
+    ]
+}
+
+/// The checked and unchecked address casts and the bool cast.
+fn other_definitions() -> [Definition; 3] {
+    let (value, address_mask) = (|| Expr::Arg("value"), || Expr::lit(mask(160)));
+
+    [
+        Definition {
+            name: "TO_ADDRESS".to_string(),
+            args: vec!["value"],
+            checks: vec![value().le(address_mask())],
+            results: vec![("result", value())],
+        },
+        Definition {
+            name: "UNSAFE_TO_ADDRESS".to_string(),
+            args: vec!["value"],
+            checks: Vec::new(),
+            results: vec![("result", value().and(address_mask()))],
+        },
+        Definition {
+            name: "TO_BOOL".to_string(),
+            args: vec!["value"],
+            checks: vec![value().le(Expr::lit("0x01"))],
+            results: vec![("result", value())],
+        },
+    ]
+}
+
+fn cast_context(size: u16, revert: OverflowRevert, push0: bool) -> Context {
+    let mask = mask(size);
+    let [cast, unsafe_cast, try_cast, pair, triple] = size_definitions(size);
+    let mask_conditions = conditions(&[], &[&format!("result == {}", mask)]);
+    // the mask is pushed by the mask macro of the size, or of `MINI_` macros its mini mask macro
+    let body = |definition: &Definition, prefix: &str| {
+        let masks = |lit: &str| (lit == mask).then(|| format!("{}U{}_MASK()", prefix, size));
+        lower::body(definition, &masks, revert.body())
+    };
+
+    Context::new()
+        .text("name", format!("U{}", size))
+        .text("size", size.to_string())
+        .text("mask", mask.clone())
+        .text("mask_code", mask_encoding(size, Objective::Gas, push0).code)
+        .text(
+            "mini_mask_code",
+            mask_encoding(size, Objective::Size, push0).code,
+        )
+        .text("mask_conditions", mask_conditions)
+        .text("cast_conditions", cast.conditions())
+        .text("cast_body", body(&cast, ""))
+        .text("mini_cast_body", body(&cast, "MINI_"))
+        .text("unsafe_conditions", unsafe_cast.conditions())
+        .text("unsafe_body", body(&unsafe_cast, ""))
+        .text("mini_unsafe_body", body(&unsafe_cast, "MINI_"))
+        .text("pair_conditions", pair.conditions())
+        .text("pair_body", body(&pair, ""))
+        .text("triple_conditions", triple.conditions())
+        .text("triple_body", body(&triple, ""))
+        .text("try_conditions", try_cast.conditions())
+        .text("try_body", body(&try_cast, ""))
+        // narrower masks are already smaller than the `__MINI_MASK` construction
+        .flag("mini", size >= 32)
+}
+
+/// Builds the hex literal of `2 ** bits - 1`.
+pub fn mask(bits: u16) -> String {
+    let mut mask = String::with_capacity(bits as usize / 4 + 3);
+    mask.push_str("0x");
+
+    if !bits.is_multiple_of(4) {
+        mask.push_str(&format!("{:x}", (1u8 << (bits % 4)) - 1));
+    }
+
+    for _ in 0..bits / 4 {
+        mask.push('f');
+    }
+
+    mask
+}
+
+const ERROR_REVERT: &str = r#"        __ERROR(Overflow)   // [err]
+        0x00                // [ptr, err]
+        mstore              // []
+        0x04                // [err_len]
+        0x00                // [ptr, err_len]
+        revert              // []
+"#;
+
+const PANIC_REVERT: &str = r#"        0x4e487b71          // [panic_selector]
+        0x00                // [ptr, panic_selector]
+        mstore              // []
+        0x11                // [overflow_code]
+        0x20                // [ptr, overflow_code]
+        mstore              // []
+        0x24                // [panic_len]
+        0x1c                // [ptr, panic_len]
+        revert              // []
+"#;
+
+const PANIC_NOTE: &str = r#"//! Checked casts revert with Solidity's `Panic(0x11)`, as checked arithmetic does, rather than
+//! `Overflow()`.
+//! 
+"#;
+
+const YUL_DOC: &str = r#"//  ------------------------------------------------------------------------------------------------
+//! # Casting Library
+//!
+//! Provides the casts of `libcast.huff` as Yul functions, for Solidity inline assembly.
+//!
+//! Bit sizes supported range from 8 to 256 inclusive and are multiples of 8.
+//!
+//! Functions prefixed with `unsafe` will not revert on overflow, and those checking several values
+//! are suffixed with their count, `toU8x2`.
+//!
+//! ## API
+//!
+//! For a given type, `Typename`:
+//!
+//! - `toTypename` - Downcasts a value to a smaller type.
+//! - `toTypenamex2` - Downcasts two values to a smaller type.
+//! - `toTypenamex3` - Downcasts three values to a smaller type.
+//! - `unsafeToTypename` - Downcasts a value to a smaller type.
+//! - `tryToTypename` - Downcasts a value to a smaller type, returning whether it fits.
+//!
+//! - `toAddress` - Casts a value to an address.
+//! - `unsafeToAddress` - Casts a value to an address.
+//! - `toBool` - Casts a value to a bool.
+"#;
+
+const LIBRARY_TEMPLATE: &str = r#"
+//  ------------------------------------------------------------------------------------------------
+//! # Casting Library
+//! 
+//! Provides macros for casting values.
+//! 
+//! Bit sizes supported range from 8 to 256 inclusive and are multiples of 8.
+//! 
+//! Items prefixed with `UNSAFE_` will not revert on overflow.
+//! 
+{{revert_note}}//! Items prefixed with `MINI_` will consume more runtime gas to the benefit of a smaller runtime
+//! size, encoding their masks in the fewest bytes for the target EVM version.
+//! 
+//! ## API
+//! 
+//! For a given type, `TYPENAME`:
+//! 
+//! - `TYPENAME_MASK` - Used to downcast a value to a smaller type.
+//! - `TO_TYPENAME` - Downcasts a value to a smaller type.
+//! - `TO_TYPENAME_2` - Downcasts the top two values to a smaller type.
+//! - `TO_TYPENAME_3` - Downcasts the top three values to a smaller type.
+//! - `UNSAFE_TO_TYPENAME` - Downcasts a value to a smaller type.
+//! - `TRY_TO_TYPENAME` - Downcasts a value to a smaller type, returning whether it fits.
+//! - `MINI_TYPENAME_MASK` - Used to downcast a value to a smaller type.
+//! - `MINI_TO_TYPENAME` - Downcasts a value to a smaller type.
+//! - `UNSAFE_MINI_TO_TYPENAME` - Downcasts a value to a smaller type.
+//! 
+//! - `ADDRESS_MASK` - Used to cast a value to an address.
+//! - `TO_ADDRESS` - Casts a value to an address.
+//! - `UNSAFE_TO_ADDRESS` - Casts a value to an address.
+//! - `TO_BOOL` - Casts a value to a bool.
+//! 
+
+/// ## Overflow Error
+/// 
+/// Thrown when a cast overflows.
+#define error Overflow()
+{{#each casts}}
+/// ## {{name}} Mask
+/// 
+/// Used to downcast a value to a smaller type.
+/// 
+/// ### Conditions
+/// 
+{{mask_conditions}}/// 
+/// ### Usage
+/// 
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x00 calldataload
+///     {{name}}_MASK() and
+/// }
+/// ```
+#define macro {{name}}_MASK() = takes (0) returns (1) { {{mask_code}} }
+
+/// ## {{name}} Cast
+/// 
+/// Downcasts a value to a smaller type.
+/// 
+/// The `UNSAFE_TO_{{name}}` macro will not revert on overflow.
+/// 
+/// ### Conditions
+/// 
+{{cast_conditions}}#define macro TO_{{name}}() = takes (1) returns (1) {
+{{cast_body}}}
+
+/// ## {{name}} Pair Cast
+/// 
+/// Downcasts the top two values to a smaller type, reverting if either overflows.
+/// 
+/// The values fit in the type if and only if their bitwise or does, so both are checked at once.
+/// 
+/// ### Conditions
+/// 
+{{pair_conditions}}#define macro TO_{{name}}_2() = takes (2) returns (2) {
+{{pair_body}}}
+
+/// ## {{name}} Triple Cast
+/// 
+/// Downcasts the top three values to a smaller type, reverting if any overflows.
+/// 
+/// The values fit in the type if and only if their bitwise or does, so all are checked at once.
+/// 
+/// ### Conditions
+/// 
+{{triple_conditions}}#define macro TO_{{name}}_3() = takes (3) returns (3) {
+{{triple_body}}}
+
+/// ## Unsafe {{name}} Cast
+/// 
+/// Downcasts a value to a smaller type, discarding the bits above its size.
+/// 
+/// ### Conditions
+/// 
+{{unsafe_conditions}}#define macro UNSAFE_TO_{{name}}() = takes (1) returns (1) {
+{{unsafe_body}}}
+
+/// ## Try {{name}} Cast
+/// 
+/// Downcasts a value to a smaller type without reverting, leaving whether the value fits in the
+/// type above the masked value for the caller to handle.
+/// 
+/// ### Conditions
+/// 
+{{try_conditions}}/// 
+/// ### Usage
+/// 
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x04 calldataload
+///     TRY_TO_{{name}}()
+///     iszero invalid_value jumpi
+///     0x00 sstore
+/// }
+/// ```
+#define macro TRY_TO_{{name}}() = takes (1) returns (2) {
+{{try_body}}}{{#if mini}}
+
+/// ## Mini {{name}} Mask
+/// 
+/// Used to downcast a value to a smaller type.
+/// 
+/// This consumes more runtime gas to the benefit of a smaller runtime size.
+/// 
+/// ### Conditions
+/// 
+{{mask_conditions}}/// 
+/// ### Usage
+/// 
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x00 calldataload
+///     MINI_{{name}}_MASK() and
+/// }
+/// ```
+#define macro MINI_{{name}}_MASK() = takes (0) returns (1) { {{mini_mask_code}} }
+
+/// ## Mini {{name}} Cast
+/// 
+/// Downcasts a value to a smaller type.
+/// 
+/// This consumes more runtime gas to the benefit of a smaller runtime size.
+/// 
+/// The `UNSAFE_MINI_TO_{{name}}` macro will not revert on overflow.
+/// 
+/// ### Conditions
+/// 
+{{cast_conditions}}#define macro MINI_TO_{{name}}() = takes (1) returns (1) {
+{{mini_cast_body}}}
+
+/// ## Unsafe Mini {{name}} Cast
+/// 
+/// Downcasts a value to a smaller type, discarding the bits above its size.
+/// 
+/// This consumes more runtime gas to the benefit of a smaller runtime size.
+/// 
+/// ### Conditions
+/// 
+{{unsafe_conditions}}#define macro UNSAFE_MINI_TO_{{name}}() = takes (1) returns (1) {
+{{mini_unsafe_body}}}{{/if}}
+{{/each}}
+/// ## Address Mask
+/// 
+/// Used to cast a value to an address, the low 160 bits of a word.
+/// 
+/// ### Conditions
+/// 
+{{address_mask_conditions}}/// 
+/// ### Usage
+/// 
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x00 calldataload
+///     ADDRESS_MASK() and
+/// }
+/// ```
+#define macro ADDRESS_MASK() = takes (0) returns (1) { {{address_mask_code}} }
+
+/// ## Address Cast
+/// 
+/// Casts a value to an address, reverting if any of its upper 96 bits are set, as for addresses
+/// read from calldata or storage that should have been clean.
+/// 
+/// The `UNSAFE_TO_ADDRESS` macro will not revert on dirty upper bits.
+/// 
+/// ### Conditions
+/// 
+{{address_conditions}}/// 
+/// ### Usage
+/// 
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x04 calldataload
+///     TO_ADDRESS()
+///     balance
+/// }
+/// ```
+#define macro TO_ADDRESS() = takes (1) returns (1) {
+{{address_body}}}
+
+/// ## Unsafe Address Cast
+/// 
+/// Casts a value to an address, discarding its upper 96 bits.
+/// 
+/// ### Conditions
+/// 
+{{unsafe_address_conditions}}#define macro UNSAFE_TO_ADDRESS() = takes (1) returns (1) {
+{{unsafe_address_body}}}
+
+/// ## Bool Cast
+/// 
+/// Casts a value to a bool, reverting unless it is `0` or `1`.
+/// 
+/// ### Conditions
+/// 
+{{bool_conditions}}/// 
+/// ### Usage
+/// 
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x04 calldataload
+///     TO_BOOL()
+///     0x00 sstore
+/// }
+/// ```
+#define macro TO_BOOL() = takes (1) returns (1) {
+{{bool_body}}}
+
+/// ## Mini Mask
+///
+/// Used as a utility to generate the mask
+///
+/// The macro body is functionally equivalent to the following: `2 ** bitsize - 1`
+///
+/// ### Template Arguments
+///
+/// - `bitsize` - The number of bits to generate a mask for.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MINI_U32_MASK() = takes (0) returns (1) { __MINI_MASK(32)}
+/// ```
+#define macro __MINI_MASK(bitsize) = takes (0) returns (1) {
+    0x01        // [one]
+    dup1        // [one, one]
+    <bitsize>   // [bisize, one, one]
+    shl         // [mask_plus_one, one]
+    sub         // [mask]
+}
+"#;
@@ -0,0 +1,236 @@
+pub fn render() -> String {
+    format!(
+        "{}{}{}{}",
+        HEADER, ERROR_DEFINITION, CHAIN_ID_DEFINITION, DOMAIN_SEPARATOR_DEFINITION,
+    )
+}
+
+const HEADER: &str = r#"
+//  ------------------------------------------------------------------------------------------------
+//! # Chain ID Library
+//!
+//! Provides macros for reading and asserting the chain id.
+//!
+//! Signatures and cached domain separators that do not commit to the chain id may be replayed on
+//! a fork. The `*_DOMAIN_SEPARATOR` macros recompute the separator whenever the chain id no longer
+//! matches the one it was cached against.
+//!
+//! ## API
+//!
+//! - `CHAIN_ID` - Pushes the current chain id.
+//! - `IS_CHAIN` - Checks the current chain id against an expected chain id.
+//! - `REQUIRE_CHAIN` - Reverts if the current chain id is not the expected chain id.
+//! - `IS_DOMAIN_STALE` - Checks if a cached chain id no longer matches the current chain id.
+//! - `CACHED_DOMAIN_SEPARATOR` - Uses a constant separator unless the chain id has changed.
+//! - `STORED_DOMAIN_SEPARATOR` - Uses a stored separator, recomputing and storing on chain change.
+"#;
+
+const ERROR_DEFINITION: &str = r#"
+/// ## Invalid Chain Error
+///
+/// Thrown when the current chain id is not the expected chain id.
+#define error InvalidChain()
+"#;
+
+const CHAIN_ID_DEFINITION: &str = r#"
+/// ## Chain ID
+///
+/// Pushes the current chain id.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     CHAIN_ID()
+///     0x00 mstore
+///     0x20 0x00 return
+/// }
+/// ```
+#define macro CHAIN_ID() = takes (0) returns (1) {
+    chainid                 // [chain_id]
+}
+
+/// ## Is Chain
+///
+/// Checks the current chain id against an expected chain id.
+///
+/// ### Template Arguments
+///
+/// - `id`: expected chain id.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     IS_CHAIN(0x01)
+///     0x00 mstore
+///     0x20 0x00 return
+/// }
+/// ```
+#define macro IS_CHAIN(id) = takes (0) returns (1) {
+    chainid                 // [chain_id]
+    <id>                    // [id, chain_id]
+    eq                      // [is_chain]
+}
+
+/// ## Require Chain
+///
+/// Reverts if the current chain id is not the expected chain id.
+///
+/// ### Template Arguments
+///
+/// - `id`: expected chain id.
+///
+/// ### Panics
+///
+/// - if the current chain id is not `id`.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     REQUIRE_CHAIN(0x01)
+///     stop
+/// }
+/// ```
+#define macro REQUIRE_CHAIN(id) = takes (0) returns (0) {
+    IS_CHAIN(<id>)          // [is_chain]
+    is_chain                // [is_chain_dest, is_chain]
+    jumpi                   // []
+        __ERROR(InvalidChain) // [err]
+        0x00                // [ptr, err]
+        mstore              // []
+        0x04                // [err_len]
+        0x00                // [ptr, err_len]
+        revert              // []
+    is_chain:               // []
+}
+"#;
+
+const DOMAIN_SEPARATOR_DEFINITION: &str = r#"
+/// ## Is Domain Stale
+///
+/// Checks if a cached chain id no longer matches the current chain id, in which case any domain
+/// separator derived from it must be recomputed.
+///
+/// ### Template Arguments
+///
+/// - `cached_chain_id`: chain id the domain separator was derived with.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define constant CACHED_CHAIN_ID = 0x01
+///
+/// #define macro MAIN() = takes (0) returns (0) {
+///     IS_DOMAIN_STALE([CACHED_CHAIN_ID])
+///     0x00 mstore
+///     0x20 0x00 return
+/// }
+/// ```
+#define macro IS_DOMAIN_STALE(cached_chain_id) = takes (0) returns (1) {
+    IS_CHAIN(<cached_chain_id>) // [is_cached_chain]
+    iszero                  // [is_stale]
+}
+
+/// ## Cached Domain Separator
+///
+/// Pushes a constant domain separator if the current chain id matches the chain id it was derived
+/// with, otherwise evaluates `compute` to derive a fresh separator.
+///
+/// ### Template Arguments
+///
+/// - `cached_chain_id`: chain id the cached separator was derived with.
+/// - `cached_separator`: domain separator derived with `cached_chain_id`.
+/// - `compute`: expression that resolves to a domain separator for the current chain id.
+///
+/// ### Panics
+///
+/// - if the chain id is stale and `compute` panics.
+///
+/// ### Usage
+///
+/// ```huff
+/// #include "libchain.huff"
+///
+/// #define constant CACHED_CHAIN_ID = 0x01
+/// #define constant CACHED_SEPARATOR = 0x00
+///
+/// #define macro COMPUTE_SEPARATOR() = takes (0) returns (1) {
+///     [EIP712_DOMAIN_TYPEHASH] 0x00 mstore
+///     chainid 0x20 mstore
+///     address 0x40 mstore
+///     0x60 0x00 sha3
+/// }
+///
+/// #define macro MAIN() = takes (0) returns (0) {
+///     CACHED_DOMAIN_SEPARATOR([CACHED_CHAIN_ID], [CACHED_SEPARATOR], COMPUTE_SEPARATOR())
+///     0x00 mstore
+///     0x20 0x00 return
+/// }
+/// ```
+#define macro CACHED_DOMAIN_SEPARATOR(cached_chain_id, cached_separator, compute) = takes (0) returns (1) {
+    IS_CHAIN(<cached_chain_id>) // [is_cached_chain]
+    use_cached              // [use_cached_dest, is_cached_chain]
+    jumpi                   // []
+        <compute>           // [separator]
+        done                // [done_dest, separator]
+        jump                // [separator]
+    use_cached:             // []
+        <cached_separator>  // [separator]
+    done:                   // [separator]
+}
+
+/// ## Stored Domain Separator
+///
+/// Loads the domain separator from storage if the stored chain id matches the current chain id,
+/// otherwise evaluates `compute` and stores both the fresh separator and the current chain id.
+///
+/// The zero value of `chain_id_slot` is never a valid chain id, so the first call always computes.
+///
+/// ### Template Arguments
+///
+/// - `chain_id_slot`: storage slot of the chain id the separator was derived with.
+/// - `separator_slot`: storage slot of the cached domain separator.
+/// - `compute`: expression that resolves to a domain separator for the current chain id.
+///
+/// ### Panics
+///
+/// - if the chain id is stale and `compute` panics.
+///
+/// ### Usage
+///
+/// ```huff
+/// #include "libchain.huff"
+///
+/// #define constant CHAIN_ID_SLOT = FREE_STORAGE_POINTER()
+/// #define constant SEPARATOR_SLOT = FREE_STORAGE_POINTER()
+///
+/// #define macro MAIN() = takes (0) returns (0) {
+///     STORED_DOMAIN_SEPARATOR([CHAIN_ID_SLOT], [SEPARATOR_SLOT], COMPUTE_SEPARATOR())
+///     0x00 mstore
+///     0x20 0x00 return
+/// }
+/// ```
+#define macro STORED_DOMAIN_SEPARATOR(chain_id_slot, separator_slot, compute) = takes (0) returns (1) {
+    <chain_id_slot>         // [chain_id_slot]
+    sload                   // [cached_chain_id]
+    chainid                 // [chain_id, cached_chain_id]
+    eq                      // [is_cached_chain]
+    use_cached              // [use_cached_dest, is_cached_chain]
+    jumpi                   // []
+        <compute>           // [separator]
+        dup1                // [separator, separator]
+        <separator_slot>    // [separator_slot, separator, separator]
+        sstore              // [separator]
+        chainid             // [chain_id, separator]
+        <chain_id_slot>     // [chain_id_slot, chain_id, separator]
+        sstore              // [separator]
+        done                // [done_dest, separator]
+        jump                // [separator]
+    use_cached:             // []
+        <separator_slot>    // [separator_slot]
+        sload               // [separator]
+    done:                   // [separator]
+}
+"#;
@@ -0,0 +1,166 @@
+use crate::templates::{self, Context};
+
+pub fn render() -> String {
+    let table = b"0123456789abcdef"
+        .iter()
+        .map(|c| format!("{:02x}", c))
+        .collect::<String>();
+    let hex_table = format!("0x{:0<64}", table);
+
+    format!(
+        "{}{}",
+        HEADER,
+        templates::render(
+            STRING_DEFINITION,
+            &Context::new().text("hex_table", hex_table)
+        ),
+    )
+}
+
+const HEADER: &str = r#"
+//  ------------------------------------------------------------------------------------------------
+//! # Checksum Library
+//!
+//! Provides encoding of addresses as `0x` prefixed hex strings in memory, such as for rendering
+//! addresses into on-chain metadata or error strings.
+//!
+//! ## API
+//!
+//! - `ADDRESS_TO_HEX_STRING` - Writes the lower case hex string of an address to memory.
+//! - `ADDRESS_TO_CHECKSUM_STRING` - Writes the EIP-55 checksummed hex string of an address to
+//!   memory.
+
+#include "libkeccak.huff"
+"#;
+
+const STRING_DEFINITION: &str = r#"
+/// ## Address To Hex String
+///
+/// Writes the 42 byte, `0x` prefixed, lower case hex string of an address to `ptr`.
+///
+/// > WARNING: Memory from `ptr` to `ptr + 0x2a` will be overwritten.
+///
+/// ### Stack Arguments
+///
+/// - `address`: (`stack[0]`) address to encode.
+/// - `ptr`: (`stack[1]`) memory pointer to write the string to.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x00 caller
+///     ADDRESS_TO_HEX_STRING()
+///     0x2a 0x00 return
+/// }
+/// ```
+#define macro ADDRESS_TO_HEX_STRING() = takes (2) returns (0) {
+    // takes:               // [address, ptr]
+    0x3078                  // [prefix, address, ptr]
+    0xf0                    // [0xf0, prefix, address, ptr]
+    shl                     // [prefix, address, ptr]
+    dup3                    // [ptr, prefix, address, ptr]
+    mstore                  // [address, ptr]
+    dup2                    // [ptr, address, ptr]
+    0x29                    // [0x29, ptr, address, ptr]
+    add                     // [char_ptr, address, ptr]
+    swap1                   // [address, char_ptr, ptr]
+    hex_loop:               // [address, char_ptr, ptr]
+        {{hex_table}} // [table, address, char_ptr, ptr]
+        dup2                // [address, table, address, char_ptr, ptr]
+        0x0f                // [0x0f, address, table, address, char_ptr, ptr]
+        and                 // [nibble, table, address, char_ptr, ptr]
+        byte                // [char, address, char_ptr, ptr]
+        dup3                // [char_ptr, char, address, char_ptr, ptr]
+        mstore8             // [address, char_ptr, ptr]
+        0x04                // [0x04, address, char_ptr, ptr]
+        shr                 // [address, char_ptr, ptr]
+        swap1               // [char_ptr, address, ptr]
+        0x01                // [0x01, char_ptr, address, ptr]
+        swap1               // [char_ptr, 0x01, address, ptr]
+        sub                 // [char_ptr, address, ptr]
+        swap1               // [address, char_ptr, ptr]
+        dup3                // [ptr, address, char_ptr, ptr]
+        0x01                // [0x01, ptr, address, char_ptr, ptr]
+        add                 // [prefix_end, address, char_ptr, ptr]
+        dup3                // [char_ptr, prefix_end, address, char_ptr, ptr]
+        gt                  // [is_pending, address, char_ptr, ptr]
+        hex_loop            // [hex_loop_dest, is_pending, address, char_ptr, ptr]
+        jumpi               // [address, char_ptr, ptr]
+    pop                     // [char_ptr, ptr]
+    pop                     // [ptr]
+    pop                     // []
+}
+
+/// ## Address To Checksum String
+///
+/// Writes the 42 byte, `0x` prefixed, EIP-55 checksummed hex string of an address to `ptr`.
+///
+/// Each letter of the lower case hex string is upper cased if the corresponding nibble of the
+/// keccak256 hash of the lower case string, without its prefix, is at least 8.
+///
+/// > WARNING: Memory from `ptr` to `ptr + 0x2a` will be overwritten.
+///
+/// ### Stack Arguments
+///
+/// - `address`: (`stack[0]`) address to encode.
+/// - `ptr`: (`stack[1]`) memory pointer to write the string to.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x00 caller
+///     ADDRESS_TO_CHECKSUM_STRING()
+///     0x2a 0x00 return
+/// }
+/// ```
+#define macro ADDRESS_TO_CHECKSUM_STRING() = takes (2) returns (0) {
+    // takes:               // [address, ptr]
+    dup2                    // [ptr, address, ptr]
+    swap1                   // [address, ptr, ptr]
+    ADDRESS_TO_HEX_STRING() // [ptr]
+    0x02                    // [0x02, ptr]
+    add                     // [char_ptr]
+    dup1                    // [char_ptr, char_ptr]
+    0x28                    // [0x28, char_ptr, char_ptr]
+    add                     // [end, char_ptr]
+    0x28                    // [len, end, char_ptr]
+    dup3                    // [char_ptr, len, end, char_ptr]
+    HASH_MEM_RANGE()        // [hash, end, char_ptr]
+    swap1                   // [end, hash, char_ptr]
+    swap2                   // [char_ptr, hash, end]
+    swap1                   // [hash, char_ptr, end]
+    checksum_loop:          // [hash, char_ptr, end]
+        dup2                // [char_ptr, hash, char_ptr, end]
+        mload               // [word, hash, char_ptr, end]
+        0x00                // [0x00, word, hash, char_ptr, end]
+        byte                // [char, hash, char_ptr, end]
+        dup1                // [char, char, hash, char_ptr, end]
+        0x06                // [0x06, char, char, hash, char_ptr, end]
+        shr                 // [is_letter, char, hash, char_ptr, end]
+        dup3                // [hash, is_letter, char, hash, char_ptr, end]
+        0xff                // [0xff, hash, is_letter, char, hash, char_ptr, end]
+        shr                 // [hash_bit, is_letter, char, hash, char_ptr, end]
+        and                 // [is_upper, char, hash, char_ptr, end]
+        0x05                // [0x05, is_upper, char, hash, char_ptr, end]
+        shl                 // [case_bit, char, hash, char_ptr, end]
+        xor                 // [char, hash, char_ptr, end]
+        dup3                // [char_ptr, char, hash, char_ptr, end]
+        mstore8             // [hash, char_ptr, end]
+        0x04                // [0x04, hash, char_ptr, end]
+        shl                 // [hash, char_ptr, end]
+        swap1               // [char_ptr, hash, end]
+        0x01                // [0x01, char_ptr, hash, end]
+        add                 // [char_ptr, hash, end]
+        swap1               // [hash, char_ptr, end]
+        dup3                // [end, hash, char_ptr, end]
+        dup3                // [char_ptr, end, hash, char_ptr, end]
+        lt                  // [is_pending, hash, char_ptr, end]
+        checksum_loop       // [checksum_loop_dest, is_pending, hash, char_ptr, end]
+        jumpi               // [hash, char_ptr, end]
+    pop                     // [char_ptr, end]
+    pop                     // [end]
+    pop                     // []
+}
+"#;
@@ -0,0 +1,237 @@
+use crate::emit::{conditions, line};
+use crate::libcast::mask;
+use crate::templates::{self, Context};
+
+/// Renders `libcmp.huff` with the comparisons of the bit `sizes`.
+pub fn render(sizes: &[u16]) -> String {
+    let sizes = sizes.iter().map(|size| size_context(*size)).collect();
+    let context = Context::new().list("sizes", sizes);
+
+    templates::render(LIBRARY_TEMPLATE, &context)
+}
+
+fn size_context(size: u16) -> Context {
+    let name = format!("U{}", size);
+    let mask = mask(size);
+
+    let min_conditions = conditions(
+        &[],
+        &["result <= a", "result <= b", "result == a || result == b"],
+    );
+    let max_conditions = conditions(
+        &[],
+        &["result >= a", "result >= b", "result == a || result == b"],
+    );
+    let clamp_conditions = conditions(
+        &[],
+        &[
+            &format!("result <= {}", mask),
+            &format!("result == value || value > {}", mask),
+            &format!("result == {} || value <= {}", mask, mask),
+        ],
+    );
+    let is_conditions = conditions(&[], &[&format!("result == (value <= {})", mask)]);
+
+    Context::new()
+        .text("name", name.clone())
+        .text("size", size.to_string())
+        .text("shift", format!("{:#04x}", size))
+        .text(
+            "mask_line",
+            line(&format!("{}_MASK()", name), "[mask, value]"),
+        )
+        .text("min_line", line(&format!("MIN_{}()", name), "[clamped]"))
+        .text(
+            "branchless_min_line",
+            line(&format!("BRANCHLESS_MIN_{}()", name), "[clamped]"),
+        )
+        .text("min_conditions", min_conditions)
+        .text("max_conditions", max_conditions)
+        .text("clamp_conditions", clamp_conditions)
+        .text("is_conditions", is_conditions)
+        // every word is a 256 bit value
+        .flag("bounded", size != 256)
+}
+
+const LIBRARY_TEMPLATE: &str = r#"
+//  ------------------------------------------------------------------------------------------------
+//! # Comparison Library
+//!
+//! Provides minimums, maximums, clamping and range checks of unsigned integers of the sizes of
+//! `libcast.huff`.
+//!
+//! Bit sizes supported range from 8 to 256 inclusive and are multiples of 8. Each comparison has a
+//! branching variant and a `BRANCHLESS` variant, which selects its result arithmetically instead,
+//! in constant gas and fewer bytes.
+//!
+//! ## API
+//!
+//! For a given type, `TYPENAME`:
+//!
+//! - `MIN_TYPENAME` - The smaller of two values.
+//! - `MAX_TYPENAME` - The larger of two values.
+//! - `CLAMP_TYPENAME` - Clamps a value of any size to the largest value of the type.
+//! - `IS_TYPENAME` - Whether a value fits in the type.
+//!
+//! - `BRANCHLESS_MIN_TYPENAME`, `BRANCHLESS_MAX_TYPENAME`, `BRANCHLESS_CLAMP_TYPENAME` - The same,
+//!   without branching.
+
+#include "libcast.huff"
+{{#each sizes}}
+/// ## {{name}} Minimum
+///
+/// Pushes the smaller of two {{size}} bit values.
+///
+/// ### Stack Arguments
+///
+/// - `a`: (`stack[0]`) first value.
+/// - `b`: (`stack[1]`) second value.
+///
+/// ### Conditions
+///
+{{min_conditions}}///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x24 calldataload
+///     0x04 calldataload
+///     MIN_{{name}}()
+///     0x00 mstore
+///     0x20 0x00 return
+/// }
+/// ```
+#define macro MIN_{{name}}() = takes (2) returns (1) {
+    // takes:               // [a, b]
+    dup2                    // [b, a, b]
+    dup2                    // [a, b, a, b]
+    lt                      // [is_a, a, b]
+    is_min                  // [is_min_dest, is_a, a, b]
+    jumpi                   // [a, b]
+        swap1               // [b, a]
+    is_min:                 // [min, max]
+        swap1               // [max, min]
+        pop                 // [min]
+}
+
+/// ## {{name}} Branchless Minimum
+///
+/// Pushes the smaller of two {{size}} bit values, as `b ^ ((a ^ b) * (a < b))`.
+///
+/// ### Conditions
+///
+{{min_conditions}}#define macro BRANCHLESS_MIN_{{name}}() = takes (2) returns (1) {
+    // takes:               // [a, b]
+    dup2                    // [b, a, b]
+    dup2                    // [a, b, a, b]
+    lt                      // [is_a, a, b]
+    swap1                   // [a, is_a, b]
+    dup3                    // [b, a, is_a, b]
+    xor                     // [difference, is_a, b]
+    mul                     // [selected_difference, b]
+    xor                     // [min]
+}
+
+/// ## {{name}} Maximum
+///
+/// Pushes the larger of two {{size}} bit values.
+///
+/// ### Conditions
+///
+{{max_conditions}}#define macro MAX_{{name}}() = takes (2) returns (1) {
+    // takes:               // [a, b]
+    dup2                    // [b, a, b]
+    dup2                    // [a, b, a, b]
+    gt                      // [is_a, a, b]
+    is_max                  // [is_max_dest, is_a, a, b]
+    jumpi                   // [a, b]
+        swap1               // [b, a]
+    is_max:                 // [max, min]
+        swap1               // [min, max]
+        pop                 // [max]
+}
+
+/// ## {{name}} Branchless Maximum
+///
+/// Pushes the larger of two {{size}} bit values, as `b ^ ((a ^ b) * (a > b))`.
+///
+/// ### Conditions
+///
+{{max_conditions}}#define macro BRANCHLESS_MAX_{{name}}() = takes (2) returns (1) {
+    // takes:               // [a, b]
+    dup2                    // [b, a, b]
+    dup2                    // [a, b, a, b]
+    gt                      // [is_a, a, b]
+    swap1                   // [a, is_a, b]
+    dup3                    // [b, a, is_a, b]
+    xor                     // [difference, is_a, b]
+    mul                     // [selected_difference, b]
+    xor                     // [max]
+}
+
+/// ## {{name}} Clamp
+///
+/// Clamps a value of any size to the largest {{size}} bit value, saturating rather than
+/// truncating or reverting as the casts of `libcast.huff` do.
+///
+/// ### Stack Arguments
+///
+/// - `value`: (`stack[0]`) value to clamp.
+///
+/// ### Conditions
+///
+{{clamp_conditions}}///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x04 calldataload
+///     CLAMP_{{name}}()
+///     0x00 mstore
+///     0x20 0x00 return
+/// }
+/// ```
+#define macro CLAMP_{{name}}() = takes (1) returns (1) {
+    // takes:               // [value]
+{{#if bounded}}{{mask_line}}{{min_line}}{{/if}}}
+
+/// ## {{name}} Branchless Clamp
+///
+/// Clamps a value of any size to the largest {{size}} bit value, without branching.
+///
+/// ### Conditions
+///
+{{clamp_conditions}}#define macro BRANCHLESS_CLAMP_{{name}}() = takes (1) returns (1) {
+    // takes:               // [value]
+{{#if bounded}}{{mask_line}}{{branchless_min_line}}{{/if}}}
+
+/// ## Is {{name}}
+///
+/// Pushes whether a value fits in {{size}} bits.
+///
+/// ### Stack Arguments
+///
+/// - `value`: (`stack[0]`) value to check.
+///
+/// ### Conditions
+///
+{{is_conditions}}///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x04 calldataload
+///     IS_{{name}}()
+///     0x00 mstore
+///     0x20 0x00 return
+/// }
+/// ```
+#define macro IS_{{name}}() = takes (1) returns (1) {
+    // takes:               // [value]
+{{#if bounded}}    {{shift}}                    // [shift, value]
+    shr                     // [high_bits]
+    iszero                  // [fits]
+{{else}}    pop                     // []
+    0x01                    // [fits]
+{{/if}}}
+{{/each}}"#;
@@ -0,0 +1,464 @@
+pub fn render() -> String {
+    format!(
+        "{}{}{}{}",
+        HEADER, ERROR_DEFINITION, STACK_DEFINITION, QUEUE_DEFINITION,
+    )
+}
+
+const HEADER: &str = r#"
+//  ------------------------------------------------------------------------------------------------
+//! # Collections Library
+//!
+//! Provides fixed-capacity stack and queue data structures over memory regions.
+//!
+//! Each collection lives at a constant memory pointer, `ptr`, and holds at most `cap` words.
+//!
+//! A stack occupies `0x20 + cap * 0x20` bytes: its length at `ptr`, followed by its elements from
+//! the bottom up.
+//!
+//! A queue occupies `0x40 + cap * 0x20` bytes: the index of its head at `ptr`, its length at
+//! `ptr + 0x20`, followed by its elements as a ring buffer.
+//!
+//! > WARNING: Collections must be initialized before use unless their memory is known to be zero.
+//!
+//! ## API
+//!
+//! - `MSTACK_INIT` - Empties a stack.
+//! - `MSTACK_LENGTH` - Pushes the number of elements in a stack.
+//! - `MSTACK_PUSH` - Pushes an element onto a stack.
+//! - `MSTACK_POP` - Pops the top element off of a stack.
+//! - `MSTACK_PEEK` - Reads the top element of a stack.
+//! - `MQUEUE_INIT` - Empties a queue.
+//! - `MQUEUE_LENGTH` - Pushes the number of elements in a queue.
+//! - `MQUEUE_PUSH` - Pushes an element onto the back of a queue.
+//! - `MQUEUE_POP` - Pops the front element off of a queue.
+//! - `MQUEUE_PEEK` - Reads the front element of a queue.
+"#;
+
+const ERROR_DEFINITION: &str = r#"
+/// ## Collection Full Error
+///
+/// Thrown when pushing to a collection that holds `cap` elements.
+#define error CollectionFull()
+
+/// ## Collection Empty Error
+///
+/// Thrown when popping or peeking an empty collection.
+#define error CollectionEmpty()
+"#;
+
+const STACK_DEFINITION: &str = r#"
+/// ## Memory Stack Init
+///
+/// Empties a stack.
+///
+/// ### Template Arguments
+///
+/// - `ptr`: memory pointer of the stack.
+///
+/// ### Usage
+///
+/// ```huff
+/// #include "libcollections.huff"
+///
+/// #define macro MAIN() = takes (0) returns (0) {
+///     MSTACK_INIT(0x80)
+/// }
+/// ```
+#define macro MSTACK_INIT(ptr) = takes (0) returns (0) {
+    0x00                    // [zero]
+    <ptr>                   // [ptr, zero]
+    mstore                  // []
+}
+
+/// ## Memory Stack Length
+///
+/// Pushes the number of elements in a stack.
+///
+/// ### Template Arguments
+///
+/// - `ptr`: memory pointer of the stack.
+///
+/// ### Usage
+///
+/// ```huff
+/// #include "libcollections.huff"
+///
+/// #define macro MAIN() = takes (0) returns (0) {
+///     MSTACK_LENGTH(0x80)
+///     0x00 mstore
+///     0x20 0x00 return
+/// }
+/// ```
+#define macro MSTACK_LENGTH(ptr) = takes (0) returns (1) {
+    <ptr>                   // [ptr]
+    mload                   // [len]
+}
+
+/// ## Memory Stack Push
+///
+/// Pushes an element onto a stack.
+///
+/// ### Template Arguments
+///
+/// - `ptr`: memory pointer of the stack.
+/// - `cap`: maximum number of elements in the stack.
+///
+/// ### Stack Arguments
+///
+/// - `value`: (`stack[0]`) element to push.
+///
+/// ### Panics
+///
+/// - if the stack holds `cap` elements.
+///
+/// ### Usage
+///
+/// ```huff
+/// #include "libcollections.huff"
+///
+/// #define macro MAIN() = takes (0) returns (0) {
+///     MSTACK_INIT(0x80)
+///     0x04 calldataload
+///     MSTACK_PUSH(0x80, 0x10)
+/// }
+/// ```
+#define macro MSTACK_PUSH(ptr, cap) = takes (1) returns (0) {
+    // takes:               // [value]
+    <ptr>                   // [ptr, value]
+    mload                   // [len, value]
+    dup1                    // [len, len, value]
+    <cap>                   // [cap, len, len, value]
+    gt                      // [has_capacity, len, value]
+    has_capacity            // [has_capacity_dest, has_capacity, len, value]
+    jumpi                   // [len, value]
+        __ERROR(CollectionFull) // [err, len, value]
+        0x00                // [ptr, err, len, value]
+        mstore              // [len, value]
+        0x04                // [err_len, len, value]
+        0x00                // [ptr, err_len, len, value]
+        revert              // []
+    has_capacity:           // [len, value]
+    dup1                    // [len, len, value]
+    0x01                    // [one, len, len, value]
+    add                     // [new_len, len, value]
+    <ptr>                   // [ptr, new_len, len, value]
+    mstore                  // [len, value]
+    0x05                    // [five, len, value]
+    shl                     // [offset, value]
+    <ptr> 0x20 add          // [elements_ptr, offset, value]
+    add                     // [element_ptr, value]
+    mstore                  // []
+}
+
+/// ## Memory Stack Pop
+///
+/// Pops the top element off of a stack.
+///
+/// ### Template Arguments
+///
+/// - `ptr`: memory pointer of the stack.
+///
+/// ### Panics
+///
+/// - if the stack is empty.
+///
+/// ### Usage
+///
+/// ```huff
+/// #include "libcollections.huff"
+///
+/// #define macro MAIN() = takes (0) returns (0) {
+///     MSTACK_POP(0x80)
+///     0x00 mstore
+///     0x20 0x00 return
+/// }
+/// ```
+#define macro MSTACK_POP(ptr) = takes (0) returns (1) {
+    <ptr>                   // [ptr]
+    mload                   // [len]
+    dup1                    // [len, len]
+    not_empty               // [not_empty_dest, len, len]
+    jumpi                   // [len]
+        __ERROR(CollectionEmpty) // [err, len]
+        0x00                // [ptr, err, len]
+        mstore              // [len]
+        0x04                // [err_len, len]
+        0x00                // [ptr, err_len, len]
+        revert              // []
+    not_empty:              // [len]
+    dup1                    // [len, len]
+    0x05                    // [five, len, len]
+    shl                     // [offset, len]
+    <ptr>                   // [ptr, offset, len]
+    add                     // [element_ptr, len]
+    mload                   // [value, len]
+    swap1                   // [len, value]
+    0x01                    // [one, len, value]
+    swap1                   // [len, one, value]
+    sub                     // [new_len, value]
+    <ptr>                   // [ptr, new_len, value]
+    mstore                  // [value]
+}
+
+/// ## Memory Stack Peek
+///
+/// Reads the top element of a stack without removing it.
+///
+/// ### Template Arguments
+///
+/// - `ptr`: memory pointer of the stack.
+///
+/// ### Panics
+///
+/// - if the stack is empty.
+///
+/// ### Usage
+///
+/// ```huff
+/// #include "libcollections.huff"
+///
+/// #define macro MAIN() = takes (0) returns (0) {
+///     MSTACK_PEEK(0x80)
+///     0x00 mstore
+///     0x20 0x00 return
+/// }
+/// ```
+#define macro MSTACK_PEEK(ptr) = takes (0) returns (1) {
+    <ptr>                   // [ptr]
+    mload                   // [len]
+    dup1                    // [len, len]
+    not_empty               // [not_empty_dest, len, len]
+    jumpi                   // [len]
+        __ERROR(CollectionEmpty) // [err, len]
+        0x00                // [ptr, err, len]
+        mstore              // [len]
+        0x04                // [err_len, len]
+        0x00                // [ptr, err_len, len]
+        revert              // []
+    not_empty:              // [len]
+    0x05                    // [five, len]
+    shl                     // [offset]
+    <ptr>                   // [ptr, offset]
+    add                     // [element_ptr]
+    mload                   // [value]
+}
+"#;
+
+const QUEUE_DEFINITION: &str = r#"
+/// ## Memory Queue Init
+///
+/// Empties a queue.
+///
+/// ### Template Arguments
+///
+/// - `ptr`: memory pointer of the queue.
+///
+/// ### Usage
+///
+/// ```huff
+/// #include "libcollections.huff"
+///
+/// #define macro MAIN() = takes (0) returns (0) {
+///     MQUEUE_INIT(0x80)
+/// }
+/// ```
+#define macro MQUEUE_INIT(ptr) = takes (0) returns (0) {
+    0x00                    // [zero]
+    dup1                    // [zero, zero]
+    <ptr>                   // [ptr, zero, zero]
+    mstore                  // [zero]
+    <ptr> 0x20 add          // [len_ptr, zero]
+    mstore                  // []
+}
+
+/// ## Memory Queue Length
+///
+/// Pushes the number of elements in a queue.
+///
+/// ### Template Arguments
+///
+/// - `ptr`: memory pointer of the queue.
+///
+/// ### Usage
+///
+/// ```huff
+/// #include "libcollections.huff"
+///
+/// #define macro MAIN() = takes (0) returns (0) {
+///     MQUEUE_LENGTH(0x80)
+///     0x00 mstore
+///     0x20 0x00 return
+/// }
+/// ```
+#define macro MQUEUE_LENGTH(ptr) = takes (0) returns (1) {
+    <ptr> 0x20 add          // [len_ptr]
+    mload                   // [len]
+}
+
+/// ## Memory Queue Push
+///
+/// Pushes an element onto the back of a queue.
+///
+/// ### Template Arguments
+///
+/// - `ptr`: memory pointer of the queue.
+/// - `cap`: maximum number of elements in the queue.
+///
+/// ### Stack Arguments
+///
+/// - `value`: (`stack[0]`) element to push.
+///
+/// ### Panics
+///
+/// - if the queue holds `cap` elements.
+///
+/// ### Usage
+///
+/// ```huff
+/// #include "libcollections.huff"
+///
+/// #define macro MAIN() = takes (0) returns (0) {
+///     MQUEUE_INIT(0x80)
+///     0x04 calldataload
+///     MQUEUE_PUSH(0x80, 0x10)
+/// }
+/// ```
+#define macro MQUEUE_PUSH(ptr, cap) = takes (1) returns (0) {
+    // takes:               // [value]
+    <ptr> 0x20 add          // [len_ptr, value]
+    mload                   // [len, value]
+    dup1                    // [len, len, value]
+    <cap>                   // [cap, len, len, value]
+    gt                      // [has_capacity, len, value]
+    has_capacity            // [has_capacity_dest, has_capacity, len, value]
+    jumpi                   // [len, value]
+        __ERROR(CollectionFull) // [err, len, value]
+        0x00                // [ptr, err, len, value]
+        mstore              // [len, value]
+        0x04                // [err_len, len, value]
+        0x00                // [ptr, err_len, len, value]
+        revert              // []
+    has_capacity:           // [len, value]
+    dup1                    // [len, len, value]
+    0x01                    // [one, len, len, value]
+    add                     // [new_len, len, value]
+    <ptr> 0x20 add          // [len_ptr, new_len, len, value]
+    mstore                  // [len, value]
+    <ptr>                   // [ptr, len, value]
+    mload                   // [head, len, value]
+    add                     // [tail, value]
+    <cap>                   // [cap, tail, value]
+    swap1                   // [tail, cap, value]
+    mod                     // [index, value]
+    0x05                    // [five, index, value]
+    shl                     // [offset, value]
+    <ptr> 0x40 add          // [elements_ptr, offset, value]
+    add                     // [element_ptr, value]
+    mstore                  // []
+}
+
+/// ## Memory Queue Pop
+///
+/// Pops the front element off of a queue.
+///
+/// ### Template Arguments
+///
+/// - `ptr`: memory pointer of the queue.
+/// - `cap`: maximum number of elements in the queue.
+///
+/// ### Panics
+///
+/// - if the queue is empty.
+///
+/// ### Usage
+///
+/// ```huff
+/// #include "libcollections.huff"
+///
+/// #define macro MAIN() = takes (0) returns (0) {
+///     MQUEUE_POP(0x80, 0x10)
+///     0x00 mstore
+///     0x20 0x00 return
+/// }
+/// ```
+#define macro MQUEUE_POP(ptr, cap) = takes (0) returns (1) {
+    <ptr> 0x20 add          // [len_ptr]
+    mload                   // [len]
+    dup1                    // [len, len]
+    not_empty               // [not_empty_dest, len, len]
+    jumpi                   // [len]
+        __ERROR(CollectionEmpty) // [err, len]
+        0x00                // [ptr, err, len]
+        mstore              // [len]
+        0x04                // [err_len, len]
+        0x00                // [ptr, err_len, len]
+        revert              // []
+    not_empty:              // [len]
+    0x01                    // [one, len]
+    swap1                   // [len, one]
+    sub                     // [new_len]
+    <ptr> 0x20 add          // [len_ptr, new_len]
+    mstore                  // []
+    <ptr>                   // [ptr]
+    mload                   // [head]
+    dup1                    // [head, head]
+    0x05                    // [five, head, head]
+    shl                     // [offset, head]
+    <ptr> 0x40 add          // [elements_ptr, offset, head]
+    add                     // [element_ptr, head]
+    mload                   // [value, head]
+    swap1                   // [head, value]
+    0x01                    // [one, head, value]
+    add                     // [next, value]
+    <cap>                   // [cap, next, value]
+    swap1                   // [next, cap, value]
+    mod                     // [new_head, value]
+    <ptr>                   // [ptr, new_head, value]
+    mstore                  // [value]
+}
+
+/// ## Memory Queue Peek
+///
+/// Reads the front element of a queue without removing it.
+///
+/// ### Template Arguments
+///
+/// - `ptr`: memory pointer of the queue.
+///
+/// ### Panics
+///
+/// - if the queue is empty.
+///
+/// ### Usage
+///
+/// ```huff
+/// #include "libcollections.huff"
+///
+/// #define macro MAIN() = takes (0) returns (0) {
+///     MQUEUE_PEEK(0x80)
+///     0x00 mstore
+///     0x20 0x00 return
+/// }
+/// ```
+#define macro MQUEUE_PEEK(ptr) = takes (0) returns (1) {
+    <ptr> 0x20 add          // [len_ptr]
+    mload                   // [len]
+    not_empty               // [not_empty_dest, len]
+    jumpi                   // []
+        __ERROR(CollectionEmpty) // [err]
+        0x00                // [ptr, err]
+        mstore              // []
+        0x04                // [err_len]
+        0x00                // [ptr, err_len]
+        revert              // []
+    not_empty:              // []
+    <ptr>                   // [ptr]
+    mload                   // [head]
+    0x05                    // [five, head]
+    shl                     // [offset]
+    <ptr> 0x40 add          // [elements_ptr, offset]
+    add                     // [element_ptr]
+    mload                   // [value]
+}
+"#;
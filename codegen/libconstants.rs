@@ -0,0 +1,123 @@
+use crate::config::{invalid, Table, Value};
+use crate::libcast::mask;
+use crate::templates::{self, Context};
+
+/// Renders `libconstants.huff` with the bounds of the bit `sizes`, as constants or, if the config
+/// sets `constants.macros`, as macros pushing them.
+pub fn render(config: &Table, sizes: &[u16]) -> std::io::Result<String> {
+    let macros = parse_constants(config)?;
+
+    let sizes = sizes.iter().map(|size| size_context(*size)).collect();
+    let context = Context::new()
+        .flag("macros", macros)
+        .text("address_max", mask(160))
+        .list("sizes", sizes);
+
+    Ok(templates::render(LIBRARY_TEMPLATE, &context))
+}
+
+/// Reads `constants.macros` of the config, defaulting to constants.
+fn parse_constants(config: &Table) -> std::io::Result<bool> {
+    let constants = match config.get("constants") {
+        None => return Ok(false),
+        Some(Value::Table(constants)) => constants,
+        Some(_) => return Err(invalid("`constants` must be a table")),
+    };
+
+    match constants.get("macros") {
+        None => Ok(false),
+        Some(Value::Boolean(macros)) => Ok(*macros),
+        Some(_) => Err(invalid("`constants.macros` must be a boolean")),
+    }
+}
+
+fn size_context(size: u16) -> Context {
+    // the smallest signed value as a two's complement word, its sign bit and every bit above set
+    let bytes = size as usize / 8;
+    let min = format!(
+        "0x{}8{}",
+        "ff".repeat(32 - bytes),
+        "0".repeat(bytes * 2 - 1)
+    );
+
+    Context::new()
+        .text("size", size.to_string())
+        .text("unsigned_max", mask(size))
+        .text("signed_max", mask(size - 1))
+        .text("signed_min", min)
+}
+
+const LIBRARY_TEMPLATE: &str = r#"
+//  ------------------------------------------------------------------------------------------------
+//! # Constants Library
+//!
+//! Provides the bounds of the integer types of the sizes of `libcast.huff` and `libsigned.huff`,
+//! and common addresses{{#if macros}}, as macros pushing them{{/if}}.
+//!
+//! Bit sizes supported range from 8 to 256 inclusive and are multiples of 8. The smallest value of
+//! a signed type is its two's complement word, as `libsigned.huff` casts to it.
+{{#if macros}}//!
+//! The bounds are named after their type, `U8_MAX` rather than `MAX_U8`, as `libcmp.huff` names
+//! its maximums `MAX_U8`.
+{{/if}}//!
+//! ## API
+//!
+{{#if macros}}//! - `ZERO_ADDRESS` - The zero address.
+//! - `MAX_ADDRESS` - The largest address, the largest `uint160`.
+//!
+//! For a given size, `N`:
+//!
+//! - `UN_MAX` - The largest `uintN`.
+//! - `IN_MAX` - The largest `intN`.
+//! - `IN_MIN` - The smallest `intN`.
+
+/// ## Zero Address
+///
+/// Pushes the zero address.
+#define macro ZERO_ADDRESS() = takes (0) returns (1) { 0x00 }
+
+/// ## Max Address
+///
+/// Pushes the largest address, the largest `uint160`.
+#define macro MAX_ADDRESS() = takes (0) returns (1) { {{address_max}} }
+{{#each sizes}}
+/// ## U{{size}} Bounds
+///
+/// Pushes the largest `uint{{size}}`.
+#define macro U{{size}}_MAX() = takes (0) returns (1) { {{unsigned_max}} }
+
+/// ## I{{size}} Bounds
+///
+/// Pushes the largest and the smallest `int{{size}}`.
+#define macro I{{size}}_MAX() = takes (0) returns (1) { {{signed_max}} }
+#define macro I{{size}}_MIN() = takes (0) returns (1) { {{signed_min}} }
+{{/each}}{{else}}//! - `ZERO_ADDRESS` - The zero address.
+//! - `MAX_ADDRESS` - The largest address, the largest `uint160`.
+//!
+//! For a given size, `N`:
+//!
+//! - `MAX_UN` - The largest `uintN`.
+//! - `MAX_IN` - The largest `intN`.
+//! - `MIN_IN` - The smallest `intN`.
+
+/// ## Zero Address
+///
+/// The zero address.
+#define constant ZERO_ADDRESS = 0x00
+
+/// ## Max Address
+///
+/// The largest address, the largest `uint160`.
+#define constant MAX_ADDRESS = {{address_max}}
+{{#each sizes}}
+/// ## U{{size}} Bounds
+///
+/// The largest `uint{{size}}`.
+#define constant MAX_U{{size}} = {{unsigned_max}}
+
+/// ## I{{size}} Bounds
+///
+/// The largest and the smallest `int{{size}}`.
+#define constant MAX_I{{size}} = {{signed_max}}
+#define constant MIN_I{{size}} = {{signed_min}}
+{{/each}}{{/if}}"#;
@@ -0,0 +1,216 @@
+use crate::config::{invalid, Table, Value};
+use crate::emit::line;
+use crate::templates::{self, Context};
+
+pub fn render(config: &Table) -> std::io::Result<String> {
+    let events = parse_counter(config)?;
+
+    let header = if events {
+        format!("{}{}", HEADER, EVENT_DEFINITION)
+    } else {
+        HEADER.to_string()
+    };
+
+    let context = Context::new()
+        .flag("events", events)
+        .text("increment_store", store(events, "CounterIncremented"))
+        .text("decrement_store", store(events, "CounterDecremented"))
+        .text("reset_store", store(events, "CounterReset"));
+
+    let libcounter = format!(
+        "{}{}",
+        header,
+        templates::render(COUNTER_DEFINITION, &context)
+    );
+
+    Ok(libcounter)
+}
+
+/// Reads `counter.events` of the config, defaulting to no events.
+fn parse_counter(config: &Table) -> std::io::Result<bool> {
+    let counter = match config.get("counter") {
+        None => return Ok(false),
+        Some(Value::Table(counter)) => counter,
+        Some(_) => return Err(invalid("`counter` must be a table")),
+    };
+
+    match counter.get("events") {
+        None => Ok(false),
+        Some(Value::Boolean(events)) => Ok(*events),
+        Some(_) => Err(invalid("`counter.events` must be a boolean")),
+    }
+}
+
+/// Builds the lines storing the new count, logging `event` with it if events are enabled.
+fn store(events: bool, event: &str) -> String {
+    if !events {
+        return [line("<slot>", "[slot, count]"), line("sstore", "[]")].concat();
+    }
+
+    [
+        line("dup1", "[count, count]"),
+        line("<slot>", "[slot, count, count]"),
+        line("sstore", "[count]"),
+        line("0x00", "[ptr, count]"),
+        line("mstore", "[]"),
+        line("<slot>", "[slot]"),
+        line(&format!("__EVENT_HASH({})", event), "[sig, slot]"),
+        line("0x20", "[len, sig, slot]"),
+        line("0x00", "[ptr, len, sig, slot]"),
+        line("log2", "[]"),
+    ]
+    .concat()
+}
+
+const HEADER: &str = r#"
+//  ------------------------------------------------------------------------------------------------
+//! # Counter Library
+//!
+//! Provides storage counters that can only be incremented, decremented or reset, such as for
+//! token ids, nonces and element counts.
+//!
+//! Setting `counter.events` in `libhuff.toml` logs an event for every change of a counter.
+//!
+//! ## API
+//!
+//! - `COUNTER_CURRENT` - Loads the current count.
+//! - `COUNTER_INCREMENT` - Increments the count, unchecked.
+//! - `COUNTER_DECREMENT_CHECKED` - Decrements the count, reverting at zero.
+//! - `COUNTER_RESET` - Resets the count to zero.
+
+/// ## Counter Underflow Error
+///
+/// Thrown when decrementing a counter at zero.
+#define error CounterUnderflow()
+"#;
+
+const EVENT_DEFINITION: &str = r#"
+/// ## Counter Events
+///
+/// Logged with the slot of the counter and its new count.
+#define event CounterIncremented(bytes32 indexed, uint256)
+#define event CounterDecremented(bytes32 indexed, uint256)
+#define event CounterReset(bytes32 indexed, uint256)
+"#;
+
+const COUNTER_DEFINITION: &str = r#"
+/// ## Counter Current
+///
+/// Loads the current count of the counter at `slot`.
+///
+/// ### Template Arguments
+///
+/// - `slot`: storage slot of the counter.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define constant COUNTER_SLOT = FREE_STORAGE_POINTER()
+///
+/// #define macro MAIN() = takes (0) returns (0) {
+///     COUNTER_CURRENT([COUNTER_SLOT])
+///     0x00 mstore
+///     0x20 0x00 return
+/// }
+/// ```
+#define macro COUNTER_CURRENT(slot) = takes (0) returns (1) {
+    <slot>                  // [slot]
+    sload                   // [count]
+}
+
+/// ## Counter Increment
+///
+/// Increments the counter at `slot` by one.
+///
+/// The increment is unchecked. Counting up from zero by one, overflowing 256 bits would take
+/// more increments than could ever be paid for in gas, so the check is omitted.
+{{#if events}}///
+/// > WARNING: Memory from `0x00` to `0x20` will be overwritten.
+{{/if}}///
+/// ### Template Arguments
+///
+/// - `slot`: storage slot of the counter.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define constant COUNTER_SLOT = FREE_STORAGE_POINTER()
+///
+/// #define macro MAIN() = takes (0) returns (0) {
+///     COUNTER_INCREMENT([COUNTER_SLOT])
+///     stop
+/// }
+/// ```
+#define macro COUNTER_INCREMENT(slot) = takes (0) returns (0) {
+    <slot>                  // [slot]
+    sload                   // [count]
+    0x01                    // [0x01, count]
+    add                     // [count]
+{{increment_store}}}
+
+/// ## Counter Decrement Checked
+///
+/// Decrements the counter at `slot` by one.
+{{#if events}}///
+/// > WARNING: Memory from `0x00` to `0x20` will be overwritten.
+{{/if}}///
+/// ### Template Arguments
+///
+/// - `slot`: storage slot of the counter.
+///
+/// ### Panics
+///
+/// - if the count is zero.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define constant COUNTER_SLOT = FREE_STORAGE_POINTER()
+///
+/// #define macro MAIN() = takes (0) returns (0) {
+///     COUNTER_DECREMENT_CHECKED([COUNTER_SLOT])
+///     stop
+/// }
+/// ```
+#define macro COUNTER_DECREMENT_CHECKED(slot) = takes (0) returns (0) {
+    <slot>                  // [slot]
+    sload                   // [count]
+    dup1                    // [count, count]
+    not_zero                // [not_zero_dest, count, count]
+    jumpi                   // [count]
+        __ERROR(CounterUnderflow) // [err, count]
+        0x00                // [ptr, err, count]
+        mstore              // [count]
+        0x04                // [err_len, count]
+        0x00                // [ptr, err_len, count]
+        revert              // []
+    not_zero:               // [count]
+    0x01                    // [0x01, count]
+    swap1                   // [count, 0x01]
+    sub                     // [count]
+{{decrement_store}}}
+
+/// ## Counter Reset
+///
+/// Resets the counter at `slot` to zero.
+{{#if events}}///
+/// > WARNING: Memory from `0x00` to `0x20` will be overwritten.
+{{/if}}///
+/// ### Template Arguments
+///
+/// - `slot`: storage slot of the counter.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define constant COUNTER_SLOT = FREE_STORAGE_POINTER()
+///
+/// #define macro MAIN() = takes (0) returns (0) {
+///     COUNTER_RESET([COUNTER_SLOT])
+///     stop
+/// }
+/// ```
+#define macro COUNTER_RESET(slot) = takes (0) returns (0) {
+    0x00                    // [count]
+{{reset_store}}}
+"#;
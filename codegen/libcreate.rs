@@ -0,0 +1,136 @@
+pub fn render() -> String {
+    format!("{}{}", HEADER, ADDRESS_DEFINITION)
+}
+
+const HEADER: &str = r#"
+//  ------------------------------------------------------------------------------------------------
+//! # Create Library
+//!
+//! Provides prediction of the address of a contract deployed with `CREATE`, which is the last 20
+//! bytes of `keccak256(rlp([deployer, nonce]))`.
+//!
+//! With the nonce bounded to 64 bits (EIP-2681), the encoding is at most 31 bytes, so it is
+//! assembled in a single word instead of with the general RLP encoders.
+//!
+//! ## API
+//!
+//! - `COMPUTE_CREATE_ADDRESS` - Computes the address a deployer creates at a nonce.
+
+#include "librlp.huff"
+
+/// ## Invalid Nonce Error
+///
+/// Thrown when a nonce does not fit in 64 bits.
+#define error InvalidNonce()
+"#;
+
+const ADDRESS_DEFINITION: &str = r#"
+/// ## Compute Create Address
+///
+/// Computes the address of the contract created by `deployer` with `CREATE` at `nonce`.
+///
+/// > WARNING: Memory from `0x00` to `0x20` will be overwritten.
+///
+/// ### Stack Arguments
+///
+/// - `deployer`: (`stack[0]`) address of the deployer.
+/// - `nonce`: (`stack[1]`) nonce of the deployer.
+///
+/// ### Panics
+///
+/// - if `nonce` is at least `2 ** 64`.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x04 calldataload
+///     address
+///     COMPUTE_CREATE_ADDRESS()
+///     0x00 mstore
+///     0x20 0x00 return
+/// }
+/// ```
+#define macro COMPUTE_CREATE_ADDRESS() = takes (2) returns (1) {
+    // takes:               // [deployer, nonce]
+    dup2                    // [nonce, deployer, nonce]
+    0x40                    // [0x40, nonce, deployer, nonce]
+    shr                     // [overflow, deployer, nonce]
+    iszero                  // [is_valid, deployer, nonce]
+    is_valid                // [is_valid_dest, is_valid, deployer, nonce]
+    jumpi                   // [deployer, nonce]
+        __ERROR(InvalidNonce) // [err, deployer, nonce]
+        0x00                // [ptr, err, deployer, nonce]
+        mstore              // [deployer, nonce]
+        0x04                // [err_len, deployer, nonce]
+        0x00                // [ptr, err_len, deployer, nonce]
+        revert              // []
+    is_valid:               // [deployer, nonce]
+        0x60                // [0x60, deployer, nonce]
+        shl                 // [deployer, nonce]
+        0x10                // [0x10, deployer, nonce]
+        shr                 // [deployer_bits, nonce]
+        0x94                // [deployer_prefix, deployer_bits, nonce]
+        0xf0                // [0xf0, deployer_prefix, deployer_bits, nonce]
+        shl                 // [deployer_prefix_bits, deployer_bits, nonce]
+        or                  // [word, nonce]
+        swap1               // [nonce, word]
+        dup1                // [nonce, nonce, word]
+        0x80                // [0x80, nonce, nonce, word]
+        gt                  // [is_small, nonce, word]
+        dup2                // [nonce, is_small, nonce, word]
+        iszero              // [is_zero, is_small, nonce, word]
+        iszero              // [not_zero, is_small, nonce, word]
+        and                 // [is_byte, nonce, word]
+        single_byte         // [single_byte_dest, is_byte, nonce, word]
+        jumpi               // [nonce, word]
+        dup1                // [nonce, nonce, word]
+        __RLP_BYTE_LENGTH() // [nonce_len, nonce, word]
+        swap1               // [nonce, nonce_len, word]
+        dup2                // [nonce_len, nonce, nonce_len, word]
+        0x80                // [0x80, nonce_len, nonce, nonce_len, word]
+        add                 // [nonce_prefix, nonce, nonce_len, word]
+        dup3                // [nonce_len, nonce_prefix, nonce, nonce_len, word]
+        0x03                // [0x03, nonce_len, nonce_prefix, nonce, nonce_len, word]
+        shl                 // [nonce_bits, nonce_prefix, nonce, nonce_len, word]
+        shl                 // [nonce_prefix_bits, nonce, nonce_len, word]
+        or                  // [nonce_item, nonce_len, word]
+        swap1               // [nonce_len, nonce_item, word]
+        0x01                // [0x01, nonce_len, nonce_item, word]
+        add                 // [item_len, nonce_item, word]
+        encoded             // [encoded_dest, item_len, nonce_item, word]
+        jump                // [item_len, nonce_item, word]
+    single_byte:            // [nonce_item, word]
+        0x01                // [item_len, nonce_item, word]
+    encoded:                // [item_len, nonce_item, word]
+        swap1               // [nonce_item, item_len, word]
+        dup2                // [item_len, nonce_item, item_len, word]
+        0x03                // [0x03, item_len, nonce_item, item_len, word]
+        shl                 // [item_bits, nonce_item, item_len, word]
+        0x50                // [0x50, item_bits, nonce_item, item_len, word]
+        sub                 // [shift, nonce_item, item_len, word]
+        shl                 // [nonce_item_bits, item_len, word]
+        swap1               // [item_len, nonce_item_bits, word]
+        0x15                // [deployer_item_len, item_len, nonce_item_bits, word]
+        add                 // [payload_len, nonce_item_bits, word]
+        dup1                // [payload_len, payload_len, nonce_item_bits, word]
+        0xc0                // [0xc0, payload_len, payload_len, nonce_item_bits, word]
+        add                 // [list_prefix, payload_len, nonce_item_bits, word]
+        0xf8                // [0xf8, list_prefix, payload_len, nonce_item_bits, word]
+        shl                 // [list_prefix_bits, payload_len, nonce_item_bits, word]
+        swap1               // [payload_len, list_prefix_bits, nonce_item_bits, word]
+        swap3               // [word, list_prefix_bits, nonce_item_bits, payload_len]
+        or                  // [word, nonce_item_bits, payload_len]
+        or                  // [word, payload_len]
+        0x00                // [ptr, word, payload_len]
+        mstore              // [payload_len]
+        0x01                // [0x01, payload_len]
+        add                 // [len]
+        0x00                // [ptr, len]
+        sha3                // [hash]
+        0x60                // [0x60, hash]
+        shl                 // [address_bits]
+        0x60                // [0x60, address_bits]
+        shr                 // [address]
+}
+"#;
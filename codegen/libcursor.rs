@@ -0,0 +1,150 @@
+pub fn render() -> String {
+    format!("{}{}", HEADER, CURSOR_DEFINITION)
+}
+
+const HEADER: &str = r#"
+//  ------------------------------------------------------------------------------------------------
+//! # Cursor Library
+//!
+//! Provides a calldata cursor for decoding calldata sequentially.
+//!
+//! The cursor is a calldata offset kept on the stack. Every read advances it past the data read,
+//! and reverts with `OutOfBounds` if the data would overflow the offset or extend past the end of
+//! calldata.
+//!
+//! ## API
+//!
+//! - `CURSOR_INIT` - Creates a cursor at the first byte after the selector.
+//! - `CURSOR_READ_WORD` - Reads a word and advances the cursor.
+//! - `CURSOR_READ_BYTES` - Copies bytes to memory and advances the cursor.
+//! - `CURSOR_SKIP` - Advances the cursor without reading.
+
+#include "libkeccak.huff"
+"#;
+
+const CURSOR_DEFINITION: &str = r#"
+/// ## Cursor Init
+///
+/// Creates a cursor at the first byte after the selector.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     CURSOR_INIT()
+///     CURSOR_READ_WORD()
+/// }
+/// ```
+#define macro CURSOR_INIT() = takes (0) returns (1) {
+    0x04                    // [cursor]
+}
+
+/// ## Cursor Read Word
+///
+/// Reads the word at the cursor and advances it by 32 bytes.
+///
+/// ### Stack Arguments
+///
+/// - `cursor`: (`stack[0]`) calldata cursor.
+///
+/// ### Panics
+///
+/// - if the word extends past the end of calldata.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     CURSOR_INIT()
+///     CURSOR_READ_WORD()
+///     0x00 mstore
+///     0x20 0x00 return
+/// }
+/// ```
+#define macro CURSOR_READ_WORD() = takes (1) returns (2) {
+    // takes:               // [cursor]
+    dup1                    // [cursor, cursor]
+    0x20                    // [0x20, cursor, cursor]
+    add                     // [next, cursor]
+    swap1                   // [cursor, next]
+    dup2                    // [next, cursor, next]
+    __CHECK_CALLDATA_RANGE() // [cursor, next]
+    calldataload            // [word, next]
+}
+
+/// ## Cursor Read Bytes
+///
+/// Copies bytes at the cursor to memory and advances it past them.
+///
+/// > WARNING: Memory from `ptr` to `ptr + len` will be overwritten.
+///
+/// ### Stack Arguments
+///
+/// - `cursor`: (`stack[0]`) calldata cursor.
+/// - `ptr`: (`stack[1]`) memory pointer to copy the bytes to.
+/// - `len`: (`stack[2]`) number of bytes to read.
+///
+/// ### Panics
+///
+/// - if the bytes overflow the cursor or extend past the end of calldata.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     CURSOR_INIT()
+///     CURSOR_READ_WORD()      // [len, cursor]
+///     dup1 0x00 dup4          // [cursor, ptr, len, len, cursor]
+///     CURSOR_READ_BYTES()     // [next, len, cursor]
+/// }
+/// ```
+#define macro CURSOR_READ_BYTES() = takes (3) returns (1) {
+    // takes:               // [cursor, ptr, len]
+    dup3                    // [len, cursor, ptr, len]
+    dup2                    // [cursor, len, cursor, ptr, len]
+    add                     // [next, cursor, ptr, len]
+    swap1                   // [cursor, next, ptr, len]
+    dup2                    // [next, cursor, next, ptr, len]
+    __CHECK_CALLDATA_RANGE() // [cursor, next, ptr, len]
+    swap2                   // [ptr, next, cursor, len]
+    swap1                   // [next, ptr, cursor, len]
+    swap3                   // [len, ptr, cursor, next]
+    swap2                   // [cursor, ptr, len, next]
+    swap1                   // [ptr, cursor, len, next]
+    calldatacopy            // [next]
+}
+
+/// ## Cursor Skip
+///
+/// Advances the cursor without reading.
+///
+/// ### Stack Arguments
+///
+/// - `cursor`: (`stack[0]`) calldata cursor.
+/// - `len`: (`stack[1]`) number of bytes to skip.
+///
+/// ### Panics
+///
+/// - if the skipped bytes overflow the cursor or extend past the end of calldata.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x20
+///     CURSOR_INIT()
+///     CURSOR_SKIP()
+///     CURSOR_READ_WORD()
+/// }
+/// ```
+#define macro CURSOR_SKIP() = takes (2) returns (1) {
+    // takes:               // [cursor, len]
+    dup1                    // [cursor, cursor, len]
+    swap2                   // [len, cursor, cursor]
+    add                     // [next, cursor]
+    swap1                   // [cursor, next]
+    dup2                    // [next, cursor, next]
+    __CHECK_CALLDATA_RANGE() // [cursor, next]
+    pop                     // [next]
+}
+"#;
@@ -0,0 +1,263 @@
+pub fn render() -> String {
+    format!("{}{}{}", HEADER, LINEAR_DEFINITION, EXP_DEFINITION)
+}
+
+const HEADER: &str = r#"
+//  ------------------------------------------------------------------------------------------------
+//! # Decay Library
+//!
+//! Provides prices decaying over time from the block timestamp, such as the price of a Dutch
+//! auction.
+//!
+//! Products are split into quotient and remainder terms, `(a / d) * b + (a % d) * b / d`, so the
+//! decayed prices are exact for any price without a 512 bit intermediate.
+//!
+//! ## API
+//!
+//! - `LINEAR_DECAY` - Computes a price decaying linearly to an end price.
+//! - `EXP_DECAY` - Computes a price decaying exponentially by a factor per second.
+
+#include "libdecimal.huff"
+
+/// ## Invalid Decay Error
+///
+/// Thrown when decay parameters are out of range.
+#define error InvalidDecay()
+"#;
+
+const LINEAR_DEFINITION: &str = r#"
+/// ## Linear Decay
+///
+/// Computes the price decaying linearly from `start_price` at `start_time` to `end_price` at
+/// `start_time + duration`, and constant before and after.
+///
+/// The decrease is rounded down, so the price is rounded up.
+///
+/// ### Stack Arguments
+///
+/// - `start_price`: (`stack[0]`) price until `start_time`.
+/// - `end_price`: (`stack[1]`) price from `start_time + duration`.
+/// - `start_time`: (`stack[2]`) timestamp the decay starts at.
+/// - `duration`: (`stack[3]`) duration of the decay in seconds.
+///
+/// ### Panics
+///
+/// - if `end_price` exceeds `start_price`.
+/// - if `duration` is at least `2 ** 128`.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x0e10                  // 1 hour
+///     0x02 sload              // start time
+///     0x01 sload              // end price
+///     0x00 sload              // start price
+///     LINEAR_DECAY()
+///     0x00 mstore
+///     0x20 0x00 return
+/// }
+/// ```
+#define macro LINEAR_DECAY() = takes (4) returns (1) {
+    // takes:               // [start_price, end_price, start_time, duration]
+    dup2                    // [end_price, start_price, end_price, start_time, duration]
+    dup2                    // [start_price, end_price, start_price, end_price, start_time, duration]
+    lt                      // [is_rising, start_price, end_price, start_time, duration]
+    dup5                    // [duration, is_rising, start_price, end_price, start_time, duration]
+    0x80                    // [0x80, duration, is_rising, start_price, end_price, start_time, duration]
+    shr                     // [is_too_long, is_rising, start_price, end_price, start_time, duration]
+    or                      // [is_invalid, start_price, end_price, start_time, duration]
+    iszero                  // [is_valid, start_price, end_price, start_time, duration]
+    is_valid                // [is_valid_dest, is_valid, start_price, end_price, start_time, duration]
+    jumpi                   // [start_price, end_price, start_time, duration]
+        __ERROR(InvalidDecay) // [err, start_price, end_price, start_time, duration]
+        0x00                // [ptr, err, start_price, end_price, start_time, duration]
+        mstore              // [start_price, end_price, start_time, duration]
+        0x04                // [err_len, start_price, end_price, start_time, duration]
+        0x00                // [ptr, err_len, start_price, end_price, start_time, duration]
+        revert              // []
+    is_valid:               // [start_price, end_price, start_time, duration]
+        dup3                // [start_time, start_price, end_price, start_time, duration]
+        timestamp           // [timestamp, start_time, start_price, end_price, start_time, duration]
+        gt                  // [has_started, start_price, end_price, start_time, duration]
+        has_started         // [has_started_dest, has_started, start_price, end_price, start_time, duration]
+        jumpi               // [start_price, end_price, start_time, duration]
+        swap3               // [duration, end_price, start_time, start_price]
+        pop                 // [end_price, start_time, start_price]
+        pop                 // [start_time, start_price]
+        pop                 // [start_price]
+        decayed             // [decayed_dest, start_price]
+        jump                // [start_price]
+    has_started:            // [start_price, end_price, start_time, duration]
+        swap2               // [start_time, end_price, start_price, duration]
+        timestamp           // [timestamp, start_time, end_price, start_price, duration]
+        sub                 // [elapsed, end_price, start_price, duration]
+        dup4                // [duration, elapsed, end_price, start_price, duration]
+        dup2                // [elapsed, duration, elapsed, end_price, start_price, duration]
+        lt                  // [is_decaying, elapsed, end_price, start_price, duration]
+        is_decaying         // [is_decaying_dest, is_decaying, elapsed, end_price, start_price, duration]
+        jumpi               // [elapsed, end_price, start_price, duration]
+        pop                 // [end_price, start_price, duration]
+        swap2               // [duration, start_price, end_price]
+        pop                 // [start_price, end_price]
+        pop                 // [end_price]
+        decayed             // [decayed_dest, end_price]
+        jump                // [end_price]
+    is_decaying:            // [elapsed, end_price, start_price, duration]
+        swap1               // [end_price, elapsed, start_price, duration]
+        dup3                // [start_price, end_price, elapsed, start_price, duration]
+        sub                 // [range, elapsed, start_price, duration]
+        dup4                // [duration, range, elapsed, start_price, duration]
+        dup2                // [range, duration, range, elapsed, start_price, duration]
+        mod                 // [range_rem, range, elapsed, start_price, duration]
+        dup3                // [elapsed, range_rem, range, elapsed, start_price, duration]
+        mul                 // [rem_product, range, elapsed, start_price, duration]
+        dup5                // [duration, rem_product, range, elapsed, start_price, duration]
+        swap1               // [rem_product, duration, range, elapsed, start_price, duration]
+        div                 // [rem_decrease, range, elapsed, start_price, duration]
+        swap1               // [range, rem_decrease, elapsed, start_price, duration]
+        dup5                // [duration, range, rem_decrease, elapsed, start_price, duration]
+        swap1               // [range, duration, rem_decrease, elapsed, start_price, duration]
+        div                 // [range_quot, rem_decrease, elapsed, start_price, duration]
+        dup3                // [elapsed, range_quot, rem_decrease, elapsed, start_price, duration]
+        mul                 // [quot_decrease, rem_decrease, elapsed, start_price, duration]
+        add                 // [decrease, elapsed, start_price, duration]
+        swap1               // [elapsed, decrease, start_price, duration]
+        pop                 // [decrease, start_price, duration]
+        swap1               // [start_price, decrease, duration]
+        sub                 // [price, duration]
+        swap1               // [duration, price]
+        pop                 // [price]
+    decayed:                // [price]
+}
+"#;
+
+const EXP_DEFINITION: &str = r#"
+/// ## Exponential Decay
+///
+/// Computes the price decaying from `start_price` at `start_time` by `factor`, an 18 decimal
+/// fixed point ratio of at most one, every second, that is
+/// `start_price * factor ** (timestamp - start_time)`.
+///
+/// The power of `factor` rounds half up at every multiplication, and the price is rounded down.
+///
+/// ### Stack Arguments
+///
+/// - `start_price`: (`stack[0]`) price until `start_time`.
+/// - `start_time`: (`stack[1]`) timestamp the decay starts at.
+/// - `factor`: (`stack[2]`) ratio the price keeps every second, with 18 decimals.
+///
+/// ### Panics
+///
+/// - if `factor` exceeds `10 ** 18`.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x0de0ad9b58f16000      // 0.99999 per second
+///     0x01 sload              // start time
+///     0x00 sload              // start price
+///     EXP_DECAY()
+///     0x00 mstore
+///     0x20 0x00 return
+/// }
+/// ```
+#define macro EXP_DECAY() = takes (3) returns (1) {
+    // takes:               // [start_price, start_time, factor]
+    dup3                    // [factor, start_price, start_time, factor]
+    [POW10_18]              // [wad, factor, start_price, start_time, factor]
+    lt                      // [is_invalid, start_price, start_time, factor]
+    iszero                  // [is_valid, start_price, start_time, factor]
+    is_valid                // [is_valid_dest, is_valid, start_price, start_time, factor]
+    jumpi                   // [start_price, start_time, factor]
+        __ERROR(InvalidDecay) // [err, start_price, start_time, factor]
+        0x00                // [ptr, err, start_price, start_time, factor]
+        mstore              // [start_price, start_time, factor]
+        0x04                // [err_len, start_price, start_time, factor]
+        0x00                // [ptr, err_len, start_price, start_time, factor]
+        revert              // []
+    is_valid:               // [start_price, start_time, factor]
+        dup2                // [start_time, start_price, start_time, factor]
+        timestamp           // [timestamp, start_time, start_price, start_time, factor]
+        gt                  // [has_started, start_price, start_time, factor]
+        has_started         // [has_started_dest, has_started, start_price, start_time, factor]
+        jumpi               // [start_price, start_time, factor]
+        swap2               // [factor, start_time, start_price]
+        pop                 // [start_time, start_price]
+        pop                 // [start_price]
+        decayed             // [decayed_dest, start_price]
+        jump                // [start_price]
+    has_started:            // [start_price, start_time, factor]
+        swap1               // [start_time, start_price, factor]
+        timestamp           // [timestamp, start_time, start_price, factor]
+        sub                 // [elapsed, start_price, factor]
+        swap1               // [start_price, elapsed, factor]
+        swap2               // [factor, elapsed, start_price]
+        __DECAY_RPOW()      // [ratio, start_price]
+        [POW10_18]          // [wad, ratio, start_price]
+        dup3                // [start_price, wad, ratio, start_price]
+        mod                 // [price_rem, ratio, start_price]
+        dup2                // [ratio, price_rem, ratio, start_price]
+        mul                 // [rem_product, ratio, start_price]
+        [POW10_18]          // [wad, rem_product, ratio, start_price]
+        swap1               // [rem_product, wad, ratio, start_price]
+        div                 // [rem_price, ratio, start_price]
+        swap2               // [start_price, ratio, rem_price]
+        [POW10_18]          // [wad, start_price, ratio, rem_price]
+        swap1               // [start_price, wad, ratio, rem_price]
+        div                 // [price_quot, ratio, rem_price]
+        mul                 // [quot_price, rem_price]
+        add                 // [price]
+    decayed:                // [price]
+}
+
+/// ## Decay Rpow
+///
+/// Raises an 18 decimal fixed point value of at most one to a power by squaring, rounding half up.
+#define macro __DECAY_RPOW() = takes (2) returns (1) {
+    // takes:               // [base, exponent]
+    [POW10_18]              // [result, base, exponent]
+    rpow_loop:              // [result, base, exponent]
+        dup3                // [exponent, result, base, exponent]
+        iszero              // [is_done, result, base, exponent]
+        rpow_done           // [rpow_done_dest, is_done, result, base, exponent]
+        jumpi               // [result, base, exponent]
+        dup3                // [exponent, result, base, exponent]
+        0x01                // [0x01, exponent, result, base, exponent]
+        and                 // [is_odd, result, base, exponent]
+        iszero              // [is_even, result, base, exponent]
+        rpow_square         // [rpow_square_dest, is_even, result, base, exponent]
+        jumpi               // [result, base, exponent]
+        dup2                // [base, result, base, exponent]
+        __DECAY_WAD_MUL()   // [result, base, exponent]
+    rpow_square:            // [result, base, exponent]
+        swap1               // [base, result, exponent]
+        dup1                // [base, base, result, exponent]
+        __DECAY_WAD_MUL()   // [base, result, exponent]
+        swap1               // [result, base, exponent]
+        swap2               // [exponent, base, result]
+        0x01                // [0x01, exponent, base, result]
+        shr                 // [exponent, base, result]
+        swap2               // [result, base, exponent]
+        rpow_loop           // [rpow_loop_dest, result, base, exponent]
+        jump                // [result, base, exponent]
+    rpow_done:              // [result, base, exponent]
+        swap2               // [exponent, base, result]
+        pop                 // [base, result]
+        pop                 // [result]
+}
+
+/// ## Decay Wad Mul
+///
+/// Multiplies two 18 decimal fixed point values of at most one, rounding half up.
+#define macro __DECAY_WAD_MUL() = takes (2) returns (1) {
+    // takes:               // [a, b]
+    mul                     // [product]
+    0x06f05b59d3b20000      // [half_wad, product]
+    add                     // [rounded]
+    [POW10_18]              // [wad, rounded]
+    swap1                   // [rounded, wad]
+    div                     // [result]
+}
+"#;
@@ -0,0 +1,255 @@
+use crate::emit::line;
+use crate::templates::{self, Context};
+
+/// Largest exponent of ten that fits in a word.
+const MAX_EXPONENT: usize = 77;
+
+pub fn render() -> String {
+    let mut power = [0u64; 4];
+    power[3] = 1;
+
+    let constants = (0..=MAX_EXPONENT)
+        .map(|exponent| {
+            let context = Context::new()
+                .text("exponent", exponent.to_string())
+                .text("power", hex(&power));
+            let constant = templates::render(CONSTANT_TEMPLATE, &context);
+            power = times_ten(&power);
+            constant
+        })
+        .collect::<String>();
+    let max_line = line(
+        &format!("{:#04x}", MAX_EXPONENT + 1),
+        "[max_exponent, exponent, exponent]",
+    );
+
+    format!(
+        "{}{}{}",
+        HEADER,
+        constants,
+        templates::render(
+            SCALE_DEFINITION,
+            &Context::new().text("max_exponent_line", max_line)
+        ),
+    )
+}
+
+/// Multiplies a big endian word of 64 bit limbs by ten.
+fn times_ten(word: &[u64; 4]) -> [u64; 4] {
+    let mut product = [0u64; 4];
+    let mut carry = 0u128;
+    for i in (0..4).rev() {
+        let limb = word[i] as u128 * 10 + carry;
+        product[i] = limb as u64;
+        carry = limb >> 64;
+    }
+    product
+}
+
+/// Formats a big endian word of 64 bit limbs as a minimal hex literal.
+fn hex(word: &[u64; 4]) -> String {
+    let digits = word
+        .iter()
+        .map(|limb| format!("{:016x}", limb))
+        .collect::<String>();
+    let digits = digits.trim_start_matches('0');
+    let digits = if digits.len() % 2 == 1 {
+        format!("0{}", digits)
+    } else {
+        digits.to_string()
+    };
+
+    format!("0x{}", digits)
+}
+
+const HEADER: &str = r#"
+//  ------------------------------------------------------------------------------------------------
+//! # Decimal Library
+//!
+//! Provides powers of ten and scaling of fixed point values between decimals, such as normalizing
+//! token amounts of differing decimals in price and vault math.
+//!
+//! ## API
+//!
+//! - `POW10_N` - `10 ** N`, for `N` from 0 to 77.
+//! - `POW10` - Computes a power of ten.
+//! - `SCALE_UP` - Scales a value to more decimals.
+//! - `SCALE_DOWN` - Scales a value to fewer decimals, truncating.
+
+#include "libcast.huff"
+
+/// ## Invalid Decimals Error
+///
+/// Thrown when scaling a value in the wrong direction.
+#define error InvalidDecimals()
+"#;
+
+const CONSTANT_TEMPLATE: &str = r#"
+/// ## Pow10 {{exponent}}
+///
+/// `10 ** {{exponent}}`.
+#define constant POW10_{{exponent}} = {{power}}
+"#;
+
+const SCALE_DEFINITION: &str = r#"
+/// ## Pow10
+///
+/// Computes `10 ** exponent`.
+///
+/// ### Stack Arguments
+///
+/// - `exponent`: (`stack[0]`) exponent of ten.
+///
+/// ### Panics
+///
+/// - if the power overflows, that is if `exponent` exceeds 77.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x12
+///     POW10()
+///     0x00 mstore
+///     0x20 0x00 return
+/// }
+/// ```
+#define macro POW10() = takes (1) returns (1) {
+    // takes:               // [exponent]
+    dup1                    // [exponent, exponent]
+{{max_exponent_line}}    gt                      // [is_safe, exponent]
+    is_safe                 // [is_safe_dest, is_safe, exponent]
+    jumpi                   // [exponent]
+        __ERROR(Overflow)   // [err, exponent]
+        0x00                // [ptr, err, exponent]
+        mstore              // [exponent]
+        0x04                // [err_len, exponent]
+        0x00                // [ptr, err_len, exponent]
+        revert              // []
+    is_safe:                // [exponent]
+        0x0a                // [0x0a, exponent]
+        exp                 // [power]
+}
+
+/// ## Scale Up
+///
+/// Scales a value of `decimals_from` decimals to `decimals_to` decimals.
+///
+/// ### Template Arguments
+///
+/// - `decimals_from`: decimals of the value.
+/// - `decimals_to`: decimals to scale the value to.
+///
+/// ### Stack Arguments
+///
+/// - `value`: (`stack[0]`) value to scale.
+///
+/// ### Panics
+///
+/// - if `decimals_from` exceeds `decimals_to`.
+/// - if the scaled value overflows.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x04 calldataload
+///     SCALE_UP(0x06, 0x12)
+///     0x00 mstore
+///     0x20 0x00 return
+/// }
+/// ```
+#define macro SCALE_UP(decimals_from, decimals_to) = takes (1) returns (1) {
+    // takes:               // [value]
+    <decimals_to>           // [decimals_to, value]
+    <decimals_from>         // [decimals_from, decimals_to, value]
+    dup2                    // [decimals_to, decimals_from, decimals_to, value]
+    dup2                    // [decimals_from, decimals_to, decimals_from, decimals_to, value]
+    gt                      // [is_down, decimals_from, decimals_to, value]
+    iszero                  // [is_up, decimals_from, decimals_to, value]
+    is_up                   // [is_up_dest, is_up, decimals_from, decimals_to, value]
+    jumpi                   // [decimals_from, decimals_to, value]
+        __ERROR(InvalidDecimals) // [err, decimals_from, decimals_to, value]
+        0x00                // [ptr, err, decimals_from, decimals_to, value]
+        mstore              // [decimals_from, decimals_to, value]
+        0x04                // [err_len, decimals_from, decimals_to, value]
+        0x00                // [ptr, err_len, decimals_from, decimals_to, value]
+        revert              // []
+    is_up:                  // [decimals_from, decimals_to, value]
+        swap1               // [decimals_to, decimals_from, value]
+        sub                 // [exponent, value]
+        POW10()             // [factor, value]
+        dup2                // [value, factor, value]
+        dup2                // [factor, value, factor, value]
+        mul                 // [scaled, factor, value]
+        swap1               // [factor, scaled, value]
+        dup2                // [scaled, factor, scaled, value]
+        div                 // [unscaled, scaled, value]
+        dup3                // [value, unscaled, scaled, value]
+        eq                  // [in_range, scaled, value]
+        in_range            // [in_range_dest, in_range, scaled, value]
+        jumpi               // [scaled, value]
+            __ERROR(Overflow) // [err, scaled, value]
+            0x00            // [ptr, err, scaled, value]
+            mstore          // [scaled, value]
+            0x04            // [err_len, scaled, value]
+            0x00            // [ptr, err_len, scaled, value]
+            revert          // []
+        in_range:           // [scaled, value]
+            swap1           // [value, scaled]
+            pop             // [scaled]
+}
+
+/// ## Scale Down
+///
+/// Scales a value of `decimals_from` decimals to `decimals_to` decimals, truncating the
+/// remainder.
+///
+/// ### Template Arguments
+///
+/// - `decimals_from`: decimals of the value.
+/// - `decimals_to`: decimals to scale the value to.
+///
+/// ### Stack Arguments
+///
+/// - `value`: (`stack[0]`) value to scale.
+///
+/// ### Panics
+///
+/// - if `decimals_to` exceeds `decimals_from`.
+/// - if the decimals differ by more than 77.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x04 calldataload
+///     SCALE_DOWN(0x12, 0x06)
+///     0x00 mstore
+///     0x20 0x00 return
+/// }
+/// ```
+#define macro SCALE_DOWN(decimals_from, decimals_to) = takes (1) returns (1) {
+    // takes:               // [value]
+    <decimals_from>         // [decimals_from, value]
+    <decimals_to>           // [decimals_to, decimals_from, value]
+    dup2                    // [decimals_from, decimals_to, decimals_from, value]
+    dup2                    // [decimals_to, decimals_from, decimals_to, decimals_from, value]
+    gt                      // [is_up, decimals_to, decimals_from, value]
+    iszero                  // [is_down, decimals_to, decimals_from, value]
+    is_down                 // [is_down_dest, is_down, decimals_to, decimals_from, value]
+    jumpi                   // [decimals_to, decimals_from, value]
+        __ERROR(InvalidDecimals) // [err, decimals_to, decimals_from, value]
+        0x00                // [ptr, err, decimals_to, decimals_from, value]
+        mstore              // [decimals_to, decimals_from, value]
+        0x04                // [err_len, decimals_to, decimals_from, value]
+        0x00                // [ptr, err_len, decimals_to, decimals_from, value]
+        revert              // []
+    is_down:                // [decimals_to, decimals_from, value]
+        swap1               // [decimals_from, decimals_to, value]
+        sub                 // [exponent, value]
+        POW10()             // [factor, value]
+        swap1               // [value, factor]
+        div                 // [scaled]
+}
+"#;
@@ -0,0 +1,699 @@
+use std::collections::HashSet;
+
+use crate::config::{identifier, invalid, Table, Value};
+use crate::emit::line;
+use crate::templates::{self, Context};
+
+/// Largest head size of a static type, keeping every head offset well within calldata sizes.
+const MAX_HEAD_SIZE: u128 = 1 << 32;
+
+/// Names of the indices of nested arrays in docs, from the outermost array.
+const INDICES: [&str; 4] = ["i", "j", "k", "l"];
+
+/// An ABI type.
+enum Type {
+    /// A static elementary type, decoded to its word.
+    Elementary(String),
+    /// `bytes` or `string`.
+    Bytes(String),
+    /// An array of the element type, with a fixed length if any.
+    Array(Box<Type>, Option<u128>),
+    Tuple(Vec<Type>),
+}
+
+impl Type {
+    fn parse(s: &str) -> Option<Type> {
+        let (mut ty, mut rest) = if s.starts_with('(') {
+            let close = closing_paren(s)?;
+            let members = split_members(&s[1..close])?
+                .into_iter()
+                .map(Type::parse)
+                .collect::<Option<Vec<Type>>>()?;
+            (Type::Tuple(members), &s[close + 1..])
+        } else {
+            let end = s.find('[').unwrap_or(s.len());
+            (Type::elementary(&s[..end])?, &s[end..])
+        };
+
+        while !rest.is_empty() {
+            let (len, tail) = rest.strip_prefix('[')?.split_once(']')?;
+            let len = match len {
+                "" => None,
+                len => Some(len.parse::<u128>().ok().filter(|len| *len > 0)?),
+            };
+            ty = Type::Array(Box::new(ty), len);
+            rest = tail;
+        }
+
+        Some(ty).filter(|ty| ty.head_size() <= MAX_HEAD_SIZE)
+    }
+
+    fn elementary(name: &str) -> Option<Type> {
+        let bits = |digits: &str| {
+            digits
+                .parse::<u16>()
+                .is_ok_and(|bits| bits > 0 && bits <= 256 && bits % 8 == 0)
+        };
+        let valid = match name {
+            "address" | "bool" => true,
+            "bytes" | "string" => return Some(Type::Bytes(name.to_string())),
+            _ => match (
+                name.strip_prefix("uint"),
+                name.strip_prefix("int"),
+                name.strip_prefix("bytes"),
+            ) {
+                (Some(digits), _, _) | (_, Some(digits), _) => bits(digits),
+                (_, _, Some(digits)) => digits
+                    .parse::<u8>()
+                    .is_ok_and(|len| (1..=32).contains(&len)),
+                _ => false,
+            },
+        };
+        Some(Type::Elementary(name.to_string())).filter(|_| valid)
+    }
+
+    fn is_dynamic(&self) -> bool {
+        match self {
+            Type::Elementary(_) => false,
+            Type::Bytes(_) => true,
+            Type::Array(element, len) => len.is_none() || element.is_dynamic(),
+            Type::Tuple(members) => members.iter().any(Type::is_dynamic),
+        }
+    }
+
+    /// Size of the type in the head of its enclosing tuple, saturating on overflow.
+    fn head_size(&self) -> u128 {
+        match self {
+            _ if self.is_dynamic() => 0x20,
+            Type::Array(element, Some(len)) => element.head_size().saturating_mul(*len),
+            Type::Tuple(members) => members.iter().fold(0u128, |size, member| {
+                size.saturating_add(member.head_size())
+            }),
+            _ => 0x20,
+        }
+    }
+
+    fn canonical(&self) -> String {
+        match self {
+            Type::Elementary(name) | Type::Bytes(name) => name.clone(),
+            Type::Array(element, None) => format!("{}[]", element.canonical()),
+            Type::Array(element, Some(len)) => format!("{}[{}]", element.canonical(), len),
+            Type::Tuple(members) => format!(
+                "({})",
+                members
+                    .iter()
+                    .map(Type::canonical)
+                    .collect::<Vec<String>>()
+                    .join(",")
+            ),
+        }
+    }
+}
+
+/// Finds the parenthesis closing the one opening `s`.
+fn closing_paren(s: &str) -> Option<usize> {
+    let mut depth = 0;
+    for (i, c) in s.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Splits the members of a tuple at its top level commas, rejecting empty members.
+fn split_members(s: &str) -> Option<Vec<&str>> {
+    let mut members = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for (i, c) in s.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ',' if depth == 0 => {
+                members.push(&s[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    members.push(&s[start..]);
+    Some(members).filter(|members| members.iter().all(|member| !member.is_empty()))
+}
+
+struct Param {
+    name: String,
+    ty: Type,
+}
+
+struct Decoder {
+    name: String,
+    params: Vec<Param>,
+}
+
+/// A step of the accessor chain reaching a type, for usage examples.
+struct Step {
+    takes_index: bool,
+    accessor: String,
+}
+
+/// Renders `libdecoders.huff` from the `[[decoder]]` entries of the config, if any.
+pub fn render(config: &Table) -> std::io::Result<Option<String>> {
+    let decoders = parse_decoders(config)?;
+
+    if decoders.is_empty() {
+        return Ok(None);
+    }
+
+    let libdecoders = format!(
+        "{}{}{}",
+        HEADER,
+        HELPER_DEFINITION,
+        decoders.iter().map(generate_decoder).collect::<String>(),
+    );
+
+    Ok(Some(libdecoders))
+}
+
+fn parse_decoders(config: &Table) -> std::io::Result<Vec<Decoder>> {
+    let entries = match config.get("decoder") {
+        None => return Ok(Vec::new()),
+        Some(Value::Array(entries)) => entries,
+        Some(_) => return Err(invalid("`decoder` must be an array of tables")),
+    };
+
+    let mut names = HashSet::new();
+
+    entries
+        .iter()
+        .map(|entry| {
+            let entry = entry
+                .as_table()
+                .ok_or_else(|| invalid("`decoder` must be an array of tables"))?;
+            let name = identifier(entry, "name", "decoder")?;
+
+            if !names.insert(name.clone()) {
+                return Err(invalid(format!("duplicate decoder `{}`", name)));
+            }
+
+            let mut param_names = HashSet::new();
+            let params = entry
+                .get("params")
+                .and_then(Value::as_array)
+                .filter(|params| !params.is_empty())
+                .ok_or_else(|| invalid(format!("decoder `{}` requires `params`", name)))?
+                .iter()
+                .map(|param| {
+                    let param = param.as_table().ok_or_else(|| {
+                        invalid(format!("params of decoder `{}` must be tables", name))
+                    })?;
+                    let param_name = identifier(param, "name", &format!("param of `{}`", name))?;
+
+                    if !param_names.insert(param_name.clone()) {
+                        return Err(invalid(format!(
+                            "duplicate param `{}.{}`",
+                            name, param_name
+                        )));
+                    }
+
+                    let ty = param
+                        .get("type")
+                        .and_then(Value::as_str)
+                        .and_then(Type::parse)
+                        .ok_or_else(|| {
+                            invalid(format!(
+                                "param `{}.{}` requires a canonical ABI `type`, e.g. \"uint256[]\"",
+                                name, param_name
+                            ))
+                        })?;
+
+                    Ok(Param {
+                        name: param_name,
+                        ty,
+                    })
+                })
+                .collect::<std::io::Result<Vec<Param>>>()?;
+
+            Ok(Decoder { name, params })
+        })
+        .collect()
+}
+
+/// An accessor macro of a decoder.
+struct Accessor {
+    name: String,
+    doc: String,
+    /// Description of the location taken, and whether an index is taken under it, if any.
+    takes: Option<(String, bool)>,
+    returns: u8,
+    panics: String,
+    usage: String,
+    body: String,
+}
+
+impl Accessor {
+    fn render(&self) -> String {
+        let (takes, location, takes_index) = match &self.takes {
+            None => (0, "", false),
+            Some((desc, takes_index)) => (1 + *takes_index as u8, desc.as_str(), *takes_index),
+        };
+
+        let context = Context::new()
+            .text("title", title(&self.name))
+            .text("doc", doc(&self.doc))
+            .flag("takes_location", self.takes.is_some())
+            .text("location", location)
+            .flag("takes_index", takes_index)
+            .text("panics", self.panics.as_str())
+            .text("usage", self.usage.as_str())
+            .text("name", self.name.as_str())
+            .text("takes", takes.to_string())
+            .text("returns", self.returns.to_string())
+            .text("body", self.body.as_str());
+        templates::render(ACCESSOR_TEMPLATE, &context)
+    }
+}
+
+fn generate_decoder(decoder: &Decoder) -> String {
+    let mut out = String::new();
+    let mut offset = 0x04;
+
+    for param in &decoder.params {
+        let name = format!("DECODE_{}_{}", decoder.name, param.name);
+
+        let mut body = line("0x04", "[base]");
+        body.push_str(&line(&format!("0x{:02x}", offset), "[head, base]"));
+        body.push_str(&load(&param.ty));
+
+        let steps = [Step {
+            takes_index: false,
+            accessor: name.clone(),
+        }];
+
+        out.push_str(
+            &Accessor {
+                doc: format!(
+                    "Decodes the `{}` parameter of `{}`, a `{}`, {}.",
+                    param.name,
+                    decoder.name,
+                    param.ty.canonical(),
+                    returns(&param.ty)
+                ),
+                takes: None,
+                returns: 1,
+                panics: format!("the head of `{}` is out of bounds", param.name),
+                usage: usage(&steps),
+                body,
+                name: name.clone(),
+            }
+            .render(),
+        );
+
+        generate_nested(&name, &param.name, &param.ty, &steps, &mut out);
+
+        offset += param.ty.head_size();
+    }
+
+    out
+}
+
+/// Generates the accessors of the types nested in `ty`, the type at `desc` reached by `steps`.
+fn generate_nested(prefix: &str, desc: &str, ty: &Type, steps: &[Step], out: &mut String) {
+    match ty {
+        Type::Elementary(_) => {}
+        Type::Bytes(type_name) => {
+            out.push_str(&length(prefix, desc, type_name, steps));
+
+            let name = format!("{}_DATA", prefix);
+            let mut body = line("// takes:", "[loc]");
+            body.push_str(&line("dup1", "[loc, loc]"));
+            body.push_str(&line("__DECODE_WORD()", "[len, loc]"));
+            body.push_str(&line("swap1", "[loc, len]"));
+            body.push_str(&line("0x20", "[0x20, loc, len]"));
+            body.push_str(&line("add", "[data, len]"));
+            body.push_str(&line("dup2", "[len, data, len]"));
+            body.push_str(&line("dup2", "[data, len, data, len]"));
+            body.push_str(&line("add", "[end, data, len]"));
+            body.push_str(&line("__CHECK_CALLDATA_RANGE()", "[data, len]"));
+
+            out.push_str(
+                &Accessor {
+                    doc: format!(
+                        "Decodes `{}`, a `{}`, returning the calldata offset and length of its data.",
+                        desc, type_name
+                    ),
+                    takes: Some((desc.to_string(), false)),
+                    returns: 2,
+                    panics: format!("the data of `{}` is out of bounds", desc),
+                    usage: usage(&steps_with(steps, false, &name)),
+                    body,
+                    name,
+                }
+                .render(),
+            );
+        }
+        Type::Array(element, len) => {
+            if len.is_none() {
+                out.push_str(&length(prefix, desc, &ty.canonical(), steps));
+            }
+
+            let name = format!("{}_AT", prefix);
+            let mut body = line("// takes:", "[loc, i]");
+            body.push_str(&line("dup2", "[i, loc, i]"));
+            match len {
+                None => {
+                    body.push_str(&line("dup2", "[loc, i, loc, i]"));
+                    body.push_str(&line("__DECODE_WORD()", "[len, i, loc, i]"));
+                }
+                Some(len) => body.push_str(&line(&format!("0x{:02x}", len), "[len, i, loc, i]")),
+            }
+            body.push_str(&line("__DECODE_CHECK_INDEX()", "[loc, i]"));
+            if len.is_none() {
+                body.push_str(&line("0x20", "[0x20, loc, i]"));
+                body.push_str(&line("add", "[base, i]"));
+            }
+            body.push_str(&line("swap1", "[i, base]"));
+            match element.head_size() {
+                0x20 => {
+                    body.push_str(&line("0x05", "[0x05, i, base]"));
+                    body.push_str(&line("shl", "[rel_head, base]"));
+                }
+                size => {
+                    body.push_str(&line(&format!("0x{:02x}", size), "[size, i, base]"));
+                    body.push_str(&line("mul", "[rel_head, base]"));
+                }
+            }
+            body.push_str(&line("dup2", "[base, rel_head, base]"));
+            body.push_str(&line("add", "[head, base]"));
+            body.push_str(&load(element));
+
+            let index =
+                INDICES[steps.iter().filter(|step| step.takes_index).count() % INDICES.len()];
+            let item = format!("{}[{}]", desc, index);
+            let steps = steps_with(steps, true, &name);
+
+            out.push_str(
+                &Accessor {
+                    doc: format!(
+                        "Decodes `{}`, a `{}` element of `{}`, {}.",
+                        item,
+                        element.canonical(),
+                        desc,
+                        returns(element)
+                    ),
+                    takes: Some((desc.to_string(), true)),
+                    returns: 1,
+                    panics: format!(
+                        "`i` is out of range, or the head of `{}` is out of bounds",
+                        item
+                    ),
+                    usage: usage(&steps),
+                    body,
+                    name,
+                }
+                .render(),
+            );
+
+            generate_nested(&format!("{}_ITEM", prefix), &item, element, &steps, out);
+        }
+        Type::Tuple(members) => {
+            let mut offset = 0;
+            for (i, member) in members.iter().enumerate() {
+                let name = format!("{}_{}", prefix, i);
+                let mut body = line("// takes:", "[base]");
+                if offset > 0 {
+                    body.push_str(&line("dup1", "[base, base]"));
+                    body.push_str(&line(
+                        &format!("0x{:02x}", offset),
+                        "[rel_head, base, base]",
+                    ));
+                    body.push_str(&line("add", "[head, base]"));
+                } else {
+                    body.push_str(&line("dup1", "[head, base]"));
+                }
+                body.push_str(&load(member));
+
+                let item = format!("{}.{}", desc, i);
+                let steps = steps_with(steps, false, &name);
+
+                out.push_str(
+                    &Accessor {
+                        doc: format!(
+                            "Decodes `{}`, the `{}` member {} of `{}`, {}.",
+                            item,
+                            member.canonical(),
+                            i,
+                            desc,
+                            returns(member)
+                        ),
+                        takes: Some((desc.to_string(), false)),
+                        returns: 1,
+                        panics: format!("the head of `{}` is out of bounds", item),
+                        usage: usage(&steps),
+                        body,
+                        name: name.clone(),
+                    }
+                    .render(),
+                );
+
+                generate_nested(&name, &item, member, &steps, out);
+
+                offset += member.head_size();
+            }
+        }
+    }
+}
+
+/// Generates the length accessor of a dynamic array, `bytes` or `string`.
+fn length(prefix: &str, desc: &str, type_name: &str, steps: &[Step]) -> String {
+    let name = format!("{}_LENGTH", prefix);
+    let mut body = line("// takes:", "[loc]");
+    body.push_str(&line("__DECODE_WORD()", "[len]"));
+
+    Accessor {
+        doc: format!("Loads the length of `{}`, a `{}`.", desc, type_name),
+        takes: Some((desc.to_string(), false)),
+        returns: 1,
+        panics: format!("the length of `{}` is out of bounds", desc),
+        usage: usage(&steps_with(steps, false, &name)),
+        body,
+        name,
+    }
+    .render()
+}
+
+/// Loads the type with its head at `head` in the tuple at `base`, validating any offset.
+fn load(ty: &Type) -> String {
+    if ty.is_dynamic() {
+        line("__DECODE_OFFSET()", "[loc]")
+    } else if let Type::Elementary(_) = ty {
+        let mut code = line("__DECODE_WORD()", "[value, base]");
+        code.push_str(&line("swap1", "[base, value]"));
+        code.push_str(&line("pop", "[value]"));
+        code
+    } else {
+        let mut code = line("dup1", "[head, head, base]");
+        code.push_str(&line(
+            &format!("0x{:02x}", ty.head_size()),
+            "[size, head, head, base]",
+        ));
+        code.push_str(&line("add", "[end, head, base]"));
+        code.push_str(&line("__CHECK_CALLDATA_RANGE()", "[head, base]"));
+        code.push_str(&line("swap1", "[base, loc]"));
+        code.push_str(&line("pop", "[loc]"));
+        code
+    }
+}
+
+fn returns(ty: &Type) -> &'static str {
+    match ty {
+        Type::Elementary(_) => "returning its value",
+        _ if ty.is_dynamic() => "returning the calldata location of its encoding",
+        _ => "returning the calldata location of its inline encoding",
+    }
+}
+
+fn title(name: &str) -> String {
+    name.split('_')
+        .map(|part| {
+            let mut chars = part.chars();
+            match chars.next() {
+                Some(c) => c
+                    .to_uppercase()
+                    .chain(chars.flat_map(char::to_lowercase))
+                    .collect(),
+                None => String::new(),
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+/// Wraps a doc comment paragraph to the repo's line width.
+fn doc(text: &str) -> String {
+    let mut out = String::new();
+    let mut current = String::from("///");
+    for word in text.split(' ') {
+        if current.len() + 1 + word.len() > 100 {
+            out.push_str(&current);
+            out.push('\n');
+            current = String::from("///");
+        }
+        current.push(' ');
+        current.push_str(word);
+    }
+    out.push_str(&current);
+    out.push('\n');
+    out
+}
+
+fn steps_with(steps: &[Step], takes_index: bool, accessor: &str) -> Vec<Step> {
+    steps
+        .iter()
+        .map(|step| Step {
+            takes_index: step.takes_index,
+            accessor: step.accessor.clone(),
+        })
+        .chain(std::iter::once(Step {
+            takes_index,
+            accessor: accessor.to_string(),
+        }))
+        .collect()
+}
+
+/// Builds a usage body calling the accessor chain, pushing indices of the deepest array first.
+fn usage(steps: &[Step]) -> String {
+    let mut usage = String::new();
+    for step in steps.iter().rev().filter(|step| step.takes_index) {
+        usage.push_str(&format!(
+            "///     {:<24}// index of `{}`\n",
+            "0x00", step.accessor
+        ));
+    }
+    for step in steps {
+        usage.push_str(&format!("///     {}()\n", step.accessor));
+    }
+    usage
+}
+
+const HEADER: &str = r#"
+//  ------------------------------------------------------------------------------------------------
+//! # Decoders Library
+//!
+//! Provides bounds checked decoders for the ABI encoded calldata declared in `libhuff.toml`.
+//!
+//! Static elementary values are decoded to their word. Any other value is decoded to its location,
+//! the calldata offset of its encoding, which the accessors of its nested types take on the stack.
+//! Every offset is validated against calldata at each level of nesting, so a malformed encoding
+//! reverts instead of decoding out of bounds.
+//!
+//! ## API
+//!
+//! For a given decoder, `NAME`, and parameter, `param`:
+//!
+//! - `DECODE_NAME_param` - Decodes a parameter.
+//! - `DECODE_NAME_param_LENGTH` - Loads the length of a dynamic array, `bytes` or `string`.
+//! - `DECODE_NAME_param_DATA` - Decodes the calldata offset and length of a `bytes` or `string`.
+//! - `DECODE_NAME_param_AT` - Decodes an element of an array.
+//! - `DECODE_NAME_param_N` - Decodes member `N` of a tuple.
+//!
+//! Accessors of the elements of an array are suffixed with `_ITEM`, and of the members of a tuple
+//! with the member index, e.g. `DECODE_NAME_param_ITEM_0_DATA` for the `bytes` members of an
+//! array of tuples.
+
+#include "libkeccak.huff"
+"#;
+
+const HELPER_DEFINITION: &str = r#"
+/// ## Decode Word
+///
+/// Loads a word of calldata, reverting if it is out of bounds.
+#define macro __DECODE_WORD() = takes (1) returns (1) {
+    // takes:               // [ptr]
+    dup1                    // [ptr, ptr]
+    0x20                    // [0x20, ptr, ptr]
+    add                     // [end, ptr]
+    __CHECK_CALLDATA_RANGE() // [ptr]
+    calldataload            // [word]
+}
+
+/// ## Decode Offset
+///
+/// Resolves the offset at `head` relative to `base`, reverting if it overflows or the location is
+/// out of bounds.
+#define macro __DECODE_OFFSET() = takes (2) returns (1) {
+    // takes:               // [head, base]
+    __DECODE_WORD()         // [rel, base]
+    dup2                    // [base, rel, base]
+    add                     // [loc, base]
+    swap1                   // [base, loc]
+    dup2                    // [loc, base, loc]
+    lt                      // [overflowed, loc]
+    iszero                  // [in_bounds, loc]
+    in_bounds               // [in_bounds_dest, in_bounds, loc]
+    jumpi                   // [loc]
+        __ERROR(OutOfBounds) // [err, loc]
+        0x00                // [ptr, err, loc]
+        mstore              // [loc]
+        0x04                // [err_len, loc]
+        0x00                // [ptr, err_len, loc]
+        revert              // []
+    in_bounds:              // [loc]
+        dup1                // [loc, loc]
+        0x20                // [0x20, loc, loc]
+        add                 // [end, loc]
+        __CHECK_CALLDATA_RANGE() // [loc]
+}
+
+/// ## Decode Check Index
+///
+/// Reverts if `i` is not less than `len`, or too large to index calldata.
+#define macro __DECODE_CHECK_INDEX() = takes (2) returns (0) {
+    // takes:               // [len, i]
+    dup2                    // [i, len, i]
+    lt                      // [in_range, i]
+    swap1                   // [i, in_range]
+    calldatasize            // [calldatasize, i, in_range]
+    gt                      // [in_calldata, in_range]
+    and                     // [valid]
+    valid                   // [valid_dest, valid]
+    jumpi                   // []
+        __ERROR(OutOfBounds) // [err]
+        0x00                // [ptr, err]
+        mstore              // []
+        0x04                // [err_len]
+        0x00                // [ptr, err_len]
+        revert              // []
+    valid:                  // []
+}
+"#;
+
+const ACCESSOR_TEMPLATE: &str = r#"
+/// ## {{title}}
+///
+{{doc}}{{#if takes_location}}///
+/// ### Stack Arguments
+///
+/// - `loc`: (`stack[0]`) calldata location of `{{location}}`.
+{{#if takes_index}}/// - `i`: (`stack[1]`) index of the element.
+{{/if}}{{/if}}///
+/// ### Panics
+///
+/// - if {{panics}}.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+{{usage}}/// }
+/// ```
+#define macro {{name}}() = takes ({{takes}}) returns ({{returns}}) {
+{{body}}}
+"#;
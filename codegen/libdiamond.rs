@@ -0,0 +1,591 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::config::{identifier, invalid, Table, Value};
+use crate::emit::line;
+use crate::keccak::keccak256;
+use crate::templates::{self, Context};
+
+/// Diamond storage position of the EIP-2535 reference implementation.
+const STORAGE_ID: &str = "diamond.standard.diamond.storage";
+
+/// Signature of the EIP-2535 `DiamondCut` event.
+const DIAMOND_CUT_EVENT: &str = "DiamondCut((address,uint8,bytes4[])[],address,bytes)";
+
+struct Function {
+    signature: String,
+    selector: u32,
+}
+
+struct Facet {
+    name: String,
+    functions: Vec<Function>,
+}
+
+/// Renders `libdiamond.huff` from the `[[facet]]` entries of the config, if any.
+pub fn render(config: &Table) -> std::io::Result<Option<String>> {
+    let facets = parse_facets(config)?;
+
+    if facets.is_empty() {
+        return Ok(None);
+    }
+
+    let routing = Context::new()
+        .text("storage_position", hex(&keccak256(STORAGE_ID.as_bytes())))
+        .text(
+            "event_signature",
+            hex(&keccak256(DIAMOND_CUT_EVENT.as_bytes())),
+        );
+    let libdiamond = format!(
+        "{}{}{}",
+        templates::render(
+            HEADER,
+            &Context::new().list("functions", facet_table(&facets))
+        ),
+        templates::render(ROUTING_DEFINITION, &routing),
+        facets.iter().map(generate_facet).collect::<String>(),
+    );
+
+    Ok(Some(libdiamond))
+}
+
+fn parse_facets(config: &Table) -> std::io::Result<Vec<Facet>> {
+    let entries = match config.get("facet") {
+        None => return Ok(Vec::new()),
+        Some(Value::Array(entries)) => entries,
+        Some(_) => return Err(invalid("`facet` must be an array of tables")),
+    };
+
+    let mut facet_names = HashSet::new();
+    let mut selectors = HashMap::new();
+
+    entries
+        .iter()
+        .map(|entry| {
+            let entry = entry
+                .as_table()
+                .ok_or_else(|| invalid("`facet` must be an array of tables"))?;
+            let name = identifier(entry, "name", "facet")?;
+
+            if !facet_names.insert(name.clone()) {
+                return Err(invalid(format!("duplicate facet `{}`", name)));
+            }
+
+            let functions = entry
+                .get("functions")
+                .and_then(Value::as_array)
+                .filter(|functions| !functions.is_empty())
+                .ok_or_else(|| invalid(format!("facet `{}` requires `functions`", name)))?
+                .iter()
+                .map(|function| {
+                    let signature = function
+                        .as_str()
+                        .filter(|signature| is_signature(signature))
+                        .ok_or_else(|| {
+                            invalid(format!(
+                                "functions of facet `{}` must be signatures, e.g. \"owner()\"",
+                                name
+                            ))
+                        })?
+                        .to_string();
+                    let hash = keccak256(signature.as_bytes());
+                    let selector = u32::from_be_bytes([hash[0], hash[1], hash[2], hash[3]]);
+
+                    if let Some(existing) = selectors.insert(selector, signature.clone()) {
+                        return Err(invalid(format!(
+                            "selector 0x{:08x} of `{}` collides with `{}`",
+                            selector, signature, existing
+                        )));
+                    }
+
+                    Ok(Function {
+                        signature,
+                        selector,
+                    })
+                })
+                .collect::<std::io::Result<Vec<Function>>>()?;
+
+            Ok(Facet { name, functions })
+        })
+        .collect()
+}
+
+/// Checks a function signature is canonical, a name followed by parenthesised types.
+fn is_signature(signature: &str) -> bool {
+    match signature.split_once('(') {
+        Some((name, rest)) => {
+            name.chars()
+                .next()
+                .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+                && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+                && rest.ends_with(')')
+                && !rest.contains(char::is_whitespace)
+        }
+        None => false,
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    format!(
+        "0x{}",
+        bytes
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect::<String>()
+    )
+}
+
+fn facet_table(facets: &[Facet]) -> Vec<Context> {
+    facets
+        .iter()
+        .flat_map(|facet| {
+            facet.functions.iter().map(move |function| {
+                Context::new()
+                    .text("facet", facet.name.as_str())
+                    .text("selector", format!("{:08x}", function.selector))
+                    .text("signature", function.signature.as_str())
+            })
+        })
+        .collect()
+}
+
+/// Builds a line of a reverting branch, indented one level deeper than `line`.
+fn branch(code: &str, comment: &str) -> String {
+    format!("        {:<19} // {}\n", code, comment)
+}
+
+fn generate_facet(facet: &Facet) -> String {
+    let selectors = facet
+        .functions
+        .iter()
+        .map(|function| {
+            Context::new()
+                .text("selector", format!("{:08x}", function.selector))
+                .text("signature", function.signature.as_str())
+        })
+        .collect();
+
+    let mut add = line("// takes:", "[facet]");
+    add.push_str(&line("dup1", "[facet, facet]"));
+    add.push_str(&line("__DIAMOND_REQUIRE_CODE()", "[facet]"));
+    let mut replace = add.clone();
+    let mut remove = String::new();
+
+    for (i, function) in facet.functions.iter().enumerate() {
+        let selector = format!("0x{:08x}", function.selector);
+        let free = format!("selector_{}_free", i);
+        let found = format!("selector_{}_found", i);
+
+        add.push_str(&line(&selector, "[selector, facet]"));
+        add.push_str(&line(
+            "LOAD_MAPPING([DIAMOND_STORAGE_POSITION])",
+            "[current, facet]",
+        ));
+        add.push_str(&line("iszero", "[is_free, facet]"));
+        add.push_str(&line(&free, "[free_dest, is_free, facet]"));
+        add.push_str(&line("jumpi", "[facet]"));
+        add.push_str(&branch(&selector, "[selector, facet]"));
+        add.push_str(&branch("__DIAMOND_FUNCTION_EXISTS()", "[]"));
+        add.push_str(&line(&format!("{}:", free), "[facet]"));
+        add.push_str(&line("dup1", "[facet, facet]"));
+        add.push_str(&line(&selector, "[selector, facet, facet]"));
+        add.push_str(&line(
+            "STORE_MAPPING([DIAMOND_STORAGE_POSITION])",
+            "[facet]",
+        ));
+
+        for (body, stack) in [(&mut replace, ", facet"), (&mut remove, "")] {
+            body.push_str(&line(&selector, &format!("[selector{}]", stack)));
+            body.push_str(&line(
+                "LOAD_MAPPING([DIAMOND_STORAGE_POSITION])",
+                &format!("[current{}]", stack),
+            ));
+            body.push_str(&line(&found, &format!("[found_dest, current{}]", stack)));
+            body.push_str(&line(
+                "jumpi",
+                &format!("[{}]", stack.trim_start_matches(", ")),
+            ));
+            body.push_str(&branch(&selector, &format!("[selector{}]", stack)));
+            body.push_str(&branch("__DIAMOND_FUNCTION_NOT_FOUND()", "[]"));
+            body.push_str(&line(
+                &format!("{}:", found),
+                &format!("[{}]", stack.trim_start_matches(", ")),
+            ));
+        }
+
+        replace.push_str(&line("dup1", "[facet, facet]"));
+        replace.push_str(&line(&selector, "[selector, facet, facet]"));
+        replace.push_str(&line(
+            "STORE_MAPPING([DIAMOND_STORAGE_POSITION])",
+            "[facet]",
+        ));
+
+        remove.push_str(&line("0x00", "[zero]"));
+        remove.push_str(&line(&selector, "[selector, zero]"));
+        remove.push_str(&line("STORE_MAPPING([DIAMOND_STORAGE_POSITION])", "[]"));
+    }
+
+    let name = &facet.name;
+    add.push_str(&line(&format!("__DIAMOND_CUT_EVENT_{}(0x00)", name), "[]"));
+    replace.push_str(&line(&format!("__DIAMOND_CUT_EVENT_{}(0x01)", name), "[]"));
+    remove.push_str(&line("0x00", "[zero]"));
+    remove.push_str(&line(&format!("__DIAMOND_CUT_EVENT_{}(0x02)", name), "[]"));
+
+    let context = Context::new()
+        .text("name", name.as_str())
+        .list("selectors", selectors)
+        .text("add_body", add)
+        .text("replace_body", replace)
+        .text("remove_body", remove)
+        .text("event_body", cut_event(facet));
+    templates::render(FACET_TEMPLATE, &context)
+}
+
+/// Builds the body writing the ABI encoded `DiamondCut` data of a single facet cut without an
+/// initializer to memory, then logging it.
+fn cut_event(facet: &Facet) -> String {
+    let count = facet.functions.len();
+    let calldata_ptr = 0x120 + 0x20 * count;
+    let len = calldata_ptr + 0x20;
+
+    let mut body = line("// takes:", "[facet]");
+    body.push_str(&line("0xa0", "[ptr, facet]"));
+    body.push_str(&line("mstore", "[]"));
+
+    let words = [
+        (0x60, 0x00, "cuts_offset"),
+        (0x00, 0x20, "init"),
+        (calldata_ptr, 0x40, "calldata_offset"),
+        (0x01, 0x60, "cuts_len"),
+        (0x20, 0x80, "cut_offset"),
+    ];
+
+    for (value, ptr, name) in words {
+        body.push_str(&line(&format!("0x{:02x}", value), &format!("[{}]", name)));
+        body.push_str(&line(
+            &format!("0x{:02x}", ptr),
+            &format!("[ptr, {}]", name),
+        ));
+        body.push_str(&line("mstore", "[]"));
+    }
+
+    body.push_str(&line("<action>", "[action]"));
+    body.push_str(&line("0xc0", "[ptr, action]"));
+    body.push_str(&line("mstore", "[]"));
+
+    let words = [
+        (0x60, 0xe0, "selectors_offset"),
+        (count, 0x100, "selectors_len"),
+    ];
+
+    for (value, ptr, name) in words {
+        body.push_str(&line(&format!("0x{:02x}", value), &format!("[{}]", name)));
+        body.push_str(&line(
+            &format!("0x{:02x}", ptr),
+            &format!("[ptr, {}]", name),
+        ));
+        body.push_str(&line("mstore", "[]"));
+    }
+
+    for (i, function) in facet.functions.iter().enumerate() {
+        body.push_str(&line(&format!("0x{:08x}", function.selector), "[selector]"));
+        body.push_str(&line("0xe0", "[shift, selector]"));
+        body.push_str(&line("shl", "[selector]"));
+        body.push_str(&line(
+            &format!("0x{:02x}", 0x120 + 0x20 * i),
+            "[ptr, selector]",
+        ));
+        body.push_str(&line("mstore", "[]"));
+    }
+
+    body.push_str(&line("0x00", "[calldata_len]"));
+    body.push_str(&line(
+        &format!("0x{:02x}", calldata_ptr),
+        "[ptr, calldata_len]",
+    ));
+    body.push_str(&line("mstore", "[]"));
+    body.push_str(&line("[DIAMOND_CUT_EVENT_SIGNATURE]", "[sig]"));
+    body.push_str(&line(&format!("0x{:02x}", len), "[len, sig]"));
+    body.push_str(&line("0x00", "[ptr, len, sig]"));
+    body.push_str(&line("log1", "[]"));
+
+    body
+}
+
+const HEADER: &str = r#"
+//  ------------------------------------------------------------------------------------------------
+//! # Diamond Library
+//!
+//! Provides EIP-2535 diamond routing for the facets declared in `libhuff.toml`.
+//!
+//! Selectors map to facet addresses in storage at the position of the EIP-2535 reference
+//! implementation, `keccak256("diamond.standard.diamond.storage")`. `DIAMOND_FALLBACK` delegates
+//! calls to the facet of their selector, and the generated cut macros add, replace, or remove
+//! every function of a facet at once, emitting `DiamondCut` without an initializer.
+//!
+//! | Facet | Selector | Function |
+//! | ----- | -------- | -------- |
+{{#each functions}}//! | `{{facet}}` | `0x{{selector}}` | `{{signature}}` |
+{{/each}}//!
+//! ## API
+//!
+//! - `DIAMOND_FACET_ADDRESS` - Loads the facet of a selector.
+//! - `DIAMOND_FALLBACK` - Delegates the call to the facet of its selector.
+//!
+//! For a given facet, `FACET`:
+//!
+//! - `DIAMOND_ADD_FACET` - Routes the functions of the facet to an address.
+//! - `DIAMOND_REPLACE_FACET` - Reroutes the functions of the facet to an address.
+//! - `DIAMOND_REMOVE_FACET` - Removes the routes of the functions of the facet.
+
+#include "libmapping.huff"
+"#;
+
+const ROUTING_DEFINITION: &str = r#"
+/// ## Diamond Storage Position
+///
+/// Storage slot of the mapping of selectors to facet addresses.
+#define constant DIAMOND_STORAGE_POSITION = {{storage_position}}
+
+/// ## Diamond Cut Event Signature
+///
+/// Topic of `DiamondCut((address,uint8,bytes4[])[],address,bytes)`.
+#define constant DIAMOND_CUT_EVENT_SIGNATURE = {{event_signature}}
+
+/// ## Function Not Found Error
+///
+/// Thrown when a selector has no facet.
+#define error FunctionNotFound(bytes4)
+
+/// ## Function Already Exists Error
+///
+/// Thrown when adding a selector that already has a facet.
+#define error FunctionAlreadyExists(bytes4)
+
+/// ## Facet Has No Code Error
+///
+/// Thrown when routing selectors to an address without code.
+#define error FacetHasNoCode(address)
+
+/// ## Diamond Facet Address
+///
+/// Loads the facet of a selector, or zero if it has none.
+///
+/// > WARNING: Memory from `0x00` to `0x40` will be overwritten.
+///
+/// ### Stack Arguments
+///
+/// - `selector`: (`stack[0]`) right aligned function selector.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x04 calldataload 0xe0 shr
+///     DIAMOND_FACET_ADDRESS()
+///     0x00 mstore
+///     0x20 0x00 return
+/// }
+/// ```
+#define macro DIAMOND_FACET_ADDRESS() = takes (1) returns (1) {
+    // takes:               // [selector]
+    LOAD_MAPPING([DIAMOND_STORAGE_POSITION]) // [facet]
+}
+
+/// ## Diamond Fallback
+///
+/// Delegates the call to the facet of its selector, returning or reverting with its returndata.
+///
+/// > WARNING: Memory from `0x00` to `calldatasize` and `returndatasize` will be overwritten.
+///
+/// ### Panics
+///
+/// - if the selector has no facet
+/// - if the facet reverts
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     DIAMOND_FALLBACK()
+/// }
+/// ```
+#define macro DIAMOND_FALLBACK() = takes (0) returns (0) {
+    0x00                    // [ptr]
+    calldataload            // [word]
+    0xe0                    // [shift, word]
+    shr                     // [selector]
+    dup1                    // [selector, selector]
+    DIAMOND_FACET_ADDRESS() // [facet, selector]
+    dup1                    // [facet, facet, selector]
+    found                   // [found_dest, facet, facet, selector]
+    jumpi                   // [facet, selector]
+        pop                 // [selector]
+        __DIAMOND_FUNCTION_NOT_FOUND() // []
+    found:                  // [facet, selector]
+    swap1                   // [selector, facet]
+    pop                     // [facet]
+    calldatasize            // [calldata_len, facet]
+    0x00                    // [calldata_offset, calldata_len, facet]
+    0x00                    // [ptr, calldata_offset, calldata_len, facet]
+    calldatacopy            // [facet]
+    0x00                    // [ret_len, facet]
+    0x00                    // [ret_ptr, ret_len, facet]
+    calldatasize            // [arg_len, ret_ptr, ret_len, facet]
+    0x00                    // [arg_ptr, arg_len, ret_ptr, ret_len, facet]
+    dup5                    // [facet, arg_ptr, arg_len, ret_ptr, ret_len, facet]
+    gas                     // [gas, facet, arg_ptr, arg_len, ret_ptr, ret_len, facet]
+    delegatecall            // [success, facet]
+    returndatasize          // [returndata_len, success, facet]
+    0x00                    // [returndata_offset, returndata_len, success, facet]
+    0x00                    // [ptr, returndata_offset, returndata_len, success, facet]
+    returndatacopy          // [success, facet]
+    success                 // [success_dest, success, facet]
+    jumpi                   // [facet]
+        returndatasize      // [returndata_len, facet]
+        0x00                // [ptr, returndata_len, facet]
+        revert              // []
+    success:                // [facet]
+    returndatasize          // [returndata_len, facet]
+    0x00                    // [ptr, returndata_len, facet]
+    return                  // []
+}
+
+/// ## Require Code
+///
+/// Reverts with `FacetHasNoCode` if an address has no code.
+#define macro __DIAMOND_REQUIRE_CODE() = takes (1) returns (0) {
+    // takes:               // [facet]
+    dup1                    // [facet, facet]
+    extcodesize             // [code_size, facet]
+    has_code                // [has_code_dest, code_size, facet]
+    jumpi                   // [facet]
+        __ERROR(FacetHasNoCode) // [err, facet]
+        0x00                // [ptr, err, facet]
+        mstore              // [facet]
+        0x04                // [ptr, facet]
+        mstore              // []
+        0x24                // [err_len]
+        0x00                // [ptr, err_len]
+        revert              // []
+    has_code:               // [facet]
+    pop                     // []
+}
+
+/// ## Function Not Found
+///
+/// Reverts with `FunctionNotFound` for a selector.
+#define macro __DIAMOND_FUNCTION_NOT_FOUND() = takes (1) returns (0) {
+    // takes:               // [selector]
+    0xe0                    // [shift, selector]
+    shl                     // [selector]
+    __ERROR(FunctionNotFound) // [err, selector]
+    0x00                    // [ptr, err, selector]
+    mstore                  // [selector]
+    0x04                    // [ptr, selector]
+    mstore                  // []
+    0x24                    // [err_len]
+    0x00                    // [ptr, err_len]
+    revert                  // []
+}
+
+/// ## Function Already Exists
+///
+/// Reverts with `FunctionAlreadyExists` for a selector.
+#define macro __DIAMOND_FUNCTION_EXISTS() = takes (1) returns (0) {
+    // takes:               // [selector]
+    0xe0                    // [shift, selector]
+    shl                     // [selector]
+    __ERROR(FunctionAlreadyExists) // [err, selector]
+    0x00                    // [ptr, err, selector]
+    mstore                  // [selector]
+    0x04                    // [ptr, selector]
+    mstore                  // []
+    0x24                    // [err_len]
+    0x00                    // [ptr, err_len]
+    revert                  // []
+}
+"#;
+
+const FACET_TEMPLATE: &str = r#"
+/// ## Diamond Add {{name}}
+///
+/// Routes the functions of `{{name}}` to a facet address and emits `DiamondCut`.
+///
+{{#each selectors}}/// - `0x{{selector}}`: `{{signature}}`
+{{/each}}///
+/// > WARNING: Memory from `0x00` to the end of the `DiamondCut` data will be overwritten.
+///
+/// ### Stack Arguments
+///
+/// - `facet`: (`stack[0]`) address of the facet.
+///
+/// ### Panics
+///
+/// - if `facet` has no code
+/// - if any of the functions already has a facet
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro CONSTRUCTOR() = takes (0) returns (0) {
+///     0x00 calldataload
+///     DIAMOND_ADD_{{name}}()
+/// }
+/// ```
+#define macro DIAMOND_ADD_{{name}}() = takes (1) returns (0) {
+{{add_body}}}
+
+/// ## Diamond Replace {{name}}
+///
+/// Reroutes the functions of `{{name}}` to a facet address and emits `DiamondCut`.
+///
+/// > WARNING: Memory from `0x00` to the end of the `DiamondCut` data will be overwritten.
+///
+/// ### Stack Arguments
+///
+/// - `facet`: (`stack[0]`) address of the facet.
+///
+/// ### Panics
+///
+/// - if `facet` has no code
+/// - if any of the functions has no facet
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x04 calldataload
+///     DIAMOND_REPLACE_{{name}}()
+///     stop
+/// }
+/// ```
+#define macro DIAMOND_REPLACE_{{name}}() = takes (1) returns (0) {
+{{replace_body}}}
+
+/// ## Diamond Remove {{name}}
+///
+/// Removes the routes of the functions of `{{name}}` and emits `DiamondCut`.
+///
+/// > WARNING: Memory from `0x00` to the end of the `DiamondCut` data will be overwritten.
+///
+/// ### Panics
+///
+/// - if any of the functions has no facet
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     DIAMOND_REMOVE_{{name}}()
+///     stop
+/// }
+/// ```
+#define macro DIAMOND_REMOVE_{{name}}() = takes (0) returns (0) {
+{{remove_body}}}
+
+/// ## Diamond Cut Event {{name}}
+///
+/// Emits `DiamondCut` for a cut of every function of `{{name}}` without an initializer.
+#define macro __DIAMOND_CUT_EVENT_{{name}}(action) = takes (1) returns (0) {
+{{event_body}}}
+"#;
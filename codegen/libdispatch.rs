@@ -0,0 +1,1128 @@
+use std::collections::{BTreeSet, HashSet};
+
+use crate::abi::Abi;
+use crate::config::{identifier, invalid, Table, Value};
+use crate::emit::line;
+use crate::keccak::keccak256;
+use crate::profile::Profile;
+use crate::templates::{self, Context};
+
+/// Largest jump table, in entries, searched for a collision free index.
+const MAX_TABLE_SIZE: u32 = 1 << 12;
+
+/// Largest range of selectors the binary search strategy compares linearly.
+const BINARY_LEAF_SIZE: usize = 3;
+
+/// Gas of a push, dup or swap, and the other very low tier opcodes.
+const G_VERYLOW: u64 = 3;
+
+/// Gas of `jumpi`.
+const G_HIGH: u64 = 10;
+
+/// Gas of `jump`.
+const G_MID: u64 = 8;
+
+/// Gas of `pop`.
+const G_BASE: u64 = 2;
+
+/// Gas of `jumpdest`.
+const G_JUMPDEST: u64 = 1;
+
+/// Bytes of a push of a label, `PUSH2`.
+const LABEL_SIZE: u32 = 3;
+
+/// Gas of comparing the selector against a constant and jumping, `dup1 push eq push jumpi`.
+const G_COMPARE: u64 = 4 * G_VERYLOW + G_HIGH;
+
+#[derive(Clone, Copy, PartialEq)]
+enum Strategy {
+    Linear,
+    Binary,
+    JumpTable,
+}
+
+impl Strategy {
+    const ALL: [Strategy; 3] = [Strategy::Linear, Strategy::Binary, Strategy::JumpTable];
+
+    fn parse(name: &str) -> Option<Self> {
+        match name {
+            "linear" => Some(Strategy::Linear),
+            "binary" => Some(Strategy::Binary),
+            "jump_table" => Some(Strategy::JumpTable),
+            _ => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Strategy::Linear => "linear",
+            Strategy::Binary => "binary",
+            Strategy::JumpTable => "jump_table",
+        }
+    }
+}
+
+/// Handling of ether sent to a function of a dispatcher.
+#[derive(Clone, Copy, PartialEq)]
+enum EtherPolicy {
+    /// Accepts ether.
+    Payable,
+    /// Reverts with `EtherNotAccepted` if the call sends ether.
+    Reject,
+}
+
+impl EtherPolicy {
+    fn parse(name: &str) -> Option<Self> {
+        match name {
+            "payable" => Some(EtherPolicy::Payable),
+            "reject" => Some(EtherPolicy::Reject),
+            _ => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            EtherPolicy::Payable => "payable",
+            EtherPolicy::Reject => "reject",
+        }
+    }
+}
+
+/// The path of calls with empty calldata, which accepts ether.
+enum Receive {
+    /// Stops.
+    Stop,
+    /// Evaluates a macro, which must halt.
+    Macro(String),
+}
+
+impl Receive {
+    fn describe(receive: Option<&Receive>) -> String {
+        match receive {
+            None => "Empty calldata is dispatched as the selector `0x00000000`.".to_string(),
+            Some(Receive::Stop) => {
+                "Empty calldata takes the receive path, which stops.".to_string()
+            }
+            Some(Receive::Macro(target)) => {
+                format!(
+                    "Empty calldata takes the receive path, which evaluates `{}`.",
+                    target
+                )
+            }
+        }
+    }
+}
+
+struct Function {
+    signature: String,
+    selector: u32,
+    target: String,
+    ether: EtherPolicy,
+}
+
+struct Dispatcher {
+    name: String,
+    strategy: Strategy,
+    receive: Option<Receive>,
+    functions: Vec<Function>,
+}
+
+struct Case {
+    value: u32,
+    target: String,
+}
+
+struct Switch {
+    name: String,
+    cases: Vec<Case>,
+    default: Option<String>,
+}
+
+/// How a switch indexes its jump table.
+enum SwitchLayout {
+    /// An entry per value from `offset` to `offset + size - 1`, indexed by `value - offset`.
+    Dense { offset: u32, size: u32 },
+    /// Entries indexed by bits of the value, checked against the value of the case.
+    Indexed(TableIndex),
+}
+
+impl SwitchLayout {
+    fn name(&self) -> &'static str {
+        match self {
+            SwitchLayout::Dense { .. } => "dense",
+            SwitchLayout::Indexed(_) => "indexed",
+        }
+    }
+}
+
+/// Collision free jump table index, `(key >> shift) & (size - 1)`.
+struct TableIndex {
+    shift: u32,
+    size: u32,
+}
+
+/// Renders `libdispatchers.huff` from the `[[dispatcher]]` and `[[switch]]` entries of the
+/// config, if any.
+pub fn render(config: &Table) -> std::io::Result<Option<String>> {
+    let dispatchers = parse_dispatchers(config)?;
+    let switches = parse_switches(config)?;
+    let push0 = Profile::load(config)?.push0();
+
+    if dispatchers.is_empty() && switches.is_empty() {
+        return Ok(None);
+    }
+
+    let libdispatchers = format!(
+        "{}{}{}{}",
+        HEADER,
+        GUARD_DEFINITION,
+        dispatchers
+            .iter()
+            .map(|dispatcher| generate_dispatcher(dispatcher, push0))
+            .collect::<std::io::Result<String>>()?,
+        switches
+            .iter()
+            .map(|switch| generate_switch(switch, push0))
+            .collect::<std::io::Result<String>>()?,
+    );
+
+    Ok(Some(libdispatchers))
+}
+
+/// The macros the dispatchers and switches of the config delegate to: their functions, receive,
+/// cases and defaults.
+pub fn delegates(config: &Table) -> std::io::Result<BTreeSet<String>> {
+    let mut delegates = BTreeSet::new();
+    for dispatcher in parse_dispatchers(config)? {
+        delegates.extend(dispatcher.functions.into_iter().map(|f| f.target));
+        if let Some(Receive::Macro(target)) = dispatcher.receive {
+            delegates.insert(target);
+        }
+    }
+    for switch in parse_switches(config)? {
+        delegates.extend(switch.cases.into_iter().map(|case| case.target));
+        delegates.extend(switch.default);
+    }
+
+    Ok(delegates)
+}
+
+/// Renders a dispatcher of the functions of `abi` and a `MAIN` macro reverting on unknown selectors.
+///
+/// Each function rejects ether unless it is payable, and calls with empty calldata evaluate a
+/// `RECEIVE` macro if the ABI declares a receive function.
+pub fn render_abi(abi: &Abi, name: &str, strategy: &str, push0: bool) -> std::io::Result<String> {
+    let strategy = Strategy::parse(strategy)
+        .ok_or_else(|| invalid("strategy must be one of \"linear\", \"binary\", \"jump_table\""))?;
+
+    let receive = abi.receive.then(|| Receive::Macro("RECEIVE".to_string()));
+
+    let mut selectors = HashSet::new();
+    let functions = abi
+        .functions
+        .iter()
+        .map(|f| {
+            let ether = match f.payable {
+                true => EtherPolicy::Payable,
+                false => EtherPolicy::Reject,
+            };
+            let function = function(f.signature.clone(), f.target.clone(), ether);
+            if !selectors.insert(function.selector) {
+                return Err(invalid(format!(
+                    "selector 0x{:08x} of `{}` collides",
+                    function.selector, function.signature
+                )));
+            }
+            Ok(function)
+        })
+        .collect::<std::io::Result<Vec<Function>>>()?;
+
+    let dispatcher = Dispatcher {
+        name: name.to_string(),
+        strategy,
+        receive,
+        functions,
+    };
+
+    let dispatch = line(&format!("DISPATCH_{}()", name), "[]");
+    Ok(format!(
+        "{}{}{}{}",
+        ABI_HEADER,
+        GUARD_DEFINITION,
+        generate_dispatcher(&dispatcher, push0)?,
+        templates::render(MAIN_TEMPLATE, &Context::new().text("dispatch", dispatch)),
+    ))
+}
+
+fn function(signature: String, target: String, ether: EtherPolicy) -> Function {
+    let hash = keccak256(signature.as_bytes());
+    let selector = u32::from_be_bytes([hash[0], hash[1], hash[2], hash[3]]);
+
+    Function {
+        signature,
+        selector,
+        target,
+        ether,
+    }
+}
+
+fn parse_dispatchers(config: &Table) -> std::io::Result<Vec<Dispatcher>> {
+    let entries = match config.get("dispatcher") {
+        None => return Ok(Vec::new()),
+        Some(Value::Array(entries)) => entries,
+        Some(_) => return Err(invalid("`dispatcher` must be an array of tables")),
+    };
+
+    let mut names = HashSet::new();
+
+    entries
+        .iter()
+        .map(|entry| {
+            let entry = entry
+                .as_table()
+                .ok_or_else(|| invalid("`dispatcher` must be an array of tables"))?;
+            let name = identifier(entry, "name", "dispatcher")?;
+
+            if !names.insert(name.clone()) {
+                return Err(invalid(format!("duplicate dispatcher `{}`", name)));
+            }
+
+            let strategy = match entry.get("strategy") {
+                None => Strategy::Linear,
+                Some(value) => value.as_str().and_then(Strategy::parse).ok_or_else(|| {
+                    invalid(format!(
+                        "strategy of dispatcher `{}` must be one of \"linear\", \"binary\", \"jump_table\"",
+                        name
+                    ))
+                })?,
+            };
+
+            // `receive_only` rejects ether with every function, taking it only through the
+            // receive path
+            let (ether, receive_only) = match entry.get("ether").map(|value| value.as_str()) {
+                None => (EtherPolicy::Payable, false),
+                Some(Some("receive_only")) => (EtherPolicy::Reject, true),
+                Some(value) => match value.and_then(EtherPolicy::parse) {
+                    Some(ether) => (ether, false),
+                    None => {
+                        return Err(invalid(format!(
+                            "ether of dispatcher `{}` must be one of \"payable\", \"reject\", \"receive_only\"",
+                            name
+                        )))
+                    }
+                },
+            };
+
+            let receive = match entry.get("receive") {
+                None if receive_only => Some(Receive::Stop),
+                None => None,
+                Some(_) => Some(Receive::Macro(identifier(entry, "receive", "dispatcher")?)),
+            };
+
+            let mut selectors = HashSet::new();
+            let functions = entry
+                .get("functions")
+                .and_then(Value::as_array)
+                .filter(|functions| !functions.is_empty())
+                .ok_or_else(|| invalid(format!("dispatcher `{}` requires `functions`", name)))?
+                .iter()
+                .map(|function| {
+                    let function = function.as_table().ok_or_else(|| {
+                        invalid(format!("functions of dispatcher `{}` must be tables", name))
+                    })?;
+                    let context = format!("function of dispatcher `{}`", name);
+                    let signature = function
+                        .get("signature")
+                        .and_then(Value::as_str)
+                        .ok_or_else(|| invalid(format!("{} requires a `signature`", context)))?
+                        .to_string();
+                    let target = identifier(function, "macro", &context)?;
+                    let ether = match function.get("ether") {
+                        None => ether,
+                        Some(value) => value.as_str().and_then(EtherPolicy::parse).ok_or_else(|| {
+                            invalid(format!(
+                                "ether of {} `{}` must be one of \"payable\", \"reject\"",
+                                context, signature
+                            ))
+                        })?,
+                    };
+                    let function = self::function(signature, target, ether);
+
+                    if !selectors.insert(function.selector) {
+                        return Err(invalid(format!(
+                            "selector 0x{:08x} of `{}` collides in dispatcher `{}`",
+                            function.selector, function.signature, name
+                        )));
+                    }
+
+                    Ok(function)
+                })
+                .collect::<std::io::Result<Vec<Function>>>()?;
+
+            Ok(Dispatcher {
+                name,
+                strategy,
+                receive,
+                functions,
+            })
+        })
+        .collect()
+}
+
+fn parse_switches(config: &Table) -> std::io::Result<Vec<Switch>> {
+    let entries = match config.get("switch") {
+        None => return Ok(Vec::new()),
+        Some(Value::Array(entries)) => entries,
+        Some(_) => return Err(invalid("`switch` must be an array of tables")),
+    };
+
+    let mut names = HashSet::new();
+
+    entries
+        .iter()
+        .map(|entry| {
+            let entry = entry
+                .as_table()
+                .ok_or_else(|| invalid("`switch` must be an array of tables"))?;
+            let name = identifier(entry, "name", "switch")?;
+
+            if !names.insert(name.clone()) {
+                return Err(invalid(format!("duplicate switch `{}`", name)));
+            }
+
+            let default = match entry.get("default") {
+                None => None,
+                Some(_) => Some(identifier(entry, "default", "switch")?),
+            };
+
+            let mut values = HashSet::new();
+            let cases = entry
+                .get("cases")
+                .and_then(Value::as_array)
+                .filter(|cases| !cases.is_empty())
+                .ok_or_else(|| invalid(format!("switch `{}` requires `cases`", name)))?
+                .iter()
+                .map(|case| {
+                    let case = case.as_table().ok_or_else(|| {
+                        invalid(format!("cases of switch `{}` must be tables", name))
+                    })?;
+                    let context = format!("case of switch `{}`", name);
+                    let value = case
+                        .get("value")
+                        .and_then(Value::as_integer)
+                        .and_then(|value| u32::try_from(value).ok())
+                        .ok_or_else(|| {
+                            invalid(format!("{} requires a `value` below 2^32", context))
+                        })?;
+                    let target = identifier(case, "macro", &context)?;
+
+                    if !values.insert(value) {
+                        return Err(invalid(format!(
+                            "duplicate value {} in switch `{}`",
+                            value, name
+                        )));
+                    }
+
+                    Ok(Case { value, target })
+                })
+                .collect::<std::io::Result<Vec<Case>>>()?;
+
+            Ok(Switch {
+                name,
+                cases,
+                default,
+            })
+        })
+        .collect()
+}
+
+/// Finds the smallest jump table, then the smallest shift, indexing every key uniquely.
+fn table_index(keys: &[u32]) -> Option<TableIndex> {
+    let mut size = (keys.len() as u32).next_power_of_two();
+
+    while size <= MAX_TABLE_SIZE {
+        let bits = size.trailing_zeros();
+        for shift in 0..=32 - bits {
+            let mut seen = HashSet::new();
+            if keys
+                .iter()
+                .all(|key| seen.insert((key >> shift) & (size - 1)))
+            {
+                return Some(TableIndex { shift, size });
+            }
+        }
+        size <<= 1;
+    }
+
+    None
+}
+
+/// Dispatch gas of each function, in declaration order, and the most of an unknown selector.
+///
+/// `push0` prices `0x00` literals as `PUSH0`, available from Shanghai.
+fn dispatch_costs(strategy: Strategy, functions: &[Function], push0: bool) -> (Vec<u64>, u64) {
+    // a jump to `no_match`, `push jump jumpdest pop`
+    let miss = G_VERYLOW + G_MID + G_JUMPDEST + G_BASE;
+    let n = functions.len() as u64;
+    match strategy {
+        Strategy::Linear => (
+            (1..=n)
+                .map(|i| i * G_COMPARE + G_JUMPDEST + G_BASE)
+                .collect(),
+            n * G_COMPARE + miss,
+        ),
+        Strategy::Binary => {
+            let mut order = (0..functions.len()).collect::<Vec<usize>>();
+            order.sort_by_key(|&i| functions[i].selector);
+            let mut costs = vec![0; functions.len()];
+            let unknown = binary_costs(&order, 0, &mut costs);
+            (costs, unknown + miss)
+        }
+        Strategy::JumpTable => {
+            // dup1 push shr push and push shl push add
+            let index = 9 * G_VERYLOW;
+            // push swap1 push0 push0 mload push shr, a one word codecopy expanding memory, jump
+            let zero = if push0 { G_BASE } else { G_VERYLOW };
+            let load = 5 * G_VERYLOW + 2 * zero + 3 * G_VERYLOW + G_MID;
+            // jumpdest push dup2 eq iszero push jumpi
+            let check = G_JUMPDEST + 5 * G_VERYLOW + G_HIGH;
+            let cost = index + load + check;
+            (
+                vec![cost + G_BASE; functions.len()],
+                cost + G_JUMPDEST + G_BASE,
+            )
+        }
+    }
+}
+
+/// Fills the dispatch gas of the functions in `order`, returning the most gas spent comparing an
+/// unknown selector.
+fn binary_costs(order: &[usize], base: u64, costs: &mut [u64]) -> u64 {
+    if order.len() <= BINARY_LEAF_SIZE {
+        for (i, &function) in order.iter().enumerate() {
+            costs[function] = base + (i as u64 + 1) * G_COMPARE + G_JUMPDEST + G_BASE;
+        }
+        return base + order.len() as u64 * G_COMPARE;
+    }
+
+    let (left, right) = order.split_at(order.len() / 2);
+    let left = binary_costs(left, base + G_COMPARE + G_JUMPDEST, costs);
+    let right = binary_costs(right, base + G_COMPARE, costs);
+    left.max(right)
+}
+
+fn gas_table(dispatcher: &Dispatcher, push0: bool) -> String {
+    Strategy::ALL
+        .iter()
+        .map(|&strategy| {
+            let (costs, miss) = dispatch_costs(strategy, &dispatcher.functions, push0);
+            let min = costs.iter().min().unwrap();
+            let max = costs.iter().max().unwrap();
+            let average = costs.iter().sum::<u64>() / costs.len() as u64;
+            let selected = if strategy == dispatcher.strategy {
+                " (selected)"
+            } else {
+                ""
+            };
+            format!(
+                "/// | `{}`{} | {} | {} | {} | {} |\n",
+                strategy.name(),
+                selected,
+                min,
+                max,
+                average,
+                miss
+            )
+        })
+        .collect()
+}
+
+fn generate_dispatcher(dispatcher: &Dispatcher, push0: bool) -> std::io::Result<String> {
+    let functions = dispatcher
+        .functions
+        .iter()
+        .map(|f| {
+            Context::new()
+                .text("selector", format!("{:08x}", f.selector))
+                .text("signature", f.signature.as_str())
+                .text("target", f.target.as_str())
+                .text("ether", f.ether.name())
+        })
+        .collect();
+
+    let mut table = String::new();
+    let mut body = String::new();
+
+    if dispatcher.receive.is_some() {
+        body.push_str(&line("calldatasize", "[calldata_len]"));
+        body.push_str(&line("iszero", "[is_empty]"));
+        body.push_str(&line("receive", "[receive_dest, is_empty]"));
+        body.push_str(&line("jumpi", "[]"));
+    }
+
+    body.push_str(&line("0x00", "[offset]"));
+    body.push_str(&line("calldataload", "[word]"));
+    body.push_str(&line("0xe0", "[shift, word]"));
+    body.push_str(&line("shr", "[selector]"));
+
+    match dispatcher.strategy {
+        Strategy::Linear => {
+            let indices = (0..dispatcher.functions.len()).collect::<Vec<usize>>();
+            body.push_str(&linear(&dispatcher.functions, &indices));
+            body.push_str(&line("no_match", "[no_match_dest, selector]"));
+            body.push_str(&line("jump", "[selector]"));
+        }
+        Strategy::Binary => {
+            let mut order = (0..dispatcher.functions.len()).collect::<Vec<usize>>();
+            order.sort_by_key(|&i| dispatcher.functions[i].selector);
+            body.push_str(&binary(&dispatcher.functions, &order));
+        }
+        Strategy::JumpTable => {
+            let selectors = dispatcher
+                .functions
+                .iter()
+                .map(|f| f.selector)
+                .collect::<Vec<u32>>();
+            let index = table_index(&selectors).ok_or_else(|| {
+                invalid(format!(
+                    "no jump table of up to {} entries indexes dispatcher `{}`",
+                    MAX_TABLE_SIZE, dispatcher.name
+                ))
+            })?;
+            let table_name = format!("DISPATCH_{}_TABLE", dispatcher.name);
+
+            let mut entries = vec!["no_match".to_string(); index.size as usize];
+            for (i, f) in dispatcher.functions.iter().enumerate() {
+                entries[((f.selector >> index.shift) & (index.size - 1)) as usize] =
+                    format!("function_{}", i);
+            }
+
+            body.push_str(&line("dup1", "[selector, selector]"));
+            body.push_str(&line(
+                &format!("0x{:02x}", index.shift),
+                "[shift, selector, selector]",
+            ));
+            body.push_str(&line("shr", "[shifted, selector]"));
+            body.push_str(&line(
+                &format!("0x{:x}", index.size - 1),
+                "[mask, shifted, selector]",
+            ));
+            body.push_str(&line("and", "[index, selector]"));
+            body.push_str(&table_jump(&table_name, ", selector"));
+
+            for (i, f) in dispatcher.functions.iter().enumerate() {
+                body.push_str(&line(&format!("function_{}:", i), "[selector]"));
+                body.push_str(&line(
+                    &format!("0x{:08x}", f.selector),
+                    "[expected, selector]",
+                ));
+                body.push_str(&line("dup2", "[selector, expected, selector]"));
+                body.push_str(&line("eq", "[is_match, selector]"));
+                body.push_str(&line("iszero", "[is_mismatch, selector]"));
+                body.push_str(&line("no_match", "[no_match_dest, is_mismatch, selector]"));
+                body.push_str(&line("jumpi", "[selector]"));
+                body.push_str(&line("pop", "[]"));
+                body.push_str(&call(f));
+            }
+
+            let context = Context::new()
+                .text("name", dispatcher.name.as_str())
+                .text("shift", index.shift.to_string())
+                .text("size", index.size.to_string())
+                .text("entries", entries.join(" "));
+            table = templates::render(TABLE_TEMPLATE, &context);
+        }
+    }
+
+    if let Some(receive) = &dispatcher.receive {
+        let receive = match receive {
+            Receive::Macro(target) => format!("{}()", target),
+            Receive::Stop => "stop".to_string(),
+        };
+        body.push_str(&line("receive:", "[]"));
+        body.push_str(&line(&receive, "[]"));
+    }
+
+    if dispatcher.strategy != Strategy::JumpTable {
+        for (i, f) in dispatcher.functions.iter().enumerate() {
+            body.push_str(&line(&format!("function_{}:", i), "[selector]"));
+            body.push_str(&line("pop", "[]"));
+            body.push_str(&call(f));
+        }
+    }
+
+    body.push_str(&line("no_match:", "[selector]"));
+    body.push_str(&line("pop", "[]"));
+
+    let context = Context::new()
+        .text("name", dispatcher.name.as_str())
+        .text("strategy", dispatcher.strategy.name())
+        .text("receive", Receive::describe(dispatcher.receive.as_ref()))
+        .list("functions", functions)
+        .text("gas", gas_table(dispatcher, push0))
+        .flag("memory", dispatcher.strategy == Strategy::JumpTable)
+        .text("body", body);
+
+    Ok(format!(
+        "{}{}",
+        table,
+        templates::render(DISPATCHER_TEMPLATE, &context)
+    ))
+}
+
+/// Evaluates the macro of `f`, rejecting ether first unless it is payable.
+fn call(f: &Function) -> String {
+    let mut body = String::new();
+    if f.ether == EtherPolicy::Reject {
+        body.push_str(&line("REJECT_ETHER()", "[]"));
+    }
+    body.push_str(&line(&format!("{}()", f.target), "[]"));
+    body
+}
+
+/// Compares the selector against each function in turn, jumping to the first match.
+fn linear(functions: &[Function], indices: &[usize]) -> String {
+    let mut body = String::new();
+    for &i in indices {
+        body.push_str(&line("dup1", "[selector, selector]"));
+        body.push_str(&line(
+            &format!("0x{:08x}", functions[i].selector),
+            "[expected, selector, selector]",
+        ));
+        body.push_str(&line("eq", "[is_match, selector]"));
+        body.push_str(&line(
+            &format!("function_{}", i),
+            "[function_dest, is_match, selector]",
+        ));
+        body.push_str(&line("jumpi", "[selector]"));
+    }
+    body
+}
+
+/// Halves the sorted selectors until at most `BINARY_LEAF_SIZE` remain, then compares linearly.
+fn binary(functions: &[Function], order: &[usize]) -> String {
+    if order.len() <= BINARY_LEAF_SIZE {
+        let mut body = linear(functions, order);
+        body.push_str(&line("no_match", "[no_match_dest, selector]"));
+        body.push_str(&line("jump", "[selector]"));
+        return body;
+    }
+
+    let (left, right) = order.split_at(order.len() / 2);
+    let pivot = functions[right[0]].selector;
+    let label = format!("below_{:08x}", pivot);
+
+    let mut body = line(&format!("0x{:08x}", pivot), "[pivot, selector]");
+    body.push_str(&line("dup2", "[selector, pivot, selector]"));
+    body.push_str(&line("lt", "[is_below, selector]"));
+    body.push_str(&line(&label, "[below_dest, is_below, selector]"));
+    body.push_str(&line("jumpi", "[selector]"));
+    body.push_str(&binary(functions, right));
+    body.push_str(&line(&format!("{}:", label), "[selector]"));
+    body.push_str(&binary(functions, left));
+    body
+}
+
+/// Indexes the packed jump table `table` with the index on the stack and jumps to the destination
+/// of the entry, with `rest` of the stack below the index.
+fn table_jump(table: &str, rest: &str) -> String {
+    let stack = |top: &str| format!("[{}{}]", top, rest);
+
+    let mut body = line("0x01", &stack("0x01, index"));
+    body.push_str(&line("shl", &stack("entry_offset")));
+    body.push_str(&line(
+        &format!("__tablestart({})", table),
+        &stack("table, entry_offset"),
+    ));
+    body.push_str(&line("add", &stack("entry")));
+    body.push_str(&line("0x02", &stack("entry_len, entry")));
+    body.push_str(&line("swap1", &stack("entry, entry_len")));
+    body.push_str(&line("0x00", &stack("ptr, entry, entry_len")));
+    body.push_str(&line(
+        "codecopy",
+        &format!("[{}]", rest.trim_start_matches(", ")),
+    ));
+    body.push_str(&line("0x00", &stack("ptr")));
+    body.push_str(&line("mload", &stack("word")));
+    body.push_str(&line("0xf0", &stack("shift, word")));
+    body.push_str(&line("shr", &stack("dest")));
+    body.push_str(&line(
+        "jump",
+        &format!("[{}]", rest.trim_start_matches(", ")),
+    ));
+    body
+}
+
+/// Bytes of a push of `value`, `PUSH0` for zero when `push0`.
+fn push_size(value: u32, push0: bool) -> u32 {
+    match value {
+        0 if push0 => 1,
+        0 => 2,
+        value => 1 + (32 - value.leading_zeros()).div_ceil(8),
+    }
+}
+
+/// The layouts able to index the cases of `switch`, with the bytes each spends on its table and
+/// the checks of the value, leaving out the jump common to both.
+fn switch_layouts(switch: &Switch, push0: bool) -> Vec<(SwitchLayout, u32)> {
+    let values = switch
+        .cases
+        .iter()
+        .map(|case| case.value)
+        .collect::<Vec<u32>>();
+    let mut layouts = Vec::new();
+
+    let offset = *values.iter().min().unwrap();
+    let size = values.iter().max().unwrap() - offset + 1;
+    if size <= MAX_TABLE_SIZE {
+        // push swap1 sub, then push dup2 lt iszero push jumpi
+        let index = if offset == 0 {
+            0
+        } else {
+            push_size(offset, push0) + 2
+        };
+        let bounds = push_size(size, push0) + 3 + LABEL_SIZE + 1;
+        layouts.push((
+            SwitchLayout::Dense { offset, size },
+            2 * size + index + bounds,
+        ));
+    }
+
+    if let Some(index) = table_index(&values) {
+        // dup1 push shr push and, then push dup2 eq iszero push jumpi pop for each case
+        let bits = 1 + push_size(index.shift, push0) + 1 + push_size(index.size - 1, push0) + 1;
+        let checks = values
+            .iter()
+            .map(|&value| push_size(value, push0) + 3 + LABEL_SIZE + 2)
+            .sum::<u32>();
+        let bytes = 2 * index.size + bits + checks;
+        layouts.push((SwitchLayout::Indexed(index), bytes));
+    }
+
+    layouts
+}
+
+fn generate_switch(switch: &Switch, push0: bool) -> std::io::Result<String> {
+    let mut layouts = switch_layouts(switch, push0);
+    let sizes = layouts
+        .iter()
+        .map(|(layout, bytes)| (layout.name(), *bytes))
+        .collect::<Vec<(&str, u32)>>();
+    // the dense layout checks no value, so it also wins ties
+    layouts.sort_by_key(|(_, bytes)| *bytes);
+    let (layout, _) = layouts.into_iter().next().ok_or_else(|| {
+        invalid(format!(
+            "no jump table of up to {} entries indexes switch `{}`",
+            MAX_TABLE_SIZE, switch.name
+        ))
+    })?;
+
+    let cases = switch
+        .cases
+        .iter()
+        .map(|case| {
+            Context::new()
+                .text("value", format!("{:02x}", case.value))
+                .text("target", case.target.as_str())
+        })
+        .collect();
+    let layout_sizes = sizes
+        .iter()
+        .map(|&(name, bytes)| {
+            Context::new()
+                .text("layout", name)
+                .flag("selected", name == layout.name())
+                .text("bytes", bytes.to_string())
+        })
+        .collect();
+
+    let table_name = format!("SWITCH_{}_TABLE", switch.name);
+    let mut body = String::new();
+    let (indexed_by, entries) = match &layout {
+        SwitchLayout::Dense { offset, size } => {
+            if *offset != 0 {
+                body.push_str(&line(&format!("0x{:02x}", offset), "[offset, value]"));
+                body.push_str(&line("swap1", "[value, offset]"));
+                body.push_str(&line("sub", "[index]"));
+            }
+            body.push_str(&line(&format!("0x{:x}", size), "[size, index]"));
+            body.push_str(&line("dup2", "[index, size, index]"));
+            body.push_str(&line("lt", "[in_range, index]"));
+            body.push_str(&line("iszero", "[out_of_range, index]"));
+            body.push_str(&line("no_match", "[no_match_dest, out_of_range, index]"));
+            body.push_str(&line("jumpi", "[index]"));
+            body.push_str(&table_jump(&table_name, ""));
+
+            let mut entries = vec!["default".to_string(); *size as usize];
+            for (i, case) in switch.cases.iter().enumerate() {
+                entries[(case.value - offset) as usize] = format!("case_{}", i);
+            }
+            for (i, case) in switch.cases.iter().enumerate() {
+                body.push_str(&line(&format!("case_{}:", i), "[]"));
+                body.push_str(&line(&format!("{}()", case.target), "[]"));
+                body.push_str(&line("end", "[end_dest]"));
+                body.push_str(&line("jump", "[]"));
+            }
+
+            (
+                format!("`value - 0x{:02x}`, checked against the table size", offset),
+                entries,
+            )
+        }
+        SwitchLayout::Indexed(index) => {
+            body.push_str(&line("dup1", "[value, value]"));
+            body.push_str(&line(
+                &format!("0x{:02x}", index.shift),
+                "[shift, value, value]",
+            ));
+            body.push_str(&line("shr", "[shifted, value]"));
+            body.push_str(&line(
+                &format!("0x{:x}", index.size - 1),
+                "[mask, shifted, value]",
+            ));
+            body.push_str(&line("and", "[index, value]"));
+            body.push_str(&table_jump(&table_name, ", value"));
+
+            let mut entries = vec!["no_match".to_string(); index.size as usize];
+            for (i, case) in switch.cases.iter().enumerate() {
+                entries[((case.value >> index.shift) & (index.size - 1)) as usize] =
+                    format!("case_{}", i);
+            }
+            for (i, case) in switch.cases.iter().enumerate() {
+                body.push_str(&line(&format!("case_{}:", i), "[value]"));
+                body.push_str(&line(&format!("0x{:02x}", case.value), "[expected, value]"));
+                body.push_str(&line("dup2", "[value, expected, value]"));
+                body.push_str(&line("eq", "[is_match, value]"));
+                body.push_str(&line("iszero", "[is_mismatch, value]"));
+                body.push_str(&line("no_match", "[no_match_dest, is_mismatch, value]"));
+                body.push_str(&line("jumpi", "[value]"));
+                body.push_str(&line("pop", "[]"));
+                body.push_str(&line(&format!("{}()", case.target), "[]"));
+                body.push_str(&line("end", "[end_dest]"));
+                body.push_str(&line("jump", "[]"));
+            }
+
+            let indexed_by = format!(
+                "`(value >> {}) % {}`, checked against the case",
+                index.shift, index.size
+            );
+            (indexed_by, entries)
+        }
+    };
+
+    let missed = match layout {
+        SwitchLayout::Dense { .. } => "[index]",
+        SwitchLayout::Indexed(_) => "[value]",
+    };
+    body.push_str(&line("no_match:", missed));
+    body.push_str(&line("pop", "[]"));
+    body.push_str(&line("default:", "[]"));
+    if let Some(target) = &switch.default {
+        body.push_str(&line(&format!("{}()", target), "[]"));
+    }
+    body.push_str(&line("end:", "[]"));
+
+    let context = Context::new()
+        .text("name", switch.name.as_str())
+        .text("index", indexed_by)
+        .text("entries", entries.join(" "))
+        .text("layout", layout.name())
+        .flag("has_default", switch.default.is_some())
+        .text("default", switch.default.clone().unwrap_or_default())
+        .list("cases", cases)
+        .list("sizes", layout_sizes)
+        .text("body", body);
+
+    Ok(format!(
+        "{}{}",
+        templates::render(SWITCH_TABLE_TEMPLATE, &context),
+        templates::render(SWITCH_TEMPLATE, &context),
+    ))
+}
+
+const HEADER: &str = r#"
+//  ------------------------------------------------------------------------------------------------
+//! # Dispatchers Library
+//!
+//! Provides function dispatchers and switches for the dispatchers and switches declared in
+//! `libhuff.toml`.
+//!
+//! Each dispatcher routes the selector of the call to the macro of its function, using one of
+//! three strategies:
+//!
+//! - `linear` - Compares the selector against each function in declaration order.
+//! - `binary` - Binary searches the sorted selectors, comparing the last three linearly.
+//! - `jump_table` - Indexes a packed jump table with bits of the selector, then verifies it.
+//!
+//! Function macros must halt. Unknown selectors fall through the end of the dispatcher, so a
+//! fallback or revert can follow it.
+//!
+//! Each switch jumps to the macro of the case of a value through a jump table, laid out either
+//! `dense`, with an entry per value in the range of the cases, or `indexed` by bits of the value,
+//! whichever is smaller. Values matching no case evaluate the default macro, if any.
+//!
+//! Ether is handled by the `ether` policy of each function, defaulting to that of its dispatcher:
+//!
+//! - `payable` - The function accepts ether.
+//! - `reject` - The function reverts if the call sends ether, before evaluating its macro.
+//! - `receive_only` - Of a dispatcher only, every function rejects ether and calls with empty
+//!   calldata take the receive path, which accepts ether and stops.
+//!
+//! A dispatcher with a `receive` macro, which must halt, evaluates it on the receive path instead,
+//! whatever its policy.
+//!
+//! ## API
+//!
+//! - `REJECT_ETHER` - Reverts if the call sends ether.
+//!
+//! For a given dispatcher, `DISPATCHER`:
+//!
+//! - `DISPATCH_DISPATCHER` - Dispatches the call to the macro of its function.
+//!
+//! For a given switch, `SWITCH`:
+//!
+//! - `SWITCH_SWITCH` - Evaluates the macro of the case of a value.
+"#;
+
+const ABI_HEADER: &str = r#"
+//  ------------------------------------------------------------------------------------------------
+//! # Dispatcher
+//!
+//! Provides the entry point of a contract, generated by `huffgen dispatcher` from its ABI.
+//!
+//! `MAIN` routes the selector of the call to the macro of its function, named after the function
+//! in upper snake case, and reverts on unknown selectors. The function macros must be defined by
+//! the contract including this file, and must halt.
+"#;
+
+const MAIN_TEMPLATE: &str = r#"
+/// ## Main
+///
+/// Dispatches the call to the macro of its function, reverting with empty data if the selector is
+/// unknown.
+#define macro MAIN() = takes (0) returns (0) {
+{{dispatch}}    0x00                    // [size]
+    dup1                    // [offset, size]
+    revert                  // []
+}
+"#;
+
+const GUARD_DEFINITION: &str = r#"
+/// ## Ether Not Accepted Error
+///
+/// Thrown when ether is sent to a call that does not accept it.
+#define error EtherNotAccepted()
+
+/// ## Reject Ether
+///
+/// Reverts if the call sends ether.
+///
+/// ### Panics
+///
+/// - if `callvalue` is not zero
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     REJECT_ETHER()
+///     stop
+/// }
+/// ```
+#define macro REJECT_ETHER() = takes (0) returns (0) {
+    callvalue               // [value]
+    iszero                  // [is_zero]
+    no_value                // [no_value_dest, is_zero]
+    jumpi                   // []
+        __ERROR(EtherNotAccepted) // [err]
+        0x00                // [ptr, err]
+        mstore              // []
+        0x04                // [err_len]
+        0x00                // [ptr, err_len]
+        revert              // []
+    no_value:               // []
+}
+"#;
+
+const TABLE_TEMPLATE: &str = r#"
+/// ## {{name}} Dispatch Table
+///
+/// Jump table of `DISPATCH_{{name}}`, indexed by `(selector >> {{shift}}) % {{size}}`.
+#define jumptable__packed DISPATCH_{{name}}_TABLE {
+    {{entries}}
+}
+"#;
+
+const DISPATCHER_TEMPLATE: &str = r#"
+/// ## Dispatch {{name}}
+///
+/// Dispatches the call to the macro of its function with the `{{strategy}}` strategy, falling
+/// through if the selector is unknown.
+///
+/// {{receive}}
+///
+/// | Selector | Function | Macro | Ether |
+/// | -------- | -------- | ----- | ----- |
+{{#each functions}}/// | `0x{{selector}}` | `{{signature}}` | `{{target}}` | `{{ether}}` |
+{{/each}}///
+/// Dispatch gas by strategy, after loading the selector and before the ether guard of the
+/// function:
+///
+/// | Strategy | Min | Max | Average | Unknown |
+/// | -------- | --- | --- | ------- | ------- |
+{{gas}}{{#if memory}}///
+/// > WARNING: Memory from `0x00` to `0x20` will be overwritten.
+{{/if}}///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     DISPATCH_{{name}}()
+///     0x00 0x00 revert
+/// }
+/// ```
+#define macro DISPATCH_{{name}}() = takes (0) returns (0) {
+{{body}}}
+"#;
+
+const SWITCH_TABLE_TEMPLATE: &str = r#"
+/// ## {{name}} Switch Table
+///
+/// Jump table of `SWITCH_{{name}}`, indexed by {{index}}.
+#define jumptable__packed SWITCH_{{name}}_TABLE {
+    {{entries}}
+}
+"#;
+
+const SWITCH_TEMPLATE: &str = r#"
+/// ## Switch {{name}}
+///
+/// Evaluates the macro of the case of the value, then continues after the switch. A value matching
+/// no case {{#if has_default}}evaluates `{{default}}`{{else}}does nothing{{/if}}.
+///
+/// The `{{layout}}` jump table is indexed by {{index}}.
+///
+/// | Value | Macro |
+/// | ----- | ----- |
+{{#each cases}}/// | `0x{{value}}` | `{{target}}` |
+{{/each}}///
+/// Bytes of the table and the checks of the value by layout:
+///
+/// | Layout | Bytes |
+/// | ------ | ----- |
+{{#each sizes}}/// | `{{layout}}`{{#if selected}} (selected){{/if}} | {{bytes}} |
+{{/each}}///
+/// > WARNING: Memory from `0x00` to `0x20` will be overwritten.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x00 calldataload       // [value]
+///     SWITCH_{{name}}()
+///     stop
+/// }
+/// ```
+#define macro SWITCH_{{name}}() = takes (1) returns (0) {
+{{body}}}
+"#;
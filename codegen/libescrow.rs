@@ -0,0 +1,139 @@
+pub fn render() -> String {
+    format!("{}{}", HEADER, ESCROW_DEFINITION)
+}
+
+const HEADER: &str = r#"
+//  ------------------------------------------------------------------------------------------------
+//! # Escrow Library
+//!
+//! Provides escrow macros for holding ether on behalf of payees until it is withdrawn.
+//!
+//! Deposits are credited to a payee chosen by the depositor rather than the caller, and
+//! withdrawals send the entire balance of a payee to that payee, so funds can only ever reach
+//! their payee. Restricting who may deposit or trigger withdrawals is left to the caller, for
+//! example with `libauth`.
+//!
+//! The balances, events and errors are shared with `libpayment`, and withdrawals likewise run
+//! inside the contract-level `REENTRANCY_GUARD`, which requires the Cancun hardfork.
+//!
+//! ## API
+//!
+//! - `ESCROW_BALANCE_OF` - Loads the escrowed balance of a payee.
+//! - `ESCROW_DEPOSIT` - Credits `callvalue` to a payee.
+//! - `ESCROW_WITHDRAW` - Withdraws the entire balance of a payee to the payee.
+
+#include "libpayment.huff"
+"#;
+
+const ESCROW_DEFINITION: &str = r#"
+/// ## Escrow Balance Of
+///
+/// Loads the escrowed balance of a payee.
+///
+/// > WARNING: Memory from `0x00` to `0x40` will be overwritten.
+///
+/// ### Template Arguments
+///
+/// - `deposits_slot`: storage slot of the deposit mapping.
+///
+/// ### Stack Arguments
+///
+/// - `payee`: (`stack[0]`) payee to load the balance of.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define constant DEPOSITS_SLOT = FREE_STORAGE_POINTER()
+///
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x04 calldataload
+///     ESCROW_BALANCE_OF([DEPOSITS_SLOT])
+///     0x00 mstore
+///     0x20 0x00 return
+/// }
+/// ```
+#define macro ESCROW_BALANCE_OF(deposits_slot) = takes (1) returns (1) {
+    // takes:               // [payee]
+    DEPOSIT_OF(<deposits_slot>) // [balance]
+}
+
+/// ## Escrow Deposit
+///
+/// Credits `callvalue` to the balance of a payee and emits `Deposited`.
+///
+/// > WARNING: Memory from `0x00` to `0x40` will be overwritten.
+///
+/// ### Template Arguments
+///
+/// - `deposits_slot`: storage slot of the deposit mapping.
+///
+/// ### Stack Arguments
+///
+/// - `payee`: (`stack[0]`) payee to credit.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define constant DEPOSITS_SLOT = FREE_STORAGE_POINTER()
+///
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x04 calldataload
+///     ESCROW_DEPOSIT([DEPOSITS_SLOT])
+///     stop
+/// }
+/// ```
+#define macro ESCROW_DEPOSIT(deposits_slot) = takes (1) returns (0) {
+    // takes:               // [payee]
+    __CREDIT(<deposits_slot>) // []
+}
+
+/// ## Escrow Withdraw
+///
+/// Withdraws the entire balance of a payee to the payee and emits `Withdrawn`.
+///
+/// > WARNING: Memory from `0x00` to `0x40` will be overwritten.
+///
+/// ### Template Arguments
+///
+/// - `deposits_slot`: storage slot of the deposit mapping.
+///
+/// ### Stack Arguments
+///
+/// - `payee`: (`stack[0]`) payee to withdraw for.
+///
+/// ### Panics
+///
+/// - if reentered
+/// - if sending ether to the payee fails
+///
+/// ### Usage
+///
+/// ```huff
+/// #define constant DEPOSITS_SLOT = FREE_STORAGE_POINTER()
+///
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x04 calldataload
+///     ESCROW_WITHDRAW([DEPOSITS_SLOT])
+///     stop
+/// }
+/// ```
+#define macro ESCROW_WITHDRAW(deposits_slot) = takes (1) returns (0) {
+    // takes:               // [payee]
+    0x00                    // [ptr, payee]
+    mstore                  // []
+    REENTRANCY_GUARD(__ESCROW_WITHDRAW_STORED(<deposits_slot>)) // []
+}
+
+/// ## Escrow Withdraw Stored
+///
+/// Withdraws the entire balance of the payee stored at `0x00` to the payee, without a reentrancy
+/// guard.
+#define macro __ESCROW_WITHDRAW_STORED(deposits_slot) = takes (0) returns (0) {
+    0x00                    // [ptr]
+    mload                   // [payee]
+    dup1                    // [payee, payee]
+    DEPOSIT_OF(<deposits_slot>) // [balance, payee]
+    swap1                   // [payee, balance]
+    __WITHDRAW(<deposits_slot>) // []
+}
+"#;
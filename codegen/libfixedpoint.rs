@@ -0,0 +1,240 @@
+use crate::templates::{render as render_template, Context};
+
+/// The `WAD` macros, by name and title, with the scaled macros they invoke.
+const WAD_OPERATIONS: [(&str, &str, &str); 4] = [
+    ("MUL_WAD", "Multiplication", "MUL_SCALED"),
+    ("MUL_WAD_UP", "Multiplication Rounding Up", "MUL_SCALED_UP"),
+    ("DIV_WAD", "Division", "DIV_SCALED"),
+    ("DIV_WAD_UP", "Division Rounding Up", "DIV_SCALED_UP"),
+];
+
+pub fn render() -> String {
+    let scaled = [false, true]
+        .iter()
+        .map(|up| {
+            let context = Context::new()
+                .flag("up", *up)
+                .text("suffix", if *up { "_UP" } else { "" })
+                .text("title", if *up { " Rounding Up" } else { "" })
+                .text("rounding", if *up { "up" } else { "down" });
+            (
+                render_template(MUL_TEMPLATE, &context),
+                render_template(DIV_TEMPLATE, &context),
+            )
+        })
+        .collect::<Vec<(String, String)>>();
+
+    let wads = WAD_OPERATIONS
+        .iter()
+        .map(|(name, title, scaled)| {
+            Context::new()
+                .text("name", *name)
+                .text("title", *title)
+                .text("invocation", format!("{}([WAD])", scaled))
+        })
+        .collect();
+
+    format!(
+        "{}{}{}{}{}{}",
+        HEADER,
+        scaled[0].0,
+        scaled[1].0,
+        scaled[0].1,
+        scaled[1].1,
+        render_template(WAD_TEMPLATE, &Context::new().list("operations", wads)),
+    )
+}
+
+const HEADER: &str = r#"
+//  ------------------------------------------------------------------------------------------------
+//! # Fixed Point Library
+//!
+//! Provides checked multiplication and division of unsigned fixed point numbers, scaled by a
+//! template argument or by `WAD`, `10 ** 18`, as UD60x18 numbers are.
+//!
+//! ## API
+//!
+//! - `MUL_SCALED` - Multiplies two values of a scale, rounding down.
+//! - `MUL_SCALED_UP` - Multiplies two values of a scale, rounding up.
+//! - `DIV_SCALED` - Divides two values of a scale, rounding down.
+//! - `DIV_SCALED_UP` - Divides two values of a scale, rounding up.
+//!
+//! - `MUL_WAD`, `MUL_WAD_UP`, `DIV_WAD`, `DIV_WAD_UP` - The same, for values scaled by `WAD`.
+
+#include "libsafemath.huff"
+
+/// ## Wad
+///
+/// The scale of UD60x18 numbers, `10 ** 18`.
+#define constant WAD = 0x0de0b6b3a7640000
+"#;
+
+const MUL_TEMPLATE: &str = r#"
+/// ## Scaled Multiplication{{title}}
+///
+/// Multiplies `a` by `b`, values scaled by `scale`, rounding {{rounding}}.
+///
+/// ### Template Arguments
+///
+/// - `scale`: nonzero scale of the values, such as `0x0de0b6b3a7640000` for 18 decimals.
+///
+/// ### Stack Arguments
+///
+/// - `a`: (`stack[0]`) multiplicand.
+/// - `b`: (`stack[1]`) multiplier.
+///
+/// ### Panics
+///
+/// - if `a * b` overflows, even where the scaled product would not.
+///
+/// ### Conditions
+///
+/// @pre a * b < 2 ** 256
+{{#if up}}/// @post result == (a * b + scale - 1) / scale
+{{else}}/// @post result == a * b / scale
+{{/if}}///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x24 calldataload
+///     0x04 calldataload
+///     MUL_SCALED{{suffix}}(0x0f4240)
+///     0x00 mstore
+///     0x20 0x00 return
+/// }
+/// ```
+#define macro MUL_SCALED{{suffix}}(scale) = takes (2) returns (1) {
+    // takes:               // [a, b]
+    dup2                    // [b, a, b]
+    dup2                    // [a, b, a, b]
+    mul                     // [product, a, b]
+    dup3                    // [b, product, a, b]
+    dup2                    // [product, b, product, a, b]
+    div                     // [quotient, product, a, b]
+    dup3                    // [a, quotient, product, a, b]
+    eq                      // [is_exact, product, a, b]
+    dup4                    // [b, is_exact, product, a, b]
+    iszero                  // [is_zero, is_exact, product, a, b]
+    or                      // [is_safe, product, a, b]
+    is_safe                 // [is_safe_dest, is_safe, product, a, b]
+    jumpi                   // [product, a, b]
+        __ERROR(Overflow)   // [err, product, a, b]
+        0x00                // [ptr, err, product, a, b]
+        mstore              // [product, a, b]
+        0x04                // [err_len, product, a, b]
+        0x00                // [ptr, err_len, product, a, b]
+        revert              // []
+    is_safe:                // [product, a, b]
+        swap2               // [b, a, product]
+        pop                 // [a, product]
+        pop                 // [product]
+        <scale>             // [scale, product]
+{{#if up}}        dup2                // [product, scale, product]
+        dup2                // [scale, product, scale, product]
+        swap1               // [product, scale, scale, product]
+        mod                 // [remainder, scale, product]
+        iszero              // [is_exact, scale, product]
+        iszero              // [is_inexact, scale, product]
+        swap2               // [product, scale, is_inexact]
+        div                 // [quotient, is_inexact]
+        add                 // [result]
+{{else}}        swap1               // [product, scale]
+        div                 // [result]
+{{/if}}}
+"#;
+
+const DIV_TEMPLATE: &str = r#"
+/// ## Scaled Division{{title}}
+///
+/// Divides `a` by `b`, values scaled by `scale`, rounding {{rounding}}.
+///
+/// ### Template Arguments
+///
+/// - `scale`: nonzero scale of the values, such as `0x0de0b6b3a7640000` for 18 decimals.
+///
+/// ### Stack Arguments
+///
+/// - `a`: (`stack[0]`) dividend.
+/// - `b`: (`stack[1]`) divisor.
+///
+/// ### Panics
+///
+/// - if `b` is zero.
+/// - if `a * scale` overflows, even where the scaled quotient would not.
+///
+/// ### Conditions
+///
+/// @pre b != 0
+/// @pre a * scale < 2 ** 256
+{{#if up}}/// @post result == (a * scale + b - 1) / b
+{{else}}/// @post result == a * scale / b
+{{/if}}///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x24 calldataload
+///     0x04 calldataload
+///     DIV_SCALED{{suffix}}(0x0f4240)
+///     0x00 mstore
+///     0x20 0x00 return
+/// }
+/// ```
+#define macro DIV_SCALED{{suffix}}(scale) = takes (2) returns (1) {
+    // takes:               // [a, b]
+    dup2                    // [b, a, b]
+    is_nonzero              // [is_nonzero_dest, b, a, b]
+    jumpi                   // [a, b]
+        __ERROR(DivByZero)  // [err, a, b]
+        0x00                // [ptr, err, a, b]
+        mstore              // [a, b]
+        0x04                // [err_len, a, b]
+        0x00                // [ptr, err_len, a, b]
+        revert              // []
+    is_nonzero:             // [a, b]
+        <scale>             // [scale, a, b]
+        dup2                // [a, scale, a, b]
+        mul                 // [product, a, b]
+        <scale>             // [scale, product, a, b]
+        dup2                // [product, scale, product, a, b]
+        div                 // [quotient, product, a, b]
+        dup3                // [a, quotient, product, a, b]
+        eq                  // [is_safe, product, a, b]
+        is_safe             // [is_safe_dest, is_safe, product, a, b]
+        jumpi               // [product, a, b]
+            __ERROR(Overflow) // [err, product, a, b]
+            0x00            // [ptr, err, product, a, b]
+            mstore          // [product, a, b]
+            0x04            // [err_len, product, a, b]
+            0x00            // [ptr, err_len, product, a, b]
+            revert          // []
+        is_safe:            // [product, a, b]
+            swap1           // [a, product, b]
+            pop             // [product, b]
+{{#if up}}            dup2            // [b, product, b]
+            dup2            // [product, b, product, b]
+            mod             // [remainder, product, b]
+            iszero          // [is_exact, product, b]
+            iszero          // [is_inexact, product, b]
+            swap2           // [b, product, is_inexact]
+            swap1           // [product, b, is_inexact]
+            div             // [quotient, is_inexact]
+            add             // [result]
+{{else}}            div             // [result]
+{{/if}}}
+"#;
+
+const WAD_TEMPLATE: &str = r#"{{#each operations}}
+/// ## Wad {{title}}
+///
+/// `{{invocation}}`, for values scaled by `WAD`.
+///
+/// ### Stack Arguments
+///
+/// - `a`: (`stack[0]`) first operand.
+/// - `b`: (`stack[1]`) second operand.
+#define macro {{name}}() = takes (2) returns (1) {
+    {{invocation}}
+}
+{{/each}}"#;
@@ -0,0 +1,314 @@
+use std::collections::HashSet;
+
+use crate::config::{identifier, invalid, Table, Value};
+use crate::templates::{self, Context};
+
+struct Flags {
+    name: String,
+    flags: Vec<String>,
+}
+
+/// Renders `libflags.huff`, with constants for the `[[flags]]` entries of the config, if any.
+pub fn render(config: &Table) -> std::io::Result<String> {
+    let constants = parse_flags(config)?
+        .iter()
+        .map(generate_flags)
+        .collect::<String>();
+
+    let libflags = format!("{}{}{}", HEADER, constants, FLAG_DEFINITION);
+
+    Ok(libflags)
+}
+
+fn parse_flags(config: &Table) -> std::io::Result<Vec<Flags>> {
+    let entries = match config.get("flags") {
+        None => return Ok(Vec::new()),
+        Some(Value::Array(entries)) => entries,
+        Some(_) => return Err(invalid("`flags` must be an array of tables")),
+    };
+
+    let mut names = HashSet::new();
+
+    entries
+        .iter()
+        .map(|entry| {
+            let entry = entry
+                .as_table()
+                .ok_or_else(|| invalid("`flags` must be an array of tables"))?;
+            let name = identifier(entry, "name", "flags")?;
+
+            if !names.insert(name.clone()) {
+                return Err(invalid(format!("duplicate flags `{}`", name)));
+            }
+
+            let mut flag_names = HashSet::new();
+            let flags = entry
+                .get("flags")
+                .and_then(Value::as_array)
+                .ok_or_else(|| invalid(format!("flags `{}` requires `flags`", name)))?
+                .iter()
+                .map(|flag| {
+                    let flag = flag
+                        .as_table()
+                        .ok_or_else(|| invalid(format!("flags of `{}` must be tables", name)))?;
+                    let flag_name = identifier(flag, "name", &format!("flag of `{}`", name))?;
+
+                    if !flag_names.insert(flag_name.clone()) {
+                        return Err(invalid(format!("duplicate flag `{}.{}`", name, flag_name)));
+                    }
+
+                    Ok(flag_name)
+                })
+                .collect::<std::io::Result<Vec<String>>>()?;
+
+            if flags.len() > 256 {
+                return Err(invalid(format!("flags `{}` exceed 256 flags", name)));
+            }
+
+            Ok(Flags { name, flags })
+        })
+        .collect()
+}
+
+fn generate_flags(f: &Flags) -> String {
+    let flags = f
+        .flags
+        .iter()
+        .enumerate()
+        .map(|(bit, flag)| {
+            let mut mask = [0u8; 32];
+            mask[31 - bit / 8] = 1 << (bit % 8);
+            let mask = mask
+                .iter()
+                .map(|b| format!("{:02x}", b))
+                .collect::<String>();
+            let mask = mask.trim_start_matches('0');
+            let mask = if mask.len() % 2 == 1 {
+                format!("0{}", mask)
+            } else {
+                mask.to_string()
+            };
+
+            Context::new()
+                .text("flag", flag.as_str())
+                .text("bit", bit.to_string())
+                .text("bit_hex", format!("{:02x}", bit))
+                .text("mask", mask)
+        })
+        .collect();
+
+    let context = Context::new()
+        .text("name", f.name.as_str())
+        .list("flags", flags);
+    templates::render(FLAGS_TEMPLATE, &context)
+}
+
+const HEADER: &str = r#"
+//  ------------------------------------------------------------------------------------------------
+//! # Flags Library
+//!
+//! Provides packing of up to 256 boolean flags into a single word, such as a storage slot of
+//! feature toggles or permissions.
+//!
+//! Flag `n` is bit `n` of the word, counting from the least significant bit. Named flags declared
+//! in `libhuff.toml` generate constants for their bit and mask.
+//!
+//! ## API
+//!
+//! - `FLAG_GET` - Reads a flag of a word.
+//! - `FLAG_SET` - Sets a flag of a word.
+//! - `FLAG_CLEAR` - Clears a flag of a word.
+//! - `FLAGS_ANY` - Returns whether any flag of a mask is set.
+//! - `FLAGS_ALL` - Returns whether every flag of a mask is set.
+//!
+//! For a given set of flags, `FLAGS`, and flag, `FLAG`:
+//!
+//! - `FLAGS_FLAG` - Bit of a flag.
+//! - `FLAGS_FLAG_MASK` - Mask of a flag.
+"#;
+
+const FLAGS_TEMPLATE: &str = r#"
+/// ## {{name}} Flags
+///
+/// Bits and masks of the `{{name}}` flags.
+///
+/// | Flag | Bit |
+/// | ---- | --- |
+{{#each flags}}/// | `{{flag}}` | {{bit}} |
+{{/each}}{{#each flags}}#define constant {{name}}_{{flag}} = 0x{{bit_hex}}
+#define constant {{name}}_{{flag}}_MASK = 0x{{mask}}
+{{/each}}"#;
+
+const FLAG_DEFINITION: &str = r#"
+/// ## Flag Get
+///
+/// Reads flag `n` of a word.
+///
+/// ### Template Arguments
+///
+/// - `n`: bit of the flag.
+///
+/// ### Stack Arguments
+///
+/// - `word`: (`stack[0]`) word of flags.
+///
+/// ### Conditions
+///
+/// @pre n < 256
+/// @post result == (word >> n) & 1
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x00 sload
+///     FLAG_GET(0x03)
+///     0x00 mstore
+///     0x20 0x00 return
+/// }
+/// ```
+#define macro FLAG_GET(n) = takes (1) returns (1) {
+    // takes:               // [word]
+    <n>                     // [n, word]
+    shr                     // [shifted]
+    0x01                    // [0x01, shifted]
+    and                     // [flag]
+}
+
+/// ## Flag Set
+///
+/// Sets flag `n` of a word.
+///
+/// ### Template Arguments
+///
+/// - `n`: bit of the flag.
+///
+/// ### Stack Arguments
+///
+/// - `word`: (`stack[0]`) word of flags.
+///
+/// ### Conditions
+///
+/// @pre n < 256
+/// @post result == word | (1 << n)
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x00 sload
+///     FLAG_SET(0x03)
+///     0x00 sstore
+/// }
+/// ```
+#define macro FLAG_SET(n) = takes (1) returns (1) {
+    // takes:               // [word]
+    0x01                    // [0x01, word]
+    <n>                     // [n, 0x01, word]
+    shl                     // [mask, word]
+    or                      // [word]
+}
+
+/// ## Flag Clear
+///
+/// Clears flag `n` of a word.
+///
+/// ### Template Arguments
+///
+/// - `n`: bit of the flag.
+///
+/// ### Stack Arguments
+///
+/// - `word`: (`stack[0]`) word of flags.
+///
+/// ### Conditions
+///
+/// @pre n < 256
+/// @post result == word & ~(1 << n)
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x00 sload
+///     FLAG_CLEAR(0x03)
+///     0x00 sstore
+/// }
+/// ```
+#define macro FLAG_CLEAR(n) = takes (1) returns (1) {
+    // takes:               // [word]
+    0x01                    // [0x01, word]
+    <n>                     // [n, 0x01, word]
+    shl                     // [mask, word]
+    not                     // [clear_mask, word]
+    and                     // [word]
+}
+
+/// ## Flags Any
+///
+/// Returns whether any flag of `mask` is set in a word.
+///
+/// ### Template Arguments
+///
+/// - `mask`: mask of the flags.
+///
+/// ### Stack Arguments
+///
+/// - `word`: (`stack[0]`) word of flags.
+///
+/// ### Conditions
+///
+/// @post result == ((word & mask) != 0)
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x00 sload
+///     FLAGS_ANY(0x0c)
+///     0x00 mstore
+///     0x20 0x00 return
+/// }
+/// ```
+#define macro FLAGS_ANY(mask) = takes (1) returns (1) {
+    // takes:               // [word]
+    <mask>                  // [mask, word]
+    and                     // [set_flags]
+    iszero                  // [none]
+    iszero                  // [any]
+}
+
+/// ## Flags All
+///
+/// Returns whether every flag of `mask` is set in a word.
+///
+/// ### Template Arguments
+///
+/// - `mask`: mask of the flags.
+///
+/// ### Stack Arguments
+///
+/// - `word`: (`stack[0]`) word of flags.
+///
+/// ### Conditions
+///
+/// @post result == ((word & mask) == mask)
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x00 sload
+///     FLAGS_ALL(0x0c)
+///     0x00 mstore
+///     0x20 0x00 return
+/// }
+/// ```
+#define macro FLAGS_ALL(mask) = takes (1) returns (1) {
+    // takes:               // [word]
+    <mask>                  // [mask, word]
+    and                     // [set_flags]
+    <mask>                  // [mask, set_flags]
+    eq                      // [all]
+}
+"#;
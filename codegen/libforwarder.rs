@@ -0,0 +1,132 @@
+pub fn render() -> String {
+    format!("{}{}", HEADER, CONTEXT_DEFINITION)
+}
+
+const HEADER: &str = r#"
+//  ------------------------------------------------------------------------------------------------
+//! # Forwarder Library
+//!
+//! Provides ERC2771 meta transaction context macros.
+//!
+//! A trusted forwarder appends the address of the original sender to the calldata it forwards.
+//! Calls from any other account, or forwarded calls too short to carry a sender, fall back to
+//! `caller` and the full calldata.
+//!
+//! ## API
+//!
+//! - `IS_TRUSTED_FORWARDER` - Checks if an account is the trusted forwarder.
+//! - `MSG_SENDER_2771` - Pushes the original sender of the call.
+//! - `MSG_DATA_2771` - Pushes the length of the original calldata.
+"#;
+
+const CONTEXT_DEFINITION: &str = r#"
+/// ## Is Trusted Forwarder
+///
+/// Checks if an account is the trusted forwarder.
+///
+/// ### Template Arguments
+///
+/// - `forwarder`: address of the trusted forwarder.
+///
+/// ### Stack Arguments
+///
+/// - `account`: (`stack[0]`) account to check.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define constant FORWARDER = 0x0000000000000000000000000000000000000000
+///
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x04 calldataload
+///     IS_TRUSTED_FORWARDER([FORWARDER])
+///     0x00 mstore
+///     0x20 0x00 return
+/// }
+/// ```
+#define macro IS_TRUSTED_FORWARDER(forwarder) = takes (1) returns (1) {
+    // takes:               // [account]
+    <forwarder>             // [forwarder, account]
+    eq                      // [is_forwarder]
+}
+
+/// ## Message Sender 2771
+///
+/// Pushes the original sender of the call, read from the last 20 bytes of calldata if the caller
+/// is the trusted forwarder, otherwise `caller`.
+///
+/// ### Template Arguments
+///
+/// - `forwarder`: address of the trusted forwarder.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define constant FORWARDER = 0x0000000000000000000000000000000000000000
+///
+/// #define macro MAIN() = takes (0) returns (0) {
+///     MSG_SENDER_2771([FORWARDER])
+///     0x00 mstore
+///     0x20 0x00 return
+/// }
+/// ```
+#define macro MSG_SENDER_2771(forwarder) = takes (0) returns (1) {
+    __IS_FORWARDED(<forwarder>) // [is_forwarded]
+    forwarded               // [forwarded_dest, is_forwarded]
+    jumpi                   // []
+        caller              // [sender]
+        done                // [done_dest, sender]
+        jump                // [sender]
+    forwarded:              // []
+        0x14                // [0x14]
+        calldatasize        // [calldatasize, 0x14]
+        sub                 // [sender_offset]
+        calldataload        // [sender_word]
+        0x60                // [0x60, sender_word]
+        shr                 // [sender]
+    done:                   // [sender]
+}
+
+/// ## Message Data 2771
+///
+/// Pushes the length of the original calldata, which excludes the appended sender if the caller is
+/// the trusted forwarder. The original calldata always starts at offset zero.
+///
+/// ### Template Arguments
+///
+/// - `forwarder`: address of the trusted forwarder.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define constant FORWARDER = 0x0000000000000000000000000000000000000000
+///
+/// #define macro MAIN() = takes (0) returns (0) {
+///     MSG_DATA_2771([FORWARDER])  // [len]
+///     0x00                        // [offset, len]
+///     0x00                        // [ptr, offset, len]
+///     calldatacopy                // []
+/// }
+/// ```
+#define macro MSG_DATA_2771(forwarder) = takes (0) returns (1) {
+    calldatasize            // [calldatasize]
+    __IS_FORWARDED(<forwarder>) // [is_forwarded, calldatasize]
+    0x14                    // [0x14, is_forwarded, calldatasize]
+    mul                     // [suffix_len, calldatasize]
+    swap1                   // [calldatasize, suffix_len]
+    sub                     // [len]
+}
+
+/// ## Is Forwarded
+///
+/// Checks if the caller is the trusted forwarder and the calldata is long enough to carry a sender.
+#define macro __IS_FORWARDED(forwarder) = takes (0) returns (1) {
+    caller                  // [caller]
+    IS_TRUSTED_FORWARDER(<forwarder>) // [is_forwarder]
+    0x14                    // [0x14, is_forwarder]
+    calldatasize            // [calldatasize, 0x14, is_forwarder]
+    lt                      // [is_short, is_forwarder]
+    iszero                  // [has_sender, is_forwarder]
+    and                     // [is_forwarded]
+}
+"#;
@@ -0,0 +1,345 @@
+use crate::templates::{self, Context};
+
+/// Renders `libguard.huff`, with the transient storage reentrancy guard where the opcode profile
+/// allows `tload` and `tstore`, from Cancun.
+pub fn render(transient: bool) -> String {
+    let context = Context::new().flag("transient", transient);
+
+    templates::render(LIBRARY_TEMPLATE, &context)
+}
+
+const LIBRARY_TEMPLATE: &str = r#"
+//  ------------------------------------------------------------------------------------------------
+//! # Guard Library
+//!
+//! Provides the guards most contracts open their functions with: owner only access, reentrancy
+//! locks and pausing. Each guard takes the storage slot of its state as a template argument, so
+//! contracts choose their own layout.
+//!
+//! The reentrancy lock stores `1` when unlocked and `2` when locked, treating an unset slot as
+//! unlocked, so leaving a function leaves the slot nonzero and locking it again stays cheap.
+{{#if transient}}//! The transient lock instead clears a transient storage slot on leaving.
+{{/if}}//!
+//! ## API
+//!
+//! - `ONLY_OWNER` - Reverts unless the caller is the owner.
+//! - `NON_REENTRANT_ENTER` - Locks the reentrancy guard, reverting if it is locked.
+//! - `NON_REENTRANT_EXIT` - Unlocks the reentrancy guard.
+{{#if transient}}//! - `TRANSIENT_NON_REENTRANT_ENTER` - Locks the transient reentrancy guard, reverting if
+//!   it is locked.
+//! - `TRANSIENT_NON_REENTRANT_EXIT` - Unlocks the transient reentrancy guard.
+{{/if}}//! - `WHEN_NOT_PAUSED` - Reverts if the contract is paused.
+//! - `WHEN_PAUSED` - Reverts unless the contract is paused.
+//! - `PAUSE` - Pauses the contract.
+//! - `UNPAUSE` - Unpauses the contract.
+
+/// ## Unauthorized Error
+///
+/// Thrown when the caller is not the owner.
+#define error Unauthorized()
+
+/// ## Reentrant Error
+///
+/// Thrown when a guarded function is reentered.
+#define error Reentrant()
+
+/// ## Enforced Pause Error
+///
+/// Thrown when the contract is paused.
+#define error EnforcedPause()
+
+/// ## Expected Pause Error
+///
+/// Thrown when the contract is not paused.
+#define error ExpectedPause()
+
+/// ## Paused Event
+///
+/// Emitted when `account` pauses the contract.
+#define event Paused(address)
+
+/// ## Unpaused Event
+///
+/// Emitted when `account` unpauses the contract.
+#define event Unpaused(address)
+
+/// ## Only Owner
+///
+/// Reverts unless the caller is the stored owner.
+///
+/// ### Template Arguments
+///
+/// - `owner_slot`: storage slot of the owner.
+///
+/// ### Panics
+///
+/// - if the caller is not the owner.
+///
+/// ### Usage
+///
+/// ```huff
+/// #include "libguard.huff"
+///
+/// #define constant OWNER_SLOT = FREE_STORAGE_POINTER()
+///
+/// #define macro MAIN() = takes (0) returns (0) {
+///     ONLY_OWNER([OWNER_SLOT])
+///     stop
+/// }
+/// ```
+#define macro ONLY_OWNER(owner_slot) = takes (0) returns (0) {
+    <owner_slot>            // [owner_slot]
+    sload                   // [owner]
+    caller                  // [caller, owner]
+    eq                      // [is_owner]
+    authorized              // [authorized_dest, is_owner]
+    jumpi                   // []
+        __ERROR(Unauthorized) // [err]
+        0x00                // [ptr, err]
+        mstore              // []
+        0x04                // [err_len]
+        0x00                // [ptr, err_len]
+        revert              // []
+    authorized:             // []
+}
+
+/// ## Non Reentrant Enter
+///
+/// Locks the reentrancy guard for the rest of the call, to be unlocked by `NON_REENTRANT_EXIT`.
+///
+/// ### Template Arguments
+///
+/// - `lock_slot`: storage slot of the lock.
+///
+/// ### Panics
+///
+/// - if the guard is locked.
+///
+/// ### Usage
+///
+/// ```huff
+/// #include "libguard.huff"
+///
+/// #define constant LOCK_SLOT = FREE_STORAGE_POINTER()
+///
+/// #define macro MAIN() = takes (0) returns (0) {
+///     NON_REENTRANT_ENTER([LOCK_SLOT])
+///     NON_REENTRANT_EXIT([LOCK_SLOT])
+///     stop
+/// }
+/// ```
+#define macro NON_REENTRANT_ENTER(lock_slot) = takes (0) returns (0) {
+    <lock_slot>             // [lock_slot]
+    sload                   // [lock]
+    0x02                    // [locked, lock]
+    sub                     // [is_unlocked]
+    unlocked                // [unlocked_dest, is_unlocked]
+    jumpi                   // []
+        __ERROR(Reentrant)  // [err]
+        0x00                // [ptr, err]
+        mstore              // []
+        0x04                // [err_len]
+        0x00                // [ptr, err_len]
+        revert              // []
+    unlocked:               // []
+        0x02                // [locked]
+        <lock_slot>         // [lock_slot, locked]
+        sstore              // []
+}
+
+/// ## Non Reentrant Exit
+///
+/// Unlocks the reentrancy guard locked by `NON_REENTRANT_ENTER`.
+///
+/// ### Template Arguments
+///
+/// - `lock_slot`: storage slot of the lock.
+#define macro NON_REENTRANT_EXIT(lock_slot) = takes (0) returns (0) {
+    0x01                    // [unlocked]
+    <lock_slot>             // [lock_slot, unlocked]
+    sstore                  // []
+}
+{{#if transient}}
+/// ## Transient Non Reentrant Enter
+///
+/// Locks the transient reentrancy guard for the rest of the call, to be unlocked by
+/// `TRANSIENT_NON_REENTRANT_EXIT`. The lock is cleared at the end of the transaction regardless.
+///
+/// ### Template Arguments
+///
+/// - `lock_slot`: transient storage slot of the lock.
+///
+/// ### Panics
+///
+/// - if the guard is locked.
+///
+/// ### Usage
+///
+/// ```huff
+/// #include "libguard.huff"
+///
+/// #define constant LOCK_SLOT = FREE_STORAGE_POINTER()
+///
+/// #define macro MAIN() = takes (0) returns (0) {
+///     TRANSIENT_NON_REENTRANT_ENTER([LOCK_SLOT])
+///     TRANSIENT_NON_REENTRANT_EXIT([LOCK_SLOT])
+///     stop
+/// }
+/// ```
+#define macro TRANSIENT_NON_REENTRANT_ENTER(lock_slot) = takes (0) returns (0) {
+    <lock_slot>             // [lock_slot]
+    tload                   // [lock]
+    iszero                  // [is_unlocked]
+    unlocked                // [unlocked_dest, is_unlocked]
+    jumpi                   // []
+        __ERROR(Reentrant)  // [err]
+        0x00                // [ptr, err]
+        mstore              // []
+        0x04                // [err_len]
+        0x00                // [ptr, err_len]
+        revert              // []
+    unlocked:               // []
+        0x01                // [locked]
+        <lock_slot>         // [lock_slot, locked]
+        tstore              // []
+}
+
+/// ## Transient Non Reentrant Exit
+///
+/// Unlocks the transient reentrancy guard locked by `TRANSIENT_NON_REENTRANT_ENTER`.
+///
+/// ### Template Arguments
+///
+/// - `lock_slot`: transient storage slot of the lock.
+#define macro TRANSIENT_NON_REENTRANT_EXIT(lock_slot) = takes (0) returns (0) {
+    0x00                    // [unlocked]
+    <lock_slot>             // [lock_slot, unlocked]
+    tstore                  // []
+}
+{{/if}}
+/// ## When Not Paused
+///
+/// Reverts if the contract is paused.
+///
+/// ### Template Arguments
+///
+/// - `paused_slot`: storage slot of the paused flag.
+///
+/// ### Panics
+///
+/// - if the contract is paused.
+///
+/// ### Usage
+///
+/// ```huff
+/// #include "libguard.huff"
+///
+/// #define constant PAUSED_SLOT = FREE_STORAGE_POINTER()
+///
+/// #define macro MAIN() = takes (0) returns (0) {
+///     WHEN_NOT_PAUSED([PAUSED_SLOT])
+///     stop
+/// }
+/// ```
+#define macro WHEN_NOT_PAUSED(paused_slot) = takes (0) returns (0) {
+    <paused_slot>           // [paused_slot]
+    sload                   // [paused]
+    iszero                  // [is_unpaused]
+    unpaused                // [unpaused_dest, is_unpaused]
+    jumpi                   // []
+        __ERROR(EnforcedPause) // [err]
+        0x00                // [ptr, err]
+        mstore              // []
+        0x04                // [err_len]
+        0x00                // [ptr, err_len]
+        revert              // []
+    unpaused:               // []
+}
+
+/// ## When Paused
+///
+/// Reverts unless the contract is paused.
+///
+/// ### Template Arguments
+///
+/// - `paused_slot`: storage slot of the paused flag.
+///
+/// ### Panics
+///
+/// - if the contract is not paused.
+#define macro WHEN_PAUSED(paused_slot) = takes (0) returns (0) {
+    <paused_slot>           // [paused_slot]
+    sload                   // [paused]
+    paused                  // [paused_dest, paused]
+    jumpi                   // []
+        __ERROR(ExpectedPause) // [err]
+        0x00                // [ptr, err]
+        mstore              // []
+        0x04                // [err_len]
+        0x00                // [ptr, err_len]
+        revert              // []
+    paused:                 // []
+}
+
+/// ## Pause
+///
+/// Pauses the contract, emitting `Paused` with the caller.
+///
+/// ### Template Arguments
+///
+/// - `paused_slot`: storage slot of the paused flag.
+///
+/// ### Panics
+///
+/// - if the contract is paused.
+///
+/// ### Usage
+///
+/// ```huff
+/// #include "libguard.huff"
+///
+/// #define macro PAUSE_WRAPPER() = takes (0) returns (0) {
+///     ONLY_OWNER([OWNER_SLOT])
+///     PAUSE([PAUSED_SLOT])
+///     stop
+/// }
+/// ```
+#define macro PAUSE(paused_slot) = takes (0) returns (0) {
+    WHEN_NOT_PAUSED(<paused_slot>) // []
+    0x01                    // [paused]
+    <paused_slot>           // [paused_slot, paused]
+    sstore                  // []
+    caller                  // [caller]
+    0x00                    // [ptr, caller]
+    mstore                  // []
+    __EVENT_HASH(Paused)    // [sig]
+    0x20                    // [len, sig]
+    0x00                    // [ptr, len, sig]
+    log1                    // []
+}
+
+/// ## Unpause
+///
+/// Unpauses the contract, emitting `Unpaused` with the caller.
+///
+/// ### Template Arguments
+///
+/// - `paused_slot`: storage slot of the paused flag.
+///
+/// ### Panics
+///
+/// - if the contract is not paused.
+#define macro UNPAUSE(paused_slot) = takes (0) returns (0) {
+    WHEN_PAUSED(<paused_slot>) // []
+    0x00                    // [unpaused]
+    <paused_slot>           // [paused_slot, unpaused]
+    sstore                  // []
+    caller                  // [caller]
+    0x00                    // [ptr, caller]
+    mstore                  // []
+    __EVENT_HASH(Unpaused)  // [sig]
+    0x20                    // [len, sig]
+    0x00                    // [ptr, len, sig]
+    log1                    // []
+}
+"#;
@@ -0,0 +1,247 @@
+use crate::emit::line;
+use crate::templates::{self, Context};
+
+/// Word counts that get a dedicated `HASH_CALLDATA_WORDS_N` macro.
+const WORD_COUNTS: std::ops::RangeInclusive<u16> = 1..=4;
+
+pub fn render() -> String {
+    let words = WORD_COUNTS.map(generate_words).collect::<String>();
+
+    format!(
+        "{}{}{}{}",
+        HEADER, ERROR_DEFINITION, RANGE_DEFINITION, words,
+    )
+}
+
+fn generate_words(words: u16) -> String {
+    let len = format!("0x{:02x}", words * 0x20);
+
+    let mut body = line("// takes:", "[offset]");
+    body.push_str(&line("dup1", "[offset, offset]"));
+    body.push_str(&line(&len, "[len, offset, offset]"));
+    body.push_str(&line("add", "[end, offset]"));
+    body.push_str(&line("__CHECK_CALLDATA_RANGE()", "[offset]"));
+
+    for word in 0..words {
+        let last = word + 1 == words;
+        let rest = if last { "" } else { ", offset" };
+        let step = format!("0x{:02x}", word * 0x20);
+
+        if !last {
+            body.push_str(&line("dup1", "[offset, offset]"));
+        }
+        if word > 0 {
+            body.push_str(&line(&step, &format!("[{}, offset{}]", step, rest)));
+            body.push_str(&line("add", &format!("[word_offset{}]", rest)));
+        }
+        body.push_str(&line("calldataload", &format!("[word{}]", rest)));
+        body.push_str(&line("<ptr>", &format!("[ptr, word{}]", rest)));
+        if word > 0 {
+            body.push_str(&line(&step, &format!("[{}, ptr, word{}]", step, rest)));
+            body.push_str(&line("add", &format!("[word_ptr, word{}]", rest)));
+        }
+        body.push_str(&line("mstore", if last { "[]" } else { "[offset]" }));
+    }
+
+    body.push_str(&line(&len, "[len]"));
+    body.push_str(&line("<ptr>", "[ptr, len]"));
+    body.push_str(&line("sha3", "[hash]"));
+
+    let context = Context::new()
+        .text("count", words.to_string())
+        .text("len", len)
+        .text("body", body);
+    templates::render(WORDS_TEMPLATE, &context)
+}
+
+const HEADER: &str = r#"
+//  ------------------------------------------------------------------------------------------------
+//! # Keccak Library
+//!
+//! Provides bounds checked keccak256 hashing of calldata and memory ranges.
+//!
+//! Word aligned calldata ranges of a fixed number of words have dedicated macros that load each
+//! word directly instead of computing the copy at runtime.
+//!
+//! ## API
+//!
+//! - `HASH_CALLDATA_RANGE` - Hashes a range of calldata.
+//! - `HASH_MEM_RANGE` - Hashes a range of memory.
+//! - `HASH_CALLDATA_WORDS_N` - Hashes `N` words of calldata.
+"#;
+
+const ERROR_DEFINITION: &str = r#"
+/// ## Out Of Bounds Error
+///
+/// Thrown when a range exceeds calldata or overflows.
+#define error OutOfBounds()
+"#;
+
+const RANGE_DEFINITION: &str = r#"
+/// ## Hash Calldata Range
+///
+/// Copies a range of calldata to memory and hashes it.
+///
+/// > WARNING: Memory from `ptr` to `ptr + len` will be overwritten.
+///
+/// ### Template Arguments
+///
+/// - `ptr`: memory pointer to copy the range to.
+///
+/// ### Stack Arguments
+///
+/// - `offset`: (`stack[0]`) calldata offset of the range.
+/// - `len`: (`stack[1]`) length of the range.
+///
+/// ### Panics
+///
+/// - if the range overflows or exceeds calldata.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x24 calldataload
+///     0x04 calldataload
+///     HASH_CALLDATA_RANGE(0x00)
+///     0x00 mstore
+///     0x20 0x00 return
+/// }
+/// ```
+#define macro HASH_CALLDATA_RANGE(ptr) = takes (2) returns (1) {
+    // takes:               // [offset, len]
+    dup2                    // [len, offset, len]
+    dup2                    // [offset, len, offset, len]
+    add                     // [end, offset, len]
+    __CHECK_CALLDATA_RANGE() // [offset, len]
+    dup2                    // [len, offset, len]
+    swap1                   // [offset, len, len]
+    <ptr>                   // [ptr, offset, len, len]
+    calldatacopy            // [len]
+    <ptr>                   // [ptr, len]
+    sha3                    // [hash]
+}
+
+/// ## Hash Memory Range
+///
+/// Hashes a range of memory in place.
+///
+/// ### Stack Arguments
+///
+/// - `ptr`: (`stack[0]`) memory pointer of the range.
+/// - `len`: (`stack[1]`) length of the range.
+///
+/// ### Panics
+///
+/// - if the range overflows.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x40 0x00
+///     HASH_MEM_RANGE()
+///     0x00 mstore
+///     0x20 0x00 return
+/// }
+/// ```
+#define macro HASH_MEM_RANGE() = takes (2) returns (1) {
+    // takes:               // [ptr, len]
+    dup2                    // [len, ptr, len]
+    dup2                    // [ptr, len, ptr, len]
+    add                     // [end, ptr, len]
+    dup2                    // [ptr, end, ptr, len]
+    gt                      // [overflowed, ptr, len]
+    iszero                  // [in_bounds, ptr, len]
+    in_bounds               // [in_bounds_dest, in_bounds, ptr, len]
+    jumpi                   // [ptr, len]
+        __ERROR(OutOfBounds) // [err, ptr, len]
+        0x00                // [ptr, err, ptr, len]
+        mstore              // [ptr, len]
+        0x04                // [err_len, ptr, len]
+        0x00                // [ptr, err_len, ptr, len]
+        revert              // []
+    in_bounds:              // [ptr, len]
+        sha3                // [hash]
+}
+
+/// ## Check Calldata Range
+///
+/// Reverts if a calldata range ending at `end` overflowed or exceeds calldata.
+#define macro __CHECK_CALLDATA_RANGE() = takes (2) returns (1) {
+    // takes:               // [end, offset]
+    dup2                    // [offset, end, offset]
+    dup2                    // [end, offset, end, offset]
+    lt                      // [overflowed, end, offset]
+    swap1                   // [end, overflowed, offset]
+    calldatasize            // [calldatasize, end, overflowed, offset]
+    lt                      // [exceeds, overflowed, offset]
+    or                      // [out_of_bounds, offset]
+    iszero                  // [in_bounds, offset]
+    in_bounds               // [in_bounds_dest, in_bounds, offset]
+    jumpi                   // [offset]
+        __ERROR(OutOfBounds) // [err, offset]
+        0x00                // [ptr, err, offset]
+        mstore              // [offset]
+        0x04                // [err_len, offset]
+        0x00                // [ptr, err_len, offset]
+        revert              // []
+    in_bounds:              // [offset]
+}
+"#;
+
+const WORDS_TEMPLATE: &str = r#"
+/// ## Hash Calldata Words {{count}}
+///
+/// Hashes {{count}} words of calldata, loading each word directly.
+///
+/// > WARNING: Memory from `ptr` to `ptr + {{len}}` will be overwritten.
+///
+/// ### Template Arguments
+///
+/// - `ptr`: memory pointer to load the words to.
+///
+/// ### Stack Arguments
+///
+/// - `offset`: (`stack[0]`) calldata offset of the first word.
+///
+/// ### Panics
+///
+/// - if the range overflows or exceeds calldata.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x04
+///     HASH_CALLDATA_WORDS_{{count}}(0x00)
+///     0x00 mstore
+///     0x20 0x00 return
+/// }
+/// ```
+#define macro HASH_CALLDATA_WORDS_{{count}}(ptr) = takes (1) returns (1) {
+{{body}}}
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn words_of_each_count() {
+        let rendered = render();
+        for count in WORD_COUNTS {
+            let definition = format!("#define macro HASH_CALLDATA_WORDS_{}(ptr)", count);
+            assert!(rendered.contains(&definition));
+        }
+        assert!(!rendered.contains("HASH_CALLDATA_WORDS_5"));
+
+        // a single word is loaded without keeping the offset
+        let one = generate_words(1);
+        assert!(!one.contains("word_offset"));
+        assert!(one.contains("    calldataload            // [word]\n"));
+        let two = generate_words(2);
+        assert!(two.contains("    0x40                    // [len, offset, offset]\n"));
+        assert!(two.contains("    0x20                    // [0x20, ptr, word]\n"));
+    }
+}
@@ -0,0 +1,229 @@
+pub fn render() -> String {
+    format!("{}{}{}", HEADER, SLOT_DEFINITION, ACCESS_DEFINITION)
+}
+
+const HEADER: &str = r#"
+//  ------------------------------------------------------------------------------------------------
+//! # Mapping Library
+//!
+//! Provides macros for deriving and accessing Solidity-compatible mapping slots.
+//!
+//! The slot of `mapping[key]` declared at `slot` is `keccak256(abi.encode(key, slot))`, and the
+//! slot of `mapping[key0][key1]` is `keccak256(abi.encode(key1, keccak256(abi.encode(key0, slot))))`.
+//!
+//! > WARNING: Every macro in this library overwrites memory from `0x00` to `0x40`.
+//!
+//! ## API
+//!
+//! - `MAPPING_SLOT` - Derives the slot of `mapping[key]`.
+//! - `NESTED_MAPPING_SLOT` - Derives the slot of `mapping[key0][key1]`.
+//! - `LOAD_MAPPING` - Loads `mapping[key]`.
+//! - `STORE_MAPPING` - Stores `mapping[key]`.
+//! - `LOAD_NESTED_MAPPING` - Loads `mapping[key0][key1]`.
+//! - `STORE_NESTED_MAPPING` - Stores `mapping[key0][key1]`.
+"#;
+
+const SLOT_DEFINITION: &str = r#"
+/// ## Mapping Slot
+///
+/// Derives the slot of `mapping[key]` for a mapping declared at `slot`.
+///
+/// ### Template Arguments
+///
+/// - `slot`: storage slot the mapping is declared at.
+///
+/// ### Stack Arguments
+///
+/// - `key`: (`stack[0]`) mapping key.
+///
+/// ### Usage
+///
+/// ```huff
+/// #include "libmapping.huff"
+///
+/// #define constant BALANCES_SLOT = FREE_STORAGE_POINTER()
+///
+/// #define macro MAIN() = takes (0) returns (0) {
+///     caller
+///     MAPPING_SLOT([BALANCES_SLOT])
+///     sload
+///     0x00 mstore
+///     0x20 0x00 return
+/// }
+/// ```
+#define macro MAPPING_SLOT(slot) = takes (1) returns (1) {
+    // takes:               // [key]
+    0x00                    // [key_ptr, key]
+    mstore                  // []
+    <slot>                  // [slot]
+    0x20                    // [slot_ptr, slot]
+    mstore                  // []
+    0x40                    // [len]
+    0x00                    // [ptr, len]
+    sha3                    // [value_slot]
+}
+
+/// ## Nested Mapping Slot
+///
+/// Derives the slot of `mapping[key0][key1]` for a mapping declared at `slot`.
+///
+/// ### Template Arguments
+///
+/// - `slot`: storage slot the mapping is declared at.
+///
+/// ### Stack Arguments
+///
+/// - `key0`: (`stack[0]`) outer mapping key.
+/// - `key1`: (`stack[1]`) inner mapping key.
+///
+/// ### Usage
+///
+/// ```huff
+/// #include "libmapping.huff"
+///
+/// #define constant ALLOWANCES_SLOT = FREE_STORAGE_POINTER()
+///
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x04 calldataload caller
+///     NESTED_MAPPING_SLOT([ALLOWANCES_SLOT])
+///     sload
+///     0x00 mstore
+///     0x20 0x00 return
+/// }
+/// ```
+#define macro NESTED_MAPPING_SLOT(slot) = takes (2) returns (1) {
+    // takes:               // [key0, key1]
+    MAPPING_SLOT(<slot>)    // [inner_slot, key1]
+    0x20                    // [inner_slot_ptr, inner_slot, key1]
+    mstore                  // [key1]
+    0x00                    // [key1_ptr, key1]
+    mstore                  // []
+    0x40                    // [len]
+    0x00                    // [ptr, len]
+    sha3                    // [value_slot]
+}
+"#;
+
+const ACCESS_DEFINITION: &str = r#"
+/// ## Load Mapping
+///
+/// Loads `mapping[key]` for a mapping declared at `slot`.
+///
+/// ### Template Arguments
+///
+/// - `slot`: storage slot the mapping is declared at.
+///
+/// ### Stack Arguments
+///
+/// - `key`: (`stack[0]`) mapping key.
+///
+/// ### Usage
+///
+/// ```huff
+/// #include "libmapping.huff"
+///
+/// #define macro MAIN() = takes (0) returns (0) {
+///     caller
+///     LOAD_MAPPING([BALANCES_SLOT])
+///     0x00 mstore
+///     0x20 0x00 return
+/// }
+/// ```
+#define macro LOAD_MAPPING(slot) = takes (1) returns (1) {
+    // takes:               // [key]
+    MAPPING_SLOT(<slot>)    // [value_slot]
+    sload                   // [value]
+}
+
+/// ## Store Mapping
+///
+/// Stores `mapping[key]` for a mapping declared at `slot`.
+///
+/// ### Template Arguments
+///
+/// - `slot`: storage slot the mapping is declared at.
+///
+/// ### Stack Arguments
+///
+/// - `key`: (`stack[0]`) mapping key.
+/// - `value`: (`stack[1]`) value to store.
+///
+/// ### Usage
+///
+/// ```huff
+/// #include "libmapping.huff"
+///
+/// #define macro MAIN() = takes (0) returns (0) {
+///     callvalue caller
+///     STORE_MAPPING([BALANCES_SLOT])
+///     stop
+/// }
+/// ```
+#define macro STORE_MAPPING(slot) = takes (2) returns (0) {
+    // takes:               // [key, value]
+    MAPPING_SLOT(<slot>)    // [value_slot, value]
+    sstore                  // []
+}
+
+/// ## Load Nested Mapping
+///
+/// Loads `mapping[key0][key1]` for a mapping declared at `slot`.
+///
+/// ### Template Arguments
+///
+/// - `slot`: storage slot the mapping is declared at.
+///
+/// ### Stack Arguments
+///
+/// - `key0`: (`stack[0]`) outer mapping key.
+/// - `key1`: (`stack[1]`) inner mapping key.
+///
+/// ### Usage
+///
+/// ```huff
+/// #include "libmapping.huff"
+///
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x04 calldataload caller
+///     LOAD_NESTED_MAPPING([ALLOWANCES_SLOT])
+///     0x00 mstore
+///     0x20 0x00 return
+/// }
+/// ```
+#define macro LOAD_NESTED_MAPPING(slot) = takes (2) returns (1) {
+    // takes:                   // [key0, key1]
+    NESTED_MAPPING_SLOT(<slot>) // [value_slot]
+    sload                       // [value]
+}
+
+/// ## Store Nested Mapping
+///
+/// Stores `mapping[key0][key1]` for a mapping declared at `slot`.
+///
+/// ### Template Arguments
+///
+/// - `slot`: storage slot the mapping is declared at.
+///
+/// ### Stack Arguments
+///
+/// - `key0`: (`stack[0]`) outer mapping key.
+/// - `key1`: (`stack[1]`) inner mapping key.
+/// - `value`: (`stack[2]`) value to store.
+///
+/// ### Usage
+///
+/// ```huff
+/// #include "libmapping.huff"
+///
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x24 calldataload 0x04 calldataload caller
+///     STORE_NESTED_MAPPING([ALLOWANCES_SLOT])
+///     stop
+/// }
+/// ```
+#define macro STORE_NESTED_MAPPING(slot) = takes (3) returns (0) {
+    // takes:                   // [key0, key1, value]
+    NESTED_MAPPING_SLOT(<slot>) // [value_slot, value]
+    sstore                      // []
+}
+"#;
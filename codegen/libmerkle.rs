@@ -0,0 +1,244 @@
+pub fn render() -> String {
+    format!(
+        "{}{}{}{}{}",
+        HEADER, CALLDATA_DEFINITION, MEMORY_DEFINITION, VERIFY_DEFINITION, HASH_PAIR_DEFINITION,
+    )
+}
+
+const HEADER: &str = r#"
+//  ------------------------------------------------------------------------------------------------
+//! # Merkle Library
+//!
+//! Provides verification of Merkle proofs of trees hashing sorted pairs, as OpenZeppelin's
+//! `MerkleProof` does: each parent is the keccak256 hash of its two children, the smaller first.
+//!
+//! A proof is laid out as a `bytes32[]` is ABI encoded: a length word, the number of nodes,
+//! followed by the nodes from the leaf up. Hashing uses the scratch space, `0x00` to `0x40`.
+//!
+//! ## API
+//!
+//! - `PROCESS_CALLDATA_PROOF` - Computes the root of a leaf and a proof in calldata.
+//! - `PROCESS_MEMORY_PROOF` - Computes the root of a leaf and a proof in memory.
+//! - `VERIFY_CALLDATA_PROOF` - Returns whether a proof in calldata proves a leaf of a root.
+//! - `VERIFY_MEMORY_PROOF` - Returns whether a proof in memory proves a leaf of a root.
+
+/// ## Out Of Bounds Error
+///
+/// Thrown when a proof exceeds calldata.
+#define error OutOfBounds()
+"#;
+
+const CALLDATA_DEFINITION: &str = r#"
+/// ## Process Calldata Proof
+///
+/// Computes the root of the tree a leaf belongs to by the proof at `proof_offset` in calldata.
+///
+/// ### Template Arguments
+///
+/// - `proof_offset`: calldata offset of the length word of the proof, such as `0x64` for the
+///   `bytes32[]` of `verify(bytes32,bytes32,bytes32[])`, encoded after the static arguments.
+///
+/// ### Stack Arguments
+///
+/// - `leaf`: (`stack[0]`) leaf to prove.
+///
+/// ### Panics
+///
+/// - if the proof exceeds calldata.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x24 calldataload
+///     PROCESS_CALLDATA_PROOF(0x64)
+///     0x00 mstore
+///     0x20 0x00 return
+/// }
+/// ```
+#define macro PROCESS_CALLDATA_PROOF(proof_offset) = takes (1) returns (1) {
+    // takes:               // [leaf]
+    <proof_offset>          // [proof_offset, leaf]
+    calldataload            // [count, leaf]
+    dup1                    // [count, count, leaf]
+    0x05                    // [0x05, count, count, leaf]
+    shl                     // [len, count, leaf]
+    swap1                   // [count, len, leaf]
+    calldatasize            // [calldatasize, count, len, leaf]
+    lt                      // [is_too_long, len, leaf]
+    swap1                   // [len, is_too_long, leaf]
+    <proof_offset>          // [proof_offset, len, is_too_long, leaf]
+    0x20                    // [0x20, proof_offset, len, is_too_long, leaf]
+    add                     // [node_ptr, len, is_too_long, leaf]
+    swap1                   // [len, node_ptr, is_too_long, leaf]
+    dup2                    // [node_ptr, len, node_ptr, is_too_long, leaf]
+    add                     // [end, node_ptr, is_too_long, leaf]
+    swap2                   // [is_too_long, node_ptr, end, leaf]
+    dup3                    // [end, is_too_long, node_ptr, end, leaf]
+    calldatasize            // [calldatasize, end, is_too_long, node_ptr, end, leaf]
+    lt                      // [is_past_end, is_too_long, node_ptr, end, leaf]
+    or                      // [out_of_bounds, node_ptr, end, leaf]
+    iszero                  // [in_bounds, node_ptr, end, leaf]
+    loop                    // [loop_dest, in_bounds, node_ptr, end, leaf]
+    jumpi                   // [node_ptr, end, leaf]
+        __ERROR(OutOfBounds) // [err, node_ptr, end, leaf]
+        0x00                // [ptr, err, node_ptr, end, leaf]
+        mstore              // [node_ptr, end, leaf]
+        0x04                // [err_len, node_ptr, end, leaf]
+        0x00                // [ptr, err_len, node_ptr, end, leaf]
+        revert              // []
+    loop:                   // [node_ptr, end, computed]
+        dup2                // [end, node_ptr, end, computed]
+        dup2                // [node_ptr, end, node_ptr, end, computed]
+        lt                  // [is_pending, node_ptr, end, computed]
+        iszero              // [is_done, node_ptr, end, computed]
+        done                // [done_dest, is_done, node_ptr, end, computed]
+        jumpi               // [node_ptr, end, computed]
+        dup3                // [computed, node_ptr, end, computed]
+        dup2                // [node_ptr, computed, node_ptr, end, computed]
+        calldataload        // [node, computed, node_ptr, end, computed]
+        __HASH_PAIR()       // [parent, node_ptr, end, computed]
+        swap3               // [computed, node_ptr, end, parent]
+        pop                 // [node_ptr, end, computed]
+        0x20                // [0x20, node_ptr, end, computed]
+        add                 // [node_ptr, end, computed]
+        loop                // [loop_dest, node_ptr, end, computed]
+        jump                // [node_ptr, end, computed]
+    done:                   // [node_ptr, end, root]
+        pop                 // [end, root]
+        pop                 // [root]
+}
+"#;
+
+const MEMORY_DEFINITION: &str = r#"
+/// ## Process Memory Proof
+///
+/// Computes the root of the tree a leaf belongs to by the proof at `ptr` in memory.
+///
+/// ### Stack Arguments
+///
+/// - `ptr`: (`stack[0]`) memory pointer of the length word of the proof, at least `0x40`.
+/// - `leaf`: (`stack[1]`) leaf to prove.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x64 calldataload 0x05 shl 0x20 add
+///     0x64 0x80 calldatacopy
+///     0x24 calldataload
+///     0x80
+///     PROCESS_MEMORY_PROOF()
+///     0x00 mstore
+///     0x20 0x00 return
+/// }
+/// ```
+#define macro PROCESS_MEMORY_PROOF() = takes (2) returns (1) {
+    // takes:               // [ptr, leaf]
+    dup1                    // [ptr, ptr, leaf]
+    mload                   // [count, ptr, leaf]
+    0x05                    // [0x05, count, ptr, leaf]
+    shl                     // [len, ptr, leaf]
+    swap1                   // [ptr, len, leaf]
+    0x20                    // [0x20, ptr, len, leaf]
+    add                     // [node_ptr, len, leaf]
+    swap1                   // [len, node_ptr, leaf]
+    dup2                    // [node_ptr, len, node_ptr, leaf]
+    add                     // [end, node_ptr, leaf]
+    swap1                   // [node_ptr, end, leaf]
+    loop:                   // [node_ptr, end, computed]
+        dup2                // [end, node_ptr, end, computed]
+        dup2                // [node_ptr, end, node_ptr, end, computed]
+        lt                  // [is_pending, node_ptr, end, computed]
+        iszero              // [is_done, node_ptr, end, computed]
+        done                // [done_dest, is_done, node_ptr, end, computed]
+        jumpi               // [node_ptr, end, computed]
+        dup3                // [computed, node_ptr, end, computed]
+        dup2                // [node_ptr, computed, node_ptr, end, computed]
+        mload               // [node, computed, node_ptr, end, computed]
+        __HASH_PAIR()       // [parent, node_ptr, end, computed]
+        swap3               // [computed, node_ptr, end, parent]
+        pop                 // [node_ptr, end, computed]
+        0x20                // [0x20, node_ptr, end, computed]
+        add                 // [node_ptr, end, computed]
+        loop                // [loop_dest, node_ptr, end, computed]
+        jump                // [node_ptr, end, computed]
+    done:                   // [node_ptr, end, root]
+        pop                 // [end, root]
+        pop                 // [root]
+}
+"#;
+
+const VERIFY_DEFINITION: &str = r#"
+/// ## Verify Calldata Proof
+///
+/// Returns whether the proof at `proof_offset` in calldata proves a leaf of a root.
+///
+/// ### Template Arguments
+///
+/// - `proof_offset`: calldata offset of the length word of the proof.
+///
+/// ### Stack Arguments
+///
+/// - `leaf`: (`stack[0]`) leaf to prove.
+/// - `root`: (`stack[1]`) root of the tree.
+///
+/// ### Panics
+///
+/// - if the proof exceeds calldata.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x04 calldataload
+///     0x24 calldataload
+///     VERIFY_CALLDATA_PROOF(0x64)
+///     0x00 mstore
+///     0x20 0x00 return
+/// }
+/// ```
+#define macro VERIFY_CALLDATA_PROOF(proof_offset) = takes (2) returns (1) {
+    // takes:               // [leaf, root]
+    PROCESS_CALLDATA_PROOF(<proof_offset>) // [computed, root]
+    eq                      // [is_valid]
+}
+
+/// ## Verify Memory Proof
+///
+/// Returns whether the proof at `ptr` in memory proves a leaf of a root.
+///
+/// ### Stack Arguments
+///
+/// - `ptr`: (`stack[0]`) memory pointer of the length word of the proof, at least `0x40`.
+/// - `leaf`: (`stack[1]`) leaf to prove.
+/// - `root`: (`stack[2]`) root of the tree.
+#define macro VERIFY_MEMORY_PROOF() = takes (3) returns (1) {
+    // takes:               // [ptr, leaf, root]
+    PROCESS_MEMORY_PROOF()  // [computed, root]
+    eq                      // [is_valid]
+}
+"#;
+
+const HASH_PAIR_DEFINITION: &str = r#"
+/// ## Hash Pair
+///
+/// Hashes two nodes, the smaller first, in the scratch space.
+#define macro __HASH_PAIR() = takes (2) returns (1) {
+    // takes:               // [a, b]
+    dup2                    // [b, a, b]
+    dup2                    // [a, b, a, b]
+    gt                      // [is_greater, a, b]
+    0x05                    // [0x05, is_greater, a, b]
+    shl                     // [a_ptr, a, b]
+    swap1                   // [a, a_ptr, b]
+    dup2                    // [a_ptr, a, a_ptr, b]
+    mstore                  // [a_ptr, b]
+    0x20                    // [0x20, a_ptr, b]
+    xor                     // [b_ptr, b]
+    mstore                  // []
+    0x40                    // [0x40]
+    0x00                    // [ptr, 0x40]
+    sha3                    // [parent]
+}
+"#;
@@ -0,0 +1,693 @@
+pub fn render() -> String {
+    format!(
+        "{}{}{}{}{}{}",
+        HEADER,
+        INTERFACE_DEFINITION,
+        EVENT_DEFINITION,
+        GETTER_DEFINITION,
+        TRANSFER_DEFINITION,
+        SUPPLY_DEFINITION,
+    )
+}
+
+const HEADER: &str = r#"
+//  ------------------------------------------------------------------------------------------------
+//! # Multi Token Library
+//!
+//! Provides a minimal ERC6909 multi token implementation.
+//!
+//! Balances, allowances and operators are stored in Solidity compatible mappings.
+//!
+//! ```solidity
+//! mapping(address owner => mapping(uint256 id => uint256 amount)) balanceOf;
+//! mapping(address owner => mapping(address spender => mapping(uint256 id => uint256 amount))) allowance;
+//! mapping(address owner => mapping(address spender => bool)) isOperator;
+//! ```
+//!
+//! The macros do not return; the caller is expected to return `true` from the mutating functions.
+//!
+//! ## API
+//!
+//! - `ERC6909_BALANCE_OF` - Loads the balance of an owner for an id.
+//! - `ERC6909_ALLOWANCE` - Loads the allowance of a spender over an owner's id.
+//! - `ERC6909_IS_OPERATOR` - Checks if a spender is an operator of an owner.
+//! - `ERC6909_TRANSFER` - Transfers an amount of an id from the caller.
+//! - `ERC6909_TRANSFER_FROM` - Transfers an amount of an id on behalf of a sender.
+//! - `ERC6909_APPROVE` - Sets the allowance of a spender over the caller's id.
+//! - `ERC6909_SET_OPERATOR` - Sets or unsets a spender as an operator of the caller.
+//! - `ERC6909_MINT` - Mints an amount of an id to a receiver.
+//! - `ERC6909_BURN` - Burns an amount of an id from a sender.
+//! - `ERC6909_EMIT_TRANSFER` - Emits a `Transfer` event.
+//! - `ERC6909_EMIT_APPROVAL` - Emits an `Approval` event.
+//! - `ERC6909_EMIT_OPERATOR_SET` - Emits an `OperatorSet` event.
+
+#include "libmapping.huff"
+"#;
+
+const INTERFACE_DEFINITION: &str = r#"
+/// ## ERC6909 Interface
+#define function balanceOf(address,uint256) view returns (uint256)
+#define function allowance(address,address,uint256) view returns (uint256)
+#define function isOperator(address,address) view returns (bool)
+#define function transfer(address,uint256,uint256) nonpayable returns (bool)
+#define function transferFrom(address,address,uint256,uint256) nonpayable returns (bool)
+#define function approve(address,uint256,uint256) nonpayable returns (bool)
+#define function setOperator(address,bool) nonpayable returns (bool)
+
+#define event Transfer(address, address indexed, address indexed, uint256 indexed, uint256)
+#define event Approval(address indexed, address indexed, uint256 indexed, uint256)
+#define event OperatorSet(address indexed, address indexed, bool)
+
+/// ## ERC6909 Interface ID
+///
+/// ERC165 interface id of ERC6909.
+#define constant ERC6909_INTERFACE_ID = 0x0f632fb3
+
+/// ## Insufficient Balance Error
+///
+/// Thrown when an amount exceeds the balance of its owner.
+#define error InsufficientBalance()
+
+/// ## Insufficient Permission Error
+///
+/// Thrown when the caller is neither the sender nor an operator and its allowance is insufficient.
+#define error InsufficientPermission()
+
+/// ## Balance Overflow Error
+///
+/// Thrown when crediting an amount overflows the balance of its owner.
+#define error BalanceOverflow()
+"#;
+
+const EVENT_DEFINITION: &str = r#"
+/// ## Emit Transfer
+///
+/// Emits a `Transfer` event with the caller as the `caller` field.
+///
+/// > WARNING: Memory from `0x00` to `0x40` will be overwritten.
+///
+/// ### Stack Arguments
+///
+/// - `sender`: (`stack[0]`) sender of the transfer, zero for mints.
+/// - `receiver`: (`stack[1]`) receiver of the transfer, zero for burns.
+/// - `id`: (`stack[2]`) token id.
+/// - `amount`: (`stack[3]`) amount transferred.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x01 0x02 0x03 caller
+///     ERC6909_EMIT_TRANSFER()
+///     stop
+/// }
+/// ```
+#define macro ERC6909_EMIT_TRANSFER() = takes (4) returns (0) {
+    // takes:               // [sender, receiver, id, amount]
+    caller                  // [caller, sender, receiver, id, amount]
+    0x00                    // [ptr, caller, sender, receiver, id, amount]
+    mstore                  // [sender, receiver, id, amount]
+    swap3                   // [amount, receiver, id, sender]
+    0x20                    // [amount_ptr, amount, receiver, id, sender]
+    mstore                  // [receiver, id, sender]
+    swap1                   // [id, receiver, sender]
+    swap2                   // [sender, receiver, id]
+    __EVENT_HASH(Transfer)  // [sig, sender, receiver, id]
+    0x40                    // [len, sig, sender, receiver, id]
+    0x00                    // [ptr, len, sig, sender, receiver, id]
+    log4                    // []
+}
+
+/// ## Emit Approval
+///
+/// Emits an `Approval` event.
+///
+/// > WARNING: Memory from `0x00` to `0x20` will be overwritten.
+///
+/// ### Stack Arguments
+///
+/// - `owner`: (`stack[0]`) owner of the tokens.
+/// - `spender`: (`stack[1]`) approved spender.
+/// - `id`: (`stack[2]`) token id.
+/// - `amount`: (`stack[3]`) approved amount.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x01 0x02 0x03 caller
+///     ERC6909_EMIT_APPROVAL()
+///     stop
+/// }
+/// ```
+#define macro ERC6909_EMIT_APPROVAL() = takes (4) returns (0) {
+    // takes:               // [owner, spender, id, amount]
+    swap3                   // [amount, spender, id, owner]
+    0x00                    // [ptr, amount, spender, id, owner]
+    mstore                  // [spender, id, owner]
+    swap1                   // [id, spender, owner]
+    swap2                   // [owner, spender, id]
+    __EVENT_HASH(Approval)  // [sig, owner, spender, id]
+    0x20                    // [len, sig, owner, spender, id]
+    0x00                    // [ptr, len, sig, owner, spender, id]
+    log4                    // []
+}
+
+/// ## Emit Operator Set
+///
+/// Emits an `OperatorSet` event.
+///
+/// > WARNING: Memory from `0x00` to `0x20` will be overwritten.
+///
+/// ### Stack Arguments
+///
+/// - `owner`: (`stack[0]`) owner of the tokens.
+/// - `spender`: (`stack[1]`) operator.
+/// - `approved`: (`stack[2]`) whether the spender is an operator.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x01 0x02 caller
+///     ERC6909_EMIT_OPERATOR_SET()
+///     stop
+/// }
+/// ```
+#define macro ERC6909_EMIT_OPERATOR_SET() = takes (3) returns (0) {
+    // takes:               // [owner, spender, approved]
+    swap2                   // [approved, spender, owner]
+    0x00                    // [ptr, approved, spender, owner]
+    mstore                  // [spender, owner]
+    swap1                   // [owner, spender]
+    __EVENT_HASH(OperatorSet) // [sig, owner, spender]
+    0x20                    // [len, sig, owner, spender]
+    0x00                    // [ptr, len, sig, owner, spender]
+    log3                    // []
+}
+"#;
+
+const GETTER_DEFINITION: &str = r#"
+/// ## Balance Of
+///
+/// Loads the balance of an owner for an id.
+///
+/// > WARNING: Memory from `0x00` to `0x40` will be overwritten.
+///
+/// ### Template Arguments
+///
+/// - `balance_slot`: storage slot of the balance mapping.
+///
+/// ### Stack Arguments
+///
+/// - `owner`: (`stack[0]`) owner of the tokens.
+/// - `id`: (`stack[1]`) token id.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define constant BALANCE_SLOT = FREE_STORAGE_POINTER()
+///
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x24 calldataload
+///     0x04 calldataload
+///     ERC6909_BALANCE_OF([BALANCE_SLOT])
+///     0x00 mstore
+///     0x20 0x00 return
+/// }
+/// ```
+#define macro ERC6909_BALANCE_OF(balance_slot) = takes (2) returns (1) {
+    // takes:               // [owner, id]
+    LOAD_NESTED_MAPPING(<balance_slot>) // [balance]
+}
+
+/// ## Allowance
+///
+/// Loads the allowance of a spender over an owner's id.
+///
+/// > WARNING: Memory from `0x00` to `0x40` will be overwritten.
+///
+/// ### Template Arguments
+///
+/// - `allowance_slot`: storage slot of the allowance mapping.
+///
+/// ### Stack Arguments
+///
+/// - `owner`: (`stack[0]`) owner of the tokens.
+/// - `spender`: (`stack[1]`) spender of the tokens.
+/// - `id`: (`stack[2]`) token id.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define constant ALLOWANCE_SLOT = FREE_STORAGE_POINTER()
+///
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x44 calldataload
+///     0x24 calldataload
+///     0x04 calldataload
+///     ERC6909_ALLOWANCE([ALLOWANCE_SLOT])
+///     0x00 mstore
+///     0x20 0x00 return
+/// }
+/// ```
+#define macro ERC6909_ALLOWANCE(allowance_slot) = takes (3) returns (1) {
+    // takes:               // [owner, spender, id]
+    __ERC6909_ALLOWANCE_SLOT(<allowance_slot>) // [slot]
+    sload                   // [allowance]
+}
+
+/// ## Is Operator
+///
+/// Checks if a spender is an operator of an owner.
+///
+/// > WARNING: Memory from `0x00` to `0x40` will be overwritten.
+///
+/// ### Template Arguments
+///
+/// - `operator_slot`: storage slot of the operator mapping.
+///
+/// ### Stack Arguments
+///
+/// - `owner`: (`stack[0]`) owner of the tokens.
+/// - `spender`: (`stack[1]`) potential operator.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define constant OPERATOR_SLOT = FREE_STORAGE_POINTER()
+///
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x24 calldataload
+///     0x04 calldataload
+///     ERC6909_IS_OPERATOR([OPERATOR_SLOT])
+///     0x00 mstore
+///     0x20 0x00 return
+/// }
+/// ```
+#define macro ERC6909_IS_OPERATOR(operator_slot) = takes (2) returns (1) {
+    // takes:               // [owner, spender]
+    LOAD_NESTED_MAPPING(<operator_slot>) // [is_operator]
+}
+
+/// ## Allowance Slot
+///
+/// Computes the storage slot of `allowance[owner][spender][id]`.
+#define macro __ERC6909_ALLOWANCE_SLOT(allowance_slot) = takes (3) returns (1) {
+    // takes:               // [owner, spender, id]
+    NESTED_MAPPING_SLOT(<allowance_slot>) // [spender_slot, id]
+    0x20                    // [ptr, spender_slot, id]
+    mstore                  // [id]
+    0x00                    // [ptr, id]
+    mstore                  // []
+    0x40                    // [len]
+    0x00                    // [ptr, len]
+    sha3                    // [slot]
+}
+"#;
+
+const TRANSFER_DEFINITION: &str = r#"
+/// ## Transfer
+///
+/// Transfers an amount of an id from the caller and emits `Transfer`.
+///
+/// > WARNING: Memory from `0x00` to `0x40` will be overwritten.
+///
+/// ### Template Arguments
+///
+/// - `balance_slot`: storage slot of the balance mapping.
+///
+/// ### Stack Arguments
+///
+/// - `receiver`: (`stack[0]`) receiver of the tokens.
+/// - `id`: (`stack[1]`) token id.
+/// - `amount`: (`stack[2]`) amount to transfer.
+///
+/// ### Panics
+///
+/// - if `amount` exceeds the balance of the caller
+/// - if `amount` overflows the balance of `receiver`
+///
+/// ### Usage
+///
+/// ```huff
+/// #define constant BALANCE_SLOT = FREE_STORAGE_POINTER()
+///
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x44 calldataload
+///     0x24 calldataload
+///     0x04 calldataload
+///     ERC6909_TRANSFER([BALANCE_SLOT])
+///     0x01 0x00 mstore
+///     0x20 0x00 return
+/// }
+/// ```
+#define macro ERC6909_TRANSFER(balance_slot) = takes (3) returns (0) {
+    // takes:               // [receiver, id, amount]
+    caller                  // [caller, receiver, id, amount]
+    __ERC6909_TRANSFER(<balance_slot>) // []
+}
+
+/// ## Transfer From
+///
+/// Transfers an amount of an id on behalf of a sender and emits `Transfer`.
+///
+/// Unless the caller is the sender or one of its operators, the amount is deducted from the
+/// caller's allowance. An allowance of `type(uint256).max` is never deducted.
+///
+/// > WARNING: Memory from `0x00` to `0x40` will be overwritten.
+///
+/// ### Template Arguments
+///
+/// - `balance_slot`: storage slot of the balance mapping.
+/// - `allowance_slot`: storage slot of the allowance mapping.
+/// - `operator_slot`: storage slot of the operator mapping.
+///
+/// ### Stack Arguments
+///
+/// - `sender`: (`stack[0]`) sender of the tokens.
+/// - `receiver`: (`stack[1]`) receiver of the tokens.
+/// - `id`: (`stack[2]`) token id.
+/// - `amount`: (`stack[3]`) amount to transfer.
+///
+/// ### Panics
+///
+/// - if the caller is not permitted to transfer `amount` of `id` from `sender`
+/// - if `amount` exceeds the balance of `sender`
+/// - if `amount` overflows the balance of `receiver`
+///
+/// ### Usage
+///
+/// ```huff
+/// #define constant BALANCE_SLOT = FREE_STORAGE_POINTER()
+/// #define constant ALLOWANCE_SLOT = FREE_STORAGE_POINTER()
+/// #define constant OPERATOR_SLOT = FREE_STORAGE_POINTER()
+///
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x64 calldataload
+///     0x44 calldataload
+///     0x24 calldataload
+///     0x04 calldataload
+///     ERC6909_TRANSFER_FROM([BALANCE_SLOT], [ALLOWANCE_SLOT], [OPERATOR_SLOT])
+///     0x01 0x00 mstore
+///     0x20 0x00 return
+/// }
+/// ```
+#define macro ERC6909_TRANSFER_FROM(balance_slot, allowance_slot, operator_slot) = takes (4) returns (0) {
+    // takes:               // [sender, receiver, id, amount]
+    dup1                    // [sender, sender, receiver, id, amount]
+    caller                  // [caller, sender, sender, receiver, id, amount]
+    eq                      // [is_sender, sender, receiver, id, amount]
+    permitted               // [permitted_dest, is_sender, sender, receiver, id, amount]
+    jumpi                   // [sender, receiver, id, amount]
+    caller                  // [caller, sender, receiver, id, amount]
+    dup2                    // [sender, caller, sender, receiver, id, amount]
+    ERC6909_IS_OPERATOR(<operator_slot>) // [is_operator, sender, receiver, id, amount]
+    permitted               // [permitted_dest, is_operator, sender, receiver, id, amount]
+    jumpi                   // [sender, receiver, id, amount]
+
+    dup3                    // [id, sender, receiver, id, amount]
+    caller                  // [caller, id, sender, receiver, id, amount]
+    dup3                    // [sender, caller, id, sender, receiver, id, amount]
+    __ERC6909_ALLOWANCE_SLOT(<allowance_slot>) // [slot, sender, receiver, id, amount]
+    dup1                    // [slot, slot, sender, receiver, id, amount]
+    sload                   // [allowance, slot, sender, receiver, id, amount]
+    dup1                    // [allowance, allowance, slot, sender, receiver, id, amount]
+    not                     // [not_allowance, allowance, slot, sender, receiver, id, amount]
+    iszero                  // [is_max, allowance, slot, sender, receiver, id, amount]
+    unlimited               // [unlimited_dest, is_max, allowance, slot, sender, receiver, id, amount]
+    jumpi                   // [allowance, slot, sender, receiver, id, amount]
+    dup1                    // [allowance, allowance, slot, sender, receiver, id, amount]
+    dup7                    // [amount, allowance, allowance, slot, sender, receiver, id, amount]
+    gt                      // [insufficient, allowance, slot, sender, receiver, id, amount]
+    iszero                  // [sufficient, allowance, slot, sender, receiver, id, amount]
+    sufficient              // [sufficient_dest, sufficient, allowance, slot, sender, receiver, id, amount]
+    jumpi                   // [allowance, slot, sender, receiver, id, amount]
+        __ERROR(InsufficientPermission) // [err, allowance, slot, sender, receiver, id, amount]
+        0x00                // [ptr, err, allowance, slot, sender, receiver, id, amount]
+        mstore              // [allowance, slot, sender, receiver, id, amount]
+        0x04                // [err_len, allowance, slot, sender, receiver, id, amount]
+        0x00                // [ptr, err_len, allowance, slot, sender, receiver, id, amount]
+        revert              // []
+    sufficient:             // [allowance, slot, sender, receiver, id, amount]
+        dup6                // [amount, allowance, slot, sender, receiver, id, amount]
+        swap1               // [allowance, amount, slot, sender, receiver, id, amount]
+        sub                 // [new_allowance, slot, sender, receiver, id, amount]
+        swap1               // [slot, new_allowance, sender, receiver, id, amount]
+        sstore              // [sender, receiver, id, amount]
+        permitted           // [permitted_dest, sender, receiver, id, amount]
+        jump                // [sender, receiver, id, amount]
+    unlimited:              // [allowance, slot, sender, receiver, id, amount]
+        pop                 // [slot, sender, receiver, id, amount]
+        pop                 // [sender, receiver, id, amount]
+    permitted:              // [sender, receiver, id, amount]
+        __ERC6909_TRANSFER(<balance_slot>) // []
+}
+
+/// ## Approve
+///
+/// Sets the allowance of a spender over the caller's id and emits `Approval`.
+///
+/// > WARNING: Memory from `0x00` to `0x40` will be overwritten.
+///
+/// ### Template Arguments
+///
+/// - `allowance_slot`: storage slot of the allowance mapping.
+///
+/// ### Stack Arguments
+///
+/// - `spender`: (`stack[0]`) spender of the tokens.
+/// - `id`: (`stack[1]`) token id.
+/// - `amount`: (`stack[2]`) amount to approve.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define constant ALLOWANCE_SLOT = FREE_STORAGE_POINTER()
+///
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x44 calldataload
+///     0x24 calldataload
+///     0x04 calldataload
+///     ERC6909_APPROVE([ALLOWANCE_SLOT])
+///     0x01 0x00 mstore
+///     0x20 0x00 return
+/// }
+/// ```
+#define macro ERC6909_APPROVE(allowance_slot) = takes (3) returns (0) {
+    // takes:               // [spender, id, amount]
+    dup3                    // [amount, spender, id, amount]
+    dup3                    // [id, amount, spender, id, amount]
+    dup3                    // [spender, id, amount, spender, id, amount]
+    caller                  // [caller, spender, id, amount, spender, id, amount]
+    __ERC6909_ALLOWANCE_SLOT(<allowance_slot>) // [slot, amount, spender, id, amount]
+    sstore                  // [spender, id, amount]
+    caller                  // [caller, spender, id, amount]
+    ERC6909_EMIT_APPROVAL() // []
+}
+
+/// ## Set Operator
+///
+/// Sets or unsets a spender as an operator of the caller and emits `OperatorSet`.
+///
+/// > WARNING: Memory from `0x00` to `0x40` will be overwritten.
+///
+/// ### Template Arguments
+///
+/// - `operator_slot`: storage slot of the operator mapping.
+///
+/// ### Stack Arguments
+///
+/// - `spender`: (`stack[0]`) operator.
+/// - `approved`: (`stack[1]`) whether the spender is an operator, any nonzero value is `true`.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define constant OPERATOR_SLOT = FREE_STORAGE_POINTER()
+///
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x24 calldataload
+///     0x04 calldataload
+///     ERC6909_SET_OPERATOR([OPERATOR_SLOT])
+///     0x01 0x00 mstore
+///     0x20 0x00 return
+/// }
+/// ```
+#define macro ERC6909_SET_OPERATOR(operator_slot) = takes (2) returns (0) {
+    // takes:               // [spender, approved]
+    swap1                   // [approved, spender]
+    iszero                  // [not_approved, spender]
+    iszero                  // [approved, spender]
+    swap1                   // [spender, approved]
+    dup2                    // [approved, spender, approved]
+    dup2                    // [spender, approved, spender, approved]
+    caller                  // [caller, spender, approved, spender, approved]
+    STORE_NESTED_MAPPING(<operator_slot>) // [spender, approved]
+    caller                  // [caller, spender, approved]
+    ERC6909_EMIT_OPERATOR_SET() // []
+}
+
+/// ## Transfer
+///
+/// Moves an amount of an id from a sender to a receiver and emits `Transfer`, without checking
+/// permissions.
+#define macro __ERC6909_TRANSFER(balance_slot) = takes (4) returns (0) {
+    // takes:               // [sender, receiver, id, amount]
+    dup4                    // [amount, sender, receiver, id, amount]
+    dup4                    // [id, amount, sender, receiver, id, amount]
+    dup3                    // [sender, id, amount, sender, receiver, id, amount]
+    __ERC6909_DEBIT(<balance_slot>) // [sender, receiver, id, amount]
+    dup4                    // [amount, sender, receiver, id, amount]
+    dup4                    // [id, amount, sender, receiver, id, amount]
+    dup4                    // [receiver, id, amount, sender, receiver, id, amount]
+    __ERC6909_CREDIT(<balance_slot>) // [sender, receiver, id, amount]
+    ERC6909_EMIT_TRANSFER() // []
+}
+
+/// ## Debit
+///
+/// Subtracts an amount from the balance of an owner for an id.
+#define macro __ERC6909_DEBIT(balance_slot) = takes (3) returns (0) {
+    // takes:               // [owner, id, amount]
+    NESTED_MAPPING_SLOT(<balance_slot>) // [slot, amount]
+    dup1                    // [slot, slot, amount]
+    sload                   // [balance, slot, amount]
+    dup1                    // [balance, balance, slot, amount]
+    dup4                    // [amount, balance, balance, slot, amount]
+    gt                      // [insufficient, balance, slot, amount]
+    iszero                  // [sufficient, balance, slot, amount]
+    sufficient              // [sufficient_dest, sufficient, balance, slot, amount]
+    jumpi                   // [balance, slot, amount]
+        __ERROR(InsufficientBalance) // [err, balance, slot, amount]
+        0x00                // [ptr, err, balance, slot, amount]
+        mstore              // [balance, slot, amount]
+        0x04                // [err_len, balance, slot, amount]
+        0x00                // [ptr, err_len, balance, slot, amount]
+        revert              // []
+    sufficient:             // [balance, slot, amount]
+        dup3                // [amount, balance, slot, amount]
+        swap1               // [balance, amount, slot, amount]
+        sub                 // [new_balance, slot, amount]
+        swap1               // [slot, new_balance, amount]
+        sstore              // [amount]
+        pop                 // []
+}
+
+/// ## Credit
+///
+/// Adds an amount to the balance of an owner for an id.
+#define macro __ERC6909_CREDIT(balance_slot) = takes (3) returns (0) {
+    // takes:               // [owner, id, amount]
+    NESTED_MAPPING_SLOT(<balance_slot>) // [slot, amount]
+    dup1                    // [slot, slot, amount]
+    sload                   // [balance, slot, amount]
+    dup3                    // [amount, balance, slot, amount]
+    add                     // [new_balance, slot, amount]
+    dup1                    // [new_balance, new_balance, slot, amount]
+    dup4                    // [amount, new_balance, new_balance, slot, amount]
+    gt                      // [overflow, new_balance, slot, amount]
+    iszero                  // [no_overflow, new_balance, slot, amount]
+    no_overflow             // [no_overflow_dest, no_overflow, new_balance, slot, amount]
+    jumpi                   // [new_balance, slot, amount]
+        __ERROR(BalanceOverflow) // [err, new_balance, slot, amount]
+        0x00                // [ptr, err, new_balance, slot, amount]
+        mstore              // [new_balance, slot, amount]
+        0x04                // [err_len, new_balance, slot, amount]
+        0x00                // [ptr, err_len, new_balance, slot, amount]
+        revert              // []
+    no_overflow:            // [new_balance, slot, amount]
+        swap1               // [slot, new_balance, amount]
+        sstore              // [amount]
+        pop                 // []
+}
+"#;
+
+const SUPPLY_DEFINITION: &str = r#"
+/// ## Mint
+///
+/// Mints an amount of an id to a receiver and emits `Transfer` from the zero address.
+///
+/// > WARNING: Memory from `0x00` to `0x40` will be overwritten.
+///
+/// ### Template Arguments
+///
+/// - `balance_slot`: storage slot of the balance mapping.
+///
+/// ### Stack Arguments
+///
+/// - `receiver`: (`stack[0]`) receiver of the tokens.
+/// - `id`: (`stack[1]`) token id.
+/// - `amount`: (`stack[2]`) amount to mint.
+///
+/// ### Panics
+///
+/// - if `amount` overflows the balance of `receiver`
+///
+/// ### Usage
+///
+/// ```huff
+/// #define constant BALANCE_SLOT = FREE_STORAGE_POINTER()
+///
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x44 calldataload
+///     0x24 calldataload
+///     0x04 calldataload
+///     ERC6909_MINT([BALANCE_SLOT])
+///     stop
+/// }
+/// ```
+#define macro ERC6909_MINT(balance_slot) = takes (3) returns (0) {
+    // takes:               // [receiver, id, amount]
+    dup3                    // [amount, receiver, id, amount]
+    dup3                    // [id, amount, receiver, id, amount]
+    dup3                    // [receiver, id, amount, receiver, id, amount]
+    __ERC6909_CREDIT(<balance_slot>) // [receiver, id, amount]
+    0x00                    // [sender, receiver, id, amount]
+    ERC6909_EMIT_TRANSFER() // []
+}
+
+/// ## Burn
+///
+/// Burns an amount of an id from a sender and emits `Transfer` to the zero address.
+///
+/// > WARNING: Memory from `0x00` to `0x40` will be overwritten.
+///
+/// ### Template Arguments
+///
+/// - `balance_slot`: storage slot of the balance mapping.
+///
+/// ### Stack Arguments
+///
+/// - `sender`: (`stack[0]`) owner of the tokens.
+/// - `id`: (`stack[1]`) token id.
+/// - `amount`: (`stack[2]`) amount to burn.
+///
+/// ### Panics
+///
+/// - if `amount` exceeds the balance of `sender`
+///
+/// ### Usage
+///
+/// ```huff
+/// #define constant BALANCE_SLOT = FREE_STORAGE_POINTER()
+///
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x24 calldataload
+///     0x04 calldataload
+///     caller
+///     ERC6909_BURN([BALANCE_SLOT])
+///     stop
+/// }
+/// ```
+#define macro ERC6909_BURN(balance_slot) = takes (3) returns (0) {
+    // takes:               // [sender, id, amount]
+    dup3                    // [amount, sender, id, amount]
+    dup3                    // [id, amount, sender, id, amount]
+    dup3                    // [sender, id, amount, sender, id, amount]
+    __ERC6909_DEBIT(<balance_slot>) // [sender, id, amount]
+    0x00                    // [receiver, sender, id, amount]
+    swap1                   // [sender, receiver, id, amount]
+    ERC6909_EMIT_TRANSFER() // []
+}
+"#;
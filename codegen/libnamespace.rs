@@ -0,0 +1,179 @@
+use std::collections::HashSet;
+
+use crate::config::{identifier, invalid, Table, Value};
+use crate::slots::{erc7201, hex};
+use crate::templates::{self, Context};
+
+struct Namespace {
+    name: String,
+    id: String,
+    location: [u8; 32],
+}
+
+/// Renders `libnamespaces.huff` from the `[[namespace]]` entries of the config, if any.
+pub fn render(config: &Table) -> std::io::Result<Option<String>> {
+    let namespaces = parse_namespaces(config)?;
+
+    if namespaces.is_empty() {
+        return Ok(None);
+    }
+
+    let libnamespaces = format!(
+        "{}{}",
+        HEADER,
+        namespaces
+            .iter()
+            .map(generate_namespace)
+            .collect::<String>(),
+    );
+
+    Ok(Some(libnamespaces))
+}
+
+fn parse_namespaces(config: &Table) -> std::io::Result<Vec<Namespace>> {
+    let entries = match config.get("namespace") {
+        None => return Ok(Vec::new()),
+        Some(Value::Array(entries)) => entries,
+        Some(_) => return Err(invalid("`namespace` must be an array of tables")),
+    };
+
+    let mut names = HashSet::new();
+    let mut ids = HashSet::new();
+
+    entries
+        .iter()
+        .map(|entry| {
+            let entry = entry
+                .as_table()
+                .ok_or_else(|| invalid("`namespace` must be an array of tables"))?;
+            let name = identifier(entry, "name", "namespace")?;
+
+            if !names.insert(name.clone()) {
+                return Err(invalid(format!("duplicate namespace `{}`", name)));
+            }
+
+            let id = entry
+                .get("id")
+                .and_then(Value::as_str)
+                .filter(|id| !id.is_empty())
+                .ok_or_else(|| invalid(format!("namespace `{}` requires an `id`", name)))?
+                .to_string();
+
+            if !ids.insert(id.clone()) {
+                return Err(invalid(format!("duplicate namespace id `{}`", id)));
+            }
+
+            let location = erc7201(&id);
+
+            Ok(Namespace { name, id, location })
+        })
+        .collect()
+}
+
+fn generate_namespace(namespace: &Namespace) -> String {
+    let context = Context::new()
+        .text("name", namespace.name.as_str())
+        .text("id", namespace.id.as_str())
+        .text("location", hex(&namespace.location));
+    templates::render(NAMESPACE_TEMPLATE, &context)
+}
+
+const HEADER: &str = r#"
+//  ------------------------------------------------------------------------------------------------
+//! # Namespaces Library
+//!
+//! Provides ERC-7201 namespaced storage locations for the namespaces declared in `libhuff.toml`.
+//!
+//! Each location is `keccak256(keccak256(id) - 1) & ~0xff`, computed at generation time, so
+//! storage layouts of different modules and upgrades never collide. The low byte is cleared, so
+//! the 256 slots following a location belong to its namespace.
+//!
+//! ## API
+//!
+//! For a given namespace, `NAMESPACE`:
+//!
+//! - `NAMESPACE_STORAGE_LOCATION` - Root slot of the namespace.
+//! - `NAMESPACE_SLOT` - Pushes the slot at an offset from the root.
+//! - `GET_NAMESPACE` - Loads the slot at an offset from the root.
+//! - `SET_NAMESPACE` - Stores the slot at an offset from the root.
+"#;
+
+const NAMESPACE_TEMPLATE: &str = r#"
+/// ## {{name}} Storage Location
+///
+/// ERC-7201 root slot of the `{{id}}` namespace.
+///
+/// @custom:storage-location erc7201:{{id}}
+#define constant {{name}}_STORAGE_LOCATION = {{location}}
+
+/// ## {{name}} Slot
+///
+/// Pushes the slot at an offset from the root of the `{{id}}` namespace.
+///
+/// ### Template Arguments
+///
+/// - `offset`: slot offset from the root, below `0x100`.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     {{name}}_SLOT(0x01)
+///     sload
+/// }
+/// ```
+#define macro {{name}}_SLOT(offset) = takes (0) returns (1) {
+    <offset>                // [offset]
+    [{{name}}_STORAGE_LOCATION] // [location, offset]
+    add                     // [slot]
+}
+
+/// ## Get {{name}}
+///
+/// Loads the slot at an offset from the root of the `{{id}}` namespace.
+///
+/// ### Template Arguments
+///
+/// - `offset`: slot offset from the root, below `0x100`.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     GET_{{name}}(0x00)
+///     0x00 mstore
+///     0x20 0x00 return
+/// }
+/// ```
+#define macro GET_{{name}}(offset) = takes (0) returns (1) {
+    {{name}}_SLOT(<offset>) // [slot]
+    sload                   // [value]
+}
+
+/// ## Set {{name}}
+///
+/// Stores the slot at an offset from the root of the `{{id}}` namespace.
+///
+/// ### Template Arguments
+///
+/// - `offset`: slot offset from the root, below `0x100`.
+///
+/// ### Stack Arguments
+///
+/// - `value`: (`stack[0]`) value to store.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x04 calldataload
+///     SET_{{name}}(0x00)
+///     stop
+/// }
+/// ```
+#define macro SET_{{name}}(offset) = takes (1) returns (0) {
+    // takes:               // [value]
+    {{name}}_SLOT(<offset>) // [slot, value]
+    sstore                  // []
+}
+"#;
@@ -0,0 +1,265 @@
+use crate::templates::{self, Context};
+
+pub fn render() -> String {
+    let context = Context::new().text("max_quotient", max_quotient(10));
+    let decimal = templates::render(DECIMAL_DEFINITION, &context);
+
+    format!("{}{}{}", HEADER, decimal, HEX_DEFINITION)
+}
+
+/// Largest value that can be multiplied by `divisor` without overflowing, as a hex literal.
+fn max_quotient(divisor: u32) -> String {
+    let mut remainder = 0;
+    let quotient = [0xffu32; 32]
+        .iter()
+        .map(|byte| {
+            let dividend = remainder << 8 | byte;
+            remainder = dividend % divisor;
+            format!("{:02x}", dividend / divisor)
+        })
+        .collect::<String>();
+
+    format!("0x{}", quotient.trim_start_matches('0'))
+}
+
+const HEADER: &str = r#"
+//  ------------------------------------------------------------------------------------------------
+//! # Parse Library
+//!
+//! Provides parsing of unsigned integers from ASCII strings in memory, such as the names and
+//! records handled by on-chain resolvers.
+//!
+//! ## API
+//!
+//! - `PARSE_UINT` - Parses a decimal string to a uint.
+//! - `PARSE_HEX_UINT` - Parses a hexadecimal string to a uint.
+
+#include "libcast.huff"
+
+/// ## Invalid Number Error
+///
+/// Thrown when a string is empty or contains a character that is not a digit.
+#define error InvalidNumber()
+"#;
+
+const DECIMAL_DEFINITION: &str = r#"
+/// ## Parse Uint
+///
+/// Parses a string of decimal digits to a uint. Leading zeros are allowed, signs and whitespace
+/// are not.
+///
+/// ### Stack Arguments
+///
+/// - `ptr`: (`stack[0]`) memory pointer of the string.
+/// - `len`: (`stack[1]`) length of the string.
+///
+/// ### Panics
+///
+/// - if the string is empty or contains a character other than `0` to `9`.
+/// - if the value overflows.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x3432 0xf0 shl 0x00 mstore
+///     0x02 0x00
+///     PARSE_UINT()
+///     0x00 mstore
+///     0x20 0x00 return
+/// }
+/// ```
+#define macro PARSE_UINT() = takes (2) returns (1) {
+    // takes:               // [ptr, len]
+    __PARSE_BOUNDS()        // [value, ptr, end]
+    loop:                   // [value, ptr, end]
+        dup2                // [ptr, value, ptr, end]
+        mload               // [word, value, ptr, end]
+        0x00                // [0x00, word, value, ptr, end]
+        byte                // [char, value, ptr, end]
+        0x30                // [0x30, char, value, ptr, end]
+        swap1               // [char, 0x30, value, ptr, end]
+        sub                 // [digit, value, ptr, end]
+        dup1                // [digit, digit, value, ptr, end]
+        0x0a                // [0x0a, digit, digit, value, ptr, end]
+        gt                  // [is_digit, digit, value, ptr, end]
+        is_digit            // [is_digit_dest, is_digit, digit, value, ptr, end]
+        jumpi               // [digit, value, ptr, end]
+            __ERROR(InvalidNumber) // [err, digit, value, ptr, end]
+            0x00            // [ptr, err, digit, value, ptr, end]
+            mstore          // [digit, value, ptr, end]
+            0x04            // [err_len, digit, value, ptr, end]
+            0x00            // [ptr, err_len, digit, value, ptr, end]
+            revert          // []
+        is_digit:           // [digit, value, ptr, end]
+        swap1               // [value, digit, ptr, end]
+        dup1                // [value, value, digit, ptr, end]
+        {{max_quotient}} // [max_quotient, value, value, digit, ptr, end]
+        lt                  // [too_large, value, digit, ptr, end]
+        swap1               // [value, too_large, digit, ptr, end]
+        0x0a                // [0x0a, value, too_large, digit, ptr, end]
+        mul                 // [shifted, too_large, digit, ptr, end]
+        dup3                // [digit, shifted, too_large, digit, ptr, end]
+        add                 // [value, too_large, digit, ptr, end]
+        swap2               // [digit, too_large, value, ptr, end]
+        dup3                // [value, digit, too_large, value, ptr, end]
+        lt                  // [wrapped, too_large, value, ptr, end]
+        or                  // [overflowed, value, ptr, end]
+        iszero              // [in_range, value, ptr, end]
+        in_range            // [in_range_dest, in_range, value, ptr, end]
+        jumpi               // [value, ptr, end]
+            __ERROR(Overflow) // [err, value, ptr, end]
+            0x00            // [ptr, err, value, ptr, end]
+            mstore          // [value, ptr, end]
+            0x04            // [err_len, value, ptr, end]
+            0x00            // [ptr, err_len, value, ptr, end]
+            revert          // []
+        in_range:           // [value, ptr, end]
+        __PARSE_NEXT(loop)  // [value, ptr, end]
+    swap2                   // [end, ptr, value]
+    pop                     // [ptr, value]
+    pop                     // [value]
+}
+"#;
+
+const HEX_DEFINITION: &str = r#"
+/// ## Parse Hex Uint
+///
+/// Parses a string of hexadecimal digits to a uint. Digits may be upper or lower case, and
+/// leading zeros are allowed. A `0x` prefix is not, it is to be skipped by the caller.
+///
+/// ### Stack Arguments
+///
+/// - `ptr`: (`stack[0]`) memory pointer of the string.
+/// - `len`: (`stack[1]`) length of the string.
+///
+/// ### Panics
+///
+/// - if the string is empty or contains a character other than `0` to `9`, `a` to `f` and `A`
+///   to `F`.
+/// - if the value overflows.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x6646 0xf0 shl 0x00 mstore
+///     0x02 0x00
+///     PARSE_HEX_UINT()
+///     0x00 mstore
+///     0x20 0x00 return
+/// }
+/// ```
+#define macro PARSE_HEX_UINT() = takes (2) returns (1) {
+    // takes:               // [ptr, len]
+    __PARSE_BOUNDS()        // [value, ptr, end]
+    loop:                   // [value, ptr, end]
+        dup2                // [ptr, value, ptr, end]
+        mload               // [word, value, ptr, end]
+        0x00                // [0x00, word, value, ptr, end]
+        byte                // [char, value, ptr, end]
+        __PARSE_HEX_DIGIT() // [digit, value, ptr, end]
+        swap1               // [value, digit, ptr, end]
+        dup1                // [value, value, digit, ptr, end]
+        0xfc                // [0xfc, value, value, digit, ptr, end]
+        shr                 // [overflowed, value, digit, ptr, end]
+        iszero              // [in_range, value, digit, ptr, end]
+        in_range            // [in_range_dest, in_range, value, digit, ptr, end]
+        jumpi               // [value, digit, ptr, end]
+            __ERROR(Overflow) // [err, value, digit, ptr, end]
+            0x00            // [ptr, err, value, digit, ptr, end]
+            mstore          // [value, digit, ptr, end]
+            0x04            // [err_len, value, digit, ptr, end]
+            0x00            // [ptr, err_len, value, digit, ptr, end]
+            revert          // []
+        in_range:           // [value, digit, ptr, end]
+        0x04                // [0x04, value, digit, ptr, end]
+        shl                 // [shifted, digit, ptr, end]
+        or                  // [value, ptr, end]
+        __PARSE_NEXT(loop)  // [value, ptr, end]
+    swap2                   // [end, ptr, value]
+    pop                     // [ptr, value]
+    pop                     // [value]
+}
+
+/// ## Parse Hex Digit
+///
+/// Converts a hexadecimal character to its value, reverting if it is not a digit.
+#define macro __PARSE_HEX_DIGIT() = takes (1) returns (1) {
+    // takes:               // [char]
+    0x30                    // [0x30, char]
+    dup2                    // [char, 0x30, char]
+    sub                     // [digit, char]
+    dup1                    // [digit, digit, char]
+    0x0a                    // [0x0a, digit, digit, char]
+    gt                      // [is_decimal, digit, char]
+    decimal                 // [decimal_dest, is_decimal, digit, char]
+    jumpi                   // [digit, char]
+    pop                     // [char]
+    0x20                    // [0x20, char]
+    or                      // [lower_char]
+    0x61                    // [0x61, lower_char]
+    swap1                   // [lower_char, 0x61]
+    sub                     // [letter]
+    dup1                    // [letter, letter]
+    0x06                    // [0x06, letter, letter]
+    gt                      // [is_letter, letter]
+    letter                  // [letter_dest, is_letter, letter]
+    jumpi                   // [letter]
+        __ERROR(InvalidNumber) // [err, letter]
+        0x00                // [ptr, err, letter]
+        mstore              // [letter]
+        0x04                // [err_len, letter]
+        0x00                // [ptr, err_len, letter]
+        revert              // []
+    letter:                 // [letter]
+        0x0a                // [0x0a, letter]
+        add                 // [digit]
+        decoded             // [decoded_dest, digit]
+        jump                // [digit]
+    decimal:                // [digit, char]
+        swap1               // [char, digit]
+        pop                 // [digit]
+    decoded:                // [digit]
+}
+
+/// ## Parse Bounds
+///
+/// Reverts if a string is empty, otherwise computes its end and the initial value.
+#define macro __PARSE_BOUNDS() = takes (2) returns (3) {
+    // takes:               // [ptr, len]
+    dup2                    // [len, ptr, len]
+    iszero                  // [is_empty, ptr, len]
+    iszero                  // [not_empty, ptr, len]
+    not_empty               // [not_empty_dest, not_empty, ptr, len]
+    jumpi                   // [ptr, len]
+        __ERROR(InvalidNumber) // [err, ptr, len]
+        0x00                // [ptr, err, ptr, len]
+        mstore              // [ptr, len]
+        0x04                // [err_len, ptr, len]
+        0x00                // [ptr, err_len, ptr, len]
+        revert              // []
+    not_empty:              // [ptr, len]
+        swap1               // [len, ptr]
+        dup2                // [ptr, len, ptr]
+        add                 // [end, ptr]
+        swap1               // [ptr, end]
+        0x00                // [value, ptr, end]
+}
+
+/// ## Parse Next
+///
+/// Advances to the next character, jumping to `loop` if the string has not ended.
+#define macro __PARSE_NEXT(loop) = takes (3) returns (3) {
+    // takes:               // [value, ptr, end]
+    swap1                   // [ptr, value, end]
+    0x01                    // [0x01, ptr, value, end]
+    add                     // [ptr, value, end]
+    swap1                   // [value, ptr, end]
+    dup3                    // [end, value, ptr, end]
+    dup3                    // [ptr, end, value, ptr, end]
+    lt                      // [is_pending, value, ptr, end]
+    <loop>                  // [loop_dest, is_pending, value, ptr, end]
+    jumpi                   // [value, ptr, end]
+}
+"#;
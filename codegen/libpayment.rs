@@ -0,0 +1,277 @@
+pub fn render() -> String {
+    format!(
+        "{}{}{}{}",
+        HEADER, INTERFACE_DEFINITION, DEPOSIT_DEFINITION, WITHDRAW_DEFINITION,
+    )
+}
+
+const HEADER: &str = r#"
+//  ------------------------------------------------------------------------------------------------
+//! # Payment Library
+//!
+//! Provides pull payment macros for crediting ether deposits and withdrawing them.
+//!
+//! Withdrawals follow checks-effects-interactions ordering, debiting the balance before sending
+//! ether, and run inside the contract-level `REENTRANCY_GUARD`. The guard uses transient storage,
+//! so withdrawals require the Cancun hardfork.
+//!
+//! ## API
+//!
+//! - `DEPOSIT_OF` - Loads the deposited balance of an account.
+//! - `CREDIT_DEPOSIT` - Credits `callvalue` to the caller.
+//! - `WITHDRAW_ALL` - Withdraws the entire balance of the caller.
+//! - `WITHDRAW_AMOUNT` - Withdraws an amount from the balance of the caller.
+
+#include "libmapping.huff"
+#include "libreentrancy.huff"
+"#;
+
+const INTERFACE_DEFINITION: &str = r#"
+/// ## Deposited Event
+///
+/// Emitted when ether is credited to an account.
+#define event Deposited(address indexed, uint256)
+
+/// ## Withdrawn Event
+///
+/// Emitted when ether is withdrawn by an account.
+#define event Withdrawn(address indexed, uint256)
+
+/// ## Insufficient Balance Error
+///
+/// Thrown when a withdrawal exceeds the balance of the caller.
+#define error InsufficientBalance()
+
+/// ## Withdraw Failed Error
+///
+/// Thrown when sending ether to the caller fails.
+#define error WithdrawFailed()
+"#;
+
+const DEPOSIT_DEFINITION: &str = r#"
+/// ## Deposit Of
+///
+/// Loads the deposited balance of an account.
+///
+/// > WARNING: Memory from `0x00` to `0x40` will be overwritten.
+///
+/// ### Template Arguments
+///
+/// - `balances_slot`: storage slot of the balance mapping.
+///
+/// ### Stack Arguments
+///
+/// - `account`: (`stack[0]`) account to load the balance of.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define constant BALANCES_SLOT = FREE_STORAGE_POINTER()
+///
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x04 calldataload
+///     DEPOSIT_OF([BALANCES_SLOT])
+///     0x00 mstore
+///     0x20 0x00 return
+/// }
+/// ```
+#define macro DEPOSIT_OF(balances_slot) = takes (1) returns (1) {
+    // takes:               // [account]
+    LOAD_MAPPING(<balances_slot>) // [balance]
+}
+
+/// ## Credit Deposit
+///
+/// Credits `callvalue` to the balance of the caller and emits `Deposited`.
+///
+/// The balance can not overflow, as it is bounded by the total supply of ether.
+///
+/// > WARNING: Memory from `0x00` to `0x40` will be overwritten.
+///
+/// ### Template Arguments
+///
+/// - `balances_slot`: storage slot of the balance mapping.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define constant BALANCES_SLOT = FREE_STORAGE_POINTER()
+///
+/// #define macro MAIN() = takes (0) returns (0) {
+///     CREDIT_DEPOSIT([BALANCES_SLOT])
+///     stop
+/// }
+/// ```
+#define macro CREDIT_DEPOSIT(balances_slot) = takes (0) returns (0) {
+    caller                  // [caller]
+    __CREDIT(<balances_slot>) // []
+}
+
+/// ## Credit
+///
+/// Credits `callvalue` to the balance of an account and emits `Deposited`.
+#define macro __CREDIT(balances_slot) = takes (1) returns (0) {
+    // takes:               // [account]
+    dup1                    // [account, account]
+    LOAD_MAPPING(<balances_slot>) // [balance, account]
+    callvalue               // [value, balance, account]
+    add                     // [new_balance, account]
+    dup2                    // [account, new_balance, account]
+    STORE_MAPPING(<balances_slot>) // [account]
+    callvalue               // [value, account]
+    0x00                    // [ptr, value, account]
+    mstore                  // [account]
+    __EVENT_HASH(Deposited) // [sig, account]
+    0x20                    // [len, sig, account]
+    0x00                    // [ptr, len, sig, account]
+    log2                    // []
+}
+"#;
+
+const WITHDRAW_DEFINITION: &str = r#"
+/// ## Withdraw All
+///
+/// Withdraws the entire balance of the caller.
+///
+/// > WARNING: Memory from `0x00` to `0x40` will be overwritten.
+///
+/// ### Template Arguments
+///
+/// - `balances_slot`: storage slot of the balance mapping.
+///
+/// ### Panics
+///
+/// - if reentered
+/// - if sending ether to the caller fails
+///
+/// ### Usage
+///
+/// ```huff
+/// #define constant BALANCES_SLOT = FREE_STORAGE_POINTER()
+///
+/// #define macro MAIN() = takes (0) returns (0) {
+///     WITHDRAW_ALL([BALANCES_SLOT])
+///     stop
+/// }
+/// ```
+#define macro WITHDRAW_ALL(balances_slot) = takes (0) returns (0) {
+    REENTRANCY_GUARD(__WITHDRAW_BALANCE(<balances_slot>)) // []
+}
+
+/// ## Withdraw Amount
+///
+/// Withdraws an amount from the balance of the caller.
+///
+/// > WARNING: Memory from `0x00` to `0x40` will be overwritten.
+///
+/// ### Template Arguments
+///
+/// - `balances_slot`: storage slot of the balance mapping.
+///
+/// ### Stack Arguments
+///
+/// - `amount`: (`stack[0]`) amount to withdraw.
+///
+/// ### Panics
+///
+/// - if reentered
+/// - if `amount` exceeds the balance of the caller
+/// - if sending ether to the caller fails
+///
+/// ### Usage
+///
+/// ```huff
+/// #define constant BALANCES_SLOT = FREE_STORAGE_POINTER()
+///
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x04 calldataload
+///     WITHDRAW_AMOUNT([BALANCES_SLOT])
+///     stop
+/// }
+/// ```
+#define macro WITHDRAW_AMOUNT(balances_slot) = takes (1) returns (0) {
+    // takes:               // [amount]
+    0x00                    // [ptr, amount]
+    mstore                  // []
+    REENTRANCY_GUARD(__WITHDRAW_STORED(<balances_slot>)) // []
+}
+
+/// ## Withdraw Balance
+///
+/// Withdraws the entire balance of the caller, without a reentrancy guard.
+#define macro __WITHDRAW_BALANCE(balances_slot) = takes (0) returns (0) {
+    caller                  // [caller]
+    dup1                    // [caller, caller]
+    LOAD_MAPPING(<balances_slot>) // [amount, caller]
+    swap1                   // [caller, amount]
+    __WITHDRAW(<balances_slot>) // []
+}
+
+/// ## Withdraw Stored
+///
+/// Withdraws the amount stored at `0x00` from the balance of the caller, without a reentrancy
+/// guard.
+#define macro __WITHDRAW_STORED(balances_slot) = takes (0) returns (0) {
+    0x00                    // [ptr]
+    mload                   // [amount]
+    caller                  // [caller, amount]
+    __WITHDRAW(<balances_slot>) // []
+}
+
+/// ## Withdraw
+///
+/// Debits an amount from the balance of an account, emits `Withdrawn`, then sends the amount to
+/// the account, without a reentrancy guard.
+#define macro __WITHDRAW(balances_slot) = takes (2) returns (0) {
+    // takes:               // [account, amount]
+    dup1                    // [account, account, amount]
+    LOAD_MAPPING(<balances_slot>) // [balance, account, amount]
+    dup3                    // [amount, balance, account, amount]
+    dup2                    // [balance, amount, balance, account, amount]
+    lt                      // [insufficient, balance, account, amount]
+    iszero                  // [sufficient, balance, account, amount]
+    sufficient              // [sufficient_dest, sufficient, balance, account, amount]
+    jumpi                   // [balance, account, amount]
+        __ERROR(InsufficientBalance) // [err, balance, account, amount]
+        0x00                // [ptr, err, balance, account, amount]
+        mstore              // [balance, account, amount]
+        0x04                // [err_len, balance, account, amount]
+        0x00                // [ptr, err_len, balance, account, amount]
+        revert              // []
+    sufficient:             // [balance, account, amount]
+
+    dup3                    // [amount, balance, account, amount]
+    swap1                   // [balance, amount, account, amount]
+    sub                     // [new_balance, account, amount]
+    dup2                    // [account, new_balance, account, amount]
+    STORE_MAPPING(<balances_slot>) // [account, amount]
+    dup2                    // [amount, account, amount]
+    0x00                    // [ptr, amount, account, amount]
+    mstore                  // [account, amount]
+    dup1                    // [account, account, amount]
+    __EVENT_HASH(Withdrawn) // [sig, account, account, amount]
+    0x20                    // [len, sig, account, account, amount]
+    0x00                    // [ptr, len, sig, account, account, amount]
+    log2                    // [account, amount]
+
+    0x00                    // [ret_len, account, amount]
+    0x00                    // [ret_ptr, ret_len, account, amount]
+    0x00                    // [arg_len, ret_ptr, ret_len, account, amount]
+    0x00                    // [arg_ptr, arg_len, ret_ptr, ret_len, account, amount]
+    dup6                    // [amount, arg_ptr, arg_len, ret_ptr, ret_len, account, amount]
+    dup6                    // [account, amount, arg_ptr, arg_len, ret_ptr, ret_len, account, amount]
+    gas                     // [gas, account, amount, arg_ptr, arg_len, ret_ptr, ret_len, account, amount]
+    call                    // [success, account, amount]
+    sent                    // [sent_dest, success, account, amount]
+    jumpi                   // [account, amount]
+        __ERROR(WithdrawFailed) // [err, account, amount]
+        0x00                // [ptr, err, account, amount]
+        mstore              // [account, amount]
+        0x04                // [err_len, account, amount]
+        0x00                // [ptr, err_len, account, amount]
+        revert              // []
+    sent:                   // [account, amount]
+    pop                     // [amount]
+    pop                     // []
+}
+"#;
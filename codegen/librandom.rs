@@ -0,0 +1,172 @@
+pub fn render() -> String {
+    format!("{}{}{}", HEADER, SOURCE_DEFINITION, RANGE_DEFINITION)
+}
+
+const HEADER: &str = r#"
+//  ------------------------------------------------------------------------------------------------
+//! # Randomness Library
+//!
+//! Provides macros for pseudo-random number generation.
+//!
+//! > WARNING: Values produced here are NOT secure randomness. `prevrandao` may be biased by the
+//! > block proposer and `timestamp` may be chosen within protocol bounds, so anything these values
+//! > decide must be worth less than the cost of manipulating a block. Use a commit-reveal scheme or
+//! > an oracle when the outcome is valuable.
+//!
+//! ## API
+//!
+//! - `PREVRANDAO` - Pushes the beacon chain randomness of the current block.
+//! - `PSEUDO_RANDOM` - Derives a pseudo-random word and advances a stored seed.
+//! - `RANDOM_BELOW` - Reduces a random word to the range `[0, n)`.
+//! - `RANDOM_BETWEEN` - Reduces a random word to the range `[min, max]`.
+"#;
+
+const SOURCE_DEFINITION: &str = r#"
+/// ## Prevrandao
+///
+/// Pushes the beacon chain randomness of the current block.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     PREVRANDAO()
+///     0x00 mstore
+///     0x20 0x00 return
+/// }
+/// ```
+#define macro PREVRANDAO() = takes (0) returns (1) {
+    prevrandao              // [randao]
+}
+
+/// ## Pseudo Random
+///
+/// Derives a pseudo-random word as `keccak256(seed, prevrandao, timestamp, caller)` and stores it
+/// as the next seed, so successive calls within the same block produce distinct values.
+///
+/// > WARNING: Memory from `0x00` to `0x80` will be overwritten.
+///
+/// ### Template Arguments
+///
+/// - `seed_slot`: storage slot of the seed.
+///
+/// ### Usage
+///
+/// ```huff
+/// #include "librandom.huff"
+///
+/// #define constant SEED_SLOT = FREE_STORAGE_POINTER()
+///
+/// #define macro MAIN() = takes (0) returns (0) {
+///     PSEUDO_RANDOM([SEED_SLOT])
+///     0x00 mstore
+///     0x20 0x00 return
+/// }
+/// ```
+#define macro PSEUDO_RANDOM(seed_slot) = takes (0) returns (1) {
+    <seed_slot>             // [seed_slot]
+    sload                   // [seed]
+    0x00                    // [seed_ptr, seed]
+    mstore                  // []
+    prevrandao              // [randao]
+    0x20                    // [randao_ptr, randao]
+    mstore                  // []
+    timestamp               // [timestamp]
+    0x40                    // [timestamp_ptr, timestamp]
+    mstore                  // []
+    caller                  // [caller]
+    0x60                    // [caller_ptr, caller]
+    mstore                  // []
+    0x80                    // [len]
+    0x00                    // [ptr, len]
+    sha3                    // [random]
+    dup1                    // [random, random]
+    <seed_slot>             // [seed_slot, random, random]
+    sstore                  // [random]
+}
+"#;
+
+const RANGE_DEFINITION: &str = r#"
+/// ## Random Below
+///
+/// Reduces a random word to the range `[0, n)` using `random % n`.
+///
+/// The reduction has a modulo bias: when `2 ** 256` is not a multiple of `n`, the lowest
+/// `2 ** 256 % n` outcomes are each one occurrence more likely than the rest. The relative bias is
+/// at most `n / 2 ** 256`, which is negligible for any `n` much smaller than `2 ** 128`, but it is
+/// not zero. If `n` is zero, the result is zero.
+///
+/// ### Template Arguments
+///
+/// - `n`: exclusive upper bound.
+///
+/// ### Stack Arguments
+///
+/// - `random`: (`stack[0]`) random word.
+///
+/// ### Usage
+///
+/// ```huff
+/// #include "librandom.huff"
+///
+/// #define macro MAIN() = takes (0) returns (0) {
+///     PSEUDO_RANDOM([SEED_SLOT])
+///     RANDOM_BELOW(0x64)
+///     0x00 mstore
+///     0x20 0x00 return
+/// }
+/// ```
+#define macro RANDOM_BELOW(n) = takes (1) returns (1) {
+    // takes:               // [random]
+    <n>                     // [n, random]
+    swap1                   // [random, n]
+    mod                     // [reduced]
+}
+
+/// ## Random Between
+///
+/// Reduces a random word to the inclusive range `[min, max]` using `min + random % (max - min + 1)`.
+///
+/// The same modulo bias as `RANDOM_BELOW` applies with `n = max - min + 1`. The span of the range
+/// `[0, 2 ** 256 - 1]` wraps to zero, so that range returns `random` unreduced. If `min` is
+/// greater than `max`, the result is unspecified.
+///
+/// ### Template Arguments
+///
+/// - `min`: inclusive lower bound.
+/// - `max`: inclusive upper bound.
+///
+/// ### Stack Arguments
+///
+/// - `random`: (`stack[0]`) random word.
+///
+/// ### Usage
+///
+/// ```huff
+/// #include "librandom.huff"
+///
+/// #define macro MAIN() = takes (0) returns (0) {
+///     PSEUDO_RANDOM([SEED_SLOT])
+///     RANDOM_BETWEEN(0x01, 0x06)
+///     0x00 mstore
+///     0x20 0x00 return
+/// }
+/// ```
+#define macro RANDOM_BETWEEN(min, max) = takes (1) returns (1) {
+    // takes:               // [random]
+    <min>                   // [min, random]
+    <max>                   // [max, min, random]
+    sub                     // [span_minus_one, random]
+    0x01                    // [one, span_minus_one, random]
+    add                     // [span, random]
+    dup1                    // [span, span, random]
+    iszero                  // [spans_every_word, span, random]
+    dup3                    // [random, spans_every_word, span, random]
+    mul                     // [unreduced, span, random]
+    swap2                   // [random, span, unreduced]
+    mod                     // [offset, unreduced]
+    add                     // [offset]
+    <min>                   // [min, offset]
+    add                     // [reduced]
+}
+"#;
@@ -0,0 +1,1282 @@
+//! The generated libraries, for selecting and rendering them by name.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::config::{self, EvmVersion, Table, Value};
+use crate::diagnostics::Diagnostic;
+use crate::diff;
+use crate::huff;
+use crate::keccak::keccak256;
+use crate::layout::{self, Layout};
+use crate::libcast::{OverflowRevert, INT_SIZES};
+use crate::naming::{self, Naming};
+use crate::optimize::{mask_encoding, Objective};
+use crate::profile::Profile;
+use crate::validate;
+use crate::yul::Target;
+use crate::{
+    libabi, libaccount, libauth, libbits, libblob, libbloom, libbytes, libbytescast, libcall,
+    libcalldata, libcast, libchain, libchecksum, libcmp, libcollections, libconstants, libcounter,
+    libcreate, libcursor, libdecay, libdecimal, libdecode, libdiamond, libdispatch, libescrow,
+    libfixedpoint, libflags, libforwarder, libguard, libkeccak, libmapping, libmerkle,
+    libmultitoken, libnamespace, libparse, libpayment, librandom, libratelimit, libreturn,
+    librevert, librlp, libroyalty, libsafemath, libsafetransfer, libsignature, libsigned, libslots,
+    libstruct, libtime, libtimelock, libtry, libtype, libunits, libwrapping,
+};
+
+/// The version of the generator, and of the libraries it generates.
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// A generated library.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Library {
+    Abi,
+    Account,
+    Auth,
+    Bits,
+    Blob,
+    Bloom,
+    Bytes,
+    BytesCast,
+    Call,
+    Calldata,
+    Cast,
+    Chain,
+    Checksum,
+    Cmp,
+    Collections,
+    Constants,
+    Counter,
+    Create,
+    Cursor,
+    Decay,
+    Decimal,
+    Decoders,
+    Diamond,
+    Dispatchers,
+    Escrow,
+    FixedPoint,
+    Flags,
+    Forwarder,
+    Guard,
+    Keccak,
+    Mapping,
+    Merkle,
+    MultiToken,
+    Namespaces,
+    Parse,
+    Payment,
+    Random,
+    RateLimit,
+    Return,
+    Revert,
+    Rlp,
+    Royalty,
+    SafeMath,
+    SafeTransfer,
+    Signature,
+    Signed,
+    Slots,
+    Structs,
+    Time,
+    Timelock,
+    Try,
+    Types,
+    Units,
+    Wrapping,
+}
+
+/// Inputs of the generators.
+#[derive(Clone, Debug, Default)]
+pub struct Options {
+    /// The generation config.
+    pub config: Table,
+    /// Bit sizes of the sized libraries, every size of `libcast.huff` if empty.
+    pub sizes: Vec<u16>,
+    /// How the checked casts of `libcast.huff` and `libsigned.huff` revert on overflow.
+    pub overflow_revert: OverflowRevert,
+    /// Prefixed to the name of every generated macro, to keep them apart from those of a project.
+    pub prefix: String,
+}
+
+impl Options {
+    /// Bit sizes of the sized libraries.
+    pub fn sizes(&self) -> &[u16] {
+        if self.sizes.is_empty() {
+            &INT_SIZES
+        } else {
+            &self.sizes
+        }
+    }
+}
+
+impl Library {
+    /// Every library, in alphabetical order.
+    pub const ALL: [Library; 54] = [
+        Library::Abi,
+        Library::Account,
+        Library::Auth,
+        Library::Bits,
+        Library::Blob,
+        Library::Bloom,
+        Library::Bytes,
+        Library::BytesCast,
+        Library::Call,
+        Library::Calldata,
+        Library::Cast,
+        Library::Chain,
+        Library::Checksum,
+        Library::Cmp,
+        Library::Collections,
+        Library::Constants,
+        Library::Counter,
+        Library::Create,
+        Library::Cursor,
+        Library::Decay,
+        Library::Decimal,
+        Library::Decoders,
+        Library::Diamond,
+        Library::Dispatchers,
+        Library::Escrow,
+        Library::FixedPoint,
+        Library::Flags,
+        Library::Forwarder,
+        Library::Guard,
+        Library::Keccak,
+        Library::Mapping,
+        Library::Merkle,
+        Library::MultiToken,
+        Library::Namespaces,
+        Library::Parse,
+        Library::Payment,
+        Library::Random,
+        Library::RateLimit,
+        Library::Return,
+        Library::Revert,
+        Library::Rlp,
+        Library::Royalty,
+        Library::SafeMath,
+        Library::SafeTransfer,
+        Library::Signature,
+        Library::Signed,
+        Library::Slots,
+        Library::Structs,
+        Library::Time,
+        Library::Timelock,
+        Library::Try,
+        Library::Types,
+        Library::Units,
+        Library::Wrapping,
+    ];
+
+    /// Name of the library, its file name without the `lib` prefix and `.huff` extension.
+    pub fn name(self) -> &'static str {
+        match self {
+            Library::Abi => "abi",
+            Library::Account => "account",
+            Library::Auth => "auth",
+            Library::Bits => "bits",
+            Library::Blob => "blob",
+            Library::Bloom => "bloom",
+            Library::Bytes => "bytes",
+            Library::BytesCast => "bytescast",
+            Library::Call => "call",
+            Library::Calldata => "calldata",
+            Library::Cast => "cast",
+            Library::Chain => "chain",
+            Library::Checksum => "checksum",
+            Library::Cmp => "cmp",
+            Library::Collections => "collections",
+            Library::Constants => "constants",
+            Library::Counter => "counter",
+            Library::Create => "create",
+            Library::Cursor => "cursor",
+            Library::Decay => "decay",
+            Library::Decimal => "decimal",
+            Library::Decoders => "decoders",
+            Library::Diamond => "diamond",
+            Library::Dispatchers => "dispatchers",
+            Library::Escrow => "escrow",
+            Library::FixedPoint => "fixedpoint",
+            Library::Flags => "flags",
+            Library::Forwarder => "forwarder",
+            Library::Guard => "guard",
+            Library::Keccak => "keccak",
+            Library::Mapping => "mapping",
+            Library::Merkle => "merkle",
+            Library::MultiToken => "multitoken",
+            Library::Namespaces => "namespaces",
+            Library::Parse => "parse",
+            Library::Payment => "payment",
+            Library::Random => "random",
+            Library::RateLimit => "ratelimit",
+            Library::Return => "return",
+            Library::Revert => "revert",
+            Library::Rlp => "rlp",
+            Library::Royalty => "royalty",
+            Library::SafeMath => "safemath",
+            Library::SafeTransfer => "safetransfer",
+            Library::Signature => "signature",
+            Library::Signed => "signed",
+            Library::Slots => "slots",
+            Library::Structs => "structs",
+            Library::Time => "time",
+            Library::Timelock => "timelock",
+            Library::Try => "try",
+            Library::Types => "types",
+            Library::Units => "units",
+            Library::Wrapping => "wrapping",
+        }
+    }
+
+    /// Parses the name of a library, with or without the `lib` prefix.
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.strip_prefix("lib").unwrap_or(name);
+
+        Library::ALL
+            .into_iter()
+            .find(|library| library.name() == name)
+    }
+
+    /// File name of the library.
+    pub fn file_name(self) -> String {
+        format!("lib{}.huff", self.name())
+    }
+
+    /// Whether the library defines its items for each of the bit sizes of the options.
+    pub fn is_sized(self) -> bool {
+        matches!(
+            self,
+            Library::BytesCast
+                | Library::Calldata
+                | Library::Cast
+                | Library::Cmp
+                | Library::Constants
+                | Library::SafeMath
+                | Library::Signed
+                | Library::Wrapping
+        )
+    }
+
+    /// The file name of the library of the Yul target, `libcast.yul`.
+    pub fn yul_file_name(self) -> String {
+        format!("lib{}.yul", self.name())
+    }
+
+    /// Renders the Yul functions of the library, or `None` if it has none.
+    pub fn render_yul(self, options: &Options) -> Option<String> {
+        match self {
+            Library::Cast => Some(libcast::render_yul(
+                options.sizes(),
+                options.overflow_revert,
+            )),
+            _ => None,
+        }
+    }
+
+    /// Renders the library, or `None` if the config declares nothing for it to generate.
+    pub fn render(self, options: &Options) -> io::Result<Option<String>> {
+        let config = &options.config;
+        let sizes = options.sizes();
+
+        let library = match self {
+            Library::Abi => libabi::render(),
+            Library::Account => libaccount::render(),
+            Library::Auth => libauth::render(),
+            Library::Bits => libbits::render(),
+            Library::Blob => return libblob::render(config),
+            Library::Bloom => libbloom::render(config)?,
+            Library::Bytes => libbytes::render(Profile::load(config)?.allows(0x5e)),
+            Library::BytesCast => libbytescast::render(sizes),
+            Library::Call => libcall::render(sizes),
+            Library::Calldata => libcalldata::render(sizes),
+            Library::Cast => libcast::render(
+                sizes,
+                options.overflow_revert,
+                Profile::load(config)?.push0(),
+            ),
+            Library::Chain => libchain::render(),
+            Library::Checksum => libchecksum::render(),
+            Library::Cmp => libcmp::render(sizes),
+            Library::Collections => libcollections::render(),
+            Library::Constants => libconstants::render(config, sizes)?,
+            Library::Counter => libcounter::render(config)?,
+            Library::Create => libcreate::render(),
+            Library::Cursor => libcursor::render(),
+            Library::Decay => libdecay::render(),
+            Library::Decimal => libdecimal::render(),
+            Library::Decoders => return libdecode::render(config),
+            Library::Diamond => return libdiamond::render(config),
+            Library::Dispatchers => return libdispatch::render(config),
+            Library::Escrow => libescrow::render(),
+            Library::FixedPoint => libfixedpoint::render(),
+            Library::Flags => libflags::render(config)?,
+            Library::Forwarder => libforwarder::render(),
+            Library::Guard => {
+                let profile = Profile::load(config)?;
+                libguard::render(profile.allows(0x5c) && profile.allows(0x5d))
+            }
+            Library::Keccak => libkeccak::render(),
+            Library::Mapping => libmapping::render(),
+            Library::Merkle => libmerkle::render(),
+            Library::MultiToken => libmultitoken::render(),
+            Library::Namespaces => return libnamespace::render(config),
+            Library::Parse => libparse::render(),
+            Library::Payment => libpayment::render(),
+            Library::Random => librandom::render(),
+            Library::RateLimit => libratelimit::render(config)?,
+            Library::Return => libreturn::render(),
+            Library::Revert => librevert::render(),
+            Library::Rlp => librlp::render(),
+            Library::Royalty => libroyalty::render(),
+            Library::SafeMath => libsafemath::render(sizes),
+            Library::SafeTransfer => libsafetransfer::render(),
+            Library::Signature => libsignature::render(),
+            Library::Signed => libsigned::render(sizes, options.overflow_revert),
+            Library::Slots => libslots::render(),
+            Library::Structs => return libstruct::render(config),
+            Library::Time => libtime::render(),
+            Library::Timelock => libtimelock::render(config)?,
+            Library::Try => libtry::render(),
+            Library::Types => return libtype::render(config),
+            Library::Units => libunits::render(),
+            Library::Wrapping => libwrapping::render(sizes),
+        };
+
+        Ok(Some(library))
+    }
+}
+
+/// Generates a selection of the libraries, every library unless any are selected.
+///
+/// ```no_run
+/// // build.rs
+/// huffgen::Generator::new()
+///     .with_library(huffgen::Library::SafeMath)
+///     .with_sizes(&[64, 128])
+///     .write_to("huff")
+///     .unwrap();
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Generator {
+    libraries: Vec<Library>,
+    options: Options,
+    layout: Layout,
+    target: Target,
+    naming: Option<Arc<dyn Naming>>,
+    timestamp: Option<u64>,
+}
+
+impl Generator {
+    /// A generator of every library with an empty config and every size.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A generator configured by `config`, as parsed from `libhuff.toml`, whose `[generate]`
+    /// table selects the `libraries`, their `sizes`, how checked casts `revert`, the `layout`, the
+    /// `target` language, the macro `prefix` and the `naming` convention, each optional.
+    pub fn from_config(config: Table) -> io::Result<Self> {
+        let mut generator = Generator::new();
+
+        if let Some(libraries) = config::generate_value(&config, "libraries")? {
+            let libraries = libraries
+                .as_array()
+                .and_then(|names| {
+                    names
+                        .iter()
+                        .map(Value::as_str)
+                        .collect::<Option<Vec<&str>>>()
+                })
+                .ok_or_else(|| config::invalid("`generate.libraries` must be an array of names"))?;
+            for name in libraries {
+                let library = Library::from_name(name)
+                    .ok_or_else(|| config::invalid(format!("unknown library `{}`", name)))?;
+                generator = generator.with_library(library);
+            }
+        }
+        if let Some(sizes) = config::generate_value(&config, "sizes")? {
+            let sizes = sizes
+                .as_array()
+                .and_then(|sizes| {
+                    sizes
+                        .iter()
+                        .map(Value::as_integer)
+                        .collect::<Option<Vec<_>>>()
+                })
+                .ok_or_else(|| config::invalid("`generate.sizes` must be an array of integers"))?;
+            // sizes that are not supported are reported by the diagnostics, at the config
+            let sizes = sizes
+                .iter()
+                .map(|size| u16::try_from(*size).map_err(|_| config::invalid(invalid_size(size))))
+                .collect::<io::Result<Vec<u16>>>()?;
+            generator = generator.with_sizes(&sizes);
+        }
+        if let Some(name) = config::generate_string(&config, "revert")? {
+            let revert = OverflowRevert::from_name(name).ok_or_else(|| {
+                config::invalid("`generate.revert` must be one of \"error\", \"panic\"")
+            })?;
+            generator = generator.with_overflow_revert(revert);
+        }
+        if let Some(name) = config::generate_string(&config, "layout")? {
+            let layout = Layout::from_name(name).ok_or_else(|| {
+                let expected = "\"library\", \"type\", \"bundle\"";
+                config::invalid(format!("`generate.layout` must be one of {}", expected))
+            })?;
+            generator = generator.with_layout(layout);
+        }
+        if let Some(name) = config::generate_string(&config, "target")? {
+            let target = Target::from_name(name).ok_or_else(|| {
+                config::invalid("`generate.target` must be one of \"huff\", \"yul\"")
+            })?;
+            generator = generator.with_target(target);
+        }
+        if let Some(prefix) = config::generate_string(&config, "prefix")? {
+            generator = generator.with_prefix(prefix)?;
+        }
+        if let Some(naming) = config::generate_value(&config, "naming")? {
+            generator.naming = Some(naming::from_config(naming)?);
+        }
+
+        Ok(generator.with_config(config))
+    }
+
+    /// Selects exactly `libraries`, every library if it is empty.
+    pub fn with_libraries(mut self, libraries: &[Library]) -> Self {
+        self.libraries.clear();
+        for library in libraries {
+            self = self.with_library(*library);
+        }
+        self
+    }
+
+    /// Selects a library, in addition to any already selected.
+    pub fn with_library(mut self, library: Library) -> Self {
+        if !self.libraries.contains(&library) {
+            self.libraries.push(library);
+            self.libraries.sort();
+        }
+        self
+    }
+
+    /// Sets the bit sizes of the sized libraries.
+    pub fn with_sizes(mut self, sizes: &[u16]) -> Self {
+        self.options.sizes = sizes.to_vec();
+        self.options.sizes.sort_unstable();
+        self.options.sizes.dedup();
+        self
+    }
+
+    /// Sets how checked casts revert on overflow, the `Overflow()` error by default.
+    pub fn with_overflow_revert(mut self, revert: OverflowRevert) -> Self {
+        self.options.overflow_revert = revert;
+        self
+    }
+
+    /// Sets the prefix of the names of the generated macros, none by default, failing if it would
+    /// not make valid identifiers.
+    pub fn with_prefix(mut self, prefix: &str) -> io::Result<Self> {
+        let valid = prefix
+            .chars()
+            .next()
+            .is_none_or(|c| c.is_ascii_alphabetic() || c == '_')
+            && prefix
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !valid {
+            return Err(config::invalid(format!(
+                "`{}` is not a valid macro prefix",
+                prefix
+            )));
+        }
+
+        self.options.prefix = prefix.to_string();
+        Ok(self)
+    }
+
+    /// Sets how the libraries are laid out in files, a file per library by default.
+    pub fn with_layout(mut self, layout: Layout) -> Self {
+        self.layout = layout;
+        self
+    }
+
+    /// Sets the language the libraries are generated in, Huff by default. The Yul target generates
+    /// the functions of the libraries that have them, in a file per library.
+    pub fn with_target(mut self, target: Target) -> Self {
+        self.target = target;
+        self
+    }
+
+    /// Sets the naming convention of the macros, errors and files, the names the libraries are
+    /// generated with by default.
+    pub fn with_naming(mut self, naming: impl Naming + 'static) -> Self {
+        self.naming = Some(Arc::new(naming));
+        self
+    }
+
+    /// Stamps the headers of the generated files with the time of generation, in seconds since the
+    /// Unix epoch. Without a timestamp, the output is the same for the same inputs, byte for byte.
+    pub fn with_timestamp(mut self, timestamp: u64) -> Self {
+        self.timestamp = Some(timestamp);
+        self
+    }
+
+    /// Sets the generation config, as parsed from `libhuff.toml`.
+    pub fn with_config(mut self, config: Table) -> Self {
+        self.options.config = config;
+        self
+    }
+
+    /// Sets the targeted hardfork, overriding the `evm_version` of the config, which `with_config`
+    /// replaces.
+    pub fn with_evm_version(mut self, version: EvmVersion) -> Self {
+        let version = Value::String(version.name().to_string());
+        self.options
+            .config
+            .insert("evm_version".to_string(), version);
+        self
+    }
+
+    /// The selected libraries, in alphabetical order.
+    pub fn libraries(&self) -> &[Library] {
+        if self.libraries.is_empty() {
+            &Library::ALL
+        } else {
+            &self.libraries
+        }
+    }
+
+    /// The language the libraries are generated in.
+    pub fn target(&self) -> Target {
+        self.target
+    }
+
+    /// The generator inputs.
+    pub fn options(&self) -> &Options {
+        &self.options
+    }
+
+    /// The warnings and errors of generating the selected libraries, each attributed to the config
+    /// key it comes from. Rendering fails if there are any errors.
+    pub fn diagnostics(&self) -> Vec<Diagnostic> {
+        let (sizes, libraries) = (&self.options.sizes, self.libraries());
+        let mut diagnostics = Vec::new();
+        for size in sizes.iter().filter(|size| !INT_SIZES.contains(size)) {
+            diagnostics.push(Diagnostic::error(invalid_size(size)));
+        }
+
+        // the default sizes are every size, rather than a request for any one of them
+        let casts = [Library::Cast, Library::Signed];
+        let casts = libraries.iter().any(|library| casts.contains(library));
+        if casts && sizes.contains(&256) {
+            let message = "the checked casts of size 256 never revert, as every word fits";
+            diagnostics.push(Diagnostic::warning(message));
+        }
+        let profile = Profile::load(&self.options.config);
+        if let (true, Ok(profile)) = (libraries.contains(&Library::Cast), profile) {
+            let bytes = |size, objective| mask_encoding(size, objective, profile.push0()).bytes;
+            for size in sizes
+                .iter()
+                .filter(|size| **size >= 32 && INT_SIZES.contains(size))
+            {
+                if bytes(*size, Objective::Size) >= bytes(*size, Objective::Gas) {
+                    diagnostics.push(Diagnostic::warning(format!(
+                        "`MINI_TO_U{0}` is no smaller than `TO_U{0}` for the targeted EVM version",
+                        size,
+                    )));
+                }
+            }
+        }
+
+        // each comes from the sizes
+        diagnostics
+            .into_iter()
+            .map(|diagnostic| diagnostic.with_key("generate.sizes"))
+            .collect()
+    }
+
+    /// Renders the selected libraries by file name, skipping those the config declares nothing for,
+    /// laid out in files by the layout. File names of the `type` layout are relative paths. With
+    /// the Yul target, the files are those of the Yul functions of the selected libraries instead.
+    ///
+    /// Each file starts with a header recording its provenance: the version of the generator and of
+    /// the library, the hash of the config it is generated with, and the timestamp, if any.
+    pub fn render(&self) -> io::Result<BTreeMap<String, String>> {
+        self.render_timed().map(|(files, _)| files)
+    }
+
+    /// Renders the selected libraries as `render` does, with the time each library took to
+    /// render, by the file name of the library, for the files to be checked before
+    /// `write_rendered` writes them.
+    pub fn render_timed(
+        &self,
+    ) -> io::Result<(BTreeMap<String, String>, BTreeMap<String, Duration>)> {
+        if let Some(size) = self
+            .options
+            .sizes
+            .iter()
+            .find(|size| !INT_SIZES.contains(size))
+        {
+            return Err(config::invalid(invalid_size(size)));
+        }
+
+        if self.target == Target::Yul {
+            let mut files = self.render_yul()?;
+            self.stamp(&mut files);
+            return Ok((files, BTreeMap::new()));
+        }
+
+        // every library renaming, as the selected libraries may include and invoke the others
+        let renaming = !self.options.prefix.is_empty() || self.naming.is_some();
+        let mut selected = self.libraries().to_vec();
+        let mut rendered = BTreeMap::new();
+        let mut pending = if renaming {
+            Library::ALL.to_vec()
+        } else {
+            selected.clone()
+        };
+        // the libraries the selected ones include are generated with them
+        while !pending.is_empty() {
+            for (library, contents, elapsed) in render_libraries(&pending, &self.options) {
+                rendered.insert(library, (contents, elapsed));
+            }
+            let mut i = 0;
+            while i < selected.len() {
+                if let Some((Ok(Some(contents)), _)) = rendered.get(&selected[i]) {
+                    let included = includes(contents)
+                        .filter_map(|include| {
+                            Library::ALL
+                                .into_iter()
+                                .find(|library| library.file_name() == include)
+                        })
+                        .collect::<Vec<Library>>();
+                    for library in included {
+                        if !selected.contains(&library) {
+                            selected.push(library);
+                        }
+                    }
+                }
+                i += 1;
+            }
+            pending = selected
+                .iter()
+                .filter(|library| !rendered.contains_key(library))
+                .copied()
+                .collect();
+        }
+
+        let (mut files, mut timings, mut all) = (BTreeMap::new(), BTreeMap::new(), Vec::new());
+        for (library, (contents, elapsed)) in rendered {
+            let selected = selected.contains(&library);
+            match contents {
+                Ok(Some(contents)) => {
+                    if selected {
+                        files.insert(library.file_name(), contents.clone());
+                        timings.insert(library.file_name(), elapsed);
+                    }
+                    all.push((library.file_name(), contents));
+                }
+                Err(e) if selected => return Err(e),
+                _ => {}
+            }
+        }
+
+        let mut moves = BTreeMap::new();
+        if renaming {
+            let renames = self.renames(&all)?;
+            for contents in files.values_mut() {
+                *contents = renamed(contents, &renames);
+                if let Some(name) = macros(contents).find(|name| huff::opcode(name).is_some()) {
+                    let naming = self
+                        .naming
+                        .as_ref()
+                        .map_or("libhuff", |naming| naming.name());
+                    return Err(config::invalid(format!(
+                        "naming `{}` names a macro after the opcode `{}`",
+                        naming, name,
+                    )));
+                }
+            }
+            if let Some(naming) = &self.naming {
+                for (name, contents) in &all {
+                    if let Some(path) = naming.file_name(name, title(contents, name)) {
+                        moves.insert(name.clone(), path);
+                    }
+                }
+            }
+        }
+
+        let handwritten = HANDWRITTEN
+            .into_iter()
+            .filter(|(name, _)| {
+                files
+                    .values()
+                    .any(|contents| includes(contents).any(|i| i == *name))
+            })
+            .collect::<Vec<_>>();
+
+        match self.layout {
+            Layout::Library => {}
+            Layout::Type => {
+                let sized = selected.iter().filter(|library| library.is_sized());
+                let sized = sized
+                    .map(|library| library.file_name())
+                    .collect::<Vec<String>>();
+                files = layout::by_type(files, &sized);
+            }
+            Layout::Bundle => {
+                let bundle = layout::bundle(files.keys());
+                files.insert(layout::BUNDLE.to_string(), bundle);
+            }
+        }
+
+        if !moves.is_empty() {
+            files = moved(files, &moves);
+        }
+
+        self.stamp(&mut files);
+        for (name, contents) in handwritten {
+            files.insert(name.to_string(), contents.to_string());
+        }
+        validate::validate(&files, &self.delegates()?)?;
+
+        Ok((files, timings))
+    }
+
+    /// Renders the Yul functions of the selected libraries by file name, failing if a library
+    /// selected explicitly has none, or for the options of Huff macros.
+    fn render_yul(&self) -> io::Result<BTreeMap<String, String>> {
+        if !self.options.prefix.is_empty() || self.naming.is_some() {
+            return Err(config::invalid("the yul target does not rename functions"));
+        }
+        if self.layout != Layout::Library {
+            let layout = self.layout.name();
+            return Err(config::invalid(format!(
+                "the yul target has no `{}` layout",
+                layout
+            )));
+        }
+
+        let mut files = BTreeMap::new();
+        for library in self.libraries() {
+            match library.render_yul(&self.options) {
+                Some(contents) => {
+                    files.insert(library.yul_file_name(), contents);
+                }
+                None if !self.libraries.is_empty() => {
+                    let name = library.file_name();
+                    return Err(config::invalid(format!("`{}` has no yul target", name)));
+                }
+                None => {}
+            }
+        }
+
+        Ok(files)
+    }
+
+    /// Prefixes each of `files` with its header.
+    fn stamp(&self, files: &mut BTreeMap<String, String>) {
+        let config = self.config_hash();
+        for (name, contents) in files.iter_mut() {
+            let mut header = format!(
+                "// @generated by huffgen {}\n// @library {} {}\n// @config {}\n",
+                VERSION, name, VERSION, config,
+            );
+            if let Some(timestamp) = self.timestamp {
+                header.push_str(&format!("{}{}\n", TIMESTAMP, rfc3339(timestamp)));
+            }
+            contents.insert_str(0, &header);
+        }
+    }
+
+    /// The macros the selected libraries delegate to, defined by the contract including them.
+    fn delegates(&self) -> io::Result<BTreeSet<String>> {
+        match self.libraries().contains(&Library::Dispatchers) {
+            true => libdispatch::delegates(&self.options.config),
+            false => Ok(BTreeSet::new()),
+        }
+    }
+
+    /// The new names of the macros and errors of `files`, prefixed and renamed by the naming
+    /// convention, failing if two would share a name.
+    fn renames(&self, files: &[(String, String)]) -> io::Result<BTreeMap<String, String>> {
+        let mut renames = BTreeMap::new();
+        for (_, contents) in files {
+            for name in macros(contents) {
+                let new = self
+                    .naming
+                    .as_ref()
+                    .and_then(|naming| naming.macro_name(name));
+                let new = format!("{}{}", self.options.prefix, new.as_deref().unwrap_or(name));
+                renames.insert(name.to_string(), new);
+            }
+            for name in errors(contents) {
+                let new = self
+                    .naming
+                    .as_ref()
+                    .and_then(|naming| naming.error_name(name));
+                renames.insert(name.to_string(), new.unwrap_or_else(|| name.to_string()));
+            }
+        }
+
+        let mut names = BTreeMap::new();
+        for (name, new) in &renames {
+            if let Some(other) = names.insert(new, name) {
+                let naming = self
+                    .naming
+                    .as_ref()
+                    .map_or("libhuff", |naming| naming.name());
+                return Err(config::invalid(format!(
+                    "naming `{}` renames both `{}` and `{}` to `{}`",
+                    naming, other, name, new,
+                )));
+            }
+        }
+
+        // the module docs name families of macros by placeholders, `TO_TYPENAME` for `TO_U8`
+        for (_, contents) in files {
+            for name in placeholders(contents) {
+                let new = self
+                    .naming
+                    .as_ref()
+                    .and_then(|naming| naming.macro_name(name));
+                let new = format!("{}{}", self.options.prefix, new.as_deref().unwrap_or(name));
+                renames.entry(name.to_string()).or_insert(new);
+            }
+        }
+
+        renames.retain(|name, new| name != new);
+        Ok(renames)
+    }
+
+    /// The hash of the inputs the contents of the generated files depend on: the config, but for
+    /// its `[generate]` table, and the sizes, checked cast reverts, prefix, layout, target and
+    /// naming convention it sets. The default target and naming convention are left out of the
+    /// hash.
+    fn config_hash(&self) -> String {
+        let mut config = self.options.config.clone();
+        config.remove("generate");
+        let mut inputs = format!(
+            "{}\nsizes = {:?}\nrevert = {:?}\nprefix = {:?}\nlayout = {:?}\n",
+            config::canonical(&config),
+            self.options.sizes(),
+            self.options.overflow_revert.name(),
+            self.options.prefix,
+            self.layout.name(),
+        );
+        if self.target != Target::Huff {
+            inputs.push_str(&format!("target = {:?}\n", self.target.name()));
+        }
+        if let Some(naming) = &self.naming {
+            inputs.push_str(&format!("naming = {:?}\n", naming));
+        }
+
+        let hash = keccak256(inputs.as_bytes());
+        format!(
+            "0x{}",
+            hash.iter()
+                .map(|byte| format!("{:02x}", byte))
+                .collect::<String>()
+        )
+    }
+
+    /// Writes the rendered libraries to `dir`, creating it if needed.
+    pub fn write_to(&self, dir: impl AsRef<Path>) -> io::Result<()> {
+        self.write_summarized(dir).map(|_| ())
+    }
+
+    /// Writes the rendered libraries to `dir` as `write_to` does, summarizing each file written.
+    pub fn write_summarized(&self, dir: impl AsRef<Path>) -> io::Result<Vec<Summary>> {
+        let (files, timings) = self.render_timed()?;
+        self.write_rendered(dir, files, &timings)
+    }
+
+    /// Writes the `files` `render_timed` rendered to `dir`, summarizing each file written with the
+    /// time of `timings` it took to render.
+    pub fn write_rendered(
+        &self,
+        dir: impl AsRef<Path>,
+        files: BTreeMap<String, String>,
+        timings: &BTreeMap<String, Duration>,
+    ) -> io::Result<Vec<Summary>> {
+        let dir = dir.as_ref();
+        fs::create_dir_all(dir)?;
+        let mut summaries = Vec::new();
+        for (name, contents) in files {
+            let start = Instant::now();
+            let path = dir.join(&name);
+            fs::create_dir_all(path.parent().unwrap_or(dir))?;
+            let mut file = BufWriter::new(fs::File::create(&path)?);
+            file.write_all(contents.as_bytes())?;
+            file.flush()?;
+
+            let lines = contents.lines();
+            let definitions = match self.target {
+                Target::Huff => macros(&contents).count(),
+                Target::Yul => lines.filter(|line| line.starts_with("function ")).count(),
+            };
+            let rendering = timings.get(&name).copied().unwrap_or_default();
+            summaries.push(Summary {
+                path,
+                definitions,
+                bytes: contents.len(),
+                elapsed: rendering + start.elapsed(),
+            });
+        }
+
+        Ok(summaries)
+    }
+
+    /// Compares the rendered libraries to those in `dir`, returning a unified diff of each file
+    /// that is missing or out of date. Timestamps are not compared.
+    pub fn check(&self, dir: impl AsRef<Path>) -> io::Result<Vec<String>> {
+        let mut diffs = Vec::new();
+        for (name, contents) in self.render()? {
+            let path = dir.as_ref().join(name);
+            let old = match fs::read_to_string(&path) {
+                Ok(old) => old,
+                Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+                Err(e) => return Err(e),
+            };
+            let (old, new) = (without_timestamp(&old), without_timestamp(&contents));
+            diffs.extend(diff::unified(&path.display().to_string(), &old, &new));
+        }
+
+        Ok(diffs)
+    }
+}
+
+/// A file written by the generator.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Summary {
+    pub path: PathBuf,
+    /// The macros it defines, or functions of the Yul target.
+    pub definitions: usize,
+    /// Its size, in bytes.
+    pub bytes: usize,
+    /// The time its library took to render, and the file to write.
+    pub elapsed: Duration,
+}
+
+/// Renders each of `libraries` on a thread of its own, in order, with the time each took.
+fn render_libraries(
+    libraries: &[Library],
+    options: &Options,
+) -> Vec<(Library, io::Result<Option<String>>, Duration)> {
+    let render = |library: Library| {
+        let start = Instant::now();
+        let rendered = library.render(options);
+        (library, rendered, start.elapsed())
+    };
+    if libraries.len() < 2 {
+        return libraries.iter().map(|library| render(*library)).collect();
+    }
+
+    std::thread::scope(|scope| {
+        let threads = libraries
+            .iter()
+            .map(|library| scope.spawn(move || render(*library)))
+            .collect::<Vec<_>>();
+        threads
+            .into_iter()
+            .map(|thread| thread.join().expect("rendering panicked"))
+            .collect()
+    })
+}
+
+/// The hand-written libraries of `src` that generated libraries include, written alongside them
+/// as they are, so that any selection compiles in any directory.
+const HANDWRITTEN: [(&str, &str); 1] = [(
+    "libreentrancy.huff",
+    include_str!("../src/libreentrancy.huff"),
+)];
+
+/// The start of the timestamp line of the header of generated files.
+const TIMESTAMP: &str = "// @timestamp ";
+
+fn without_timestamp(contents: &str) -> String {
+    contents
+        .split_inclusive('\n')
+        .filter(|line| !line.starts_with(TIMESTAMP))
+        .collect()
+}
+
+/// Formats seconds since the Unix epoch as an RFC 3339 UTC timestamp, e.g.
+/// `2024-03-13T13:55:35Z`.
+fn rfc3339(timestamp: u64) -> String {
+    let (days, seconds) = (timestamp / 86400, timestamp % 86400);
+
+    // the civil date of a day count, counting years from March so leap days end them
+    let days = days as i64 + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days.rem_euclid(146097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month + 2) / 5 + 1;
+    let month = if month < 10 { month + 3 } else { month - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60,
+    )
+}
+
+/// The names of the macros `contents` defines.
+fn macros(contents: &str) -> impl Iterator<Item = &str> {
+    contents.lines().filter_map(|line| {
+        let rest = line
+            .strip_prefix("#define macro ")
+            .or(line.strip_prefix("#define fn "))?;
+        rest.split('(').next()
+    })
+}
+
+/// The names the API of the module docs of `contents` lists without defining them, the
+/// placeholders of families of macros such as `TO_TYPENAME`.
+fn placeholders(contents: &str) -> impl Iterator<Item = &str> {
+    let defined = contents
+        .lines()
+        .filter_map(|line| {
+            let rest = line
+                .strip_prefix("#define macro ")
+                .or(line.strip_prefix("#define constant "))?;
+            rest.split(['(', ' ']).next()
+        })
+        .collect::<BTreeSet<&str>>();
+
+    contents.lines().filter_map(move |line| {
+        let name = line.strip_prefix("//! - `")?.split('`').next()?;
+        let is_name = name
+            .chars()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_');
+        (is_name && !defined.contains(name)).then_some(name)
+    })
+}
+
+/// The files `contents` includes, as written.
+fn includes(contents: &str) -> impl Iterator<Item = &str> {
+    contents.lines().filter_map(|line| {
+        let rest = line.strip_prefix("#include \"")?;
+        rest.split('"').next()
+    })
+}
+
+/// The names of the errors `contents` defines.
+fn errors(contents: &str) -> impl Iterator<Item = &str> {
+    contents
+        .lines()
+        .filter_map(|line| line.strip_prefix("#define error ")?.split('(').next())
+}
+
+/// The title of the library `file_name`, as its header names it, `Safe Math` for `# Safe Math
+/// Library`, or its file name without the `lib` prefix and extension.
+fn title<'a>(contents: &'a str, file_name: &'a str) -> &'a str {
+    contents
+        .lines()
+        .find_map(|line| line.strip_prefix("//! # "))
+        .map(|title| title.strip_suffix(" Library").unwrap_or(title))
+        .unwrap_or_else(|| {
+            file_name
+                .trim_start_matches("lib")
+                .trim_end_matches(".huff")
+        })
+}
+
+/// Renames the identifiers of `renames` in `contents`. Code is renamed with its stack comments
+/// kept at their column, and comments only in their code spans and the usage examples of docs,
+/// so prose such as the `Overflow` of `## Overflow Error` keeps its words.
+fn renamed(contents: &str, renames: &BTreeMap<String, String>) -> String {
+    let mut example = false;
+
+    contents
+        .split_inclusive('\n')
+        .map(|line| {
+            let (line, newline) = line
+                .strip_suffix('\n')
+                .map_or((line, ""), |line| (line, "\n"));
+            let indent = line.len() - line.trim_start().len();
+            let doc = ["/// ", "//! "]
+                .into_iter()
+                .find_map(|marker| Some((marker, line[indent..].strip_prefix(marker)?)));
+            let line = match doc {
+                Some((_, text)) if text.starts_with("```") => {
+                    example = !example;
+                    line.to_string()
+                }
+                Some((marker, text)) if example => {
+                    format!(
+                        "{}{}{}",
+                        &line[..indent],
+                        marker,
+                        renamed_code(text, renames)
+                    )
+                }
+                _ if line[indent..].starts_with("//") => line
+                    .split('`')
+                    .enumerate()
+                    .map(|(i, part)| match i % 2 {
+                        1 => renamed_identifiers(part, renames),
+                        _ => part.to_string(),
+                    })
+                    .collect::<Vec<String>>()
+                    .join("`"),
+                _ => renamed_code(line, renames),
+            };
+            line + newline
+        })
+        .collect()
+}
+
+/// Renames the identifiers of `renames` in the code of `line`, realigning its stack comment to the
+/// column it was at, or a space after the code where the code grew past it.
+fn renamed_code(line: &str, renames: &BTreeMap<String, String>) -> String {
+    let Some(column) = line.find("//") else {
+        return renamed_identifiers(line, renames);
+    };
+    let (code, comment) = line.split_at(column);
+    let renamed = renamed_identifiers(code.trim_end(), renames);
+    if renamed == code.trim_end() {
+        return line.to_string();
+    }
+    match code.trim().is_empty() {
+        true => line.to_string(),
+        false => format!("{:<width$}{}", renamed + " ", comment, width = column),
+    }
+}
+
+/// Renames each occurrence of the identifiers of `renames` in `text`.
+fn renamed_identifiers(text: &str, renames: &BTreeMap<String, String>) -> String {
+    let is_ident = |c: char| c.is_ascii_alphanumeric() || c == '_';
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find(is_ident) {
+        let end = rest[start..]
+            .find(|c: char| !is_ident(c))
+            .map_or(rest.len(), |end| start + end);
+        out.push_str(&rest[..start]);
+        out.push_str(
+            renames
+                .get(&rest[start..end])
+                .map_or(&rest[start..end], String::as_str),
+        );
+        rest = &rest[end..];
+    }
+
+    out.push_str(rest);
+    out
+}
+
+/// Moves the files of `moves`, rewriting the includes of and of the moved files to follow them,
+/// as well as those of the usage examples of their docs, relative to the output directory.
+fn moved(
+    files: BTreeMap<String, String>,
+    moves: &BTreeMap<String, String>,
+) -> BTreeMap<String, String> {
+    let path = |name: &String| moves.get(name).unwrap_or(name).clone();
+
+    files
+        .iter()
+        .map(|(name, contents)| {
+            let (dir, new_dir) = (parent(name), parent(moves.get(name).unwrap_or(name)));
+            let contents = contents
+                .split_inclusive('\n')
+                .map(|line| {
+                    let include = line
+                        .strip_prefix("#include \"")
+                        .and_then(|rest| rest.split_once('"'))
+                        .map(|(include, rest)| (resolve(dir, include), rest));
+                    let example = line
+                        .strip_prefix("/// #include \"")
+                        .and_then(|rest| rest.split_once('"'))
+                        .filter(|(include, _)| moves.contains_key(*include));
+                    match (include, example) {
+                        (Some((target, rest)), _)
+                            if moves.contains_key(name) || moves.contains_key(&target) =>
+                        {
+                            format!("#include \"{}\"{}", relative(new_dir, &path(&target)), rest)
+                        }
+                        (_, Some((include, rest))) => {
+                            format!("/// #include \"{}\"{}", moves[include], rest)
+                        }
+                        _ => line.to_string(),
+                    }
+                })
+                .collect::<String>();
+            (path(name), contents)
+        })
+        .collect()
+}
+
+/// The directory of `path`, empty at the root.
+pub(crate) fn parent(path: &str) -> &str {
+    path.rsplit_once('/').map_or("", |(dir, _)| dir)
+}
+
+/// The path of `include`, relative to the directory `dir`, from the root.
+pub(crate) fn resolve(dir: &str, include: &str) -> String {
+    let mut components = dir
+        .split('/')
+        .filter(|c| !c.is_empty())
+        .collect::<Vec<&str>>();
+    for component in include.split('/') {
+        match component {
+            ".." => {
+                components.pop();
+            }
+            "." | "" => {}
+            component => components.push(component),
+        }
+    }
+    components.join("/")
+}
+
+/// The path of `path`, from the root, relative to the directory `dir`.
+fn relative(dir: &str, path: &str) -> String {
+    let dir = dir
+        .split('/')
+        .filter(|c| !c.is_empty())
+        .collect::<Vec<&str>>();
+    let path = path.split('/').collect::<Vec<&str>>();
+    let common = dir.iter().zip(&path).take_while(|(a, b)| a == b).count();
+
+    let mut components = vec![".."; dir.len() - common];
+    components.extend(&path[common..]);
+    components.join("/")
+}
+
+/// Parses a comma separated list of bit sizes, each a multiple of 8 from 8 to 256.
+pub fn parse_sizes(list: &str) -> Result<Vec<u16>, String> {
+    let mut sizes = list
+        .split(',')
+        .map(|size| match size.trim().parse::<u16>() {
+            Ok(size) if INT_SIZES.contains(&size) => Ok(size),
+            _ => Err(invalid_size(size)),
+        })
+        .collect::<Result<Vec<u16>, String>>()?;
+
+    sizes.sort_unstable();
+    sizes.dedup();
+
+    Ok(sizes)
+}
+
+fn invalid_size(size: impl std::fmt::Display) -> String {
+    format!(
+        "invalid size `{}`, expected a multiple of 8 from 8 to 256",
+        size
+    )
+}
@@ -0,0 +1,235 @@
+use crate::config::{invalid, Table, Value};
+use crate::libcast::mask;
+use crate::templates::{self, Context};
+
+/// Width of the consumed amount when the config does not set `rate_limit.amount_bits`.
+const DEFAULT_AMOUNT_BITS: u128 = 192;
+
+/// Width of the window start when the config does not set `rate_limit.timestamp_bits`.
+const DEFAULT_TIMESTAMP_BITS: u128 = 64;
+
+/// Minimum width of the window start, enough for timestamps until 2106.
+const MIN_TIMESTAMP_BITS: u128 = 32;
+
+pub fn render(config: &Table) -> std::io::Result<String> {
+    let (amount_bits, timestamp_bits) = parse_rate_limit(config)?;
+
+    let context = Context::new()
+        .text("amount_bits", amount_bits.to_string())
+        .text("timestamp_bits", timestamp_bits.to_string())
+        .text("amount_shift", format!("0x{:02x}", amount_bits))
+        .text("amount_mask", mask(amount_bits));
+
+    let libratelimit = format!(
+        "{}{}{}",
+        templates::render(HEADER, &context),
+        templates::render(CONSTANT_DEFINITION, &context),
+        RATE_LIMIT_DEFINITION,
+    );
+
+    Ok(libratelimit)
+}
+
+fn parse_rate_limit(config: &Table) -> std::io::Result<(u16, u16)> {
+    let rate_limit = match config.get("rate_limit") {
+        None => return Ok((DEFAULT_AMOUNT_BITS as u16, DEFAULT_TIMESTAMP_BITS as u16)),
+        Some(Value::Table(rate_limit)) => rate_limit,
+        Some(_) => return Err(invalid("`rate_limit` must be a table")),
+    };
+
+    let read = |key: &str, default: u128, min: u128| match rate_limit.get(key) {
+        None => Ok(default),
+        Some(value) => value
+            .as_integer()
+            .filter(|n| (min..=256).contains(n))
+            .ok_or_else(|| {
+                invalid(format!(
+                    "`rate_limit.{}` must be between {} and 256",
+                    key, min
+                ))
+            }),
+    };
+
+    let amount_bits = read("amount_bits", DEFAULT_AMOUNT_BITS, 1)?;
+    let timestamp_bits = read("timestamp_bits", DEFAULT_TIMESTAMP_BITS, MIN_TIMESTAMP_BITS)?;
+
+    if amount_bits + timestamp_bits > 256 {
+        return Err(invalid(
+            "`rate_limit.amount_bits` and `rate_limit.timestamp_bits` exceed 256 bits",
+        ));
+    }
+
+    Ok((amount_bits as u16, timestamp_bits as u16))
+}
+
+const HEADER: &str = r#"
+//  ------------------------------------------------------------------------------------------------
+//! # Rate Limit Library
+//!
+//! Provides fixed window rate limiting backed by a single packed storage slot.
+//!
+//! | Field | Bits | Offset |
+//! | ----- | ---- | ------ |
+//! | `consumed` | {{amount_bits}} | 0 |
+//! | `window_start` | {{timestamp_bits}} | {{amount_bits}} |
+//!
+//! A window starts at the first consumption after the previous window has elapsed, at which point
+//! the consumed amount resets to zero. Widths are configurable with `rate_limit.amount_bits` and
+//! `rate_limit.timestamp_bits` in `libhuff.toml`.
+//!
+//! ## API
+//!
+//! - `RATE_LIMIT_CONSUME` - Consumes an amount, reverting if the limit would be exceeded.
+//! - `RATE_LIMIT_REMAINING` - Pushes the amount that may still be consumed in the current window.
+"#;
+
+const CONSTANT_DEFINITION: &str = r#"
+/// ## Rate Limit Amount Mask
+///
+/// Mask of the consumed amount in a rate limit slot.
+#define constant RATE_LIMIT_AMOUNT_MASK = {{amount_mask}}
+
+/// ## Rate Limit Start Shift
+///
+/// Bit offset of the window start in a rate limit slot.
+#define constant RATE_LIMIT_START_SHIFT = {{amount_shift}}
+
+/// ## Rate Limited Error
+///
+/// Thrown when consuming an amount would exceed the limit of the current window.
+#define error RateLimited()
+"#;
+
+const RATE_LIMIT_DEFINITION: &str = r#"
+/// ## Rate Limit Consume
+///
+/// Consumes an amount from the current window, rolling over to a new window if the current one has
+/// elapsed.
+///
+/// ### Template Arguments
+///
+/// - `slot`: storage slot of the rate limit.
+/// - `limit`: maximum amount consumable per window, must fit in the consumed amount width.
+/// - `window`: duration of a window in seconds.
+///
+/// ### Stack Arguments
+///
+/// - `amount`: (`stack[0]`) amount to consume.
+///
+/// ### Panics
+///
+/// - if the consumed amount of the window would exceed `limit`.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define constant RATE_LIMIT_SLOT = FREE_STORAGE_POINTER()
+///
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x04 calldataload
+///     RATE_LIMIT_CONSUME([RATE_LIMIT_SLOT], 0x0de0b6b3a7640000, 0x015180)
+///     stop
+/// }
+/// ```
+#define macro RATE_LIMIT_CONSUME(slot, limit, window) = takes (1) returns (0) {
+    // takes:               // [amount]
+    <slot>                  // [slot, amount]
+    sload                   // [word, amount]
+    __RATE_LIMIT_CURRENT(<window>) // [word, amount]
+    dup1                    // [word, word, amount]
+    [RATE_LIMIT_AMOUNT_MASK] // [amount_mask, word, word, amount]
+    and                     // [consumed, word, amount]
+    dup3                    // [amount, consumed, word, amount]
+    add                     // [new_consumed, word, amount]
+    dup1                    // [new_consumed, new_consumed, word, amount]
+    dup4                    // [amount, new_consumed, new_consumed, word, amount]
+    gt                      // [overflowed, new_consumed, word, amount]
+    dup2                    // [new_consumed, overflowed, new_consumed, word, amount]
+    <limit>                 // [limit, new_consumed, overflowed, new_consumed, word, amount]
+    lt                      // [exceeds, overflowed, new_consumed, word, amount]
+    or                      // [limited, new_consumed, word, amount]
+    iszero                  // [allowed, new_consumed, word, amount]
+    allowed                 // [allowed_dest, allowed, new_consumed, word, amount]
+    jumpi                   // [new_consumed, word, amount]
+        __ERROR(RateLimited) // [err, new_consumed, word, amount]
+        0x00                // [ptr, err, new_consumed, word, amount]
+        mstore              // [new_consumed, word, amount]
+        0x04                // [err_len, new_consumed, word, amount]
+        0x00                // [ptr, err_len, new_consumed, word, amount]
+        revert              // []
+    allowed:                // [new_consumed, word, amount]
+        swap1               // [word, new_consumed, amount]
+        [RATE_LIMIT_AMOUNT_MASK] // [amount_mask, word, new_consumed, amount]
+        not                 // [start_mask, word, new_consumed, amount]
+        and                 // [start, new_consumed, amount]
+        or                  // [new_word, amount]
+        <slot>              // [slot, new_word, amount]
+        sstore              // [amount]
+        pop                 // []
+}
+
+/// ## Rate Limit Remaining
+///
+/// Pushes the amount that may still be consumed in the current window, which is `limit` if the
+/// window has elapsed.
+///
+/// ### Template Arguments
+///
+/// - `slot`: storage slot of the rate limit.
+/// - `limit`: maximum amount consumable per window.
+/// - `window`: duration of a window in seconds.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define constant RATE_LIMIT_SLOT = FREE_STORAGE_POINTER()
+///
+/// #define macro MAIN() = takes (0) returns (0) {
+///     RATE_LIMIT_REMAINING([RATE_LIMIT_SLOT], 0x0de0b6b3a7640000, 0x015180)
+///     0x00 mstore
+///     0x20 0x00 return
+/// }
+/// ```
+#define macro RATE_LIMIT_REMAINING(slot, limit, window) = takes (0) returns (1) {
+    <slot>                  // [slot]
+    sload                   // [word]
+    __RATE_LIMIT_CURRENT(<window>) // [word]
+    [RATE_LIMIT_AMOUNT_MASK] // [amount_mask, word]
+    and                     // [consumed]
+    <limit>                 // [limit, consumed]
+    dup2                    // [consumed, limit, consumed]
+    dup2                    // [limit, consumed, limit, consumed]
+    lt                      // [exhausted, limit, consumed]
+    exhausted               // [exhausted_dest, exhausted, limit, consumed]
+    jumpi                   // [limit, consumed]
+        sub                 // [remaining]
+        done                // [done_dest, remaining]
+        jump                // [remaining]
+    exhausted:              // [limit, consumed]
+        pop                 // [consumed]
+        pop                 // []
+        0x00                // [remaining]
+    done:                   // [remaining]
+}
+
+/// ## Rate Limit Current
+///
+/// Replaces a rate limit word with a fresh window starting now if its window has elapsed.
+#define macro __RATE_LIMIT_CURRENT(window) = takes (1) returns (1) {
+    // takes:               // [word]
+    dup1                    // [word, word]
+    [RATE_LIMIT_START_SHIFT] // [shift, word, word]
+    shr                     // [start, word]
+    <window>                // [window, start, word]
+    add                     // [end, word]
+    timestamp               // [timestamp, end, word]
+    lt                      // [is_active, word]
+    active                  // [active_dest, is_active, word]
+    jumpi                   // [word]
+        pop                 // []
+        timestamp           // [timestamp]
+        [RATE_LIMIT_START_SHIFT] // [shift, timestamp]
+        shl                 // [word]
+    active:                 // [word]
+}
+"#;
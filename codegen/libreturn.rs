@@ -0,0 +1,178 @@
+pub fn render() -> String {
+    format!("{}{}", HEADER, RETURN_DEFINITION)
+}
+
+const HEADER: &str = r#"
+//  ------------------------------------------------------------------------------------------------
+//! # Return Library
+//!
+//! Provides ABI encoded returns of dynamic values.
+//!
+//! Values are encoded in place: the head and length words are written to the words immediately
+//! before the value in memory, and the value is zero padded to a whole number of words, so the
+//! value is never copied. Values must be at least `0x40` bytes past the start of memory, more if
+//! other words precede them in the head.
+//!
+//! ## API
+//!
+//! - `RETURN_BYTES` - Returns `bytes` or `string`.
+//! - `RETURN_UINT_ARRAY` - Returns `uint256[]`.
+//! - `RETURN_STRING_AND_UINT` - Returns `(string, uint256)`.
+"#;
+
+const RETURN_DEFINITION: &str = r#"
+/// ## Return Bytes
+///
+/// Returns `len` bytes of memory at `ptr` ABI encoded as `bytes`, which is also the encoding of
+/// `string`.
+///
+/// > WARNING: Memory from `ptr - 0x40` to `ptr` and from `ptr + len` to `ptr + len + 0x20` will be
+/// > overwritten.
+///
+/// ### Stack Arguments
+///
+/// - `ptr`: (`stack[0]`) memory pointer of the bytes, at least `0x40`.
+/// - `len`: (`stack[1]`) length of the bytes.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x04 calldatasize sub
+///     dup1 0x04 0x40 calldatacopy
+///     0x40
+///     RETURN_BYTES()
+/// }
+/// ```
+#define macro RETURN_BYTES() = takes (2) returns (0) {
+    // takes:               // [ptr, len]
+    __RETURN_TAIL()         // [ptr, padded_len]
+    0x20                    // [offset, ptr, padded_len]
+    0x40                    // [0x40, offset, ptr, padded_len]
+    dup3                    // [ptr, 0x40, offset, ptr, padded_len]
+    sub                     // [offset_ptr, offset, ptr, padded_len]
+    mstore                  // [ptr, padded_len]
+    0x40                    // [0x40, ptr, padded_len]
+    swap1                   // [ptr, 0x40, padded_len]
+    sub                     // [enc_ptr, padded_len]
+    swap1                   // [padded_len, enc_ptr]
+    0x40                    // [0x40, padded_len, enc_ptr]
+    add                     // [enc_len, enc_ptr]
+    swap1                   // [enc_ptr, enc_len]
+    return                  // []
+}
+
+/// ## Return Uint Array
+///
+/// Returns `len` words of memory at `ptr` ABI encoded as `uint256[]`.
+///
+/// > WARNING: Memory from `ptr - 0x40` to `ptr` will be overwritten.
+///
+/// ### Stack Arguments
+///
+/// - `ptr`: (`stack[0]`) memory pointer of the words, at least `0x40`.
+/// - `len`: (`stack[1]`) number of words.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x01 0x40 mstore
+///     0x02 0x60 mstore
+///     0x02 0x40
+///     RETURN_UINT_ARRAY()
+/// }
+/// ```
+#define macro RETURN_UINT_ARRAY() = takes (2) returns (0) {
+    // takes:               // [ptr, len]
+    dup2                    // [len, ptr, len]
+    0x20                    // [0x20, len, ptr, len]
+    dup3                    // [ptr, 0x20, len, ptr, len]
+    sub                     // [len_ptr, len, ptr, len]
+    mstore                  // [ptr, len]
+    0x20                    // [offset, ptr, len]
+    0x40                    // [0x40, offset, ptr, len]
+    dup3                    // [ptr, 0x40, offset, ptr, len]
+    sub                     // [offset_ptr, offset, ptr, len]
+    mstore                  // [ptr, len]
+    0x40                    // [0x40, ptr, len]
+    swap1                   // [ptr, 0x40, len]
+    sub                     // [enc_ptr, len]
+    swap1                   // [len, enc_ptr]
+    0x05                    // [0x05, len, enc_ptr]
+    shl                     // [words_len, enc_ptr]
+    0x40                    // [0x40, words_len, enc_ptr]
+    add                     // [enc_len, enc_ptr]
+    swap1                   // [enc_ptr, enc_len]
+    return                  // []
+}
+
+/// ## Return String And Uint
+///
+/// Returns `len` bytes of memory at `ptr` and `value` ABI encoded as `(string, uint256)`.
+///
+/// > WARNING: Memory from `ptr - 0x60` to `ptr` and from `ptr + len` to `ptr + len + 0x20` will be
+/// > overwritten.
+///
+/// ### Stack Arguments
+///
+/// - `ptr`: (`stack[0]`) memory pointer of the string, at least `0x60`.
+/// - `len`: (`stack[1]`) length of the string.
+/// - `value`: (`stack[2]`) uint to return.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x2a
+///     0x05
+///     0x68656c6c6f000000000000000000000000000000000000000000000000000000 0x60 mstore
+///     0x60
+///     RETURN_STRING_AND_UINT()
+/// }
+/// ```
+#define macro RETURN_STRING_AND_UINT() = takes (3) returns (0) {
+    // takes:               // [ptr, len, value]
+    __RETURN_TAIL()         // [ptr, padded_len, value]
+    swap2                   // [value, padded_len, ptr]
+    0x40                    // [0x40, value, padded_len, ptr]
+    dup4                    // [ptr, 0x40, value, padded_len, ptr]
+    sub                     // [value_ptr, value, padded_len, ptr]
+    mstore                  // [padded_len, ptr]
+    0x40                    // [offset, padded_len, ptr]
+    0x60                    // [0x60, offset, padded_len, ptr]
+    dup4                    // [ptr, 0x60, offset, padded_len, ptr]
+    sub                     // [offset_ptr, offset, padded_len, ptr]
+    mstore                  // [padded_len, ptr]
+    0x60                    // [0x60, padded_len, ptr]
+    add                     // [enc_len, ptr]
+    0x60                    // [0x60, enc_len, ptr]
+    dup3                    // [ptr, 0x60, enc_len, ptr]
+    sub                     // [enc_ptr, enc_len, ptr]
+    return                  // []
+}
+
+/// ## Return Tail
+///
+/// Writes the length of a dynamic value before it and zero pads it to a whole number of words.
+#define macro __RETURN_TAIL() = takes (2) returns (2) {
+    // takes:               // [ptr, len]
+    0x00                    // [0x00, ptr, len]
+    dup3                    // [len, 0x00, ptr, len]
+    dup3                    // [ptr, len, 0x00, ptr, len]
+    add                     // [end, 0x00, ptr, len]
+    mstore                  // [ptr, len]
+    dup2                    // [len, ptr, len]
+    0x20                    // [0x20, len, ptr, len]
+    dup3                    // [ptr, 0x20, len, ptr, len]
+    sub                     // [len_ptr, len, ptr, len]
+    mstore                  // [ptr, len]
+    swap1                   // [len, ptr]
+    0x1f                    // [0x1f, len, ptr]
+    add                     // [len_up, ptr]
+    0x1f                    // [0x1f, len_up, ptr]
+    not                     // [word_mask, len_up, ptr]
+    and                     // [padded_len, ptr]
+    swap1                   // [ptr, padded_len]
+}
+"#;
@@ -0,0 +1,332 @@
+pub fn render() -> String {
+    format!("{}{}{}", HEADER, SELECTOR_DEFINITION, DECODE_DEFINITION)
+}
+
+const HEADER: &str = r#"
+//  ------------------------------------------------------------------------------------------------
+//! # Revert Library
+//!
+//! Provides inspection of the returndata of a failed call, to handle downstream failures by kind.
+//!
+//! ## API
+//!
+//! - `REVERT_SELECTOR` - Loads the selector of the revert data.
+//! - `JUMP_IF_EMPTY_REVERT` - Jumps if the revert data is empty.
+//! - `JUMP_IF_ERROR_STRING` - Jumps if the revert data is an `Error(string)`.
+//! - `JUMP_IF_PANIC` - Jumps if the revert data is a `Panic(uint256)`.
+//! - `JUMP_IF_CUSTOM_ERROR` - Jumps if the revert data has a given selector.
+//! - `ERROR_STRING_MESSAGE` - Decodes the message of an `Error(string)`.
+//! - `PANIC_CODE` - Decodes the code of a `Panic(uint256)`.
+//! - `BUBBLE_REVERT` - Reverts with the revert data.
+
+/// ## Error String Selector
+///
+/// Selector of `Error(string)`, the revert data of `require` and `revert` with a reason.
+#define constant ERROR_STRING_SELECTOR = 0x08c379a0
+
+/// ## Panic Selector
+///
+/// Selector of `Panic(uint256)`, the revert data of failed assertions and arithmetic.
+#define constant PANIC_SELECTOR = 0x4e487b71
+
+/// ## Invalid Revert Data Error
+///
+/// Thrown when revert data does not decode as expected.
+#define error InvalidRevertData()
+"#;
+
+const SELECTOR_DEFINITION: &str = r#"
+/// ## Revert Selector
+///
+/// Loads the selector of the revert data, or zero if it is shorter than a selector.
+///
+/// > WARNING: Memory from `0x00` to `0x20` will be overwritten.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     REVERT_SELECTOR()
+///     0x00 mstore
+///     0x20 0x00 return
+/// }
+/// ```
+#define macro REVERT_SELECTOR() = takes (0) returns (1) {
+    0x00                    // [0x00]
+    0x00                    // [ptr, 0x00]
+    mstore                  // []
+    0x04                    // [0x04]
+    returndatasize          // [returndatasize, 0x04]
+    lt                      // [is_short]
+    iszero                  // [has_selector]
+    0x02                    // [0x02, has_selector]
+    shl                     // [len]
+    0x00                    // [offset, len]
+    0x00                    // [ptr, offset, len]
+    returndatacopy          // []
+    0x00                    // [ptr]
+    mload                   // [word]
+    0xe0                    // [shift, word]
+    shr                     // [selector]
+}
+
+/// ## Jump If Empty Revert
+///
+/// Jumps to `dest` if the revert data is empty, as with `revert()` or running out of gas.
+///
+/// ### Template Arguments
+///
+/// - `dest`: label to jump to.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     JUMP_IF_EMPTY_REVERT(empty)
+///     BUBBLE_REVERT()
+///     empty:
+///         stop
+/// }
+/// ```
+#define macro JUMP_IF_EMPTY_REVERT(dest) = takes (0) returns (0) {
+    returndatasize          // [returndatasize]
+    iszero                  // [is_empty]
+    <dest>                  // [dest, is_empty]
+    jumpi                   // []
+}
+
+/// ## Jump If Error String
+///
+/// Jumps to `dest` if the revert data is an `Error(string)`.
+///
+/// > WARNING: Memory from `0x00` to `0x20` will be overwritten.
+///
+/// ### Template Arguments
+///
+/// - `dest`: label to jump to.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     JUMP_IF_ERROR_STRING(error_string)
+///     BUBBLE_REVERT()
+///     error_string:
+///         ERROR_STRING_MESSAGE(0x00)
+///         stop
+/// }
+/// ```
+#define macro JUMP_IF_ERROR_STRING(dest) = takes (0) returns (0) {
+    REVERT_SELECTOR()       // [selector]
+    [ERROR_STRING_SELECTOR] // [error_string_selector, selector]
+    eq                      // [is_error_string]
+    0x43                    // [0x43, is_error_string]
+    returndatasize          // [returndatasize, 0x43, is_error_string]
+    gt                      // [has_message, is_error_string]
+    and                     // [is_error_string]
+    <dest>                  // [dest, is_error_string]
+    jumpi                   // []
+}
+
+/// ## Jump If Panic
+///
+/// Jumps to `dest` if the revert data is a `Panic(uint256)`.
+///
+/// > WARNING: Memory from `0x00` to `0x20` will be overwritten.
+///
+/// ### Template Arguments
+///
+/// - `dest`: label to jump to.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     JUMP_IF_PANIC(panic)
+///     BUBBLE_REVERT()
+///     panic:
+///         PANIC_CODE()
+///         stop
+/// }
+/// ```
+#define macro JUMP_IF_PANIC(dest) = takes (0) returns (0) {
+    REVERT_SELECTOR()       // [selector]
+    [PANIC_SELECTOR]        // [panic_selector, selector]
+    eq                      // [is_panic]
+    0x23                    // [0x23, is_panic]
+    returndatasize          // [returndatasize, 0x23, is_panic]
+    gt                      // [has_code, is_panic]
+    and                     // [is_panic]
+    <dest>                  // [dest, is_panic]
+    jumpi                   // []
+}
+
+/// ## Jump If Custom Error
+///
+/// Jumps to `dest` if the revert data has the selector of a custom error.
+///
+/// > WARNING: Memory from `0x00` to `0x20` will be overwritten.
+///
+/// ### Template Arguments
+///
+/// - `selector`: selector of the custom error.
+/// - `dest`: label to jump to.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     // Unauthorized()
+///     JUMP_IF_CUSTOM_ERROR(0x82b42900, unauthorized)
+///     BUBBLE_REVERT()
+///     unauthorized:
+///         stop
+/// }
+/// ```
+#define macro JUMP_IF_CUSTOM_ERROR(selector, dest) = takes (0) returns (0) {
+    REVERT_SELECTOR()       // [selector]
+    <selector>              // [expected, selector]
+    eq                      // [is_match]
+    <dest>                  // [dest, is_match]
+    jumpi                   // []
+}
+"#;
+
+const DECODE_DEFINITION: &str = r#"
+/// ## Error String Message
+///
+/// Copies the revert data of an `Error(string)` after its selector to `ptr`, and validates it,
+/// returning the memory pointer and length of the message.
+///
+/// > WARNING: Memory from `ptr` to `ptr + returndatasize - 0x04` will be overwritten.
+///
+/// ### Template Arguments
+///
+/// - `ptr`: memory pointer to copy the revert data to.
+///
+/// ### Panics
+///
+/// - if the offset or length of the message is out of bounds of the revert data.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     JUMP_IF_ERROR_STRING(error_string)
+///     BUBBLE_REVERT()
+///     error_string:
+///         ERROR_STRING_MESSAGE(0x00)
+///         return
+/// }
+/// ```
+#define macro ERROR_STRING_MESSAGE(ptr) = takes (0) returns (2) {
+    0x04                    // [0x04]
+    returndatasize          // [returndatasize, 0x04]
+    sub                     // [size]
+    dup1                    // [size, size]
+    0x04                    // [offset, size, size]
+    <ptr>                   // [ptr, offset, size, size]
+    returndatacopy          // [size]
+    <ptr>                   // [ptr, size]
+    mload                   // [offset, size]
+    0x20                    // [0x20, offset, size]
+    add                     // [len_end, size]
+    dup2                    // [size, len_end, size]
+    dup2                    // [len_end, size, len_end, size]
+    gt                      // [exceeds, len_end, size]
+    dup2                    // [len_end, exceeds, len_end, size]
+    0x20                    // [0x20, len_end, exceeds, len_end, size]
+    gt                      // [overflowed, exceeds, len_end, size]
+    or                      // [is_invalid, len_end, size]
+    invalid_data            // [invalid_dest, is_invalid, len_end, size]
+    jumpi                   // [len_end, size]
+    dup1                    // [len_end, len_end, size]
+    <ptr>                   // [ptr, len_end, len_end, size]
+    add                     // [data_ptr, len_end, size]
+    0x20                    // [0x20, data_ptr, len_end, size]
+    swap1                   // [data_ptr, 0x20, len_end, size]
+    sub                     // [len_ptr, len_end, size]
+    mload                   // [len, len_end, size]
+    dup2                    // [len_end, len, len_end, size]
+    dup4                    // [size, len_end, len, len_end, size]
+    sub                     // [remaining, len, len_end, size]
+    dup2                    // [len, remaining, len, len_end, size]
+    gt                      // [is_invalid, len, len_end, size]
+    invalid_data            // [invalid_dest, is_invalid, len, len_end, size]
+    jumpi                   // [len, len_end, size]
+    swap1                   // [len_end, len, size]
+    <ptr>                   // [ptr, len_end, len, size]
+    add                     // [data_ptr, len, size]
+    swap2                   // [size, len, data_ptr]
+    pop                     // [len, data_ptr]
+    swap1                   // [data_ptr, len]
+    decoded                 // [decoded_dest, data_ptr, len]
+    jump                    // [data_ptr, len]
+    invalid_data:           // []
+        __ERROR(InvalidRevertData) // [err]
+        0x00                // [ptr, err]
+        mstore              // []
+        0x04                // [err_len]
+        0x00                // [ptr, err_len]
+        revert              // []
+    decoded:                // [data_ptr, len]
+}
+
+/// ## Panic Code
+///
+/// Decodes the code of a `Panic(uint256)`, e.g. `0x01` for a failed assertion or `0x11` for an
+/// arithmetic overflow.
+///
+/// > WARNING: Memory from `0x00` to `0x20` will be overwritten.
+///
+/// ### Panics
+///
+/// - if the revert data is shorter than a `Panic(uint256)`.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     JUMP_IF_PANIC(panic)
+///     BUBBLE_REVERT()
+///     panic:
+///         PANIC_CODE()
+///         0x00 mstore
+///         0x20 0x00 return
+/// }
+/// ```
+#define macro PANIC_CODE() = takes (0) returns (1) {
+    0x20                    // [len]
+    0x04                    // [offset, len]
+    0x00                    // [ptr, offset, len]
+    returndatacopy          // []
+    0x00                    // [ptr]
+    mload                   // [code]
+}
+
+/// ## Bubble Revert
+///
+/// Reverts with the revert data of the last call.
+///
+/// > WARNING: Memory from `0x00` to `returndatasize` will be overwritten.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     JUMP_IF_EMPTY_REVERT(empty)
+///     BUBBLE_REVERT()
+///     empty:
+///         stop
+/// }
+/// ```
+#define macro BUBBLE_REVERT() = takes (0) returns (0) {
+    returndatasize          // [returndatasize]
+    0x00                    // [offset, returndatasize]
+    0x00                    // [ptr, offset, returndatasize]
+    returndatacopy          // []
+    returndatasize          // [returndatasize]
+    0x00                    // [ptr, returndatasize]
+    revert                  // []
+}
+"#;
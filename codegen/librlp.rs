@@ -0,0 +1,359 @@
+pub fn render() -> String {
+    format!(
+        "{}{}{}{}",
+        HEADER, LENGTH_DEFINITION, ITEM_DEFINITION, LIST_DEFINITION
+    )
+}
+
+const HEADER: &str = r#"
+//  ------------------------------------------------------------------------------------------------
+//! # RLP Library
+//!
+//! Provides recursive length prefix encoding of byte strings, integers and short lists in memory.
+//!
+//! Encoders write to `dest_ptr` and return the length of the encoding. Prefixes are written a word
+//! at a time, so up to `0x21` bytes from `dest_ptr` are overwritten even if the encoding is
+//! shorter. Payloads are copied with the identity precompile.
+//!
+//! ## API
+//!
+//! - `RLP_PREFIX_LENGTH` - Computes the length of the prefix of a payload.
+//! - `RLP_ENCODE_LENGTH` - Writes the prefix of a payload.
+//! - `RLP_ENCODE_BYTES` - Encodes a byte string.
+//! - `RLP_ENCODE_UINT` - Encodes an integer.
+//! - `RLP_ENCODE_LIST_2` - Encodes a list of two encoded items.
+"#;
+
+const LENGTH_DEFINITION: &str = r#"
+/// ## RLP Prefix Length
+///
+/// Computes the length of the prefix of a payload of `len` bytes, one byte below 56 bytes and
+/// one byte plus the length of `len` otherwise.
+///
+/// ### Stack Arguments
+///
+/// - `len`: (`stack[0]`) length of the payload.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     calldatasize
+///     RLP_PREFIX_LENGTH()
+///     0x00 mstore
+///     0x20 0x00 return
+/// }
+/// ```
+#define macro RLP_PREFIX_LENGTH() = takes (1) returns (1) {
+    // takes:               // [len]
+    dup1                    // [len, len]
+    0x38                    // [0x38, len, len]
+    gt                      // [is_short, len]
+    short_prefix            // [short_prefix_dest, is_short, len]
+    jumpi                   // [len]
+        __RLP_BYTE_LENGTH() // [len_len]
+        0x01                // [0x01, len_len]
+        add                 // [prefix_len]
+        prefix_computed     // [prefix_computed_dest, prefix_len]
+        jump                // [prefix_len]
+    short_prefix:           // [len]
+        pop                 // []
+        0x01                // [prefix_len]
+    prefix_computed:        // [prefix_len]
+}
+
+/// ## RLP Encode Length
+///
+/// Writes the prefix of a payload of `len` bytes to `dest_ptr`.
+///
+/// > WARNING: Memory from `dest_ptr` to `dest_ptr + 0x21` will be overwritten.
+///
+/// ### Template Arguments
+///
+/// - `offset`: `0x80` for a byte string, `0xc0` for a list.
+///
+/// ### Stack Arguments
+///
+/// - `len`: (`stack[0]`) length of the payload.
+/// - `dest_ptr`: (`stack[1]`) memory pointer to write the prefix to.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x00 calldatasize
+///     RLP_ENCODE_LENGTH(0x80)
+///     0x00 return
+/// }
+/// ```
+#define macro RLP_ENCODE_LENGTH(offset) = takes (2) returns (1) {
+    // takes:               // [len, dest_ptr]
+    dup1                    // [len, len, dest_ptr]
+    0x38                    // [0x38, len, len, dest_ptr]
+    gt                      // [is_short, len, dest_ptr]
+    short_length            // [short_length_dest, is_short, len, dest_ptr]
+    jumpi                   // [len, dest_ptr]
+        dup1                // [len, len, dest_ptr]
+        __RLP_BYTE_LENGTH() // [len_len, len, dest_ptr]
+        dup1                // [len_len, len_len, len, dest_ptr]
+        <offset>            // [offset, len_len, len_len, len, dest_ptr]
+        0x37                // [0x37, offset, len_len, len_len, len, dest_ptr]
+        add                 // [long_offset, len_len, len_len, len, dest_ptr]
+        add                 // [prefix, len_len, len, dest_ptr]
+        dup4                // [dest_ptr, prefix, len_len, len, dest_ptr]
+        mstore8             // [len_len, len, dest_ptr]
+        swap1               // [len, len_len, dest_ptr]
+        __RLP_LEFT_ALIGN()  // [len_word, len_len, dest_ptr]
+        dup3                // [dest_ptr, len_word, len_len, dest_ptr]
+        0x01                // [0x01, dest_ptr, len_word, len_len, dest_ptr]
+        add                 // [len_ptr, len_word, len_len, dest_ptr]
+        mstore              // [len_len, dest_ptr]
+        0x01                // [0x01, len_len, dest_ptr]
+        add                 // [prefix_len, dest_ptr]
+        swap1               // [dest_ptr, prefix_len]
+        pop                 // [prefix_len]
+        length_encoded      // [length_encoded_dest, prefix_len]
+        jump                // [prefix_len]
+    short_length:           // [len, dest_ptr]
+        <offset>            // [offset, len, dest_ptr]
+        add                 // [prefix, dest_ptr]
+        swap1               // [dest_ptr, prefix]
+        mstore8             // []
+        0x01                // [prefix_len]
+    length_encoded:         // [prefix_len]
+}
+"#;
+
+const ITEM_DEFINITION: &str = r#"
+/// ## RLP Encode Bytes
+///
+/// Encodes the byte string of `len` bytes at `src_ptr` to `dest_ptr`. A single byte below
+/// `0x80` encodes as itself.
+///
+/// > WARNING: Memory from `dest_ptr` to `dest_ptr + 0x21`, or to the end of the encoding if
+/// > further, will be overwritten.
+///
+/// ### Stack Arguments
+///
+/// - `src_ptr`: (`stack[0]`) memory pointer of the byte string.
+/// - `len`: (`stack[1]`) length of the byte string.
+/// - `dest_ptr`: (`stack[2]`) memory pointer to write the encoding to.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     calldatasize 0x00 0x40 calldatacopy
+///     0x00 calldatasize 0x40
+///     RLP_ENCODE_BYTES()
+///     0x00 return
+/// }
+/// ```
+#define macro RLP_ENCODE_BYTES() = takes (3) returns (1) {
+    // takes:               // [src_ptr, len, dest_ptr]
+    dup2                    // [len, src_ptr, len, dest_ptr]
+    0x01                    // [0x01, len, src_ptr, len, dest_ptr]
+    eq                      // [is_single, src_ptr, len, dest_ptr]
+    dup2                    // [src_ptr, is_single, src_ptr, len, dest_ptr]
+    mload                   // [word, is_single, src_ptr, len, dest_ptr]
+    0xf8                    // [0xf8, word, is_single, src_ptr, len, dest_ptr]
+    shr                     // [byte, is_single, src_ptr, len, dest_ptr]
+    0x80                    // [0x80, byte, is_single, src_ptr, len, dest_ptr]
+    gt                      // [is_small, is_single, src_ptr, len, dest_ptr]
+    and                     // [is_byte, src_ptr, len, dest_ptr]
+    single_byte             // [single_byte_dest, is_byte, src_ptr, len, dest_ptr]
+    jumpi                   // [src_ptr, len, dest_ptr]
+        dup3                // [dest_ptr, src_ptr, len, dest_ptr]
+        dup3                // [len, dest_ptr, src_ptr, len, dest_ptr]
+        RLP_ENCODE_LENGTH(0x80) // [prefix_len, src_ptr, len, dest_ptr]
+        dup1                // [prefix_len, prefix_len, src_ptr, len, dest_ptr]
+        dup5                // [dest_ptr, prefix_len, prefix_len, src_ptr, len, dest_ptr]
+        add                 // [payload_ptr, prefix_len, src_ptr, len, dest_ptr]
+        dup4                // [len, payload_ptr, prefix_len, src_ptr, len, dest_ptr]
+        dup4                // [src_ptr, len, payload_ptr, prefix_len, src_ptr, len, dest_ptr]
+        __RLP_COPY()        // [prefix_len, src_ptr, len, dest_ptr]
+        swap3               // [dest_ptr, src_ptr, len, prefix_len]
+        pop                 // [src_ptr, len, prefix_len]
+        pop                 // [len, prefix_len]
+        add                 // [encoded_len]
+        bytes_encoded       // [bytes_encoded_dest, encoded_len]
+        jump                // [encoded_len]
+    single_byte:            // [src_ptr, len, dest_ptr]
+        mload               // [word, len, dest_ptr]
+        0xf8                // [0xf8, word, len, dest_ptr]
+        shr                 // [byte, len, dest_ptr]
+        dup3                // [dest_ptr, byte, len, dest_ptr]
+        mstore8             // [len, dest_ptr]
+        pop                 // [dest_ptr]
+        pop                 // []
+        0x01                // [encoded_len]
+    bytes_encoded:          // [encoded_len]
+}
+
+/// ## RLP Encode Uint
+///
+/// Encodes an integer as its minimal big endian byte string to `dest_ptr`. Zero encodes as the
+/// empty byte string, and values below `0x80` as themselves.
+///
+/// > WARNING: Memory from `dest_ptr` to `dest_ptr + 0x21` will be overwritten.
+///
+/// ### Stack Arguments
+///
+/// - `value`: (`stack[0]`) integer to encode.
+/// - `dest_ptr`: (`stack[1]`) memory pointer to write the encoding to.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x00 0x04 calldataload
+///     RLP_ENCODE_UINT()
+///     0x00 return
+/// }
+/// ```
+#define macro RLP_ENCODE_UINT() = takes (2) returns (1) {
+    // takes:               // [value, dest_ptr]
+    dup1                    // [value, value, dest_ptr]
+    0x80                    // [0x80, value, value, dest_ptr]
+    gt                      // [is_small, value, dest_ptr]
+    dup2                    // [value, is_small, value, dest_ptr]
+    iszero                  // [is_zero, is_small, value, dest_ptr]
+    iszero                  // [not_zero, is_small, value, dest_ptr]
+    and                     // [is_byte, value, dest_ptr]
+    single_byte             // [single_byte_dest, is_byte, value, dest_ptr]
+    jumpi                   // [value, dest_ptr]
+        dup1                // [value, value, dest_ptr]
+        __RLP_BYTE_LENGTH() // [len, value, dest_ptr]
+        dup1                // [len, len, value, dest_ptr]
+        0x80                // [0x80, len, len, value, dest_ptr]
+        add                 // [prefix, len, value, dest_ptr]
+        dup4                // [dest_ptr, prefix, len, value, dest_ptr]
+        mstore8             // [len, value, dest_ptr]
+        swap1               // [value, len, dest_ptr]
+        __RLP_LEFT_ALIGN()  // [value_word, len, dest_ptr]
+        dup3                // [dest_ptr, value_word, len, dest_ptr]
+        0x01                // [0x01, dest_ptr, value_word, len, dest_ptr]
+        add                 // [value_ptr, value_word, len, dest_ptr]
+        mstore              // [len, dest_ptr]
+        0x01                // [0x01, len, dest_ptr]
+        add                 // [encoded_len, dest_ptr]
+        swap1               // [dest_ptr, encoded_len]
+        pop                 // [encoded_len]
+        uint_encoded        // [uint_encoded_dest, encoded_len]
+        jump                // [encoded_len]
+    single_byte:            // [value, dest_ptr]
+        swap1               // [dest_ptr, value]
+        mstore8             // []
+        0x01                // [encoded_len]
+    uint_encoded:           // [encoded_len]
+}
+"#;
+
+const LIST_DEFINITION: &str = r#"
+/// ## RLP Encode List 2
+///
+/// Encodes the list of the two encoded items at `item0_ptr` and `item1_ptr` to `dest_ptr`. The
+/// items may not lie in the memory overwritten by the encoding.
+///
+/// > WARNING: Memory from `dest_ptr` to `dest_ptr + 0x21`, or to the end of the encoding if
+/// > further, will be overwritten.
+///
+/// ### Stack Arguments
+///
+/// - `item0_ptr`: (`stack[0]`) memory pointer of the encoding of the first item.
+/// - `item0_len`: (`stack[1]`) length of the encoding of the first item.
+/// - `item1_ptr`: (`stack[2]`) memory pointer of the encoding of the second item.
+/// - `item1_len`: (`stack[3]`) length of the encoding of the second item.
+/// - `dest_ptr`: (`stack[4]`) memory pointer to write the encoding to.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x00
+///     0xc0 0x24 calldataload RLP_ENCODE_UINT()
+///     0xc0
+///     0x80 0x04 calldataload RLP_ENCODE_UINT()
+///     0x80
+///     RLP_ENCODE_LIST_2()
+///     0x00 return
+/// }
+/// ```
+#define macro RLP_ENCODE_LIST_2() = takes (5) returns (1) {
+    // takes:               // [item0_ptr, item0_len, item1_ptr, item1_len, dest_ptr]
+    dup5                    // [dest_ptr, item0_ptr, item0_len, item1_ptr, item1_len, dest_ptr]
+    dup5                    // [item1_len, dest_ptr, item0_ptr, item0_len, item1_ptr, item1_len, dest_ptr]
+    dup4                    // [item0_len, item1_len, dest_ptr, item0_ptr, item0_len, item1_ptr, item1_len, dest_ptr]
+    add                     // [payload_len, dest_ptr, item0_ptr, item0_len, item1_ptr, item1_len, dest_ptr]
+    RLP_ENCODE_LENGTH(0xc0) // [prefix_len, item0_ptr, item0_len, item1_ptr, item1_len, dest_ptr]
+    dup6                    // [dest_ptr, prefix_len, item0_ptr, item0_len, item1_ptr, item1_len, dest_ptr]
+    add                     // [item0_dest, item0_ptr, item0_len, item1_ptr, item1_len, dest_ptr]
+    dup1                    // [item0_dest, item0_dest, item0_ptr, item0_len, item1_ptr, item1_len, dest_ptr]
+    dup4                    // [item0_len, item0_dest, item0_dest, item0_ptr, item0_len, item1_ptr, item1_len, dest_ptr]
+    dup4                    // [item0_ptr, item0_len, item0_dest, item0_dest, item0_ptr, item0_len, item1_ptr, item1_len, dest_ptr]
+    __RLP_COPY()            // [item0_dest, item0_ptr, item0_len, item1_ptr, item1_len, dest_ptr]
+    swap1                   // [item0_ptr, item0_dest, item0_len, item1_ptr, item1_len, dest_ptr]
+    pop                     // [item0_dest, item0_len, item1_ptr, item1_len, dest_ptr]
+    add                     // [item1_dest, item1_ptr, item1_len, dest_ptr]
+    dup1                    // [item1_dest, item1_dest, item1_ptr, item1_len, dest_ptr]
+    dup4                    // [item1_len, item1_dest, item1_dest, item1_ptr, item1_len, dest_ptr]
+    dup4                    // [item1_ptr, item1_len, item1_dest, item1_dest, item1_ptr, item1_len, dest_ptr]
+    __RLP_COPY()            // [item1_dest, item1_ptr, item1_len, dest_ptr]
+    swap1                   // [item1_ptr, item1_dest, item1_len, dest_ptr]
+    pop                     // [item1_dest, item1_len, dest_ptr]
+    add                     // [end, dest_ptr]
+    sub                     // [encoded_len]
+}
+
+/// ## RLP Byte Length
+///
+/// Computes the number of bytes of the minimal big endian encoding of a value.
+#define macro __RLP_BYTE_LENGTH() = takes (1) returns (1) {
+    // takes:               // [value]
+    0x00                    // [len, value]
+    swap1                   // [value, len]
+    byte_length_loop:       // [value, len]
+        dup1                // [value, value, len]
+        iszero              // [is_done, value, len]
+        byte_length_done    // [byte_length_done_dest, is_done, value, len]
+        jumpi               // [value, len]
+        0x08                // [0x08, value, len]
+        shr                 // [value, len]
+        swap1               // [len, value]
+        0x01                // [0x01, len, value]
+        add                 // [len, value]
+        swap1               // [value, len]
+        byte_length_loop    // [byte_length_loop_dest, value, len]
+        jump                // [value, len]
+    byte_length_done:       // [value, len]
+        pop                 // [len]
+}
+
+/// ## RLP Left Align
+///
+/// Shifts a value of `len` bytes to the most significant bytes of a word.
+#define macro __RLP_LEFT_ALIGN() = takes (2) returns (2) {
+    // takes:               // [value, len]
+    dup2                    // [len, value, len]
+    0x03                    // [0x03, len, value, len]
+    shl                     // [bits, value, len]
+    0x0100                  // [0x0100, bits, value, len]
+    sub                     // [shift, value, len]
+    shl                     // [word, len]
+}
+
+/// ## RLP Copy
+///
+/// Copies `len` bytes from `src_ptr` to `dest_ptr` with the identity precompile.
+#define macro __RLP_COPY() = takes (3) returns (0) {
+    // takes:               // [src_ptr, len, dest_ptr]
+    dup2                    // [len, src_ptr, len, dest_ptr]
+    swap3                   // [dest_ptr, src_ptr, len, len]
+    swap2                   // [len, src_ptr, dest_ptr, len]
+    swap1                   // [src_ptr, len, dest_ptr, len]
+    0x04                    // [identity, src_ptr, len, dest_ptr, len]
+    gas                     // [gas, identity, src_ptr, len, dest_ptr, len]
+    staticcall              // [success]
+    pop                     // []
+}
+"#;
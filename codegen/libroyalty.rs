@@ -0,0 +1,346 @@
+pub fn render() -> String {
+    format!(
+        "{}{}{}{}{}",
+        HEADER, CONSTANT_DEFINITION, AMOUNT_DEFINITION, SETTER_DEFINITION, INFO_DEFINITION,
+    )
+}
+
+const HEADER: &str = r#"
+//  ------------------------------------------------------------------------------------------------
+//! # Royalty Library
+//!
+//! Provides ERC-2981 royalty storage and computation.
+//!
+//! Royalties are stored as a single word, `(fraction << 160) | receiver`, where `fraction` is in
+//! basis points out of `FEE_DENOMINATOR`. A default royalty is stored at a fixed slot and
+//! per-token royalties are stored in a mapping keyed by token id. A per-token royalty with a zero
+//! receiver falls back to the default royalty.
+//!
+//! Royalty amounts round down, matching the OpenZeppelin reference implementation.
+//!
+//! > WARNING: Macros that access per-token royalties overwrite memory from `0x00` to `0x40`.
+//!
+//! ## API
+//!
+//! - `ROYALTY_AMOUNT` - Computes the royalty owed on a sale price.
+//! - `SET_DEFAULT_ROYALTY` - Sets the default royalty.
+//! - `DELETE_DEFAULT_ROYALTY` - Clears the default royalty.
+//! - `SET_TOKEN_ROYALTY` - Sets the royalty of a token.
+//! - `RESET_TOKEN_ROYALTY` - Clears the royalty of a token, falling back to the default.
+//! - `ROYALTY_INFO` - Resolves the royalty receiver and amount of a token sale.
+
+#include "libmapping.huff"
+"#;
+
+const CONSTANT_DEFINITION: &str = r#"
+/// ## Royalty Info Function
+#define function royaltyInfo(uint256,uint256) view returns (address,uint256)
+
+/// ## ERC-2981 Interface Id
+#define constant ERC2981_INTERFACE_ID = 0x2a55205a
+
+/// ## Fee Denominator
+///
+/// Basis points denominator, royalty fractions are out of `10_000`.
+#define constant FEE_DENOMINATOR = 0x2710
+
+/// ## Receiver Mask
+///
+/// Masks the receiver out of a stored royalty.
+#define constant ROYALTY_RECEIVER_MASK = 0xffffffffffffffffffffffffffffffffffffffff
+
+/// ## Invalid Royalty Error
+///
+/// Thrown when a royalty fraction exceeds `FEE_DENOMINATOR`.
+#define error InvalidRoyalty()
+
+/// ## Invalid Royalty Receiver Error
+///
+/// Thrown when a royalty receiver is the zero address.
+#define error InvalidRoyaltyReceiver()
+"#;
+
+const AMOUNT_DEFINITION: &str = r#"
+/// ## Royalty Amount
+///
+/// Computes `sale_price * fraction / FEE_DENOMINATOR`, rounding down.
+///
+/// The sale price is split into `quotient * FEE_DENOMINATOR + remainder` and each part is scaled
+/// separately, so the intermediate product never overflows for any `fraction <= FEE_DENOMINATOR`.
+///
+/// ### Stack Arguments
+///
+/// - `sale_price`: (`stack[0]`) sale price.
+/// - `fraction`: (`stack[1]`) royalty fraction in basis points.
+///
+/// ### Usage
+///
+/// ```huff
+/// #include "libroyalty.huff"
+///
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x01f4 0x24 calldataload
+///     ROYALTY_AMOUNT()
+///     0x00 mstore
+///     0x20 0x00 return
+/// }
+/// ```
+#define macro ROYALTY_AMOUNT() = takes (2) returns (1) {
+    // takes:               // [sale_price, fraction]
+    [FEE_DENOMINATOR]       // [denominator, sale_price, fraction]
+    dup2                    // [sale_price, denominator, sale_price, fraction]
+    dup2                    // [denominator, sale_price, denominator, sale_price, fraction]
+    swap1                   // [sale_price, denominator, denominator, sale_price, fraction]
+    div                     // [quotient, denominator, sale_price, fraction]
+    dup4                    // [fraction, quotient, denominator, sale_price, fraction]
+    mul                     // [quotient_amount, denominator, sale_price, fraction]
+    swap2                   // [sale_price, denominator, quotient_amount, fraction]
+    mod                     // [remainder, quotient_amount, fraction]
+    dup3                    // [fraction, remainder, quotient_amount, fraction]
+    mul                     // [scaled_remainder, quotient_amount, fraction]
+    [FEE_DENOMINATOR]       // [denominator, scaled_remainder, quotient_amount, fraction]
+    swap1                   // [scaled_remainder, denominator, quotient_amount, fraction]
+    div                     // [remainder_amount, quotient_amount, fraction]
+    add                     // [amount, fraction]
+    swap1                   // [fraction, amount]
+    pop                     // [amount]
+}
+"#;
+
+const SETTER_DEFINITION: &str = r#"
+/// ## Pack Royalty
+///
+/// Validates and packs a royalty into its stored representation.
+///
+/// ### Stack Arguments
+///
+/// - `receiver`: (`stack[0]`) royalty receiver.
+/// - `fraction`: (`stack[1]`) royalty fraction in basis points.
+///
+/// ### Panics
+///
+/// - if `fraction` exceeds `FEE_DENOMINATOR`.
+/// - if `receiver` is the zero address.
+#define macro __PACK_ROYALTY() = takes (2) returns (1) {
+    // takes:                   // [receiver, fraction]
+    dup2                        // [fraction, receiver, fraction]
+    [FEE_DENOMINATOR]           // [denominator, fraction, receiver, fraction]
+    lt                          // [exceeds_denominator, receiver, fraction]
+    invalid_royalty             // [invalid_royalty_dest, exceeds_denominator, receiver, fraction]
+    jumpi                       // [receiver, fraction]
+    [ROYALTY_RECEIVER_MASK]     // [mask, receiver, fraction]
+    and                         // [receiver, fraction]
+    dup1                        // [receiver, receiver, fraction]
+    iszero                      // [no_receiver, receiver, fraction]
+    invalid_receiver            // [invalid_receiver_dest, no_receiver, receiver, fraction]
+    jumpi                       // [receiver, fraction]
+    swap1                       // [fraction, receiver]
+    0xa0 shl                    // [shifted_fraction, receiver]
+    or                          // [royalty]
+    valid                       // [valid_dest, royalty]
+    jump                        // [royalty]
+
+    invalid_royalty:            // [receiver, fraction]
+        __ERROR(InvalidRoyalty) // [err, receiver, fraction]
+        0x00                    // [ptr, err, receiver, fraction]
+        mstore                  // [receiver, fraction]
+        0x04                    // [err_len, receiver, fraction]
+        0x00                    // [ptr, err_len, receiver, fraction]
+        revert                  // []
+
+    invalid_receiver:           // [receiver, fraction]
+        __ERROR(InvalidRoyaltyReceiver) // [err, receiver, fraction]
+        0x00                    // [ptr, err, receiver, fraction]
+        mstore                  // [receiver, fraction]
+        0x04                    // [err_len, receiver, fraction]
+        0x00                    // [ptr, err_len, receiver, fraction]
+        revert                  // []
+
+    valid:                      // [royalty]
+}
+
+/// ## Set Default Royalty
+///
+/// Sets the royalty applied to every token without a token royalty.
+///
+/// ### Template Arguments
+///
+/// - `default_slot`: storage slot of the default royalty.
+///
+/// ### Stack Arguments
+///
+/// - `receiver`: (`stack[0]`) royalty receiver.
+/// - `fraction`: (`stack[1]`) royalty fraction in basis points.
+///
+/// ### Panics
+///
+/// - if `fraction` exceeds `FEE_DENOMINATOR`.
+/// - if `receiver` is the zero address.
+///
+/// ### Usage
+///
+/// ```huff
+/// #include "libroyalty.huff"
+///
+/// #define constant DEFAULT_ROYALTY_SLOT = FREE_STORAGE_POINTER()
+///
+/// #define macro CONSTRUCTOR() = takes (0) returns (0) {
+///     0x01f4 caller
+///     SET_DEFAULT_ROYALTY([DEFAULT_ROYALTY_SLOT])
+/// }
+/// ```
+#define macro SET_DEFAULT_ROYALTY(default_slot) = takes (2) returns (0) {
+    // takes:               // [receiver, fraction]
+    __PACK_ROYALTY()        // [royalty]
+    <default_slot>          // [default_slot, royalty]
+    sstore                  // []
+}
+
+/// ## Delete Default Royalty
+///
+/// Clears the default royalty.
+///
+/// ### Template Arguments
+///
+/// - `default_slot`: storage slot of the default royalty.
+///
+/// ### Usage
+///
+/// ```huff
+/// #include "libroyalty.huff"
+///
+/// #define macro MAIN() = takes (0) returns (0) {
+///     DELETE_DEFAULT_ROYALTY([DEFAULT_ROYALTY_SLOT])
+///     stop
+/// }
+/// ```
+#define macro DELETE_DEFAULT_ROYALTY(default_slot) = takes (0) returns (0) {
+    0x00                    // [zero]
+    <default_slot>          // [default_slot, zero]
+    sstore                  // []
+}
+
+/// ## Set Token Royalty
+///
+/// Sets the royalty of a single token, overriding the default royalty.
+///
+/// ### Template Arguments
+///
+/// - `token_slot`: storage slot of the token royalty mapping.
+///
+/// ### Stack Arguments
+///
+/// - `token_id`: (`stack[0]`) token id.
+/// - `receiver`: (`stack[1]`) royalty receiver.
+/// - `fraction`: (`stack[2]`) royalty fraction in basis points.
+///
+/// ### Panics
+///
+/// - if `fraction` exceeds `FEE_DENOMINATOR`.
+/// - if `receiver` is the zero address.
+///
+/// ### Usage
+///
+/// ```huff
+/// #include "libroyalty.huff"
+///
+/// #define constant TOKEN_ROYALTY_SLOT = FREE_STORAGE_POINTER()
+///
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x03e8 caller 0x04 calldataload
+///     SET_TOKEN_ROYALTY([TOKEN_ROYALTY_SLOT])
+///     stop
+/// }
+/// ```
+#define macro SET_TOKEN_ROYALTY(token_slot) = takes (3) returns (0) {
+    // takes:               // [token_id, receiver, fraction]
+    swap2                   // [fraction, receiver, token_id]
+    swap1                   // [receiver, fraction, token_id]
+    __PACK_ROYALTY()        // [royalty, token_id]
+    swap1                   // [token_id, royalty]
+    STORE_MAPPING(<token_slot>) // []
+}
+
+/// ## Reset Token Royalty
+///
+/// Clears the royalty of a single token, falling back to the default royalty.
+///
+/// ### Template Arguments
+///
+/// - `token_slot`: storage slot of the token royalty mapping.
+///
+/// ### Stack Arguments
+///
+/// - `token_id`: (`stack[0]`) token id.
+///
+/// ### Usage
+///
+/// ```huff
+/// #include "libroyalty.huff"
+///
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x04 calldataload
+///     RESET_TOKEN_ROYALTY([TOKEN_ROYALTY_SLOT])
+///     stop
+/// }
+/// ```
+#define macro RESET_TOKEN_ROYALTY(token_slot) = takes (1) returns (0) {
+    // takes:               // [token_id]
+    0x00                    // [zero, token_id]
+    swap1                   // [token_id, zero]
+    STORE_MAPPING(<token_slot>) // []
+}
+"#;
+
+const INFO_DEFINITION: &str = r#"
+/// ## Royalty Info
+///
+/// Resolves the royalty receiver and amount of a token sale, as returned by
+/// `royaltyInfo(uint256,uint256)`.
+///
+/// ### Template Arguments
+///
+/// - `default_slot`: storage slot of the default royalty.
+/// - `token_slot`: storage slot of the token royalty mapping.
+///
+/// ### Stack Arguments
+///
+/// - `token_id`: (`stack[0]`) token id.
+/// - `sale_price`: (`stack[1]`) sale price.
+///
+/// ### Usage
+///
+/// ```huff
+/// #include "libroyalty.huff"
+///
+/// #define macro ROYALTY_INFO_WRAPPER() = takes (0) returns (0) {
+///     0x24 calldataload
+///     0x04 calldataload
+///     ROYALTY_INFO([DEFAULT_ROYALTY_SLOT], [TOKEN_ROYALTY_SLOT])
+///     0x00 mstore
+///     0x20 mstore
+///     0x40 0x00 return
+/// }
+/// ```
+#define macro ROYALTY_INFO(default_slot, token_slot) = takes (2) returns (2) {
+    // takes:                   // [token_id, sale_price]
+    LOAD_MAPPING(<token_slot>)  // [royalty, sale_price]
+    dup1                        // [royalty, royalty, sale_price]
+    [ROYALTY_RECEIVER_MASK]     // [mask, royalty, royalty, sale_price]
+    and                         // [receiver, royalty, sale_price]
+    has_token_royalty           // [has_token_royalty_dest, receiver, royalty, sale_price]
+    jumpi                       // [royalty, sale_price]
+        pop                     // [sale_price]
+        <default_slot>          // [default_slot, sale_price]
+        sload                   // [royalty, sale_price]
+    has_token_royalty:          // [royalty, sale_price]
+    dup1                        // [royalty, royalty, sale_price]
+    0xa0 shr                    // [fraction, royalty, sale_price]
+    swap1                       // [royalty, fraction, sale_price]
+    [ROYALTY_RECEIVER_MASK]     // [mask, royalty, fraction, sale_price]
+    and                         // [receiver, fraction, sale_price]
+    swap2                       // [sale_price, fraction, receiver]
+    ROYALTY_AMOUNT()            // [amount, receiver]
+    swap1                       // [receiver, amount]
+}
+"#;
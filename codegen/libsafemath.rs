@@ -0,0 +1,317 @@
+use crate::emit::{conditions, line};
+use crate::libcast::mask;
+use crate::templates::{self, Context};
+
+/// Renders `libsafemath.huff` with the arithmetic of the bit `sizes`.
+pub fn render(sizes: &[u16]) -> String {
+    let operations = sizes
+        .iter()
+        .map(|size| generate_operations(*size))
+        .collect::<String>();
+
+    format!(
+        "{}{}{}{}",
+        HEADER, ERROR_DEFINITION, SHARED_DEFINITION, operations,
+    )
+}
+
+fn generate_operations(size: u16) -> String {
+    let name = format!("U{}", size);
+    let mask = mask(size);
+
+    let a_range = format!("a <= {}", mask);
+    let b_range = format!("b <= {}", mask);
+    let sum_range = format!("a + b <= {}", mask);
+    let product_range = format!("a * b <= {}", mask);
+
+    let add_conditions = conditions(&[&a_range, &b_range, &sum_range], &["result == a + b"]);
+    let sub_conditions = conditions(&[&a_range, &b_range, "b <= a"], &["result == a - b"]);
+    let mul_conditions = conditions(&[&a_range, &b_range, &product_range], &["result == a * b"]);
+    let div_conditions = conditions(&[&a_range, &b_range, "b != 0"], &["result == a / b"]);
+
+    // one past the largest value of the type
+    let limit = format!("0x1{}", "0".repeat(size as usize / 4));
+
+    let add = if size == 256 {
+        [
+            line("dup2", "[b, a, b]"),
+            line("add", "[sum, b]"),
+            line("swap1", "[b, sum]"),
+            line("dup2", "[sum, b, sum]"),
+            line("lt", "[is_overflow, sum]"),
+            line("iszero", "[is_safe, sum]"),
+        ]
+        .concat()
+    } else {
+        [
+            line("add", "[sum]"),
+            line(&limit, "[limit, sum]"),
+            line("dup2", "[sum, limit, sum]"),
+            line("lt", "[is_safe, sum]"),
+        ]
+        .concat()
+    };
+
+    // products of values of at most 128 bits fit in a word, wider ones are checked by division
+    let mul = if size <= 128 {
+        [
+            line("mul", "[product]"),
+            line(&limit, "[limit, product]"),
+            line("dup2", "[product, limit, product]"),
+            line("lt", "[is_safe, product]"),
+            line("is_safe", "[is_safe_dest, is_safe, product]"),
+            line("jumpi", "[product]"),
+        ]
+        .concat()
+    } else {
+        let fits = if size == 256 {
+            String::new()
+        } else {
+            [
+                line(&limit, "[limit, is_exact, product, a, b]"),
+                line("dup3", "[product, limit, is_exact, product, a, b]"),
+                line("lt", "[fits, is_exact, product, a, b]"),
+                line("and", "[is_safe, product, a, b]"),
+            ]
+            .concat()
+        };
+        [
+            line("dup2", "[b, a, b]"),
+            line("dup2", "[a, b, a, b]"),
+            line("mul", "[product, a, b]"),
+            line("dup2", "[a, product, a, b]"),
+            line("dup2", "[product, a, product, a, b]"),
+            line("div", "[quotient, product, a, b]"),
+            line("dup4", "[b, quotient, product, a, b]"),
+            line("eq", "[is_exact, product, a, b]"),
+            line("dup3", "[a, is_exact, product, a, b]"),
+            line("iszero", "[is_zero, is_exact, product, a, b]"),
+            line("or", "[is_exact, product, a, b]"),
+            fits,
+            line("is_safe", "[is_safe_dest, is_safe, product, a, b]"),
+            line("jumpi", "[product, a, b]"),
+        ]
+        .concat()
+    };
+    let mul_cleanup = if size <= 128 {
+        line("is_safe:", "[product]")
+    } else {
+        [
+            line("is_safe:", "[product, a, b]"),
+            line("swap2", "[b, a, product]"),
+            line("pop", "[a, product]"),
+            line("pop", "[product]"),
+        ]
+        .concat()
+    };
+
+    let context = Context::new()
+        .text("add_conditions", add_conditions)
+        .text("sub_conditions", sub_conditions)
+        .text("mul_conditions", mul_conditions)
+        .text("div_conditions", div_conditions)
+        .text("add_body", add)
+        .text("mul_body", mul)
+        .text("mul_cleanup", mul_cleanup)
+        .text("name", name)
+        .text("size", size.to_string());
+    templates::render(OPERATIONS_TEMPLATE, &context)
+}
+
+const HEADER: &str = r#"
+//  ------------------------------------------------------------------------------------------------
+//! # Safe Math Library
+//!
+//! Provides checked arithmetic on unsigned integers of the sizes of `libcast.huff`.
+//!
+//! Bit sizes supported range from 8 to 256 inclusive and are multiples of 8. Operands are expected
+//! to fit in the type, as after a cast, and results that do not revert always do. For arithmetic
+//! that wraps around instead, see `libwrapping.huff`.
+//!
+//! ## API
+//!
+//! For a given type, `TYPENAME`:
+//!
+//! - `SAFE_ADD_TYPENAME` - Adds two values, reverting on overflow.
+//! - `SAFE_SUB_TYPENAME` - Subtracts two values, reverting on underflow.
+//! - `SAFE_MUL_TYPENAME` - Multiplies two values, reverting on overflow.
+//! - `SAFE_DIV_TYPENAME` - Divides two values, reverting on division by zero.
+//!
+//! - `SAFE_SUB` - Subtracts two values of any size, reverting on underflow.
+//! - `SAFE_DIV` - Divides two values of any size, reverting on division by zero.
+
+#include "libcast.huff"
+"#;
+
+const ERROR_DEFINITION: &str = r#"
+/// ## Underflow Error
+///
+/// Thrown when a subtraction underflows.
+#define error Underflow()
+
+/// ## Division By Zero Error
+///
+/// Thrown when dividing by zero.
+#define error DivByZero()
+"#;
+
+const SHARED_DEFINITION: &str = r#"
+/// ## Safe Subtraction
+///
+/// Subtracts `b` from `a`, for values of any size.
+///
+/// ### Stack Arguments
+///
+/// - `a`: (`stack[0]`) minuend.
+/// - `b`: (`stack[1]`) subtrahend.
+///
+/// ### Panics
+///
+/// - if `b` is greater than `a`.
+///
+/// ### Conditions
+///
+/// @pre b <= a
+/// @post result == a - b
+#define macro SAFE_SUB() = takes (2) returns (1) {
+    // takes:               // [a, b]
+    dup2                    // [b, a, b]
+    dup2                    // [a, b, a, b]
+    lt                      // [is_underflow, a, b]
+    iszero                  // [is_safe, a, b]
+    is_safe                 // [is_safe_dest, is_safe, a, b]
+    jumpi                   // [a, b]
+        __ERROR(Underflow)  // [err]
+        0x00                // [ptr, err]
+        mstore              // []
+        0x04                // [err_len]
+        0x00                // [ptr, err_len]
+        revert              // []
+    is_safe:                // [a, b]
+    sub                     // [difference]
+}
+
+/// ## Safe Division
+///
+/// Divides `a` by `b`, rounding down, for values of any size.
+///
+/// ### Stack Arguments
+///
+/// - `a`: (`stack[0]`) dividend.
+/// - `b`: (`stack[1]`) divisor.
+///
+/// ### Panics
+///
+/// - if `b` is zero.
+///
+/// ### Conditions
+///
+/// @pre b != 0
+/// @post result == a / b
+#define macro SAFE_DIV() = takes (2) returns (1) {
+    // takes:               // [a, b]
+    dup2                    // [b, a, b]
+    is_safe                 // [is_safe_dest, b, a, b]
+    jumpi                   // [a, b]
+        __ERROR(DivByZero)  // [err]
+        0x00                // [ptr, err]
+        mstore              // []
+        0x04                // [err_len]
+        0x00                // [ptr, err_len]
+        revert              // []
+    is_safe:                // [a, b]
+    div                     // [quotient]
+}
+"#;
+
+const OPERATIONS_TEMPLATE: &str = r#"
+/// ## {{name}} Safe Addition
+///
+/// Adds two {{size}} bit values.
+///
+/// ### Stack Arguments
+///
+/// - `a`: (`stack[0]`) left hand side.
+/// - `b`: (`stack[1]`) right hand side.
+///
+/// ### Panics
+///
+/// - if the sum does not fit in the type.
+///
+/// ### Conditions
+///
+{{add_conditions}}///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x24 calldataload TO_{{name}}()
+///     0x04 calldataload TO_{{name}}()
+///     SAFE_ADD_{{name}}()
+///     0x00 mstore
+///     0x20 0x00 return
+/// }
+/// ```
+#define macro SAFE_ADD_{{name}}() = takes (2) returns (1) {
+    // takes:               // [a, b]
+{{add_body}}    is_safe                 // [is_safe_dest, is_safe, sum]
+    jumpi                   // [sum]
+        __ERROR(Overflow)   // [err]
+        0x00                // [ptr, err]
+        mstore              // []
+        0x04                // [err_len]
+        0x00                // [ptr, err_len]
+        revert              // []
+    is_safe:                // [sum]
+}
+
+/// ## {{name}} Safe Subtraction
+///
+/// Subtracts `b` from `a`, both {{size}} bit values.
+///
+/// ### Panics
+///
+/// - if `b` is greater than `a`.
+///
+/// ### Conditions
+///
+{{sub_conditions}}#define macro SAFE_SUB_{{name}}() = takes (2) returns (1) {
+    // takes:               // [a, b]
+    SAFE_SUB()              // [difference]
+}
+
+/// ## {{name}} Safe Multiplication
+///
+/// Multiplies two {{size}} bit values.
+///
+/// ### Panics
+///
+/// - if the product does not fit in the type.
+///
+/// ### Conditions
+///
+{{mul_conditions}}#define macro SAFE_MUL_{{name}}() = takes (2) returns (1) {
+    // takes:               // [a, b]
+{{mul_body}}        __ERROR(Overflow)   // [err]
+        0x00                // [ptr, err]
+        mstore              // []
+        0x04                // [err_len]
+        0x00                // [ptr, err_len]
+        revert              // []
+{{mul_cleanup}}}
+
+/// ## {{name}} Safe Division
+///
+/// Divides `a` by `b`, both {{size}} bit values, rounding down.
+///
+/// ### Panics
+///
+/// - if `b` is zero.
+///
+/// ### Conditions
+///
+{{div_conditions}}#define macro SAFE_DIV_{{name}}() = takes (2) returns (1) {
+    // takes:               // [a, b]
+    SAFE_DIV()              // [quotient]
+}
+"#;
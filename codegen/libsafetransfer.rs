@@ -0,0 +1,277 @@
+pub fn render() -> String {
+    format!(
+        "{}{}{}{}",
+        HEADER, TRANSFER_DEFINITION, APPROVE_DEFINITION, SUCCEEDED_DEFINITION
+    )
+}
+
+const HEADER: &str = r#"
+//  ------------------------------------------------------------------------------------------------
+//! # Safe Transfer Library
+//!
+//! Provides ERC20 transfers and approvals that revert if the token does not report success.
+//!
+//! Tokens disagree on how they report success: most return `true`, some return nothing, and some
+//! return `false` rather than reverting. A call succeeds if it does not revert and either returns
+//! a word equal to `1`, or returns no data from an account with code, as calls to accounts without
+//! code always succeed.
+//!
+//! Calldata is encoded from `0x1c`, overwriting the scratch space and the free memory pointer, and
+//! for `SAFE_TRANSFER_FROM` the zero slot. The free memory pointer is restored and the zero slot
+//! cleared before the macros return.
+//!
+//! ## API
+//!
+//! - `SAFE_TRANSFER` - Transfers tokens from this contract.
+//! - `SAFE_TRANSFER_FROM` - Transfers tokens from an account that approved this contract.
+//! - `SAFE_APPROVE` - Approves an account to spend the tokens of this contract.
+
+/// ## Transfer Failed Error
+///
+/// Thrown when a `transfer` call reverts or does not report success.
+#define error TransferFailed()
+
+/// ## Transfer From Failed Error
+///
+/// Thrown when a `transferFrom` call reverts or does not report success.
+#define error TransferFromFailed()
+
+/// ## Approve Failed Error
+///
+/// Thrown when an `approve` call reverts or does not report success.
+#define error ApproveFailed()
+"#;
+
+const TRANSFER_DEFINITION: &str = r#"
+/// ## Safe Transfer
+///
+/// Transfers `amount` of `token` from this contract to `to`, calling `transfer(address,uint256)`.
+///
+/// ### Stack Arguments
+///
+/// - `token`: (`stack[0]`) address of the token.
+/// - `to`: (`stack[1]`) recipient of the tokens.
+/// - `amount`: (`stack[2]`) amount of tokens to transfer.
+///
+/// ### Panics
+///
+/// - if the call reverts or does not report success.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x24 calldataload
+///     0x04 calldataload
+///     0xda1
+///     SAFE_TRANSFER()
+///     stop
+/// }
+/// ```
+#define macro SAFE_TRANSFER() = takes (3) returns (0) {
+    // takes:               // [token, to, amount]
+    0x40                    // [0x40, token, to, amount]
+    mload                   // [free_ptr, token, to, amount]
+    swap3                   // [amount, token, to, free_ptr]
+    0x40                    // [amount_ptr, amount, token, to, free_ptr]
+    mstore                  // [token, to, free_ptr]
+    swap1                   // [to, token, free_ptr]
+    0x20                    // [to_ptr, to, token, free_ptr]
+    mstore                  // [token, free_ptr]
+    0xa9059cbb              // [selector, token, free_ptr]
+    0x00                    // [ptr, selector, token, free_ptr]
+    mstore                  // [token, free_ptr]
+    0x20                    // [ret_len, token, free_ptr]
+    0x00                    // [ret_ptr, ret_len, token, free_ptr]
+    0x44                    // [args_len, ret_ptr, ret_len, token, free_ptr]
+    0x1c                    // [args_ptr, args_len, ret_ptr, ret_len, token, free_ptr]
+    0x00                    // [value, args_ptr, args_len, ret_ptr, ret_len, token, free_ptr]
+    dup6                    // [token, value, args_ptr, args_len, ret_ptr, ret_len, token, free_ptr]
+    gas                     // [gas, token, value, args_ptr, args_len, ret_ptr, ret_len, token, free_ptr]
+    call                    // [success, token, free_ptr]
+    __TOKEN_CALL_SUCCEEDED() // [succeeded, free_ptr]
+    swap1                   // [free_ptr, succeeded]
+    0x40                    // [0x40, free_ptr, succeeded]
+    mstore                  // [succeeded]
+    succeeded               // [succeeded_dest, succeeded]
+    jumpi                   // []
+        __ERROR(TransferFailed) // [err]
+        0x00                // [ptr, err]
+        mstore              // []
+        0x04                // [err_len]
+        0x00                // [ptr, err_len]
+        revert              // []
+    succeeded:              // []
+}
+
+/// ## Safe Transfer From
+///
+/// Transfers `amount` of `token` from `from` to `to`, calling
+/// `transferFrom(address,address,uint256)`. `from` must have approved this contract.
+///
+/// ### Stack Arguments
+///
+/// - `token`: (`stack[0]`) address of the token.
+/// - `from`: (`stack[1]`) owner of the tokens.
+/// - `to`: (`stack[2]`) recipient of the tokens.
+/// - `amount`: (`stack[3]`) amount of tokens to transfer.
+///
+/// ### Panics
+///
+/// - if the call reverts or does not report success.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x04 calldataload
+///     address
+///     caller
+///     0xda1
+///     SAFE_TRANSFER_FROM()
+///     stop
+/// }
+/// ```
+#define macro SAFE_TRANSFER_FROM() = takes (4) returns (0) {
+    // takes:               // [token, from, to, amount]
+    0x40                    // [0x40, token, from, to, amount]
+    mload                   // [free_ptr, token, from, to, amount]
+    swap4                   // [amount, token, from, to, free_ptr]
+    0x60                    // [amount_ptr, amount, token, from, to, free_ptr]
+    mstore                  // [token, from, to, free_ptr]
+    swap2                   // [to, from, token, free_ptr]
+    0x40                    // [to_ptr, to, from, token, free_ptr]
+    mstore                  // [from, token, free_ptr]
+    0x20                    // [from_ptr, from, token, free_ptr]
+    mstore                  // [token, free_ptr]
+    0x23b872dd              // [selector, token, free_ptr]
+    0x00                    // [ptr, selector, token, free_ptr]
+    mstore                  // [token, free_ptr]
+    0x20                    // [ret_len, token, free_ptr]
+    0x00                    // [ret_ptr, ret_len, token, free_ptr]
+    0x64                    // [args_len, ret_ptr, ret_len, token, free_ptr]
+    0x1c                    // [args_ptr, args_len, ret_ptr, ret_len, token, free_ptr]
+    0x00                    // [value, args_ptr, args_len, ret_ptr, ret_len, token, free_ptr]
+    dup6                    // [token, value, args_ptr, args_len, ret_ptr, ret_len, token, free_ptr]
+    gas                     // [gas, token, value, args_ptr, args_len, ret_ptr, ret_len, token, free_ptr]
+    call                    // [success, token, free_ptr]
+    __TOKEN_CALL_SUCCEEDED() // [succeeded, free_ptr]
+    swap1                   // [free_ptr, succeeded]
+    0x40                    // [0x40, free_ptr, succeeded]
+    mstore                  // [succeeded]
+    0x00                    // [zero, succeeded]
+    0x60                    // [zero_ptr, zero, succeeded]
+    mstore                  // [succeeded]
+    succeeded               // [succeeded_dest, succeeded]
+    jumpi                   // []
+        __ERROR(TransferFromFailed) // [err]
+        0x00                // [ptr, err]
+        mstore              // []
+        0x04                // [err_len]
+        0x00                // [ptr, err_len]
+        revert              // []
+    succeeded:              // []
+}
+"#;
+
+const APPROVE_DEFINITION: &str = r#"
+/// ## Safe Approve
+///
+/// Approves `spender` to spend `amount` of the `token` of this contract, calling
+/// `approve(address,uint256)`.
+///
+/// Some tokens, such as USDT, revert when changing a nonzero allowance to another nonzero
+/// allowance. Approve `0` first for those.
+///
+/// ### Stack Arguments
+///
+/// - `token`: (`stack[0]`) address of the token.
+/// - `spender`: (`stack[1]`) account allowed to spend the tokens.
+/// - `amount`: (`stack[2]`) amount of tokens to allow.
+///
+/// ### Panics
+///
+/// - if the call reverts or does not report success.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x24 calldataload
+///     0x04 calldataload
+///     0xda1
+///     SAFE_APPROVE()
+///     stop
+/// }
+/// ```
+#define macro SAFE_APPROVE() = takes (3) returns (0) {
+    // takes:               // [token, spender, amount]
+    0x40                    // [0x40, token, spender, amount]
+    mload                   // [free_ptr, token, spender, amount]
+    swap3                   // [amount, token, spender, free_ptr]
+    0x40                    // [amount_ptr, amount, token, spender, free_ptr]
+    mstore                  // [token, spender, free_ptr]
+    swap1                   // [spender, token, free_ptr]
+    0x20                    // [spender_ptr, spender, token, free_ptr]
+    mstore                  // [token, free_ptr]
+    0x095ea7b3              // [selector, token, free_ptr]
+    0x00                    // [ptr, selector, token, free_ptr]
+    mstore                  // [token, free_ptr]
+    0x20                    // [ret_len, token, free_ptr]
+    0x00                    // [ret_ptr, ret_len, token, free_ptr]
+    0x44                    // [args_len, ret_ptr, ret_len, token, free_ptr]
+    0x1c                    // [args_ptr, args_len, ret_ptr, ret_len, token, free_ptr]
+    0x00                    // [value, args_ptr, args_len, ret_ptr, ret_len, token, free_ptr]
+    dup6                    // [token, value, args_ptr, args_len, ret_ptr, ret_len, token, free_ptr]
+    gas                     // [gas, token, value, args_ptr, args_len, ret_ptr, ret_len, token, free_ptr]
+    call                    // [success, token, free_ptr]
+    __TOKEN_CALL_SUCCEEDED() // [succeeded, free_ptr]
+    swap1                   // [free_ptr, succeeded]
+    0x40                    // [0x40, free_ptr, succeeded]
+    mstore                  // [succeeded]
+    succeeded               // [succeeded_dest, succeeded]
+    jumpi                   // []
+        __ERROR(ApproveFailed) // [err]
+        0x00                // [ptr, err]
+        mstore              // []
+        0x04                // [err_len]
+        0x00                // [ptr, err_len]
+        revert              // []
+    succeeded:              // []
+}
+"#;
+
+const SUCCEEDED_DEFINITION: &str = r#"
+/// ## Token Call Succeeded
+///
+/// Returns whether a call to `token`, its first returned word copied to `0x00`, succeeded: it did
+/// not revert, and it returned a word equal to `1`, or no data from an account with code.
+#define macro __TOKEN_CALL_SUCCEEDED() = takes (2) returns (1) {
+    // takes:               // [success, token]
+    returndatasize          // [returndatasize, success, token]
+    iszero                  // [is_empty, success, token]
+    empty                   // [empty_dest, is_empty, success, token]
+    jumpi                   // [success, token]
+        0x20                // [0x20, success, token]
+        returndatasize      // [returndatasize, 0x20, success, token]
+        lt                  // [is_short, success, token]
+        iszero              // [has_word, success, token]
+        0x00                // [ptr, has_word, success, token]
+        mload               // [word, has_word, success, token]
+        0x01                // [true, word, has_word, success, token]
+        eq                  // [is_true, has_word, success, token]
+        and                 // [returned_true, success, token]
+        and                 // [succeeded, token]
+        swap1               // [token, succeeded]
+        pop                 // [succeeded]
+        done                // [done_dest, succeeded]
+        jump                // [succeeded]
+    empty:                  // [success, token]
+        swap1               // [token, success]
+        extcodesize         // [code_size, success]
+        iszero              // [has_no_code, success]
+        iszero              // [has_code, success]
+        and                 // [succeeded]
+    done:                   // [succeeded]
+}
+"#;
@@ -0,0 +1,327 @@
+pub fn render() -> String {
+    format!(
+        "{}{}{}{}",
+        HEADER, CONSTANT_DEFINITION, ECRECOVER_DEFINITION, VALIDATION_DEFINITION,
+    )
+}
+
+const HEADER: &str = r#"
+//  ------------------------------------------------------------------------------------------------
+//! # Signature Library
+//!
+//! Provides ECDSA recovery and EIP1271 signature validation.
+//!
+//! Signatures are expected in memory as the 65 byte `r ++ s ++ v` encoding. Signatures with an `s`
+//! value in the upper half of the curve order are rejected to prevent malleability.
+//!
+//! ## API
+//!
+//! - `ETH_SIGNED_MESSAGE_HASH` - Hashes a hash as an EIP-191 signed message.
+//! - `ECRECOVER` - Recovers the signer of a hash, or zero if the signature is invalid.
+//! - `IS_VALID_ERC1271_SIGNATURE` - Checks a signature against a contract's `isValidSignature`.
+//! - `IS_VALID_SIGNATURE_CALL` - Checks a signature for any account, contract or EOA.
+"#;
+
+const CONSTANT_DEFINITION: &str = r#"
+/// ## ERC1271 Interface
+#define function isValidSignature(bytes32,bytes) view returns (bytes4)
+
+/// ## ERC1271 Magic Value
+///
+/// Value returned by `isValidSignature` for valid signatures, equal to its selector.
+#define constant ERC1271_MAGIC_VALUE = 0x1626ba7e
+
+/// ## ECRECOVER Precompile
+///
+/// Address of the `ecrecover` precompile.
+#define constant ECRECOVER_PRECOMPILE = 0x01
+
+/// ## Identity Precompile
+///
+/// Address of the identity precompile, used for copying memory.
+#define constant IDENTITY_PRECOMPILE = 0x04
+
+/// ## Secp256k1 Half Order
+///
+/// Upper bound of the `s` value of a non-malleable signature.
+#define constant SECP256K1_HALF_ORDER = 0x7fffffffffffffffffffffffffffffff5d576e7357a4501ddfe92f46681b20a0
+
+/// ## Eth Signed Message Prefix
+///
+/// EIP-191 prefix of a signed 32 byte message, `"\x19Ethereum Signed Message:\n32"`.
+#define constant ETH_SIGNED_MESSAGE_PREFIX = 0x19457468657265756d205369676e6564204d6573736167653a0a3332
+"#;
+
+const ECRECOVER_DEFINITION: &str = r#"
+/// ## Eth Signed Message Hash
+///
+/// Hashes a hash as an EIP-191 signed message, as signed by `eth_sign` and `personal_sign`.
+///
+/// > WARNING: Memory from `ptr` to `ptr + 0x40` will be overwritten.
+///
+/// ### Template Arguments
+///
+/// - `ptr`: pointer to the memory to encode the message in.
+///
+/// ### Stack Arguments
+///
+/// - `hash`: (`stack[0]`) hash to sign.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x04 calldataload
+///     ETH_SIGNED_MESSAGE_HASH(0x00)
+///     0x00 mstore
+///     0x20 0x00 return
+/// }
+/// ```
+#define macro ETH_SIGNED_MESSAGE_HASH(ptr) = takes (1) returns (1) {
+    // takes:               // [hash]
+    <ptr>                   // [ptr, hash]
+    0x20                    // [0x20, ptr, hash]
+    add                     // [hash_ptr, hash]
+    mstore                  // []
+    [ETH_SIGNED_MESSAGE_PREFIX] // [prefix]
+    <ptr>                   // [ptr, prefix]
+    mstore                  // []
+    0x3c                    // [message_len]
+    <ptr>                   // [ptr, message_len]
+    0x04                    // [0x04, ptr, message_len]
+    add                     // [message_ptr, message_len]
+    sha3                    // [message_hash]
+}
+/// ## ECRECOVER
+///
+/// Recovers the signer of a hash, or zero if the signature is not 65 bytes long, has a high `s`
+/// value, or does not recover.
+///
+/// > WARNING: Memory from `free_mem_ptr` to `free_mem_ptr + 0x80` will be overwritten.
+///
+/// ### Template Arguments
+///
+/// - `free_mem_ptr`: pointer to free memory (requires at least 128 bytes of memory following it).
+///
+/// ### Stack Arguments
+///
+/// - `hash`: (`stack[0]`) signed hash.
+/// - `sig_ptr`: (`stack[1]`) memory pointer of the signature.
+/// - `sig_len`: (`stack[2]`) length of the signature.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x41 0x24 0x80 calldatacopy
+///     0x41 0x80 0x04 calldataload
+///     ECRECOVER(0x00)
+///     0x00 mstore
+///     0x20 0x00 return
+/// }
+/// ```
+#define macro ECRECOVER(free_mem_ptr) = takes (3) returns (1) {
+    // takes:               // [hash, sig_ptr, sig_len]
+    <free_mem_ptr>          // [hash_ptr, hash, sig_ptr, sig_len]
+    mstore                  // [sig_ptr, sig_len]
+    dup1                    // [sig_ptr, sig_ptr, sig_len]
+    0x40                    // [0x40, sig_ptr, sig_ptr, sig_len]
+    add                     // [v_ptr, sig_ptr, sig_len]
+    mload                   // [v_word, sig_ptr, sig_len]
+    0x00                    // [0x00, v_word, sig_ptr, sig_len]
+    byte                    // [v, sig_ptr, sig_len]
+    <free_mem_ptr>          // [free_mem_ptr, v, sig_ptr, sig_len]
+    0x20                    // [0x20, free_mem_ptr, v, sig_ptr, sig_len]
+    add                     // [v_arg_ptr, v, sig_ptr, sig_len]
+    mstore                  // [sig_ptr, sig_len]
+    dup1                    // [sig_ptr, sig_ptr, sig_len]
+    mload                   // [r, sig_ptr, sig_len]
+    <free_mem_ptr>          // [free_mem_ptr, r, sig_ptr, sig_len]
+    0x40                    // [0x40, free_mem_ptr, r, sig_ptr, sig_len]
+    add                     // [r_arg_ptr, r, sig_ptr, sig_len]
+    mstore                  // [sig_ptr, sig_len]
+    0x20                    // [0x20, sig_ptr, sig_len]
+    add                     // [s_ptr, sig_len]
+    mload                   // [s, sig_len]
+    dup1                    // [s, s, sig_len]
+    <free_mem_ptr>          // [free_mem_ptr, s, s, sig_len]
+    0x60                    // [0x60, free_mem_ptr, s, s, sig_len]
+    add                     // [s_arg_ptr, s, s, sig_len]
+    mstore                  // [s, sig_len]
+    [SECP256K1_HALF_ORDER]  // [half_order, s, sig_len]
+    lt                      // [is_high_s, sig_len]
+    iszero                  // [is_low_s, sig_len]
+    swap1                   // [sig_len, is_low_s]
+    0x41                    // [0x41, sig_len, is_low_s]
+    eq                      // [is_65, is_low_s]
+    and                     // [is_well_formed]
+    recover                 // [recover_dest, is_well_formed]
+    jumpi                   // []
+        0x00                // [signer]
+        done                // [done_dest, signer]
+        jump                // [signer]
+    recover:                // []
+        0x20                // [ret_size]
+        <free_mem_ptr>      // [ret_ptr, ret_size]
+        0x80                // [arg_size, ret_ptr, ret_size]
+        <free_mem_ptr>      // [arg_ptr, arg_size, ret_ptr, ret_size]
+        [ECRECOVER_PRECOMPILE] // [ecrecover, arg_ptr, arg_size, ret_ptr, ret_size]
+        gas                 // [gas, ecrecover, arg_ptr, arg_size, ret_ptr, ret_size]
+        staticcall          // [success]
+        pop                 // []
+        returndatasize      // [ret_len]
+        iszero              // [failed]
+        iszero              // [recovered]
+        <free_mem_ptr>      // [ret_ptr, recovered]
+        mload               // [signer, recovered]
+        mul                 // [signer]
+    done:                   // [signer]
+}
+"#;
+
+const VALIDATION_DEFINITION: &str = r#"
+/// ## Is Valid ERC1271 Signature
+///
+/// Checks a signature by calling `isValidSignature(hash, signature)` on the signer, which is valid
+/// if the call succeeds and returns `ERC1271_MAGIC_VALUE`.
+///
+/// > WARNING: Memory from `free_mem_ptr` to `free_mem_ptr + 0x80 + sig_len` will be overwritten.
+///
+/// ### Template Arguments
+///
+/// - `free_mem_ptr`: pointer to free memory (requires at least `0x80 + sig_len` bytes of memory
+///   following it).
+///
+/// ### Stack Arguments
+///
+/// - `signer`: (`stack[0]`) contract to validate the signature with.
+/// - `hash`: (`stack[1]`) signed hash.
+/// - `sig_ptr`: (`stack[2]`) memory pointer of the signature.
+/// - `sig_len`: (`stack[3]`) length of the signature.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x41 0x44 0x80 calldatacopy
+///     0x41 0x80 0x24 calldataload 0x04 calldataload
+///     IS_VALID_ERC1271_SIGNATURE(0x100)
+///     0x00 mstore
+///     0x20 0x00 return
+/// }
+/// ```
+#define macro IS_VALID_ERC1271_SIGNATURE(free_mem_ptr) = takes (4) returns (1) {
+    // takes:               // [signer, hash, sig_ptr, sig_len]
+    [ERC1271_MAGIC_VALUE]   // [selector, signer, hash, sig_ptr, sig_len]
+    <free_mem_ptr>          // [selector_ptr, selector, signer, hash, sig_ptr, sig_len]
+    mstore                  // [signer, hash, sig_ptr, sig_len]
+    swap1                   // [hash, signer, sig_ptr, sig_len]
+    <free_mem_ptr>          // [free_mem_ptr, hash, signer, sig_ptr, sig_len]
+    0x20                    // [0x20, free_mem_ptr, hash, signer, sig_ptr, sig_len]
+    add                     // [hash_ptr, hash, signer, sig_ptr, sig_len]
+    mstore                  // [signer, sig_ptr, sig_len]
+    0x40                    // [sig_offset, signer, sig_ptr, sig_len]
+    <free_mem_ptr>          // [free_mem_ptr, sig_offset, signer, sig_ptr, sig_len]
+    0x40                    // [0x40, free_mem_ptr, sig_offset, signer, sig_ptr, sig_len]
+    add                     // [sig_offset_ptr, sig_offset, signer, sig_ptr, sig_len]
+    mstore                  // [signer, sig_ptr, sig_len]
+    dup3                    // [sig_len, signer, sig_ptr, sig_len]
+    <free_mem_ptr>          // [free_mem_ptr, sig_len, signer, sig_ptr, sig_len]
+    0x60                    // [0x60, free_mem_ptr, sig_len, signer, sig_ptr, sig_len]
+    add                     // [sig_len_ptr, sig_len, signer, sig_ptr, sig_len]
+    mstore                  // [signer, sig_ptr, sig_len]
+
+    dup3                    // [ret_size, signer, sig_ptr, sig_len]
+    <free_mem_ptr>          // [free_mem_ptr, ret_size, signer, sig_ptr, sig_len]
+    0x80                    // [0x80, free_mem_ptr, ret_size, signer, sig_ptr, sig_len]
+    add                     // [ret_ptr, ret_size, signer, sig_ptr, sig_len]
+    dup2                    // [arg_size, ret_ptr, ret_size, signer, sig_ptr, sig_len]
+    dup5                    // [arg_ptr, arg_size, ret_ptr, ret_size, signer, sig_ptr, sig_len]
+    [IDENTITY_PRECOMPILE]   // [identity, arg_ptr, arg_size, ret_ptr, ret_size, signer, sig_ptr, sig_len]
+    gas                     // [gas, identity, arg_ptr, arg_size, ret_ptr, ret_size, signer, sig_ptr, sig_len]
+    staticcall              // [success, signer, sig_ptr, sig_len]
+    pop                     // [signer, sig_ptr, sig_len]
+    swap2                   // [sig_len, sig_ptr, signer]
+    0x64                    // [0x64, sig_len, sig_ptr, signer]
+    add                     // [arg_size, sig_ptr, signer]
+    swap1                   // [sig_ptr, arg_size, signer]
+    pop                     // [arg_size, signer]
+
+    0x20                    // [ret_size, arg_size, signer]
+    <free_mem_ptr>          // [ret_ptr, ret_size, arg_size, signer]
+    dup3                    // [arg_size, ret_ptr, ret_size, arg_size, signer]
+    <free_mem_ptr>          // [free_mem_ptr, arg_size, ret_ptr, ret_size, arg_size, signer]
+    0x1c                    // [0x1c, free_mem_ptr, arg_size, ret_ptr, ret_size, arg_size, signer]
+    add                     // [arg_ptr, arg_size, ret_ptr, ret_size, arg_size, signer]
+    dup6                    // [signer, arg_ptr, arg_size, ret_ptr, ret_size, arg_size, signer]
+    gas                     // [gas, signer, arg_ptr, arg_size, ret_ptr, ret_size, arg_size, signer]
+    staticcall              // [success, arg_size, signer]
+    swap2                   // [signer, arg_size, success]
+    pop                     // [arg_size, success]
+    pop                     // [success]
+    0x1f                    // [0x1f, success]
+    returndatasize          // [returndatasize, 0x1f, success]
+    gt                      // [has_ret, success]
+    and                     // [success]
+    <free_mem_ptr>          // [ret_ptr, success]
+    mload                   // [ret, success]
+    [ERC1271_MAGIC_VALUE]   // [magic, ret, success]
+    0xe0                    // [0xe0, magic, ret, success]
+    shl                     // [magic_word, ret, success]
+    eq                      // [is_magic, success]
+    and                     // [is_valid]
+}
+
+/// ## Is Valid Signature Call
+///
+/// Checks a signature for any account. If the signer has code, the signature is checked with
+/// `IS_VALID_ERC1271_SIGNATURE`, otherwise it is valid if `ECRECOVER` recovers the signer.
+///
+/// > WARNING: Memory from `free_mem_ptr` to `free_mem_ptr + 0x80 + sig_len` will be overwritten.
+///
+/// ### Template Arguments
+///
+/// - `free_mem_ptr`: pointer to free memory (requires at least `0x80 + sig_len` bytes of memory
+///   following it).
+///
+/// ### Stack Arguments
+///
+/// - `signer`: (`stack[0]`) account the signature is expected from.
+/// - `hash`: (`stack[1]`) signed hash.
+/// - `sig_ptr`: (`stack[2]`) memory pointer of the signature.
+/// - `sig_len`: (`stack[3]`) length of the signature.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x41 0x44 0x80 calldatacopy
+///     0x41 0x80 0x24 calldataload 0x04 calldataload
+///     IS_VALID_SIGNATURE_CALL(0x100)
+///     0x00 mstore
+///     0x20 0x00 return
+/// }
+/// ```
+#define macro IS_VALID_SIGNATURE_CALL(free_mem_ptr) = takes (4) returns (1) {
+    // takes:               // [signer, hash, sig_ptr, sig_len]
+    dup1                    // [signer, signer, hash, sig_ptr, sig_len]
+    extcodesize             // [code_size, signer, hash, sig_ptr, sig_len]
+    is_contract             // [is_contract_dest, code_size, signer, hash, sig_ptr, sig_len]
+    jumpi                   // [signer, hash, sig_ptr, sig_len]
+        swap3               // [sig_len, hash, sig_ptr, signer]
+        swap2               // [sig_ptr, hash, sig_len, signer]
+        swap1               // [hash, sig_ptr, sig_len, signer]
+        ECRECOVER(<free_mem_ptr>) // [recovered, signer]
+        dup1                // [recovered, recovered, signer]
+        iszero              // [is_zero, recovered, signer]
+        iszero              // [is_nonzero, recovered, signer]
+        swap2               // [signer, recovered, is_nonzero]
+        eq                  // [is_signer, is_nonzero]
+        and                 // [is_valid]
+        validated           // [validated_dest, is_valid]
+        jump                // [is_valid]
+    is_contract:            // [signer, hash, sig_ptr, sig_len]
+        IS_VALID_ERC1271_SIGNATURE(<free_mem_ptr>) // [is_valid]
+    validated:              // [is_valid]
+}
+"#;
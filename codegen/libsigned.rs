@@ -0,0 +1,116 @@
+use crate::emit::{conditions, line};
+use crate::libcast::OverflowRevert;
+use crate::templates::{self, Context};
+
+/// Renders `libsigned.huff` with the casts of the bit `sizes`, reverting on overflow as `revert`.
+pub fn render(sizes: &[u16], revert: OverflowRevert) -> String {
+    let casts = sizes
+        .iter()
+        .map(|size| generate_cast(*size, revert))
+        .collect::<String>();
+
+    format!("{}{}", HEADER, casts)
+}
+
+fn generate_cast(size: u16, revert: OverflowRevert) -> String {
+    let name = format!("I{}", size);
+    let shift = 256 - size;
+
+    let range = format!(
+        "int256(value) >= -2 ** {} && int256(value) < 2 ** {}",
+        size - 1,
+        size - 1
+    );
+    let cast_conditions = conditions(&[&range], &["result == value"]);
+    let unsafe_conditions = if size == 256 {
+        conditions(&[], &["result == value"])
+    } else {
+        conditions(
+            &[],
+            &[&format!(
+                "int256(result) == int256(value << {}) >> {}",
+                shift, shift
+            )],
+        )
+    };
+
+    let byte = format!("{:#04x}", size / 8 - 1);
+    let context = Context::new()
+        .text("cast_conditions", cast_conditions)
+        .text("unsafe_conditions", unsafe_conditions)
+        // every word is an int256, so the widest casts are no-ops
+        .flag("extends", size < 256)
+        .text("byte_line", line(&byte, "[byte, value, value]"))
+        .text("extend_line", line(&byte, "[byte, value]"))
+        .text("revert", revert.body())
+        .text("name", name)
+        .text("size", size.to_string());
+    templates::render(CAST_TEMPLATE, &context)
+}
+
+const HEADER: &str = r#"
+//  ------------------------------------------------------------------------------------------------
+//! # Signed Casting Library
+//!
+//! Provides macros for casting signed values.
+//!
+//! Bit sizes supported range from 8 to 256 inclusive and are multiples of 8. Signed values are held
+//! on the stack sign extended to a full word, as Solidity's ABI encodes `intN`, so a value fits in
+//! a type when sign extending it from the width of the type leaves it unchanged.
+//!
+//! Items prefixed with `UNSAFE_` will not revert on overflow.
+//!
+//! ## API
+//!
+//! For a given type, `TYPENAME`:
+//!
+//! - `TO_TYPENAME` - Downcasts a signed value to a smaller type.
+//! - `UNSAFE_TO_TYPENAME` - Downcasts a signed value to a smaller type, truncating it.
+
+#include "libcast.huff"
+"#;
+
+const CAST_TEMPLATE: &str = r#"
+/// ## {{name}} Cast
+///
+/// Downcasts a signed value to the {{size}} bit signed type.
+///
+/// ### Panics
+///
+/// - if the value does not fit in the type.
+///
+/// ### Conditions
+///
+{{cast_conditions}}///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x04 calldataload
+///     TO_{{name}}()
+///     0x00 sstore
+/// }
+/// ```
+#define macro TO_{{name}}() = takes (1) returns (1) {
+    // takes:               // [value]
+{{#if extends}}    dup1                    // [value, value]
+{{byte_line}}    signextend              // [extended, value]
+    dup2                    // [value, extended, value]
+    eq                      // [is_safe, value]
+    is_safe                 // [is_safe_dest, is_safe, value]
+    jumpi                   // [value]
+{{revert}}    is_safe:                // [value]
+{{/if}}}
+
+/// ## Unsafe {{name}} Cast
+///
+/// Downcasts a signed value to the {{size}} bit signed type, discarding the bits above its width
+/// and sign extending the rest.
+///
+/// ### Conditions
+///
+{{unsafe_conditions}}#define macro UNSAFE_TO_{{name}}() = takes (1) returns (1) {
+    // takes:               // [value]
+{{#if extends}}{{extend_line}}    signextend              // [extended]
+{{/if}}}
+"#;
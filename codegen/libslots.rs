@@ -0,0 +1,63 @@
+use crate::slots::{constant, Derivation};
+
+pub fn render() -> String {
+    let slots = [
+        (
+            "EIP1967_IMPLEMENTATION_SLOT",
+            "Implementation Slot",
+            "eip1967.proxy.implementation",
+        ),
+        ("EIP1967_ADMIN_SLOT", "Admin Slot", "eip1967.proxy.admin"),
+        ("EIP1967_BEACON_SLOT", "Beacon Slot", "eip1967.proxy.beacon"),
+    ]
+    .iter()
+    .map(|(name, title, id)| constant(name, title, Derivation::Eip1967, id))
+    .collect::<String>();
+
+    format!("{}{}", HEADER, slots)
+}
+
+const HEADER: &str = r#"
+//  ------------------------------------------------------------------------------------------------
+//! # Slots Library
+//!
+//! Provides the EIP-1967 proxy storage slots, computed at generation time.
+//!
+//! Each slot is `keccak256(id) - 1`, so it has no known preimage and can not collide with a slot
+//! of a Solidity mapping or dynamic array.
+//!
+//! ## API
+//!
+//! - `EIP1967_IMPLEMENTATION_SLOT` - Slot of the implementation address.
+//! - `EIP1967_ADMIN_SLOT` - Slot of the admin address.
+//! - `EIP1967_BEACON_SLOT` - Slot of the beacon address.
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slots_of_eip_1967() {
+        // the slots published in EIP-1967
+        let rendered = render();
+        let slots = [
+            (
+                "EIP1967_IMPLEMENTATION_SLOT",
+                "0x360894a13ba1a3210667c828492db98dca3e2076cc3735a920a3ca505d382bbc",
+            ),
+            (
+                "EIP1967_ADMIN_SLOT",
+                "0xb53127684a568b3173ae13b9f8a6016e243e63b6e8ee1178d6a717850b5d6103",
+            ),
+            (
+                "EIP1967_BEACON_SLOT",
+                "0xa3f0ad74e5423aebfd80d3ef4346578335a9a72aeaee59ff6cb3582b35133d50",
+            ),
+        ];
+        for (name, slot) in slots {
+            let definition = format!("#define constant {} = {}\n", name, slot);
+            assert!(rendered.contains(&definition));
+        }
+    }
+}
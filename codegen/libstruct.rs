@@ -0,0 +1,342 @@
+use std::collections::HashSet;
+
+use crate::config::{identifier, invalid, Table, Value};
+use crate::emit::line;
+use crate::libcast::mask;
+use crate::templates::{self, Context};
+
+struct Field {
+    name: String,
+    bits: u16,
+    offset: u16,
+}
+
+struct Struct {
+    name: String,
+    slot: String,
+    fields: Vec<Field>,
+    /// Prefix of the accessor macro names, before the field name.
+    prefix: String,
+    /// Whether accessor macro names upper case the field name.
+    upper: bool,
+}
+
+impl Struct {
+    fn macro_name(&self, field: &Field) -> String {
+        if self.upper {
+            format!("{}{}", self.prefix, field.name.to_uppercase())
+        } else {
+            format!("{}{}", self.prefix, field.name)
+        }
+    }
+}
+
+/// Renders `libstructs.huff` from the `[[struct]]` entries of the config, if any.
+pub fn render(config: &Table) -> std::io::Result<Option<String>> {
+    let structs = parse_structs(config)?;
+
+    if structs.is_empty() {
+        return Ok(None);
+    }
+
+    let libstructs = format!(
+        "{}{}",
+        HEADER,
+        structs.iter().map(generate_struct).collect::<String>(),
+    );
+
+    Ok(Some(libstructs))
+}
+
+/// Renders the accessors of a standalone packed slot layout, as read by `huffgen pack`.
+///
+/// The layout declares a `slot` and `fields` as a `[[struct]]` entry does. Its `name` is optional
+/// and, when given, prefixes the accessors, which otherwise take the upper cased field name alone,
+/// e.g. `GET_OWNER`.
+pub fn render_layout(layout: &Table) -> std::io::Result<String> {
+    let named = layout.contains_key("name");
+    let name = if named {
+        identifier(layout, "name", "layout")?
+    } else {
+        "LAYOUT".to_string()
+    };
+
+    let mut layout = parse_struct(layout, name, "layout")?;
+    if !named {
+        layout.prefix.clear();
+    }
+    layout.upper = true;
+
+    Ok(format!("{}{}", LAYOUT_HEADER, generate_struct(&layout)))
+}
+
+fn parse_structs(config: &Table) -> std::io::Result<Vec<Struct>> {
+    let entries = match config.get("struct") {
+        None => return Ok(Vec::new()),
+        Some(Value::Array(entries)) => entries,
+        Some(_) => return Err(invalid("`struct` must be an array of tables")),
+    };
+
+    let mut names = HashSet::new();
+
+    entries
+        .iter()
+        .map(|entry| {
+            let entry = entry
+                .as_table()
+                .ok_or_else(|| invalid("`struct` must be an array of tables"))?;
+            let name = identifier(entry, "name", "struct")?;
+
+            if !names.insert(name.clone()) {
+                return Err(invalid(format!("duplicate struct `{}`", name)));
+            }
+
+            parse_struct(entry, name, "struct")
+        })
+        .collect()
+}
+
+/// Parses the `slot` and `fields` of a struct, `kind` naming it in errors.
+fn parse_struct(entry: &Table, name: String, kind: &str) -> std::io::Result<Struct> {
+    let slot = match entry.get("slot") {
+        Some(Value::Integer(slot)) => format!("0x{:02x}", slot),
+        Some(Value::String(slot)) => slot.clone(),
+        _ => return Err(invalid(format!("{} `{}` requires a `slot`", kind, name))),
+    };
+
+    let mut offset = 0u16;
+    let mut field_names = HashSet::new();
+    let fields = entry
+        .get("fields")
+        .and_then(Value::as_array)
+        .ok_or_else(|| invalid(format!("{} `{}` requires `fields`", kind, name)))?
+        .iter()
+        .map(|field| {
+            let field = field
+                .as_table()
+                .ok_or_else(|| invalid(format!("fields of {} `{}` must be tables", kind, name)))?;
+            let field_name = identifier(field, "name", &format!("field of `{}`", name))?;
+            let bits = field
+                .get("bits")
+                .and_then(Value::as_integer)
+                .filter(|bits| (1..=256).contains(bits))
+                .ok_or_else(|| {
+                    invalid(format!(
+                        "field `{}.{}` requires `bits` between 1 and 256",
+                        name, field_name
+                    ))
+                })? as u16;
+
+            if !field_names.insert(field_name.clone()) {
+                return Err(invalid(format!(
+                    "duplicate field `{}.{}`",
+                    name, field_name
+                )));
+            }
+
+            if offset + bits > 256 {
+                return Err(invalid(format!(
+                    "fields of {} `{}` exceed 256 bits",
+                    kind, name
+                )));
+            }
+
+            let field = Field {
+                name: field_name,
+                bits,
+                offset,
+            };
+            offset += bits;
+            Ok(field)
+        })
+        .collect::<std::io::Result<Vec<Field>>>()?;
+
+    Ok(Struct {
+        prefix: format!("{}_", name),
+        name,
+        slot,
+        fields,
+        upper: false,
+    })
+}
+
+/// Builds the hex literal of the complement of a `bits` wide field at bit `offset`.
+fn clear_mask(offset: u16, bits: u16) -> String {
+    let mut word = [0xffu8; 32];
+
+    for bit in offset..offset + bits {
+        word[31 - bit as usize / 8] &= !(1 << (bit % 8));
+    }
+
+    let hex = word
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect::<String>();
+
+    format!("0x{}", hex.trim_start_matches('0'))
+}
+
+fn generate_struct(s: &Struct) -> String {
+    let layout = s
+        .fields
+        .iter()
+        .map(|field| {
+            Context::new()
+                .text("name", field.name.as_str())
+                .text("bits", field.bits.to_string())
+                .text("offset", field.offset.to_string())
+        })
+        .collect();
+
+    let fields = s
+        .fields
+        .iter()
+        .map(|field| generate_field(s, field))
+        .collect::<String>();
+
+    let context = Context::new()
+        .text("name", s.name.as_str())
+        .text("slot", s.slot.as_str())
+        .list("layout", layout)
+        .text("fields", fields);
+    templates::render(STRUCT_TEMPLATE, &context)
+}
+
+fn generate_field(s: &Struct, field: &Field) -> String {
+    let slot = format!("[{}_SLOT]", s.name);
+    let is_top = field.offset + field.bits == 256;
+
+    let mut getter = line(&slot, "[slot]");
+    let mut value = "word";
+    getter.push_str(&line("sload", "[word]"));
+    if field.offset > 0 {
+        getter.push_str(&line(&format!("0x{:02x}", field.offset), "[offset, word]"));
+        getter.push_str(&line("shr", "[shifted]"));
+        value = "shifted";
+    }
+    if !is_top {
+        getter.push_str(&line(&mask(field.bits), &format!("[mask, {}]", value)));
+        getter.push_str(&line("and", &format!("[{}]", field.name)));
+    }
+
+    let mut setter = line("// takes:", &format!("[{}]", field.name));
+    let mut value = field.name.as_str();
+    if field.bits == 256 {
+        setter.push_str(&line(&slot, &format!("[slot, {}]", value)));
+        setter.push_str(&line("sstore", "[]"));
+    } else {
+        if !is_top {
+            setter.push_str(&line(&mask(field.bits), &format!("[mask, {}]", value)));
+            setter.push_str(&line("and", "[masked]"));
+            value = "masked";
+        }
+        if field.offset > 0 {
+            setter.push_str(&line(
+                &format!("0x{:02x}", field.offset),
+                &format!("[offset, {}]", value),
+            ));
+            setter.push_str(&line("shl", "[shifted]"));
+            value = "shifted";
+        }
+        setter.push_str(&line(&slot, &format!("[slot, {}]", value)));
+        setter.push_str(&line("sload", &format!("[word, {}]", value)));
+        setter.push_str(&line(
+            &clear_mask(field.offset, field.bits),
+            &format!("[clear_mask, word, {}]", value),
+        ));
+        setter.push_str(&line("and", &format!("[cleared, {}]", value)));
+        setter.push_str(&line("or", "[new_word]"));
+        setter.push_str(&line(&slot, "[slot, new_word]"));
+        setter.push_str(&line("sstore", "[]"));
+    }
+
+    let context = Context::new()
+        .text("macro_name", s.macro_name(field))
+        .text("struct_name", s.name.as_str())
+        .text("name", field.name.as_str())
+        .text("bits", field.bits.to_string())
+        .text("offset", field.offset.to_string())
+        .text("getter_body", getter)
+        .text("setter_body", setter);
+    templates::render(FIELD_TEMPLATE, &context)
+}
+
+const HEADER: &str = r#"
+//  ------------------------------------------------------------------------------------------------
+//! # Structs Library
+//!
+//! Provides getters and setters for the packed storage structs declared in `libhuff.toml`.
+//!
+//! Fields are packed from the least significant bit of the slot upwards in declaration order,
+//! matching Solidity's storage packing.
+//!
+//! ## API
+//!
+//! For a given struct, `STRUCT`, and field, `field`:
+//!
+//! - `STRUCT_SLOT` - Storage slot of the struct.
+//! - `GET_STRUCT_field` - Loads a field.
+//! - `SET_STRUCT_field` - Stores a field, leaving every other field untouched.
+"#;
+
+const LAYOUT_HEADER: &str = r#"
+//  ------------------------------------------------------------------------------------------------
+//! # Packed Layout
+//!
+//! Provides getters and setters for the fields of a packed storage slot, generated by
+//! `huffgen pack`.
+//!
+//! Fields are packed from the least significant bit of the slot upwards in declaration order,
+//! matching Solidity's storage packing.
+"#;
+
+const STRUCT_TEMPLATE: &str = r#"
+/// ## {{name}} Slot
+///
+/// Storage slot of the packed `{{name}}` struct.
+///
+/// | Field | Bits | Offset |
+/// | ----- | ---- | ------ |
+{{#each layout}}/// | `{{name}}` | {{bits}} | {{offset}} |
+{{/each}}#define constant {{name}}_SLOT = {{slot}}
+{{fields}}"#;
+
+const FIELD_TEMPLATE: &str = r#"
+/// ## Get {{struct_name}} {{name}}
+///
+/// Loads the {{bits}} bit `{{name}}` field at bit offset {{offset}} of `{{struct_name}}`.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     GET_{{macro_name}}()
+///     0x00 mstore
+///     0x20 0x00 return
+/// }
+/// ```
+#define macro GET_{{macro_name}}() = takes (0) returns (1) {
+{{getter_body}}}
+
+/// ## Set {{struct_name}} {{name}}
+///
+/// Stores the {{bits}} bit `{{name}}` field at bit offset {{offset}} of `{{struct_name}}`.
+///
+/// Bits of the value above the field width are discarded, so other fields are never overwritten.
+///
+/// ### Stack Arguments
+///
+/// - `{{name}}`: (`stack[0]`) value to store.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x04 calldataload
+///     SET_{{macro_name}}()
+///     stop
+/// }
+/// ```
+#define macro SET_{{macro_name}}() = takes (1) returns (0) {
+{{setter_body}}}
+"#;
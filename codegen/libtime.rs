@@ -0,0 +1,275 @@
+pub fn render() -> String {
+    format!(
+        "{}{}{}{}",
+        HEADER, EXPIRY_DEFINITION, START_DEFINITION, WINDOW_DEFINITION
+    )
+}
+
+const HEADER: &str = r#"
+//  ------------------------------------------------------------------------------------------------
+//! # Time Library
+//!
+//! Provides deadline and time window checks against the block timestamp.
+//!
+//! Each check states which of its boundaries are inclusive. `REQUIRE_NOT_EXPIRED` accepts the
+//! deadline itself, as `require(block.timestamp <= deadline)` does, and `IN_WINDOW` is half open,
+//! so consecutive windows sharing a boundary never overlap.
+//!
+//! ## API
+//!
+//! - `REQUIRE_NOT_EXPIRED` - Reverts after a deadline, inclusive of the deadline.
+//! - `REQUIRE_NOT_EXPIRED_EXCLUSIVE` - Reverts at or after a deadline.
+//! - `REQUIRE_AFTER` - Reverts at or before a start.
+//! - `REQUIRE_AFTER_INCLUSIVE` - Reverts before a start, inclusive of the start.
+//! - `IN_WINDOW` - Returns whether the timestamp is in a window, inclusive of its start only.
+//! - `IN_WINDOW_INCLUSIVE` - Returns whether the timestamp is in a window, inclusive of both ends.
+
+/// ## Expired Error
+///
+/// Thrown when a deadline has passed.
+#define error Expired()
+
+/// ## Too Early Error
+///
+/// Thrown when a start has not been reached.
+#define error TooEarly()
+"#;
+
+const EXPIRY_DEFINITION: &str = r#"
+/// ## Require Not Expired
+///
+/// Reverts if the timestamp is after `deadline`. The deadline itself is valid.
+///
+/// ### Stack Arguments
+///
+/// - `deadline`: (`stack[0]`) last valid timestamp.
+///
+/// ### Panics
+///
+/// - if `timestamp > deadline`.
+///
+/// ### Conditions
+///
+/// @pre timestamp <= deadline
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x04 calldataload
+///     REQUIRE_NOT_EXPIRED()
+///     stop
+/// }
+/// ```
+#define macro REQUIRE_NOT_EXPIRED() = takes (1) returns (0) {
+    // takes:               // [deadline]
+    timestamp               // [timestamp, deadline]
+    gt                      // [is_expired]
+    iszero                  // [not_expired]
+    not_expired             // [not_expired_dest, not_expired]
+    jumpi                   // []
+        __ERROR(Expired)    // [err]
+        0x00                // [ptr, err]
+        mstore              // []
+        0x04                // [err_len]
+        0x00                // [ptr, err_len]
+        revert              // []
+    not_expired:            // []
+}
+
+/// ## Require Not Expired Exclusive
+///
+/// Reverts if the timestamp is at or after `deadline`. The deadline itself is expired.
+///
+/// ### Stack Arguments
+///
+/// - `deadline`: (`stack[0]`) first expired timestamp.
+///
+/// ### Panics
+///
+/// - if `timestamp >= deadline`.
+///
+/// ### Conditions
+///
+/// @pre timestamp < deadline
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x04 calldataload
+///     REQUIRE_NOT_EXPIRED_EXCLUSIVE()
+///     stop
+/// }
+/// ```
+#define macro REQUIRE_NOT_EXPIRED_EXCLUSIVE() = takes (1) returns (0) {
+    // takes:               // [deadline]
+    timestamp               // [timestamp, deadline]
+    lt                      // [not_expired]
+    not_expired             // [not_expired_dest, not_expired]
+    jumpi                   // []
+        __ERROR(Expired)    // [err]
+        0x00                // [ptr, err]
+        mstore              // []
+        0x04                // [err_len]
+        0x00                // [ptr, err_len]
+        revert              // []
+    not_expired:            // []
+}
+"#;
+
+const START_DEFINITION: &str = r#"
+/// ## Require After
+///
+/// Reverts if the timestamp is at or before `start`. The start itself is too early.
+///
+/// ### Stack Arguments
+///
+/// - `start`: (`stack[0]`) last timestamp that is too early.
+///
+/// ### Panics
+///
+/// - if `timestamp <= start`.
+///
+/// ### Conditions
+///
+/// @pre timestamp > start
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x04 calldataload
+///     REQUIRE_AFTER()
+///     stop
+/// }
+/// ```
+#define macro REQUIRE_AFTER() = takes (1) returns (0) {
+    // takes:               // [start]
+    timestamp               // [timestamp, start]
+    gt                      // [is_after]
+    is_after                // [is_after_dest, is_after]
+    jumpi                   // []
+        __ERROR(TooEarly)   // [err]
+        0x00                // [ptr, err]
+        mstore              // []
+        0x04                // [err_len]
+        0x00                // [ptr, err_len]
+        revert              // []
+    is_after:               // []
+}
+
+/// ## Require After Inclusive
+///
+/// Reverts if the timestamp is before `start`. The start itself is valid.
+///
+/// ### Stack Arguments
+///
+/// - `start`: (`stack[0]`) first valid timestamp.
+///
+/// ### Panics
+///
+/// - if `timestamp < start`.
+///
+/// ### Conditions
+///
+/// @pre timestamp >= start
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x04 calldataload
+///     REQUIRE_AFTER_INCLUSIVE()
+///     stop
+/// }
+/// ```
+#define macro REQUIRE_AFTER_INCLUSIVE() = takes (1) returns (0) {
+    // takes:               // [start]
+    timestamp               // [timestamp, start]
+    lt                      // [is_before]
+    iszero                  // [is_after]
+    is_after                // [is_after_dest, is_after]
+    jumpi                   // []
+        __ERROR(TooEarly)   // [err]
+        0x00                // [ptr, err]
+        mstore              // []
+        0x04                // [err_len]
+        0x00                // [ptr, err_len]
+        revert              // []
+    is_after:               // []
+}
+"#;
+
+const WINDOW_DEFINITION: &str = r#"
+/// ## In Window
+///
+/// Returns whether `start <= timestamp < end`. The start is in the window, the end is not.
+///
+/// ### Stack Arguments
+///
+/// - `start`: (`stack[0]`) first timestamp of the window.
+/// - `end`: (`stack[1]`) first timestamp after the window.
+///
+/// ### Conditions
+///
+/// @post result == (start <= timestamp && timestamp < end)
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x24 calldataload
+///     0x04 calldataload
+///     IN_WINDOW()
+///     0x00 mstore
+///     0x20 0x00 return
+/// }
+/// ```
+#define macro IN_WINDOW() = takes (2) returns (1) {
+    // takes:               // [start, end]
+    timestamp               // [timestamp, start, end]
+    lt                      // [is_before, end]
+    iszero                  // [has_started, end]
+    swap1                   // [end, has_started]
+    timestamp               // [timestamp, end, has_started]
+    lt                      // [not_ended, has_started]
+    and                     // [in_window]
+}
+
+/// ## In Window Inclusive
+///
+/// Returns whether `start <= timestamp <= end`. Both the start and the end are in the window.
+///
+/// ### Stack Arguments
+///
+/// - `start`: (`stack[0]`) first timestamp of the window.
+/// - `end`: (`stack[1]`) last timestamp of the window.
+///
+/// ### Conditions
+///
+/// @post result == (start <= timestamp && timestamp <= end)
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x24 calldataload
+///     0x04 calldataload
+///     IN_WINDOW_INCLUSIVE()
+///     0x00 mstore
+///     0x20 0x00 return
+/// }
+/// ```
+#define macro IN_WINDOW_INCLUSIVE() = takes (2) returns (1) {
+    // takes:               // [start, end]
+    timestamp               // [timestamp, start, end]
+    lt                      // [is_before, end]
+    iszero                  // [has_started, end]
+    swap1                   // [end, has_started]
+    timestamp               // [timestamp, end, has_started]
+    gt                      // [is_after, has_started]
+    iszero                  // [not_ended, has_started]
+    and                     // [in_window]
+}
+"#;
@@ -0,0 +1,375 @@
+use crate::config::{invalid, Table, Value};
+use crate::templates::{self, Context};
+
+/// Minimum delay in seconds when the config does not set `timelock.min_delay`, two days.
+const DEFAULT_MIN_DELAY: u128 = 2 * 24 * 60 * 60;
+
+/// Maximum delay in seconds when the config does not set `timelock.max_delay`, thirty days.
+const DEFAULT_MAX_DELAY: u128 = 30 * 24 * 60 * 60;
+
+pub fn render(config: &Table) -> std::io::Result<String> {
+    let (min_delay, max_delay) = parse_timelock(config)?;
+
+    let context = Context::new()
+        .text("min_delay", format!("0x{:x}", min_delay))
+        .text("max_delay", format!("0x{:x}", max_delay));
+    let constants = templates::render(CONSTANT_DEFINITION, &context);
+
+    let libtimelock = format!(
+        "{}{}{}{}",
+        HEADER, constants, QUEUE_DEFINITION, EXECUTE_DEFINITION,
+    );
+
+    Ok(libtimelock)
+}
+
+fn parse_timelock(config: &Table) -> std::io::Result<(u128, u128)> {
+    let timelock = match config.get("timelock") {
+        None => return Ok((DEFAULT_MIN_DELAY, DEFAULT_MAX_DELAY)),
+        Some(Value::Table(timelock)) => timelock,
+        Some(_) => return Err(invalid("`timelock` must be a table")),
+    };
+
+    let read = |key: &str, default: u128| match timelock.get(key) {
+        None => Ok(default),
+        Some(value) => value
+            .as_integer()
+            .filter(|delay| *delay <= u64::MAX as u128)
+            .ok_or_else(|| invalid(format!("`timelock.{}` must be a 64 bit integer", key))),
+    };
+
+    let min_delay = read("min_delay", DEFAULT_MIN_DELAY)?;
+    let max_delay = read("max_delay", DEFAULT_MAX_DELAY)?;
+
+    if min_delay > max_delay {
+        return Err(invalid(
+            "`timelock.min_delay` must not exceed `timelock.max_delay`",
+        ));
+    }
+
+    Ok((min_delay, max_delay))
+}
+
+const HEADER: &str = r#"
+//  ------------------------------------------------------------------------------------------------
+//! # Timelock Library
+//!
+//! Provides macros for queueing operations and executing them once their delay has passed.
+//!
+//! Operations are identified by a hash chosen by the caller, typically of the target, value and
+//! calldata, and map to the timestamp at which they become ready. Delays are bounded by
+//! `TIMELOCK_MIN_DELAY` and `TIMELOCK_MAX_DELAY`, configurable with `timelock.min_delay` and
+//! `timelock.max_delay` in `libhuff.toml`.
+//!
+//! ## API
+//!
+//! - `OPERATION_ETA` - Loads the timestamp an operation becomes ready at, or zero if not queued.
+//! - `QUEUE_OPERATION` - Queues an operation to become ready at a timestamp.
+//! - `CANCEL_OPERATION` - Cancels a queued operation.
+//! - `REQUIRE_READY` - Reverts if an operation is not queued or not yet ready.
+//! - `EXECUTE_GUARD` - Consumes a ready operation and evaluates an expression.
+
+#include "libmapping.huff"
+"#;
+
+const CONSTANT_DEFINITION: &str = r#"
+/// ## Timelock Delays
+///
+/// Minimum and maximum delay in seconds between queueing an operation and it becoming ready.
+#define constant TIMELOCK_MIN_DELAY = {{min_delay}}
+#define constant TIMELOCK_MAX_DELAY = {{max_delay}}
+
+/// ## Timelock Events
+#define event OperationQueued(bytes32 indexed, uint256)
+#define event OperationCancelled(bytes32 indexed)
+#define event OperationExecuted(bytes32 indexed)
+
+/// ## Invalid Delay Error
+///
+/// Thrown when an operation is queued with a delay outside of the configured bounds.
+#define error InvalidDelay()
+
+/// ## Operation Already Queued Error
+///
+/// Thrown when queueing an operation that is already queued.
+#define error OperationAlreadyQueued()
+
+/// ## Operation Not Queued Error
+///
+/// Thrown when an operation is not queued.
+#define error OperationNotQueued()
+
+/// ## Operation Not Ready Error
+///
+/// Thrown when an operation is queued but its delay has not passed.
+#define error OperationNotReady()
+"#;
+
+const QUEUE_DEFINITION: &str = r#"
+/// ## Operation ETA
+///
+/// Loads the timestamp an operation becomes ready at, or zero if it is not queued.
+///
+/// > WARNING: Memory from `0x00` to `0x40` will be overwritten.
+///
+/// ### Template Arguments
+///
+/// - `queue_slot`: storage slot of the operation mapping.
+///
+/// ### Stack Arguments
+///
+/// - `id`: (`stack[0]`) operation id.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define constant QUEUE_SLOT = FREE_STORAGE_POINTER()
+///
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x04 calldataload
+///     OPERATION_ETA([QUEUE_SLOT])
+///     0x00 mstore
+///     0x20 0x00 return
+/// }
+/// ```
+#define macro OPERATION_ETA(queue_slot) = takes (1) returns (1) {
+    // takes:               // [id]
+    LOAD_MAPPING(<queue_slot>) // [eta]
+}
+
+/// ## Queue Operation
+///
+/// Queues an operation to become ready at a timestamp and emits `OperationQueued`.
+///
+/// > WARNING: Memory from `0x00` to `0x40` will be overwritten.
+///
+/// ### Template Arguments
+///
+/// - `queue_slot`: storage slot of the operation mapping.
+///
+/// ### Stack Arguments
+///
+/// - `id`: (`stack[0]`) operation id.
+/// - `eta`: (`stack[1]`) timestamp the operation becomes ready at.
+///
+/// ### Panics
+///
+/// - if `eta` is less than `TIMELOCK_MIN_DELAY` or more than `TIMELOCK_MAX_DELAY` from now.
+/// - if the operation is already queued.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define constant QUEUE_SLOT = FREE_STORAGE_POINTER()
+///
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x24 calldataload
+///     0x04 calldataload
+///     QUEUE_OPERATION([QUEUE_SLOT])
+///     stop
+/// }
+/// ```
+#define macro QUEUE_OPERATION(queue_slot) = takes (2) returns (0) {
+    // takes:               // [id, eta]
+    [TIMELOCK_MIN_DELAY]    // [min_delay, id, eta]
+    timestamp               // [timestamp, min_delay, id, eta]
+    add                     // [earliest, id, eta]
+    dup3                    // [eta, earliest, id, eta]
+    lt                      // [too_soon, id, eta]
+    [TIMELOCK_MAX_DELAY]    // [max_delay, too_soon, id, eta]
+    timestamp               // [timestamp, max_delay, too_soon, id, eta]
+    add                     // [latest, too_soon, id, eta]
+    dup4                    // [eta, latest, too_soon, id, eta]
+    gt                      // [too_late, too_soon, id, eta]
+    or                      // [invalid_delay, id, eta]
+    iszero                  // [valid_delay, id, eta]
+    valid_delay             // [valid_delay_dest, valid_delay, id, eta]
+    jumpi                   // [id, eta]
+        __ERROR(InvalidDelay) // [err, id, eta]
+        0x00                // [ptr, err, id, eta]
+        mstore              // [id, eta]
+        0x04                // [err_len, id, eta]
+        0x00                // [ptr, err_len, id, eta]
+        revert              // []
+    valid_delay:            // [id, eta]
+    dup1                    // [id, id, eta]
+    LOAD_MAPPING(<queue_slot>) // [queued_eta, id, eta]
+    iszero                  // [not_queued, id, eta]
+    not_queued              // [not_queued_dest, not_queued, id, eta]
+    jumpi                   // [id, eta]
+        __ERROR(OperationAlreadyQueued) // [err, id, eta]
+        0x00                // [ptr, err, id, eta]
+        mstore              // [id, eta]
+        0x04                // [err_len, id, eta]
+        0x00                // [ptr, err_len, id, eta]
+        revert              // []
+    not_queued:             // [id, eta]
+    dup2                    // [eta, id, eta]
+    dup2                    // [id, eta, id, eta]
+    STORE_MAPPING(<queue_slot>) // [id, eta]
+    swap1                   // [eta, id]
+    0x00                    // [ptr, eta, id]
+    mstore                  // [id]
+    __EVENT_HASH(OperationQueued) // [sig, id]
+    0x20                    // [len, sig, id]
+    0x00                    // [ptr, len, sig, id]
+    log2                    // []
+}
+
+/// ## Cancel Operation
+///
+/// Cancels a queued operation and emits `OperationCancelled`.
+///
+/// > WARNING: Memory from `0x00` to `0x40` will be overwritten.
+///
+/// ### Template Arguments
+///
+/// - `queue_slot`: storage slot of the operation mapping.
+///
+/// ### Stack Arguments
+///
+/// - `id`: (`stack[0]`) operation id.
+///
+/// ### Panics
+///
+/// - if the operation is not queued.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define constant QUEUE_SLOT = FREE_STORAGE_POINTER()
+///
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x04 calldataload
+///     CANCEL_OPERATION([QUEUE_SLOT])
+///     stop
+/// }
+/// ```
+#define macro CANCEL_OPERATION(queue_slot) = takes (1) returns (0) {
+    // takes:               // [id]
+    dup1                    // [id, id]
+    LOAD_MAPPING(<queue_slot>) // [eta, id]
+    queued                  // [queued_dest, eta, id]
+    jumpi                   // [id]
+        __ERROR(OperationNotQueued) // [err, id]
+        0x00                // [ptr, err, id]
+        mstore              // [id]
+        0x04                // [err_len, id]
+        0x00                // [ptr, err_len, id]
+        revert              // []
+    queued:                 // [id]
+    0x00                    // [0x00, id]
+    dup2                    // [id, 0x00, id]
+    STORE_MAPPING(<queue_slot>) // [id]
+    __EVENT_HASH(OperationCancelled) // [sig, id]
+    0x00                    // [len, sig, id]
+    0x00                    // [ptr, len, sig, id]
+    log2                    // []
+}
+"#;
+
+const EXECUTE_DEFINITION: &str = r#"
+/// ## Require Ready
+///
+/// Reverts if an operation is not queued or its delay has not passed.
+///
+/// > WARNING: Memory from `0x00` to `0x40` will be overwritten.
+///
+/// ### Template Arguments
+///
+/// - `queue_slot`: storage slot of the operation mapping.
+///
+/// ### Stack Arguments
+///
+/// - `id`: (`stack[0]`) operation id.
+///
+/// ### Panics
+///
+/// - if the operation is not queued.
+/// - if the operation is not yet ready.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define constant QUEUE_SLOT = FREE_STORAGE_POINTER()
+///
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x04 calldataload
+///     REQUIRE_READY([QUEUE_SLOT])
+///     stop
+/// }
+/// ```
+#define macro REQUIRE_READY(queue_slot) = takes (1) returns (0) {
+    // takes:               // [id]
+    LOAD_MAPPING(<queue_slot>) // [eta]
+    dup1                    // [eta, eta]
+    queued                  // [queued_dest, eta, eta]
+    jumpi                   // [eta]
+        __ERROR(OperationNotQueued) // [err, eta]
+        0x00                // [ptr, err, eta]
+        mstore              // [eta]
+        0x04                // [err_len, eta]
+        0x00                // [ptr, err_len, eta]
+        revert              // []
+    queued:                 // [eta]
+    timestamp               // [timestamp, eta]
+    lt                      // [is_pending]
+    iszero                  // [is_ready]
+    ready                   // [ready_dest, is_ready]
+    jumpi                   // []
+        __ERROR(OperationNotReady) // [err]
+        0x00                // [ptr, err]
+        mstore              // []
+        0x04                // [err_len]
+        0x00                // [ptr, err_len]
+        revert              // []
+    ready:                  // []
+}
+
+/// ## Execute Guard
+///
+/// Consumes a ready operation, emits `OperationExecuted`, and evaluates an expression. The
+/// operation is dequeued before `inner` is evaluated, so it can not be executed twice.
+///
+/// > WARNING: Memory from `0x00` to `0x40` will be overwritten.
+///
+/// ### Template Arguments
+///
+/// - `queue_slot`: storage slot of the operation mapping.
+/// - `inner`: expression that is evaluated if the operation is ready.
+///
+/// ### Stack Arguments
+///
+/// - `id`: (`stack[0]`) operation id.
+///
+/// ### Panics
+///
+/// - if the operation is not queued.
+/// - if the operation is not yet ready.
+/// - if `inner` panics.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define constant QUEUE_SLOT = FREE_STORAGE_POINTER()
+///
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x04 calldataload
+///     EXECUTE_GUARD([QUEUE_SLOT], EXECUTE())
+///     stop
+/// }
+/// ```
+#define macro EXECUTE_GUARD(queue_slot, inner) = takes (1) returns (0) {
+    // takes:               // [id]
+    dup1                    // [id, id]
+    REQUIRE_READY(<queue_slot>) // [id]
+    0x00                    // [0x00, id]
+    dup2                    // [id, 0x00, id]
+    STORE_MAPPING(<queue_slot>) // [id]
+    __EVENT_HASH(OperationExecuted) // [sig, id]
+    0x00                    // [len, sig, id]
+    0x00                    // [ptr, len, sig, id]
+    log2                    // []
+    <inner>                 // []
+}
+"#;
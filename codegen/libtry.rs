@@ -0,0 +1,148 @@
+pub fn render() -> String {
+    format!("{}{}", HEADER, TRY_DEFINITION)
+}
+
+const HEADER: &str = r#"
+//  ------------------------------------------------------------------------------------------------
+//! # Try Library
+//!
+//! Provides try/catch style external calls, routing control flow on the success of the call.
+//!
+//! Both branches receive the memory location and length of the returndata on the stack. The
+//! returndata buffer is left intact, so a failure branch can inspect it with the `librevert`
+//! macros before making any other call.
+//!
+//! ## API
+//!
+//! - `TRY_CALL` - Calls a contract, jumping to a success or failure label.
+//! - `TRY_STATICCALL` - Static calls a contract, jumping to a success or failure label.
+
+#include "librevert.huff"
+"#;
+
+const TRY_DEFINITION: &str = r#"
+/// ## Try Call
+///
+/// Calls `target` with the arguments at `args_ptr`, copies the returndata over the arguments, then
+/// jumps to `success` or `failure` with the returndata location and length on the stack:
+/// `[ret_ptr, ret_len]`.
+///
+/// Calls to accounts without code succeed with empty returndata.
+///
+/// > WARNING: Memory from `args_ptr` to `args_ptr + returndatasize` will be overwritten.
+///
+/// ### Template Arguments
+///
+/// - `target`: address to call.
+/// - `success`: label to jump to if the call succeeds.
+/// - `failure`: label to jump to if the call reverts.
+///
+/// ### Stack Arguments
+///
+/// - `value`: (`stack[0]`) wei to send.
+/// - `args_ptr`: (`stack[1]`) memory pointer of the calldata.
+/// - `args_len`: (`stack[2]`) length of the calldata.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x70a08231 0x00 mstore
+///     0x04 0x1c callvalue
+///     TRY_CALL(0x3a11e7, success, failure)
+///     success:                // [ret_ptr, ret_len]
+///         return
+///     failure:                // [ret_ptr, ret_len]
+///         JUMP_IF_PANIC(panic)
+///         revert
+///     panic:
+///         PANIC_CODE()
+///         0x00 mstore
+///         0x20 0x00 return
+/// }
+/// ```
+#define macro TRY_CALL(target, success, failure) = takes (3) returns (2) {
+    // takes:               // [value, args_ptr, args_len]
+    0x00                    // [ret_len, value, args_ptr, args_len]
+    0x00                    // [ret_ptr, ret_len, value, args_ptr, args_len]
+    dup5                    // [args_len, ret_ptr, ret_len, value, args_ptr, args_len]
+    dup5                    // [args_ptr, args_len, ret_ptr, ret_len, value, args_ptr, args_len]
+    dup5                    // [value, args_ptr, args_len, ret_ptr, ret_len, value, args_ptr, args_len]
+    <target>                // [target, value, args_ptr, args_len, ret_ptr, ret_len, value, args_ptr, args_len]
+    gas                     // [gas, target, value, args_ptr, args_len, ret_ptr, ret_len, value, args_ptr, args_len]
+    call                    // [success, value, args_ptr, args_len]
+    swap3                   // [args_len, value, args_ptr, success]
+    pop                     // [value, args_ptr, success]
+    pop                     // [args_ptr, success]
+    __TRY_COPY_RETURNDATA() // [success, ret_ptr, ret_len]
+    <success>               // [success_dest, success, ret_ptr, ret_len]
+    jumpi                   // [ret_ptr, ret_len]
+    <failure>               // [failure_dest, ret_ptr, ret_len]
+    jump                    // [ret_ptr, ret_len]
+}
+
+/// ## Try Static Call
+///
+/// Static calls `target` with the arguments at `args_ptr`, copies the returndata over the
+/// arguments, then jumps to `success` or `failure` with the returndata location and length on the
+/// stack: `[ret_ptr, ret_len]`.
+///
+/// Calls to accounts without code succeed with empty returndata.
+///
+/// > WARNING: Memory from `args_ptr` to `args_ptr + returndatasize` will be overwritten.
+///
+/// ### Template Arguments
+///
+/// - `target`: address to call.
+/// - `success`: label to jump to if the call succeeds.
+/// - `failure`: label to jump to if the call reverts.
+///
+/// ### Stack Arguments
+///
+/// - `args_ptr`: (`stack[0]`) memory pointer of the calldata.
+/// - `args_len`: (`stack[1]`) length of the calldata.
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x70a08231 0x00 mstore
+///     0x04 0x1c
+///     TRY_STATICCALL(0x3a11e7, success, failure)
+///     success:                // [ret_ptr, ret_len]
+///         return
+///     failure:                // [ret_ptr, ret_len]
+///         revert
+/// }
+/// ```
+#define macro TRY_STATICCALL(target, success, failure) = takes (2) returns (2) {
+    // takes:               // [args_ptr, args_len]
+    0x00                    // [ret_len, args_ptr, args_len]
+    0x00                    // [ret_ptr, ret_len, args_ptr, args_len]
+    dup4                    // [args_len, ret_ptr, ret_len, args_ptr, args_len]
+    dup4                    // [args_ptr, args_len, ret_ptr, ret_len, args_ptr, args_len]
+    <target>                // [target, args_ptr, args_len, ret_ptr, ret_len, args_ptr, args_len]
+    gas                     // [gas, target, args_ptr, args_len, ret_ptr, ret_len, args_ptr, args_len]
+    staticcall              // [success, args_ptr, args_len]
+    swap2                   // [args_len, args_ptr, success]
+    pop                     // [args_ptr, success]
+    __TRY_COPY_RETURNDATA() // [success, ret_ptr, ret_len]
+    <success>               // [success_dest, success, ret_ptr, ret_len]
+    jumpi                   // [ret_ptr, ret_len]
+    <failure>               // [failure_dest, ret_ptr, ret_len]
+    jump                    // [ret_ptr, ret_len]
+}
+
+/// ## Try Copy Returndata
+///
+/// Copies the returndata to `ret_ptr`, arranging the stack for the branch on `success`.
+#define macro __TRY_COPY_RETURNDATA() = takes (2) returns (3) {
+    // takes:               // [ret_ptr, success]
+    returndatasize          // [ret_len, ret_ptr, success]
+    0x00                    // [offset, ret_len, ret_ptr, success]
+    dup3                    // [ret_ptr, offset, ret_len, ret_ptr, success]
+    returndatacopy          // [ret_ptr, success]
+    returndatasize          // [ret_len, ret_ptr, success]
+    swap2                   // [success, ret_ptr, ret_len]
+}
+"#;
@@ -0,0 +1,391 @@
+use std::collections::HashSet;
+
+use crate::config::{identifier, invalid, Table, Value};
+use crate::emit::{conditions, line};
+use crate::libcast::mask;
+use crate::templates::{self, Context};
+
+struct CustomType {
+    name: String,
+    macro_name: String,
+    bits: u16,
+    signed: bool,
+}
+
+/// Renders `libtypes.huff` from the `[[type]]` entries of the config, if any.
+pub fn render(config: &Table) -> std::io::Result<Option<String>> {
+    let types = parse_types(config)?;
+
+    if types.is_empty() {
+        return Ok(None);
+    }
+
+    let libtypes = format!(
+        "{}{}",
+        HEADER,
+        types.iter().map(generate_type).collect::<String>(),
+    );
+
+    Ok(Some(libtypes))
+}
+
+fn parse_types(config: &Table) -> std::io::Result<Vec<CustomType>> {
+    let entries = match config.get("type") {
+        None => return Ok(Vec::new()),
+        Some(Value::Array(entries)) => entries,
+        Some(_) => return Err(invalid("`type` must be an array of tables")),
+    };
+
+    let mut names = HashSet::new();
+
+    entries
+        .iter()
+        .map(|entry| {
+            let entry = entry
+                .as_table()
+                .ok_or_else(|| invalid("`type` must be an array of tables"))?;
+            let name = identifier(entry, "name", "type")?;
+            let macro_name = name.to_ascii_uppercase();
+
+            if !names.insert(macro_name.clone()) {
+                return Err(invalid(format!("duplicate type `{}`", name)));
+            }
+
+            let is_cast_name = macro_name.len() > 1
+                && macro_name.starts_with('U')
+                && macro_name[1..].chars().all(|c| c.is_ascii_digit());
+            if is_cast_name {
+                return Err(invalid(format!(
+                    "type `{}` collides with the libcast types",
+                    name
+                )));
+            }
+
+            let bits = entry
+                .get("bits")
+                .and_then(Value::as_integer)
+                .filter(|bits| (1..=256).contains(bits))
+                .ok_or_else(|| {
+                    invalid(format!("type `{}` requires `bits` between 1 and 256", name))
+                })? as u16;
+
+            let signed = match entry.get("signed") {
+                None => false,
+                Some(Value::Boolean(signed)) => *signed,
+                Some(_) => {
+                    return Err(invalid(format!(
+                        "`signed` of type `{}` must be a boolean",
+                        name
+                    )))
+                }
+            };
+
+            Ok(CustomType {
+                name,
+                macro_name,
+                bits,
+                signed,
+            })
+        })
+        .collect()
+}
+
+fn generate_type(t: &CustomType) -> String {
+    let mask = mask(t.bits);
+    let shift = format!("0x{:02x}", 256 - t.bits);
+
+    let truncate = if t.signed {
+        [
+            line(&shift, "[shift, value]"),
+            line("shl", "[shifted]"),
+            line(&shift, "[shift, shifted]"),
+            line("sar", "[value]"),
+        ]
+        .concat()
+    } else {
+        [line(&mask, "[mask, value]"), line("and", "[value]")].concat()
+    };
+
+    let unsafe_cast = format!("UNSAFE_TO_{}()", t.macro_name);
+    let type_mask = format!("{}_MASK()", t.macro_name);
+
+    let (kind, lt, gt) = if t.signed {
+        ("signed", "slt", "sgt")
+    } else {
+        ("unsigned", "lt", "gt")
+    };
+
+    let (truncated, unpacked, range, a, b) = if t.signed {
+        (
+            format!("int256(result) == int256(value << {}) >> {}", shift, shift),
+            format!(
+                "int256(result) == int256((word >> offset) << {}) >> {}",
+                shift, shift
+            ),
+            format!(
+                "int256(value) >= -2 ** {} && int256(value) < 2 ** {}",
+                t.bits - 1,
+                t.bits - 1
+            ),
+            "int256(a)",
+            "int256(b)",
+        )
+    } else {
+        (
+            format!("result == value & {}", mask),
+            format!("result == (word >> offset) & {}", mask),
+            format!("value <= {}", mask),
+            "a",
+            "b",
+        )
+    };
+    let packed = format!(
+        "result == (word & ~({} << offset)) | ((value & {}) << offset)",
+        mask, mask
+    );
+    let compare = |op: &str| conditions(&[], &[&format!("result == ({} {} {})", a, op, b)]);
+
+    let in_word = format!("offset + {} <= 256", t.bits);
+
+    let context = Context::new()
+        .text(
+            "mask_conditions",
+            conditions(&[], &[&format!("result == {}", mask)]),
+        )
+        .text("unsafe_conditions", conditions(&[], &[&truncated]))
+        .text(
+            "cast_conditions",
+            conditions(&[&range], &["result == value"]),
+        )
+        .text("unpack_conditions", conditions(&[&in_word], &[&unpacked]))
+        .text("pack_conditions", conditions(&[&in_word], &[&packed]))
+        .text("lte_conditions", compare("<="))
+        .text("gte_conditions", compare(">="))
+        .text("lt_conditions", compare("<"))
+        .text("gt_conditions", compare(">"))
+        .text("truncate_body", truncate)
+        .text("cast_line", line(&unsafe_cast, "[cast_value, value]"))
+        .text("unpack_line", line(&unsafe_cast, "[value]"))
+        .text("value_mask_line", line(&type_mask, "[mask, value, word]"))
+        .text("word_mask_line", line(&type_mask, "[mask, word, shifted]"))
+        .text("lt_line", line(lt, "[is_lt]"))
+        .text("gt_line", line(gt, "[is_gt]"))
+        .text("lte_line", line(gt, "[is_gt]"))
+        .text("gte_line", line(lt, "[is_lt]"))
+        .text("display", t.name.as_str())
+        .text("name", t.macro_name.as_str())
+        .text("kind", kind)
+        .text("bits", t.bits.to_string())
+        .text("mask", mask);
+    templates::render(TYPE_TEMPLATE, &context)
+}
+
+const HEADER: &str = r#"
+//  ------------------------------------------------------------------------------------------------
+//! # Types Library
+//!
+//! Provides casts, packing and comparisons for the custom types declared in `libhuff.toml`.
+//!
+//! Values of a type are held on the stack as full words: zero extended if unsigned, and sign
+//! extended if signed, matching Solidity's ABI. Packed, a type occupies its width at a bit offset
+//! of a word.
+//!
+//! ## API
+//!
+//! For a given type, `TYPE`:
+//!
+//! - `TYPE_MASK` - Mask of the width of the type.
+//! - `TO_TYPE` - Casts a value to the type, reverting if it does not fit.
+//! - `UNSAFE_TO_TYPE` - Casts a value to the type, truncating it.
+//! - `PACK_TYPE` - Packs a value into a word at a bit offset.
+//! - `UNPACK_TYPE` - Unpacks a value from a word at a bit offset.
+//! - `TYPE_LT`, `TYPE_GT`, `TYPE_LTE`, `TYPE_GTE` - Compares two values of the type.
+
+#include "libcast.huff"
+"#;
+
+const TYPE_TEMPLATE: &str = r#"
+/// ## {{display}} Mask
+///
+/// Mask of the {{bits}} bits of the {{kind}} `{{display}}` type.
+///
+/// ### Conditions
+///
+{{mask_conditions}}#define macro {{name}}_MASK() = takes (0) returns (1) { {{mask}} }
+
+/// ## Unsafe {{display}} Cast
+///
+/// Casts a value to the `{{display}}` type, discarding the bits above its width.
+///
+/// ### Conditions
+///
+{{unsafe_conditions}}#define macro UNSAFE_TO_{{name}}() = takes (1) returns (1) {
+    // takes:               // [value]
+{{truncate_body}}}
+
+/// ## {{display}} Cast
+///
+/// Casts a value to the {{bits}} bit {{kind}} `{{display}}` type.
+///
+/// ### Panics
+///
+/// - if the value does not fit in the type.
+///
+/// ### Conditions
+///
+{{cast_conditions}}///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x04 calldataload
+///     TO_{{name}}()
+///     0x00 sstore
+/// }
+/// ```
+#define macro TO_{{name}}() = takes (1) returns (1) {
+    // takes:               // [value]
+    dup1                    // [value, value]
+{{cast_line}}    dup2                    // [value, cast_value, value]
+    eq                      // [is_safe, value]
+    is_safe                 // [is_safe_dest, is_safe, value]
+    jumpi                   // [value]
+        __ERROR(Overflow)   // [err]
+        0x00                // [ptr, err]
+        mstore              // []
+        0x04                // [err_len]
+        0x00                // [ptr, err_len]
+        revert              // []
+    is_safe:                // [value]
+}
+
+/// ## Pack {{display}}
+///
+/// Packs a value of the `{{display}}` type into a word at bit `offset`, leaving every other bit
+/// untouched.
+///
+/// ### Template Arguments
+///
+/// - `offset`: bit offset of the value, at most `256 - {{bits}}`.
+///
+/// ### Stack Arguments
+///
+/// - `value`: (`stack[0]`) value to pack.
+/// - `word`: (`stack[1]`) word to pack into.
+///
+/// ### Conditions
+///
+{{pack_conditions}}///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x00 sload
+///     0x04 calldataload TO_{{name}}()
+///     PACK_{{name}}(0x20)
+///     0x00 sstore
+/// }
+/// ```
+#define macro PACK_{{name}}(offset) = takes (2) returns (1) {
+    // takes:               // [value, word]
+{{value_mask_line}}    and                     // [bits, word]
+    <offset>                // [offset, bits, word]
+    shl                     // [shifted, word]
+    swap1                   // [word, shifted]
+{{word_mask_line}}    <offset>                // [offset, mask, word, shifted]
+    shl                     // [field_mask, word, shifted]
+    not                     // [clear_mask, word, shifted]
+    and                     // [cleared, shifted]
+    or                      // [word]
+}
+
+/// ## Unpack {{display}}
+///
+/// Unpacks a value of the `{{display}}` type from a word at bit `offset`.
+///
+/// ### Template Arguments
+///
+/// - `offset`: bit offset of the value, at most `256 - {{bits}}`.
+///
+/// ### Stack Arguments
+///
+/// - `word`: (`stack[0]`) word to unpack from.
+///
+/// ### Conditions
+///
+{{unpack_conditions}}///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x00 sload
+///     UNPACK_{{name}}(0x20)
+///     0x00 mstore
+///     0x20 0x00 return
+/// }
+/// ```
+#define macro UNPACK_{{name}}(offset) = takes (1) returns (1) {
+    // takes:               // [word]
+    <offset>                // [offset, word]
+    shr                     // [shifted]
+{{unpack_line}}}
+
+/// ## {{display}} Less Than
+///
+/// Returns whether `a` is less than `b`, comparing `{{display}}` values as {{kind}} integers.
+///
+/// ### Stack Arguments
+///
+/// - `a`: (`stack[0]`) left hand side.
+/// - `b`: (`stack[1]`) right hand side.
+///
+/// ### Conditions
+///
+{{lt_conditions}}///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x24 calldataload TO_{{name}}()
+///     0x04 calldataload TO_{{name}}()
+///     {{name}}_LT()
+///     0x00 mstore
+///     0x20 0x00 return
+/// }
+/// ```
+#define macro {{name}}_LT() = takes (2) returns (1) {
+    // takes:               // [a, b]
+{{lt_line}}}
+
+/// ## {{display}} Greater Than
+///
+/// Returns whether `a` is greater than `b`.
+///
+/// ### Conditions
+///
+{{gt_conditions}}#define macro {{name}}_GT() = takes (2) returns (1) {
+    // takes:               // [a, b]
+{{gt_line}}}
+
+/// ## {{display}} Less Than Or Equal
+///
+/// Returns whether `a` is at most `b`.
+///
+/// ### Conditions
+///
+{{lte_conditions}}#define macro {{name}}_LTE() = takes (2) returns (1) {
+    // takes:               // [a, b]
+{{lte_line}}    iszero                  // [is_lte]
+}
+
+/// ## {{display}} Greater Than Or Equal
+///
+/// Returns whether `a` is at least `b`.
+///
+/// ### Conditions
+///
+{{gte_conditions}}#define macro {{name}}_GTE() = takes (2) returns (1) {
+    // takes:               // [a, b]
+{{gte_line}}    iszero                  // [is_gte]
+}
+"#;
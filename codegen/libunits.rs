@@ -0,0 +1,184 @@
+use crate::templates::{self, Context};
+
+pub fn render() -> String {
+    let units = [("GWEI", "Gwei", 9), ("ETHER", "Ether", 18)]
+        .iter()
+        .map(|(name, title, decimals)| generate_unit(name, title, *decimals))
+        .collect::<String>();
+
+    format!("{}{}", HEADER, units)
+}
+
+fn generate_unit(name: &str, title: &str, decimals: u8) -> String {
+    let factor = format!("[POW10_{}]", decimals);
+    let context = Context::new()
+        .text("nested_factor", format!("{:<20}", factor))
+        .text("factor", format!("{:<24}", factor))
+        .text("name", name)
+        .text("title", title)
+        .text("lower", title.to_lowercase())
+        .text("decimals", decimals.to_string());
+    templates::render(UNIT_TEMPLATE, &context)
+}
+
+const HEADER: &str = r#"
+//  ------------------------------------------------------------------------------------------------
+//! # Units Library
+//!
+//! Provides conversions of ether amounts between wei, gwei and ether.
+//!
+//! Conversions to wei revert on overflow. Conversions from wei either truncate the remainder, or
+//! revert on a remainder so that no dust is silently dropped.
+//!
+//! ## API
+//!
+//! - `FROM_UNIT` - Converts an amount of `UNIT` to wei.
+//! - `TO_WEI_FROM_UNIT` - Alias of `FROM_UNIT`, naming the unit converted to first.
+//! - `TO_UNIT` - Converts an amount of wei to `UNIT`, truncating.
+//! - `TO_UNIT_EXACT` - Converts an amount of wei to `UNIT`, reverting on a remainder.
+//!
+//! Where `UNIT` is `GWEI` or `ETHER`.
+
+#include "libdecimal.huff"
+
+/// ## Inexact Conversion Error
+///
+/// Thrown when an amount of wei is not a whole amount of the unit it is converted to.
+#define error InexactConversion()
+"#;
+
+const UNIT_TEMPLATE: &str = r#"
+/// ## From {{title}}
+///
+/// Converts an amount of {{lower}} to wei, that is multiplies it by `10 ** {{decimals}}`.
+///
+/// ### Stack Arguments
+///
+/// - `amount`: (`stack[0]`) amount of {{lower}}.
+///
+/// ### Panics
+///
+/// - if the amount of wei overflows.
+///
+/// ### Conditions
+///
+/// @pre amount * 10 ** {{decimals}} < 2 ** 256
+/// @post result == amount * 10 ** {{decimals}}
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x04 calldataload
+///     FROM_{{name}}()
+///     0x00 mstore
+///     0x20 0x00 return
+/// }
+/// ```
+#define macro FROM_{{name}}() = takes (1) returns (1) {
+    // takes:               // [amount]
+    {{factor}}// [factor, amount]
+    dup2                    // [amount, factor, amount]
+    mul                     // [wei, amount]
+    {{factor}}// [factor, wei, amount]
+    dup2                    // [wei, factor, wei, amount]
+    div                     // [unscaled, wei, amount]
+    dup3                    // [amount, unscaled, wei, amount]
+    eq                      // [in_range, wei, amount]
+    in_range                // [in_range_dest, in_range, wei, amount]
+    jumpi                   // [wei, amount]
+        __ERROR(Overflow)   // [err, wei, amount]
+        0x00                // [ptr, err, wei, amount]
+        mstore              // [wei, amount]
+        0x04                // [err_len, wei, amount]
+        0x00                // [ptr, err_len, wei, amount]
+        revert              // []
+    in_range:               // [wei, amount]
+        swap1               // [amount, wei]
+        pop                 // [wei]
+}
+
+/// ## To Wei From {{title}}
+///
+/// Alias of `FROM_{{name}}`, converting an amount of {{lower}} to wei.
+#define macro TO_WEI_FROM_{{name}}() = takes (1) returns (1) {
+    FROM_{{name}}()
+}
+
+/// ## To {{title}}
+///
+/// Converts an amount of wei to {{lower}}, that is divides it by `10 ** {{decimals}}`, truncating the
+/// remainder.
+///
+/// ### Stack Arguments
+///
+/// - `wei`: (`stack[0]`) amount of wei.
+///
+/// ### Conditions
+///
+/// @post result == wei / 10 ** {{decimals}}
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     callvalue
+///     TO_{{name}}()
+///     0x00 mstore
+///     0x20 0x00 return
+/// }
+/// ```
+#define macro TO_{{name}}() = takes (1) returns (1) {
+    // takes:               // [wei]
+    {{factor}}// [factor, wei]
+    swap1                   // [wei, factor]
+    div                     // [amount]
+}
+
+/// ## To {{title}} Exact
+///
+/// Converts an amount of wei to {{lower}}, that is divides it by `10 ** {{decimals}}`.
+///
+/// ### Stack Arguments
+///
+/// - `wei`: (`stack[0]`) amount of wei.
+///
+/// ### Panics
+///
+/// - if the amount of wei is not a multiple of `10 ** {{decimals}}`.
+///
+/// ### Conditions
+///
+/// @pre wei % 10 ** {{decimals}} == 0
+/// @post result * 10 ** {{decimals}} == wei
+///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     callvalue
+///     TO_{{name}}_EXACT()
+///     0x00 mstore
+///     0x20 0x00 return
+/// }
+/// ```
+#define macro TO_{{name}}_EXACT() = takes (1) returns (1) {
+    // takes:               // [wei]
+    {{factor}}// [factor, wei]
+    dup2                    // [wei, factor, wei]
+    mod                     // [remainder, wei]
+    iszero                  // [is_exact, wei]
+    is_exact                // [is_exact_dest, is_exact, wei]
+    jumpi                   // [wei]
+        __ERROR(InexactConversion) // [err, wei]
+        0x00                // [ptr, err, wei]
+        mstore              // [wei]
+        0x04                // [err_len, wei]
+        0x00                // [ptr, err_len, wei]
+        revert              // []
+    is_exact:               // [wei]
+        {{nested_factor}}// [factor, wei]
+        swap1               // [wei, factor]
+        div                 // [amount]
+}
+"#;
@@ -0,0 +1,101 @@
+use crate::emit::conditions;
+use crate::templates::{self, Context};
+
+/// Renders `libwrapping.huff` with the arithmetic of the bit `sizes`.
+pub fn render(sizes: &[u16]) -> String {
+    let sizes = sizes.iter().map(|size| size_context(*size)).collect();
+    let context = Context::new().list("sizes", sizes);
+
+    templates::render(LIBRARY_TEMPLATE, &context)
+}
+
+fn size_context(size: u16) -> Context {
+    let modulus = format!("2 ** {}", size);
+    let post = |op: &str| format!("result == (a {} b) % {}", op, modulus);
+
+    Context::new()
+        .text("name", format!("U{}", size))
+        .text("size", size.to_string())
+        .text("add_conditions", conditions(&[], &[&post("+")]))
+        .text("sub_conditions", conditions(&[], &[&post("-")]))
+        .text("mul_conditions", conditions(&[], &[&post("*")]))
+        // words wrap at 256 bits on their own, narrower results are masked back to the type
+        .flag("masked", size != 256)
+}
+
+const LIBRARY_TEMPLATE: &str = r#"
+//  ------------------------------------------------------------------------------------------------
+//! # Wrapping Math Library
+//!
+//! Provides modular arithmetic on unsigned integers of the sizes of `libcast.huff`.
+//!
+//! Bit sizes supported range from 8 to 256 inclusive and are multiples of 8. Operands are expected
+//! to fit in the type, and results wrap around to fit in it rather than reverting, as for counters
+//! and nonces packed into part of a slot. For arithmetic that reverts instead, see
+//! `libsafemath.huff`.
+//!
+//! ## API
+//!
+//! For a given type, `TYPENAME`:
+//!
+//! - `WRAPPING_ADD_TYPENAME` - Adds two values, modulo the size of the type.
+//! - `WRAPPING_SUB_TYPENAME` - Subtracts two values, modulo the size of the type.
+//! - `WRAPPING_MUL_TYPENAME` - Multiplies two values, modulo the size of the type.
+
+#include "libcast.huff"
+{{#each sizes}}
+/// ## {{name}} Wrapping Addition
+///
+/// Adds two {{size}} bit values, wrapping around on overflow.
+///
+/// ### Stack Arguments
+///
+/// - `a`: (`stack[0]`) left hand side.
+/// - `b`: (`stack[1]`) right hand side.
+///
+/// ### Conditions
+///
+{{add_conditions}}///
+/// ### Usage
+///
+/// ```huff
+/// #define macro MAIN() = takes (0) returns (0) {
+///     0x01
+///     0x00 sload
+///     WRAPPING_ADD_{{name}}()
+///     0x00 sstore
+/// }
+/// ```
+#define macro WRAPPING_ADD_{{name}}() = takes (2) returns (1) {
+    // takes:               // [a, b]
+    add                     // [sum]
+{{#if masked}}    {{name}}_MASK()         // [mask, sum]
+    and                     // [sum]
+{{/if}}}
+
+/// ## {{name}} Wrapping Subtraction
+///
+/// Subtracts `b` from `a`, both {{size}} bit values, wrapping around on underflow.
+///
+/// ### Conditions
+///
+{{sub_conditions}}#define macro WRAPPING_SUB_{{name}}() = takes (2) returns (1) {
+    // takes:               // [a, b]
+    sub                     // [difference]
+{{#if masked}}    {{name}}_MASK()         // [mask, difference]
+    and                     // [difference]
+{{/if}}}
+
+/// ## {{name}} Wrapping Multiplication
+///
+/// Multiplies two {{size}} bit values, wrapping around on overflow.
+///
+/// ### Conditions
+///
+{{mul_conditions}}#define macro WRAPPING_MUL_{{name}}() = takes (2) returns (1) {
+    // takes:               // [a, b]
+    mul                     // [product]
+{{#if masked}}    {{name}}_MASK()         // [mask, product]
+    and                     // [product]
+{{/if}}}
+{{/each}}"#;
@@ -0,0 +1,327 @@
+//! Opcode-level listings of compiled macros, annotated for review.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::huff::{self, Program};
+use crate::profile::{self, Profile};
+
+/// Static properties of an opcode.
+struct Info {
+    name: String,
+    pops: usize,
+    pushes: usize,
+    /// Static gas cost on mainnet, excluding memory expansion, cold access and other dynamic costs.
+    gas: u64,
+    /// Whether the opcode has a dynamic cost on top of its static cost.
+    dynamic: bool,
+}
+
+/// Renders listings of the macros of the library at `file`, compiled and costed for `profile`.
+///
+/// `invocation` selects a single macro, with its template arguments if it takes any. Without
+/// one, every macro without template arguments is listed, in alphabetical order.
+pub fn render(
+    file: &Path,
+    invocation: Option<&str>,
+    profile: &Profile,
+) -> Result<String, huff::Error> {
+    let program = wrap(file, "")?;
+
+    let invocations = match invocation {
+        Some(invocation) if invocation.contains('(') => vec![invocation.to_string()],
+        Some(invocation) => vec![format!("{}()", invocation)],
+        None => {
+            let mut names = program
+                .macros
+                .iter()
+                .filter(|(name, definition)| *name != ENTRY && definition.params.is_empty())
+                .map(|(name, _)| name.clone())
+                .collect::<Vec<String>>();
+            names.sort();
+            names.iter().map(|name| format!("{}()", name)).collect()
+        }
+    };
+
+    invocations
+        .iter()
+        .map(|invocation| listing(file, &program, invocation, profile))
+        .collect::<Result<Vec<String>, huff::Error>>()
+        .map(|listings| listings.join("\n"))
+}
+
+/// The name of the macro `wrap` invokes a body from, which no library can define as it is not an
+/// identifier.
+pub const ENTRY: &str = "<main>";
+
+/// Parses the library at `file` with an `ENTRY` macro of `body`, next to its own `MAIN`, if any.
+pub fn wrap(file: &Path, body: &str) -> Result<Program, huff::Error> {
+    let src = std::fs::read_to_string(file).map_err(|e| huff::Error {
+        msg: format!("{}: {}", file.display(), e),
+    })?;
+    let mut program = Program::parse_file(&src, file)?;
+
+    let entry = format!(
+        "#define macro MAIN() = takes (0) returns (0) {{\n    {}\n}}\n",
+        body
+    );
+    let entry = Program::parse_file(&entry, &file.with_file_name(ENTRY))?;
+    program.macros.extend(
+        entry
+            .macros
+            .into_values()
+            .map(|entry| (ENTRY.to_string(), entry)),
+    );
+    Ok(program)
+}
+
+/// Renders the listing of a single macro invocation.
+fn listing(
+    file: &Path,
+    program: &Program,
+    invocation: &str,
+    profile: &Profile,
+) -> Result<String, huff::Error> {
+    let name = &invocation[..invocation.find('(').unwrap_or(invocation.len())];
+    let definition = program.macros.get(name).ok_or_else(|| huff::Error {
+        msg: format!("no macro `{}`", name),
+    })?;
+    let code = profile::compile(wrap(file, invocation)?, ENTRY, profile)?;
+
+    let mut out = format!(
+        "; {} = takes ({}) returns ({}), {} bytes\n",
+        invocation,
+        definition.takes,
+        definition.returns,
+        code.len(),
+    );
+    out.push_str(&format!(
+        "{:<8}{:>6}{:>7}  {}\n",
+        "offset", "gas", "stack", "opcode"
+    ));
+
+    let destinations = destinations(&code, definition.takes);
+    let mut height = Some(definition.takes as i64);
+    let mut total = 0;
+    let mut dynamic = false;
+    let mut pc = 0;
+
+    while pc < code.len() {
+        let op = code[pc];
+        let size = immediate_size(op);
+        let end = (pc + 1 + size).min(code.len());
+        let info = info(op);
+        let cost = profile.gas(op, info.gas);
+
+        if op == 0x5b {
+            height = height.or_else(|| destinations.get(&pc).copied());
+        }
+        height = height.map(|h| h - info.pops as i64 + info.pushes as i64);
+
+        let mnemonic = if size == 0 {
+            info.name.clone()
+        } else {
+            format!("{} 0x{}", info.name, hex(&code[pc + 1..end]))
+        };
+        let gas = format!("{}{}", cost, if info.dynamic { "+" } else { "" });
+        let stack = height.map_or("?".to_string(), |h| h.to_string());
+
+        out.push_str(&format!(
+            "{:<8}{:>6}{:>7}  {}\n",
+            format!("0x{:04x}", pc),
+            gas,
+            stack,
+            mnemonic,
+        ));
+
+        total += cost;
+        dynamic |= info.dynamic;
+        if terminates(op) {
+            height = None;
+        }
+        pc = end;
+    }
+
+    out.push_str(&format!(
+        "; static gas {}{} over every path, final stack {}\n",
+        total,
+        if dynamic { "+" } else { "" },
+        height.map_or("?".to_string(), |h| h.to_string()),
+    ));
+
+    Ok(out)
+}
+
+/// Maps jump destinations to their stack height, for the `PUSH2 <dest> JUMP(I)` sequences the
+/// compiler emits for labels.
+fn destinations(code: &[u8], takes: usize) -> HashMap<usize, i64> {
+    let mut destinations = HashMap::new();
+    let mut height = Some(takes as i64);
+    let mut pc = 0;
+
+    while pc < code.len() {
+        let op = code[pc];
+        let end = pc + 1 + immediate_size(op);
+        let effect = info(op);
+
+        if op == 0x5b {
+            height = height.or_else(|| destinations.get(&pc).copied());
+        }
+        height = height.map(|h| h - effect.pops as i64 + effect.pushes as i64);
+
+        let jumps = code
+            .get(end)
+            .is_some_and(|next| *next == 0x56 || *next == 0x57);
+        if let (0x61, true, Some(h)) = (op, jumps, height) {
+            let dest = ((code[pc + 1] as usize) << 8) | code[pc + 2] as usize;
+            destinations
+                .entry(dest)
+                .or_insert(h - info(code[end]).pops as i64);
+        }
+
+        if terminates(op) {
+            height = None;
+        }
+        pc = end;
+    }
+
+    destinations
+}
+
+fn terminates(op: u8) -> bool {
+    matches!(op, 0x00 | 0x56 | 0xf3 | 0xfd | 0xfe | 0xff)
+}
+
+fn immediate_size(op: u8) -> usize {
+    match op {
+        0x60..=0x7f => (op - 0x5f) as usize,
+        _ => 0,
+    }
+}
+
+fn info(op: u8) -> Info {
+    let (name, pops, pushes, gas, dynamic) = match op {
+        0x00 => ("stop", 0, 0, 0, false),
+        0x01 => ("add", 2, 1, 3, false),
+        0x02 => ("mul", 2, 1, 5, false),
+        0x03 => ("sub", 2, 1, 3, false),
+        0x04 => ("div", 2, 1, 5, false),
+        0x05 => ("sdiv", 2, 1, 5, false),
+        0x06 => ("mod", 2, 1, 5, false),
+        0x07 => ("smod", 2, 1, 5, false),
+        0x08 => ("addmod", 3, 1, 8, false),
+        0x09 => ("mulmod", 3, 1, 8, false),
+        0x0a => ("exp", 2, 1, 10, true),
+        0x0b => ("signextend", 2, 1, 5, false),
+        0x10 => ("lt", 2, 1, 3, false),
+        0x11 => ("gt", 2, 1, 3, false),
+        0x12 => ("slt", 2, 1, 3, false),
+        0x13 => ("sgt", 2, 1, 3, false),
+        0x14 => ("eq", 2, 1, 3, false),
+        0x15 => ("iszero", 1, 1, 3, false),
+        0x16 => ("and", 2, 1, 3, false),
+        0x17 => ("or", 2, 1, 3, false),
+        0x18 => ("xor", 2, 1, 3, false),
+        0x19 => ("not", 1, 1, 3, false),
+        0x1a => ("byte", 2, 1, 3, false),
+        0x1b => ("shl", 2, 1, 3, false),
+        0x1c => ("shr", 2, 1, 3, false),
+        0x1d => ("sar", 2, 1, 3, false),
+        0x20 => ("sha3", 2, 1, 30, true),
+        0x30 => ("address", 0, 1, 2, false),
+        0x31 => ("balance", 1, 1, 100, true),
+        0x32 => ("origin", 0, 1, 2, false),
+        0x33 => ("caller", 0, 1, 2, false),
+        0x34 => ("callvalue", 0, 1, 2, false),
+        0x35 => ("calldataload", 1, 1, 3, false),
+        0x36 => ("calldatasize", 0, 1, 2, false),
+        0x37 => ("calldatacopy", 3, 0, 3, true),
+        0x38 => ("codesize", 0, 1, 2, false),
+        0x39 => ("codecopy", 3, 0, 3, true),
+        0x3a => ("gasprice", 0, 1, 2, false),
+        0x3b => ("extcodesize", 1, 1, 100, true),
+        0x3c => ("extcodecopy", 4, 0, 100, true),
+        0x3d => ("returndatasize", 0, 1, 2, false),
+        0x3e => ("returndatacopy", 3, 0, 3, true),
+        0x3f => ("extcodehash", 1, 1, 100, true),
+        0x40 => ("blockhash", 1, 1, 20, false),
+        0x41 => ("coinbase", 0, 1, 2, false),
+        0x42 => ("timestamp", 0, 1, 2, false),
+        0x43 => ("number", 0, 1, 2, false),
+        0x44 => ("prevrandao", 0, 1, 2, false),
+        0x45 => ("gaslimit", 0, 1, 2, false),
+        0x46 => ("chainid", 0, 1, 2, false),
+        0x47 => ("selfbalance", 0, 1, 5, false),
+        0x48 => ("basefee", 0, 1, 2, false),
+        0x49 => ("blobhash", 1, 1, 3, false),
+        0x4a => ("blobbasefee", 0, 1, 2, false),
+        0x50 => ("pop", 1, 0, 2, false),
+        0x51 => ("mload", 1, 1, 3, true),
+        0x52 => ("mstore", 2, 0, 3, true),
+        0x53 => ("mstore8", 2, 0, 3, true),
+        0x54 => ("sload", 1, 1, 100, true),
+        0x55 => ("sstore", 2, 0, 100, true),
+        0x56 => ("jump", 1, 0, 8, false),
+        0x57 => ("jumpi", 2, 0, 10, false),
+        0x58 => ("pc", 0, 1, 2, false),
+        0x59 => ("msize", 0, 1, 2, false),
+        0x5a => ("gas", 0, 1, 2, false),
+        0x5b => ("jumpdest", 0, 0, 1, false),
+        0x5c => ("tload", 1, 1, 100, false),
+        0x5d => ("tstore", 2, 0, 100, false),
+        0x5e => ("mcopy", 3, 0, 3, true),
+        0x5f => ("push0", 0, 1, 2, false),
+        0x60..=0x7f => return opcode(format!("push{}", op - 0x5f), 0, 1, 3),
+        0x80..=0x8f => {
+            let n = (op - 0x7f) as usize;
+            return opcode(format!("dup{}", n), n, n + 1, 3);
+        }
+        0x90..=0x9f => {
+            let n = (op - 0x8f) as usize;
+            return opcode(format!("swap{}", n), n + 1, n + 1, 3);
+        }
+        0xa0..=0xa4 => {
+            let n = (op - 0xa0) as usize;
+            return Info {
+                name: format!("log{}", n),
+                pops: n + 2,
+                pushes: 0,
+                gas: 375 * (n as u64 + 1),
+                dynamic: true,
+            };
+        }
+        0xf0 => ("create", 3, 1, 32000, true),
+        0xf1 => ("call", 7, 1, 100, true),
+        0xf2 => ("callcode", 7, 1, 100, true),
+        0xf3 => ("return", 2, 0, 0, true),
+        0xf4 => ("delegatecall", 6, 1, 100, true),
+        0xf5 => ("create2", 4, 1, 32000, true),
+        0xfa => ("staticcall", 6, 1, 100, true),
+        0xfd => ("revert", 2, 0, 0, true),
+        0xfe => ("invalid", 0, 0, 0, false),
+        0xff => ("selfdestruct", 1, 0, 5000, true),
+        _ => return opcode(format!("unknown 0x{:02x}", op), 0, 0, 0),
+    };
+
+    Info {
+        name: name.to_string(),
+        pops,
+        pushes,
+        gas,
+        dynamic,
+    }
+}
+
+fn opcode(name: String, pops: usize, pushes: usize, gas: u64) -> Info {
+    Info {
+        name,
+        pops,
+        pushes,
+        gas,
+        dynamic: false,
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
@@ -0,0 +1,367 @@
+//! Huff macro bodies lowered from the definitions of the generated libraries, the same definitions
+//! the Yul functions are rendered from, tracking the stack to comment every line.
+
+use crate::emit::line;
+use crate::ir::{Definition, Expr};
+use crate::u256::U256;
+
+/// A value the body computes on the stack.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Value {
+    /// The name of the value in the stack comments.
+    name: String,
+    op: Op,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Op {
+    /// An argument, on the stack from the start.
+    Arg,
+    /// Pushed by a literal or the macro pushing it.
+    Push(String),
+    /// An opcode of the value of `a`, pushed first, and `b`, so that `lt` is `b < a`.
+    Binary(&'static str, Box<Value>, Box<Value>),
+    /// An opcode of a single value.
+    Unary(&'static str, Box<Value>),
+}
+
+impl Value {
+    fn new(name: impl Into<String>, op: Op) -> Self {
+        Value {
+            name: name.into(),
+            op,
+        }
+    }
+
+    fn binary(name: impl Into<String>, code: &'static str, a: Value, b: Value) -> Self {
+        Value::new(name, Op::Binary(code, Box::new(a), Box::new(b)))
+    }
+}
+
+/// Each evaluation of `values` in order, and of the values they are computed from, which are
+/// computed once and found on the stack from then on.
+fn evaluations(values: &[&Value]) -> Vec<Value> {
+    fn evaluate(value: &Value, evaluations: &mut Vec<Value>) {
+        if !evaluations.contains(value) {
+            match &value.op {
+                Op::Arg | Op::Push(_) => {}
+                Op::Binary(_, a, b) => {
+                    evaluate(a, evaluations);
+                    evaluate(b, evaluations);
+                }
+                Op::Unary(_, a) => evaluate(a, evaluations),
+            }
+        }
+        evaluations.push(value.clone());
+    }
+
+    let mut evaluations = Vec::new();
+    for value in values {
+        evaluate(value, &mut evaluations);
+    }
+    evaluations
+}
+
+/// Lowers the body of the macro of `definition` below its opening brace, pushing the literals in
+/// `masks` with the macro it names, such as `0xff` with `U8_MASK()`, and reverting as `revert`,
+/// indented below the jump, when a check does not hold.
+///
+/// Checks of arguments bounded by the same mask are made at once on the bitwise or of the
+/// arguments, which fits in the mask if and only if each argument does. Results that are the
+/// arguments themselves are left in place, other results consume the arguments.
+pub fn body(
+    definition: &Definition,
+    masks: &dyn Fn(&str) -> Option<String>,
+    revert: &str,
+) -> String {
+    let args = definition
+        .args
+        .iter()
+        .map(|arg| Value::new(*arg, Op::Arg))
+        .collect();
+    let mut lowering = Lowering {
+        masks,
+        stack: args,
+        pending: Vec::new(),
+        kept: Vec::new(),
+        body: String::new(),
+    };
+    lowering.body = line("// takes:", &lowering.comment());
+
+    let kept = definition.results.len() == definition.args.len()
+        && definition
+            .results
+            .iter()
+            .zip(&definition.args)
+            .all(|((_, r), a)| *r == Expr::Arg(a));
+    let results = match kept {
+        true => lowering.stack.clone(),
+        false => definition
+            .results
+            .iter()
+            .map(|(_, r)| lowering.value(r, "success"))
+            .collect(),
+    };
+    let check = lowering.check(&definition.checks);
+
+    // the check, then each result from the deepest
+    let mut evaluated = check.iter().collect::<Vec<&Value>>();
+    match kept {
+        true => lowering.kept = results.clone(),
+        false => evaluated.extend(results.iter().rev()),
+    }
+    lowering.pending = evaluations(&evaluated);
+    if let Some(check) = &check {
+        lowering.eval(check, Some(0));
+        lowering.emit("is_safe", |stack| {
+            stack.insert(0, Value::new("is_safe_dest", Op::Arg))
+        });
+        lowering.emit("jumpi", |stack| drop(stack.drain(..2)));
+        lowering.body.push_str(revert);
+        let label = line("is_safe:", &lowering.comment());
+        lowering.body.push_str(&label);
+    }
+
+    if !kept {
+        // each result from the deepest is computed above the arguments and sunk below them
+        for (i, result) in results.iter().enumerate().rev() {
+            lowering.kept.push(result.clone());
+            lowering.eval(result, Some(0));
+            let live = lowering.stack.len() - (results.len() - i);
+            if i > 0 && live > 0 {
+                lowering.emit(&format!("swap{}", live), |stack| stack.swap(0, live));
+            }
+        }
+    }
+    assert_eq!(
+        lowering.stack, results,
+        "{} leaves its arguments",
+        definition.name
+    );
+
+    lowering.body
+}
+
+struct Lowering<'a> {
+    masks: &'a dyn Fn(&str) -> Option<String>,
+    /// The values on the stack, top first.
+    stack: Vec<Value>,
+    /// The evaluations still to come, each a use of the value.
+    pending: Vec<Value>,
+    /// The values left on the stack as the results.
+    kept: Vec<Value>,
+    body: String,
+}
+
+impl Lowering<'_> {
+    /// The value of `expr`, its comparisons named `compared`.
+    fn value(&self, expr: &Expr, compared: &str) -> Value {
+        let value = |expr: &Expr| self.value(expr, compared);
+        match expr {
+            Expr::Arg(name) => Value::new(*name, Op::Arg),
+            Expr::Lit(lit) => match (self.masks)(lit) {
+                Some(mask) => Value::new("mask", Op::Push(mask)),
+                None => Value::new(lit.clone(), Op::Push(lit.clone())),
+            },
+            Expr::And(a, b) => {
+                let (a, b) = (value(a), value(b));
+                Value::binary(format!("masked_{}", a.name), "and", a, b)
+            }
+            Expr::Le(a, b) => self.at_most(value(a), b, compared),
+        }
+    }
+
+    /// Whether `a` is at most `bound`, named `compared`.
+    fn at_most(&self, a: Value, bound: &Expr, compared: &str) -> Value {
+        match bound {
+            // a value fits in a mask if masking leaves it unchanged
+            Expr::Lit(lit) if (self.masks)(lit).is_some() => {
+                let masked = Value::binary(
+                    format!("masked_{}", a.name),
+                    "and",
+                    a.clone(),
+                    self.value(bound, compared),
+                );
+                Value::binary(compared, "eq", masked, a)
+            }
+            Expr::Lit(lit) => {
+                let bound = Value::new("bound", Op::Push(successor(lit)));
+                Value::binary(compared, "lt", bound, a)
+            }
+            bound => {
+                let exceeds = Value::binary("exceeds", "gt", self.value(bound, compared), a);
+                Value::new(compared, Op::Unary("iszero", Box::new(exceeds)))
+            }
+        }
+    }
+
+    /// Whether every check holds, checking the arguments bounded by the same mask at once.
+    fn check(&self, checks: &[Expr]) -> Option<Value> {
+        let mut groups = Vec::<(&Expr, Vec<&'static str>)>::new();
+        let mut others = Vec::new();
+        for check in checks {
+            if let Expr::Le(a, bound) = check {
+                if let Expr::Arg(arg) = **a {
+                    match groups.iter_mut().find(|(b, _)| *b == &**bound) {
+                        Some((_, args)) => args.push(arg),
+                        None => groups.push((bound, vec![arg])),
+                    }
+                    continue;
+                }
+            }
+            others.push(self.value(check, "is_safe"));
+        }
+
+        let groups = groups.into_iter().map(|(bound, args)| {
+            // the bitwise or of the arguments from the deepest, `b_or_c` and then `bits`
+            let mut bits = Value::new(*args.last().unwrap(), Op::Arg);
+            for (i, arg) in args.iter().enumerate().rev().skip(1) {
+                let name = match i {
+                    0 => "bits".to_string(),
+                    _ => args[i..].join("_or_"),
+                };
+                bits = Value::binary(name, "or", bits, Value::new(*arg, Op::Arg));
+            }
+            self.at_most(bits, bound, "is_safe")
+        });
+        let checks = groups.chain(others).collect::<Vec<Value>>();
+        checks
+            .into_iter()
+            .reduce(|a, b| Value::binary("is_safe", "and", a, b))
+    }
+
+    /// Evaluates `value` onto the top of the stack, using it in place if it is found at `place`
+    /// and not used again.
+    fn eval(&mut self, value: &Value, place: Option<usize>) {
+        let pending = |lowering: &Self| lowering.pending.contains(value);
+        if let Some(i) = self.pending.iter().position(|v| v == value) {
+            self.pending.remove(i);
+        }
+
+        if let Some(position) = self.stack.iter().position(|v| v == value) {
+            if Some(position) != place || pending(self) || self.kept.contains(value) {
+                let value = value.clone();
+                self.emit(&format!("dup{}", position + 1), |stack| {
+                    stack.insert(0, value)
+                });
+            }
+            return;
+        }
+
+        let computed = value.clone();
+        match &value.op {
+            Op::Arg => panic!("`{}` is not on the stack", value.name),
+            Op::Push(code) => self.emit(code, |stack| stack.insert(0, computed)),
+            Op::Binary(code, a, b) => {
+                self.eval(a, Some(0));
+                // the first value is already in place below the second of a commutative opcode
+                self.eval(b, matches!(*code, "and" | "or" | "eq").then_some(1));
+                self.emit(code, |stack| drop(stack.splice(..2, [computed])));
+            }
+            Op::Unary(code, a) => {
+                self.eval(a, Some(0));
+                self.emit(code, |stack| stack[0] = computed);
+            }
+        }
+        // a copy is left for the evaluations to come, unless it is left as a result
+        if pending(self) && !self.kept.contains(value) {
+            let value = value.clone();
+            self.emit("dup1", |stack| stack.insert(0, value));
+        }
+    }
+
+    /// Emits `code`, changing the stack with `effect`.
+    fn emit(&mut self, code: &str, effect: impl FnOnce(&mut Vec<Value>)) {
+        effect(&mut self.stack);
+        let line = line(code, &self.comment());
+        self.body.push_str(&line);
+    }
+
+    fn comment(&self) -> String {
+        let names = self
+            .stack
+            .iter()
+            .map(|value| value.name.as_str())
+            .collect::<Vec<&str>>();
+        format!("[{}]", names.join(", "))
+    }
+}
+
+/// The literal one above the literal `lit`, `0x02` above `0x01`.
+fn successor(lit: &str) -> String {
+    let value = U256::from_hex(lit).unwrap_or_else(|| panic!("`{}` is not a literal", lit));
+    let digits = value.wrapping_add(U256::ONE).to_hex().split_off(2);
+    format!(
+        "0x{:0>width$}",
+        digits,
+        width = digits.len().div_ceil(2) * 2
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bodies() {
+        let mask = || Expr::lit("0xff");
+        let masks = |lit: &str| (lit == "0xff").then(|| "U8_MASK()".to_string());
+        let value = || Expr::Arg("value");
+
+        let pair = Definition {
+            name: "TO_U8_2".to_string(),
+            args: vec!["a", "b"],
+            checks: vec![Expr::Arg("a").le(mask()), Expr::Arg("b").le(mask())],
+            results: vec![("result", Expr::Arg("a")), ("b", Expr::Arg("b"))],
+        };
+        assert_eq!(
+            body(&pair, &masks, "        revert\n"),
+            "    // takes:               // [a, b]\n    \
+             dup2                    // [b, a, b]\n    \
+             dup2                    // [a, b, a, b]\n    \
+             or                      // [bits, a, b]\n    \
+             dup1                    // [bits, bits, a, b]\n    \
+             U8_MASK()               // [mask, bits, bits, a, b]\n    \
+             and                     // [masked_bits, bits, a, b]\n    \
+             eq                      // [is_safe, a, b]\n    \
+             is_safe                 // [is_safe_dest, is_safe, a, b]\n    \
+             jumpi                   // [a, b]\n        revert\n    \
+             is_safe:                // [a, b]\n",
+        );
+
+        let try_cast = Definition {
+            name: "TRY_TO_U8".to_string(),
+            args: vec!["value"],
+            checks: Vec::new(),
+            results: vec![
+                ("result", value().le(mask())),
+                ("masked_value", value().and(mask())),
+            ],
+        };
+        assert_eq!(
+            body(&try_cast, &masks, ""),
+            "    // takes:               // [value]\n    \
+             dup1                    // [value, value]\n    \
+             U8_MASK()               // [mask, value, value]\n    \
+             and                     // [masked_value, value]\n    \
+             swap1                   // [value, masked_value]\n    \
+             dup2                    // [masked_value, value, masked_value]\n    \
+             eq                      // [success, masked_value]\n",
+        );
+
+        // bounds other than masks are compared against
+        let bool = Definition {
+            name: "TO_BOOL".to_string(),
+            args: vec!["value"],
+            checks: vec![value().le(Expr::lit("0x01"))],
+            results: vec![("result", value())],
+        };
+        assert!(body(&bool, &masks, "").starts_with(
+            "    // takes:               // [value]\n    \
+             0x02                    // [bound, value]\n    \
+             dup2                    // [value, bound, value]\n    \
+             lt                      // [is_safe, value]\n",
+        ));
+        assert_eq!(successor("0xff"), "0x0100");
+    }
+}
@@ -1,184 +1,946 @@
-use std::fs::File;
-use std::io::prelude::*;
+use huffgen::diagnostics::{Diagnostic, Severity};
+use huffgen::{
+    abi, bench, config, diff, difftest, docs, library, listing, manifest, pack, profile, repl,
+    report, slots, testgen, EvmVersion, Generator, Layout, Library, OverflowRevert, Target,
+};
+
+const USAGE: &str = "\
+usage: huffgen [generate] [--check] [--manifest] [--lib <name>]... [--out <dir>]
+                [--differential <dir>] [--sizes <sizes>] [--revert <error|panic>]
+                [--evm-version <paris|shanghai|cancun>] [--layout <library|type|bundle>]
+                [--target <huff|yul>] [--no-timestamp] [--watch] [--deny-warnings]
+                [--message-format <human|json>]
+       huffgen slot <eip1967|erc7201> <id>
+       huffgen assemble <file> <macro> [--deploy] [--call <calldata>]
+       huffgen listing <file> [macro]
+       huffgen pack --layout <file> [--out <file>]
+       huffgen dispatcher --abi <file> [--strategy <strategy>] [--name <name>] [--out <file>]
+       huffgen report <file> [--format <markdown|json>]
+       huffgen bench [<config>]
+       huffgen gen-tests <file>... [--out <dir>]
+       huffgen docs [<file>...] [--out <dir>]
+       huffgen repl <file>";
 
 fn main() {
-    generate_libcast().unwrap();
-}
-
-fn generate_libcast() -> std::io::Result<()> {
-    let int_sizes = [
-        8, 16, 24, 32, 40, 48, 56, 64, 72, 80, 88, 96, 104, 112, 120, 128, 136, 144, 152, 160, 168,
-        176, 184, 192, 200, 208, 216, 224, 232, 240, 248, 256,
-    ]
-    .iter()
-    .map(|size| generate_cast(*size))
-    .collect::<Vec<String>>()
-    .join("\n");
-
-    let libcast = format!(
-        "{}{}{}{}",
-        HEADER,
-        ERROR_DEFINITION,
-        int_sizes,
-        MINI_MASK_DEFINITION,
-    );
+    let args = std::env::args().skip(1).collect::<Vec<String>>();
+
+    match args.first().map(String::as_str) {
+        Some("generate") => generate(&args[1..]),
+        Some("slot") => slot(&args[1..]),
+        Some("assemble") => assemble(&args[1..]),
+        Some("listing") => listing(&args[1..]),
+        Some("pack") => pack(&args[1..]),
+        Some("dispatcher") => dispatcher(&args[1..]),
+        Some("report") => report(&args[1..]),
+        Some("bench") => bench(&args[1..]),
+        Some("gen-tests") => gen_tests(&args[1..]),
+        Some("docs") => docs(&args[1..]),
+        Some("repl") => repl(&args[1..]),
+        _ => generate(&args),
+    }
+}
+
+/// Writes the libraries selected by `huffgen generate` to the output directory, `src` by default,
+/// with the differential tests of `libcast.huff` in a sibling `differential` directory, or with
+/// `--check` prints the diff of each file that is out of date and fails if there are any. With
+/// `--manifest`, each library is accompanied by its JSON manifest. `--evm-version` overrides the
+/// targeted hardfork of the config, `--layout` splits or bundles the files, and `--target yul`
+/// generates Yul functions in place of Huff macros. The headers of the files are stamped with the
+/// time of generation unless `--no-timestamp` is given. With `--watch`, the libraries are
+/// generated again whenever the config, the templates or the binary changes. Each file written is
+/// summarized, and a failure ends with its reason.
+///
+/// The `[generate]` table of the config sets the defaults of the flags, which override it.
+fn generate(args: &[String]) {
+    let generation = Generation::parse(args).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    });
+    if generation.watch {
+        watch(args, generation);
+    }
+
+    let generated = match generation.check {
+        true => check(&generation),
+        false => write(&generation).map(|(summaries, _)| {
+            if !generation.json {
+                print!("{}", summaries);
+            }
+        }),
+    };
+    if let Err(reason) = generated {
+        if !reason.is_empty() {
+            eprintln!("{}", reason);
+        }
+        std::process::exit(1);
+    }
+}
 
-    let mut f = File::create("src/libcast.huff")?;
+/// Prints the diff of each file of `generation` that is out of date, failing if there are any.
+fn check(generation: &Generation) -> Result<(), String> {
+    generation.report(&generation.diagnostics())?;
 
-    f.write_all(libcast.as_bytes())?;
+    let config = &generation.generator.options().config;
+    let profile = generation.located(profile::Profile::load(config))?;
+    let out = &generation.out;
+    let mut diffs = generation.located(generation.generator.check(out))?;
+    for (path, contents) in generation.tests() {
+        diffs.extend(generation.located(diff::against_file(&path, &contents))?);
+    }
+    if generation.manifests {
+        for name in generation.located(generation.generator.render())?.keys() {
+            let contents = generation.manifest(&out.join(name), &profile)?;
+            let path = out.join(manifest::file_name(name));
+            diffs.extend(generation.located(diff::against_file(&path, &contents))?);
+        }
+    }
 
+    if !diffs.is_empty() {
+        print!("{}", diffs.concat());
+        return Err(format!("{} generated files are out of date", diffs.len()));
+    }
     Ok(())
 }
 
-fn generate_cast(size: u16) -> String {
-    let name = format!("U{}", size);
-    let mut mask = String::with_capacity(size as usize / 8 + 2);
-    mask.push_str("0x");
-
-    for _ in 0..size / 8 {
-        mask.push_str("ff");
-    }
-
-    let mask_template = MASK_TEMPLATE
-        .replace("TYPENAME", &name)
-        .replace("TYPEMASK", &mask)
-        .replace("TYPESIZE", &size.to_string());
-
-    if size < 32 {
-        return mask_template;
-    }
-
-    let mini_mask_template = MINI_MASK_TEMPLATE
-        .replace("TYPENAME", &name)
-        .replace("TYPEMASK", &mask)
-        .replace("TYPESIZE", &size.to_string());
-
-    format!("{}{}", mask_template, mini_mask_template)
-}
-
-const HEADER: &'static str = r#"
-//  ------------------------------------------------------------------------------------------------
-//! # Casting Library
-//! 
-//! Provides macros for casting values.
-//! 
-//! Bit sizes supported range from 8 to 256 inclusive and are multiples of 8.
-//! 
-//! Items prefixed with `UNSAFE_` will not revert on overflow.
-//! 
-//! Items prefixed with `MINI_` will consume more runtime gas to the benefit of a smaller runtime
-//! size.
-//! 
-//! ## API
-//! 
-//! For a given type, `TYPENAME`:
-//! 
-//! - `TYPENAME_MASK` - Used to downcast a value to a smaller type.
-//! - `TO_TYPENAME` - Downcasts a value to a smaller type.
-//! - `UNSAFE_TO_TYPENAME` - Downcasts a value to a smaller type.
-//! - `MINI_TYPENAME_MASK` - Used to downcast a value to a smaller type.
-//! - `MINI_TO_TYPENAME` - Downcasts a value to a smaller type.
-//! - `UNSAFE_MINI_TO_TYPENAME` - Downcasts a value to a smaller type.
-//! 
-"#;
-
-const ERROR_DEFINITION: &'static str = r#"
-/// ## Overflow Error
-/// 
-/// Thrown when a cast overflows.
-#define error Overflow()
-"#;
-
-const MASK_TEMPLATE: &'static str = r#"
-/// ## TYPENAME Mask
-/// 
-/// Used to downcast a value to a smaller type.
-/// 
-/// ### Usage
-/// 
-/// ```huff
-/// #define macro MAIN() = takes (0) returns (0) {
-///     0x00 calldataload
-///     TYPENAME_MASK() and
-/// }
-/// ```
-#define macro TYPENAME_MASK() = takes (0) returns (1) { TYPEMASK }
-
-/// ## TYPENAME Cast
-/// 
-/// Downcasts a value to a smaller type.
-/// 
-/// The `UNSAFE_TO_TYPENAME` macro will not revert on overflow.
-#define macro TO_TYPENAME() = takes (1) returns (1) {
-    // takes:               // [value]
-    dup1                    // [value, value]
-    TYPENAME_MASK()         // [mask, value, value]
-    and                     // [masked_value, value]
-    dup2                    // [value, masked_value, value]
-    eq                      // [is_safe, value]
-    is_safe                 // [is_safe_dest, is_safe, value]
-    jumpi                   // [value]
-        __ERROR(Overflow)   // [err]
-        0x00                // [ptr, err]
-        mstore              // []
-        0x04                // [err_len]
-        0x00                // [ptr, err_len]
-        revert              // []
-    is_safe:                // [value]
-}"#;
-
-const MINI_MASK_TEMPLATE: &'static str = r#"
-
-/// ## Mini TYPENAME Mask
-/// 
-/// Used to downcast a value to a smaller type.
-/// 
-/// This consumes more runtime gas to the benefit of a smaller runtime size.
-/// 
-/// ### Usage
-/// 
-/// ```huff
-/// #define macro MAIN() = takes (0) returns (0) {
-///     0x00 calldataload
-///     MINI_TYPENAME_MASK() and
-/// }
-/// ```
-#define macro MINI_TYPENAME_MASK() = takes (0) returns (1) { __MINI_MASK(TYPESIZE) }
-
-/// ## Mini TYPENAME Cast
-/// 
-/// Downcasts a value to a smaller type.
-/// 
-/// This consumes more runtime gas to the benefit of a smaller runtime size.
-/// 
-/// The `UNSAFE_MINI_TO_TYPENAME` macro will not revert on overflow.
-#define macro UNSAFE_MINI_TO_TYPENAME() = takes (0) returns (0) {
-    // takes:               // [value]
-    MINI_TYPENAME_MASK()         // [mask, value]
-    and                     // [masked_value]
-}"#;
-
-const MINI_MASK_DEFINITION: &'static str = r#"
-/// ## Mini Mask
-///
-/// Used as a utility to generate the mask
-///
-/// The macro body is functionally equivalent to the following: `2 ** bitsize - 1`
-///
-/// ### Template Arguments
-///
-/// - `bitsize` - The number of bits to generate a mask for.
-///
-/// ### Usage
-///
-/// ```huff
-/// #define macro MINI_U32_MASK() = takes (0) returns (1) { __MINI_MASK(32)}
-/// ```
-#define macro __MINI_MASK(bitsize) = takes (0) returns (1) {
-    0x01        // [one]
-    dup1        // [one, one]
-    <bitsize>   // [bisize, one, one]
-    shl         // [mask_plus_one, one]
-    sub         // [mask]
-}
-"#;
+/// Writes the libraries of `generation`, their manifests and differential tests, returning the
+/// summary of the libraries and a line for each file that changed, counting the lines inserted
+/// and deleted.
+fn write(generation: &Generation) -> Result<(String, String), String> {
+    generation.report(&generation.diagnostics())?;
+
+    let tests = generation.tests();
+    let config = &generation.generator.options().config;
+    let profile = generation.located(profile::Profile::load(config))?;
+    let (files, timings) = generation.located(generation.generator.render_timed())?;
+    let manifests = match generation.manifests {
+        true => files.keys().cloned().collect(),
+        false => Vec::new(),
+    };
+    let out = &generation.out;
+
+    // the libraries are checked against the target before any is written
+    let violations = generation.located(profile::check(out, &files, &profile))?;
+    generation.report(
+        &violations
+            .into_iter()
+            .map(Diagnostic::warning)
+            .collect::<Vec<_>>(),
+    )?;
+
+    let mut changes = files
+        .iter()
+        .map(|(name, contents)| change(&out.join(name), contents))
+        .collect::<String>();
+    let start = std::time::Instant::now();
+    let written = generation.generator.write_rendered(out, files, &timings);
+    let summaries = generation.located(written)?;
+    let definitions = match generation.generator.target() {
+        Target::Huff => "macros",
+        Target::Yul => "functions",
+    };
+    let summaries = summary(&summaries, definitions, start.elapsed());
+
+    for (path, contents) in &tests {
+        changes.push_str(&change(path, contents));
+        let dir = path.parent().unwrap_or(std::path::Path::new("."));
+        let written = std::fs::create_dir_all(dir).and_then(|_| std::fs::write(path, contents));
+        generation.located(written)?;
+    }
+    for name in &manifests {
+        let contents = generation.manifest(&out.join(name), &profile)?;
+        let path = out.join(manifest::file_name(name));
+        changes.push_str(&change(&path, &contents));
+        generation.located(std::fs::write(path, contents))?;
+    }
+
+    Ok((summaries, changes))
+}
+
+/// A line counting the lines inserted and deleted by writing `contents` to `path`, timestamps
+/// aside, if any are.
+fn change(path: &std::path::Path, contents: &str) -> String {
+    let old = std::fs::read_to_string(path).unwrap_or_default();
+    let (insertions, deletions) = diff::stat(&unstamped(&old), &unstamped(contents));
+    match insertions + deletions {
+        0 => String::new(),
+        _ => format!("{} +{} -{}\n", path.display(), insertions, deletions),
+    }
+}
+
+/// A line for each of the written files of `summaries`, of its `definitions`, size, and the time
+/// it took to render and write, and a line of their totals.
+fn summary(
+    summaries: &[library::Summary],
+    definitions: &str,
+    elapsed: std::time::Duration,
+) -> String {
+    let width = summaries
+        .iter()
+        .map(|file| file.path.display().to_string().len())
+        .max();
+    let mut summary = String::new();
+    for file in summaries {
+        summary.push_str(&format!(
+            "{:<width$} {:>4} {} {:>8} bytes {:>10.1?}\n",
+            file.path.display(),
+            file.definitions,
+            definitions,
+            file.bytes,
+            file.elapsed,
+            width = width.unwrap_or_default(),
+        ));
+    }
+
+    let total = summaries.iter().map(|file| file.definitions).sum::<usize>();
+    let bytes = summaries.iter().map(|file| file.bytes).sum::<usize>();
+    summary.push_str(&format!(
+        "{} files, {} {}, {} bytes in {:.1?}\n",
+        summaries.len(),
+        total,
+        definitions,
+        bytes,
+        elapsed,
+    ));
+    summary
+}
+
+/// What `huffgen generate` generates, from the config and the flags overriding it.
+struct Generation {
+    generator: Generator,
+    out: std::path::PathBuf,
+    /// The directory the differential tests of `libcast.huff` are written to, if any.
+    differential: Option<std::path::PathBuf>,
+    manifests: bool,
+    check: bool,
+    watch: bool,
+    /// Whether warnings fail generation as errors do.
+    deny_warnings: bool,
+    /// Whether diagnostics are printed as JSON, one object a line.
+    json: bool,
+    /// Whether the sizes are given by `--sizes` rather than the config.
+    sizes_flag: bool,
+}
+
+impl Generation {
+    /// Reads the config and the flags `args`, failing with the message to print.
+    fn parse(args: &[String]) -> Result<Self, String> {
+        // errors of the config are located in it
+        let src = std::fs::read_to_string(config::CONFIG_PATH).unwrap_or_default();
+        let located = |message: String| {
+            Diagnostic::from_message(&message, &src)
+                .render()
+                .trim_end()
+                .to_string()
+        };
+        let config = config::load().map_err(|e| located(e.to_string()))?;
+        let defaults = Generator::from_config(config.clone()).and_then(|generator| {
+            let out = config::generate_string(&config, "out")?.unwrap_or("src");
+            let flag = |key: &str, default: bool| match config::generate_value(&config, key)? {
+                None => Ok(default),
+                Some(config::Value::Boolean(flag)) => Ok(*flag),
+                Some(_) => Err(config::invalid(format!(
+                    "`generate.{}` must be a boolean",
+                    key
+                ))),
+            };
+            let differential = config::generate_string(&config, "differential")?;
+            let (manifests, timestamp) = (flag("manifest", false)?, flag("timestamp", true)?);
+            Ok((
+                generator,
+                std::path::PathBuf::from(out),
+                differential.map(std::path::PathBuf::from),
+                manifests,
+                timestamp,
+            ))
+        });
+        let (mut generator, mut out, mut differential, mut manifests, mut timestamp) =
+            defaults.map_err(|e| located(format!("{}: {}", config::CONFIG_PATH, e)))?;
+        let (mut libraries, mut sizes) = (Vec::new(), Vec::new());
+        let (mut check, mut watch, mut deny_warnings, mut json) = (false, false, false, false);
+
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--check" => {
+                    check = true;
+                    continue;
+                }
+                "--manifest" => {
+                    manifests = true;
+                    continue;
+                }
+                "--no-timestamp" => {
+                    timestamp = false;
+                    continue;
+                }
+                "--watch" => {
+                    watch = true;
+                    continue;
+                }
+                "--deny-warnings" => {
+                    deny_warnings = true;
+                    continue;
+                }
+                _ => {}
+            }
+
+            match (arg.as_str(), args.next()) {
+                ("--evm-version", Some(name)) => match EvmVersion::parse(name) {
+                    Some(version) => generator = generator.with_evm_version(version),
+                    None => {
+                        return Err(format!(
+                            "unknown evm version `{}`, expected `paris`, `shanghai` or `cancun`",
+                            name
+                        ))
+                    }
+                },
+                ("--layout", Some(name)) => match Layout::from_name(name) {
+                    Some(layout) => generator = generator.with_layout(layout),
+                    None => {
+                        return Err(format!(
+                            "unknown layout `{}`, expected `library`, `type` or `bundle`",
+                            name
+                        ))
+                    }
+                },
+                ("--message-format", Some(format)) => match format.as_str() {
+                    "human" => json = false,
+                    "json" => json = true,
+                    _ => {
+                        let expected = "expected `human` or `json`";
+                        return Err(format!("unknown message format `{}`, {}", format, expected));
+                    }
+                },
+                ("--lib", Some(name)) => match Library::from_name(name) {
+                    Some(library) => libraries.push(library),
+                    None => return Err(format!("unknown library `{}`", name)),
+                },
+                ("--out", Some(dir)) => out = dir.into(),
+                ("--differential", Some(dir)) => differential = Some(dir.into()),
+                ("--target", Some(name)) => match Target::from_name(name) {
+                    Some(target) => generator = generator.with_target(target),
+                    None => {
+                        return Err(format!(
+                            "unknown target `{}`, expected `huff` or `yul`",
+                            name
+                        ))
+                    }
+                },
+                ("--revert", Some(name)) => match OverflowRevert::from_name(name) {
+                    Some(revert) => generator = generator.with_overflow_revert(revert),
+                    None => {
+                        let expected = "expected `error` or `panic`";
+                        return Err(format!("unknown revert `{}`, {}", name, expected));
+                    }
+                },
+                ("--sizes", Some(list)) => sizes = library::parse_sizes(list)?,
+                _ => return Err(USAGE.to_string()),
+            }
+        }
+        if check && watch {
+            return Err(USAGE.to_string());
+        }
+        if manifests && generator.target() != Target::Huff {
+            return Err("error: manifests are only generated for the huff target".to_string());
+        }
+
+        if !libraries.is_empty() {
+            generator = generator.with_libraries(&libraries);
+        }
+        if !sizes.is_empty() {
+            generator = generator.with_sizes(&sizes);
+        }
+        if timestamp {
+            generator = generator.with_timestamp(now());
+        }
+
+        let sizes_flag = !sizes.is_empty();
+        Ok(Generation {
+            generator,
+            out,
+            differential,
+            manifests,
+            check,
+            watch,
+            deny_warnings,
+            json,
+            sizes_flag,
+        })
+    }
+
+    /// The diagnostics of the generator, located in the config unless the sizes they come from
+    /// are given by `--sizes`.
+    fn diagnostics(&self) -> Vec<Diagnostic> {
+        let src = std::fs::read_to_string(config::CONFIG_PATH).unwrap_or_default();
+        let diagnostics = self.generator.diagnostics().into_iter();
+        match self.sizes_flag {
+            false => diagnostics
+                .map(|diagnostic| diagnostic.locate(&src))
+                .collect(),
+            true => diagnostics
+                .map(|diagnostic| Diagnostic {
+                    key: None,
+                    ..diagnostic
+                })
+                .collect(),
+        }
+    }
+
+    /// Prints `diagnostics`, failing if any is an error, or a warning with `--deny-warnings`. The
+    /// failure holds the reason to print after warnings, and nothing after errors, which are
+    /// their own reason.
+    fn report(&self, diagnostics: &[Diagnostic]) -> Result<(), String> {
+        for diagnostic in diagnostics {
+            match self.json {
+                true => print!("{}", diagnostic.to_json()),
+                false => eprint!("{}", diagnostic.render()),
+            }
+        }
+
+        let errors = diagnostics
+            .iter()
+            .filter(|diagnostic| diagnostic.severity == Severity::Error)
+            .count();
+        let warnings = diagnostics.len() - errors;
+        match errors {
+            0 if self.deny_warnings && warnings > 0 => Err(format!(
+                "error: aborting due to {} warning{} denied by `--deny-warnings`",
+                warnings,
+                if warnings == 1 { "" } else { "s" }
+            )),
+            0 => Ok(()),
+            _ => Err(String::new()),
+        }
+    }
+
+    /// The value of `result`, or its error reported, located in the config if it is about it.
+    fn located<T>(&self, result: std::io::Result<T>) -> Result<T, String> {
+        result.map_err(|e| {
+            let src = std::fs::read_to_string(config::CONFIG_PATH).unwrap_or_default();
+            let reported = self.report(&[Diagnostic::located(&e.to_string(), &src)]);
+            reported.err().unwrap_or_default()
+        })
+    }
+
+    /// The manifest of the library at `file`, failing if it does not compile.
+    fn manifest(
+        &self,
+        file: &std::path::Path,
+        profile: &profile::Profile,
+    ) -> Result<String, String> {
+        let manifest = manifest::render(file, profile);
+        let error = |e: huffgen::huff::Error| format!("{}: {}", file.display(), e);
+        self.located(manifest.map_err(|e| config::invalid(error(e))))
+    }
+
+    /// The differential tests of `libcast.huff`, by path, if they are asked for and it is
+    /// generated.
+    fn tests(&self) -> Vec<(std::path::PathBuf, String)> {
+        let huff = self.generator.target() == Target::Huff;
+        let Some(dir) = &self.differential else {
+            return Vec::new();
+        };
+        if !huff || !self.generator.libraries().contains(&Library::Cast) {
+            return Vec::new();
+        }
+
+        let library = relative(dir, &self.out.join("libcast.huff"));
+        difftest::render(self.generator.options(), &library)
+            .into_iter()
+            .map(|(name, contents)| (dir.join(name), contents))
+            .collect()
+    }
+}
+
+/// The path of `file` from the directory `dir`, both from the working directory unless absolute.
+fn relative(dir: &std::path::Path, file: &std::path::Path) -> String {
+    use std::path::Component;
+
+    let cwd = std::env::current_dir().unwrap_or_default();
+    let components = |path: &std::path::Path| {
+        let mut components = Vec::new();
+        for component in cwd.join(path).components() {
+            match component {
+                Component::ParentDir => {
+                    components.pop();
+                }
+                Component::CurDir => {}
+                component => components.push(component.as_os_str().to_owned()),
+            }
+        }
+        components
+    };
+    let (dir, file) = (components(dir), components(file));
+    let common = dir.iter().zip(&file).take_while(|(a, b)| a == b).count();
+
+    let mut path = vec!["..".to_string(); dir.len() - common];
+    path.extend(
+        file[common..]
+            .iter()
+            .map(|c| c.to_string_lossy().into_owned()),
+    );
+    path.join("/")
+}
+
+/// How often `huffgen generate --watch` looks for changes.
+const WATCH_INTERVAL: std::time::Duration = std::time::Duration::from_millis(300);
+
+/// The crate `huffgen` is built from, whose `codegen` sources hold the templates.
+const CRATE_DIR: &str = env!("CARGO_MANIFEST_DIR");
+
+/// Generates the libraries of `generation` as `huffgen generate` does, then again each time the
+/// config changes, printing how many lines of each file changed from what was on disk, or why
+/// generating failed. The templates are compiled into the binary, so when it runs from the crate
+/// it is built from, it is rebuilt when their sources change, and when it is rebuilt the new
+/// binary takes over, with the same `args`.
+fn watch(args: &[String], generation: Generation) -> ! {
+    let exe = std::env::current_exe().unwrap();
+    let modified = |path: &std::path::Path| std::fs::metadata(path).and_then(|m| m.modified()).ok();
+    // the last change to the sources of the templates, if the binary is built from them
+    let templates = || {
+        let sources = std::path::Path::new(CRATE_DIR).join("codegen");
+        let entries = std::fs::read_dir(sources).ok()?.filter_map(Result::ok);
+        let built = exe.starts_with(CRATE_DIR);
+        built.then(|| entries.filter_map(|entry| modified(&entry.path())).max())?
+    };
+    let (mut config, binary) = (modified(config::CONFIG_PATH.as_ref()), modified(&exe));
+    let mut sources = templates();
+
+    let mut generation = Ok(generation);
+    loop {
+        match generation.and_then(|generation| write(&generation)) {
+            Ok((_, changes)) if changes.is_empty() => println!("no changes"),
+            Ok((_, changes)) => print!("{}", changes),
+            Err(reason) if reason.is_empty() => {}
+            Err(reason) => eprintln!("{}", reason),
+        }
+        println!("watching {} for changes", config::CONFIG_PATH);
+
+        loop {
+            std::thread::sleep(WATCH_INTERVAL);
+            if modified(&exe) != binary {
+                // wait for the build to finish writing the binary
+                std::thread::sleep(WATCH_INTERVAL * 3);
+                println!("{} changed, restarting", exe.display());
+                let status = std::process::Command::new(&exe)
+                    .arg("generate")
+                    .args(args)
+                    .status();
+                std::process::exit(status.map_or(1, |status| status.code().unwrap_or(1)));
+            }
+            if templates() != sources {
+                sources = templates();
+                println!(
+                    "{}/codegen changed, rebuilding {}",
+                    CRATE_DIR,
+                    exe.display()
+                );
+                rebuild();
+            }
+            if modified(config::CONFIG_PATH.as_ref()) != config {
+                config = modified(config::CONFIG_PATH.as_ref());
+                break;
+            }
+        }
+        generation = Generation::parse(args);
+    }
+}
+
+/// Rebuilds `huffgen` from its crate with the profile it was built with, reporting a failed build,
+/// whose errors cargo prints.
+fn rebuild() {
+    let cargo = std::env::var("CARGO").unwrap_or_else(|_| "cargo".to_string());
+    let manifest = std::path::Path::new(CRATE_DIR).join("Cargo.toml");
+    let mut build = std::process::Command::new(cargo);
+    build.args(["build", "--bin", "huffgen", "--manifest-path"]);
+    build.arg(manifest);
+    if !cfg!(debug_assertions) {
+        build.arg("--release");
+    }
+    if !build.status().is_ok_and(|status| status.success()) {
+        eprintln!("error: could not rebuild huffgen, still generating with the running binary");
+    }
+}
+
+/// The `contents` of a generated file without its timestamp, as regenerating restamps every file.
+fn unstamped(contents: &str) -> String {
+    contents
+        .split_inclusive('\n')
+        .filter(|line| !line.starts_with("// @timestamp "))
+        .collect()
+}
+
+/// Seconds since the Unix epoch, or `SOURCE_DATE_EPOCH` when set, for reproducible builds.
+fn now() -> u64 {
+    match std::env::var("SOURCE_DATE_EPOCH")
+        .ok()
+        .and_then(|epoch| epoch.parse().ok())
+    {
+        Some(epoch) => epoch,
+        None => std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs()),
+    }
+}
+
+/// Prints the slot of `huffgen slot <eip1967|erc7201> <id>`.
+fn slot(args: &[String]) {
+    let derivation = args
+        .first()
+        .and_then(|name| slots::Derivation::from_name(name));
+
+    match (derivation, args.get(1), args.len()) {
+        (Some(derivation), Some(id), 2) => println!("{}", slots::hex(&derivation.slot(id))),
+        _ => {
+            eprintln!("usage: huffgen slot <eip1967|erc7201> <id>");
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Prints the runtime bytecode of `huffgen assemble <file> <macro>`, the macro invoked from an
+/// otherwise empty entry, for the opcode profile of the config. With `--deploy` the deployment
+/// bytecode running the library's `CONSTRUCTOR` is printed instead, and with `--call <calldata>`
+/// it is deployed on the embedded EVM and called, printing the outcome and failing if the call
+/// halts with an exception.
+fn assemble(args: &[String]) {
+    let usage = "usage: huffgen assemble <file> <macro> [--deploy] [--call <calldata>]";
+    let (mut positional, mut deploy, mut calldata) = (Vec::new(), false, None);
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--deploy" => deploy = true,
+            "--call" => match iter.next().and_then(|hex| repl::decode_hex(hex)) {
+                Some(data) => calldata = Some(data),
+                None => {
+                    eprintln!("{}", usage);
+                    std::process::exit(1);
+                }
+            },
+            _ => positional.push(arg),
+        }
+    }
+    let (file, invocation) = match positional[..] {
+        [file, invocation] => (std::path::Path::new(file), invocation),
+        _ => {
+            eprintln!("{}", usage);
+            std::process::exit(1);
+        }
+    };
+
+    let invocation = if invocation.contains('(') {
+        invocation.clone()
+    } else {
+        format!("{}()", invocation)
+    };
+
+    let compiled = profile().map_err(|e| e.to_string()).and_then(|profile| {
+        listing::wrap(file, &invocation)
+            .and_then(|program| {
+                let code = match deploy || calldata.is_some() {
+                    true => profile::compile_deployment(program.clone(), listing::ENTRY, &profile),
+                    false => profile::compile(program.clone(), listing::ENTRY, &profile),
+                };
+                code.map(|code| (program, code))
+            })
+            .map_err(|e| e.to_string())
+    });
+    let (program, code) = compiled.unwrap_or_else(|e| fail(e));
+
+    match calldata.map(|calldata| repl::deploy_and_call(&program, &code, &calldata)) {
+        Some(Ok(report)) => print!("{}", report),
+        Some(Err(report)) => {
+            print!("{}", report);
+            std::process::exit(1);
+        }
+        None => println!(
+            "0x{}",
+            code.iter()
+                .map(|b| format!("{:02x}", b))
+                .collect::<String>()
+        ),
+    }
+}
+
+/// Prints the annotated listings of `huffgen listing <file> [macro]`, every macro of the library
+/// without template arguments if no macro is given.
+fn listing(args: &[String]) {
+    let (file, invocation) = match args {
+        [file] => (std::path::Path::new(file), None),
+        [file, invocation] => (std::path::Path::new(file), Some(invocation.as_str())),
+        _ => {
+            eprintln!("usage: huffgen listing <file> [macro]");
+            std::process::exit(1);
+        }
+    };
+
+    let listings = profile()
+        .map_err(|e| e.to_string())
+        .and_then(|profile| listing::render(file, invocation, &profile).map_err(|e| e.to_string()));
+    match listings {
+        Ok(listings) => print!("{}", listings),
+        Err(e) => fail(e),
+    }
+}
+
+/// Prints, or writes to `--out`, the accessors of `huffgen pack --layout <file>`.
+fn pack(args: &[String]) {
+    let (layout, out) = match args {
+        [flag, layout] if flag == "--layout" => (layout, None),
+        [flag, layout, out_flag, out] if flag == "--layout" && out_flag == "--out" => {
+            (layout, Some(out))
+        }
+        _ => {
+            eprintln!("usage: huffgen pack --layout <file> [--out <file>]");
+            std::process::exit(1);
+        }
+    };
+
+    let accessors = match pack::render(std::path::Path::new(layout)) {
+        Ok(accessors) => accessors,
+        Err(e) => fail(e),
+    };
+
+    match out {
+        Some(out) => {
+            if let Err(e) = std::fs::write(out, accessors) {
+                fail(format!("{}: {}", out, e));
+            }
+        }
+        None => print!("{}", accessors),
+    }
+}
+
+/// Prints, or writes to `--out`, the dispatcher of `huffgen dispatcher --abi <file>`, named after
+/// the file unless `--name` is given, with the `linear` strategy unless `--strategy` is given.
+fn dispatcher(args: &[String]) {
+    let usage = "usage: huffgen dispatcher --abi <file> [--strategy <strategy>] [--name <name>] \
+                 [--out <file>]";
+    let (mut abi_path, mut strategy, mut name, mut out) = (None, "linear", None, None);
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match (arg.as_str(), args.next()) {
+            ("--abi", Some(path)) => abi_path = Some(std::path::Path::new(path)),
+            ("--strategy", Some(value)) => strategy = value,
+            ("--name", Some(value)) => name = Some(value.clone()),
+            ("--out", Some(path)) => out = Some(path),
+            _ => {
+                eprintln!("{}", usage);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    let Some(abi_path) = abi_path else {
+        eprintln!("{}", usage);
+        std::process::exit(1);
+    };
+    let name = name.unwrap_or_else(|| {
+        let stem = abi_path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or_default();
+        abi::macro_name(stem.split('.').next().unwrap_or_default())
+    });
+
+    let dispatcher = profile().and_then(|profile| {
+        let src = std::fs::read_to_string(abi_path)
+            .map_err(|e| std::io::Error::new(e.kind(), format!("{}: {}", abi_path.display(), e)))?;
+        abi::render_dispatcher(&src, &name, strategy, &profile)
+    });
+
+    match (dispatcher, out) {
+        (Ok(dispatcher), Some(out)) => {
+            if let Err(e) = std::fs::write(out, dispatcher) {
+                fail(format!("{}: {}", out, e));
+            }
+        }
+        (Ok(dispatcher), None) => print!("{}", dispatcher),
+        (Err(e), _) => fail(e),
+    }
+}
+
+/// Prints the gas and size report of `huffgen report <file>`, as a markdown table unless `--format`
+/// is `json`.
+fn report(args: &[String]) {
+    let (file, format) = match args {
+        [file] => (file, "markdown"),
+        [file, flag, format] if flag == "--format" => (file, format.as_str()),
+        _ => {
+            eprintln!("usage: huffgen report <file> [--format <markdown|json>]");
+            std::process::exit(1);
+        }
+    };
+    let render = match format {
+        "markdown" => report::markdown,
+        "json" => report::json,
+        _ => fail(format!(
+            "unknown format `{}`, expected `markdown` or `json`",
+            format
+        )),
+    };
+
+    let entries = profile().map_err(|e| e.to_string()).and_then(|profile| {
+        report::measure(std::path::Path::new(file), &profile).map_err(|e| e.to_string())
+    });
+    match entries {
+        Ok(entries) => print!("{}", render(&entries)),
+        Err(e) => fail(e),
+    }
+}
+
+/// Prints the benchmarks of `huffgen bench [<config>]`, `bench.toml` by default, comparing the
+/// gas and size of each operation of the library to its baselines on the embedded EVM.
+fn bench(args: &[String]) {
+    let file = match args {
+        [] => "bench.toml",
+        [file] => file.as_str(),
+        _ => {
+            eprintln!("usage: huffgen bench [<config>]");
+            std::process::exit(1);
+        }
+    };
+
+    let operations = std::fs::read_to_string(file)
+        .map_err(|e| e.to_string())
+        .and_then(|src| config::parse(&src))
+        .and_then(|config| bench::parse(&config))
+        .unwrap_or_else(|e| fail(format!("{}: {}", file, e)));
+    let measurements = profile()
+        .map_err(|e| e.to_string())
+        .and_then(|profile| bench::measure(&operations, &profile));
+    match measurements {
+        Ok(measurements) => print!("{}", bench::markdown(&measurements)),
+        Err(e) => fail(e),
+    }
+}
+
+/// Writes the Foundry test suites of `huffgen gen-tests <file>...` to `--out`, `test/huff` by
+/// default, a harness and a test contract for each library.
+fn gen_tests(args: &[String]) {
+    let usage = "usage: huffgen gen-tests <file>... [--out <dir>]";
+    let (mut files, mut out) = (Vec::new(), std::path::PathBuf::from("test/huff"));
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match (arg.as_str(), arg.starts_with("--")) {
+            ("--out", _) => match args.next() {
+                Some(dir) => out = dir.into(),
+                None => files.clear(),
+            },
+            (_, false) => files.push(std::path::PathBuf::from(arg)),
+            (_, true) => files.clear(),
+        }
+    }
+    if files.is_empty() {
+        eprintln!("{}", usage);
+        std::process::exit(1);
+    }
+
+    let profile = profile().unwrap_or_else(|e| fail(e));
+    for file in &files {
+        let suite = testgen::render(file, &out, &profile)
+            .unwrap_or_else(|e| fail(format!("{}: {}", file.display(), e)));
+        for (name, contents) in suite {
+            if let Err(e) = write_file(&out.join(name), &contents) {
+                fail(e);
+            }
+        }
+    }
+}
+
+/// Writes `contents` to `path`, creating its directory, failing with the path.
+fn write_file(path: &std::path::Path, contents: &str) -> std::io::Result<()> {
+    let dir = path.parent().unwrap_or(std::path::Path::new("."));
+    std::fs::create_dir_all(dir)
+        .and_then(|_| std::fs::write(path, contents))
+        .map_err(|e| std::io::Error::new(e.kind(), format!("{}: {}", path.display(), e)))
+}
+
+/// Writes the mdBook of `huffgen docs [<file>...]` to `--out`, `book` by default, a page for each
+/// library, every library of `src` if none are given.
+fn docs(args: &[String]) {
+    let usage = "usage: huffgen docs [<file>...] [--out <dir>]";
+    let (mut files, mut out) = (Vec::new(), std::path::PathBuf::from("book"));
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match (arg.as_str(), arg.starts_with("--")) {
+            ("--out", _) if args.len() > 0 => out = args.next().unwrap().into(),
+            (_, false) => files.push(std::path::PathBuf::from(arg)),
+            (_, true) => {
+                eprintln!("{}", usage);
+                std::process::exit(1);
+            }
+        }
+    }
+    if files.is_empty() {
+        files = huff_files("src")
+            .unwrap_or_else(|e| fail(format!("src: {}, give the libraries to document", e)));
+    }
+
+    let files = files
+        .iter()
+        .map(|file| file.as_path())
+        .collect::<Vec<&std::path::Path>>();
+    let book = profile()
+        .map_err(|e| e.to_string())
+        .and_then(|profile| docs::render(&files, &profile).map_err(|e| e.to_string()));
+    let book = book.unwrap_or_else(|e| fail(e));
+    for (name, contents) in book {
+        if let Err(e) = write_file(&out.join(name), &contents) {
+            fail(e);
+        }
+    }
+}
+
+/// The Huff files of `dir`, sorted.
+fn huff_files(dir: &str) -> std::io::Result<Vec<std::path::PathBuf>> {
+    let mut files = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path
+            .extension()
+            .is_some_and(|extension| extension == "huff")
+        {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Runs `huffgen repl <file>`, executing macros of a library interactively.
+fn repl(args: &[String]) {
+    match args {
+        [file] => {
+            let run = profile().and_then(|profile| repl::run(std::path::Path::new(file), &profile));
+            if let Err(e) = run {
+                fail(e);
+            }
+        }
+        _ => {
+            eprintln!("usage: huffgen repl <file>");
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Returns the opcode profile of the config, failing if the config does not load or describes an
+/// invalid profile.
+fn profile() -> std::io::Result<profile::Profile> {
+    let config = config::load()?;
+    profile::Profile::load(&config)
+        .map_err(|e| config::invalid(format!("{}: {}", config::CONFIG_PATH, e)))
+}
+
+/// Exits with `error` reported as a diagnostic, located in the config if it is about it.
+fn fail(error: impl std::fmt::Display) -> ! {
+    let src = std::fs::read_to_string(config::CONFIG_PATH).unwrap_or_default();
+    eprint!(
+        "{}",
+        Diagnostic::from_message(&error.to_string(), &src).render()
+    );
+    std::process::exit(1)
+}
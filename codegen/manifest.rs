@@ -0,0 +1,299 @@
+//! Machine-readable manifests of generated libraries, for editors, documentation generators and
+//! audit tooling.
+
+use std::collections::{BTreeSet, HashMap};
+use std::path::Path;
+
+use crate::docs;
+use crate::huff::{self, Arg, Program, Stmt};
+use crate::listing::{wrap, ENTRY};
+use crate::profile::Profile;
+use crate::report::{self, Entry};
+
+/// The file name of the manifest of the library `file_name`, e.g. `libcast.json` for
+/// `libcast.huff`.
+pub fn file_name(file_name: &str) -> String {
+    format!(
+        "{}.json",
+        file_name.strip_suffix(".huff").unwrap_or(file_name)
+    )
+}
+
+/// The metadata of a macro defined by a library.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Macro {
+    pub name: String,
+    /// The names of its template arguments.
+    pub params: Vec<String>,
+    pub takes: usize,
+    pub returns: usize,
+    /// The stack comments of its body, in order.
+    pub stack: Vec<String>,
+    /// The `@pre` conditions of its doc comment, required of its arguments.
+    pub pre: Vec<String>,
+    /// The `@post` conditions of its doc comment, holding whenever it completes.
+    pub post: Vec<String>,
+    /// The signatures of the errors it may revert with, sorted.
+    pub errors: Vec<String>,
+    /// The runtime size, without template arguments.
+    pub size: Option<usize>,
+    /// The gas used, without template arguments and where it completes with the arguments
+    /// `huffgen report` passes.
+    pub gas: Option<u64>,
+}
+
+/// Reads the metadata of each macro the library at `file` defines, in the order they are defined,
+/// leaving out those of the libraries it includes. Sizes and gas are measured for `profile`.
+pub fn macros(file: &Path, profile: &Profile) -> Result<Vec<Macro>, huff::Error> {
+    let program = wrap(file, "")?;
+    let src = std::fs::read_to_string(file).map_err(|e| huff::Error { msg: e.to_string() })?;
+    let comments = docs::doc_comments(&src);
+    let measured = report::measure(file, profile)?
+        .into_iter()
+        .map(|entry| (entry.name.clone(), entry))
+        .collect::<HashMap<String, Entry>>();
+
+    let mut definitions = program
+        .macros
+        .keys()
+        .filter(|name| *name != ENTRY)
+        .filter_map(|name| Some((src.find(&format!("#define macro {}(", name))?, name)))
+        .collect::<Vec<(usize, &String)>>();
+    definitions.sort();
+
+    Ok(definitions
+        .into_iter()
+        .map(|(position, name)| {
+            let definition = &program.macros[name];
+            let entry = measured.get(name);
+            let comment = comments.get(name.as_str()).map_or(&[][..], Vec::as_slice);
+
+            Macro {
+                name: name.clone(),
+                params: definition.params.clone(),
+                takes: definition.takes,
+                returns: definition.returns,
+                stack: stack_comments(&src[position..])
+                    .into_iter()
+                    .map(String::from)
+                    .collect(),
+                pre: conditions(comment, "@pre "),
+                post: conditions(comment, "@post "),
+                errors: errors(&program, name).into_iter().collect(),
+                size: entry.map(|entry| entry.size),
+                gas: entry.and_then(|entry| entry.gas),
+            }
+        })
+        .collect())
+}
+
+/// Renders the manifest of the library at `file`, listing each macro it defines in the order they
+/// are defined, with its template arguments, `takes` and `returns` counts, stack comments, `@pre`
+/// and `@post` conditions and the errors it may revert with. Macros without template arguments also
+/// hold their size and gas, as measured by `huffgen report` for `profile`.
+pub fn render(file: &Path, profile: &Profile) -> Result<String, huff::Error> {
+    let macros = macros(file, profile)?
+        .into_iter()
+        .map(|definition| {
+            format!(
+                concat!(
+                    "    {{\n",
+                    "      \"name\": {},\n",
+                    "      \"params\": [{}],\n",
+                    "      \"takes\": {},\n",
+                    "      \"returns\": {},\n",
+                    "      \"stack\": [{}],\n",
+                    "      \"pre\": [{}],\n",
+                    "      \"post\": [{}],\n",
+                    "      \"errors\": [{}],\n",
+                    "      \"bytes\": {},\n",
+                    "      \"gas\": {}\n",
+                    "    }}",
+                ),
+                string(&definition.name),
+                list(&definition.params),
+                definition.takes,
+                definition.returns,
+                list(&definition.stack),
+                list(&definition.pre),
+                list(&definition.post),
+                list(&definition.errors),
+                optional(definition.size),
+                optional(definition.gas),
+            )
+        })
+        .collect::<Vec<String>>();
+
+    let library = file
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or_default();
+    Ok(format!(
+        "{{\n  \"library\": {},\n  \"macros\": [\n{}\n  ]\n}}\n",
+        string(library),
+        macros.join(",\n"),
+    ))
+}
+
+/// The stack comments of the macro definition at the start of `src`, in order.
+fn stack_comments(src: &str) -> Vec<&str> {
+    let header = src.lines().next().unwrap_or_default();
+    let body = if header.trim_end().ends_with('}') {
+        vec![header]
+    } else {
+        src.lines()
+            .skip(1)
+            .take_while(|line| *line != "}")
+            .collect()
+    };
+
+    body.into_iter()
+        .filter_map(|line| {
+            // the last comment of a line, after any `// takes:`
+            let comment = line[line.rfind("//")? + 2..].trim();
+            comment.starts_with('[').then_some(comment)
+        })
+        .collect()
+}
+
+/// The conditions of the doc comment `lines` of a macro tagged `tag`, in order.
+fn conditions(lines: &[&str], tag: &str) -> Vec<String> {
+    lines
+        .iter()
+        .filter_map(|line| line.strip_prefix(tag))
+        .map(String::from)
+        .collect()
+}
+
+/// The signatures of the errors `name` and the macros it invokes may revert with, sorted.
+fn errors(program: &Program, name: &str) -> BTreeSet<String> {
+    let mut errors = BTreeSet::new();
+    let mut seen = BTreeSet::new();
+    let mut pending = vec![name.to_string()];
+
+    while let Some(name) = pending.pop() {
+        if !seen.insert(name.clone()) {
+            continue;
+        }
+        let Some(definition) = program.macros.get(&name) else {
+            continue;
+        };
+        for stmt in &definition.body {
+            let (callee, args) = match stmt {
+                Stmt::Call(callee, args) | Stmt::Builtin(callee, args) => (callee, args),
+                _ => continue,
+            };
+            pending.push(callee.clone());
+            collect_errors(program, callee, args, &mut errors, &mut pending);
+        }
+    }
+
+    errors
+}
+
+/// Collects the errors of `__ERROR` invocations among `callee` and its arguments, queueing the
+/// macros invoked as arguments.
+fn collect_errors(
+    program: &Program,
+    callee: &str,
+    args: &[Arg],
+    errors: &mut BTreeSet<String>,
+    pending: &mut Vec<String>,
+) {
+    if callee == "__ERROR" {
+        if let Some(Arg::Ident(error)) = args.first() {
+            errors.extend(program.errors.get(error).cloned());
+        }
+    }
+
+    for arg in args {
+        match arg {
+            Arg::Call(name, args) => {
+                pending.push(name.clone());
+                collect_errors(program, name, args, errors, pending);
+            }
+            Arg::Builtin(name, args) => collect_errors(program, name, args, errors, pending),
+            _ => {}
+        }
+    }
+}
+
+/// Renders the items of a JSON array of strings.
+fn list(items: impl IntoIterator<Item = impl AsRef<str>>) -> String {
+    items
+        .into_iter()
+        .map(|item| string(item.as_ref()))
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+/// Renders a JSON number, or `null` without one.
+pub(crate) fn optional(value: Option<impl ToString>) -> String {
+    value.map_or("null".to_string(), |value| value.to_string())
+}
+
+/// Quotes `s` as a JSON string.
+pub(crate) fn string(s: &str) -> String {
+    let mut quoted = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            c if (c as u32) < 0x20 => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stack_comments_of_definitions() {
+        let src = concat!(
+            "#define macro A() = takes (1) returns (1) {\n",
+            "    // takes:               // [value]\n",
+            "    dup1                    // [value, value]\n",
+            "    pop // drop it\n",
+            "}\n",
+            "\n",
+            "#define macro B() = takes (0) returns (0) {\n",
+            "    0x00 // [zero]\n",
+            "}\n",
+        );
+
+        assert_eq!(stack_comments(src), ["[value]", "[value, value]"]);
+        assert_eq!(
+            stack_comments("#define macro M() = takes (0) returns (1) { __MINI_MASK(80) }\n"),
+            Vec::<&str>::new()
+        );
+    }
+
+    #[test]
+    fn conditions_of_doc_comments() {
+        let src = concat!(
+            "/// ## A\n",
+            "///\n",
+            "/// @pre value <= 0xff\n",
+            "/// @post result == value\n",
+            "/// @post result <= 0xff\n",
+            "#define macro A() = takes (1) returns (1) {}\n",
+        );
+        let comments = docs::doc_comments(src);
+
+        assert_eq!(conditions(&comments["A"], "@pre "), ["value <= 0xff"]);
+        assert_eq!(
+            conditions(&comments["A"], "@post "),
+            ["result == value", "result <= 0xff"]
+        );
+    }
+
+    #[test]
+    fn quoting() {
+        assert_eq!(string("a\"b\\c\n"), "\"a\\\"b\\\\c\\u000a\"");
+        assert_eq!(file_name("libcast.huff"), "libcast.json");
+    }
+}
@@ -0,0 +1,381 @@
+//! Naming conventions of the generated libraries.
+
+use std::fmt;
+use std::io;
+use std::sync::Arc;
+
+use crate::config::{self, Table, Value};
+use crate::huff;
+
+/// A naming convention of the generated libraries, renaming their macros, errors and files.
+///
+/// Each rename returns the name under the convention, or `None` to keep the name the item is
+/// generated with. Implement it to fit a project's conventions, and select it with
+/// [`Generator::with_naming`](crate::Generator::with_naming).
+pub trait Naming: fmt::Debug + Send + Sync {
+    /// The name of the convention, as `generate.naming` selects it.
+    fn name(&self) -> &str;
+
+    /// Renames the macro `name`, such as `SAFE_ADD_U256`.
+    fn macro_name(&self, _name: &str) -> Option<String> {
+        None
+    }
+
+    /// Renames the error `name`, such as `Overflow`.
+    fn error_name(&self, _name: &str) -> Option<String> {
+        None
+    }
+
+    /// Moves the library `file_name`, such as `libsafemath.huff`, titled `title`, such as
+    /// `Safe Math`, to a path relative to the output directory.
+    fn file_name(&self, _file_name: &str, _title: &str) -> Option<String> {
+        None
+    }
+}
+
+/// The names of the conventions of `from_name`.
+pub const NAMES: &str = "\"libhuff\", \"huffmate\"";
+
+/// The convention named `name`.
+pub fn from_name(name: &str) -> Option<Arc<dyn Naming>> {
+    match name {
+        "libhuff" => Some(Arc::new(Libhuff)),
+        "huffmate" => Some(Arc::new(Huffmate)),
+        _ => None,
+    }
+}
+
+/// The names the libraries are generated with.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Libhuff;
+
+impl Naming for Libhuff {
+    fn name(&self) -> &str {
+        "libhuff"
+    }
+}
+
+/// The conventions of huffmate, so the libraries can be dropped into a huffmate project.
+///
+/// Libraries are moved to huffmate's directories and file names, `math/SafeMath.huff` for
+/// `libsafemath.huff`, and to `utils` by their title otherwise, `utils/Casting.huff` for
+/// `libcast.huff`, their includes and those of their usage examples following them. Macros and
+/// errors huffmate has counterparts of are renamed after them: full word macros lose their size,
+/// `SAFE_ADD` for `SAFE_ADD_U256`, and errors follow the revert strings of solmate, which huffmate
+/// ports.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Huffmate;
+
+const HUFFMATE_MACROS: [(&str, &str); 4] = [
+    ("SAFE_ADD_U256", "SAFE_ADD"),
+    ("SAFE_MUL_U256", "SAFE_MUL"),
+    ("NON_REENTRANT_ENTER", "LOCK"),
+    ("NON_REENTRANT_EXIT", "UNLOCK"),
+];
+
+const HUFFMATE_ERRORS: [(&str, &str); 1] = [("Reentrant", "Reentrancy")];
+
+const HUFFMATE_FILES: [(&str, &str); 8] = [
+    ("libauth.huff", "auth/Auth.huff"),
+    ("libbits.huff", "utils/LibBit.huff"),
+    ("libcall.huff", "utils/Calls.huff"),
+    ("libfixedpoint.huff", "math/FixedPointMath.huff"),
+    ("libmerkle.huff", "utils/MerkleProofLib.huff"),
+    ("libsafemath.huff", "math/SafeMath.huff"),
+    ("libsafetransfer.huff", "utils/SafeTransferLib.huff"),
+    ("libsignature.huff", "utils/ECDSA.huff"),
+];
+
+impl Naming for Huffmate {
+    fn name(&self) -> &str {
+        "huffmate"
+    }
+
+    fn macro_name(&self, name: &str) -> Option<String> {
+        lookup(&HUFFMATE_MACROS, name)
+    }
+
+    fn error_name(&self, name: &str) -> Option<String> {
+        lookup(&HUFFMATE_ERRORS, name)
+    }
+
+    fn file_name(&self, file_name: &str, title: &str) -> Option<String> {
+        let file = lookup(&HUFFMATE_FILES, file_name);
+        Some(file.unwrap_or_else(|| format!("utils/{}.huff", title.replace(' ', ""))))
+    }
+}
+
+fn lookup(table: &[(&str, &str)], name: &str) -> Option<String> {
+    table
+        .iter()
+        .find(|(from, _)| *from == name)
+        .map(|(_, to)| to.to_string())
+}
+
+/// The case of macro names.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Case {
+    /// `UNSAFE_TO_U8`, as the libraries are generated.
+    #[default]
+    Screaming,
+    /// `unsafeToU8`.
+    Camel,
+    /// `UnsafeToU8`.
+    Pascal,
+}
+
+impl Case {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "screaming" => Some(Case::Screaming),
+            "camel" => Some(Case::Camel),
+            "pascal" => Some(Case::Pascal),
+            _ => None,
+        }
+    }
+
+    /// Joins the words of a name, each given in upper case.
+    fn join(self, words: &[String]) -> String {
+        let capitalized = |word: &String| {
+            let mut chars = word.chars();
+            chars.next().map_or(String::new(), |c| {
+                c.to_string() + &chars.as_str().to_lowercase()
+            })
+        };
+
+        match self {
+            Case::Screaming => words.join("_"),
+            Case::Camel => words
+                .iter()
+                .enumerate()
+                .map(|(i, word)| {
+                    if i == 0 {
+                        word.to_lowercase()
+                    } else {
+                        capitalized(word)
+                    }
+                })
+                .collect(),
+            Case::Pascal => words.iter().map(capitalized).collect(),
+        }
+    }
+}
+
+/// A naming convention configured by `[generate.naming]`, of the case of macro names, the words
+/// naming casts and their unchecked and smaller variants, and the prefix and suffix of errors.
+///
+/// The words of variants lead the names of macros, in order: `UNSAFE_MINI_TO_U32` is the
+/// unchecked, smaller variant of the cast `TO_U32`. Macros the case would name after an opcode keep
+/// the name they are generated with, `PREVRANDAO` rather than `prevrandao`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct NamingConvention {
+    /// The case of macro names.
+    pub case: Case,
+    /// Replaces `TO` leading a cast, `CAST` for `CAST_U32`.
+    pub cast_prefix: Option<String>,
+    /// Replaces `UNSAFE` leading an unchecked variant, `UNCHECKED` for `UNCHECKED_TO_U32`.
+    pub unsafe_prefix: Option<String>,
+    /// Replaces `MINI` leading a smaller variant, `SMALL` for `SMALL_TO_U32`.
+    pub mini_prefix: Option<String>,
+    /// Prefixed to the names of errors, `LibHuff__` for `LibHuff__Overflow`.
+    pub error_prefix: String,
+    /// Appended to the names of errors, `Error` for `OverflowError`.
+    pub error_suffix: String,
+}
+
+impl NamingConvention {
+    /// The convention of the `[generate.naming]` table `table`.
+    pub fn from_table(table: &Table) -> io::Result<Self> {
+        let mut convention = NamingConvention::default();
+
+        for (key, value) in table {
+            let key_path = format!("`generate.naming.{}`", key);
+            let value = value
+                .as_str()
+                .ok_or_else(|| config::invalid(format!("{} must be a string", key_path)))?;
+            if !value.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+                let e = format!("{} must be made of identifier characters", key_path);
+                return Err(config::invalid(e));
+            }
+
+            match key.as_str() {
+                "case" => {
+                    convention.case = Case::from_name(value).ok_or_else(|| {
+                        let expected = "\"screaming\", \"camel\", \"pascal\"";
+                        config::invalid(format!("{} must be one of {}", key_path, expected))
+                    })?;
+                }
+                "cast_prefix" => convention.cast_prefix = Some(value.to_string()),
+                "unsafe_prefix" => convention.unsafe_prefix = Some(value.to_string()),
+                "mini_prefix" => convention.mini_prefix = Some(value.to_string()),
+                "error_prefix" => convention.error_prefix = value.to_string(),
+                "error_suffix" => convention.error_suffix = value.to_string(),
+                _ => return Err(config::invalid(format!("unknown key {}", key_path))),
+            }
+        }
+
+        Ok(convention)
+    }
+}
+
+impl Naming for NamingConvention {
+    fn name(&self) -> &str {
+        "generate.naming"
+    }
+
+    fn macro_name(&self, name: &str) -> Option<String> {
+        // internal macros keep their leading underscores
+        let rest = name.trim_start_matches('_');
+        let underscores = &name[..name.len() - rest.len()];
+        let mut words = rest.split('_').collect::<Vec<&str>>();
+
+        // the words of variants lead the name, each replaced by its prefix
+        let variants = [
+            ("UNSAFE", &self.unsafe_prefix),
+            ("MINI", &self.mini_prefix),
+            ("TO", &self.cast_prefix),
+        ];
+        let mut renamed = Vec::new();
+        for (word, prefix) in variants {
+            if words.first() != Some(&word) {
+                continue;
+            }
+            words.remove(0);
+            match prefix {
+                Some(prefix) => {
+                    let prefix = prefix.split('_').filter(|word| !word.is_empty());
+                    renamed.extend(prefix.map(str::to_uppercase));
+                }
+                None => renamed.push(word.to_string()),
+            }
+        }
+        renamed.extend(words.iter().map(|word| word.to_string()));
+
+        let renamed = format!("{}{}", underscores, self.case.join(&renamed));
+        (renamed != name && huff::opcode(&renamed).is_none()).then_some(renamed)
+    }
+
+    fn error_name(&self, name: &str) -> Option<String> {
+        let renamed = format!("{}{}{}", self.error_prefix, name, self.error_suffix);
+        (renamed != name).then_some(renamed)
+    }
+}
+
+/// The convention of the `generate.naming` value of the config: the name of a convention, or a
+/// `[generate.naming]` table configuring one.
+pub fn from_config(value: &Value) -> io::Result<Arc<dyn Naming>> {
+    match value {
+        Value::String(name) => from_name(name).ok_or_else(|| {
+            config::invalid(format!(
+                "`generate.naming` must be one of {}, or a table",
+                NAMES
+            ))
+        }),
+        Value::Table(table) => Ok(Arc::new(NamingConvention::from_table(table)?)),
+        _ => Err(config::invalid(
+            "`generate.naming` must be a string or a table",
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn huffmate_names() {
+        let naming = from_name("huffmate").unwrap();
+        assert_eq!(naming.name(), "huffmate");
+        assert_eq!(
+            naming.macro_name("SAFE_ADD_U256").as_deref(),
+            Some("SAFE_ADD")
+        );
+        assert_eq!(naming.macro_name("SAFE_ADD_U8"), None);
+        assert_eq!(
+            naming.error_name("Reentrant").as_deref(),
+            Some("Reentrancy")
+        );
+        assert_eq!(
+            naming.file_name("libsafemath.huff", "Safe Math").as_deref(),
+            Some("math/SafeMath.huff")
+        );
+        assert_eq!(
+            naming
+                .file_name("libbytescast.huff", "Bytes Casting")
+                .as_deref(),
+            Some("utils/BytesCasting.huff")
+        );
+
+        let libhuff = from_name("libhuff").unwrap();
+        assert_eq!(libhuff.macro_name("SAFE_ADD_U256"), None);
+        assert_eq!(libhuff.file_name("libsafemath.huff", "Safe Math"), None);
+        assert!(from_name("solady").is_none());
+    }
+
+    #[test]
+    fn conventions() {
+        let table = |src: &str| {
+            let config = config::parse(&format!("[generate.naming]\n{}", src)).unwrap();
+            let naming = config["generate"].as_table().unwrap()["naming"]
+                .as_table()
+                .unwrap();
+            NamingConvention::from_table(naming)
+        };
+        let rename = |naming: &NamingConvention, name: &str| naming.macro_name(name);
+
+        let camel = table("case = \"camel\"\nerror_suffix = \"Error\"").unwrap();
+        assert_eq!(
+            rename(&camel, "UNSAFE_MINI_TO_U32").as_deref(),
+            Some("unsafeMiniToU32")
+        );
+        assert_eq!(rename(&camel, "__HASH_PAIR").as_deref(), Some("__hashPair"));
+        assert_eq!(rename(&camel, "PREVRANDAO"), None);
+        assert_eq!(
+            camel.error_name("Overflow").as_deref(),
+            Some("OverflowError")
+        );
+
+        let pascal = table("case = \"pascal\"\nerror_prefix = \"LibHuff__\"").unwrap();
+        let signature = rename(&pascal, "IS_VALID_ERC1271_SIGNATURE");
+        assert_eq!(signature.as_deref(), Some("IsValidErc1271Signature"));
+        assert_eq!(
+            pascal.error_name("Overflow").as_deref(),
+            Some("LibHuff__Overflow")
+        );
+
+        let prefixes = table(concat!(
+            "cast_prefix = \"CAST\"\n",
+            "unsafe_prefix = \"UNCHECKED\"\n",
+            "mini_prefix = \"SMALL\"\n",
+        ))
+        .unwrap();
+        let cast = rename(&prefixes, "UNSAFE_MINI_TO_U32");
+        assert_eq!(cast.as_deref(), Some("UNCHECKED_SMALL_CAST_U32"));
+        assert_eq!(
+            rename(&prefixes, "MINI_U32_MASK").as_deref(),
+            Some("SMALL_U32_MASK")
+        );
+        assert_eq!(
+            rename(&prefixes, "TO_ADDRESS").as_deref(),
+            Some("CAST_ADDRESS")
+        );
+        // only leading words name variants
+        assert_eq!(rename(&prefixes, "WEI_TO_GWEI"), None);
+        assert_eq!(rename(&prefixes, "SAFE_ADD_U8"), None);
+        assert_eq!(prefixes.error_name("Overflow"), None);
+
+        let error = |src: &str| table(src).unwrap_err().to_string();
+        assert_eq!(
+            error("case = \"kebab\""),
+            "`generate.naming.case` must be one of \"screaming\", \"camel\", \"pascal\""
+        );
+        assert_eq!(
+            error("cast_prefix = \"TO-\""),
+            "`generate.naming.cast_prefix` must be made of identifier characters"
+        );
+        assert_eq!(
+            error("style = \"camel\""),
+            "unknown key `generate.naming.style`"
+        );
+    }
+}
@@ -0,0 +1,161 @@
+//! Cheapest encodings of masks, compared by runtime size or gas.
+
+use crate::libcast::mask;
+
+/// What an encoding is chosen to minimize, the other breaking ties.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Objective {
+    Size,
+    Gas,
+}
+
+/// A sequence of Huff code leaving a constant on the stack.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Encoding {
+    pub code: String,
+    pub bytes: usize,
+    pub gas: u64,
+}
+
+const G_VERYLOW: u64 = 3;
+const G_BASE: u64 = 2;
+
+/// The cheapest encoding of `2 ** bits - 1` for `objective`, out of the literal, the
+/// `__MINI_MASK` construction, and shifts of `not(0)`.
+///
+/// `push0` prices `0x00` literals as `PUSH0`, available from Shanghai.
+pub fn mask_encoding(bits: u16, objective: Objective, push0: bool) -> Encoding {
+    let candidates = mask_candidates(bits, push0);
+
+    let key = |encoding: &Encoding| match objective {
+        Objective::Size => (encoding.bytes as u64, encoding.gas),
+        Objective::Gas => (encoding.gas, encoding.bytes as u64),
+    };
+
+    // the first of the cheapest, the literal on ties
+    candidates
+        .into_iter()
+        .reduce(|best, e| if key(&e) < key(&best) { e } else { best })
+        .unwrap()
+}
+
+fn mask_candidates(bits: u16, push0: bool) -> Vec<Encoding> {
+    let (zero_bytes, zero_gas) = if push0 { (1, G_BASE) } else { (2, G_VERYLOW) };
+    let shift = |n: u16| format!("{:#04x}", n);
+
+    let mut candidates = vec![
+        Encoding {
+            code: mask(bits),
+            bytes: 1 + (bits as usize).div_ceil(8),
+            gas: G_VERYLOW,
+        },
+        // `(1 << bits) - 1`, with a two byte push of 256
+        Encoding {
+            code: format!("__MINI_MASK({})", bits),
+            bytes: if bits < 256 { 7 } else { 8 },
+            gas: 5 * G_VERYLOW,
+        },
+    ];
+
+    if bits == 256 {
+        candidates.push(Encoding {
+            code: "0x00 not".to_string(),
+            bytes: zero_bytes + 1,
+            gas: zero_gas + G_VERYLOW,
+        });
+    } else {
+        // `not(0) >> (256 - bits)`
+        candidates.push(Encoding {
+            code: format!("0x00 not {} shr", shift(256 - bits)),
+            bytes: zero_bytes + 4,
+            gas: zero_gas + 3 * G_VERYLOW,
+        });
+        // `not(not(0) << bits)`
+        candidates.push(Encoding {
+            code: format!("0x00 not {} shl not", shift(bits)),
+            bytes: zero_bytes + 5,
+            gas: zero_gas + 4 * G_VERYLOW,
+        });
+    }
+
+    candidates
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::evm::{addr, Call, Evm};
+    use crate::huff::{self, Program};
+    use crate::u256::U256;
+
+    /// Compiles and runs `code`, returning its size, gas and result.
+    fn run(code: &str, push0: bool) -> (usize, u64, U256) {
+        let src = format!(
+            concat!(
+                "#define macro __MINI_MASK(bitsize) = takes (0) returns (1) {{\n",
+                "    0x01 dup1 <bitsize> shl sub\n",
+                "}}\n",
+                "#define macro MAIN() = takes (0) returns (0) {{ {} }}\n",
+            ),
+            code
+        );
+        let code = huff::compile(
+            &Program::parse_str(&src, ".".as_ref()).unwrap(),
+            "MAIN",
+            push0,
+        )
+        .unwrap();
+
+        let mut evm = Evm::new();
+        evm.set_code(addr(1), code.clone());
+        let call = Call {
+            caller: addr(2),
+            address: addr(1),
+            code_address: addr(1),
+            value: U256::ZERO,
+            data: Vec::new(),
+            gas: 1_000_000,
+            is_static: false,
+            transfer: false,
+        };
+        let outcome = evm.transact(call.clone());
+        evm.set_code(addr(1), Vec::new());
+        let baseline = evm.transact(call).gas_used;
+
+        (code.len(), outcome.gas_used - baseline, outcome.stack[0])
+    }
+
+    #[test]
+    fn candidates_are_priced() {
+        for bits in (8..=256).step_by(8) {
+            let expected = U256::ONE.shl(bits as u32).wrapping_sub(U256::ONE);
+            for push0 in [false, true] {
+                for candidate in mask_candidates(bits, push0) {
+                    let measured = (candidate.bytes, candidate.gas, expected);
+                    assert_eq!(run(&candidate.code, push0), measured, "{}", candidate.code);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn cheapest_masks() {
+        assert_eq!(
+            mask_encoding(80, Objective::Gas, true).code,
+            "0xffffffffffffffffffff"
+        );
+        assert_eq!(mask_encoding(256, Objective::Gas, true).code, mask(256));
+
+        // literals are as small up to four bytes, and cheaper
+        assert_eq!(mask_encoding(32, Objective::Size, true).code, "0xffffffff");
+        assert_eq!(
+            mask_encoding(40, Objective::Size, true).code,
+            "0x00 not 0xd8 shr"
+        );
+        assert_eq!(
+            mask_encoding(40, Objective::Size, false).code,
+            "0xffffffffff"
+        );
+        assert_eq!(mask_encoding(256, Objective::Size, false).code, "0x00 not");
+    }
+}
@@ -0,0 +1,26 @@
+//! Packed slot accessors from a standalone layout file, for `huffgen pack`.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::config::{invalid, parse};
+use crate::libstruct;
+
+/// Renders the getters and setters of the packed slot layout at `path`.
+///
+/// ```toml
+/// slot = 0x00
+/// fields = [
+///     { name = "owner", bits = 160 },
+///     { name = "nonce", bits = 64 },
+///     { name = "flags", bits = 32 },
+/// ]
+/// ```
+pub fn render(path: &Path) -> io::Result<String> {
+    let src = fs::read_to_string(path)
+        .map_err(|e| io::Error::new(e.kind(), format!("{}: {}", path.display(), e)))?;
+    let layout = parse(&src).map_err(|e| invalid(format!("{}: {}", path.display(), e)))?;
+
+    libstruct::render_layout(&layout)
+}
@@ -0,0 +1,394 @@
+//! Opcode profiles of target chains, denying or re-costing opcodes for zkEVMs and other chains that
+//! diverge from mainnet.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::io;
+use std::path::Path;
+
+use crate::config::{evm_version, invalid, EvmVersion, Table, Value};
+use crate::huff::{self, Program, Stmt};
+
+/// Opcodes introduced by Shanghai.
+const SHANGHAI: &[u8] = &[0x5f];
+
+/// Opcodes introduced by Cancun.
+const CANCUN: &[u8] = &[0x49, 0x4a, 0x5c, 0x5d, 0x5e];
+
+/// The opcodes available on the target chain and their costs.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Profile {
+    deny: BTreeSet<u8>,
+    gas: BTreeMap<u8, u64>,
+}
+
+impl Profile {
+    /// Reads the profile of the config, denying the opcodes of the `deny` array of its `[opcodes]`
+    /// table and every opcode of a later hardfork than `evm_version`, and re-costing the opcodes
+    /// of its `gas` table.
+    pub fn load(config: &Table) -> io::Result<Self> {
+        let version = evm_version(config)?;
+        let mut deny = BTreeSet::new();
+
+        if version < EvmVersion::Shanghai {
+            deny.extend(SHANGHAI);
+        }
+        if version < EvmVersion::Cancun {
+            deny.extend(CANCUN);
+        }
+
+        let table = match config.get("opcodes") {
+            None => {
+                return Ok(Profile {
+                    deny,
+                    gas: BTreeMap::new(),
+                })
+            }
+            Some(Value::Table(table)) => table,
+            Some(_) => return Err(invalid("`opcodes` must be a table")),
+        };
+
+        if let Some(names) = table.get("deny") {
+            let names = names
+                .as_array()
+                .ok_or_else(|| invalid("`opcodes.deny` must be an array of opcode names"))?;
+            for name in names {
+                let name = name
+                    .as_str()
+                    .ok_or_else(|| invalid("`opcodes.deny` must be an array of opcode names"))?;
+                deny.insert(parse_opcode(name)?);
+            }
+        }
+
+        let mut gas = BTreeMap::new();
+        if let Some(costs) = table.get("gas") {
+            let costs = costs
+                .as_table()
+                .ok_or_else(|| invalid("`opcodes.gas` must be a table of opcode costs"))?;
+            for (name, cost) in costs {
+                let cost = cost
+                    .as_integer()
+                    .filter(|cost| *cost <= u64::MAX as u128)
+                    .ok_or_else(|| invalid(format!("cost of `{}` must be an integer", name)))?;
+                gas.insert(parse_opcode(name)?, cost as u64);
+            }
+        }
+
+        Ok(Profile { deny, gas })
+    }
+
+    /// Returns whether the target chain supports `op`.
+    pub fn allows(&self, op: u8) -> bool {
+        !self.deny.contains(&op)
+    }
+
+    /// Returns whether `0x00` literals may be pushed with `PUSH0`.
+    pub fn push0(&self) -> bool {
+        self.allows(0x5f)
+    }
+
+    /// Returns the static gas of `op` on the target chain, `default` unless re-costed.
+    pub fn gas(&self, op: u8, default: u64) -> u64 {
+        self.gas.get(&op).copied().unwrap_or(default)
+    }
+}
+
+/// Returns the sequence replacing `op` on chains denying it, with the same stack effect.
+///
+/// `PUSH0` needs none, as denying it pushes `0x00` literals with `PUSH1` instead.
+fn fallback(op: u8) -> Option<&'static [&'static str]> {
+    match op {
+        // selfbalance == address balance
+        0x47 => Some(&["address", "balance"]),
+        // mcopy(dest, src, len) == staticcall(gas, identity, src, len, dest, len)
+        0x5e => Some(&["swap1", "dup3", "swap1", "0x04", "gas", "staticcall", "pop"]),
+        _ => None,
+    }
+}
+
+/// Compiles `entry` for the profile, replacing denied opcodes with their fallback sequences.
+///
+/// Fails if a macro reachable from `entry` uses a denied opcode without a fallback.
+pub fn compile(
+    mut program: Program,
+    entry: &str,
+    profile: &Profile,
+) -> Result<Vec<u8>, huff::Error> {
+    for definition in program.macros.values_mut() {
+        definition.body = definition
+            .body
+            .drain(..)
+            .flat_map(|stmt| lower(stmt, profile))
+            .collect();
+    }
+
+    if let Some((name, op)) = violations(&program, entry, profile).into_iter().next() {
+        return Err(huff::Error {
+            msg: format!(
+                "macro `{}` uses `{}`, denied by the opcode profile",
+                name, op
+            ),
+        });
+    }
+
+    huff::compile(&program, entry, profile.push0())
+}
+
+/// Compiles the macro `entry` as `compile` does, wrapped in deployment bytecode that runs the
+/// `CONSTRUCTOR` macro of `program`, if it defines one, before returning the runtime code.
+pub fn compile_deployment(
+    program: Program,
+    entry: &str,
+    profile: &Profile,
+) -> Result<Vec<u8>, huff::Error> {
+    let constructor = match program.macros.contains_key("CONSTRUCTOR") {
+        true => compile(program.clone(), "CONSTRUCTOR", profile)?,
+        false => Vec::new(),
+    };
+    let runtime = compile(program, entry, profile)?;
+    huff::deployment(&constructor, &runtime, profile.push0())
+}
+
+fn lower(stmt: Stmt, profile: &Profile) -> Vec<Stmt> {
+    let op = match &stmt {
+        Stmt::Ident(name) => huff::opcode(name),
+        _ => None,
+    };
+
+    match op.filter(|op| !profile.allows(*op)).and_then(fallback) {
+        Some(sequence) => sequence
+            .iter()
+            .map(|word| match word.strip_prefix("0x") {
+                Some(hex) => Stmt::Literal(vec![u8::from_str_radix(hex, 16).unwrap()]),
+                None => Stmt::Ident(word.to_string()),
+            })
+            .collect(),
+        None => vec![stmt],
+    }
+}
+
+/// Lists the denied opcodes of the macros reachable from `entry`, with the macro using each.
+fn violations(program: &Program, entry: &str, profile: &Profile) -> Vec<(String, String)> {
+    let mut found = Vec::new();
+    let mut seen = BTreeSet::new();
+    let mut pending = vec![entry.to_string()];
+
+    while let Some(name) = pending.pop() {
+        if !seen.insert(name.clone()) {
+            continue;
+        }
+        let Some(definition) = program.macros.get(&name) else {
+            continue;
+        };
+        for stmt in &definition.body {
+            match stmt {
+                Stmt::Ident(id) if huff::opcode(id).is_some_and(|op| !profile.allows(op)) => {
+                    found.push((name.clone(), id.clone()));
+                }
+                Stmt::Call(callee, _) => pending.push(callee.clone()),
+                _ => {}
+            }
+        }
+    }
+
+    found
+}
+
+/// Lists the macros of the generated `files`, by path from `dir`, that use opcodes denied by the
+/// profile, one line each, for the libraries to be checked against the target before they are
+/// written.
+pub fn check(
+    dir: &Path,
+    files: &BTreeMap<String, String>,
+    profile: &Profile,
+) -> io::Result<Vec<String>> {
+    let mut report = Vec::new();
+    for (name, contents) in files.iter().filter(|(name, _)| name.ends_with(".huff")) {
+        let file = dir.join(name);
+        // included macros are checked with the library defining them
+        let src = contents
+            .lines()
+            .filter(|line| !line.starts_with("#include "))
+            .collect::<Vec<&str>>()
+            .join("\n");
+        let program = Program::parse_file(&src, &file).map_err(|e| invalid(e.to_string()))?;
+
+        let mut macros = program.macros.keys().collect::<Vec<&String>>();
+        macros.sort();
+        for name in macros {
+            for stmt in &program.macros[name].body {
+                let Stmt::Ident(id) = stmt else { continue };
+                let Some(op) = huff::opcode(id).filter(|op| !profile.allows(*op)) else {
+                    continue;
+                };
+                let line = match fallback(op) {
+                    Some(sequence) => format!(
+                        "{}: `{}` uses `{}`, replace with `{}`",
+                        file.display(),
+                        name,
+                        id,
+                        sequence.join(" ")
+                    ),
+                    None => format!("{}: `{}` uses `{}`", file.display(), name, id),
+                };
+                report.push(line);
+            }
+        }
+    }
+    report.dedup();
+
+    Ok(report)
+}
+
+fn parse_opcode(name: &str) -> io::Result<u8> {
+    huff::opcode(name).ok_or_else(|| invalid(format!("unknown opcode `{}`", name)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::parse;
+    use crate::evm::{addr, Call, Evm, Halt};
+    use crate::u256::U256;
+
+    fn profile(config: &str) -> Profile {
+        Profile::load(&parse(config).unwrap()).unwrap()
+    }
+
+    fn program(body: &str) -> Program {
+        let src = format!(
+            "#define macro MAIN() = takes (0) returns (0) {{ {} }}",
+            body
+        );
+        Program::parse_str(&src, Path::new(".")).unwrap()
+    }
+
+    /// Compiles and runs `body` for the profile, returning the resulting stack.
+    fn execute(body: &str, profile: &Profile) -> Vec<U256> {
+        let mut evm = Evm::new();
+        evm.set_code(
+            addr(0xc0de),
+            compile(program(body), "MAIN", profile).unwrap(),
+        );
+        evm.account(addr(0xc0de)).balance = U256::from_u64(1000);
+
+        let outcome = evm.transact(Call {
+            caller: addr(0xca11),
+            address: addr(0xc0de),
+            code_address: addr(0xc0de),
+            value: U256::ZERO,
+            data: Vec::new(),
+            gas: 1_000_000,
+            is_static: false,
+            transfer: false,
+        });
+
+        assert_eq!(outcome.halt, Halt::Stop);
+        outcome.stack
+    }
+
+    #[test]
+    fn shanghai_profile() {
+        let profile = profile("");
+
+        assert!(profile.push0());
+        assert!(!profile.allows(0x5d));
+        assert_eq!(
+            compile(program("0x00"), "MAIN", &profile).unwrap(),
+            vec![0x5f]
+        );
+        assert!(compile(program("0x01 0x00 tstore"), "MAIN", &profile).is_err());
+    }
+
+    #[test]
+    fn paris_profile() {
+        let profile = profile("evm_version = \"paris\"");
+
+        assert!(!profile.push0());
+        assert_eq!(
+            compile(program("0x00"), "MAIN", &profile).unwrap(),
+            vec![0x60, 0x00]
+        );
+        assert_eq!(execute("0x00 0x01", &profile), vec![U256::ONE, U256::ZERO]);
+    }
+
+    #[test]
+    fn selfbalance_fallback() {
+        let allowed = profile("");
+        let denied = profile("[opcodes]\ndeny = [\"selfbalance\"]");
+
+        let code = compile(program("selfbalance"), "MAIN", &denied).unwrap();
+        assert_eq!(code, vec![0x30, 0x31]);
+        assert_eq!(
+            execute("selfbalance", &denied),
+            execute("selfbalance", &allowed)
+        );
+        assert_eq!(execute("selfbalance", &denied), vec![U256::from_u64(1000)]);
+    }
+
+    #[test]
+    fn mcopy_fallback() {
+        let allowed = profile("evm_version = \"cancun\"");
+        let denied = profile("evm_version = \"cancun\"\n[opcodes]\ndeny = [\"mcopy\"]");
+        let body = "0x1234 0x00 mstore 0x20 0x00 0x30 mcopy 0x30 mload 0x10 mload";
+
+        assert!(!compile(program(body), "MAIN", &denied)
+            .unwrap()
+            .contains(&0x5e));
+        assert_eq!(execute(body, &denied), execute(body, &allowed));
+        assert_eq!(execute(body, &denied)[1], U256::from_u64(0x1234));
+    }
+
+    #[test]
+    fn denied_without_fallback() {
+        let denied = profile("[opcodes]\ndeny = [\"chainid\"]");
+        let src = "#define macro CHAIN() = takes (0) returns (1) { chainid }\n\
+                   #define macro UNUSED() = takes (0) returns (1) { chainid }\n\
+                   #define macro MAIN() = takes (0) returns (0) { 0x01 }";
+        let program = Program::parse_str(src, Path::new(".")).unwrap();
+
+        assert!(compile(program.clone(), "MAIN", &denied).is_ok());
+        assert!(compile(program, "CHAIN", &denied).is_err());
+    }
+
+    #[test]
+    fn deployments() {
+        let src = "#define macro CONSTRUCTOR() = takes (0) returns (0) { 0x2a 0x00 sstore }\n\
+                   #define macro MAIN() = takes (0) returns (0) { 0x00 sload 0x00 mstore }";
+        let contract = Program::parse_str(src, Path::new(".")).unwrap();
+
+        for profile in [profile(""), profile("evm_version = \"paris\"")] {
+            let runtime = compile(contract.clone(), "MAIN", &profile).unwrap();
+            let initcode = compile_deployment(contract.clone(), "MAIN", &profile).unwrap();
+
+            let mut evm = Evm::new();
+            let (outcome, address) = evm.create(addr(0xca11), &initcode, 1_000_000);
+            assert_eq!((outcome.halt, &outcome.output), (Halt::Return, &runtime));
+            assert_eq!(evm.account(address).code, runtime);
+            assert_eq!(evm.sload(address, U256::ZERO), U256::from_u64(0x2a));
+        }
+
+        // without a constructor the bootstrap only returns the runtime code
+        let initcode = compile_deployment(program("0x01"), "MAIN", &profile(""));
+        let bootstrap = [
+            0x61, 0x00, 0x02, 0x80, 0x61, 0x00, 0x0b, 0x5f, 0x39, 0x5f, 0xf3,
+        ];
+        assert_eq!(initcode.unwrap(), [&bootstrap[..], &[0x60, 0x01]].concat());
+
+        // failed deployments leave no code
+        let mut evm = Evm::new();
+        let (outcome, address) = evm.create(addr(0xca11), &[0x5f, 0x5f, 0xfd], 1_000_000);
+        assert_eq!(outcome.halt, Halt::Revert);
+        assert!(evm.account(address).code.is_empty());
+        assert_eq!(evm.account(addr(0xca11)).nonce, 1);
+        assert!(huff::deployment(&[], &vec![0; 0x10000], true).is_err());
+    }
+
+    #[test]
+    fn gas_overrides() {
+        let profile = profile("[opcodes]\ngas = { sha3 = 60 }");
+
+        assert_eq!(profile.gas(0x20, 30), 60);
+        assert_eq!(profile.gas(0x01, 3), 3);
+        assert!(Profile::load(&parse("[opcodes]\ndeny = [\"nope\"]").unwrap()).is_err());
+    }
+}
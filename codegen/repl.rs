@@ -0,0 +1,241 @@
+//! Interactive execution of the macros of a library on the embedded EVM.
+
+use std::io::{self, BufRead, Write};
+use std::path::Path;
+
+use crate::evm::{addr, Call, Evm, Halt, Outcome};
+use crate::huff::{self, Program};
+use crate::profile::{self, Profile};
+use crate::u256::U256;
+
+const CONTRACT: u64 = 0xc0de;
+const CALLER: u64 = 0xca11;
+
+const HELP: &str = "\
+<macro> [stack...]   runs a macro with the stack, top first, e.g. `TO_U8 0x100`
+calldata <hex>       sets the calldata of later runs
+macros               lists the macros of the library
+help                 prints this message
+quit                 exits
+";
+
+/// Runs the REPL over the library at `file` until `quit` or the end of input.
+///
+/// Storage persists between runs, so macros can be composed across lines.
+pub fn run(file: &Path, profile: &Profile) -> io::Result<()> {
+    // fail before prompting if the library cannot be read at all
+    std::fs::metadata(file)
+        .map_err(|e| io::Error::new(e.kind(), format!("{}: {}", file.display(), e)))?;
+
+    let mut evm = Evm::new();
+    let mut calldata = Vec::new();
+    let stdin = io::stdin();
+    let mut stdout = io::stdout();
+
+    write!(stdout, "{}> ", HELP)?;
+    stdout.flush()?;
+
+    for line in stdin.lock().lines() {
+        let line = line?;
+        let mut words = line.split_whitespace();
+
+        match words.next() {
+            None => {}
+            Some("quit") | Some("exit") => break,
+            Some("help") => write!(stdout, "{}", HELP)?,
+            Some("macros") => match main(file, "") {
+                Ok(program) => {
+                    let mut names = program.macros.keys().cloned().collect::<Vec<String>>();
+                    names.retain(|name| name != "MAIN");
+                    names.sort();
+                    writeln!(stdout, "{}", names.join("\n"))?;
+                }
+                Err(e) => writeln!(stdout, "error: {}", e)?,
+            },
+            Some("calldata") => match words.next().map(decode_hex) {
+                Some(Some(data)) => calldata = data,
+                _ => writeln!(stdout, "error: expected calldata as hex")?,
+            },
+            Some(invocation) => {
+                let stack = words.map(parse_word).collect::<Option<Vec<U256>>>();
+                match stack {
+                    Some(stack) => {
+                        let report =
+                            execute(&mut evm, file, invocation, &stack, &calldata, profile);
+                        write!(stdout, "{}", report)?;
+                    }
+                    None => writeln!(stdout, "error: stack values must be hex or decimal words")?,
+                }
+            }
+        }
+
+        write!(stdout, "> ")?;
+        stdout.flush()?;
+    }
+
+    Ok(())
+}
+
+/// Parses the library at `file` with a `MAIN` of `body`.
+fn main(file: &Path, body: &str) -> Result<Program, huff::Error> {
+    let src = format!(
+        "#include \"{}\"\n\n#define macro MAIN() = takes (0) returns (0) {{\n    {}\n}}\n",
+        file.file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or_default(),
+        body,
+    );
+
+    Program::parse_str(&src, file.parent().unwrap_or(Path::new(".")))
+}
+
+/// Runs `invocation` with `stack` pushed, reporting the outcome.
+fn execute(
+    evm: &mut Evm,
+    file: &Path,
+    invocation: &str,
+    stack: &[U256],
+    calldata: &[u8],
+    profile: &Profile,
+) -> String {
+    let invocation = if invocation.contains('(') {
+        invocation.to_string()
+    } else {
+        format!("{}()", invocation)
+    };
+    let pushes = stack
+        .iter()
+        .rev()
+        .map(|word| word.to_hex())
+        .collect::<Vec<String>>();
+    let body = format!("{} {}", pushes.join(" "), invocation);
+
+    let compiled = main(file, &body).and_then(|program| {
+        profile::compile(program.clone(), "MAIN", profile).map(|code| (program, code))
+    });
+    let (program, code) = match compiled {
+        Ok(compiled) => compiled,
+        Err(e) => return format!("error: {}\n", e),
+    };
+
+    evm.set_code(addr(CONTRACT), code);
+    let outcome = evm.transact(Call {
+        caller: addr(CALLER),
+        address: addr(CONTRACT),
+        code_address: addr(CONTRACT),
+        value: U256::ZERO,
+        data: calldata.to_vec(),
+        gas: 30_000_000,
+        is_static: false,
+        transfer: false,
+    });
+
+    report(&program, &outcome)
+}
+
+/// Deploys `initcode` on a fresh EVM and calls the contract with `calldata`, reporting its address
+/// and the call as a run of the REPL. The report is an error if the deployment fails or the call
+/// halts with an exception, rather than stopping, returning or reverting.
+pub fn deploy_and_call(
+    program: &Program,
+    initcode: &[u8],
+    calldata: &[u8],
+) -> Result<String, String> {
+    let mut evm = Evm::new();
+    let (outcome, address) = evm.create(addr(CALLER), initcode, 30_000_000);
+    if !outcome.success() {
+        return Err(format!("deployment {}", report(program, &outcome)));
+    }
+
+    let outcome = evm.transact(Call {
+        caller: addr(CALLER),
+        address,
+        code_address: address,
+        value: U256::ZERO,
+        data: calldata.to_vec(),
+        gas: 30_000_000,
+        is_static: false,
+        transfer: false,
+    });
+
+    let report = format!(
+        "address: 0x{}\n{}",
+        encode_hex(&address),
+        report(program, &outcome)
+    );
+    match outcome.halt {
+        Halt::Exception(_) => Err(report),
+        _ => Ok(report),
+    }
+}
+
+fn report(program: &Program, outcome: &Outcome) -> String {
+    let stack = outcome
+        .stack
+        .iter()
+        .map(|word| word.to_hex())
+        .collect::<Vec<String>>();
+    let mut report = match &outcome.halt {
+        Halt::Stop | Halt::Return => format!("stack: [{}]\n", stack.join(", ")),
+        Halt::Revert => format!("reverted: {}\n", revert_reason(program, &outcome.output)),
+        Halt::Exception(e) => format!("halted: {}\n", e),
+    };
+
+    if matches!(outcome.halt, Halt::Return) {
+        report.push_str(&format!("returndata: 0x{}\n", encode_hex(&outcome.output)));
+    }
+    for log in &outcome.logs {
+        let topics = log
+            .topics
+            .iter()
+            .map(|word| word.to_hex())
+            .collect::<Vec<String>>();
+        report.push_str(&format!(
+            "log: [{}] 0x{}\n",
+            topics.join(", "),
+            encode_hex(&log.data)
+        ));
+    }
+    report.push_str(&format!("gas: {}\n", outcome.gas_used));
+
+    report
+}
+
+/// Names the custom error of revert data if it is one of the library, or formats it as hex.
+fn revert_reason(program: &Program, output: &[u8]) -> String {
+    let error = program
+        .errors
+        .values()
+        .find(|signature| output.len() >= 4 && huff::selector(signature) == output[..4]);
+
+    match error {
+        Some(signature) if output.len() == 4 => signature.clone(),
+        Some(signature) => format!("{} 0x{}", signature, encode_hex(&output[4..])),
+        None => format!("0x{}", encode_hex(output)),
+    }
+}
+
+fn parse_word(word: &str) -> Option<U256> {
+    match word.strip_prefix("0x") {
+        Some(hex) => U256::from_hex(hex),
+        None => U256::from_dec(word),
+    }
+}
+
+/// Decodes `hex`, with or without a `0x` prefix.
+pub fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    let hex = hex.strip_prefix("0x").unwrap_or(hex);
+
+    if hex.len() % 2 == 1 {
+        return None;
+    }
+
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).ok())
+        .collect()
+}
+
+fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
@@ -0,0 +1,206 @@
+//! Gas and size reports of compiled macros, for weighing the full masks against the `MINI` ones.
+
+use std::path::Path;
+
+use crate::evm::{addr, Call, Evm, Halt};
+use crate::huff::{self, Program};
+use crate::listing::{wrap, ENTRY};
+use crate::profile::{self, Profile};
+use crate::u256::U256;
+
+const CALLER: u64 = 0xca11;
+const CONTRACT: u64 = 0xc0de;
+
+const MACRO_TABLE_HEADER: &str = "\
+| macro | takes | returns | bytes | gas |
+|---|---:|---:|---:|---:|
+";
+
+const MINI_TABLE_HEADER: &str = "
+| macro | bytes | gas | mini macro | bytes | gas |
+|---|---:|---:|---|---:|---:|
+";
+
+/// The cost of a macro invoked from an otherwise empty `MAIN`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Entry {
+    pub name: String,
+    pub takes: usize,
+    pub returns: usize,
+    /// Bytes of the compiled macro.
+    pub size: usize,
+    /// Gas spent running the macro on arguments of one, or `None` if it does not run to the end.
+    pub gas: Option<u64>,
+}
+
+/// Measures the macros defined by the library at `file` without template arguments, in the order
+/// they are defined, compiled for `profile`.
+pub fn measure(file: &Path, profile: &Profile) -> Result<Vec<Entry>, huff::Error> {
+    let program = wrap(file, "")?;
+    let src = std::fs::read_to_string(file).map_err(|e| huff::Error { msg: e.to_string() })?;
+
+    macros(&program, &src)
+        .into_iter()
+        .map(|name| {
+            let (takes, returns) = (program.macros[&name].takes, program.macros[&name].returns);
+            let invocation = format!("{}()", name);
+            let arguments = vec!["0x01"; takes].join(" ");
+
+            let size = profile::compile(wrap(file, &invocation)?, ENTRY, profile)?.len();
+            let gas = match (
+                run(
+                    wrap(file, &format!("{} {}", arguments, invocation))?,
+                    profile,
+                )?,
+                run(wrap(file, &arguments)?, profile)?,
+            ) {
+                (Some(total), Some(arguments)) => Some(total - arguments),
+                _ => None,
+            };
+
+            Ok(Entry {
+                name,
+                takes,
+                returns,
+                size,
+                gas,
+            })
+        })
+        .collect()
+}
+
+/// The macros without template arguments `src` defines, in the order they are defined, leaving
+/// out those of the libraries it includes.
+pub(crate) fn macros(program: &Program, src: &str) -> Vec<String> {
+    let mut names = program
+        .macros
+        .iter()
+        .filter(|(name, definition)| *name != ENTRY && definition.params.is_empty())
+        .filter_map(|(name, _)| {
+            let position = src.find(&format!("#define macro {}(", name))?;
+            Some((position, name.clone()))
+        })
+        .collect::<Vec<(usize, String)>>();
+    names.sort();
+
+    names.into_iter().map(|(_, name)| name).collect()
+}
+
+/// Runs the `ENTRY` of `program`, returning the gas it used if it stopped.
+fn run(program: Program, profile: &Profile) -> Result<Option<u64>, huff::Error> {
+    let mut evm = Evm::new();
+    evm.set_code(addr(CONTRACT), profile::compile(program, ENTRY, profile)?);
+
+    let outcome = evm.transact(Call {
+        caller: addr(CALLER),
+        address: addr(CONTRACT),
+        code_address: addr(CONTRACT),
+        value: U256::ZERO,
+        data: Vec::new(),
+        gas: 30_000_000,
+        is_static: false,
+        transfer: false,
+    });
+
+    Ok((outcome.halt == Halt::Stop).then_some(outcome.gas_used))
+}
+
+/// Pairs of the `MINI` macros with the full macros they stand in for, e.g. `MINI_TO_U80` with
+/// `TO_U80`.
+fn mini_pairs(entries: &[Entry]) -> Vec<(&Entry, &Entry)> {
+    entries
+        .iter()
+        .filter_map(|mini| {
+            let full = mini.name.replacen("MINI_", "", 1);
+            let full = entries
+                .iter()
+                .find(|entry| entry.name == full && entry.name != mini.name)?;
+            Some((full, mini))
+        })
+        .collect()
+}
+
+/// Renders the entries as a markdown table, followed by a comparison of the `MINI` macros with
+/// their full counterparts if there are any.
+pub fn markdown(entries: &[Entry]) -> String {
+    let gas = |entry: &Entry| entry.gas.map_or("-".to_string(), |gas| gas.to_string());
+
+    let mut out = MACRO_TABLE_HEADER.to_string();
+    for entry in entries {
+        out.push_str(&format!(
+            "| `{}` | {} | {} | {} | {} |\n",
+            entry.name,
+            entry.takes,
+            entry.returns,
+            entry.size,
+            gas(entry),
+        ));
+    }
+
+    let pairs = mini_pairs(entries);
+    if !pairs.is_empty() {
+        out.push_str(MINI_TABLE_HEADER);
+        for (full, mini) in pairs {
+            out.push_str(&format!(
+                "| `{}` | {} | {} | `{}` | {} | {} |\n",
+                full.name,
+                full.size,
+                gas(full),
+                mini.name,
+                mini.size,
+                gas(mini),
+            ));
+        }
+    }
+
+    out
+}
+
+/// Renders the entries as a JSON array of objects, with a `null` gas for macros that do not run to
+/// the end.
+pub fn json(entries: &[Entry]) -> String {
+    let objects = entries
+        .iter()
+        .map(|entry| {
+            format!(
+                "  {{ \"name\": \"{}\", \"takes\": {}, \"returns\": {}, \"bytes\": {}, \"gas\": {} }}",
+                entry.name,
+                entry.takes,
+                entry.returns,
+                entry.size,
+                entry.gas.map_or("null".to_string(), |gas| gas.to_string()),
+            )
+        })
+        .collect::<Vec<String>>();
+
+    format!("[\n{}\n]\n", objects.join(",\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(name: &str, size: usize, gas: Option<u64>) -> Entry {
+        Entry {
+            name: name.to_string(),
+            takes: 1,
+            returns: 1,
+            size,
+            gas,
+        }
+    }
+
+    #[test]
+    fn mini_comparison() {
+        let entries = [
+            entry("TO_U80", 59, Some(29)),
+            entry("MINI_TO_U80", 55, Some(41)),
+            entry("MINI_U80_MASK", 7, None),
+        ];
+
+        let markdown = markdown(&entries);
+        assert!(markdown.contains("| `MINI_U80_MASK` | 1 | 1 | 7 | - |\n"));
+        assert!(markdown.ends_with("| `TO_U80` | 59 | 29 | `MINI_TO_U80` | 55 | 41 |\n"));
+        assert!(json(&entries).ends_with("\"bytes\": 7, \"gas\": null }\n]\n"));
+    }
+}
@@ -0,0 +1,87 @@
+//! Derivation of well-known storage slots, for slot constants the generators compute ahead of time.
+
+use crate::keccak::keccak256;
+
+/// A storage slot derivation scheme.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Derivation {
+    /// EIP-1967, `keccak256(id) - 1`.
+    Eip1967,
+    /// ERC-7201, `keccak256(keccak256(id) - 1) & ~0xff`.
+    Erc7201,
+}
+
+impl Derivation {
+    /// Parses the name of a derivation, `eip1967` or `erc7201`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "eip1967" => Some(Derivation::Eip1967),
+            "erc7201" => Some(Derivation::Erc7201),
+            _ => None,
+        }
+    }
+
+    /// Derives the slot of `id`.
+    pub fn slot(self, id: &str) -> [u8; 32] {
+        match self {
+            Derivation::Eip1967 => eip1967(id),
+            Derivation::Erc7201 => erc7201(id),
+        }
+    }
+
+    /// Formula of the derivation, for documentation.
+    pub fn formula(self) -> &'static str {
+        match self {
+            Derivation::Eip1967 => "keccak256(id) - 1",
+            Derivation::Erc7201 => "keccak256(keccak256(id) - 1) & ~0xff",
+        }
+    }
+}
+
+/// Computes the EIP-1967 slot of an id, `keccak256(id) - 1`.
+pub fn eip1967(id: &str) -> [u8; 32] {
+    let mut slot = keccak256(id.as_bytes());
+    decrement(&mut slot);
+    slot
+}
+
+/// Computes the ERC-7201 storage location of a namespace id,
+/// `keccak256(keccak256(id) - 1) & ~0xff`.
+pub fn erc7201(id: &str) -> [u8; 32] {
+    let mut location = keccak256(&eip1967(id));
+    location[31] = 0x00;
+    location
+}
+
+/// Formats a slot as a 32 byte hex literal.
+pub fn hex(slot: &[u8; 32]) -> String {
+    format!(
+        "0x{}",
+        slot.iter()
+            .map(|b| format!("{:02x}", b))
+            .collect::<String>()
+    )
+}
+
+/// Formats a Huff constant `name` holding the slot of `id`, documenting its derivation.
+pub fn constant(name: &str, title: &str, derivation: Derivation, id: &str) -> String {
+    format!(
+        "\n/// ## {}\n///\n/// `{}`, with `id` = `\"{}\"`.\n#define constant {} = {}\n",
+        title,
+        derivation.formula(),
+        id,
+        name,
+        hex(&derivation.slot(id)),
+    )
+}
+
+/// Subtracts one from a big-endian word, wrapping.
+fn decrement(word: &mut [u8; 32]) {
+    for byte in word.iter_mut().rev() {
+        let (decremented, borrow) = byte.overflowing_sub(1);
+        *byte = decremented;
+        if !borrow {
+            break;
+        }
+    }
+}
@@ -0,0 +1,196 @@
+//! A small template engine for the generated libraries and test suites.
+//!
+//! Templates are text with tags in double braces:
+//!
+//! - `{{name}}` - the text value `name`, inserted verbatim.
+//! - `{{#if name}}...{{else}}...{{/if}}` - the first branch if the flag `name` is set, the second,
+//!   optional, branch otherwise.
+//! - `{{#each name}}...{{/each}}` - the body once for each context of the list `name`, looking up
+//!   names in the item before the enclosing context.
+//!
+//! Templates are constants of the generators, so a malformed template or an unknown name is a bug
+//! and panics rather than returning an error.
+
+use std::collections::BTreeMap;
+
+/// A value of a template context.
+#[derive(Clone, Debug)]
+enum Value {
+    Text(String),
+    Flag(bool),
+    List(Vec<Context>),
+}
+
+/// The values a template is rendered with, by name.
+#[derive(Clone, Debug, Default)]
+pub struct Context {
+    values: BTreeMap<&'static str, Value>,
+}
+
+impl Context {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the text of `{{name}}` tags.
+    pub fn text(mut self, name: &'static str, text: impl Into<String>) -> Self {
+        self.values.insert(name, Value::Text(text.into()));
+        self
+    }
+
+    /// Sets the flag of `{{#if name}}` blocks.
+    pub fn flag(mut self, name: &'static str, flag: bool) -> Self {
+        self.values.insert(name, Value::Flag(flag));
+        self
+    }
+
+    /// Sets the items of `{{#each name}}` blocks.
+    pub fn list(mut self, name: &'static str, items: Vec<Context>) -> Self {
+        self.values.insert(name, Value::List(items));
+        self
+    }
+}
+
+enum Node<'a> {
+    Text(&'a str),
+    Value(&'a str),
+    If(&'a str, Vec<Node<'a>>, Vec<Node<'a>>),
+    Each(&'a str, Vec<Node<'a>>),
+}
+
+/// Renders `template` with the values of `context`.
+pub fn render(template: &str, context: &Context) -> String {
+    let mut rest = template;
+    let (nodes, end) = parse(&mut rest);
+    if let Some(end) = end {
+        panic!("unexpected template tag `{{{{{}}}}}`", end);
+    }
+
+    let mut out = String::with_capacity(template.len());
+    render_nodes(&nodes, &[context], &mut out);
+    out
+}
+
+/// Parses nodes up to the end of `src` or to the `else` or closing tag of the enclosing block,
+/// returned with the nodes, leaving `src` after it.
+fn parse<'a>(src: &mut &'a str) -> (Vec<Node<'a>>, Option<&'a str>) {
+    let mut nodes = Vec::new();
+
+    while let Some(open) = src.find("{{") {
+        if open > 0 {
+            nodes.push(Node::Text(&src[..open]));
+        }
+        let close = src[open..].find("}}").expect("unclosed template tag") + open;
+        let tag = src[open + 2..close].trim();
+        *src = &src[close + 2..];
+
+        if let Some(name) = tag.strip_prefix("#if ") {
+            let (then, end) = parse(src);
+            let otherwise = match end {
+                Some("else") => block(src, "/if"),
+                _ => {
+                    expect_end(end, "/if");
+                    Vec::new()
+                }
+            };
+            nodes.push(Node::If(name.trim(), then, otherwise));
+        } else if let Some(name) = tag.strip_prefix("#each ") {
+            nodes.push(Node::Each(name.trim(), block(src, "/each")));
+        } else if tag == "else" || tag.starts_with('/') {
+            return (nodes, Some(tag));
+        } else {
+            nodes.push(Node::Value(tag));
+        }
+    }
+
+    if !src.is_empty() {
+        nodes.push(Node::Text(src));
+        *src = "";
+    }
+    (nodes, None)
+}
+
+/// Parses the nodes of a block up to its closing tag `end`.
+fn block<'a>(src: &mut &'a str, end: &str) -> Vec<Node<'a>> {
+    let (nodes, found) = parse(src);
+    expect_end(found, end);
+    nodes
+}
+
+fn expect_end(found: Option<&str>, end: &str) {
+    if found != Some(end) {
+        panic!(
+            "template block must end with `{{{{{}}}}}`, found {:?}",
+            end, found
+        );
+    }
+}
+
+fn lookup<'a>(scopes: &[&'a Context], name: &str) -> &'a Value {
+    scopes
+        .iter()
+        .rev()
+        .find_map(|scope| scope.values.get(name))
+        .unwrap_or_else(|| panic!("no template value `{}`", name))
+}
+
+fn render_nodes(nodes: &[Node], scopes: &[&Context], out: &mut String) {
+    for node in nodes {
+        match node {
+            Node::Text(text) => out.push_str(text),
+            Node::Value(name) => match lookup(scopes, name) {
+                Value::Text(text) => out.push_str(text),
+                _ => panic!("template value `{}` is not text", name),
+            },
+            Node::If(name, then, otherwise) => match lookup(scopes, name) {
+                Value::Flag(true) => render_nodes(then, scopes, out),
+                Value::Flag(false) => render_nodes(otherwise, scopes, out),
+                _ => panic!("template value `{}` is not a flag", name),
+            },
+            Node::Each(name, body) => match lookup(scopes, name) {
+                Value::List(items) => {
+                    for item in items {
+                        let mut inner = scopes.to_vec();
+                        inner.push(item);
+                        render_nodes(body, &inner, out);
+                    }
+                }
+                _ => panic!("template value `{}` is not a list", name),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn blocks() {
+        let template = "{{#each sizes}}U{{size}}{{#if last}}.{{else}}, {{/if}}{{/each}} in {{lib}}";
+        let sizes = [8, 16, 256]
+            .iter()
+            .map(|size| {
+                Context::new()
+                    .text("size", size.to_string())
+                    .flag("last", *size == 256)
+            })
+            .collect();
+        let context = Context::new().text("lib", "libcast").list("sizes", sizes);
+
+        assert_eq!(render(template, &context), "U8, U16, U256. in libcast");
+    }
+
+    #[test]
+    fn values_are_verbatim() {
+        let context = Context::new().text("a", "{{b}}").flag("set", false);
+
+        assert_eq!(render("x{{ a }}y{{#if set}}z{{/if}}", &context), "x{{b}}y");
+    }
+
+    #[test]
+    #[should_panic(expected = "must end with `{{/if}}`")]
+    fn unclosed_block() {
+        render("{{#if set}}", &Context::new().flag("set", true));
+    }
+}
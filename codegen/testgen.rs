@@ -0,0 +1,485 @@
+//! Foundry test suites of libraries, asserting the results of each macro on boundary values as run
+//! on the embedded EVM, and fuzzing them for unexpected halts.
+
+use std::collections::BTreeMap;
+use std::path::{Component, Path};
+
+use crate::evm::{addr, Call, Evm, Halt, Outcome};
+use crate::huff::{self, Program};
+use crate::listing::{wrap, ENTRY};
+use crate::profile::{self, Profile};
+use crate::report;
+use crate::templates::{self, Context};
+use crate::u256::U256;
+
+const CALLER: u64 = 0xca11;
+const HARNESS: u64 = 0x4af;
+
+/// Selector of Solidity's `Panic(uint256)`.
+const PANIC: [u8; 4] = [0x4e, 0x48, 0x7b, 0x71];
+
+/// Fuzzed inputs sampled for each macro before it is fuzzed in the suite.
+const SAMPLES: usize = 16;
+
+/// Opcodes whose results differ between the embedded EVM and a Foundry test, reading the
+/// environment, the code layout or other accounts.
+const ENVIRONMENT: &[u8] = &[
+    0x30, 0x31, 0x32, 0x33, 0x34, 0x38, 0x39, 0x3a, 0x3b, 0x3c, 0x3f, 0x40, 0x41, 0x42, 0x43, 0x44,
+    0x45, 0x46, 0x47, 0x48, 0x49, 0x4a, 0x58, 0x5a, 0xf0, 0xf1, 0xf2, 0xf4, 0xf5, 0xfa, 0xff,
+];
+
+/// A macro exposed by the harness, as a function taking its stack arguments.
+struct Function {
+    name: String,
+    takes: usize,
+    returns: usize,
+    selector: [u8; 4],
+    /// Calldata arguments and the expected success and output of each boundary case.
+    cases: Vec<(Vec<U256>, bool, Vec<u8>)>,
+    /// Whether every sampled input ran to the end or reverted with an error of the library.
+    fuzzed: bool,
+}
+
+/// Renders the Foundry test suite of the library at `file` to be written to `out`, a
+/// `NameHarness.huff` exposing each macro without template arguments and a `Name.t.sol` testing
+/// them, as file names and contents. Paths are resolved against the working directory, the project
+/// root from which Foundry deploys the harness out of `src`.
+///
+/// Macros are tested on the values observed running them for `profile`, and macros reading the
+/// environment are only fuzzed.
+pub fn render(
+    file: &Path,
+    out: &Path,
+    profile: &Profile,
+) -> Result<Vec<(String, String)>, huff::Error> {
+    let program = wrap(file, "")?;
+    let src = std::fs::read_to_string(file).map_err(|e| huff::Error { msg: e.to_string() })?;
+    // by name, so the suites are the same on every run
+    let errors = program
+        .errors
+        .iter()
+        .collect::<BTreeMap<&String, &String>>()
+        .into_values()
+        .map(|signature| huff::selector(signature))
+        .chain([PANIC])
+        .collect::<Vec<[u8; 4]>>();
+
+    let functions = report::macros(&program, &src)
+        .into_iter()
+        .map(|name| function(&program, &name, &errors, profile))
+        .collect::<Result<Vec<Function>, huff::Error>>()?;
+
+    let stem = file
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or_default();
+    let name = contract_name(stem);
+    let library = file
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or_default();
+    let root = std::env::current_dir().map_err(|e| huff::Error { msg: e.to_string() })?;
+    let include = relative(&root.join(out), &root.join(file));
+    let deploy = relative(
+        &root.join("src"),
+        &root.join(out).join(format!("{}Harness", name)),
+    );
+
+    Ok(vec![
+        (
+            format!("{}Harness.huff", name),
+            harness(&functions, library, &include),
+        ),
+        (
+            format!("{}.t.sol", name),
+            test(&functions, &name, library, &deploy, &errors),
+        ),
+    ])
+}
+
+/// The contract name of a library, its file stem without the `lib` prefix, capitalized.
+fn contract_name(stem: &str) -> String {
+    let stem = stem.strip_prefix("lib").unwrap_or(stem);
+    let mut name = stem
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .collect::<String>();
+    if let Some(first) = name.get_mut(..1) {
+        first.make_ascii_uppercase();
+    }
+    name
+}
+
+/// The path of `to` relative to the directory `from`, both absolute or relative to the same
+/// directory.
+fn relative(from: &Path, to: &Path) -> String {
+    let from = from
+        .components()
+        .filter(|c| *c != Component::CurDir)
+        .collect::<Vec<_>>();
+    let to = to
+        .components()
+        .filter(|c| *c != Component::CurDir)
+        .collect::<Vec<_>>();
+    let common = from.iter().zip(&to).take_while(|(a, b)| a == b).count();
+
+    let parents = vec![".."; from.len() - common];
+    let rest = to[common..]
+        .iter()
+        .map(|c| c.as_os_str().to_string_lossy().into_owned());
+    parents
+        .into_iter()
+        .map(String::from)
+        .chain(rest)
+        .collect::<Vec<String>>()
+        .join("/")
+}
+
+/// The harness target of `name`, loading its arguments from calldata below the selector and
+/// returning its outputs.
+fn target(name: &str, takes: usize, returns: usize) -> String {
+    let loads = (0..takes)
+        .rev()
+        .map(|i| format!("{:#04x} calldataload ", 4 + 32 * i))
+        .collect::<String>();
+    let stores = (0..returns)
+        .map(|i| format!("{:#04x} mstore ", 32 * i))
+        .collect::<String>();
+
+    format!(
+        "{}{}() {}{:#04x} 0x00 return",
+        loads,
+        name,
+        stores,
+        32 * returns
+    )
+}
+
+/// `program` with an `ENTRY` of `body`, sparing parsing the library again for each macro.
+fn with_main(program: &Program, body: &str) -> Result<Program, huff::Error> {
+    let main = format!(
+        "#define macro MAIN() = takes (0) returns (0) {{\n    {}\n}}\n",
+        body
+    );
+    let mut program = program.clone();
+    let main = Program::parse_str(&main, Path::new("."))?.macros;
+    program
+        .macros
+        .extend(main.into_values().map(|main| (ENTRY.to_string(), main)));
+    Ok(program)
+}
+
+fn function(
+    program: &Program,
+    name: &str,
+    errors: &[[u8; 4]],
+    profile: &Profile,
+) -> Result<Function, huff::Error> {
+    let (takes, returns) = (program.macros[name].takes, program.macros[name].returns);
+    let selector = huff::selector(&signature(name, takes));
+    let main = format!(
+        "0x00 calldataload 0xe0 shr {}",
+        target(name, takes, returns)
+    );
+    let code = profile::compile(with_main(program, &main)?, ENTRY, profile)?;
+    let invocation = with_main(program, &format!("{}()", name))?;
+    let invocation = profile::compile(invocation, ENTRY, profile)?;
+
+    let mut evm = Evm::new();
+    evm.set_code(addr(HARNESS), code);
+    let mut call = |args: &[U256]| {
+        let data = args
+            .iter()
+            .flat_map(|arg| arg.to_be_bytes())
+            .collect::<Vec<u8>>();
+        transact(&mut evm, [&selector[..], &data].concat())
+    };
+
+    let cases = match reads_environment(&invocation) {
+        true => Vec::new(),
+        false => boundary_args(name, takes)
+            .into_iter()
+            .filter_map(|args| {
+                let outcome = call(&args);
+                match outcome.halt {
+                    Halt::Stop | Halt::Return => Some((args, true, outcome.output)),
+                    Halt::Revert => Some((args, false, outcome.output)),
+                    // out of gas on the embedded EVM's limit, or an exception Foundry reports alike
+                    Halt::Exception(_) => None,
+                }
+            })
+            .collect(),
+    };
+
+    let mut rng = Rng(0x5eed);
+    let fuzzed = takes > 0
+        && (0..SAMPLES).all(|_| {
+            let args = (0..takes).map(|_| rng.word()).collect::<Vec<U256>>();
+            handled(&call(&args), errors)
+        });
+
+    Ok(Function {
+        name: name.to_string(),
+        takes,
+        returns,
+        selector,
+        cases,
+        fuzzed,
+    })
+}
+
+/// The signature of the harness function of a macro taking `takes` words.
+fn signature(name: &str, takes: usize) -> String {
+    format!("{}({})", name, vec!["uint256"; takes].join(","))
+}
+
+fn transact(evm: &mut Evm, data: Vec<u8>) -> Outcome {
+    evm.transact(Call {
+        caller: addr(CALLER),
+        address: addr(HARNESS),
+        code_address: addr(HARNESS),
+        value: U256::ZERO,
+        data,
+        gas: 30_000_000,
+        is_static: false,
+        transfer: false,
+    })
+}
+
+/// Whether the outcome ran to the end, or reverted with one of `errors`.
+fn handled(outcome: &Outcome, errors: &[[u8; 4]]) -> bool {
+    match outcome.halt {
+        Halt::Stop | Halt::Return => true,
+        Halt::Revert => errors.iter().any(|error| outcome.output.starts_with(error)),
+        Halt::Exception(_) => false,
+    }
+}
+
+/// Whether `code` uses an opcode reading the environment.
+fn reads_environment(code: &[u8]) -> bool {
+    let mut pc = 0;
+    while pc < code.len() {
+        let op = code[pc];
+        if ENVIRONMENT.contains(&op) {
+            return true;
+        }
+        pc += 1 + if (0x60..=0x7f).contains(&op) {
+            op as usize - 0x5f
+        } else {
+            0
+        };
+    }
+    false
+}
+
+/// The arguments of the boundary cases of a macro: zero, one, two and the largest word, with the
+/// largest value of the type and one past it for macros named after an unsigned type, every pair
+/// of them for macros of up to two arguments and each repeated for the others.
+fn boundary_args(name: &str, takes: usize) -> Vec<Vec<U256>> {
+    let mut values = vec![U256::ZERO, U256::ONE, U256::from_u64(2), U256::MAX];
+    if let Some(bits) = type_bits(name).filter(|bits| *bits < 256) {
+        let max = U256::ONE.shl(bits).wrapping_sub(U256::ONE);
+        values.extend([max, max.wrapping_add(U256::ONE)]);
+    }
+
+    match takes {
+        0 => vec![Vec::new()],
+        1 => values.into_iter().map(|value| vec![value]).collect(),
+        2 => values
+            .iter()
+            .flat_map(|a| values.iter().map(|b| vec![*a, *b]))
+            .collect(),
+        _ => values.into_iter().map(|value| vec![value; takes]).collect(),
+    }
+}
+
+/// The width of the unsigned type a macro is named after, e.g. 80 for `SAFE_ADD_U80`.
+fn type_bits(name: &str) -> Option<u32> {
+    name.split('_').find_map(|part| {
+        let bits = part.strip_prefix('U')?.parse::<u32>().ok()?;
+        (bits > 0 && bits <= 256 && bits % 8 == 0).then_some(bits)
+    })
+}
+
+/// An xorshift generator of the sampled inputs.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    /// A random word shifted right by a random amount, as the suite fuzzes them.
+    fn word(&mut self) -> U256 {
+        let word = U256([self.next(), self.next(), self.next(), self.next()]);
+        word.shr((self.next() % 256) as u32)
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn harness(functions: &[Function], library: &str, include: &str) -> String {
+    let functions = functions
+        .iter()
+        .map(|f| {
+            Context::new()
+                .text("name", f.name.as_str())
+                .text("label", f.name.to_lowercase())
+                .text("signature", signature(&f.name, f.takes))
+                .text("returns", vec!["uint256"; f.returns].join(", "))
+                .text("target", target(&f.name, f.takes, f.returns))
+        })
+        .collect();
+
+    let context = Context::new()
+        .text("library", library)
+        .text("include", include)
+        .list("functions", functions);
+    templates::render(HARNESS_TEMPLATE, &context)
+}
+
+fn test(
+    functions: &[Function],
+    name: &str,
+    library: &str,
+    deploy: &str,
+    errors: &[[u8; 4]],
+) -> String {
+    let call = |f: &Function, args: Vec<String>| {
+        let args = args
+            .iter()
+            .map(|arg| format!(", {}", arg))
+            .collect::<String>();
+        format!(
+            "abi.encodeWithSelector(bytes4(0x{}){})",
+            hex(&f.selector),
+            args
+        )
+    };
+
+    let functions = functions
+        .iter()
+        .map(|f| {
+            let cases = f
+                .cases
+                .iter()
+                .map(|(args, ok, output)| {
+                    let args = args.iter().map(|arg| format!("uint256({})", arg.to_hex()));
+                    Context::new()
+                        .text("call", call(f, args.collect()))
+                        .text("ok", ok.to_string())
+                        .text("output", hex(output))
+                })
+                .collect::<Vec<Context>>();
+            let args = (0..f.takes).map(|i| format!("values[{}] >> shifts[{}]", i, i));
+
+            Context::new()
+                .text("name", f.name.as_str())
+                .flag("has_cases", !cases.is_empty())
+                .list("cases", cases)
+                .flag("fuzzed", f.fuzzed)
+                .text("takes", f.takes.to_string())
+                .text("fuzz_call", call(f, args.collect()))
+        })
+        .collect();
+
+    let errors = errors
+        .iter()
+        .map(|error| format!("selector == bytes4(0x{})", hex(error)))
+        .collect::<Vec<String>>()
+        .join(" || ");
+
+    let context = Context::new()
+        .text("library", library)
+        .text("name", name)
+        .text("deploy", deploy)
+        .text("errors", errors)
+        .list("functions", functions);
+    templates::render(TEST_TEMPLATE, &context)
+}
+
+const HARNESS_TEMPLATE: &str = r#"/// Generated by `huffgen`, exposes `{{library}}` to its tests.
+
+#include "{{include}}"
+
+{{#each functions}}#define function {{signature}} nonpayable returns ({{returns}})
+{{/each}}
+#define macro MAIN() = takes (0) returns (0) {
+    0x00 calldataload 0xe0 shr
+
+{{#each functions}}    dup1 __FUNC_SIG({{name}}) eq {{label}} jumpi
+{{/each}}
+    0x00 dup1 revert
+
+{{#each functions}}    {{label}}:
+        {{target}}
+{{/each}}}
+"#;
+
+const TEST_TEMPLATE: &str = r#"// SPDX-License-Identifier: MIT
+// Generated by `huffgen`, tests the macros of `{{library}}` on boundary values and fuzzed inputs.
+pragma solidity ^0.8.0;
+
+import {Test} from "forge-std/Test.sol";
+import {HuffDeployer} from "foundry-huff/HuffDeployer.sol";
+
+contract {{name}}Test is Test {
+    address internal harness;
+
+    function setUp() public {
+        harness = HuffDeployer.deploy("{{deploy}}");
+    }
+
+    /// Asserts the harness succeeds or reverts as expected, with the expected output.
+    function assertCall(bytes memory data, bool ok, bytes memory output) internal {
+        (bool success, bytes memory returned) = harness.call(data);
+
+        assertEq(success, ok);
+        assertEq(returned, output);
+    }
+
+    /// Asserts the harness succeeds or reverts with an error of the library.
+    function assertHandled(bytes memory data) internal {
+        (bool success, bytes memory returned) = harness.call(data);
+
+        if (!success) {
+            assertGe(returned.length, 4);
+            bytes4 selector;
+            assembly {
+                selector := mload(add(returned, 0x20))
+            }
+            assertTrue({{errors}});
+        }
+    }
+{{#each functions}}{{#if has_cases}}
+    function test_{{name}}() public {
+{{#each cases}}        assertCall({{call}}, {{ok}}, hex"{{output}}");
+{{/each}}    }
+{{/if}}{{#if fuzzed}}
+    function testFuzz_{{name}}(uint256[{{takes}}] memory values, uint8[{{takes}}] memory shifts) public {
+        assertHandled({{fuzz_call}});
+    }
+{{/if}}{{/each}}}
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn paths() {
+        let include = relative(Path::new("test/huff"), Path::new("src/libcast.huff"));
+        let deploy = relative(Path::new("src"), Path::new("./test/huff/CastHarness"));
+
+        assert_eq!(include, "../../src/libcast.huff");
+        assert_eq!(deploy, "../test/huff/CastHarness");
+        assert_eq!(contract_name("libsafemath"), "Safemath");
+        assert_eq!(type_bits("SAFE_ADD_U80"), Some(80));
+        assert_eq!(type_bits("UNSAFE_TO_ADDRESS"), None);
+    }
+}
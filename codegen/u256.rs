@@ -0,0 +1,438 @@
+//! Minimal 256-bit unsigned integer with EVM (wrapping) semantics.
+
+// arithmetic is named after the EVM opcodes it implements, not the panicking std operators
+#![allow(clippy::should_implement_trait)]
+
+use std::cmp::Ordering;
+use std::fmt;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct U256(pub [u64; 4]);
+
+impl U256 {
+    pub const ZERO: U256 = U256([0; 4]);
+    pub const ONE: U256 = U256([1, 0, 0, 0]);
+    pub const MAX: U256 = U256([u64::MAX; 4]);
+
+    pub fn from_u64(v: u64) -> Self {
+        U256([v, 0, 0, 0])
+    }
+
+    pub fn from_be_bytes(bytes: &[u8]) -> Self {
+        assert!(bytes.len() <= 32);
+        let mut buf = [0u8; 32];
+        buf[32 - bytes.len()..].copy_from_slice(bytes);
+        let mut limbs = [0u64; 4];
+        for (i, limb) in limbs.iter_mut().enumerate() {
+            let start = 32 - (i + 1) * 8;
+            *limb = u64::from_be_bytes(buf[start..start + 8].try_into().unwrap());
+        }
+        U256(limbs)
+    }
+
+    pub fn to_be_bytes(self) -> [u8; 32] {
+        let mut buf = [0u8; 32];
+        for i in 0..4 {
+            let start = 32 - (i + 1) * 8;
+            buf[start..start + 8].copy_from_slice(&self.0[i].to_be_bytes());
+        }
+        buf
+    }
+
+    pub fn from_hex(s: &str) -> Option<Self> {
+        let s = s.strip_prefix("0x").unwrap_or(s);
+        if s.is_empty() || s.len() > 64 {
+            return None;
+        }
+        let mut v = U256::ZERO;
+        for c in s.chars() {
+            let d = c.to_digit(16)? as u64;
+            v = v.shl(4) | U256::from_u64(d);
+        }
+        Some(v)
+    }
+
+    pub fn from_dec(s: &str) -> Option<Self> {
+        let mut v = U256::ZERO;
+        let ten = U256::from_u64(10);
+        for c in s.chars() {
+            let d = c.to_digit(10)? as u64;
+            let (m, o1) = v.overflowing_mul(ten);
+            let (a, o2) = m.overflowing_add(U256::from_u64(d));
+            if o1 || o2 {
+                return None;
+            }
+            v = a;
+        }
+        Some(v)
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.0 == [0; 4]
+    }
+
+    pub fn as_usize(&self) -> Option<usize> {
+        if self.0[1] != 0 || self.0[2] != 0 || self.0[3] != 0 || self.0[0] > usize::MAX as u64 {
+            None
+        } else {
+            Some(self.0[0] as usize)
+        }
+    }
+
+    pub fn bits(&self) -> u32 {
+        for i in (0..4).rev() {
+            if self.0[i] != 0 {
+                return (i as u32) * 64 + 64 - self.0[i].leading_zeros();
+            }
+        }
+        0
+    }
+
+    pub fn bit(&self, n: u32) -> bool {
+        if n >= 256 {
+            return false;
+        }
+        (self.0[(n / 64) as usize] >> (n % 64)) & 1 == 1
+    }
+
+    pub fn overflowing_add(self, rhs: Self) -> (Self, bool) {
+        let mut out = [0u64; 4];
+        let mut carry = false;
+        for (i, o) in out.iter_mut().enumerate() {
+            let (s1, c1) = self.0[i].overflowing_add(rhs.0[i]);
+            let (s2, c2) = s1.overflowing_add(carry as u64);
+            *o = s2;
+            carry = c1 || c2;
+        }
+        (U256(out), carry)
+    }
+
+    pub fn overflowing_sub(self, rhs: Self) -> (Self, bool) {
+        let mut out = [0u64; 4];
+        let mut borrow = false;
+        for (i, o) in out.iter_mut().enumerate() {
+            let (s1, b1) = self.0[i].overflowing_sub(rhs.0[i]);
+            let (s2, b2) = s1.overflowing_sub(borrow as u64);
+            *o = s2;
+            borrow = b1 || b2;
+        }
+        (U256(out), borrow)
+    }
+
+    /// Full 512-bit product as (low, high).
+    pub fn full_mul(self, rhs: Self) -> (Self, Self) {
+        let mut res = [0u64; 8];
+        for i in 0..4 {
+            let mut carry: u128 = 0;
+            for j in 0..4 {
+                let cur = res[i + j] as u128 + (self.0[i] as u128) * (rhs.0[j] as u128) + carry;
+                res[i + j] = cur as u64;
+                carry = cur >> 64;
+            }
+            res[i + 4] = carry as u64;
+        }
+        (
+            U256([res[0], res[1], res[2], res[3]]),
+            U256([res[4], res[5], res[6], res[7]]),
+        )
+    }
+
+    pub fn overflowing_mul(self, rhs: Self) -> (Self, bool) {
+        let (lo, hi) = self.full_mul(rhs);
+        (lo, !hi.is_zero())
+    }
+
+    pub fn wrapping_add(self, rhs: Self) -> Self {
+        self.overflowing_add(rhs).0
+    }
+
+    pub fn wrapping_sub(self, rhs: Self) -> Self {
+        self.overflowing_sub(rhs).0
+    }
+
+    pub fn wrapping_mul(self, rhs: Self) -> Self {
+        self.overflowing_mul(rhs).0
+    }
+
+    pub fn checked_add(self, rhs: Self) -> Option<Self> {
+        match self.overflowing_add(rhs) {
+            (v, false) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// Returns (quotient, remainder). Division by zero yields (0, 0).
+    pub fn div_rem(self, rhs: Self) -> (Self, Self) {
+        if rhs.is_zero() {
+            return (U256::ZERO, U256::ZERO);
+        }
+        if self < rhs {
+            return (U256::ZERO, self);
+        }
+        let mut q = U256::ZERO;
+        let mut r = U256::ZERO;
+        for i in (0..self.bits()).rev() {
+            r = r.shl(1);
+            if self.bit(i) {
+                r.0[0] |= 1;
+            }
+            if r >= rhs {
+                r = r.wrapping_sub(rhs);
+                q.0[(i / 64) as usize] |= 1 << (i % 64);
+            }
+        }
+        (q, r)
+    }
+
+    pub fn div(self, rhs: Self) -> Self {
+        self.div_rem(rhs).0
+    }
+
+    pub fn rem(self, rhs: Self) -> Self {
+        self.div_rem(rhs).1
+    }
+
+    /// 512-bit by 256-bit remainder.
+    fn rem512(lo: Self, hi: Self, m: Self) -> Self {
+        let mut r = U256::ZERO;
+        for i in (0..512u32).rev() {
+            let bit = if i >= 256 { hi.bit(i - 256) } else { lo.bit(i) };
+            let overflow = r.bit(255);
+            r = r.shl(1);
+            if bit {
+                r.0[0] |= 1;
+            }
+            if overflow || r >= m {
+                r = r.wrapping_sub(m);
+            }
+        }
+        r
+    }
+
+    pub fn addmod(self, rhs: Self, m: Self) -> Self {
+        if m.is_zero() {
+            return U256::ZERO;
+        }
+        let (s, c) = self.overflowing_add(rhs);
+        Self::rem512(s, if c { U256::ONE } else { U256::ZERO }, m)
+    }
+
+    pub fn mulmod(self, rhs: Self, m: Self) -> Self {
+        if m.is_zero() {
+            return U256::ZERO;
+        }
+        let (lo, hi) = self.full_mul(rhs);
+        Self::rem512(lo, hi, m)
+    }
+
+    pub fn pow(self, mut exp: Self) -> Self {
+        let mut base = self;
+        let mut acc = U256::ONE;
+        while !exp.is_zero() {
+            if exp.bit(0) {
+                acc = acc.wrapping_mul(base);
+            }
+            base = base.wrapping_mul(base);
+            exp = exp.shr(1);
+        }
+        acc
+    }
+
+    pub fn shl(self, n: u32) -> Self {
+        if n >= 256 {
+            return U256::ZERO;
+        }
+        let limbs = (n / 64) as usize;
+        let bits = n % 64;
+        let mut out = [0u64; 4];
+        for i in (limbs..4).rev() {
+            out[i] = self.0[i - limbs] << bits;
+            if bits > 0 && i > limbs {
+                out[i] |= self.0[i - limbs - 1] >> (64 - bits);
+            }
+        }
+        U256(out)
+    }
+
+    pub fn shr(self, n: u32) -> Self {
+        if n >= 256 {
+            return U256::ZERO;
+        }
+        let limbs = (n / 64) as usize;
+        let bits = n % 64;
+        let mut out = [0u64; 4];
+        for (i, limb) in out.iter_mut().enumerate().take(4 - limbs) {
+            *limb = self.0[i + limbs] >> bits;
+            if bits > 0 && i + limbs + 1 < 4 {
+                *limb |= self.0[i + limbs + 1] << (64 - bits);
+            }
+        }
+        U256(out)
+    }
+
+    pub fn is_neg(&self) -> bool {
+        self.bit(255)
+    }
+
+    pub fn neg(self) -> Self {
+        (!self).wrapping_add(U256::ONE)
+    }
+
+    pub fn abs(self) -> Self {
+        if self.is_neg() {
+            self.neg()
+        } else {
+            self
+        }
+    }
+
+    pub fn sdiv(self, rhs: Self) -> Self {
+        if rhs.is_zero() {
+            return U256::ZERO;
+        }
+        let q = self.abs().div(rhs.abs());
+        if self.is_neg() != rhs.is_neg() {
+            q.neg()
+        } else {
+            q
+        }
+    }
+
+    pub fn smod(self, rhs: Self) -> Self {
+        if rhs.is_zero() {
+            return U256::ZERO;
+        }
+        let r = self.abs().rem(rhs.abs());
+        if self.is_neg() {
+            r.neg()
+        } else {
+            r
+        }
+    }
+
+    pub fn slt(&self, rhs: &Self) -> bool {
+        match (self.is_neg(), rhs.is_neg()) {
+            (true, false) => true,
+            (false, true) => false,
+            _ => self < rhs,
+        }
+    }
+
+    pub fn sar(self, n: u32) -> Self {
+        if !self.is_neg() {
+            return self.shr(n);
+        }
+        if n >= 256 {
+            return U256::MAX;
+        }
+        !((!self).shr(n))
+    }
+
+    pub fn signextend(self, b: Self) -> Self {
+        match b.as_usize() {
+            Some(b) if b < 31 => {
+                let bit = (b as u32) * 8 + 7;
+                let mask = U256::ONE.shl(bit + 1).wrapping_sub(U256::ONE);
+                if self.bit(bit) {
+                    self | !mask
+                } else {
+                    self & mask
+                }
+            }
+            _ => self,
+        }
+    }
+
+    pub fn byte(self, i: Self) -> Self {
+        match i.as_usize() {
+            Some(i) if i < 32 => U256::from_u64(self.to_be_bytes()[i] as u64),
+            _ => U256::ZERO,
+        }
+    }
+
+    pub fn to_hex(self) -> String {
+        let bytes = self.to_be_bytes();
+        let s: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+        let t = s.trim_start_matches('0');
+        format!("0x{}", if t.is_empty() { "0" } else { t })
+    }
+}
+
+impl PartialOrd for U256 {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for U256 {
+    fn cmp(&self, other: &Self) -> Ordering {
+        for i in (0..4).rev() {
+            match self.0[i].cmp(&other.0[i]) {
+                Ordering::Equal => continue,
+                o => return o,
+            }
+        }
+        Ordering::Equal
+    }
+}
+
+impl std::ops::BitAnd for U256 {
+    type Output = U256;
+    fn bitand(self, rhs: Self) -> Self {
+        U256([
+            self.0[0] & rhs.0[0],
+            self.0[1] & rhs.0[1],
+            self.0[2] & rhs.0[2],
+            self.0[3] & rhs.0[3],
+        ])
+    }
+}
+
+impl std::ops::BitOr for U256 {
+    type Output = U256;
+    fn bitor(self, rhs: Self) -> Self {
+        U256([
+            self.0[0] | rhs.0[0],
+            self.0[1] | rhs.0[1],
+            self.0[2] | rhs.0[2],
+            self.0[3] | rhs.0[3],
+        ])
+    }
+}
+
+impl std::ops::BitXor for U256 {
+    type Output = U256;
+    fn bitxor(self, rhs: Self) -> Self {
+        U256([
+            self.0[0] ^ rhs.0[0],
+            self.0[1] ^ rhs.0[1],
+            self.0[2] ^ rhs.0[2],
+            self.0[3] ^ rhs.0[3],
+        ])
+    }
+}
+
+impl std::ops::Not for U256 {
+    type Output = U256;
+    fn not(self) -> Self {
+        U256([!self.0[0], !self.0[1], !self.0[2], !self.0[3]])
+    }
+}
+
+impl fmt::Debug for U256 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_hex())
+    }
+}
+
+impl fmt::Display for U256 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_hex())
+    }
+}
+
+impl From<u64> for U256 {
+    fn from(v: u64) -> Self {
+        U256::from_u64(v)
+    }
+}
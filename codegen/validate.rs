@@ -0,0 +1,221 @@
+//! Validation of the generated files, before they are written.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::io;
+use std::path::Path;
+
+use crate::config;
+use crate::huff::{Arg, Program, Stmt};
+use crate::library::{parent, resolve};
+
+/// A generated file, parsed without its includes.
+struct Parsed {
+    program: Program,
+    /// The paths of its includes, from the root.
+    includes: Vec<String>,
+}
+
+/// Checks that each of the generated `files` parses as Huff, and that the macros, constants,
+/// errors and events each of their macros uses are defined by the file or the files it includes.
+///
+/// Each include must name another of the files, as a file including one that was not generated
+/// would not compile where it is written. The `external` macros, which the generated macros
+/// delegate to, are left for the contract including the files to define.
+pub fn validate(files: &BTreeMap<String, String>, external: &BTreeSet<String>) -> io::Result<()> {
+    let mut parsed = BTreeMap::new();
+    for (name, contents) in files {
+        parsed.insert(name.as_str(), parse(name, contents)?);
+    }
+
+    for (name, file) in &parsed {
+        let mut defined = definitions(name, &parsed)?;
+        defined.extend(external.iter().map(|name| ("macro", name.clone())));
+        let mut macros = file.program.macros.iter().collect::<Vec<_>>();
+        macros.sort_by_key(|(name, _)| *name);
+        for (macro_name, definition) in macros {
+            let mut uses = Vec::new();
+            for stmt in &definition.body {
+                stmt_uses(stmt, &mut uses);
+            }
+            if let Some((kind, used)) = uses.into_iter().find(|use_| !defined.contains(use_)) {
+                return Err(config::invalid(format!(
+                    "generated `{}` uses the undefined {} `{}` in `{}`",
+                    name, kind, used, macro_name,
+                )));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Parses the generated file `name`, failing with the line it does not parse at.
+fn parse(name: &str, contents: &str) -> io::Result<Parsed> {
+    // includes are resolved against the other generated files rather than loaded
+    let mut includes = Vec::new();
+    let src = contents
+        .lines()
+        .map(|line| match line.strip_prefix("#include \"") {
+            Some(include) => {
+                includes.push(resolve(
+                    parent(name),
+                    include.split('"').next().unwrap_or(""),
+                ));
+                ""
+            }
+            None => line,
+        })
+        .collect::<Vec<&str>>()
+        .join("\n");
+
+    let program = Program::parse_file(&src, Path::new(name)).map_err(|e| {
+        // errors are reported as `file:line: message`
+        let line = e.msg.strip_prefix(&format!("{}:", name)).and_then(|rest| {
+            let (line, _) = rest.split_once(':')?;
+            line.parse::<usize>().ok()
+        });
+        let source = line
+            .and_then(|line| Some((line, contents.lines().nth(line.checked_sub(1)?)?)))
+            .map_or(String::new(), |(line, source)| {
+                format!("\n{:>5} | {}", line, source)
+            });
+        config::invalid(format!(
+            "generated `{}` does not parse: {}{}",
+            name, e.msg, source
+        ))
+    })?;
+
+    Ok(Parsed { program, includes })
+}
+
+/// The definitions of the file `name` and those it includes, failing if it includes a file that
+/// was not generated.
+fn definitions(
+    name: &str,
+    parsed: &BTreeMap<&str, Parsed>,
+) -> io::Result<BTreeSet<(&'static str, String)>> {
+    let mut defined = BTreeSet::new();
+    let mut pending = vec![name.to_string()];
+    let mut seen = BTreeSet::new();
+
+    while let Some(include) = pending.pop() {
+        if !seen.insert(include.clone()) {
+            continue;
+        }
+        let Some(file) = parsed.get(include.as_str()) else {
+            return Err(config::invalid(format!(
+                "generated `{}` includes `{}`, which is not generated",
+                name, include,
+            )));
+        };
+        let program = &file.program;
+        defined.extend(program.macros.keys().map(|name| ("macro", name.clone())));
+        defined.extend(
+            program
+                .constants
+                .keys()
+                .map(|name| ("constant", name.clone())),
+        );
+        defined.extend(program.errors.keys().map(|name| ("error", name.clone())));
+        defined.extend(program.events.keys().map(|name| ("event", name.clone())));
+        pending.extend(file.includes.iter().cloned());
+    }
+
+    Ok(defined)
+}
+
+/// Collects the definitions `stmt` uses.
+fn stmt_uses(stmt: &Stmt, uses: &mut Vec<(&'static str, String)>) {
+    match stmt {
+        Stmt::Constant(name) => uses.push(("constant", name.clone())),
+        Stmt::Call(name, args) => {
+            uses.push(("macro", name.clone()));
+            args.iter().for_each(|arg| arg_uses(arg, uses));
+        }
+        Stmt::Builtin(name, args) => builtin_uses(name, args, uses),
+        _ => {}
+    }
+}
+
+fn arg_uses(arg: &Arg, uses: &mut Vec<(&'static str, String)>) {
+    match arg {
+        Arg::Constant(name) => uses.push(("constant", name.clone())),
+        Arg::Call(name, args) => {
+            uses.push(("macro", name.clone()));
+            args.iter().for_each(|arg| arg_uses(arg, uses));
+        }
+        Arg::Builtin(name, args) => builtin_uses(name, args, uses),
+        _ => {}
+    }
+}
+
+/// Collects the errors and events named by the `name` builtin.
+fn builtin_uses(name: &str, args: &[Arg], uses: &mut Vec<(&'static str, String)>) {
+    let kind = match name {
+        "__ERROR" => "error",
+        "__EVENT_HASH" => "event",
+        _ => return,
+    };
+    if let [Arg::Ident(definition)] = args {
+        uses.push((kind, definition.clone()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn files(files: &[(&str, &str)]) -> BTreeMap<String, String> {
+        files
+            .iter()
+            .map(|(name, contents)| (name.to_string(), contents.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn diagnostics() {
+        let a = "#define macro A() = takes (0) returns (1) { 0x01 }\n";
+        let b = "#include \"liba.huff\"\n#define macro B() = takes (0) returns (1) { A() }\n";
+        let valid = files(&[("liba.huff", a), ("libb.huff", b)]);
+        assert!(validate(&valid, &BTreeSet::new()).is_ok());
+
+        // a file including one that was not generated fails, rather than going unchecked
+        let c = "#include \"libd.huff\"\n#define macro C() = takes (0) returns (1) { D() }\n";
+        let unresolved = files(&[("liba.huff", a), ("libc.huff", c)]);
+        assert_eq!(
+            validate(&unresolved, &BTreeSet::new())
+                .unwrap_err()
+                .to_string(),
+            "generated `libc.huff` includes `libd.huff`, which is not generated",
+        );
+
+        let invalid = files(&[(
+            "liba.huff",
+            "// comment\n#define macro A() = takes (0) returns (1) {\n    0x01\n    ]\n}\n",
+        )]);
+        assert_eq!(
+            validate(&invalid, &BTreeSet::new())
+                .unwrap_err()
+                .to_string(),
+            "generated `liba.huff` does not parse: liba.huff:4: unexpected token in macro body: \
+             Punct(']')\n    4 |     ]",
+        );
+
+        let undefined = files(&[(
+            "liba.huff",
+            "#define macro A() = takes (0) returns (0) { __ERROR(Missing) 0x00 mstore }\n",
+        )]);
+        assert_eq!(
+            validate(&undefined, &BTreeSet::new())
+                .unwrap_err()
+                .to_string(),
+            "generated `liba.huff` uses the undefined error `Missing` in `A`",
+        );
+
+        // macros delegated to, such as the functions of a dispatcher, are defined by the contract
+        let a = "#define macro A() = takes (0) returns (0) { F() }\n";
+        let delegating = files(&[("liba.huff", a)]);
+        assert!(validate(&delegating, &BTreeSet::new()).is_err());
+        assert!(validate(&delegating, &BTreeSet::from(["F".to_string()])).is_ok());
+    }
+}
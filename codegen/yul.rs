@@ -0,0 +1,195 @@
+//! Yul functions rendered from the definitions of the generated libraries, for contracts mixing
+//! Huff with Solidity inline assembly.
+
+use crate::huff;
+use crate::ir::{Definition, Expr};
+use crate::libcast::OverflowRevert;
+
+/// The language the libraries are generated in.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Target {
+    /// Huff macros, `libcast.huff`.
+    #[default]
+    Huff,
+    /// Yul functions, `libcast.yul`, of the libraries with definitions independent of Huff.
+    Yul,
+}
+
+impl Target {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "huff" => Some(Target::Huff),
+            "yul" => Some(Target::Yul),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Target::Huff => "huff",
+            Target::Yul => "yul",
+        }
+    }
+}
+
+/// Renders the Yul functions of `definitions` below the `doc` comment of the library, reverting
+/// as `revert` when a check does not hold.
+pub fn render(doc: &str, definitions: &[Definition], revert: OverflowRevert) -> String {
+    let mut yul = format!("\n{}\n{}", doc, revert_function(revert));
+    for definition in definitions {
+        yul.push('\n');
+        yul.push_str(&function(definition));
+    }
+
+    yul
+}
+
+/// The function reverting a failed check, `revertOverflow()`.
+fn revert_function(revert: OverflowRevert) -> String {
+    let body = match revert {
+        OverflowRevert::Error => {
+            let selector = huff::selector("Overflow()");
+            let selector = selector
+                .iter()
+                .map(|byte| format!("{:02x}", byte))
+                .collect::<String>();
+            format!(
+                "    mstore(0x00, shl(0xe0, 0x{}))\n    revert(0x00, 0x04)\n",
+                selector
+            )
+        }
+        OverflowRevert::Panic => PANIC_REVERT.to_string(),
+    };
+
+    format!(
+        "/// Reverts as a cast that overflows.\nfunction revertOverflow() {{\n{}}}\n",
+        body
+    )
+}
+
+const PANIC_REVERT: &str = "    mstore(0x00, shl(0xe0, 0x4e487b71))
+    mstore(0x04, 0x11)
+    revert(0x00, 0x24)
+";
+
+fn function(definition: &Definition) -> String {
+    // returned values may not share a name with the arguments
+    let results = definition
+        .results
+        .iter()
+        .map(|(name, _)| match definition.args.contains(name) {
+            true => format!("{}Result", camel(name)),
+            false => camel(name),
+        })
+        .collect::<Vec<String>>();
+    let args = definition
+        .args
+        .iter()
+        .map(|arg| camel(arg))
+        .collect::<Vec<String>>();
+
+    let mut yul = definition.conditions_named(&camel);
+    yul.push_str(&format!(
+        "function {}({}) -> {} {{\n",
+        function_name(&definition.name),
+        args.join(", "),
+        results.join(", "),
+    ));
+    for check in &definition.checks {
+        let failed = match check {
+            Expr::Le(a, b) => format!("gt({}, {})", expr(a), expr(b)),
+            check => format!("iszero({})", expr(check)),
+        };
+        yul.push_str(&format!("    if {} {{ revertOverflow() }}\n", failed));
+    }
+    for (name, (_, result)) in results.iter().zip(&definition.results) {
+        yul.push_str(&format!("    {} := {}\n", name, expr(result)));
+    }
+    yul.push_str("}\n");
+
+    yul
+}
+
+fn expr(expr: &Expr) -> String {
+    match expr {
+        Expr::Arg(name) => camel(name),
+        Expr::Lit(lit) => lit.clone(),
+        Expr::And(a, b) => format!("and({}, {})", self::expr(a), self::expr(b)),
+        Expr::Le(a, b) => format!("iszero(gt({}, {}))", self::expr(a), self::expr(b)),
+    }
+}
+
+/// The name of the function of the macro `name`: `UNSAFE_TO_U8` is `unsafeToU8`, and the count
+/// of values a macro of several takes follows an `x`, `TO_U8_2` is `toU8x2`.
+fn function_name(name: &str) -> String {
+    let mut words = Vec::<String>::new();
+    for word in name.split('_') {
+        match (word.chars().all(|c| c.is_ascii_digit()), words.last_mut()) {
+            (true, Some(last)) => last.push_str(&format!("x{}", word)),
+            _ => words.push(word.to_string()),
+        }
+    }
+
+    camel(&words.join("_"))
+}
+
+/// The camel case of a snake case `name`, `masked_value` is `maskedValue`.
+fn camel(name: &str) -> String {
+    let mut words = name.split('_');
+    let mut camel = words.next().unwrap_or_default().to_lowercase();
+    for word in words {
+        let mut chars = word.chars();
+        camel.extend(chars.next().map(|c| c.to_ascii_uppercase()));
+        camel.push_str(&chars.as_str().to_lowercase());
+    }
+
+    camel
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn functions() {
+        assert_eq!(function_name("TO_U32"), "toU32");
+        assert_eq!(function_name("UNSAFE_TO_ADDRESS"), "unsafeToAddress");
+        assert_eq!(function_name("TO_U8_2"), "toU8x2");
+
+        let mask = || Expr::lit("0xff");
+        let pair = Definition {
+            name: "TO_U8_2".to_string(),
+            args: vec!["a", "b"],
+            checks: vec![Expr::Arg("a").le(mask()), Expr::Arg("b").le(mask())],
+            results: vec![("result", Expr::Arg("a")), ("b", Expr::Arg("b"))],
+        };
+        assert_eq!(
+            function(&pair),
+            "/// @pre a <= 0xff\n\
+             /// @pre b <= 0xff\n\
+             /// @post result == a\n\
+             function toU8x2(a, b) -> result, bResult {\n    \
+                 if gt(a, 0xff) { revertOverflow() }\n    \
+                 if gt(b, 0xff) { revertOverflow() }\n    \
+                 result := a\n    \
+                 bResult := b\n\
+             }\n",
+        );
+
+        let try_cast = Definition {
+            name: "TRY_TO_U8".to_string(),
+            args: vec!["value"],
+            checks: Vec::new(),
+            results: vec![
+                ("result", Expr::Arg("value").le(mask())),
+                ("masked_value", Expr::Arg("value").and(mask())),
+            ],
+        };
+        assert!(function(&try_cast).contains(
+            "/// @post maskedValue == value & 0xff\n\
+             function tryToU8(value) -> result, maskedValue {\n    \
+                 result := iszero(gt(value, 0xff))\n    \
+                 maskedValue := and(value, 0xff)\n",
+        ));
+    }
+}
@@ -0,0 +1,644 @@
+// SPDX-License-Identifier: MIT
+// Generated by `huffgen`, fuzzes the casts of `libcast.huff` against their Solidity mirror.
+pragma solidity ^0.8.0;
+
+import {Test} from "forge-std/Test.sol";
+import {HuffDeployer} from "foundry-huff/HuffDeployer.sol";
+
+import {CastMirror} from "./CastMirror.sol";
+
+contract CastDifferentialTest is Test {
+    address internal huff;
+    CastMirror internal mirror;
+
+    function setUp() public {
+        huff = HuffDeployer.deploy("../differential/CastHarness");
+        mirror = new CastMirror();
+    }
+
+    /// Asserts the harness and the mirror both succeed with the same output or both revert with
+    /// the same data.
+    function assertSame(bytes memory data) internal {
+        (bool huffOk, bytes memory huffOut) = huff.call(data);
+        (bool mirrorOk, bytes memory mirrorOut) = address(mirror).call(data);
+
+        assertEq(huffOk, mirrorOk);
+        assertEq(huffOut, mirrorOut);
+    }
+
+    function testToU8(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.toU8, (value >> shift)));
+    }
+
+    function testToU16(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.toU16, (value >> shift)));
+    }
+
+    function testToU24(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.toU24, (value >> shift)));
+    }
+
+    function testToU32(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.toU32, (value >> shift)));
+    }
+
+    function testToU40(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.toU40, (value >> shift)));
+    }
+
+    function testToU48(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.toU48, (value >> shift)));
+    }
+
+    function testToU56(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.toU56, (value >> shift)));
+    }
+
+    function testToU64(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.toU64, (value >> shift)));
+    }
+
+    function testToU72(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.toU72, (value >> shift)));
+    }
+
+    function testToU80(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.toU80, (value >> shift)));
+    }
+
+    function testToU88(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.toU88, (value >> shift)));
+    }
+
+    function testToU96(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.toU96, (value >> shift)));
+    }
+
+    function testToU104(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.toU104, (value >> shift)));
+    }
+
+    function testToU112(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.toU112, (value >> shift)));
+    }
+
+    function testToU120(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.toU120, (value >> shift)));
+    }
+
+    function testToU128(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.toU128, (value >> shift)));
+    }
+
+    function testToU136(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.toU136, (value >> shift)));
+    }
+
+    function testToU144(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.toU144, (value >> shift)));
+    }
+
+    function testToU152(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.toU152, (value >> shift)));
+    }
+
+    function testToU160(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.toU160, (value >> shift)));
+    }
+
+    function testToU168(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.toU168, (value >> shift)));
+    }
+
+    function testToU176(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.toU176, (value >> shift)));
+    }
+
+    function testToU184(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.toU184, (value >> shift)));
+    }
+
+    function testToU192(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.toU192, (value >> shift)));
+    }
+
+    function testToU200(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.toU200, (value >> shift)));
+    }
+
+    function testToU208(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.toU208, (value >> shift)));
+    }
+
+    function testToU216(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.toU216, (value >> shift)));
+    }
+
+    function testToU224(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.toU224, (value >> shift)));
+    }
+
+    function testToU232(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.toU232, (value >> shift)));
+    }
+
+    function testToU240(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.toU240, (value >> shift)));
+    }
+
+    function testToU248(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.toU248, (value >> shift)));
+    }
+
+    function testToU256(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.toU256, (value >> shift)));
+    }
+
+    function testUnsafeToU8(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.unsafeToU8, (value >> shift)));
+    }
+
+    function testUnsafeToU16(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.unsafeToU16, (value >> shift)));
+    }
+
+    function testUnsafeToU24(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.unsafeToU24, (value >> shift)));
+    }
+
+    function testUnsafeToU32(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.unsafeToU32, (value >> shift)));
+    }
+
+    function testUnsafeToU40(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.unsafeToU40, (value >> shift)));
+    }
+
+    function testUnsafeToU48(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.unsafeToU48, (value >> shift)));
+    }
+
+    function testUnsafeToU56(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.unsafeToU56, (value >> shift)));
+    }
+
+    function testUnsafeToU64(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.unsafeToU64, (value >> shift)));
+    }
+
+    function testUnsafeToU72(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.unsafeToU72, (value >> shift)));
+    }
+
+    function testUnsafeToU80(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.unsafeToU80, (value >> shift)));
+    }
+
+    function testUnsafeToU88(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.unsafeToU88, (value >> shift)));
+    }
+
+    function testUnsafeToU96(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.unsafeToU96, (value >> shift)));
+    }
+
+    function testUnsafeToU104(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.unsafeToU104, (value >> shift)));
+    }
+
+    function testUnsafeToU112(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.unsafeToU112, (value >> shift)));
+    }
+
+    function testUnsafeToU120(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.unsafeToU120, (value >> shift)));
+    }
+
+    function testUnsafeToU128(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.unsafeToU128, (value >> shift)));
+    }
+
+    function testUnsafeToU136(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.unsafeToU136, (value >> shift)));
+    }
+
+    function testUnsafeToU144(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.unsafeToU144, (value >> shift)));
+    }
+
+    function testUnsafeToU152(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.unsafeToU152, (value >> shift)));
+    }
+
+    function testUnsafeToU160(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.unsafeToU160, (value >> shift)));
+    }
+
+    function testUnsafeToU168(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.unsafeToU168, (value >> shift)));
+    }
+
+    function testUnsafeToU176(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.unsafeToU176, (value >> shift)));
+    }
+
+    function testUnsafeToU184(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.unsafeToU184, (value >> shift)));
+    }
+
+    function testUnsafeToU192(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.unsafeToU192, (value >> shift)));
+    }
+
+    function testUnsafeToU200(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.unsafeToU200, (value >> shift)));
+    }
+
+    function testUnsafeToU208(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.unsafeToU208, (value >> shift)));
+    }
+
+    function testUnsafeToU216(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.unsafeToU216, (value >> shift)));
+    }
+
+    function testUnsafeToU224(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.unsafeToU224, (value >> shift)));
+    }
+
+    function testUnsafeToU232(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.unsafeToU232, (value >> shift)));
+    }
+
+    function testUnsafeToU240(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.unsafeToU240, (value >> shift)));
+    }
+
+    function testUnsafeToU248(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.unsafeToU248, (value >> shift)));
+    }
+
+    function testUnsafeToU256(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.unsafeToU256, (value >> shift)));
+    }
+
+    function testTryToU8(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.tryToU8, (value >> shift)));
+    }
+
+    function testTryToU16(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.tryToU16, (value >> shift)));
+    }
+
+    function testTryToU24(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.tryToU24, (value >> shift)));
+    }
+
+    function testTryToU32(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.tryToU32, (value >> shift)));
+    }
+
+    function testTryToU40(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.tryToU40, (value >> shift)));
+    }
+
+    function testTryToU48(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.tryToU48, (value >> shift)));
+    }
+
+    function testTryToU56(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.tryToU56, (value >> shift)));
+    }
+
+    function testTryToU64(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.tryToU64, (value >> shift)));
+    }
+
+    function testTryToU72(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.tryToU72, (value >> shift)));
+    }
+
+    function testTryToU80(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.tryToU80, (value >> shift)));
+    }
+
+    function testTryToU88(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.tryToU88, (value >> shift)));
+    }
+
+    function testTryToU96(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.tryToU96, (value >> shift)));
+    }
+
+    function testTryToU104(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.tryToU104, (value >> shift)));
+    }
+
+    function testTryToU112(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.tryToU112, (value >> shift)));
+    }
+
+    function testTryToU120(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.tryToU120, (value >> shift)));
+    }
+
+    function testTryToU128(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.tryToU128, (value >> shift)));
+    }
+
+    function testTryToU136(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.tryToU136, (value >> shift)));
+    }
+
+    function testTryToU144(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.tryToU144, (value >> shift)));
+    }
+
+    function testTryToU152(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.tryToU152, (value >> shift)));
+    }
+
+    function testTryToU160(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.tryToU160, (value >> shift)));
+    }
+
+    function testTryToU168(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.tryToU168, (value >> shift)));
+    }
+
+    function testTryToU176(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.tryToU176, (value >> shift)));
+    }
+
+    function testTryToU184(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.tryToU184, (value >> shift)));
+    }
+
+    function testTryToU192(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.tryToU192, (value >> shift)));
+    }
+
+    function testTryToU200(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.tryToU200, (value >> shift)));
+    }
+
+    function testTryToU208(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.tryToU208, (value >> shift)));
+    }
+
+    function testTryToU216(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.tryToU216, (value >> shift)));
+    }
+
+    function testTryToU224(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.tryToU224, (value >> shift)));
+    }
+
+    function testTryToU232(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.tryToU232, (value >> shift)));
+    }
+
+    function testTryToU240(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.tryToU240, (value >> shift)));
+    }
+
+    function testTryToU248(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.tryToU248, (value >> shift)));
+    }
+
+    function testTryToU256(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.tryToU256, (value >> shift)));
+    }
+
+    function testMiniToU32(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.miniToU32, (value >> shift)));
+    }
+
+    function testMiniToU40(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.miniToU40, (value >> shift)));
+    }
+
+    function testMiniToU48(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.miniToU48, (value >> shift)));
+    }
+
+    function testMiniToU56(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.miniToU56, (value >> shift)));
+    }
+
+    function testMiniToU64(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.miniToU64, (value >> shift)));
+    }
+
+    function testMiniToU72(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.miniToU72, (value >> shift)));
+    }
+
+    function testMiniToU80(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.miniToU80, (value >> shift)));
+    }
+
+    function testMiniToU88(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.miniToU88, (value >> shift)));
+    }
+
+    function testMiniToU96(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.miniToU96, (value >> shift)));
+    }
+
+    function testMiniToU104(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.miniToU104, (value >> shift)));
+    }
+
+    function testMiniToU112(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.miniToU112, (value >> shift)));
+    }
+
+    function testMiniToU120(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.miniToU120, (value >> shift)));
+    }
+
+    function testMiniToU128(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.miniToU128, (value >> shift)));
+    }
+
+    function testMiniToU136(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.miniToU136, (value >> shift)));
+    }
+
+    function testMiniToU144(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.miniToU144, (value >> shift)));
+    }
+
+    function testMiniToU152(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.miniToU152, (value >> shift)));
+    }
+
+    function testMiniToU160(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.miniToU160, (value >> shift)));
+    }
+
+    function testMiniToU168(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.miniToU168, (value >> shift)));
+    }
+
+    function testMiniToU176(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.miniToU176, (value >> shift)));
+    }
+
+    function testMiniToU184(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.miniToU184, (value >> shift)));
+    }
+
+    function testMiniToU192(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.miniToU192, (value >> shift)));
+    }
+
+    function testMiniToU200(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.miniToU200, (value >> shift)));
+    }
+
+    function testMiniToU208(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.miniToU208, (value >> shift)));
+    }
+
+    function testMiniToU216(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.miniToU216, (value >> shift)));
+    }
+
+    function testMiniToU224(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.miniToU224, (value >> shift)));
+    }
+
+    function testMiniToU232(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.miniToU232, (value >> shift)));
+    }
+
+    function testMiniToU240(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.miniToU240, (value >> shift)));
+    }
+
+    function testMiniToU248(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.miniToU248, (value >> shift)));
+    }
+
+    function testMiniToU256(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.miniToU256, (value >> shift)));
+    }
+
+    function testUnsafeMiniToU32(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.unsafeMiniToU32, (value >> shift)));
+    }
+
+    function testUnsafeMiniToU40(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.unsafeMiniToU40, (value >> shift)));
+    }
+
+    function testUnsafeMiniToU48(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.unsafeMiniToU48, (value >> shift)));
+    }
+
+    function testUnsafeMiniToU56(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.unsafeMiniToU56, (value >> shift)));
+    }
+
+    function testUnsafeMiniToU64(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.unsafeMiniToU64, (value >> shift)));
+    }
+
+    function testUnsafeMiniToU72(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.unsafeMiniToU72, (value >> shift)));
+    }
+
+    function testUnsafeMiniToU80(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.unsafeMiniToU80, (value >> shift)));
+    }
+
+    function testUnsafeMiniToU88(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.unsafeMiniToU88, (value >> shift)));
+    }
+
+    function testUnsafeMiniToU96(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.unsafeMiniToU96, (value >> shift)));
+    }
+
+    function testUnsafeMiniToU104(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.unsafeMiniToU104, (value >> shift)));
+    }
+
+    function testUnsafeMiniToU112(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.unsafeMiniToU112, (value >> shift)));
+    }
+
+    function testUnsafeMiniToU120(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.unsafeMiniToU120, (value >> shift)));
+    }
+
+    function testUnsafeMiniToU128(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.unsafeMiniToU128, (value >> shift)));
+    }
+
+    function testUnsafeMiniToU136(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.unsafeMiniToU136, (value >> shift)));
+    }
+
+    function testUnsafeMiniToU144(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.unsafeMiniToU144, (value >> shift)));
+    }
+
+    function testUnsafeMiniToU152(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.unsafeMiniToU152, (value >> shift)));
+    }
+
+    function testUnsafeMiniToU160(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.unsafeMiniToU160, (value >> shift)));
+    }
+
+    function testUnsafeMiniToU168(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.unsafeMiniToU168, (value >> shift)));
+    }
+
+    function testUnsafeMiniToU176(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.unsafeMiniToU176, (value >> shift)));
+    }
+
+    function testUnsafeMiniToU184(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.unsafeMiniToU184, (value >> shift)));
+    }
+
+    function testUnsafeMiniToU192(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.unsafeMiniToU192, (value >> shift)));
+    }
+
+    function testUnsafeMiniToU200(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.unsafeMiniToU200, (value >> shift)));
+    }
+
+    function testUnsafeMiniToU208(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.unsafeMiniToU208, (value >> shift)));
+    }
+
+    function testUnsafeMiniToU216(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.unsafeMiniToU216, (value >> shift)));
+    }
+
+    function testUnsafeMiniToU224(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.unsafeMiniToU224, (value >> shift)));
+    }
+
+    function testUnsafeMiniToU232(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.unsafeMiniToU232, (value >> shift)));
+    }
+
+    function testUnsafeMiniToU240(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.unsafeMiniToU240, (value >> shift)));
+    }
+
+    function testUnsafeMiniToU248(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.unsafeMiniToU248, (value >> shift)));
+    }
+
+    function testUnsafeMiniToU256(uint256 value, uint8 shift) public {
+        assertSame(abi.encodeCall(CastMirror.unsafeMiniToU256, (value >> shift)));
+    }
+}
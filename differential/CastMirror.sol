@@ -0,0 +1,684 @@
+// SPDX-License-Identifier: MIT
+// Generated by `huffgen`, mirrors the casts of `libcast.huff` for differential testing.
+pragma solidity ^0.8.0;
+
+contract CastMirror {
+    error Overflow();
+
+    function toU8(uint256 value) external pure returns (uint8) {
+        if (value > type(uint8).max) revert Overflow();
+        return uint8(value);
+    }
+
+    function toU16(uint256 value) external pure returns (uint16) {
+        if (value > type(uint16).max) revert Overflow();
+        return uint16(value);
+    }
+
+    function toU24(uint256 value) external pure returns (uint24) {
+        if (value > type(uint24).max) revert Overflow();
+        return uint24(value);
+    }
+
+    function toU32(uint256 value) external pure returns (uint32) {
+        if (value > type(uint32).max) revert Overflow();
+        return uint32(value);
+    }
+
+    function toU40(uint256 value) external pure returns (uint40) {
+        if (value > type(uint40).max) revert Overflow();
+        return uint40(value);
+    }
+
+    function toU48(uint256 value) external pure returns (uint48) {
+        if (value > type(uint48).max) revert Overflow();
+        return uint48(value);
+    }
+
+    function toU56(uint256 value) external pure returns (uint56) {
+        if (value > type(uint56).max) revert Overflow();
+        return uint56(value);
+    }
+
+    function toU64(uint256 value) external pure returns (uint64) {
+        if (value > type(uint64).max) revert Overflow();
+        return uint64(value);
+    }
+
+    function toU72(uint256 value) external pure returns (uint72) {
+        if (value > type(uint72).max) revert Overflow();
+        return uint72(value);
+    }
+
+    function toU80(uint256 value) external pure returns (uint80) {
+        if (value > type(uint80).max) revert Overflow();
+        return uint80(value);
+    }
+
+    function toU88(uint256 value) external pure returns (uint88) {
+        if (value > type(uint88).max) revert Overflow();
+        return uint88(value);
+    }
+
+    function toU96(uint256 value) external pure returns (uint96) {
+        if (value > type(uint96).max) revert Overflow();
+        return uint96(value);
+    }
+
+    function toU104(uint256 value) external pure returns (uint104) {
+        if (value > type(uint104).max) revert Overflow();
+        return uint104(value);
+    }
+
+    function toU112(uint256 value) external pure returns (uint112) {
+        if (value > type(uint112).max) revert Overflow();
+        return uint112(value);
+    }
+
+    function toU120(uint256 value) external pure returns (uint120) {
+        if (value > type(uint120).max) revert Overflow();
+        return uint120(value);
+    }
+
+    function toU128(uint256 value) external pure returns (uint128) {
+        if (value > type(uint128).max) revert Overflow();
+        return uint128(value);
+    }
+
+    function toU136(uint256 value) external pure returns (uint136) {
+        if (value > type(uint136).max) revert Overflow();
+        return uint136(value);
+    }
+
+    function toU144(uint256 value) external pure returns (uint144) {
+        if (value > type(uint144).max) revert Overflow();
+        return uint144(value);
+    }
+
+    function toU152(uint256 value) external pure returns (uint152) {
+        if (value > type(uint152).max) revert Overflow();
+        return uint152(value);
+    }
+
+    function toU160(uint256 value) external pure returns (uint160) {
+        if (value > type(uint160).max) revert Overflow();
+        return uint160(value);
+    }
+
+    function toU168(uint256 value) external pure returns (uint168) {
+        if (value > type(uint168).max) revert Overflow();
+        return uint168(value);
+    }
+
+    function toU176(uint256 value) external pure returns (uint176) {
+        if (value > type(uint176).max) revert Overflow();
+        return uint176(value);
+    }
+
+    function toU184(uint256 value) external pure returns (uint184) {
+        if (value > type(uint184).max) revert Overflow();
+        return uint184(value);
+    }
+
+    function toU192(uint256 value) external pure returns (uint192) {
+        if (value > type(uint192).max) revert Overflow();
+        return uint192(value);
+    }
+
+    function toU200(uint256 value) external pure returns (uint200) {
+        if (value > type(uint200).max) revert Overflow();
+        return uint200(value);
+    }
+
+    function toU208(uint256 value) external pure returns (uint208) {
+        if (value > type(uint208).max) revert Overflow();
+        return uint208(value);
+    }
+
+    function toU216(uint256 value) external pure returns (uint216) {
+        if (value > type(uint216).max) revert Overflow();
+        return uint216(value);
+    }
+
+    function toU224(uint256 value) external pure returns (uint224) {
+        if (value > type(uint224).max) revert Overflow();
+        return uint224(value);
+    }
+
+    function toU232(uint256 value) external pure returns (uint232) {
+        if (value > type(uint232).max) revert Overflow();
+        return uint232(value);
+    }
+
+    function toU240(uint256 value) external pure returns (uint240) {
+        if (value > type(uint240).max) revert Overflow();
+        return uint240(value);
+    }
+
+    function toU248(uint256 value) external pure returns (uint248) {
+        if (value > type(uint248).max) revert Overflow();
+        return uint248(value);
+    }
+
+    function toU256(uint256 value) external pure returns (uint256) {
+        if (value > type(uint256).max) revert Overflow();
+        return uint256(value);
+    }
+
+    function unsafeToU8(uint256 value) external pure returns (uint8) {
+        return uint8(value);
+    }
+
+    function unsafeToU16(uint256 value) external pure returns (uint16) {
+        return uint16(value);
+    }
+
+    function unsafeToU24(uint256 value) external pure returns (uint24) {
+        return uint24(value);
+    }
+
+    function unsafeToU32(uint256 value) external pure returns (uint32) {
+        return uint32(value);
+    }
+
+    function unsafeToU40(uint256 value) external pure returns (uint40) {
+        return uint40(value);
+    }
+
+    function unsafeToU48(uint256 value) external pure returns (uint48) {
+        return uint48(value);
+    }
+
+    function unsafeToU56(uint256 value) external pure returns (uint56) {
+        return uint56(value);
+    }
+
+    function unsafeToU64(uint256 value) external pure returns (uint64) {
+        return uint64(value);
+    }
+
+    function unsafeToU72(uint256 value) external pure returns (uint72) {
+        return uint72(value);
+    }
+
+    function unsafeToU80(uint256 value) external pure returns (uint80) {
+        return uint80(value);
+    }
+
+    function unsafeToU88(uint256 value) external pure returns (uint88) {
+        return uint88(value);
+    }
+
+    function unsafeToU96(uint256 value) external pure returns (uint96) {
+        return uint96(value);
+    }
+
+    function unsafeToU104(uint256 value) external pure returns (uint104) {
+        return uint104(value);
+    }
+
+    function unsafeToU112(uint256 value) external pure returns (uint112) {
+        return uint112(value);
+    }
+
+    function unsafeToU120(uint256 value) external pure returns (uint120) {
+        return uint120(value);
+    }
+
+    function unsafeToU128(uint256 value) external pure returns (uint128) {
+        return uint128(value);
+    }
+
+    function unsafeToU136(uint256 value) external pure returns (uint136) {
+        return uint136(value);
+    }
+
+    function unsafeToU144(uint256 value) external pure returns (uint144) {
+        return uint144(value);
+    }
+
+    function unsafeToU152(uint256 value) external pure returns (uint152) {
+        return uint152(value);
+    }
+
+    function unsafeToU160(uint256 value) external pure returns (uint160) {
+        return uint160(value);
+    }
+
+    function unsafeToU168(uint256 value) external pure returns (uint168) {
+        return uint168(value);
+    }
+
+    function unsafeToU176(uint256 value) external pure returns (uint176) {
+        return uint176(value);
+    }
+
+    function unsafeToU184(uint256 value) external pure returns (uint184) {
+        return uint184(value);
+    }
+
+    function unsafeToU192(uint256 value) external pure returns (uint192) {
+        return uint192(value);
+    }
+
+    function unsafeToU200(uint256 value) external pure returns (uint200) {
+        return uint200(value);
+    }
+
+    function unsafeToU208(uint256 value) external pure returns (uint208) {
+        return uint208(value);
+    }
+
+    function unsafeToU216(uint256 value) external pure returns (uint216) {
+        return uint216(value);
+    }
+
+    function unsafeToU224(uint256 value) external pure returns (uint224) {
+        return uint224(value);
+    }
+
+    function unsafeToU232(uint256 value) external pure returns (uint232) {
+        return uint232(value);
+    }
+
+    function unsafeToU240(uint256 value) external pure returns (uint240) {
+        return uint240(value);
+    }
+
+    function unsafeToU248(uint256 value) external pure returns (uint248) {
+        return uint248(value);
+    }
+
+    function unsafeToU256(uint256 value) external pure returns (uint256) {
+        return uint256(value);
+    }
+
+    function tryToU8(uint256 value) external pure returns (bool, uint8) {
+        return (value <= type(uint8).max, uint8(value));
+    }
+
+    function tryToU16(uint256 value) external pure returns (bool, uint16) {
+        return (value <= type(uint16).max, uint16(value));
+    }
+
+    function tryToU24(uint256 value) external pure returns (bool, uint24) {
+        return (value <= type(uint24).max, uint24(value));
+    }
+
+    function tryToU32(uint256 value) external pure returns (bool, uint32) {
+        return (value <= type(uint32).max, uint32(value));
+    }
+
+    function tryToU40(uint256 value) external pure returns (bool, uint40) {
+        return (value <= type(uint40).max, uint40(value));
+    }
+
+    function tryToU48(uint256 value) external pure returns (bool, uint48) {
+        return (value <= type(uint48).max, uint48(value));
+    }
+
+    function tryToU56(uint256 value) external pure returns (bool, uint56) {
+        return (value <= type(uint56).max, uint56(value));
+    }
+
+    function tryToU64(uint256 value) external pure returns (bool, uint64) {
+        return (value <= type(uint64).max, uint64(value));
+    }
+
+    function tryToU72(uint256 value) external pure returns (bool, uint72) {
+        return (value <= type(uint72).max, uint72(value));
+    }
+
+    function tryToU80(uint256 value) external pure returns (bool, uint80) {
+        return (value <= type(uint80).max, uint80(value));
+    }
+
+    function tryToU88(uint256 value) external pure returns (bool, uint88) {
+        return (value <= type(uint88).max, uint88(value));
+    }
+
+    function tryToU96(uint256 value) external pure returns (bool, uint96) {
+        return (value <= type(uint96).max, uint96(value));
+    }
+
+    function tryToU104(uint256 value) external pure returns (bool, uint104) {
+        return (value <= type(uint104).max, uint104(value));
+    }
+
+    function tryToU112(uint256 value) external pure returns (bool, uint112) {
+        return (value <= type(uint112).max, uint112(value));
+    }
+
+    function tryToU120(uint256 value) external pure returns (bool, uint120) {
+        return (value <= type(uint120).max, uint120(value));
+    }
+
+    function tryToU128(uint256 value) external pure returns (bool, uint128) {
+        return (value <= type(uint128).max, uint128(value));
+    }
+
+    function tryToU136(uint256 value) external pure returns (bool, uint136) {
+        return (value <= type(uint136).max, uint136(value));
+    }
+
+    function tryToU144(uint256 value) external pure returns (bool, uint144) {
+        return (value <= type(uint144).max, uint144(value));
+    }
+
+    function tryToU152(uint256 value) external pure returns (bool, uint152) {
+        return (value <= type(uint152).max, uint152(value));
+    }
+
+    function tryToU160(uint256 value) external pure returns (bool, uint160) {
+        return (value <= type(uint160).max, uint160(value));
+    }
+
+    function tryToU168(uint256 value) external pure returns (bool, uint168) {
+        return (value <= type(uint168).max, uint168(value));
+    }
+
+    function tryToU176(uint256 value) external pure returns (bool, uint176) {
+        return (value <= type(uint176).max, uint176(value));
+    }
+
+    function tryToU184(uint256 value) external pure returns (bool, uint184) {
+        return (value <= type(uint184).max, uint184(value));
+    }
+
+    function tryToU192(uint256 value) external pure returns (bool, uint192) {
+        return (value <= type(uint192).max, uint192(value));
+    }
+
+    function tryToU200(uint256 value) external pure returns (bool, uint200) {
+        return (value <= type(uint200).max, uint200(value));
+    }
+
+    function tryToU208(uint256 value) external pure returns (bool, uint208) {
+        return (value <= type(uint208).max, uint208(value));
+    }
+
+    function tryToU216(uint256 value) external pure returns (bool, uint216) {
+        return (value <= type(uint216).max, uint216(value));
+    }
+
+    function tryToU224(uint256 value) external pure returns (bool, uint224) {
+        return (value <= type(uint224).max, uint224(value));
+    }
+
+    function tryToU232(uint256 value) external pure returns (bool, uint232) {
+        return (value <= type(uint232).max, uint232(value));
+    }
+
+    function tryToU240(uint256 value) external pure returns (bool, uint240) {
+        return (value <= type(uint240).max, uint240(value));
+    }
+
+    function tryToU248(uint256 value) external pure returns (bool, uint248) {
+        return (value <= type(uint248).max, uint248(value));
+    }
+
+    function tryToU256(uint256 value) external pure returns (bool, uint256) {
+        return (value <= type(uint256).max, uint256(value));
+    }
+
+    function miniToU32(uint256 value) external pure returns (uint32) {
+        if (value > type(uint32).max) revert Overflow();
+        return uint32(value);
+    }
+
+    function miniToU40(uint256 value) external pure returns (uint40) {
+        if (value > type(uint40).max) revert Overflow();
+        return uint40(value);
+    }
+
+    function miniToU48(uint256 value) external pure returns (uint48) {
+        if (value > type(uint48).max) revert Overflow();
+        return uint48(value);
+    }
+
+    function miniToU56(uint256 value) external pure returns (uint56) {
+        if (value > type(uint56).max) revert Overflow();
+        return uint56(value);
+    }
+
+    function miniToU64(uint256 value) external pure returns (uint64) {
+        if (value > type(uint64).max) revert Overflow();
+        return uint64(value);
+    }
+
+    function miniToU72(uint256 value) external pure returns (uint72) {
+        if (value > type(uint72).max) revert Overflow();
+        return uint72(value);
+    }
+
+    function miniToU80(uint256 value) external pure returns (uint80) {
+        if (value > type(uint80).max) revert Overflow();
+        return uint80(value);
+    }
+
+    function miniToU88(uint256 value) external pure returns (uint88) {
+        if (value > type(uint88).max) revert Overflow();
+        return uint88(value);
+    }
+
+    function miniToU96(uint256 value) external pure returns (uint96) {
+        if (value > type(uint96).max) revert Overflow();
+        return uint96(value);
+    }
+
+    function miniToU104(uint256 value) external pure returns (uint104) {
+        if (value > type(uint104).max) revert Overflow();
+        return uint104(value);
+    }
+
+    function miniToU112(uint256 value) external pure returns (uint112) {
+        if (value > type(uint112).max) revert Overflow();
+        return uint112(value);
+    }
+
+    function miniToU120(uint256 value) external pure returns (uint120) {
+        if (value > type(uint120).max) revert Overflow();
+        return uint120(value);
+    }
+
+    function miniToU128(uint256 value) external pure returns (uint128) {
+        if (value > type(uint128).max) revert Overflow();
+        return uint128(value);
+    }
+
+    function miniToU136(uint256 value) external pure returns (uint136) {
+        if (value > type(uint136).max) revert Overflow();
+        return uint136(value);
+    }
+
+    function miniToU144(uint256 value) external pure returns (uint144) {
+        if (value > type(uint144).max) revert Overflow();
+        return uint144(value);
+    }
+
+    function miniToU152(uint256 value) external pure returns (uint152) {
+        if (value > type(uint152).max) revert Overflow();
+        return uint152(value);
+    }
+
+    function miniToU160(uint256 value) external pure returns (uint160) {
+        if (value > type(uint160).max) revert Overflow();
+        return uint160(value);
+    }
+
+    function miniToU168(uint256 value) external pure returns (uint168) {
+        if (value > type(uint168).max) revert Overflow();
+        return uint168(value);
+    }
+
+    function miniToU176(uint256 value) external pure returns (uint176) {
+        if (value > type(uint176).max) revert Overflow();
+        return uint176(value);
+    }
+
+    function miniToU184(uint256 value) external pure returns (uint184) {
+        if (value > type(uint184).max) revert Overflow();
+        return uint184(value);
+    }
+
+    function miniToU192(uint256 value) external pure returns (uint192) {
+        if (value > type(uint192).max) revert Overflow();
+        return uint192(value);
+    }
+
+    function miniToU200(uint256 value) external pure returns (uint200) {
+        if (value > type(uint200).max) revert Overflow();
+        return uint200(value);
+    }
+
+    function miniToU208(uint256 value) external pure returns (uint208) {
+        if (value > type(uint208).max) revert Overflow();
+        return uint208(value);
+    }
+
+    function miniToU216(uint256 value) external pure returns (uint216) {
+        if (value > type(uint216).max) revert Overflow();
+        return uint216(value);
+    }
+
+    function miniToU224(uint256 value) external pure returns (uint224) {
+        if (value > type(uint224).max) revert Overflow();
+        return uint224(value);
+    }
+
+    function miniToU232(uint256 value) external pure returns (uint232) {
+        if (value > type(uint232).max) revert Overflow();
+        return uint232(value);
+    }
+
+    function miniToU240(uint256 value) external pure returns (uint240) {
+        if (value > type(uint240).max) revert Overflow();
+        return uint240(value);
+    }
+
+    function miniToU248(uint256 value) external pure returns (uint248) {
+        if (value > type(uint248).max) revert Overflow();
+        return uint248(value);
+    }
+
+    function miniToU256(uint256 value) external pure returns (uint256) {
+        if (value > type(uint256).max) revert Overflow();
+        return uint256(value);
+    }
+
+    function unsafeMiniToU32(uint256 value) external pure returns (uint32) {
+        return uint32(value);
+    }
+
+    function unsafeMiniToU40(uint256 value) external pure returns (uint40) {
+        return uint40(value);
+    }
+
+    function unsafeMiniToU48(uint256 value) external pure returns (uint48) {
+        return uint48(value);
+    }
+
+    function unsafeMiniToU56(uint256 value) external pure returns (uint56) {
+        return uint56(value);
+    }
+
+    function unsafeMiniToU64(uint256 value) external pure returns (uint64) {
+        return uint64(value);
+    }
+
+    function unsafeMiniToU72(uint256 value) external pure returns (uint72) {
+        return uint72(value);
+    }
+
+    function unsafeMiniToU80(uint256 value) external pure returns (uint80) {
+        return uint80(value);
+    }
+
+    function unsafeMiniToU88(uint256 value) external pure returns (uint88) {
+        return uint88(value);
+    }
+
+    function unsafeMiniToU96(uint256 value) external pure returns (uint96) {
+        return uint96(value);
+    }
+
+    function unsafeMiniToU104(uint256 value) external pure returns (uint104) {
+        return uint104(value);
+    }
+
+    function unsafeMiniToU112(uint256 value) external pure returns (uint112) {
+        return uint112(value);
+    }
+
+    function unsafeMiniToU120(uint256 value) external pure returns (uint120) {
+        return uint120(value);
+    }
+
+    function unsafeMiniToU128(uint256 value) external pure returns (uint128) {
+        return uint128(value);
+    }
+
+    function unsafeMiniToU136(uint256 value) external pure returns (uint136) {
+        return uint136(value);
+    }
+
+    function unsafeMiniToU144(uint256 value) external pure returns (uint144) {
+        return uint144(value);
+    }
+
+    function unsafeMiniToU152(uint256 value) external pure returns (uint152) {
+        return uint152(value);
+    }
+
+    function unsafeMiniToU160(uint256 value) external pure returns (uint160) {
+        return uint160(value);
+    }
+
+    function unsafeMiniToU168(uint256 value) external pure returns (uint168) {
+        return uint168(value);
+    }
+
+    function unsafeMiniToU176(uint256 value) external pure returns (uint176) {
+        return uint176(value);
+    }
+
+    function unsafeMiniToU184(uint256 value) external pure returns (uint184) {
+        return uint184(value);
+    }
+
+    function unsafeMiniToU192(uint256 value) external pure returns (uint192) {
+        return uint192(value);
+    }
+
+    function unsafeMiniToU200(uint256 value) external pure returns (uint200) {
+        return uint200(value);
+    }
+
+    function unsafeMiniToU208(uint256 value) external pure returns (uint208) {
+        return uint208(value);
+    }
+
+    function unsafeMiniToU216(uint256 value) external pure returns (uint216) {
+        return uint216(value);
+    }
+
+    function unsafeMiniToU224(uint256 value) external pure returns (uint224) {
+        return uint224(value);
+    }
+
+    function unsafeMiniToU232(uint256 value) external pure returns (uint232) {
+        return uint232(value);
+    }
+
+    function unsafeMiniToU240(uint256 value) external pure returns (uint240) {
+        return uint240(value);
+    }
+
+    function unsafeMiniToU248(uint256 value) external pure returns (uint248) {
+        return uint248(value);
+    }
+
+    function unsafeMiniToU256(uint256 value) external pure returns (uint256) {
+        return uint256(value);
+    }
+}
@@ -0,0 +1,171 @@
+//! A fuzzer of the compiled macros: seeded random words, the target a macro is compiled into to run
+//! on them, and the shrinking of the inputs a target diverges on from its reference.
+
+use huffgen::evm::{addr, Evm, Halt};
+use huffgen::huff::{self, Program};
+use huffgen::profile::{self, Profile};
+use huffgen::u256::U256;
+
+use super::{call, manifest};
+
+/// The outputs of a macro top first, or the name of the error it reverted with.
+pub type Outcome = Result<Vec<U256>, String>;
+
+/// An xorshift generator of the fuzzed values.
+pub struct Rng(pub u64);
+
+impl Rng {
+    /// A generator seeded from `$HUFFGEN_FUZZ_SEED`.
+    pub fn from_env() -> Rng {
+        Rng(env("HUFFGEN_FUZZ_SEED", 0x5eed).max(1))
+    }
+
+    pub fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    /// A random word shifted right by a random amount, covering every width as Foundry's fuzzer
+    /// does for the `value >> shift` of `Cast.t.sol`.
+    pub fn word(&mut self) -> U256 {
+        let word = U256([self.next(), self.next(), self.next(), self.next()]);
+        word.shr((self.next() % 256) as u32)
+    }
+}
+
+pub fn env(name: &str, default: u64) -> u64 {
+    std::env::var(name)
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(default)
+}
+
+/// The number of random inputs each target is fuzzed on, from `$HUFFGEN_FUZZ_RUNS`.
+pub fn runs() -> u64 {
+    env("HUFFGEN_FUZZ_RUNS", 64)
+}
+
+/// A macro of a library of `src`, compiled once into a `MAIN` that pushes the words of the
+/// calldata, the first on top, and invokes it.
+pub struct Target {
+    pub invocation: String,
+    program: Program,
+    evm: Evm,
+}
+
+impl Target {
+    pub fn new(file: &str, invocation: &str, takes: usize) -> Target {
+        let loads = (0..takes)
+            .rev()
+            .map(|i| format!("0x{:02x} calldataload", 4 + 32 * i))
+            .collect::<Vec<String>>();
+        let src = format!(
+            "#include \"{}\"\n\n#define macro MAIN() = takes (0) returns (0) {{\n    {} {}\n}}\n",
+            file,
+            loads.join(" "),
+            invocation,
+        );
+
+        let program = Program::parse_str(&src, &manifest("src")).unwrap();
+        let profile = Profile::load(&huffgen::config::parse("evm_version = \"cancun\"").unwrap());
+        let code = profile::compile(program.clone(), "MAIN", &profile.unwrap())
+            .unwrap_or_else(|e| panic!("{} does not compile: {}", invocation, e));
+        let mut evm = Evm::new();
+        evm.set_code(addr(0xc0de), code);
+
+        Target {
+            invocation: invocation.to_string(),
+            program,
+            evm,
+        }
+    }
+
+    /// Runs the macro on `inputs`, the first on top.
+    pub fn run(&mut self, inputs: &[U256]) -> Outcome {
+        let mut data = vec![0; 4];
+        for input in inputs {
+            data.extend(input.to_be_bytes());
+        }
+
+        let outcome = call(&mut self.evm, addr(0xc0de), &data);
+        match outcome.halt {
+            Halt::Stop => Ok(outcome.stack),
+            Halt::Revert => Err(self
+                .program
+                .errors
+                .values()
+                .find(|signature| outcome.output.starts_with(&huff::selector(signature)))
+                .map(|signature| signature.split('(').next().unwrap().to_string())
+                .unwrap_or_else(|| format!("{:?}", outcome.output))),
+            halt => panic!("{} halted with {:?}", self.invocation, halt),
+        }
+    }
+
+    /// Runs the macro on each of `edges` and `runs()` inputs drawn by `draw`, panicking with the
+    /// shrunk inputs of the first it diverges from `reference` on.
+    pub fn fuzz(
+        &mut self,
+        rng: &mut Rng,
+        edges: &[Vec<U256>],
+        draw: impl Fn(&mut Rng) -> Vec<U256>,
+        reference: impl Fn(&[U256]) -> Outcome,
+    ) {
+        let drawn = (0..runs()).map(|_| draw(rng)).collect::<Vec<_>>();
+        for inputs in edges.iter().chain(&drawn) {
+            if self.run(inputs) != reference(inputs) {
+                let shrunk = self.shrink(inputs.clone(), &reference);
+                let outcome = self.run(&shrunk);
+                panic!(
+                    "{} diverges on {} (shrunk from {}): {:?} but the reference gives {:?}",
+                    self.invocation,
+                    words(&shrunk),
+                    words(inputs),
+                    outcome,
+                    reference(&shrunk),
+                );
+            }
+        }
+    }
+
+    /// Greedily replaces each input with a smaller word the macro still diverges on, until none
+    /// does: zero, one, half of it, or it less a power of two, the largest first. The candidates
+    /// only decrease, so inputs masked into the range of a type stay in it.
+    fn shrink(
+        &mut self,
+        mut inputs: Vec<U256>,
+        reference: impl Fn(&[U256]) -> Outcome,
+    ) -> Vec<U256> {
+        let mut shrunk = true;
+        while shrunk {
+            shrunk = false;
+            for i in 0..inputs.len() {
+                let word = inputs[i];
+                let steps = (0..word.bits())
+                    .rev()
+                    .map(|k| word.wrapping_sub(U256::ONE.shl(k)));
+                let candidates = [U256::ZERO, U256::ONE, word.shr(1)]
+                    .into_iter()
+                    .chain(steps);
+                for candidate in candidates.filter(|candidate| *candidate < word) {
+                    inputs[i] = candidate;
+                    if self.run(&inputs) != reference(&inputs) {
+                        shrunk = true;
+                        break;
+                    }
+                    inputs[i] = word;
+                }
+            }
+        }
+        inputs
+    }
+}
+
+fn words(words: &[U256]) -> String {
+    let words = words
+        .iter()
+        .map(|word| word.to_hex())
+        .collect::<Vec<String>>();
+    format!("[{}]", words.join(", "))
+}
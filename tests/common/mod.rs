@@ -0,0 +1,43 @@
+//! Fixtures shared by the integration tests: their temporary directories and the call they make to
+//! the embedded EVM.
+
+#![allow(dead_code)]
+
+pub mod fuzz;
+pub mod solidity;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use huffgen::evm::{addr, Address, Call, Evm, Outcome};
+use huffgen::u256::U256;
+
+/// The account every test calls from.
+pub const CALLER: u64 = 0xca11;
+
+/// The path of `path` in the repository.
+pub fn manifest(path: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join(path)
+}
+
+/// A fresh, empty temporary directory for `name`, unique to this test run.
+pub fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("huffgen-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Calls the code at `address` in `evm` from `CALLER` with `data`, and the gas of a block.
+pub fn call(evm: &mut Evm, address: Address, data: &[u8]) -> Outcome {
+    evm.transact(Call {
+        caller: addr(CALLER),
+        address,
+        code_address: address,
+        value: U256::ZERO,
+        data: data.to_vec(),
+        gas: 30_000_000,
+        is_static: false,
+        transfer: false,
+    })
+}
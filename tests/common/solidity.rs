@@ -0,0 +1,664 @@
+//! A syntax check of the Solidity the generators write, parsing the subset of the language they
+//! use, so that the suites and mirrors are checked without `solc`.
+//!
+//! Besides the grammar, the checks `solc` makes of that subset are made: every function has one
+//! visibility and at most one mutability, reference types in parameters and variables have a data
+//! location, literals converted to `bytesN` have `N` bytes, and literals converted to `uintN` fit
+//! in `N` bits.
+
+/// A contract of a checked source, with the names of the functions it declares.
+#[derive(Debug, PartialEq)]
+pub struct Contract {
+    pub name: String,
+    pub bases: Vec<String>,
+    pub functions: Vec<String>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Ident(String),
+    Number(String),
+    Str,
+    Hex,
+    Punct(&'static str),
+    Eof,
+}
+
+const PUNCTUATION: &[&str] = &[
+    ":=", "==", "!=", "<=", ">=", "&&", "||", "<<", ">>", "+=", "-=", "*=", "/=", "++", "--", "(",
+    ")", "{", "}", "[", "]", ";", ",", ".", "=", "<", ">", "!", "+", "-", "*", "/", "%", "&", "|",
+    "^", "~", "?", ":",
+];
+
+const VISIBILITY: &[&str] = &["public", "external", "internal", "private"];
+const MUTABILITY: &[&str] = &["pure", "view", "payable"];
+const LOCATIONS: &[&str] = &["memory", "storage", "calldata"];
+
+const BINARY: &[&[&str]] = &[
+    &["||"],
+    &["&&"],
+    &["==", "!="],
+    &["<", ">", "<=", ">="],
+    &["|"],
+    &["^"],
+    &["&"],
+    &["<<", ">>"],
+    &["+", "-"],
+    &["*", "/", "%"],
+];
+
+/// Checks `src`, returning its contracts or the first error with its line.
+pub fn check(src: &str) -> Result<Vec<Contract>, String> {
+    let mut parser = Parser {
+        tokens: lex(src)?,
+        pos: 0,
+    };
+    let mut contracts = Vec::new();
+    while parser.peek() != &Token::Eof {
+        match parser.ident()?.as_str() {
+            "pragma" => {
+                parser.keyword("solidity")?;
+                while !parser.eat(";") {
+                    if parser.peek() == &Token::Eof {
+                        return Err(parser.error("`;`"));
+                    }
+                    parser.pos += 1;
+                }
+            }
+            "import" => parser.import()?,
+            "contract" => contracts.push(parser.contract()?),
+            other => return Err(format!("line {}: unexpected `{}`", parser.line(), other)),
+        }
+    }
+    Ok(contracts)
+}
+
+fn lex(src: &str) -> Result<Vec<(Token, usize)>, String> {
+    let bytes = src.as_bytes();
+    let (mut i, mut line) = (0, 1);
+    let mut tokens = Vec::new();
+    while i < bytes.len() {
+        let rest = &src[i..];
+        let c = bytes[i];
+        let start = line;
+        let token = if c == b'\n' {
+            line += 1;
+            i += 1;
+            continue;
+        } else if c.is_ascii_whitespace() {
+            i += 1;
+            continue;
+        } else if rest.starts_with("//") {
+            i += rest.find('\n').unwrap_or(rest.len());
+            continue;
+        } else if rest.starts_with("/*") {
+            let end = rest
+                .find("*/")
+                .ok_or(format!("line {}: unterminated comment", line))?;
+            line += rest[..end].matches('\n').count();
+            i += end + 2;
+            continue;
+        } else if let Some(literal) = rest.strip_prefix("hex\"") {
+            let end = literal
+                .find('"')
+                .ok_or(format!("line {}: unterminated hex", line))?;
+            let digits = &literal[..end];
+            if !digits.len().is_multiple_of(2) || !digits.bytes().all(|b| b.is_ascii_hexdigit()) {
+                return Err(format!("line {}: invalid hex literal `{}`", line, digits));
+            }
+            i += end + 5;
+            Token::Hex
+        } else if c == b'"' {
+            let end = rest[1..]
+                .find(['"', '\n'])
+                .filter(|&end| rest.as_bytes()[end + 1] == b'"');
+            i += end.ok_or(format!("line {}: unterminated string", line))? + 2;
+            Token::Str
+        } else if c.is_ascii_alphabetic() || c == b'_' || c == b'$' {
+            let len = rest
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '$'))
+                .unwrap_or(rest.len());
+            i += len;
+            Token::Ident(rest[..len].to_string())
+        } else if c.is_ascii_digit() {
+            let hex = rest.starts_with("0x");
+            let len = rest
+                .char_indices()
+                .skip(if hex { 2 } else { 0 })
+                .find(|&(_, c)| !(c.is_ascii_hexdigit() && hex || c.is_ascii_digit() || c == '.'))
+                .map_or(rest.len(), |(i, _)| i);
+            if rest[len..].starts_with(|c: char| c.is_ascii_alphanumeric() || c == '_') {
+                return Err(format!(
+                    "line {}: invalid number `{}`",
+                    line,
+                    &rest[..len + 1]
+                ));
+            }
+            i += len;
+            Token::Number(rest[..len].to_string())
+        } else {
+            let punct = PUNCTUATION
+                .iter()
+                .find(|p| rest.starts_with(**p))
+                .ok_or(format!(
+                    "line {}: unexpected character `{}`",
+                    line, c as char
+                ))?;
+            i += punct.len();
+            Token::Punct(punct)
+        };
+        tokens.push((token, start));
+    }
+    tokens.push((Token::Eof, line));
+    Ok(tokens)
+}
+
+/// Whether `name` is an elementary type.
+fn elementary(name: &str) -> bool {
+    let sized = |prefix: &str, valid: &dyn Fn(u32) -> bool| {
+        name.strip_prefix(prefix).is_some_and(|bits| {
+            bits.is_empty() && prefix != "bytes" || bits.parse().is_ok_and(valid) && bits != "0"
+        })
+    };
+    matches!(name, "bool" | "address" | "string" | "bytes")
+        || sized("uint", &|bits| bits % 8 == 0 && bits <= 256)
+        || sized("int", &|bits| bits % 8 == 0 && bits <= 256)
+        || sized("bytes", &|bytes| bytes <= 32)
+}
+
+/// The number of bits of the number literal `literal`.
+fn bit_length(literal: &str) -> Option<u32> {
+    let digits = match literal.strip_prefix("0x") {
+        Some(digits) => digits.trim_start_matches('0').to_string(),
+        None => format!("{:x}", literal.parse::<u128>().ok()?),
+    };
+    let first = u32::from_str_radix(digits.get(..1).unwrap_or("0"), 16).ok()?;
+    Some((digits.len().max(1) as u32 - 1) * 4 + (32 - first.leading_zeros()))
+}
+
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    pos: usize,
+}
+
+/// A parsed type: whether it needs a data location, and its name if it is elementary.
+struct Type {
+    reference: bool,
+    name: Option<String>,
+}
+
+impl Parser {
+    fn peek(&self) -> &Token {
+        &self.tokens[self.pos].0
+    }
+
+    fn peek_at(&self, offset: usize) -> &Token {
+        &self.tokens[(self.pos + offset).min(self.tokens.len() - 1)].0
+    }
+
+    fn line(&self) -> usize {
+        self.tokens[self.pos].1
+    }
+
+    fn error(&self, expected: &str) -> String {
+        format!(
+            "line {}: expected {}, found {:?}",
+            self.line(),
+            expected,
+            self.peek()
+        )
+    }
+
+    fn eat(&mut self, punct: &str) -> bool {
+        let found = matches!(self.peek(), Token::Punct(p) if *p == punct);
+        self.pos += found as usize;
+        found
+    }
+
+    fn expect(&mut self, punct: &str) -> Result<(), String> {
+        match self.eat(punct) {
+            true => Ok(()),
+            false => Err(self.error(&format!("`{}`", punct))),
+        }
+    }
+
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        let found = matches!(self.peek(), Token::Ident(name) if name == keyword);
+        self.pos += found as usize;
+        found
+    }
+
+    fn keyword(&mut self, keyword: &str) -> Result<(), String> {
+        match self.eat_keyword(keyword) {
+            true => Ok(()),
+            false => Err(self.error(&format!("`{}`", keyword))),
+        }
+    }
+
+    fn ident(&mut self) -> Result<String, String> {
+        match self.peek().clone() {
+            Token::Ident(name) => {
+                self.pos += 1;
+                Ok(name)
+            }
+            _ => Err(self.error("an identifier")),
+        }
+    }
+
+    /// Runs `parse`, rewinding if it fails.
+    fn attempt<T>(&mut self, parse: impl FnOnce(&mut Self) -> Result<T, String>) -> Option<T> {
+        let pos = self.pos;
+        let result = parse(self).ok();
+        if result.is_none() {
+            self.pos = pos;
+        }
+        result
+    }
+
+    fn import(&mut self) -> Result<(), String> {
+        if self.eat("{") {
+            loop {
+                self.ident()?;
+                if !self.eat(",") {
+                    break;
+                }
+            }
+            self.expect("}")?;
+            self.keyword("from")?;
+        }
+        match self.peek() {
+            Token::Str => self.pos += 1,
+            _ => return Err(self.error("a path")),
+        }
+        self.expect(";")
+    }
+
+    fn contract(&mut self) -> Result<Contract, String> {
+        let name = self.ident()?;
+        let mut bases = Vec::new();
+        if self.eat_keyword("is") {
+            loop {
+                bases.push(self.ident()?);
+                if !self.eat(",") {
+                    break;
+                }
+            }
+        }
+        self.expect("{")?;
+
+        let mut functions = Vec::new();
+        while !self.eat("}") {
+            if self.eat_keyword("error") {
+                self.ident()?;
+                self.parameters(false)?;
+                self.expect(";")?;
+            } else if self.eat_keyword("function") {
+                functions.push(self.function()?);
+            } else {
+                self.ty()?;
+                let attributes = VISIBILITY.iter().chain(&["constant", "immutable"]);
+                while attributes.clone().any(|k| self.eat_keyword(k)) {}
+                self.ident()?;
+                if self.eat("=") {
+                    self.expression()?;
+                }
+                self.expect(";")?;
+            }
+        }
+        Ok(Contract {
+            name,
+            bases,
+            functions,
+        })
+    }
+
+    fn function(&mut self) -> Result<String, String> {
+        let line = self.line();
+        let name = self.ident()?;
+        self.parameters(true)?;
+
+        let (mut visibility, mut mutability) = (0, 0);
+        loop {
+            if VISIBILITY.iter().any(|k| self.eat_keyword(k)) {
+                visibility += 1;
+            } else if MUTABILITY.iter().any(|k| self.eat_keyword(k)) {
+                mutability += 1;
+            } else if !self.eat_keyword("virtual") && !self.eat_keyword("override") {
+                break;
+            }
+        }
+        if visibility != 1 || mutability > 1 {
+            return Err(format!(
+                "line {}: `{}` needs one visibility and at most one mutability",
+                line, name
+            ));
+        }
+
+        if self.eat_keyword("returns") {
+            self.parameters(true)?;
+        }
+        if !self.eat(";") {
+            self.block()?;
+        }
+        Ok(name)
+    }
+
+    /// Parses a parenthesized parameter list, whose reference types need a location if `located`.
+    fn parameters(&mut self, located: bool) -> Result<(), String> {
+        self.expect("(")?;
+        if self.eat(")") {
+            return Ok(());
+        }
+        loop {
+            self.variable(located)?;
+            if matches!(self.peek(), Token::Ident(_)) {
+                self.pos += 1;
+            }
+            if !self.eat(",") {
+                return self.expect(")");
+            }
+        }
+    }
+
+    /// Parses a type and its location, which reference types need if `located`.
+    fn variable(&mut self, located: bool) -> Result<(), String> {
+        let line = self.line();
+        let ty = self.ty()?;
+        let location = LOCATIONS.iter().any(|k| self.eat_keyword(k));
+        match (ty.reference && located, location) {
+            (true, false) => Err(format!(
+                "line {}: reference type needs a data location",
+                line
+            )),
+            (false, true) if ty.name.is_some() && !ty.reference => Err(format!(
+                "line {}: value type cannot have a data location",
+                line
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    fn ty(&mut self) -> Result<Type, String> {
+        let name = self.ident()?;
+        if is_keyword(&name) {
+            return Err(format!(
+                "line {}: expected a type, found `{}`",
+                self.line(),
+                name
+            ));
+        }
+        let elementary = elementary(&name);
+        let sized = ["uint", "int", "bytes"].iter().any(|prefix| {
+            let bits = name.strip_prefix(prefix).unwrap_or_default();
+            !bits.is_empty() && bits.bytes().all(|b| b.is_ascii_digit())
+        });
+        if sized && !elementary {
+            return Err(format!("line {}: invalid type `{}`", self.line(), name));
+        }
+        let mut reference = matches!(name.as_str(), "bytes" | "string");
+        while self.eat("[") {
+            if let Token::Number(_) = self.peek() {
+                self.pos += 1;
+            }
+            self.expect("]")?;
+            reference = true;
+        }
+        Ok(Type {
+            reference,
+            name: elementary.then_some(name),
+        })
+    }
+
+    fn block(&mut self) -> Result<(), String> {
+        self.expect("{")?;
+        while !self.eat("}") {
+            self.statement()?;
+        }
+        Ok(())
+    }
+
+    fn statement(&mut self) -> Result<(), String> {
+        if let Token::Punct("{") = self.peek() {
+            return self.block();
+        }
+        if self.eat_keyword("if") {
+            self.expect("(")?;
+            self.expression()?;
+            self.expect(")")?;
+            self.statement()?;
+            if self.eat_keyword("else") {
+                self.statement()?;
+            }
+            return Ok(());
+        }
+        if self.eat_keyword("return") {
+            if !self.eat(";") {
+                self.expression()?;
+                self.expect(";")?;
+            }
+            return Ok(());
+        }
+        if self.eat_keyword("assembly") {
+            return self.yul_block();
+        }
+
+        let declaration = self.attempt(|parser| {
+            if parser.eat("(") {
+                loop {
+                    parser.variable(true)?;
+                    parser.ident()?;
+                    if !parser.eat(",") {
+                        break;
+                    }
+                }
+                parser.expect(")")?;
+                parser.expect("=")
+            } else {
+                parser.variable(true)?;
+                parser.ident()?;
+                match parser.eat("=") || matches!(parser.peek(), Token::Punct(";")) {
+                    true => Ok(()),
+                    false => Err(String::new()),
+                }
+            }
+        });
+        if declaration.is_some() {
+            if let Token::Punct("=") = self.tokens[self.pos - 1].0 {
+                self.expression()?;
+            }
+            return self.expect(";");
+        }
+        if let (Token::Ident(name), Token::Ident(_)) = (self.peek(), self.peek_at(1)) {
+            if name == "revert" {
+                self.pos += 1;
+            }
+        }
+        self.expression()?;
+        self.expect(";")
+    }
+
+    fn expression(&mut self) -> Result<(), String> {
+        self.binary(0)?;
+        if ["=", "+=", "-=", "*=", "/="].iter().any(|op| self.eat(op)) {
+            self.expression()?;
+        }
+        Ok(())
+    }
+
+    fn binary(&mut self, level: usize) -> Result<(), String> {
+        if level == BINARY.len() {
+            return self.unary();
+        }
+        self.binary(level + 1)?;
+        while BINARY[level].iter().any(|op| self.eat(op)) {
+            self.binary(level + 1)?;
+        }
+        Ok(())
+    }
+
+    fn unary(&mut self) -> Result<(), String> {
+        if ["!", "-", "~"].iter().any(|op| self.eat(op)) {
+            return self.unary();
+        }
+        let conversion = self.primary()?;
+        loop {
+            if self.eat("(") {
+                let arguments = self.arguments(")")?;
+                if let (Some(ty), [Token::Number(literal)]) = (&conversion, &arguments[..]) {
+                    self.conversion(ty, literal)?;
+                }
+            } else if self.eat("[") {
+                self.expression()?;
+                self.expect("]")?;
+            } else if self.eat(".") {
+                self.ident()?;
+            } else if !self.eat("++") && !self.eat("--") {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Parses a primary expression, returning the type it names if it is elementary.
+    fn primary(&mut self) -> Result<Option<String>, String> {
+        match self.peek().clone() {
+            Token::Number(_) | Token::Str | Token::Hex => self.pos += 1,
+            Token::Punct("(") => {
+                self.pos += 1;
+                self.arguments(")")?;
+            }
+            Token::Ident(name) if name == "type" => {
+                self.pos += 1;
+                self.expect("(")?;
+                self.ty()?;
+                self.expect(")")?;
+            }
+            Token::Ident(name) if name == "new" => {
+                self.pos += 1;
+                self.ty()?;
+            }
+            Token::Ident(name) if elementary(&name) => {
+                self.pos += 1;
+                return match self.peek() {
+                    Token::Punct("(") => Ok(Some(name)),
+                    _ => Err(self.error("a conversion")),
+                };
+            }
+            Token::Ident(name) if !is_keyword(&name) || name == "true" || name == "false" => {
+                self.pos += 1
+            }
+            _ => return Err(self.error("an expression")),
+        }
+        Ok(None)
+    }
+
+    /// Parses comma separated expressions up to `end`, returning the tokens of those that are a
+    /// lone token.
+    fn arguments(&mut self, end: &str) -> Result<Vec<Token>, String> {
+        let mut arguments = Vec::new();
+        if self.eat(end) {
+            return Ok(arguments);
+        }
+        loop {
+            let start = self.pos;
+            self.expression()?;
+            if self.pos == start + 1 {
+                arguments.push(self.tokens[start].0.clone());
+            }
+            if !self.eat(",") {
+                self.expect(end)?;
+                return Ok(arguments);
+            }
+        }
+    }
+
+    fn conversion(&self, ty: &str, literal: &str) -> Result<(), String> {
+        let fits = match (ty.strip_prefix("bytes"), ty.strip_prefix("uint")) {
+            (Some(bytes), _) if !bytes.is_empty() => {
+                literal == "0" || literal.len() == 2 + 2 * bytes.parse::<usize>().unwrap()
+            }
+            (_, Some(bits)) => bit_length(literal).is_some_and(|len| {
+                len <= if bits.is_empty() {
+                    256
+                } else {
+                    bits.parse().unwrap()
+                }
+            }),
+            _ => true,
+        };
+        match fits {
+            true => Ok(()),
+            false => Err(format!(
+                "line {}: `{}` does not convert to `{}`",
+                self.line(),
+                literal,
+                ty
+            )),
+        }
+    }
+
+    fn yul_block(&mut self) -> Result<(), String> {
+        self.expect("{")?;
+        while !self.eat("}") {
+            if let Token::Punct("{") = self.peek() {
+                self.yul_block()?;
+            } else if self.eat_keyword("let") || matches!(self.peek_at(1), Token::Punct(":=")) {
+                self.ident()?;
+                if self.eat(":=") {
+                    self.yul_expression()?;
+                }
+            } else if !self.yul_expression()? {
+                return Err(format!("line {}: yul statement is not a call", self.line()));
+            }
+        }
+        Ok(())
+    }
+
+    /// Parses a yul expression, returning whether it is a call.
+    fn yul_expression(&mut self) -> Result<bool, String> {
+        match self.peek() {
+            Token::Number(_) => {
+                self.pos += 1;
+                Ok(false)
+            }
+            Token::Ident(_) => {
+                self.pos += 1;
+                if !self.eat("(") {
+                    return Ok(false);
+                }
+                if self.eat(")") {
+                    return Ok(true);
+                }
+                loop {
+                    self.yul_expression()?;
+                    if !self.eat(",") {
+                        self.expect(")")?;
+                        return Ok(true);
+                    }
+                }
+            }
+            _ => Err(self.error("a yul expression")),
+        }
+    }
+}
+
+fn is_keyword(name: &str) -> bool {
+    matches!(
+        name,
+        "if" | "else"
+            | "return"
+            | "returns"
+            | "function"
+            | "contract"
+            | "error"
+            | "assembly"
+            | "true"
+            | "false"
+            | "new"
+            | "type"
+            | "is"
+            | "memory"
+            | "storage"
+            | "calldata"
+    ) || VISIBILITY.contains(&name)
+        || MUTABILITY.contains(&name)
+}
@@ -0,0 +1,231 @@
+//! Fuzzes the casts of the generated `CastHarness.huff` against the Solidity checked casts of
+//! `CastMirror.sol` on the embedded EVM, asserting both succeed with the same output or revert
+//! with the same data, and parses the generated Solidity.
+//!
+//! The mirror is compiled with the `solc` at `$SOLC`, or on the `PATH`. Without one, the harness
+//! is fuzzed against a model of the mirror's semantics instead. `$HUFFGEN_FUZZ_RUNS` and
+//! `$HUFFGEN_FUZZ_SEED` set the number of values per cast and the seed they are drawn from.
+
+mod common;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use common::fuzz::Rng;
+use huffgen::evm::{addr, Address, Evm, Halt};
+use huffgen::huff::{self, Program};
+use huffgen::u256::U256;
+use huffgen::{config, difftest, profile, profile::Profile, Generator, Library};
+
+const HARNESS: u64 = 0x4af;
+const MIRROR: u64 = 0x5011d;
+
+/// Generates the casts and their differential tests into a fresh directory, returning the
+/// directory of the tests.
+fn generate() -> PathBuf {
+    let root = common::temp_dir("differential");
+
+    let generator = Generator::new().with_library(Library::Cast);
+    generator.write_to(root.join("src")).unwrap();
+
+    let dir = root.join("differential");
+    fs::create_dir_all(&dir).unwrap();
+    for (name, contents) in difftest::render(generator.options(), "../src/libcast.huff") {
+        fs::write(dir.join(name), contents).unwrap();
+    }
+
+    dir
+}
+
+/// Compiles the runtime code of the mirror with `solc`, if one is installed.
+fn compile_mirror(dir: &Path) -> Option<Vec<u8>> {
+    let solc = std::env::var("SOLC").unwrap_or_else(|_| "solc".to_string());
+    let output = Command::new(solc)
+        .arg("--bin-runtime")
+        .arg("--optimize")
+        .arg("CastMirror.sol")
+        .current_dir(dir)
+        .output()
+        .ok()
+        .filter(|output| output.status.success())?;
+
+    let stdout = String::from_utf8(output.stdout).ok()?;
+    let hex = stdout.split("Binary of the runtime part:").nth(1)?.trim();
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+/// The output of the mirror's `function` on `value`: the returned words, or the revert data.
+fn model(function: &str, value: U256) -> (bool, Vec<u8>) {
+    let (kind, bits) = function.split_at(function.find(|c: char| c.is_ascii_digit()).unwrap());
+    let bits = bits.parse::<u32>().unwrap();
+    let max = U256::ONE.shl(bits).wrapping_sub(U256::ONE);
+    let fits = value <= max;
+    let truncated = value & max;
+
+    match kind {
+        "toU" | "miniToU" if !fits => (false, huff::selector("Overflow()").to_vec()),
+        "toU" | "miniToU" | "unsafeToU" | "unsafeMiniToU" => {
+            (true, truncated.to_be_bytes().to_vec())
+        }
+        "tryToU" => {
+            let success = if fits { U256::ONE } else { U256::ZERO };
+            (
+                true,
+                [success.to_be_bytes(), truncated.to_be_bytes()].concat(),
+            )
+        }
+        _ => panic!("unexpected cast `{}`", function),
+    }
+}
+
+fn call(evm: &mut Evm, address: Address, data: &[u8]) -> (bool, Vec<u8>) {
+    let outcome = common::call(evm, address, data);
+    match outcome.halt {
+        Halt::Return | Halt::Stop => (true, outcome.output),
+        Halt::Revert => (false, outcome.output),
+        halt => panic!("call halted with {:?}", halt),
+    }
+}
+
+#[test]
+fn casts_match_solidity() {
+    let dir = generate();
+    let harness = fs::read_to_string(dir.join("CastHarness.huff")).unwrap();
+    let program = Program::parse_str(&harness, &dir).unwrap();
+    let profile = Profile::load(&config::parse("evm_version = \"cancun\"").unwrap()).unwrap();
+
+    let mut evm = Evm::new();
+    evm.set_code(
+        addr(HARNESS),
+        profile::compile(program.clone(), "MAIN", &profile).unwrap(),
+    );
+    let solc = match compile_mirror(&dir) {
+        Some(code) => {
+            evm.set_code(addr(MIRROR), code);
+            true
+        }
+        None => {
+            eprintln!("solc not found, fuzzing against a model of `CastMirror.sol`");
+            false
+        }
+    };
+
+    let runs = common::fuzz::runs();
+    let mut rng = Rng::from_env();
+
+    let mut functions = program.functions.iter().collect::<Vec<_>>();
+    functions.sort();
+    for (function, signature) in functions {
+        let bits = function.trim_start_matches(|c: char| !c.is_ascii_digit());
+        let max = U256::ONE.shl(bits.parse().unwrap()).wrapping_sub(U256::ONE);
+        let edges = [U256::ZERO, max, max.wrapping_add(U256::ONE), U256::MAX];
+
+        for value in edges.into_iter().chain((0..runs).map(|_| rng.word())) {
+            let data = [&huff::selector(signature)[..], &value.to_be_bytes()].concat();
+            let expected = match solc {
+                true => call(&mut evm, addr(MIRROR), &data),
+                false => model(function, value),
+            };
+
+            assert_eq!(
+                call(&mut evm, addr(HARNESS), &data),
+                expected,
+                "{}({})",
+                function,
+                value
+            );
+        }
+    }
+}
+
+#[test]
+fn solidity_is_well_formed() {
+    let dir = generate();
+    let source = |name: &str| fs::read_to_string(dir.join(name)).unwrap();
+
+    let mirror = common::solidity::check(&source("CastMirror.sol")).unwrap();
+    let test = common::solidity::check(&source("Cast.t.sol")).unwrap();
+    assert_eq!((mirror.len(), test.len()), (1, 1));
+    assert_eq!(test[0].bases, ["Test"]);
+
+    // the test calls each function of the mirror, which are those of the harness
+    let harness = Program::parse_str(&source("CastHarness.huff"), &dir).unwrap();
+    let mut functions = harness.functions.keys().cloned().collect::<Vec<_>>();
+    functions.sort();
+    let mut mirrored = mirror[0].functions.clone();
+    mirrored.sort();
+    assert_eq!(mirrored, functions);
+    for function in &mirror[0].functions {
+        let call = format!("abi.encodeCall(CastMirror.{}, ", function);
+        assert!(
+            source("Cast.t.sol").contains(&call),
+            "{} is not tested",
+            function
+        );
+    }
+}
+
+#[test]
+fn solidity_errors() {
+    let contract = |body: &str| format!("pragma solidity ^0.8.0;\ncontract C {{\n{}\n}}\n", body);
+    let errors = [
+        ("function f() public { return 1 }", "line 3: expected `;`"),
+        (
+            "function f() public { g(; }",
+            "line 3: expected an expression",
+        ),
+        ("function f() pure {}", "line 3: `f` needs one visibility"),
+        (
+            "function f() external pure view {}",
+            "line 3: `f` needs one visibility",
+        ),
+        (
+            "function f(bytes b) external {}",
+            "line 3: reference type needs a data location",
+        ),
+        (
+            "function f(uint256 memory a) external {}",
+            "line 3: value type cannot have",
+        ),
+        (
+            "function f() external { bytes4(0x123456); }",
+            "line 3: `0x123456` does not convert",
+        ),
+        (
+            "function f() external { uint8(0x100); }",
+            "line 3: `0x100` does not convert",
+        ),
+        (
+            "function f() external { g(hex\"123\"); }",
+            "line 3: invalid hex literal",
+        ),
+        (
+            "function f() external { assembly { x } }",
+            "line 3: yul statement is not a call",
+        ),
+        (
+            "function f() external {",
+            "line 5: expected an identifier, found Eof",
+        ),
+        ("uint7 x;", "line 3: invalid type `uint7`"),
+    ];
+    for (body, error) in errors {
+        let result = common::solidity::check(&contract(body));
+        assert!(
+            result.as_ref().is_err_and(|e| e.starts_with(error)),
+            "{}: {:?}",
+            body,
+            result
+        );
+    }
+
+    let ok = "function f(uint256 a) external pure returns (bool, uint8) {\n\
+              if (a > type(uint8).max) revert E();\n\
+              (bool ok, bytes memory out) = address(this).staticcall(hex\"\");\n\
+              return (ok && out.length == 0, uint8(a));\n}";
+    assert!(common::solidity::check(&contract(ok)).is_ok());
+}
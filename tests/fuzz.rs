@@ -0,0 +1,303 @@
+//! Fuzzes the macros of each arithmetic library of `src` on the embedded EVM against references in
+//! Rust, on the edge cases of each type and random words drawn from `$HUFFGEN_FUZZ_SEED`,
+//! `$HUFFGEN_FUZZ_RUNS` per macro. A divergence fails with its inputs shrunk.
+
+mod common;
+
+use common::fuzz::{Outcome, Rng, Target};
+use huffgen::u256::U256;
+
+const INT_SIZES: std::ops::RangeInclusive<u16> = 8..=256;
+
+/// The reference of a macro taking two words.
+type Reference<'a> = &'a dyn Fn(U256, U256) -> Outcome;
+
+/// An operation on two words that a reference is built from.
+type Op = fn(U256, U256) -> U256;
+
+/// The largest value of `bits` bits.
+fn max(bits: u16) -> U256 {
+    U256::ONE.shl(bits as u32).wrapping_sub(U256::ONE)
+}
+
+fn ok(words: &[U256]) -> Outcome {
+    Ok(words.to_vec())
+}
+
+fn revert(error: &str) -> Outcome {
+    Err(error.to_string())
+}
+
+/// Each pair of zero, one and `max`.
+fn pairs(max: U256) -> Vec<Vec<U256>> {
+    let edges = [U256::ZERO, U256::ONE, max];
+    edges
+        .iter()
+        .flat_map(|a| edges.iter().map(move |b| vec![*a, *b]))
+        .collect()
+}
+
+/// Zero, one, `max`, one past it and the largest word.
+fn singles(max: U256) -> Vec<Vec<U256>> {
+    let edges = [
+        U256::ZERO,
+        U256::ONE,
+        max,
+        max.wrapping_add(U256::ONE),
+        U256::MAX,
+    ];
+    edges.into_iter().map(|edge| vec![edge]).collect()
+}
+
+/// `n` random words of at most `max`, as the macros of a type require.
+fn typed(n: usize, max: U256) -> impl Fn(&mut Rng) -> Vec<U256> {
+    move |rng| (0..n).map(|_| rng.word() & max).collect()
+}
+
+fn words(rng: &mut Rng) -> Vec<U256> {
+    vec![rng.word()]
+}
+
+#[test]
+fn casts() {
+    let mut rng = Rng::from_env();
+
+    for size in INT_SIZES.step_by(8) {
+        let max = max(size);
+        let checked = move |x: U256| {
+            if x <= max {
+                ok(&[x])
+            } else {
+                revert("Overflow")
+            }
+        };
+        let tried = move |x: U256| ok(&[U256::from_u64((x <= max) as u64), x & max]);
+        let unchecked = move |x: U256| ok(&[x & max]);
+        let references: [(&str, &dyn Fn(U256) -> Outcome); 3] = [
+            ("TO", &checked),
+            ("TRY_TO", &tried),
+            ("UNSAFE_TO", &unchecked),
+        ];
+
+        for (prefix, reference) in references {
+            let invocation = format!("{}_U{}()", prefix, size);
+            let mut target = Target::new("libcast.huff", &invocation, 1);
+            target.fuzz(&mut rng, &singles(max), words, |x| reference(x[0]));
+        }
+    }
+}
+
+#[test]
+fn signed_casts() {
+    let mut rng = Rng::from_env();
+
+    for size in INT_SIZES.step_by(8).filter(|size| *size < 256) {
+        let byte = U256::from_u64(size as u64 / 8 - 1);
+        let max = max(size - 1);
+        let min = max.wrapping_add(U256::ONE).neg();
+        let edges = [
+            min,
+            min.wrapping_sub(U256::ONE),
+            U256::MAX,
+            max.wrapping_add(U256::ONE),
+        ];
+        let edges = [
+            singles(max),
+            edges.into_iter().map(|edge| vec![edge]).collect(),
+        ]
+        .concat();
+
+        let mut target = Target::new("libsigned.huff", &format!("TO_I{}()", size), 1);
+        target.fuzz(&mut rng, &edges, words, |x| {
+            match x[0].signextend(byte) == x[0] {
+                true => ok(x),
+                false => revert("Overflow"),
+            }
+        });
+    }
+}
+
+#[test]
+fn safe_math() {
+    let mut rng = Rng::from_env();
+
+    for size in INT_SIZES.step_by(8) {
+        let max = max(size);
+        let fits = move |x: U256| {
+            if x <= max {
+                ok(&[x])
+            } else {
+                revert("Overflow")
+            }
+        };
+        let add = move |a: U256, b: U256| a.checked_add(b).map_or(revert("Overflow"), fits);
+        let sub = |a: U256, b: U256| match a < b {
+            true => revert("Underflow"),
+            false => ok(&[a.wrapping_sub(b)]),
+        };
+        let mul = move |a: U256, b: U256| match a.overflowing_mul(b) {
+            (product, false) => fits(product),
+            _ => revert("Overflow"),
+        };
+        let div = |a: U256, b: U256| match b.is_zero() {
+            true => revert("DivByZero"),
+            false => ok(&[a.div(b)]),
+        };
+        let references: [(&str, Reference); 4] =
+            [("ADD", &add), ("SUB", &sub), ("MUL", &mul), ("DIV", &div)];
+
+        for (name, reference) in references {
+            let invocation = format!("SAFE_{}_U{}()", name, size);
+            let mut target = Target::new("libsafemath.huff", &invocation, 2);
+            target.fuzz(&mut rng, &pairs(max), typed(2, max), |x| {
+                reference(x[0], x[1])
+            });
+        }
+    }
+}
+
+#[test]
+fn wrapping_math() {
+    let mut rng = Rng::from_env();
+
+    for size in INT_SIZES.step_by(8) {
+        let max = max(size);
+        let references: [(&str, Op); 3] = [
+            ("ADD", U256::wrapping_add),
+            ("SUB", U256::wrapping_sub),
+            ("MUL", U256::wrapping_mul),
+        ];
+
+        for (name, reference) in references {
+            let invocation = format!("WRAPPING_{}_U{}()", name, size);
+            let mut target = Target::new("libwrapping.huff", &invocation, 2);
+            target.fuzz(&mut rng, &pairs(max), typed(2, max), |x| {
+                ok(&[reference(x[0], x[1]) & max])
+            });
+        }
+    }
+}
+
+#[test]
+fn comparisons() {
+    let mut rng = Rng::from_env();
+
+    for size in INT_SIZES.step_by(8) {
+        let max = max(size);
+
+        for prefix in ["", "BRANCHLESS_"] {
+            let references: [(&str, Op); 2] = [("MIN", std::cmp::min), ("MAX", std::cmp::max)];
+            for (name, reference) in references {
+                let invocation = format!("{}{}_U{}()", prefix, name, size);
+                let mut target = Target::new("libcmp.huff", &invocation, 2);
+                target.fuzz(&mut rng, &pairs(max), typed(2, max), |x| {
+                    ok(&[reference(x[0], x[1])])
+                });
+            }
+
+            let invocation = format!("{}CLAMP_U{}()", prefix, size);
+            let mut target = Target::new("libcmp.huff", &invocation, 1);
+            target.fuzz(&mut rng, &singles(max), words, |x| ok(&[x[0].min(max)]));
+        }
+
+        let mut target = Target::new("libcmp.huff", &format!("IS_U{}()", size), 1);
+        target.fuzz(&mut rng, &singles(max), words, |x| {
+            ok(&[U256::from_u64((x[0] <= max) as u64)])
+        });
+    }
+}
+
+#[test]
+fn bits() {
+    let mut rng = Rng::from_env();
+    let n = U256::from_u64;
+    let bit = |index: U256| match index.as_usize() {
+        Some(index) if index < 256 => U256::ONE.shl(index as u32),
+        _ => U256::ZERO,
+    };
+
+    let indexed = |rng: &mut Rng| vec![n(rng.next() % 288), rng.word()];
+    let edges = [0, 1, 255, 256]
+        .into_iter()
+        .flat_map(|index| [U256::ZERO, U256::MAX].map(|word| vec![n(index), word]))
+        .collect::<Vec<_>>();
+    let references: [(&str, Op); 4] = [
+        ("GET_BIT_AT", |mask, word| {
+            U256::from_u64(!(word & mask).is_zero() as u64)
+        }),
+        ("SET_BIT_AT", |mask, word| word | mask),
+        ("CLEAR_BIT_AT", |mask, word| word & !mask),
+        ("TOGGLE_BIT_AT", |mask, word| word ^ mask),
+    ];
+    for (name, reference) in references {
+        let mut target = Target::new("libbits.huff", &format!("{}()", name), 2);
+        target.fuzz(&mut rng, &edges, indexed, |x| {
+            ok(&[reference(bit(x[0]), x[1])])
+        });
+    }
+
+    let ones = |word: U256| {
+        word.0
+            .iter()
+            .map(|limb| limb.count_ones() as u64)
+            .sum::<u64>()
+    };
+    let lowest = |word: U256| (0..256).find(|i| word.bit(*i)).unwrap_or(256) as u64;
+    let references: [(&str, &dyn Fn(U256) -> u64); 3] = [
+        ("POPCOUNT", &ones),
+        ("MSB", &|word| {
+            word.bits().checked_sub(1).unwrap_or(256) as u64
+        }),
+        ("LSB", &lowest),
+    ];
+    for (name, reference) in references {
+        let mut target = Target::new("libbits.huff", &format!("{}()", name), 1);
+        target.fuzz(&mut rng, &singles(U256::MAX), words, |x| {
+            ok(&[n(reference(x[0]))])
+        });
+    }
+}
+
+#[test]
+fn fixed_point() {
+    let mut rng = Rng::from_env();
+    let wad = U256::from_u64(1_000_000_000_000_000_000);
+
+    let up = |(quotient, remainder): (U256, U256)| match remainder.is_zero() {
+        true => quotient,
+        false => quotient.wrapping_add(U256::ONE),
+    };
+    let references: [(&str, Reference); 4] = [
+        ("MUL_WAD", &|a, b| match a.overflowing_mul(b) {
+            (product, false) => ok(&[product.div(wad)]),
+            _ => revert("Overflow"),
+        }),
+        ("MUL_WAD_UP", &|a, b| match a.overflowing_mul(b) {
+            (product, false) => ok(&[up(product.div_rem(wad))]),
+            _ => revert("Overflow"),
+        }),
+        (
+            "DIV_WAD",
+            &|a, b| match (b.is_zero(), a.overflowing_mul(wad)) {
+                (true, _) => revert("DivByZero"),
+                (false, (product, false)) => ok(&[product.div(b)]),
+                _ => revert("Overflow"),
+            },
+        ),
+        (
+            "DIV_WAD_UP",
+            &|a, b| match (b.is_zero(), a.overflowing_mul(wad)) {
+                (true, _) => revert("DivByZero"),
+                (false, (product, false)) => ok(&[up(product.div_rem(b))]),
+                _ => revert("Overflow"),
+            },
+        ),
+    ];
+
+    let edges = [pairs(U256::MAX), pairs(wad)].concat();
+    let draw = |rng: &mut Rng| vec![rng.word(), rng.word()];
+    for (name, reference) in references {
+        let mut target = Target::new("libfixedpoint.huff", &format!("{}()", name), 2);
+        target.fuzz(&mut rng, &edges, draw, |x| reference(x[0], x[1]));
+    }
+}
@@ -0,0 +1,4088 @@
+//! Compiles the generated libraries and executes their macros on the embedded EVM, checking the
+//! edge cases of each type: zero, its maximum, and one past its maximum.
+
+mod common;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use huffgen::diagnostics::Severity;
+use huffgen::evm::{self, addr, Call, Evm, Halt};
+use huffgen::huff::{self, Program};
+use huffgen::naming::Huffmate;
+use huffgen::profile::{self, Profile};
+use huffgen::u256::U256;
+use huffgen::{
+    bench, config, docs, listing, testgen, EvmVersion, Generator, Layout, Library, Naming, Target,
+};
+
+const INT_SIZES: std::ops::RangeInclusive<u16> = 8..=256;
+
+/// The result of running a macro: its outputs top first, or the name of the error it reverted with.
+type Outcome = Result<Vec<U256>, String>;
+
+/// Generates every library into a fresh directory.
+fn generate(name: &str) -> PathBuf {
+    let dir = common::temp_dir(name);
+    Generator::new().write_to(&dir).unwrap();
+    dir
+}
+
+fn cancun() -> Profile {
+    Profile::load(&config::parse("evm_version = \"cancun\"").unwrap()).unwrap()
+}
+
+/// Runs `invocation` of the library `file` in `dir` with `stack` pushed, top first.
+fn run(dir: &Path, file: &str, invocation: &str, stack: &[U256]) -> Outcome {
+    step(&mut Evm::new(), dir, file, invocation, stack)
+}
+
+/// Runs `invocation` like `run`, against the accounts and block of `evm`, keeping its state
+/// changes for the next step.
+fn step(evm: &mut Evm, dir: &Path, file: &str, invocation: &str, stack: &[U256]) -> Outcome {
+    let pushes = stack
+        .iter()
+        .rev()
+        .map(|word| word.to_hex())
+        .collect::<Vec<String>>();
+    let body = format!("{} {}", pushes.join(" "), invocation);
+    let (program, code) = compile(dir, file, &body);
+    let outcome = transact(evm, code, &[]);
+    result(&program, invocation, outcome)
+}
+
+/// The outputs of a stopped `invocation`, or the name of the error of `program` it reverted with.
+fn result(program: &Program, invocation: &str, outcome: evm::Outcome) -> Outcome {
+    match outcome.halt {
+        Halt::Stop => Ok(outcome.stack),
+        Halt::Revert => Err(program
+            .errors
+            .values()
+            .find(|signature| outcome.output.starts_with(&huff::selector(signature)))
+            .map(|signature| signature.split('(').next().unwrap().to_string())
+            .unwrap_or_else(|| format!("0x{}", hex(&outcome.output)))),
+        halt => panic!("{} halted with {:?}", invocation, halt),
+    }
+}
+
+/// Executes `body` as the `MAIN` macro of a program including the library `file` in `dir`, called
+/// with `data`.
+fn execute(dir: &Path, file: &str, body: &str, data: &[u8]) -> (Program, evm::Outcome) {
+    let (program, code) = compile(dir, file, body);
+    let outcome = transact(&mut Evm::new(), code, data);
+
+    (program, outcome)
+}
+
+/// Compiles `body` as the `MAIN` macro of a program including the library `file` in `dir`.
+fn compile(dir: &Path, file: &str, body: &str) -> (Program, Vec<u8>) {
+    let src = format!(
+        "#include \"{}\"\n\n#define macro MAIN() = takes (0) returns (0) {{\n    {}\n}}\n",
+        file, body,
+    );
+
+    let program = Program::parse_str(&src, dir).unwrap();
+    let code = profile::compile(program.clone(), "MAIN", &cancun())
+        .unwrap_or_else(|e| panic!("{} does not compile: {}", body, e));
+
+    (program, code)
+}
+
+/// Calls `code`, deployed to `0xc0de` in `evm`, from `0xca11` with `data`.
+fn transact(evm: &mut Evm, code: Vec<u8>, data: &[u8]) -> evm::Outcome {
+    evm.set_code(addr(0xc0de), code);
+    common::call(evm, addr(0xc0de), data)
+}
+
+/// Calls `code` with `data` and `value` wei, returning its stack on stopping, or its revert data.
+fn send(code: &[u8], data: &[u8], value: u64) -> Result<Vec<U256>, String> {
+    let mut evm = Evm::new();
+    evm.set_code(addr(0xc0de), code.to_vec());
+    let outcome = evm.transact(Call {
+        caller: addr(common::CALLER),
+        address: addr(0xc0de),
+        code_address: addr(0xc0de),
+        value: U256::from_u64(value),
+        data: data.to_vec(),
+        gas: 30_000_000,
+        is_static: false,
+        transfer: false,
+    });
+    match outcome.halt {
+        Halt::Stop => Ok(outcome.stack),
+        Halt::Revert => Err(hex(&outcome.output)),
+        halt => panic!("halted with {:?}", halt),
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// The largest value of `bits` bits.
+fn max(bits: u16) -> U256 {
+    U256::ONE.shl(bits as u32).wrapping_sub(U256::ONE)
+}
+
+fn ok(words: &[U256]) -> Outcome {
+    Ok(words.to_vec())
+}
+
+fn revert(error: &str) -> Outcome {
+    Err(error.to_string())
+}
+
+#[test]
+fn every_library_compiles() {
+    let dir = generate("compiles");
+    let profile = cancun();
+
+    let mut files = fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "huff"))
+        .collect::<Vec<PathBuf>>();
+    files.sort();
+
+    let failures = files
+        .iter()
+        .filter_map(|file| {
+            listing::render(file, None, &profile)
+                .err()
+                .map(|e| format!("{}: {}", file.display(), e))
+        })
+        .collect::<Vec<String>>();
+    assert!(
+        failures.is_empty(),
+        "libraries failed to compile:\n{}",
+        failures.join("\n")
+    );
+}
+
+#[test]
+fn unsigned_casts() {
+    let dir = generate("casts");
+    let src = fs::read_to_string(dir.join("libcast.huff")).unwrap();
+
+    for size in INT_SIZES.step_by(8) {
+        let max = max(size);
+        let cast =
+            |name: &str, value: U256| run(&dir, "libcast.huff", &format!("{}()", name), &[value]);
+
+        for prefix in ["TO", "MINI_TO"] {
+            let name = format!("{}_U{}", prefix, size);
+            if !src.contains(&format!("#define macro {}()", name)) {
+                continue;
+            }
+            assert_eq!(cast(&name, U256::ZERO), ok(&[U256::ZERO]), "{}(0)", name);
+            assert_eq!(cast(&name, max), ok(&[max]), "{}(MAX)", name);
+            if size < 256 {
+                assert_eq!(
+                    cast(&name, max.wrapping_add(U256::ONE)),
+                    revert("Overflow"),
+                    "{}(MAX + 1)",
+                    name
+                );
+            }
+        }
+
+        let unsafe_name = format!("UNSAFE_TO_U{}", size);
+        assert_eq!(cast(&unsafe_name, max), ok(&[max]), "{}(MAX)", unsafe_name);
+        if size < 256 {
+            assert_eq!(
+                cast(&unsafe_name, max.wrapping_add(U256::ONE)),
+                ok(&[U256::ZERO]),
+                "{}(MAX + 1)",
+                unsafe_name
+            );
+        }
+
+        let try_name = format!("TRY_TO_U{}", size);
+        assert_eq!(
+            cast(&try_name, max),
+            ok(&[U256::ONE, max]),
+            "{}(MAX)",
+            try_name
+        );
+        if size < 256 {
+            let overflowed = max.wrapping_add(U256::ONE);
+            assert_eq!(
+                cast(&try_name, overflowed),
+                ok(&[U256::ZERO, U256::ZERO]),
+                "{}(MAX + 1)",
+                try_name
+            );
+        }
+
+        let values = [max, U256::ONE, U256::ZERO];
+        let pair_name = format!("TO_U{}_2()", size);
+        let triple_name = format!("TO_U{}_3()", size);
+        let multi = |name: &str, values: &[U256]| run(&dir, "libcast.huff", name, values);
+        assert_eq!(
+            multi(&pair_name, &values[..2]),
+            ok(&values[..2]),
+            "{}",
+            pair_name
+        );
+        assert_eq!(multi(&triple_name, &values), ok(&values), "{}", triple_name);
+        if size < 256 {
+            let overflowed = max.wrapping_add(U256::ONE);
+            for i in 0..3 {
+                let mut values = values;
+                values[i] = overflowed;
+                if i < 2 {
+                    let pair = multi(&pair_name, &values[..2]);
+                    assert_eq!(pair, revert("Overflow"), "{} overflowing {}", pair_name, i);
+                }
+                let triple = multi(&triple_name, &values);
+                assert_eq!(
+                    triple,
+                    revert("Overflow"),
+                    "{} overflowing {}",
+                    triple_name,
+                    i
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn address_and_bool_casts() {
+    let dir = generate("address");
+    let cast = |name: &str, value: U256| run(&dir, "libcast.huff", name, &[value]);
+    let address = max(160);
+    let dirty = address.wrapping_add(U256::ONE);
+
+    assert_eq!(cast("TO_ADDRESS()", address), ok(&[address]));
+    assert_eq!(cast("TO_ADDRESS()", dirty), revert("Overflow"));
+    assert_eq!(
+        cast("UNSAFE_TO_ADDRESS()", dirty.wrapping_add(address)),
+        ok(&[address])
+    );
+
+    assert_eq!(cast("TO_BOOL()", U256::ZERO), ok(&[U256::ZERO]));
+    assert_eq!(cast("TO_BOOL()", U256::ONE), ok(&[U256::ONE]));
+    assert_eq!(cast("TO_BOOL()", U256::from_u64(2)), revert("Overflow"));
+    assert_eq!(cast("TO_BOOL()", U256::MAX), revert("Overflow"));
+}
+
+#[test]
+fn signed_casts() {
+    let dir = generate("signed");
+
+    for size in INT_SIZES.step_by(8).filter(|size| *size < 256) {
+        let name = format!("TO_I{}", size);
+        let cast = |value: U256| run(&dir, "libsigned.huff", &format!("{}()", name), &[value]);
+
+        let max = max(size - 1);
+        let min = max.wrapping_add(U256::ONE).neg();
+        let minus_one = U256::MAX;
+
+        assert_eq!(cast(U256::ZERO), ok(&[U256::ZERO]), "{}(0)", name);
+        assert_eq!(cast(minus_one), ok(&[minus_one]), "{}(-1)", name);
+        assert_eq!(cast(max), ok(&[max]), "{}(MAX)", name);
+        assert_eq!(cast(min), ok(&[min]), "{}(MIN)", name);
+        assert_eq!(
+            cast(max.wrapping_add(U256::ONE)),
+            revert("Overflow"),
+            "{}(MAX + 1)",
+            name
+        );
+        assert_eq!(
+            cast(min.wrapping_sub(U256::ONE)),
+            revert("Overflow"),
+            "{}(MIN - 1)",
+            name
+        );
+    }
+}
+
+#[test]
+fn safe_math() {
+    let dir = generate("safemath");
+
+    for size in INT_SIZES.step_by(8) {
+        let max = max(size);
+        let two = U256::from_u64(2);
+        let op = |name: &str, a: U256, b: U256| {
+            run(
+                &dir,
+                "libsafemath.huff",
+                &format!("SAFE_{}_U{}()", name, size),
+                &[a, b],
+            )
+        };
+
+        assert_eq!(
+            op("ADD", max.wrapping_sub(U256::ONE), U256::ONE),
+            ok(&[max]),
+            "U{} add",
+            size
+        );
+        assert_eq!(
+            op("ADD", max, U256::ONE),
+            revert("Overflow"),
+            "U{} add overflow",
+            size
+        );
+        assert_eq!(op("SUB", max, max), ok(&[U256::ZERO]), "U{} sub", size);
+        assert_eq!(
+            op("SUB", U256::ZERO, U256::ONE),
+            revert("Underflow"),
+            "U{} sub underflow",
+            size
+        );
+        assert_eq!(op("MUL", max, U256::ONE), ok(&[max]), "U{} mul", size);
+        assert_eq!(
+            op("MUL", max, two),
+            revert("Overflow"),
+            "U{} mul overflow",
+            size
+        );
+        assert_eq!(op("DIV", max, U256::ONE), ok(&[max]), "U{} div", size);
+        assert_eq!(
+            op("DIV", max, U256::ZERO),
+            revert("DivByZero"),
+            "U{} div by zero",
+            size
+        );
+    }
+}
+
+#[test]
+fn wrapping_math() {
+    let dir = generate("wrapping");
+
+    for size in INT_SIZES.step_by(8) {
+        let max = max(size);
+        let op = |name: &str, a: U256, b: U256| {
+            run(
+                &dir,
+                "libwrapping.huff",
+                &format!("WRAPPING_{}_U{}()", name, size),
+                &[a, b],
+            )
+        };
+
+        assert_eq!(
+            op("ADD", max, U256::ONE),
+            ok(&[U256::ZERO]),
+            "U{} add",
+            size
+        );
+        assert_eq!(
+            op("SUB", U256::ZERO, U256::ONE),
+            ok(&[max]),
+            "U{} sub",
+            size
+        );
+        assert_eq!(op("MUL", max, max), ok(&[U256::ONE]), "U{} mul", size);
+    }
+}
+
+#[test]
+fn comparisons() {
+    let dir = generate("cmp");
+
+    for size in INT_SIZES.step_by(8) {
+        let max = max(size);
+        let op = |name: &str, stack: &[U256]| {
+            run(&dir, "libcmp.huff", &format!("{}_U{}()", name, size), stack)
+        };
+        let (one, two) = (U256::ONE, U256::from_u64(2));
+
+        for prefix in ["", "BRANCHLESS_"] {
+            let min = format!("{}MIN", prefix);
+            let max_op = format!("{}MAX", prefix);
+            let clamp = format!("{}CLAMP", prefix);
+
+            assert_eq!(op(&min, &[one, two]), ok(&[one]), "U{} {}", size, min);
+            assert_eq!(
+                op(&min, &[max, U256::ZERO]),
+                ok(&[U256::ZERO]),
+                "U{} {}",
+                size,
+                min
+            );
+            assert_eq!(op(&max_op, &[one, two]), ok(&[two]), "U{} {}", size, max_op);
+            assert_eq!(op(&max_op, &[max, max]), ok(&[max]), "U{} {}", size, max_op);
+            assert_eq!(op(&clamp, &[max]), ok(&[max]), "U{} {}", size, clamp);
+            assert_eq!(op(&clamp, &[U256::MAX]), ok(&[max]), "U{} {}", size, clamp);
+        }
+
+        assert_eq!(op("IS", &[max]), ok(&[one]), "U{} is", size);
+        if size != 256 {
+            assert_eq!(
+                op("IS", &[max.wrapping_add(one)]),
+                ok(&[U256::ZERO]),
+                "U{} is",
+                size
+            );
+        }
+    }
+}
+
+#[test]
+fn bits() {
+    let dir = generate("bits");
+    let op = |invocation: &str, stack: &[U256]| run(&dir, "libbits.huff", invocation, stack);
+    let n = U256::from_u64;
+    let bit = |index: u32| U256::ONE.shl(index);
+    let word = n(0b1010_0100) | bit(255);
+
+    for index in [0, 2, 3, 7, 128, 255] {
+        let expected = if word.bit(index) {
+            U256::ONE
+        } else {
+            U256::ZERO
+        };
+        let indexed = format!("(0x{:02x})", index);
+
+        assert_eq!(
+            op(&format!("GET_BIT{}", indexed), &[word]),
+            ok(&[expected]),
+            "get {}",
+            index
+        );
+        assert_eq!(
+            op("GET_BIT_AT()", &[n(index as u64), word]),
+            ok(&[expected]),
+            "{}",
+            index
+        );
+        for (name, result) in [
+            ("SET_BIT", word | bit(index)),
+            ("CLEAR_BIT", word & !bit(index)),
+            ("TOGGLE_BIT", word ^ bit(index)),
+        ] {
+            assert_eq!(
+                op(&format!("{}{}", name, indexed), &[word]),
+                ok(&[result]),
+                "{}",
+                name
+            );
+            let at = format!("{}_AT()", name);
+            assert_eq!(
+                op(&at, &[n(index as u64), word]),
+                ok(&[result]),
+                "{} {}",
+                at,
+                index
+            );
+        }
+    }
+    assert_eq!(op("SET_BIT_AT()", &[n(256), word]), ok(&[word]));
+    assert_eq!(op("GET_BIT_AT()", &[U256::MAX, word]), ok(&[U256::ZERO]));
+
+    for (value, count, msb, lsb) in [
+        (U256::ZERO, 0, 256, 256),
+        (U256::ONE, 1, 0, 0),
+        (word, 4, 255, 2),
+        (n(0xf0), 4, 7, 4),
+        (bit(128), 1, 128, 128),
+        (max(255), 255, 254, 0),
+        (U256::MAX, 256, 255, 0),
+    ] {
+        assert_eq!(
+            op("POPCOUNT()", &[value]),
+            ok(&[n(count)]),
+            "popcount {:?}",
+            value
+        );
+        assert_eq!(op("MSB()", &[value]), ok(&[n(msb)]), "msb {:?}", value);
+        assert_eq!(op("LSB()", &[value]), ok(&[n(lsb)]), "lsb {:?}", value);
+    }
+}
+
+#[test]
+fn bytes() {
+    let copy_loop = generate("bytes");
+    let mcopy = generate("bytes-mcopy");
+    let cancun = Generator::new()
+        .with_evm_version(EvmVersion::Cancun)
+        .render()
+        .unwrap();
+    fs::write(mcopy.join("libbytes.huff"), &cancun["libbytes.huff"]).unwrap();
+    assert!(!fs::read_to_string(copy_loop.join("libbytes.huff"))
+        .unwrap()
+        .contains("    mcopy "));
+    assert!(cancun["libbytes.huff"].contains("    mcopy "));
+
+    // a 0x30 byte array at 0x00, a 0x05 byte array at 0x80 and ones from 0x100
+    let data = (1..=64).collect::<Vec<u8>>();
+    let mut memory = vec![0u8; 0x180];
+    memory[0x1f] = 0x30;
+    memory[0x20..0x60].copy_from_slice(&data);
+    memory[0x9f] = 0x05;
+    memory[0xa0..0xe0].copy_from_slice(&data);
+    memory[0x100..].fill(0xff);
+    let setup = memory
+        .chunks(32)
+        .enumerate()
+        .map(|(i, word)| {
+            format!(
+                "{} {:#x} mstore",
+                U256::from_be_bytes(word).to_hex(),
+                i * 32
+            )
+        })
+        .collect::<Vec<String>>()
+        .join(" ");
+    let word = |memory: &[u8], offset: usize| U256::from_be_bytes(&memory[offset..offset + 32]);
+    let n = U256::from_u64;
+
+    for dir in [&copy_loop, &mcopy] {
+        let op = |code: &str| run(dir, "libbytes.huff", &format!("{} {}", setup, code), &[]);
+
+        for len in [0, 5, 32, 37, 64] {
+            let mut copied = memory.clone();
+            copied.copy_within(0x20..0x20 + len, 0x103);
+            let code = format!(
+                "{:#x} 0x20 0x0103 MEMCOPY() 0x0140 mload 0x0120 mload 0x0100 mload",
+                len
+            );
+            assert_eq!(
+                op(&code),
+                ok(&[
+                    word(&copied, 0x100),
+                    word(&copied, 0x120),
+                    word(&copied, 0x140)
+                ]),
+                "{:?} copy of {} bytes",
+                dir,
+                len
+            );
+        }
+
+        let mut sliced = memory.clone();
+        sliced[0x100..0x120].fill(0);
+        sliced[0x11f] = 0x28;
+        sliced[0x120..0x148].copy_from_slice(&data[3..43]);
+        assert_eq!(
+            op("0x0100 0x28 0x03 0x00 BYTES_SLICE() dup1 0x40 add mload dup2 0x20 add mload"),
+            ok(&[word(&sliced, 0x120), word(&sliced, 0x140), n(0x100)])
+        );
+        assert_eq!(
+            op("0x0100 0x28 0x0a 0x00 BYTES_SLICE()"),
+            revert("OutOfBounds")
+        );
+        assert_eq!(
+            op("0x0100 0x02 0x00 not 0x00 BYTES_SLICE()"),
+            revert("OutOfBounds")
+        );
+
+        let mut concatenated = sliced.clone();
+        concatenated[0x11f] = 0x35;
+        concatenated[0x120..0x150].copy_from_slice(&data[..0x30]);
+        concatenated[0x150..0x155].copy_from_slice(&data[..0x05]);
+        assert_eq!(
+            op(
+                "0x0100 0x80 0x00 BYTES_CONCAT() dup1 mload dup2 0x40 add mload dup3 0x20 add \
+                mload"
+            ),
+            ok(&[
+                word(&concatenated, 0x120),
+                word(&concatenated, 0x140),
+                n(0x35),
+                n(0x100)
+            ])
+        );
+        let mut copied = memory.clone();
+        copied.copy_within(0x80..0xa5, 0x100);
+        assert_eq!(
+            op("0x0100 0x80 BYTES_COPY() dup1 0x20 add mload dup2 mload"),
+            ok(&[n(0x05), word(&copied, 0x120), n(0x100)])
+        );
+
+        let hashes = op("0x28 0x03 0x00 BYTES_HASH_SLICE() 0x28 0x23 sha3").unwrap();
+        assert_eq!(hashes[0], hashes[1]);
+        assert_eq!(
+            op("0x01 0x30 0x00 BYTES_HASH_SLICE()"),
+            revert("OutOfBounds")
+        );
+    }
+}
+
+#[test]
+fn abi_encoding() {
+    let dir = generate("abi");
+    let words = (1..=8)
+        .map(|i| U256::from_u64(i * 0x1111))
+        .collect::<Vec<U256>>();
+    let pushes = |count: usize| {
+        words[..count]
+            .iter()
+            .rev()
+            .map(|word| word.to_hex())
+            .collect::<Vec<String>>()
+            .join(" ")
+    };
+    let encoded =
+        |words: &[U256]| -> Vec<u8> { words.iter().flat_map(|word| word.to_be_bytes()).collect() };
+
+    for count in 1..=4 {
+        let name = match count {
+            1 => "RETURN_U256".to_string(),
+            _ => format!("RETURN_{}_WORDS", count),
+        };
+        let body = format!("{} {}()", pushes(count), name);
+        let (_, outcome) = execute(&dir, "libabi.huff", &body, &[]);
+        assert_eq!(outcome.halt, Halt::Return, "{}", name);
+        assert_eq!(outcome.output, encoded(&words[..count]), "{}", name);
+    }
+
+    for (free_ptr, ptr) in [(None, 0x80), (Some(0x0100), 0x0100)] {
+        let allocate = free_ptr.map_or(String::new(), |ptr| format!("{:#06x} 0x40 mstore ", ptr));
+        for topics in 0..=4 {
+            for data in 0..=4 {
+                let name = format!("EMIT_LOG{}_{}", topics, data);
+                let code = format!(
+                    "{}{} {}() 0x40 mload {:#06x} mload",
+                    allocate,
+                    pushes(data + topics),
+                    name,
+                    ptr
+                );
+                let (_, outcome) = execute(&dir, "libabi.huff", &code, &[]);
+
+                assert_eq!(outcome.halt, Halt::Stop, "{}", name);
+                assert_eq!(outcome.logs.len(), 1, "{}", name);
+                assert_eq!(
+                    outcome.logs[0].topics,
+                    words[data..data + topics],
+                    "{}",
+                    name
+                );
+                assert_eq!(outcome.logs[0].data, encoded(&words[..data]), "{}", name);
+                // the free memory pointer is left as it was
+                let first = if data == 0 { U256::ZERO } else { words[0] };
+                let free_ptr = U256::from_u64(free_ptr.unwrap_or(0));
+                assert_eq!(outcome.stack, [first, free_ptr], "{}", name);
+            }
+        }
+    }
+}
+
+#[test]
+fn merkle_proofs() {
+    let dir = generate("merkle");
+    let hash_pair = |a: U256, b: U256| {
+        let (a, b) = if a < b { (a, b) } else { (b, a) };
+        let body = format!(
+            "{} 0x00 mstore {} 0x20 mstore 0x40 0x00 sha3",
+            a.to_hex(),
+            b.to_hex()
+        );
+        execute(&dir, "libmerkle.huff", &body, &[]).1.stack[0]
+    };
+    let leaves = (1..=4)
+        .map(|i| U256::from_u64(i).shl(200))
+        .collect::<Vec<U256>>();
+    let (left, right) = (
+        hash_pair(leaves[0], leaves[1]),
+        hash_pair(leaves[2], leaves[3]),
+    );
+    let root = hash_pair(left, right);
+
+    // `verify(bytes32 root, bytes32 leaf, bytes32[] proof)`, with the proof from `0x64`
+    let calldata = |leaf: U256, proof: &[U256]| {
+        let head = [
+            root,
+            leaf,
+            U256::from_u64(0x60),
+            U256::from_u64(proof.len() as u64),
+        ];
+        let words = head.iter().chain(proof).flat_map(|word| word.to_be_bytes());
+        [0x12, 0x34, 0x56, 0x78]
+            .into_iter()
+            .chain(words)
+            .collect::<Vec<u8>>()
+    };
+    let verify = |leaf: U256, proof: &[U256]| {
+        let body = "0x04 calldataload 0x24 calldataload VERIFY_CALLDATA_PROOF(0x64) \
+            0x04 calldataload 0x24 calldataload \
+            0x64 calldataload 0x05 shl 0x20 add 0x64 0x80 calldatacopy 0x80 VERIFY_MEMORY_PROOF()";
+        let (_, outcome) = execute(&dir, "libmerkle.huff", body, &calldata(leaf, proof));
+        assert_eq!(outcome.halt, Halt::Stop);
+        outcome.stack
+    };
+    let (valid, invalid) = ([U256::ONE, U256::ONE], [U256::ZERO, U256::ZERO]);
+
+    assert_eq!(verify(leaves[0], &[leaves[1], right]), valid);
+    assert_eq!(verify(leaves[3], &[leaves[2], left]), valid);
+    assert_eq!(verify(left, &[right]), valid);
+    assert_eq!(verify(root, &[]), valid);
+    assert_eq!(verify(leaves[0], &[leaves[2], right]), invalid);
+    assert_eq!(verify(leaves[0], &[leaves[1]]), invalid);
+    assert_eq!(verify(leaves[0], &[leaves[1], right, root]), invalid);
+
+    let process = "0x24 calldataload PROCESS_CALLDATA_PROOF(0x64)";
+    let mut truncated = calldata(leaves[0], &[leaves[1], right]);
+    truncated.truncate(truncated.len() - 1);
+    let (_, outcome) = execute(&dir, "libmerkle.huff", process, &truncated);
+    assert_eq!(outcome.halt, Halt::Revert);
+    let mut long = calldata(leaves[0], &[leaves[1], right]);
+    long[0x64..0x84].copy_from_slice(&U256::MAX.to_be_bytes());
+    let (_, outcome) = execute(&dir, "libmerkle.huff", process, &long);
+    assert_eq!(outcome.halt, Halt::Revert);
+}
+
+#[test]
+fn guards() {
+    let dir = generate("guards");
+    let transient = generate("guards-transient");
+    let cancun = Generator::new()
+        .with_evm_version(EvmVersion::Cancun)
+        .render()
+        .unwrap();
+    fs::write(transient.join("libguard.huff"), &cancun["libguard.huff"]).unwrap();
+    let guard = |code: &str| run(&dir, "libguard.huff", code, &[]);
+    let n = U256::from_u64;
+
+    // the owner is stored in slot 0x00, and the caller is 0xca11
+    assert_eq!(guard("0xca11 0x00 sstore ONLY_OWNER(0x00)"), ok(&[]));
+    assert_eq!(
+        guard("0xbeef 0x00 sstore ONLY_OWNER(0x00)"),
+        revert("Unauthorized")
+    );
+    assert_eq!(guard("ONLY_OWNER(0x00)"), revert("Unauthorized"));
+
+    let enter = "NON_REENTRANT_ENTER(0x01)";
+    let exit = "NON_REENTRANT_EXIT(0x01)";
+    assert_eq!(guard(&format!("{} 0x01 sload", enter)), ok(&[n(2)]));
+    assert_eq!(
+        guard(&format!("{} {} 0x01 sload", enter, exit)),
+        ok(&[n(1)])
+    );
+    assert_eq!(
+        guard(&format!("{} {} {} {}", enter, exit, enter, exit)),
+        ok(&[])
+    );
+    assert_eq!(guard(&format!("{} {}", enter, enter)), revert("Reentrant"));
+    assert_eq!(
+        guard(&format!("0x02 0x01 sstore {}", enter)),
+        revert("Reentrant")
+    );
+
+    assert_eq!(guard("WHEN_NOT_PAUSED(0x02)"), ok(&[]));
+    assert_eq!(guard("WHEN_PAUSED(0x02)"), revert("ExpectedPause"));
+    assert_eq!(
+        guard("PAUSE(0x02) WHEN_PAUSED(0x02) 0x02 sload"),
+        ok(&[n(1)])
+    );
+    assert_eq!(
+        guard("PAUSE(0x02) WHEN_NOT_PAUSED(0x02)"),
+        revert("EnforcedPause")
+    );
+    assert_eq!(guard("PAUSE(0x02) PAUSE(0x02)"), revert("EnforcedPause"));
+    assert_eq!(guard("UNPAUSE(0x02)"), revert("ExpectedPause"));
+
+    let (_, outcome) = execute(
+        &dir,
+        "libguard.huff",
+        "PAUSE(0x02) UNPAUSE(0x02) 0x02 sload",
+        &[],
+    );
+    assert_eq!(
+        (outcome.halt, outcome.stack),
+        (Halt::Stop, vec![U256::ZERO])
+    );
+    for (log, event) in outcome.logs.iter().zip(["Paused", "Unpaused"]) {
+        let topic = guard(&format!("__EVENT_HASH({})", event)).unwrap();
+        assert_eq!(log.topics, topic, "{}", event);
+        assert_eq!(U256::from_be_bytes(&log.data), n(0xca11), "{}", event);
+    }
+    assert_eq!(outcome.logs.len(), 2);
+
+    // the transient guard is only generated from Cancun, and leaves storage untouched
+    let source = fs::read_to_string(dir.join("libguard.huff")).unwrap();
+    assert!(!source.contains("TRANSIENT_NON_REENTRANT_ENTER"));
+    let guard = |code: &str| run(&transient, "libguard.huff", code, &[]);
+    let enter = "TRANSIENT_NON_REENTRANT_ENTER(0x01)";
+    let exit = "TRANSIENT_NON_REENTRANT_EXIT(0x01)";
+    assert_eq!(
+        guard(&format!("{} 0x01 tload 0x01 sload", enter)),
+        ok(&[n(0), n(1)])
+    );
+    assert_eq!(
+        guard(&format!("{} {} 0x01 tload", enter, exit)),
+        ok(&[n(0)])
+    );
+    assert_eq!(
+        guard(&format!("{} {} {} {}", enter, exit, enter, exit)),
+        ok(&[])
+    );
+    assert_eq!(guard(&format!("{} {}", enter, enter)), revert("Reentrant"));
+}
+
+#[test]
+fn safe_transfers() {
+    let dir = generate("safetransfer");
+    let file = "libsafetransfer.huff";
+
+    // each token records its calldata in storage before reporting as its name says
+    let record = "0x00 calldataload 0xe0 shr 0x00 sstore 0x04 calldataload 0x01 sstore \
+        0x24 calldataload 0x02 sstore 0x44 calldataload 0x03 sstore calldatasize 0x04 sstore";
+    let tokens = [
+        ("true", "0x01 0x00 mstore 0x20 0x00 return", true),
+        ("nothing", "stop", true),
+        ("true and more", "0x01 0x00 mstore 0x40 0x00 return", true),
+        ("false", "0x00 0x00 mstore 0x20 0x00 return", false),
+        ("two", "0x02 0x00 mstore 0x20 0x00 return", false),
+        ("a byte", "0x01 0x00 mstore8 0x01 0x00 return", false),
+        ("a revert", "0x00 0x00 revert", false),
+    ];
+    // the stack arguments after the token, the error, and the calldata a token should record
+    let calls = [
+        (
+            "SAFE_TRANSFER()",
+            "0x64 0xbeef",
+            "TransferFailed",
+            0xa9059cbb,
+            [0xbeef, 0x64, 0],
+            0x44,
+        ),
+        (
+            "SAFE_TRANSFER_FROM()",
+            "0x64 0xbeef 0xf00d",
+            "TransferFromFailed",
+            0x23b872dd,
+            [0xf00d, 0xbeef, 0x64],
+            0x64,
+        ),
+        (
+            "SAFE_APPROVE()",
+            "0x64 0xbeef",
+            "ApproveFailed",
+            0x095ea7b3,
+            [0xbeef, 0x64, 0],
+            0x44,
+        ),
+    ];
+    let n = U256::from_u64;
+
+    for (invocation, arguments, error, selector, words, len) in calls {
+        for (i, (returns, tail, succeeds)) in tokens.iter().enumerate() {
+            let mut evm = Evm::new();
+            let token = addr(0x70c0 + i as u64);
+            evm.set_code(
+                token,
+                compile(&dir, file, &format!("{} {}", record, tail)).1,
+            );
+            let body = format!(
+                "0x1234 0x40 mstore 0x5678 0x60 mstore {} {:#x} {} 0x60 mload 0x40 mload",
+                arguments,
+                0x70c0 + i,
+                invocation,
+            );
+            let (program, code) = compile(&dir, file, &body);
+            let outcome = result(&program, &body, transact(&mut evm, code, &[]));
+
+            if !succeeds {
+                let message = format!("{} of a token returning {}", invocation, returns);
+                assert_eq!(outcome, revert(error), "{}", message);
+                continue;
+            }
+            // the free memory pointer is restored, and the zero slot cleared if it was used
+            let zero_slot = if len == 0x64 { 0 } else { 0x5678 };
+            assert_eq!(
+                outcome,
+                ok(&[n(0x1234), n(zero_slot)]),
+                "{} of {}",
+                invocation,
+                returns
+            );
+            let recorded = (0..5)
+                .map(|slot| evm.sload(token, n(slot)))
+                .collect::<Vec<U256>>();
+            let expected = [selector, words[0], words[1], words[2], len].map(n);
+            assert_eq!(
+                recorded, expected,
+                "{} of a token returning {}",
+                invocation, returns
+            );
+        }
+
+        // calls to accounts without code succeed with no data, but are rejected
+        let body = format!("{} 0x70ff {}", arguments, invocation);
+        assert_eq!(
+            run(&dir, file, &body, &[]),
+            revert(error),
+            "{} of no code",
+            invocation
+        );
+    }
+}
+
+#[test]
+fn constants() {
+    let dir = generate("constants");
+    let macros = generate("constants-macros");
+    let config = config::parse("[constants]\nmacros = true\n").unwrap();
+    let rendered = Generator::from_config(config).unwrap().render().unwrap();
+    fs::write(
+        macros.join("libconstants.huff"),
+        &rendered["libconstants.huff"],
+    )
+    .unwrap();
+    let file = "libconstants.huff";
+    let min = |bits: u16| U256::ZERO.wrapping_sub(U256::ONE.shl(bits as u32 - 1));
+
+    let addresses = ok(&[max(160), U256::ZERO]);
+    assert_eq!(
+        run(&dir, file, "[ZERO_ADDRESS] [MAX_ADDRESS]", &[]),
+        addresses
+    );
+    assert_eq!(
+        run(&macros, file, "ZERO_ADDRESS() MAX_ADDRESS()", &[]),
+        addresses
+    );
+    for size in (8..=256).step_by(8) {
+        let constants = format!("[MAX_U{0}] [MAX_I{0}] [MIN_I{0}]", size);
+        let pushes = format!("U{0}_MAX() I{0}_MAX() I{0}_MIN()", size);
+        let bounds = ok(&[min(size), max(size - 1), max(size)]);
+        assert_eq!(
+            run(&dir, file, &constants, &[]),
+            bounds,
+            "{} bit constants",
+            size
+        );
+        assert_eq!(
+            run(&macros, file, &pushes, &[]),
+            bounds,
+            "{} bit macros",
+            size
+        );
+    }
+
+    // the macros are named apart from the maximums of `libcmp.huff`
+    let source = fs::read_to_string(macros.join(file)).unwrap();
+    assert!(source.contains("#define macro U8_MAX()") && !source.contains("#define macro MAX_U8"));
+    let invalid = config::parse("[constants]\nmacros = \"yes\"\n").unwrap();
+    let error = Generator::from_config(invalid)
+        .unwrap()
+        .render()
+        .unwrap_err();
+    assert_eq!(error.to_string(), "`constants.macros` must be a boolean");
+}
+
+#[test]
+fn fixed_point() {
+    let dir = generate("fixedpoint");
+    let op =
+        |invocation: &str, a: U256, b: U256| run(&dir, "libfixedpoint.huff", invocation, &[a, b]);
+    let n = U256::from_u64;
+    let wad = n(1_000_000_000_000_000_000);
+    let wads = |x: u64| wad.wrapping_mul(n(x));
+    let third = wad.wrapping_mul(wad).div(n(3));
+
+    assert_eq!(op("MUL_WAD()", wads(3).div(n(2)), wads(2)), ok(&[wads(3)]));
+    assert_eq!(op("MUL_WAD()", U256::ONE, U256::ONE), ok(&[U256::ZERO]));
+    assert_eq!(op("MUL_WAD_UP()", U256::ONE, U256::ONE), ok(&[U256::ONE]));
+    assert_eq!(op("MUL_WAD_UP()", wad, n(7)), ok(&[n(7)]));
+    assert_eq!(op("MUL_WAD()", U256::MAX, U256::ZERO), ok(&[U256::ZERO]));
+    assert_eq!(op("MUL_WAD()", U256::MAX, n(2)), revert("Overflow"));
+
+    assert_eq!(op("DIV_WAD()", wad, n(3)), ok(&[third]));
+    assert_eq!(
+        op("DIV_WAD_UP()", wad, n(3)),
+        ok(&[third.wrapping_add(U256::ONE)])
+    );
+    assert_eq!(op("DIV_WAD_UP()", n(6), wads(3)), ok(&[n(2)]));
+    assert_eq!(op("DIV_WAD()", wad, U256::ZERO), revert("DivByZero"));
+    assert_eq!(op("DIV_WAD_UP()", U256::MAX, U256::ONE), revert("Overflow"));
+
+    // two decimals
+    assert_eq!(op("MUL_SCALED(0x64)", n(150), n(250)), ok(&[n(375)]));
+    assert_eq!(op("MUL_SCALED_UP(0x64)", n(155), n(101)), ok(&[n(157)]));
+    assert_eq!(op("DIV_SCALED(0x64)", n(375), n(250)), ok(&[n(150)]));
+    assert_eq!(op("DIV_SCALED_UP(0x64)", n(100), n(300)), ok(&[n(34)]));
+}
+
+#[test]
+fn units() {
+    let dir = generate("units");
+    let convert = |invocation: &str, amount| run(&dir, "libunits.huff", invocation, &[amount]);
+    let n = U256::from_u64;
+    let gwei = n(1_000_000_000);
+    let ether = gwei.wrapping_mul(gwei);
+
+    assert_eq!(convert("FROM_GWEI()", n(3)), ok(&[gwei.wrapping_mul(n(3))]));
+    assert_eq!(
+        convert("FROM_ETHER()", n(2)),
+        ok(&[ether.wrapping_mul(n(2))])
+    );
+    assert_eq!(
+        convert("TO_WEI_FROM_ETHER()", n(2)),
+        ok(&[ether.wrapping_mul(n(2))])
+    );
+    assert_eq!(convert("TO_WEI_FROM_GWEI()", U256::ONE), ok(&[gwei]));
+    let most = U256::MAX.div(ether);
+    assert_eq!(
+        convert("FROM_ETHER()", most),
+        ok(&[most.wrapping_mul(ether)])
+    );
+    assert_eq!(
+        convert("FROM_ETHER()", most.wrapping_add(U256::ONE)),
+        revert("Overflow")
+    );
+    assert_eq!(convert("TO_WEI_FROM_GWEI()", U256::MAX), revert("Overflow"));
+
+    // from wei, truncating or reverting on the remainder
+    let dust = ether.wrapping_add(n(1));
+    assert_eq!(convert("TO_GWEI()", dust), ok(&[gwei]));
+    assert_eq!(convert("TO_ETHER()", dust), ok(&[U256::ONE]));
+    assert_eq!(convert("TO_ETHER()", gwei), ok(&[U256::ZERO]));
+    assert_eq!(convert("TO_GWEI_EXACT()", ether), ok(&[gwei]));
+    assert_eq!(
+        convert("TO_ETHER_EXACT()", ether.wrapping_mul(n(5))),
+        ok(&[n(5)])
+    );
+    assert_eq!(
+        convert("TO_GWEI_EXACT()", dust),
+        revert("InexactConversion")
+    );
+    assert_eq!(
+        convert("TO_ETHER_EXACT()", gwei),
+        revert("InexactConversion")
+    );
+}
+
+#[test]
+fn create_addresses() {
+    let dir = generate("create");
+    let word = |hex: &str| U256::from_hex(hex).unwrap();
+    let compute = |deployer: U256, nonce: U256| {
+        run(
+            &dir,
+            "libcreate.huff",
+            "COMPUTE_CREATE_ADDRESS()",
+            &[deployer, nonce],
+        )
+    };
+
+    // the addresses ethers' `getContractAddress` documents for the first nonces of a deployer
+    let deployer = word("0x6ac7ea33f8831ea9dcc53393aaa88b25a785dbf0");
+    let created = [
+        "0xcd234a471b72ba2f1ccf0a70fcaba648a5eecd8d",
+        "0x343c43a37d37dff08ae8c4a11544c718abb4fcf8",
+        "0xf778b86fa74e846c4f0a1fbd1335fe81c00a0c91",
+        "0xfffd933a0bc612844eaf0c6fe3e5b8e9b6c1d19c",
+    ];
+    for (nonce, address) in created.iter().enumerate() {
+        let nonce = U256::from_u64(nonce as u64);
+        assert_eq!(
+            compute(deployer, nonce),
+            ok(&[word(address)]),
+            "{}",
+            address
+        );
+    }
+
+    // every length of the RLP encoded nonce, against the address `CREATE` deploys to on the EVM
+    let mut evm = Evm::new();
+    let factory = addr(0xfac7);
+    // push1 0x00 push1 0x00 push1 0x00 create stop
+    evm.set_code(
+        factory,
+        vec![0x60, 0x00, 0x60, 0x00, 0x60, 0x00, 0xf0, 0x00],
+    );
+    let nonces = [
+        1,
+        0x7f,
+        0x80,
+        0xff,
+        0x100,
+        0xffff,
+        0x1_0000,
+        1 << 32,
+        1 << 56,
+        u64::MAX - 1,
+    ];
+    for nonce in nonces {
+        evm.account(factory).nonce = nonce;
+        let outcome = common::call(&mut evm, factory, &[]);
+        assert_eq!(outcome.halt, Halt::Stop, "nonce {:#x}", nonce);
+        let deployer = evm::addr_to_u256(&factory);
+        assert_eq!(
+            compute(deployer, U256::from_u64(nonce)),
+            ok(&outcome.stack),
+            "nonce {:#x}",
+            nonce
+        );
+    }
+
+    // EIP-2681 bounds nonces to 64 bits, the last of which is never used by `CREATE`
+    let expected = evm::addr_to_u256(&evm::create_address(&factory, u64::MAX));
+    let factory = evm::addr_to_u256(&factory);
+    assert_eq!(compute(factory, U256::from_u64(u64::MAX)), ok(&[expected]));
+    assert_eq!(compute(deployer, U256::ONE.shl(64)), revert("InvalidNonce"));
+    assert_eq!(compute(deployer, U256::MAX), revert("InvalidNonce"));
+}
+
+#[test]
+fn rate_limits() {
+    let dir = generate("ratelimit");
+    let mut evm = Evm::new();
+    let now = evm.block.timestamp;
+    let n = U256::from_u64;
+    let consume = |evm: &mut Evm, amount: U256| {
+        let invocation = "RATE_LIMIT_CONSUME(0x01, 0x64, 0x3c)";
+        step(evm, &dir, "libratelimit.huff", invocation, &[amount])
+    };
+    let remaining = |evm: &mut Evm| {
+        let invocation = "RATE_LIMIT_REMAINING(0x01, 0x64, 0x3c)";
+        step(evm, &dir, "libratelimit.huff", invocation, &[])
+    };
+
+    // a limit of 100 per window of 60 seconds, the first consumption opening a window
+    assert_eq!(remaining(&mut evm), ok(&[n(100)]));
+    assert_eq!(consume(&mut evm, n(60)), ok(&[]));
+    assert_eq!(
+        evm.sload(addr(0xc0de), U256::ONE),
+        now.shl(192).wrapping_add(n(60))
+    );
+    assert_eq!(consume(&mut evm, n(41)), revert("RateLimited"));
+    assert_eq!(consume(&mut evm, U256::MAX), revert("RateLimited"));
+    assert_eq!(remaining(&mut evm), ok(&[n(40)]));
+    assert_eq!(consume(&mut evm, n(40)), ok(&[]));
+    assert_eq!(remaining(&mut evm), ok(&[U256::ZERO]));
+
+    // the window lasts until its start plus the window, exclusive
+    evm.block.timestamp = now.wrapping_add(n(59));
+    assert_eq!(consume(&mut evm, U256::ONE), revert("RateLimited"));
+    evm.block.timestamp = now.wrapping_add(n(60));
+    assert_eq!(remaining(&mut evm), ok(&[n(100)]));
+    assert_eq!(consume(&mut evm, n(100)), ok(&[]));
+    let start = evm.block.timestamp;
+    assert_eq!(
+        evm.sload(addr(0xc0de), U256::ONE),
+        start.shl(192).wrapping_add(n(100))
+    );
+}
+
+#[test]
+fn counters_and_flags() {
+    let dir = generate("counter");
+    let mut evm = Evm::new();
+    let mut counter = |invocation: &str| step(&mut evm, &dir, "libcounter.huff", invocation, &[]);
+    let n = U256::from_u64;
+
+    assert_eq!(counter("COUNTER_CURRENT(0x07)"), ok(&[U256::ZERO]));
+    assert_eq!(
+        counter("COUNTER_DECREMENT_CHECKED(0x07)"),
+        revert("CounterUnderflow")
+    );
+    for _ in 0..3 {
+        assert_eq!(counter("COUNTER_INCREMENT(0x07)"), ok(&[]));
+    }
+    assert_eq!(counter("COUNTER_DECREMENT_CHECKED(0x07)"), ok(&[]));
+    assert_eq!(counter("COUNTER_CURRENT(0x07)"), ok(&[n(2)]));
+    assert_eq!(counter("COUNTER_CURRENT(0x08)"), ok(&[U256::ZERO]));
+    assert_eq!(counter("COUNTER_RESET(0x07)"), ok(&[]));
+    assert_eq!(counter("COUNTER_CURRENT(0x07)"), ok(&[U256::ZERO]));
+
+    let flag = |invocation: &str, word: U256| run(&dir, "libflags.huff", invocation, &[word]);
+    let word = n(0b1010);
+    assert_eq!(flag("FLAG_GET(0x01)", word), ok(&[U256::ONE]));
+    assert_eq!(flag("FLAG_GET(0x02)", word), ok(&[U256::ZERO]));
+    assert_eq!(flag("FLAG_GET(0xff)", U256::ONE.shl(255)), ok(&[U256::ONE]));
+    assert_eq!(flag("FLAG_SET(0x00)", word), ok(&[n(0b1011)]));
+    assert_eq!(flag("FLAG_SET(0x01)", word), ok(&[word]));
+    assert_eq!(
+        flag("FLAG_SET(0xff)", U256::ZERO),
+        ok(&[U256::ONE.shl(255)])
+    );
+    assert_eq!(flag("FLAG_CLEAR(0x03)", word), ok(&[n(0b0010)]));
+    assert_eq!(flag("FLAG_CLEAR(0x00)", word), ok(&[word]));
+    assert_eq!(flag("FLAGS_ANY(0x05)", word), ok(&[U256::ZERO]));
+    assert_eq!(flag("FLAGS_ANY(0x0c)", word), ok(&[U256::ONE]));
+    assert_eq!(flag("FLAGS_ALL(0x0a)", word), ok(&[U256::ONE]));
+    assert_eq!(flag("FLAGS_ALL(0x0b)", word), ok(&[U256::ZERO]));
+}
+
+#[test]
+fn deadlines() {
+    let dir = generate("time");
+    let now = Evm::new().block.timestamp;
+    let check = |invocation: &str, stack: &[U256]| run(&dir, "libtime.huff", invocation, stack);
+    let before = now.wrapping_sub(U256::ONE);
+    let after = now.wrapping_add(U256::ONE);
+
+    assert_eq!(check("REQUIRE_NOT_EXPIRED()", &[now]), ok(&[]));
+    assert_eq!(check("REQUIRE_NOT_EXPIRED()", &[before]), revert("Expired"));
+    assert_eq!(check("REQUIRE_NOT_EXPIRED_EXCLUSIVE()", &[after]), ok(&[]));
+    assert_eq!(
+        check("REQUIRE_NOT_EXPIRED_EXCLUSIVE()", &[now]),
+        revert("Expired")
+    );
+    assert_eq!(check("REQUIRE_AFTER()", &[before]), ok(&[]));
+    assert_eq!(check("REQUIRE_AFTER()", &[now]), revert("TooEarly"));
+    assert_eq!(check("REQUIRE_AFTER_INCLUSIVE()", &[now]), ok(&[]));
+    assert_eq!(
+        check("REQUIRE_AFTER_INCLUSIVE()", &[after]),
+        revert("TooEarly")
+    );
+
+    // windows of `[start, end]`, half open unless inclusive
+    assert_eq!(check("IN_WINDOW()", &[now, after]), ok(&[U256::ONE]));
+    assert_eq!(check("IN_WINDOW()", &[before, now]), ok(&[U256::ZERO]));
+    assert_eq!(check("IN_WINDOW()", &[after, after]), ok(&[U256::ZERO]));
+    assert_eq!(
+        check("IN_WINDOW_INCLUSIVE()", &[before, now]),
+        ok(&[U256::ONE])
+    );
+    assert_eq!(
+        check("IN_WINDOW_INCLUSIVE()", &[now, now]),
+        ok(&[U256::ONE])
+    );
+    assert_eq!(
+        check("IN_WINDOW_INCLUSIVE()", &[after, after]),
+        ok(&[U256::ZERO])
+    );
+}
+
+#[test]
+fn timelocks() {
+    let dir = generate("timelock");
+    let mut evm = Evm::new();
+    let now = evm.block.timestamp;
+    let n = U256::from_u64;
+    let timelock = |evm: &mut Evm, invocation: &str, stack: &[U256]| {
+        step(evm, &dir, "libtimelock.huff", invocation, stack)
+    };
+    let (id, other) = (n(0x1d), n(0x2d));
+    let (min, max) = (n(0x2a300), n(0x278d00));
+    let eta = now.wrapping_add(min);
+
+    assert_eq!(
+        timelock(&mut evm, "OPERATION_ETA(0x05)", &[id]),
+        ok(&[U256::ZERO])
+    );
+    let early = eta.wrapping_sub(U256::ONE);
+    assert_eq!(
+        timelock(&mut evm, "QUEUE_OPERATION(0x05)", &[id, early]),
+        revert("InvalidDelay")
+    );
+    let late = now.wrapping_add(max).wrapping_add(U256::ONE);
+    assert_eq!(
+        timelock(&mut evm, "QUEUE_OPERATION(0x05)", &[id, late]),
+        revert("InvalidDelay")
+    );
+    assert_eq!(
+        timelock(&mut evm, "QUEUE_OPERATION(0x05)", &[id, eta]),
+        ok(&[])
+    );
+    assert_eq!(
+        timelock(&mut evm, "QUEUE_OPERATION(0x05)", &[id, eta]),
+        revert("OperationAlreadyQueued")
+    );
+    assert_eq!(timelock(&mut evm, "OPERATION_ETA(0x05)", &[id]), ok(&[eta]));
+    assert_eq!(
+        timelock(&mut evm, "OPERATION_ETA(0x06)", &[id]),
+        ok(&[U256::ZERO])
+    );
+
+    // ready from its eta on
+    assert_eq!(
+        timelock(&mut evm, "REQUIRE_READY(0x05)", &[id]),
+        revert("OperationNotReady")
+    );
+    assert_eq!(
+        timelock(&mut evm, "REQUIRE_READY(0x05)", &[other]),
+        revert("OperationNotQueued")
+    );
+    evm.block.timestamp = eta.wrapping_sub(U256::ONE);
+    assert_eq!(
+        timelock(&mut evm, "REQUIRE_READY(0x05)", &[id]),
+        revert("OperationNotReady")
+    );
+    evm.block.timestamp = eta;
+    assert_eq!(timelock(&mut evm, "REQUIRE_READY(0x05)", &[id]), ok(&[]));
+
+    assert_eq!(timelock(&mut evm, "CANCEL_OPERATION(0x05)", &[id]), ok(&[]));
+    assert_eq!(
+        timelock(&mut evm, "OPERATION_ETA(0x05)", &[id]),
+        ok(&[U256::ZERO])
+    );
+    assert_eq!(
+        timelock(&mut evm, "REQUIRE_READY(0x05)", &[id]),
+        revert("OperationNotQueued")
+    );
+    assert_eq!(
+        timelock(&mut evm, "CANCEL_OPERATION(0x05)", &[id]),
+        revert("OperationNotQueued")
+    );
+}
+
+#[test]
+fn bloom_filters() {
+    let dir = generate("bloom");
+    let mut evm = Evm::new();
+    let bloom = |evm: &mut Evm, invocation: &str, item: U256| {
+        step(evm, &dir, "libbloom.huff", invocation, &[item])
+    };
+    let items = (1..=16)
+        .map(|i| U256::from_u64(i * 0x9e37_79b9))
+        .collect::<Vec<U256>>();
+    let bits = |evm: &Evm| {
+        (0..5)
+            .map(|word| evm.sload(addr(0xc0de), U256::from_u64(0x10 + word)))
+            .collect::<Vec<U256>>()
+    };
+
+    for item in &items {
+        assert_eq!(
+            bloom(&mut evm, "BLOOM_MAYBE_CONTAINS(0x10)", *item),
+            ok(&[U256::ZERO])
+        );
+    }
+    for item in &items {
+        assert_eq!(bloom(&mut evm, "BLOOM_ADD(0x10)", *item), ok(&[]));
+    }
+    // no false negatives, and the filter stays within its 4 words
+    for item in &items {
+        assert_eq!(
+            bloom(&mut evm, "BLOOM_MAYBE_CONTAINS(0x10)", *item),
+            ok(&[U256::ONE])
+        );
+    }
+    let filter = bits(&evm);
+    assert_eq!(filter[4], U256::ZERO);
+    let set = filter
+        .iter()
+        .map(|word| (0..256).filter(|&bit| word.bit(bit)).count());
+    let set = set.sum::<usize>();
+    assert!(0 < set && set <= 3 * items.len(), "{} bits set", set);
+
+    // adding an item again sets no new bits
+    assert_eq!(bloom(&mut evm, "BLOOM_ADD(0x10)", items[0]), ok(&[]));
+    assert_eq!(bits(&evm), filter);
+}
+
+#[test]
+fn collections() {
+    let dir = generate("collections");
+    let collect = |body: &str| run(&dir, "libcollections.huff", body, &[]);
+    let n = U256::from_u64;
+    let push = |collection: &str, values: &[u64]| {
+        let pushes = values
+            .iter()
+            .map(|value| format!("{:#04x} M{}_PUSH(0x80, 0x02)", value, collection));
+        pushes.collect::<Vec<String>>().join(" ")
+    };
+
+    // a stack of at most two elements, popped last in first out
+    let stack = format!(
+        "MSTACK_INIT(0x80) {} MSTACK_POP(0x80) MSTACK_PEEK(0x80) MSTACK_LENGTH(0x80)",
+        push("STACK", &[1, 2])
+    );
+    assert_eq!(collect(&stack), ok(&[n(1), n(1), n(2)]));
+    assert_eq!(
+        collect(&push("STACK", &[1, 2, 3])),
+        revert("CollectionFull")
+    );
+    assert_eq!(collect("MSTACK_POP(0x80)"), revert("CollectionEmpty"));
+    assert_eq!(collect("MSTACK_PEEK(0x80)"), revert("CollectionEmpty"));
+
+    // a queue of at most two elements, popped first in first out around its ring buffer
+    let queue = format!(
+        "{} MQUEUE_POP(0x80, 0x02) {} MQUEUE_POP(0x80, 0x02) MQUEUE_PEEK(0x80) \
+         MQUEUE_POP(0x80, 0x02) MQUEUE_LENGTH(0x80)",
+        push("QUEUE", &[1, 2]),
+        push("QUEUE", &[3]),
+    );
+    assert_eq!(collect(&queue), ok(&[U256::ZERO, n(3), n(3), n(2), n(1)]));
+    assert_eq!(
+        collect(&push("QUEUE", &[1, 2, 3])),
+        revert("CollectionFull")
+    );
+    assert_eq!(collect("MQUEUE_POP(0x80, 0x02)"), revert("CollectionEmpty"));
+    assert_eq!(collect("MQUEUE_PEEK(0x80)"), revert("CollectionEmpty"));
+}
+
+#[test]
+fn decays() {
+    let dir = generate("decay");
+    let now = Evm::new().block.timestamp;
+    let n = U256::from_u64;
+    let ago = |seconds| now.wrapping_sub(n(seconds));
+    let linear = |start_price, end_price, start_time, duration| {
+        let stack = [start_price, end_price, start_time, duration];
+        run(&dir, "libdecay.huff", "LINEAR_DECAY()", &stack)
+    };
+    let exp = |start_price, start_time, factor| {
+        run(
+            &dir,
+            "libdecay.huff",
+            "EXP_DECAY()",
+            &[start_price, start_time, factor],
+        )
+    };
+
+    // the decrease rounds down, so a third of the way from 1000 to 0 is 667
+    assert_eq!(linear(n(1000), n(100), ago(25), n(100)), ok(&[n(775)]));
+    assert_eq!(linear(n(1000), n(0), ago(1), n(3)), ok(&[n(667)]));
+    assert_eq!(
+        linear(n(1000), n(100), now.wrapping_add(n(10)), n(100)),
+        ok(&[n(1000)])
+    );
+    assert_eq!(linear(n(1000), n(100), now, n(100)), ok(&[n(1000)]));
+    assert_eq!(linear(n(1000), n(100), ago(100), n(100)), ok(&[n(100)]));
+    assert_eq!(linear(n(1000), n(100), ago(200), n(100)), ok(&[n(100)]));
+    assert_eq!(
+        linear(U256::MAX, n(0), ago(1), n(2)),
+        ok(&[U256::ONE.shl(255)])
+    );
+    assert_eq!(
+        linear(n(100), n(1000), ago(1), n(2)),
+        revert("InvalidDecay")
+    );
+    assert_eq!(
+        linear(n(1000), n(100), ago(1), U256::ONE.shl(128)),
+        revert("InvalidDecay")
+    );
+
+    let wad = n(1_000_000_000_000_000_000);
+    let half = n(500_000_000_000_000_000);
+    assert_eq!(exp(n(800), ago(3), half), ok(&[n(100)]));
+    assert_eq!(exp(n(800), now, half), ok(&[n(800)]));
+    assert_eq!(exp(n(800), ago(1000), wad), ok(&[n(800)]));
+    assert_eq!(
+        exp(n(800), ago(1), wad.wrapping_add(U256::ONE)),
+        revert("InvalidDecay")
+    );
+}
+
+#[test]
+fn royalties() {
+    let dir = generate("royalty");
+    let mut evm = Evm::new();
+    let royalty = |evm: &mut Evm, invocation: &str, stack: &[U256]| {
+        step(evm, &dir, "libroyalty.huff", invocation, stack)
+    };
+    let n = U256::from_u64;
+    let (receiver, token_receiver) = (n(0xbeef), n(0xf00d));
+    let info = "ROYALTY_INFO(0x01, 0x02)";
+
+    assert_eq!(
+        royalty(&mut evm, "ROYALTY_AMOUNT()", &[n(10_000), n(250)]),
+        ok(&[n(250)])
+    );
+    assert_eq!(
+        royalty(&mut evm, "ROYALTY_AMOUNT()", &[n(9_999), n(1)]),
+        ok(&[U256::ZERO])
+    );
+    assert_eq!(
+        royalty(&mut evm, "ROYALTY_AMOUNT()", &[U256::MAX, n(10_000)]),
+        ok(&[U256::MAX])
+    );
+    let quarter = U256::MAX.div(n(4));
+    assert_eq!(
+        royalty(&mut evm, "ROYALTY_AMOUNT()", &[U256::MAX, n(2_500)]),
+        ok(&[quarter])
+    );
+
+    // the default applies to every token without a royalty of its own
+    assert_eq!(
+        royalty(&mut evm, info, &[n(7), n(10_000)]),
+        ok(&[U256::ZERO, U256::ZERO])
+    );
+    let default = "SET_DEFAULT_ROYALTY(0x01)";
+    assert_eq!(royalty(&mut evm, default, &[receiver, n(500)]), ok(&[]));
+    assert_eq!(
+        royalty(&mut evm, info, &[n(7), n(10_000)]),
+        ok(&[receiver, n(500)])
+    );
+    let token = "SET_TOKEN_ROYALTY(0x02)";
+    assert_eq!(
+        royalty(&mut evm, token, &[n(7), token_receiver, n(1_000)]),
+        ok(&[])
+    );
+    assert_eq!(
+        royalty(&mut evm, info, &[n(7), n(10_000)]),
+        ok(&[token_receiver, n(1_000)])
+    );
+    assert_eq!(
+        royalty(&mut evm, info, &[n(8), n(10_000)]),
+        ok(&[receiver, n(500)])
+    );
+    assert_eq!(
+        royalty(&mut evm, "RESET_TOKEN_ROYALTY(0x02)", &[n(7)]),
+        ok(&[])
+    );
+    assert_eq!(
+        royalty(&mut evm, info, &[n(7), n(10_000)]),
+        ok(&[receiver, n(500)])
+    );
+    assert_eq!(
+        royalty(&mut evm, "DELETE_DEFAULT_ROYALTY(0x01)", &[]),
+        ok(&[])
+    );
+    assert_eq!(
+        royalty(&mut evm, info, &[n(7), n(10_000)]),
+        ok(&[U256::ZERO, U256::ZERO])
+    );
+
+    assert_eq!(
+        royalty(&mut evm, default, &[receiver, n(10_001)]),
+        revert("InvalidRoyalty")
+    );
+    assert_eq!(
+        royalty(&mut evm, default, &[U256::ZERO, n(500)]),
+        revert("InvalidRoyaltyReceiver")
+    );
+    assert_eq!(
+        royalty(&mut evm, token, &[n(7), token_receiver, n(10_001)]),
+        revert("InvalidRoyalty")
+    );
+}
+
+#[test]
+fn parsing_and_decimals() {
+    let dir = generate("parse");
+    // writes `text` to memory from `0x00` before parsing it
+    let parse = |invocation: &str, text: &str| {
+        let mut bytes = text.as_bytes().to_vec();
+        bytes.resize(bytes.len().div_ceil(32) * 32, 0);
+        let stores = bytes
+            .chunks(32)
+            .enumerate()
+            .map(|(i, word)| format!("0x{} {:#04x} mstore", hex(word), i * 32))
+            .collect::<Vec<String>>();
+        let invocation = format!("{} {}", stores.join(" "), invocation);
+        let len = U256::from_u64(text.len() as u64);
+        run(&dir, "libparse.huff", &invocation, &[U256::ZERO, len])
+    };
+    let n = U256::from_u64;
+    let max = "115792089237316195423570985008687907853269984665640564039457584007913129639935";
+
+    assert_eq!(parse("PARSE_UINT()", "42"), ok(&[n(42)]));
+    assert_eq!(parse("PARSE_UINT()", "0012"), ok(&[n(12)]));
+    assert_eq!(parse("PARSE_UINT()", "0"), ok(&[U256::ZERO]));
+    assert_eq!(parse("PARSE_UINT()", max), ok(&[U256::MAX]));
+    let over = format!("{}6", &max[..max.len() - 1]);
+    assert_eq!(parse("PARSE_UINT()", &over), revert("Overflow"));
+    assert_eq!(
+        parse("PARSE_UINT()", &format!("{}0", max)),
+        revert("Overflow")
+    );
+    for invalid in ["", "-1", "+1", " 1", "1a", "1.0"] {
+        assert_eq!(
+            parse("PARSE_UINT()", invalid),
+            revert("InvalidNumber"),
+            "{:?}",
+            invalid
+        );
+    }
+
+    assert_eq!(parse("PARSE_HEX_UINT()", "ff"), ok(&[n(0xff)]));
+    assert_eq!(parse("PARSE_HEX_UINT()", "DeadBeef"), ok(&[n(0xdead_beef)]));
+    assert_eq!(parse("PARSE_HEX_UINT()", &"f".repeat(64)), ok(&[U256::MAX]));
+    assert_eq!(
+        parse("PARSE_HEX_UINT()", &format!("1{}", "0".repeat(64))),
+        revert("Overflow")
+    );
+    for invalid in ["", "0x1", "g", "12 "] {
+        assert_eq!(
+            parse("PARSE_HEX_UINT()", invalid),
+            revert("InvalidNumber"),
+            "{:?}",
+            invalid
+        );
+    }
+
+    let decimal = |invocation: &str, value| run(&dir, "libdecimal.huff", invocation, &[value]);
+    let mut power = U256::ONE;
+    for exponent in 0..=77 {
+        assert_eq!(
+            decimal("POW10()", n(exponent)),
+            ok(&[power]),
+            "10 ** {}",
+            exponent
+        );
+        power = power.wrapping_mul(n(10));
+    }
+    assert_eq!(decimal("POW10()", n(78)), revert("Overflow"));
+    assert_eq!(decimal("POW10()", U256::MAX), revert("Overflow"));
+
+    let wad = n(1_000_000_000_000_000_000);
+    assert_eq!(decimal("SCALE_UP(0x06, 0x12)", n(1_000_000)), ok(&[wad]));
+    assert_eq!(
+        decimal("SCALE_UP(0x06, 0x06)", n(1_000_000)),
+        ok(&[n(1_000_000)])
+    );
+    assert_eq!(decimal("SCALE_UP(0x00, 0x4d)", n(10)), revert("Overflow"));
+    assert_eq!(
+        decimal("SCALE_UP(0x12, 0x06)", n(1)),
+        revert("InvalidDecimals")
+    );
+    let dust = n(1_999_999_999_999_999_999);
+    assert_eq!(decimal("SCALE_DOWN(0x12, 0x06)", dust), ok(&[n(1_999_999)]));
+    assert_eq!(
+        decimal("SCALE_DOWN(0x4e, 0x00)", U256::MAX),
+        revert("Overflow")
+    );
+    assert_eq!(
+        decimal("SCALE_DOWN(0x06, 0x12)", n(1)),
+        revert("InvalidDecimals")
+    );
+}
+
+#[test]
+fn chains_and_auth() {
+    let dir = generate("auth");
+    let mut evm = Evm::new();
+    let chain = |evm: &mut Evm, invocation: &str| step(evm, &dir, "libchain.huff", invocation, &[]);
+
+    assert_eq!(chain(&mut evm, "CHAIN_ID()"), ok(&[U256::ONE]));
+    assert_eq!(chain(&mut evm, "IS_CHAIN(0x01)"), ok(&[U256::ONE]));
+    assert_eq!(chain(&mut evm, "IS_CHAIN(0x05)"), ok(&[U256::ZERO]));
+    assert_eq!(chain(&mut evm, "REQUIRE_CHAIN(0x01)"), ok(&[]));
+    assert_eq!(
+        chain(&mut evm, "REQUIRE_CHAIN(0x05)"),
+        revert("InvalidChain")
+    );
+    assert_eq!(chain(&mut evm, "IS_DOMAIN_STALE(0x01)"), ok(&[U256::ZERO]));
+    evm.block.chain_id = U256::from_u64(5);
+    assert_eq!(chain(&mut evm, "CHAIN_ID()"), ok(&[U256::from_u64(5)]));
+    assert_eq!(chain(&mut evm, "REQUIRE_CHAIN(0x05)"), ok(&[]));
+    assert_eq!(chain(&mut evm, "IS_DOMAIN_STALE(0x01)"), ok(&[U256::ONE]));
+
+    let mut evm = Evm::new();
+    let auth = |evm: &mut Evm, invocation: &str, stack: &[U256]| {
+        step(evm, &dir, "libauth.huff", invocation, stack)
+    };
+    let caller = evm::addr_to_u256(&addr(common::CALLER));
+    let (other, authority) = (U256::from_u64(0xdead), addr(0xa11));
+    let is_authorized = "IS_AUTHORIZED(0x00, 0x01, 0x80)";
+    let transfer = "TRANSFER_OWNERSHIP(0x00, 0x01, 0x80)";
+
+    assert_eq!(
+        auth(
+            &mut evm,
+            "AUTH_CONSTRUCTOR(0x00, 0x01)",
+            &[caller, U256::ZERO]
+        ),
+        ok(&[])
+    );
+    assert_eq!(auth(&mut evm, "OWNER(0x00)", &[]), ok(&[caller]));
+    assert_eq!(auth(&mut evm, "AUTHORITY(0x01)", &[]), ok(&[U256::ZERO]));
+    assert_eq!(
+        auth(&mut evm, is_authorized, &[caller, U256::ZERO]),
+        ok(&[U256::ONE])
+    );
+    assert_eq!(
+        auth(&mut evm, is_authorized, &[other, U256::ZERO]),
+        ok(&[U256::ZERO])
+    );
+    assert_eq!(
+        auth(&mut evm, "AUTH_REQUIRES(0x00, 0x01, 0x80)", &[]),
+        ok(&[])
+    );
+
+    // an authority answering `canCall` with true, with false, and by reverting
+    // push1 0x01 push1 0x00 mstore push1 0x20 push1 0x00 return
+    let allow = vec![0x60, 0x01, 0x60, 0x00, 0x52, 0x60, 0x20, 0x60, 0x00, 0xf3];
+    evm.set_code(authority, allow);
+    let set_authority = "SET_AUTHORITY(0x00, 0x01, 0x80)";
+    let authority = evm::addr_to_u256(&authority);
+    assert_eq!(auth(&mut evm, set_authority, &[authority]), ok(&[]));
+    assert_eq!(auth(&mut evm, transfer, &[other]), ok(&[]));
+    assert_eq!(auth(&mut evm, "OWNER(0x00)", &[]), ok(&[other]));
+    assert_eq!(
+        auth(&mut evm, is_authorized, &[caller, U256::ZERO]),
+        ok(&[U256::ONE])
+    );
+    assert_eq!(auth(&mut evm, transfer, &[other]), ok(&[]));
+
+    // push1 0x00 push1 0x00 mstore push1 0x20 push1 0x00 return
+    let deny = vec![0x60, 0x00, 0x60, 0x00, 0x52, 0x60, 0x20, 0x60, 0x00, 0xf3];
+    evm.set_code(addr(0xa11), deny);
+    assert_eq!(
+        auth(&mut evm, is_authorized, &[caller, U256::ZERO]),
+        ok(&[U256::ZERO])
+    );
+    assert_eq!(auth(&mut evm, transfer, &[caller]), revert("Unauthorized"));
+    // push1 0x00 push1 0x00 revert
+    evm.set_code(addr(0xa11), vec![0x60, 0x00, 0x60, 0x00, 0xfd]);
+    assert_eq!(
+        auth(&mut evm, is_authorized, &[caller, U256::ZERO]),
+        ok(&[U256::ZERO])
+    );
+    assert_eq!(
+        auth(&mut evm, "AUTH_REQUIRES(0x00, 0x01, 0x80)", &[]),
+        revert("Unauthorized")
+    );
+    assert_eq!(
+        auth(&mut evm, is_authorized, &[other, U256::ZERO]),
+        ok(&[U256::ONE])
+    );
+}
+
+#[test]
+fn signatures() {
+    let dir = generate("signature");
+    let file = "libsignature.huff";
+    let n = U256::from_u64;
+
+    // an ecrecover recovering `0xbeef` from signatures with a `v` of 27, and nothing otherwise
+    fn ecrecover(input: &[u8]) -> (bool, Vec<u8>) {
+        match input.get(63) {
+            Some(27) => (true, U256::from_u64(0xbeef).to_be_bytes().to_vec()),
+            _ => (true, Vec::new()),
+        }
+    }
+    let mut evm = Evm::new();
+    evm.precompiles.insert(addr(1), ecrecover);
+
+    let half_order = "0x7fffffffffffffffffffffffffffffff5d576e7357a4501ddfe92f46681b20a0";
+    // the signature `r ++ s ++ v` at `0x100`
+    let signature = |s: &str, v: u8| {
+        format!(
+            "0x01 0x100 mstore {} 0x120 mstore {:#x} 0x140 mstore8",
+            s, v
+        )
+    };
+    let low = signature(half_order, 27);
+    let high = signature(&format!("{} 0x01 add", half_order), 27);
+    let mut recover = |signature: &str, len: u64| {
+        let body = format!("{} {:#x} 0x100 0xabcd ECRECOVER(0x00)", signature, len);
+        step(&mut evm, &dir, file, &body, &[])
+    };
+    assert_eq!(recover(&low, 0x41), ok(&[n(0xbeef)]));
+    // the malleable twin of a signature, with `s` in the upper half of the order, is rejected
+    assert_eq!(recover(&high, 0x41), ok(&[U256::ZERO]));
+    assert_eq!(recover(&low, 0x40), ok(&[U256::ZERO]));
+    assert_eq!(recover(&signature(half_order, 28), 0x41), ok(&[U256::ZERO]));
+
+    // a contract signer answering with the magic value, or failing in each way a call can
+    let magic = "0x1626ba7e 0xe0 shl 0x00 mstore";
+    let signers = [
+        (
+            "the magic value",
+            format!("{} 0x20 0x00 return", magic),
+            true,
+        ),
+        (
+            "another value",
+            "0x01 0x00 mstore 0x20 0x00 return".to_string(),
+            false,
+        ),
+        (
+            "a short magic value",
+            format!("{} 0x04 0x00 return", magic),
+            false,
+        ),
+        ("nothing", "stop".to_string(), false),
+        ("a revert", format!("{} 0x20 0x00 revert", magic), false),
+    ];
+    for (i, (returns, body, valid)) in signers.iter().enumerate() {
+        let signer = 0x5160 + i as u64;
+        evm.set_code(addr(signer), compile(&dir, file, body).1);
+        for invocation in ["IS_VALID_ERC1271_SIGNATURE", "IS_VALID_SIGNATURE_CALL"] {
+            let body = format!(
+                "{} 0x41 0x100 0xabcd {:#x} {}(0x200)",
+                low, signer, invocation
+            );
+            let outcome = step(&mut evm, &dir, file, &body, &[]);
+            assert_eq!(
+                outcome,
+                ok(&[n(*valid as u64)]),
+                "{} of a signer returning {}",
+                invocation,
+                returns
+            );
+        }
+    }
+
+    // accounts without code are checked by recovery, rejecting the malleable signature
+    let call = |evm: &mut Evm, signature: &str, signer: u64| {
+        let body = format!(
+            "{} 0x41 0x100 0xabcd {:#x} IS_VALID_SIGNATURE_CALL(0x200)",
+            signature, signer
+        );
+        step(evm, &dir, file, &body, &[])
+    };
+    assert_eq!(call(&mut evm, &low, 0xbeef), ok(&[U256::ONE]));
+    assert_eq!(call(&mut evm, &low, 0xbeee), ok(&[U256::ZERO]));
+    assert_eq!(call(&mut evm, &high, 0xbeef), ok(&[U256::ZERO]));
+    // nor is a signature recovering nothing valid for the zero address
+    assert_eq!(
+        call(&mut evm, &signature(half_order, 28), 0x00),
+        ok(&[U256::ZERO])
+    );
+}
+
+#[test]
+fn accounts() {
+    let dir = generate("account");
+    let file = "libaccount.huff";
+    let n = U256::from_u64;
+    let word = |value: u64| n(value).to_be_bytes().to_vec();
+    let nonce = n(5).shl(64) | n(7);
+
+    // `validateUserOp(userOp, userOpHash, missingAccountFunds)` of a user operation with a nonce of
+    // key 5 and sequence 7, empty dynamic fields but for the signature `r ++ s ++ v`
+    let half_order = "7fffffffffffffffffffffffffffffff5d576e7357a4501ddfe92f46681b20a0";
+    let high_order = "7fffffffffffffffffffffffffffffff5d576e7357a4501ddfe92f46681b20a1";
+    let validate_user_op = |s: &str, missing: u64| {
+        let mut data = 0x19822f7c_u32.to_be_bytes().to_vec();
+        data.extend([word(0x60), word(0x4a54), word(missing)].concat());
+        let heads = [0xbeef, 0, 0x120, 0x140, 1, 2, 3, 0x160, 0x180].map(n);
+        let heads = [&heads[..1], &[nonce], &heads[2..]].concat();
+        data.extend(heads.iter().flat_map(|head| head.to_be_bytes()));
+        data.extend([word(0), word(0), word(0), word(0x41), word(1)].concat());
+        data.extend(U256::from_hex(s).unwrap().to_be_bytes());
+        data.extend([vec![27], vec![0; 31]].concat());
+        data
+    };
+    fn ecrecover(_: &[u8]) -> (bool, Vec<u8>) {
+        (true, U256::from_u64(0xbeef).to_be_bytes().to_vec())
+    }
+    let mut evm = Evm::new();
+    evm.precompiles.insert(addr(1), ecrecover);
+    let call = |evm: &mut Evm, body: &str, data: &[u8]| {
+        let (program, code) = compile(&dir, file, body);
+        let outcome = transact(evm, code, data);
+        result(&program, body, outcome)
+    };
+
+    let data = validate_user_op(half_order, 0);
+    let user_op = "0x04 calldataload 0x04 add";
+    let load = format!("{} USER_OP_NONCE()", user_op);
+    assert_eq!(call(&mut evm, &load, &data), ok(&[nonce]));
+    let key = format!("{} NONCE_KEY() {} NONCE_SEQUENCE()", load, load);
+    assert_eq!(call(&mut evm, &key, &data), ok(&[n(7), n(5)]));
+    let signature = format!("{} USER_OP_SIGNATURE()", user_op);
+    assert_eq!(
+        call(&mut evm, &signature, &data),
+        ok(&[n(0x64 + 0x1a0), n(0x41)])
+    );
+
+    // a user operation is hashed as the v0.7 entry point hashes it, committing to its fields, the
+    // entry point and the chain: the fields are packed with the hash of each empty dynamic field
+    let empty = "0xc5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470";
+    let packed = format!(
+        "0xbeef 0x00 mstore {} 0x20 mstore {} 0x40 mstore {} 0x60 mstore 0x01 0x80 mstore \
+        0x02 0xa0 mstore 0x03 0xc0 mstore {} 0xe0 mstore 0x100 0x00 sha3 0x00 mstore \
+        0xe0e0 0x20 mstore chainid 0x40 mstore 0x60 0x00 sha3",
+        nonce.to_hex(),
+        empty,
+        empty,
+        empty,
+    );
+    let expected = call(&mut evm, &packed, &data);
+    let hash = format!("{} USER_OP_HASH(0xe0e0, 0x00)", user_op);
+    assert_eq!(call(&mut evm, &hash, &data), expected);
+    let other = format!("{} USER_OP_HASH(0xe0e1, 0x00)", user_op);
+    assert_ne!(call(&mut evm, &other, &data), expected);
+
+    // only the entry point may validate, and only a valid signature of the signer validates
+    let entry_point = format!("{:#x}", common::CALLER);
+    let validate = format!("0xbeef VALIDATE_USER_OP({}, 0x200, 0x300)", entry_point);
+    assert_eq!(
+        call(
+            &mut evm,
+            "0xbeef VALIDATE_USER_OP(0xe0e0, 0x200, 0x300)",
+            &data
+        ),
+        revert("NotEntryPoint")
+    );
+    assert_eq!(call(&mut evm, &validate, &data), ok(&[U256::ZERO]));
+    let malleable = validate_user_op(high_order, 0);
+    assert_eq!(call(&mut evm, &validate, &malleable), ok(&[U256::ONE]));
+    let other = format!("0xbeee VALIDATE_USER_OP({}, 0x200, 0x300)", entry_point);
+    assert_eq!(call(&mut evm, &other, &data), ok(&[U256::ONE]));
+
+    // a contract signer failing its `isValidSignature` call fails validation rather than reverting
+    let magic = "0x1626ba7e 0xe0 shl 0x00 mstore";
+    let signers = [
+        (format!("{} 0x20 0x00 return", magic), U256::ZERO),
+        (format!("{} 0x20 0x00 revert", magic), U256::ONE),
+        ("invalid".to_string(), U256::ONE),
+    ];
+    for (body, validation) in signers {
+        evm.set_code(addr(0x5160), compile(&dir, file, &body).1);
+        let contract = format!("0x5160 VALIDATE_USER_OP({}, 0x200, 0x300)", entry_point);
+        assert_eq!(
+            call(&mut evm, &contract, &data),
+            ok(&[validation]),
+            "{}",
+            body
+        );
+    }
+
+    // the missing funds are paid to the entry point, and nothing when none are missing
+    evm.account(addr(0xc0de)).balance = n(1000);
+    let prefunded = validate_user_op(half_order, 300);
+    assert_eq!(call(&mut evm, &validate, &prefunded), ok(&[U256::ZERO]));
+    assert_eq!(evm.balance(addr(common::CALLER)), n(300));
+    assert_eq!(call(&mut evm, &validate, &data), ok(&[U256::ZERO]));
+    assert_eq!(evm.balance(addr(0xc0de)), n(700));
+}
+
+#[test]
+fn multi_tokens() {
+    let dir = generate("multitoken");
+    let file = "libmultitoken.huff";
+    let n = U256::from_u64;
+    let (alice, bob) = (common::CALLER, 0xb0b);
+    let slots = "0x01, 0x02, 0x03";
+
+    // each item of a test runs as a call of `caller` against the tokens of one contract
+    let mut evm = Evm::new();
+    let mut token = |caller: u64, invocation: &str, stack: &[u64]| {
+        let pushes = stack.iter().rev().map(|word| format!("{:#x}", word));
+        let body = format!(
+            "{} {}",
+            pushes.collect::<Vec<String>>().join(" "),
+            invocation
+        );
+        let (program, code) = compile(&dir, file, &body);
+        evm.set_code(addr(0xc0de), code);
+        let outcome = evm.transact(Call {
+            caller: addr(caller),
+            address: addr(0xc0de),
+            code_address: addr(0xc0de),
+            value: U256::ZERO,
+            data: Vec::new(),
+            gas: 30_000_000,
+            is_static: false,
+            transfer: false,
+        });
+        result(&program, &body, outcome)
+    };
+    let balance = "ERC6909_BALANCE_OF(0x01)";
+    let transfer_from = format!("ERC6909_TRANSFER_FROM({})", slots);
+
+    assert_eq!(
+        token(alice, "ERC6909_MINT(0x01)", &[alice, 7, 100]),
+        ok(&[])
+    );
+    assert_eq!(token(alice, balance, &[alice, 7]), ok(&[n(100)]));
+    assert_eq!(token(alice, balance, &[alice, 8]), ok(&[U256::ZERO]));
+    assert_eq!(
+        token(alice, "ERC6909_TRANSFER(0x01)", &[bob, 7, 30]),
+        ok(&[])
+    );
+    assert_eq!(token(alice, balance, &[alice, 7]), ok(&[n(70)]));
+    assert_eq!(token(alice, balance, &[bob, 7]), ok(&[n(30)]));
+    assert_eq!(
+        token(alice, "ERC6909_TRANSFER(0x01)", &[bob, 7, 71]),
+        revert("InsufficientBalance")
+    );
+    assert_eq!(
+        token(alice, "0x00 not 0x07 0xb0b ERC6909_MINT(0x01)", &[]),
+        revert("BalanceOverflow")
+    );
+
+    // transfers on behalf of another account need an allowance, spent unless it is unlimited, or
+    // to be an operator of it
+    assert_eq!(
+        token(alice, &transfer_from, &[bob, alice, 7, 1]),
+        revert("InsufficientPermission")
+    );
+    assert_eq!(
+        token(bob, "ERC6909_APPROVE(0x02)", &[alice, 7, 10]),
+        ok(&[])
+    );
+    assert_eq!(token(alice, &transfer_from, &[bob, alice, 7, 10]), ok(&[]));
+    assert_eq!(
+        token(alice, "ERC6909_ALLOWANCE(0x02)", &[bob, alice, 7]),
+        ok(&[U256::ZERO])
+    );
+    assert_eq!(
+        token(alice, &transfer_from, &[bob, alice, 7, 1]),
+        revert("InsufficientPermission")
+    );
+    let unlimited = format!("0x00 not 0x07 {:#x} ERC6909_APPROVE(0x02)", alice);
+    assert_eq!(token(bob, &unlimited, &[]), ok(&[]));
+    assert_eq!(token(alice, &transfer_from, &[bob, alice, 7, 5]), ok(&[]));
+    assert_eq!(
+        token(alice, "ERC6909_ALLOWANCE(0x02)", &[bob, alice, 7]),
+        ok(&[U256::MAX])
+    );
+    assert_eq!(
+        token(alice, &transfer_from, &[bob, alice, 7, 16]),
+        revert("InsufficientBalance")
+    );
+    assert_eq!(token(bob, "ERC6909_APPROVE(0x02)", &[alice, 7, 0]), ok(&[]));
+    assert_eq!(
+        token(bob, "ERC6909_SET_OPERATOR(0x03)", &[alice, 2]),
+        ok(&[])
+    );
+    assert_eq!(
+        token(alice, "ERC6909_IS_OPERATOR(0x03)", &[bob, alice]),
+        ok(&[U256::ONE])
+    );
+    assert_eq!(token(alice, &transfer_from, &[bob, alice, 7, 15]), ok(&[]));
+    assert_eq!(token(alice, balance, &[bob, 7]), ok(&[U256::ZERO]));
+    assert_eq!(token(alice, balance, &[alice, 7]), ok(&[n(100)]));
+
+    // each balance change is logged as a `Transfer` with its caller, to zero for burns
+    assert_eq!(
+        token(alice, "ERC6909_BURN(0x01)", &[alice, 7, 101]),
+        revert("InsufficientBalance")
+    );
+    let burn = format!("0x64 0x07 {:#x} ERC6909_BURN(0x01)", alice);
+    let outcome = transact(&mut evm, compile(&dir, file, &burn).1, &[]);
+    assert!(outcome.success() && outcome.logs.len() == 1);
+    let burn = &outcome.logs[0];
+    assert_eq!(burn.topics[1..], [n(alice), U256::ZERO, n(7)]);
+    assert_eq!(
+        burn.data,
+        [n(alice).to_be_bytes(), n(100).to_be_bytes()].concat()
+    );
+}
+
+#[test]
+fn payments() {
+    let dir = generate("payment");
+    let file = "libpayment.huff";
+    let n = U256::from_u64;
+
+    // a vault crediting the ether sent to it, withdrawing the amount in the calldata if any, and
+    // the whole balance of the caller otherwise
+    let vault = "callvalue deposit jumpi calldatasize amount jumpi WITHDRAW_ALL(0x01) stop \
+        amount: 0x00 calldataload WITHDRAW_AMOUNT(0x01) stop \
+        deposit: CREDIT_DEPOSIT(0x01)";
+    let (program, code) = compile(&dir, file, vault);
+    let mut evm = Evm::new();
+    evm.set_code(addr(0xc0de), code.clone());
+    let pay = |evm: &mut Evm, caller: u64, value: u64, data: &[u8]| {
+        evm.account(addr(caller)).balance = evm.balance(addr(caller)).wrapping_add(n(value));
+        let outcome = evm.transact(Call {
+            caller: addr(caller),
+            address: addr(0xc0de),
+            code_address: addr(0xc0de),
+            value: n(value),
+            data: data.to_vec(),
+            gas: 30_000_000,
+            is_static: false,
+            transfer: true,
+        });
+        result(&program, vault, outcome)
+    };
+    let amount = |amount: u64| n(amount).to_be_bytes();
+    // the deposit of an account, read by code swapped in for the vault
+    let deposit = |evm: &mut Evm, account: u64| {
+        let balance = format!("{:#x} DEPOSIT_OF(0x01)", account);
+        let outcome = transact(evm, compile(&dir, file, &balance).1, &[]);
+        evm.set_code(addr(0xc0de), code.clone());
+        result(&program, &balance, outcome)
+    };
+
+    assert_eq!(pay(&mut evm, 0xe0a, 100, &[]), ok(&[]));
+    assert_eq!(pay(&mut evm, 0xe0a, 20, &[]), ok(&[]));
+    assert_eq!(deposit(&mut evm, 0xe0a), ok(&[n(120)]));
+    assert_eq!(
+        pay(&mut evm, 0xe0a, 0, &amount(121)),
+        revert("InsufficientBalance")
+    );
+    assert_eq!(pay(&mut evm, 0xe0a, 0, &amount(20)), ok(&[]));
+    assert_eq!(evm.balance(addr(0xe0a)), n(20));
+    assert_eq!(pay(&mut evm, 0xe0a, 0, &[]), ok(&[]));
+    assert_eq!(deposit(&mut evm, 0xe0a), ok(&[U256::ZERO]));
+    assert_eq!(evm.balance(addr(0xe0a)), n(120));
+    assert_eq!(evm.balance(addr(0xc0de)), U256::ZERO);
+
+    // a receiver reentering the vault as it is paid is refused, while the payout goes through;
+    // the receiver records whether its withdrawal succeeded and the error it reverted with
+    let reenter = "0x00 0x00 0x00 0x00 0x00 0xc0de gas call 0x00 sstore \
+        returndatasize 0x00 0x00 returndatacopy 0x00 mload 0x01 sstore";
+    evm.set_code(addr(0xa77), compile(&dir, file, reenter).1);
+    assert_eq!(pay(&mut evm, 0xa77, 50, &[]), ok(&[]));
+    assert_eq!(pay(&mut evm, 0xe0a, 50, &[]), ok(&[]));
+    assert_eq!(pay(&mut evm, 0xa77, 0, &[]), ok(&[]));
+    assert_eq!(evm.sload(addr(0xa77), U256::ZERO), U256::ZERO);
+    let reentrant = U256::from_be_bytes(&huff::selector("Reentrant()")).shl(224);
+    assert_eq!(evm.sload(addr(0xa77), U256::ONE), reentrant);
+    assert_eq!(evm.balance(addr(0xa77)), n(50));
+    assert_eq!(evm.balance(addr(0xc0de)), n(50));
+    assert_eq!(deposit(&mut evm, 0xa77), ok(&[U256::ZERO]));
+
+    // a receiver refusing the payout keeps its deposit
+    evm.set_code(addr(0xa77), vec![0x5f, 0x5f, 0xfd]);
+    assert_eq!(pay(&mut evm, 0xa77, 30, &[]), ok(&[]));
+    assert_eq!(pay(&mut evm, 0xa77, 0, &[]), revert("WithdrawFailed"));
+    assert_eq!(deposit(&mut evm, 0xa77), ok(&[n(30)]));
+
+    // the guard locks its own transient slot for the duration of the macro it wraps, and releases
+    // it after, so a call may pass the guard twice
+    let slot = "0x356c122056104ddcfeabc3ef4e4e83bab80ed0e287991b1a7f1bea3aa9623c05";
+    let src = format!(
+        concat!(
+            "#include \"libreentrancy.huff\"\n\n",
+            "#define macro PROBE() = takes (0) returns (0) {{ {} tload 0x00 sstore }}\n\n",
+            "#define macro MAIN() = takes (0) returns (0) {{\n",
+            "    REENTRANCY_GUARD(PROBE()) [REENTRANCY_SLOT] tload 0x01 sstore\n",
+            "    REENTRANCY_GUARD(PROBE())\n",
+            "}}\n",
+        ),
+        slot,
+    );
+    let program = Program::parse_str(&src, &dir).unwrap();
+    let code = profile::compile(program, "MAIN", &cancun()).unwrap();
+    let mut evm = Evm::new();
+    evm.sstore(addr(0xc0de), U256::ONE, n(0xff));
+    assert!(transact(&mut evm, code, &[]).success());
+    assert_eq!(evm.sload(addr(0xc0de), U256::ZERO), U256::ONE);
+    assert_eq!(evm.sload(addr(0xc0de), U256::ONE), U256::ZERO);
+}
+
+#[test]
+fn forwarders() {
+    let dir = generate("forwarder");
+    let file = "libforwarder.huff";
+    let n = U256::from_u64;
+    let caller = n(common::CALLER);
+    let sender = n(0x5e4de7);
+    let context = |forwarder: u64, data: &[u8]| {
+        let body = format!(
+            "MSG_DATA_2771({:#x}) MSG_SENDER_2771({:#x})",
+            forwarder, forwarder
+        );
+        let (program, outcome) = execute(&dir, file, &body, data);
+        result(&program, &body, outcome)
+    };
+    // calldata with the 20 bytes of `sender` appended, as a forwarder relays it
+    let forwarded = |data: &[u8]| [data, &sender.to_be_bytes()[12..]].concat();
+
+    assert_eq!(
+        run(&dir, file, "IS_TRUSTED_FORWARDER(0xca11)", &[caller]),
+        ok(&[U256::ONE])
+    );
+    assert_eq!(
+        run(&dir, file, "IS_TRUSTED_FORWARDER(0xf00d)", &[caller]),
+        ok(&[U256::ZERO])
+    );
+
+    // calls relayed by the trusted forwarder are from the sender it appends
+    let trusted = common::CALLER;
+    let call = forwarded(&[0xa9, 0x05, 0x9c, 0xbb]);
+    assert_eq!(context(trusted, &call), ok(&[sender, n(4)]));
+    assert_eq!(context(trusted, &forwarded(&[])), ok(&[sender, U256::ZERO]));
+    // but calldata too short to carry a sender is the caller's, in full
+    assert_eq!(context(trusted, &[0xff; 19]), ok(&[caller, n(19)]));
+    assert_eq!(context(trusted, &[]), ok(&[caller, U256::ZERO]));
+    // as is the calldata of any other caller
+    assert_eq!(context(0xf00d, &call), ok(&[caller, n(24)]));
+}
+
+#[test]
+fn revert_data() {
+    let dir = generate("revert");
+    let file = "librevert.huff";
+    let n = U256::from_u64;
+    let word = |value: u64| n(value).to_be_bytes().to_vec();
+
+    // each body runs on the returndata of a call to a contract reverting with the calldata
+    let mut evm = Evm::new();
+    let echo = "calldatasize 0x00 0x00 calldatacopy calldatasize 0x00 revert";
+    evm.set_code(addr(0xfa11), compile(&dir, file, echo).1);
+    let mut fail = |body: &str, data: &[u8]| {
+        let body = format!(
+            "calldatasize 0x00 0x00 calldatacopy \
+            0x00 0x00 calldatasize 0x00 0x00 0xfa11 gas call pop {}",
+            body
+        );
+        let (program, code) = compile(&dir, file, &body);
+        result(&program, &body, transact(&mut evm, code, data))
+    };
+
+    let boom = {
+        let mut message = b"boom".to_vec();
+        message.resize(32, 0);
+        message
+    };
+    let error_string = [
+        vec![0x08, 0xc3, 0x79, 0xa0],
+        word(0x20),
+        word(4),
+        boom.clone(),
+    ]
+    .concat();
+    let panic = [vec![0x4e, 0x48, 0x7b, 0x71], word(0x11)].concat();
+    let custom = vec![0x82, 0xb4, 0x29, 0x00];
+
+    // revert data is told apart by kind, with the kinds carrying data too short for it unmatched
+    let kind = "JUMP_IF_EMPTY_REVERT(empty) JUMP_IF_ERROR_STRING(string) JUMP_IF_PANIC(panic) \
+        JUMP_IF_CUSTOM_ERROR(0x82b42900, custom) 0x00 stop \
+        empty: 0x01 stop string: 0x02 stop panic: 0x03 stop custom: 0x04 stop";
+    assert_eq!(fail(kind, &[]), ok(&[n(1)]));
+    assert_eq!(fail(kind, &error_string), ok(&[n(2)]));
+    assert_eq!(fail(kind, &panic), ok(&[n(3)]));
+    assert_eq!(fail(kind, &custom), ok(&[n(4)]));
+    assert_eq!(fail(kind, &error_string[..0x43]), ok(&[U256::ZERO]));
+    assert_eq!(fail(kind, &panic[..0x23]), ok(&[U256::ZERO]));
+    assert_eq!(fail(kind, &[0x82, 0xb4]), ok(&[U256::ZERO]));
+    assert_eq!(fail("REVERT_SELECTOR()", &custom), ok(&[n(0x82b42900)]));
+    assert_eq!(fail("REVERT_SELECTOR()", &custom[..3]), ok(&[U256::ZERO]));
+
+    // messages and codes decode, and messages overrunning the revert data are refused
+    let message = "ERROR_STRING_MESSAGE(0x100) dup1 mload";
+    let decoded = fail(message, &error_string);
+    assert_eq!(
+        decoded,
+        ok(&[U256::from_be_bytes(&boom), n(0x100 + 0x40), n(4)])
+    );
+    let overrun = [&error_string[..0x24], &word(33), &boom].concat();
+    assert_eq!(fail(message, &overrun), revert("InvalidRevertData"));
+    let offset = [&error_string[..4], &word(0x1000), &word(4), &boom].concat();
+    assert_eq!(fail(message, &offset), revert("InvalidRevertData"));
+    assert_eq!(fail("PANIC_CODE()", &panic), ok(&[n(0x11)]));
+
+    // and bubbled up as they are
+    assert_eq!(
+        fail("BUBBLE_REVERT()", &custom),
+        Err("0x82b42900".to_string())
+    );
+    assert_eq!(fail("BUBBLE_REVERT()", &[]), Err("0x".to_string()));
+}
+
+#[test]
+fn try_calls() {
+    let dir = generate("try");
+    let file = "libtry.huff";
+    let n = U256::from_u64;
+
+    // a callee doubling the word it is called with, one reverting with it, and one writing state
+    let mut evm = Evm::new();
+    let callees = [
+        (
+            0xd0b1,
+            "0x00 calldataload 0x02 mul 0x00 mstore 0x20 0x00 return",
+        ),
+        (0xfa11, "0x00 calldataload 0x00 mstore 0x20 0x00 revert"),
+        (0x5e7, "0x01 0x00 sstore stop"),
+    ];
+    for (callee, body) in callees {
+        evm.set_code(addr(callee), compile(&dir, file, body).1);
+    }
+    evm.account(addr(0xc0de)).balance = n(10);
+
+    // each branch leaves whether it was taken on success, the first word of the returndata copied
+    // over the arguments, and its length
+    let branches = "succeeded jump \
+        succeeded: mload 0x01 stop \
+        failed: mload 0x00 stop";
+    let call = |evm: &mut Evm, invocation: &str| {
+        let body = format!("0x15 0x80 mstore 0x20 0x80 {} {}", invocation, branches);
+        step(evm, &dir, file, &body, &[])
+    };
+    let try_call = |value: u64, callee: u64| {
+        format!("{:#x} TRY_CALL({:#x}, succeeded, failed)", value, callee)
+    };
+
+    assert_eq!(
+        call(&mut evm, &try_call(0, 0xd0b1)),
+        ok(&[n(1), n(0x2a), n(0x20)])
+    );
+    assert_eq!(
+        call(&mut evm, &try_call(0, 0xfa11)),
+        ok(&[n(0), n(0x15), n(0x20)])
+    );
+    assert_eq!(
+        call(&mut evm, &try_call(4, 0xd0b1)),
+        ok(&[n(1), n(0x2a), n(0x20)])
+    );
+    assert_eq!(evm.balance(addr(0xd0b1)), n(4));
+    assert_eq!(evm.balance(addr(0xc0de)), n(6));
+    // a call sending more than the balance fails with no returndata, the arguments untouched
+    assert_eq!(
+        call(&mut evm, &try_call(7, 0xd0b1)),
+        ok(&[n(0), n(0x15), U256::ZERO])
+    );
+
+    let try_static = |callee: u64| format!("TRY_STATICCALL({:#x}, succeeded, failed)", callee);
+    assert_eq!(
+        call(&mut evm, &try_static(0xd0b1)),
+        ok(&[n(1), n(0x2a), n(0x20)])
+    );
+    assert_eq!(
+        call(&mut evm, &try_static(0xfa11)),
+        ok(&[n(0), n(0x15), n(0x20)])
+    );
+    assert_eq!(
+        call(&mut evm, &try_static(0x5e7)),
+        ok(&[n(0), n(0x15), U256::ZERO])
+    );
+    assert_eq!(evm.sload(addr(0x5e7), U256::ZERO), U256::ZERO);
+
+    // the returndata is left for the macros of librevert to bubble
+    let body = "0x15 0x80 mstore 0x20 0x80 0x00 TRY_CALL(0xfa11, succeeded, failed) \
+        succeeded: stop \
+        failed: BUBBLE_REVERT()";
+    let word = format!("0x{}", "00".repeat(31) + "15");
+    assert_eq!(step(&mut evm, &dir, file, body, &[]), Err(word));
+}
+
+#[test]
+fn gas_caps() {
+    let dir = generate("gas_caps");
+    let file = "libcall.huff";
+    let n = U256::from_u64;
+
+    // a callee burning all the gas it is given, and one recording the gas it is given
+    let mut evm = Evm::new();
+    evm.set_code(addr(0xb0b), compile(&dir, file, "burn: burn jump").1);
+    evm.set_code(addr(0x9a5), compile(&dir, file, "gas 0x00 sstore stop").1);
+
+    // runs `body` in a transaction given `gas`
+    let mut limited = |body: &str, gas: u64| {
+        let (program, code) = compile(&dir, file, body);
+        evm.set_code(addr(0xc0de), code);
+        let outcome = evm.transact(Call {
+            caller: addr(common::CALLER),
+            address: addr(0xc0de),
+            code_address: addr(0xc0de),
+            value: U256::ZERO,
+            data: vec![],
+            gas,
+            is_static: false,
+            transfer: false,
+        });
+        let stored = evm.sload(addr(0x9a5), U256::ZERO);
+        (result(&program, body, outcome), stored)
+    };
+
+    let capped = |target: u64| {
+        format!(
+            "0x00 0x00 0x00 0x00 0x00 CALL_WITH_GAS_CAP({:#x}, 0x7530)",
+            target
+        )
+    };
+    // the callee is given exactly the cap, less the `gas` it records it with
+    assert_eq!(
+        limited(&capped(0x9a5), 1_000_000),
+        (ok(&[n(1)]), n(0x7530 - 2))
+    );
+    // a callee running out of the gas it was given fails the call, not its caller
+    assert_eq!(limited(&capped(0xb0b), 1_000_000).0, ok(&[n(0)]));
+    // but one the 63/64 rule gave less runs out of it with too little left to its caller
+    assert_eq!(limited(&capped(0xb0b), 32_000).0, revert("InsufficientGas"));
+
+    // a callee burning all it is given leaves the reserve to its caller, less the cold access
+    let reserved = |target: u64| {
+        format!(
+            "0x00 0x00 0x00 0x00 0x00 {:#x} CALL_RESERVE_GAS(0x2710) gas",
+            target
+        )
+    };
+    let (outcome, _) = limited(&reserved(0xb0b), 100_000);
+    let stack = outcome.unwrap();
+    assert_eq!(stack[1], n(0));
+    assert!(stack[0] < n(0x2710) && stack[0] > n(0x2710 - 2600 - 100));
+    assert_eq!(
+        limited(&reserved(0xb0b), 9_000).0,
+        revert("InsufficientGas")
+    );
+}
+
+#[test]
+fn static_calls() {
+    let dir = generate("static_calls");
+    let file = "libcall.huff";
+    let n = U256::from_u64;
+
+    // a callee returning its selector plus its argument, one returning less than a word, one
+    // reverting with its calldata, and one writing state
+    let mut evm = Evm::new();
+    let callees = [
+        (
+            0xec0,
+            "0x00 calldataload 0xe0 shr 0x04 calldataload add 0x00 mstore 0x20 0x00 return",
+        ),
+        (0x5407, "0x1f 0x00 return"),
+        (
+            0xfa11,
+            "calldatasize 0x00 0x00 calldatacopy calldatasize 0x00 revert",
+        ),
+        (0x5e7, "0x01 0x00 sstore stop"),
+    ];
+    for (callee, body) in callees {
+        evm.set_code(addr(callee), compile(&dir, file, body).1);
+    }
+    let mut call = |invocation: &str, stack: &[U256]| step(&mut evm, &dir, file, invocation, stack);
+
+    let word = "STATICCALL_RETURN_WORD(0xec0, 0x18160ddd)";
+    assert_eq!(call(word, &[]), ok(&[n(0x18160ddd)]));
+    let word = "STATICCALL_ARG_RETURN_WORD(0xec0, 0x70a08231)";
+    assert_eq!(call(word, &[n(5)]), ok(&[n(0x70a08236)]));
+    assert_eq!(
+        call("STATICCALL_RETURN_U8(0xec0, 0xff)", &[]),
+        ok(&[n(0xff)])
+    );
+    assert_eq!(
+        call("STATICCALL_RETURN_U8(0xec0, 0x100)", &[]),
+        revert("Overflow")
+    );
+    let u16 = "STATICCALL_ARG_RETURN_U16(0xec0, 0x01)";
+    assert_eq!(call(u16, &[n(0xfffe)]), ok(&[n(0xffff)]));
+    assert_eq!(call(u16, &[n(0xffff)]), revert("Overflow"));
+
+    let short = "STATICCALL_RETURN_WORD(0x5407, 0x18160ddd)";
+    assert_eq!(call(short, &[]), revert("InvalidReturnData"));
+    // a failed call bubbles its revert data, and a call writing state fails with none
+    let reverted = "STATICCALL_ARG_RETURN_WORD(0xfa11, 0x70a08231)";
+    let data = format!("0x70a08231{}", "00".repeat(31) + "05");
+    assert_eq!(call(reverted, &[n(5)]), Err(data));
+    let written = "STATICCALL_RETURN_U32(0x5e7, 0x18160ddd)";
+    assert_eq!(call(written, &[]), Err("0x".to_string()));
+}
+
+#[test]
+fn batch_calls() {
+    let dir = generate("batch_calls");
+    let file = "libcall.huff";
+    let n = U256::from_u64;
+
+    // a callee counting its calls, and one reverting with its calldata
+    let mut evm = Evm::new();
+    let counter = "0x00 sload 0x01 add 0x00 sstore stop";
+    evm.set_code(addr(0xc01), compile(&dir, file, counter).1);
+    let reverter = "calldatasize 0x00 0x00 calldatacopy calldatasize 0x00 revert";
+    evm.set_code(addr(0xfa11), compile(&dir, file, reverter).1);
+
+    // stores a batch at `0x100` calling each target with the selector `0xdeadbeef`, its calls
+    // from `0x200` on
+    let batch = |targets: &[u64]| {
+        let mut words = vec![(0x100, n(targets.len() as u64))];
+        for (i, target) in targets.iter().enumerate() {
+            let call = 0x200 + 0x100 * i as u64;
+            words.push((0x120 + 0x20 * i as u64, n(call)));
+            words.push((call, n(*target)));
+            words.push((call + 0x20, n(4)));
+            words.push((call + 0x40, n(0xdeadbeef).shl(224)));
+        }
+        let stores = words
+            .iter()
+            .map(|(ptr, word)| format!("{} {:#x} mstore", word.to_hex(), ptr))
+            .collect::<Vec<String>>();
+        stores.join(" ")
+    };
+    let calls = |evm: &Evm| evm.sload(addr(0xc01), U256::ZERO);
+
+    let all = |targets: &[u64]| format!("{} 0x100 BATCH_CALL_ALL_OR_NOTHING()", batch(targets));
+    assert_eq!(
+        step(&mut evm, &dir, file, &all(&[0xc01, 0xc01]), &[]),
+        ok(&[])
+    );
+    assert_eq!(calls(&evm), n(2));
+    // a failed call reverts the batch with its revert data, undoing the calls before it
+    let failed = step(&mut evm, &dir, file, &all(&[0xc01, 0xfa11, 0xc01]), &[]);
+    assert_eq!(failed, Err("0xdeadbeef".to_string()));
+    assert_eq!(calls(&evm), n(2));
+    assert_eq!(step(&mut evm, &dir, file, &all(&[]), &[]), ok(&[]));
+
+    // the results are the count of calls followed by the success of each
+    let best = |targets: &[u64]| {
+        format!(
+            "{} 0x600 0x100 BATCH_CALL_BEST_EFFORT() \
+            0x660 mload 0x640 mload 0x620 mload 0x600 mload",
+            batch(targets)
+        )
+    };
+    let results = step(&mut evm, &dir, file, &best(&[0xc01, 0xfa11, 0xc01]), &[]);
+    assert_eq!(results, ok(&[n(3), n(1), n(0), n(1)]));
+    assert_eq!(calls(&evm), n(4));
+}
+
+#[test]
+fn returns() {
+    let dir = generate("returns");
+    let file = "libreturn.huff";
+    let word = |n: u64| hex(&U256::from_u64(n).to_be_bytes());
+    let returned = |body: &str, data: &[u8]| {
+        let (_, outcome) = execute(&dir, file, body, data);
+        assert_eq!(outcome.halt, Halt::Return, "{}", body);
+        hex(&outcome.output)
+    };
+
+    // the bytes are zero padded over the dirty memory after them
+    let dirty = "0x00 not 0x60 mstore 0x00 not 0x80 mstore";
+    let bytes = format!(
+        "{} 0x25 0x04 0x40 calldatacopy 0x25 0x40 RETURN_BYTES()",
+        dirty
+    );
+    let data = [vec![0; 4], vec![0xab; 0x25]].concat();
+    let padding = "00".repeat(0x40 - 0x25);
+    let expected = format!(
+        "{}{}{}{}",
+        word(0x20),
+        word(0x25),
+        "ab".repeat(0x25),
+        padding
+    );
+    assert_eq!(returned(&bytes, &data), expected);
+    // empty bytes are the head and a zero length
+    let empty = format!("{} 0x00 0x40 RETURN_BYTES()", dirty);
+    assert_eq!(returned(&empty, &[]), word(0x20) + &word(0));
+
+    let array = "0x01 0x40 mstore 0x02 0x60 mstore 0x02 0x40 RETURN_UINT_ARRAY()";
+    let expected = [word(0x20), word(2), word(1), word(2)].concat();
+    assert_eq!(returned(array, &[]), expected);
+    assert_eq!(
+        returned("0x00 0x40 RETURN_UINT_ARRAY()", &[]),
+        word(0x20) + &word(0)
+    );
+
+    let hello = "0x68656c6c6f";
+    let tuple = format!(
+        "0x2a 0x05 {}{} 0x60 mstore 0x60 RETURN_STRING_AND_UINT()",
+        hello,
+        "00".repeat(27)
+    );
+    let expected = [
+        word(0x40),
+        word(0x2a),
+        word(5),
+        String::from("68656c6c6f") + &"00".repeat(27),
+    ];
+    assert_eq!(returned(&tuple, &[]), expected.concat());
+}
+
+#[test]
+fn cursors() {
+    let dir = generate("cursors");
+    let file = "libcursor.huff";
+    let n = U256::from_u64;
+
+    // a selector, a word, and the length and bytes of `abc`
+    let data = [
+        vec![0xde, 0xad, 0xbe, 0xef],
+        n(0x2a).to_be_bytes().to_vec(),
+        n(3).to_be_bytes().to_vec(),
+        b"abc".to_vec(),
+    ]
+    .concat();
+    let read = |body: &str| {
+        let (program, outcome) = execute(&dir, file, body, &data);
+        result(&program, body, outcome)
+    };
+
+    assert_eq!(read("CURSOR_INIT()"), ok(&[n(4)]));
+    let words = "CURSOR_INIT() CURSOR_READ_WORD() swap1 CURSOR_READ_WORD()";
+    assert_eq!(read(words), ok(&[n(3), n(0x44), n(0x2a)]));
+    let bytes = format!("{} dup1 0x00 dup4 CURSOR_READ_BYTES() 0x00 mload", words);
+    let abc = U256::from_be_bytes(&[b"abc".to_vec(), vec![0; 29]].concat());
+    assert_eq!(read(&bytes), ok(&[abc, n(0x47), n(3), n(0x44), n(0x2a)]));
+    assert_eq!(read("0x47 0x00 CURSOR_SKIP()"), ok(&[n(0x47)]));
+
+    // reads past the end of calldata, or overflowing the cursor, are out of bounds
+    let past = "0x40 CURSOR_INIT() CURSOR_SKIP() CURSOR_READ_WORD()";
+    assert_eq!(read(past), revert("OutOfBounds"));
+    let longer = format!("{} 0x04 0x00 dup4 CURSOR_READ_BYTES()", words);
+    assert_eq!(read(&longer), revert("OutOfBounds"));
+    assert_eq!(read("0x48 0x00 CURSOR_SKIP()"), revert("OutOfBounds"));
+    let overflowing = format!("{} 0x01 CURSOR_SKIP()", U256::MAX.to_hex());
+    assert_eq!(read(&overflowing), revert("OutOfBounds"));
+}
+
+#[test]
+fn hashes() {
+    let dir = generate("hashes");
+    let file = "libkeccak.huff";
+    let n = U256::from_u64;
+
+    // a selector and five words of distinct bytes
+    let data = (0..0xa4).map(|i| i as u8).collect::<Vec<u8>>();
+    let hash = |body: &str| {
+        let (program, outcome) = execute(&dir, file, body, &data);
+        result(&program, body, outcome)
+    };
+    // the hash of `body` and of the same range copied to `0x100` and hashed by `sha3`
+    let hashes = |body: &str, offset: u64, len: u64| {
+        let reference = format!(
+            "{len:#x} {offset:#x} 0x100 calldatacopy {len:#x} 0x100 sha3",
+            len = len,
+            offset = offset
+        );
+        let stack = hash(&format!("{} {}", body, reference)).unwrap();
+        assert_eq!(stack[0], stack[1], "{}", body);
+    };
+
+    hashes("0x45 0x07 HASH_CALLDATA_RANGE(0x00)", 0x07, 0x45);
+    hashes("0xa0 0x04 HASH_CALLDATA_RANGE(0x00)", 0x04, 0xa0);
+    for words in 1..=4 {
+        let body = format!("0x24 HASH_CALLDATA_WORDS_{}(0x40)", words);
+        hashes(&body, 0x24, 0x20 * words);
+    }
+    let empty =
+        U256::from_hex("0xc5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470")
+            .unwrap();
+    assert_eq!(hash("0x00 0xa4 HASH_CALLDATA_RANGE(0x00)"), ok(&[empty]));
+    assert_eq!(hash("0x00 0x40 HASH_MEM_RANGE()"), ok(&[empty]));
+    let memory = "0x2a 0x20 mstore 0x20 0x20 HASH_MEM_RANGE() 0x20 0x20 sha3 eq";
+    assert_eq!(hash(memory), ok(&[n(1)]));
+
+    // ranges past the end of calldata or overflowing are out of bounds
+    let past = [
+        "0xa1 0x04 HASH_CALLDATA_RANGE(0x00)",
+        "0x85 HASH_CALLDATA_WORDS_1(0x00)",
+    ];
+    for body in past {
+        assert_eq!(hash(body), revert("OutOfBounds"), "{}", body);
+    }
+    let max = U256::MAX.to_hex();
+    let overflowing = [
+        format!("0x02 {} HASH_CALLDATA_RANGE(0x00)", max),
+        format!("{} HASH_CALLDATA_WORDS_2(0x00)", max),
+        format!("0x02 {} HASH_MEM_RANGE()", max),
+    ];
+    for body in overflowing {
+        assert_eq!(hash(&body), revert("OutOfBounds"), "{}", body);
+    }
+}
+
+#[test]
+fn randomness() {
+    let dir = generate("randomness");
+    let file = "librandom.huff";
+    let n = U256::from_u64;
+
+    let mut evm = Evm::new();
+    evm.block.prevrandao = n(0x4a4d);
+    evm.block.timestamp = n(0x7e57);
+    assert_eq!(
+        step(&mut evm, &dir, file, "PREVRANDAO()", &[]),
+        ok(&[n(0x4a4d)])
+    );
+
+    // each word is the hash of the seed it replaces, the randomness, the timestamp and the caller
+    let reference = |seed: U256| {
+        let body = format!(
+            "{} 0x00 mstore 0x4a4d 0x20 mstore 0x7e57 0x40 mstore {:#x} 0x60 mstore 0x80 0x00 sha3",
+            seed.to_hex(),
+            common::CALLER
+        );
+        run(&dir, file, &body, &[]).unwrap()[0]
+    };
+    let mut seed = U256::ZERO;
+    for _ in 0..2 {
+        let random = step(&mut evm, &dir, file, "PSEUDO_RANDOM(0x05)", &[]).unwrap()[0];
+        assert_eq!(random, reference(seed));
+        assert_eq!(evm.sload(addr(0xc0de), n(5)), random);
+        seed = random;
+    }
+
+    assert_eq!(
+        run(&dir, file, "RANDOM_BELOW(0x64)", &[n(250)]),
+        ok(&[n(50)])
+    );
+    assert_eq!(
+        run(&dir, file, "RANDOM_BELOW(0x00)", &[n(250)]),
+        ok(&[n(0)])
+    );
+    let die = "RANDOM_BETWEEN(0x01, 0x06)";
+    assert_eq!(run(&dir, file, die, &[n(6)]), ok(&[n(1)]));
+    assert_eq!(run(&dir, file, die, &[n(5)]), ok(&[n(6)]));
+    assert_eq!(run(&dir, file, die, &[U256::MAX]), ok(&[n(4)]));
+
+    // the range of every word wraps its span to zero, and returns the word unreduced
+    let max = U256::MAX.to_hex();
+    let every = format!("RANDOM_BETWEEN(0x00, {})", max);
+    assert_eq!(run(&dir, file, &every, &[n(0x2a)]), ok(&[n(0x2a)]));
+    assert_eq!(run(&dir, file, &every, &[U256::MAX]), ok(&[U256::MAX]));
+    let last = format!("RANDOM_BETWEEN({}, {})", max, max);
+    assert_eq!(run(&dir, file, &last, &[n(0x2a)]), ok(&[U256::MAX]));
+}
+
+#[test]
+fn proxy_slots() {
+    let dir = generate("proxy_slots");
+    let file = "libslots.huff";
+    let n = U256::from_u64;
+
+    let slots = [
+        (
+            "EIP1967_IMPLEMENTATION_SLOT",
+            "eip1967.proxy.implementation",
+        ),
+        ("EIP1967_ADMIN_SLOT", "eip1967.proxy.admin"),
+        ("EIP1967_BEACON_SLOT", "eip1967.proxy.beacon"),
+    ];
+    for (i, (name, id)) in slots.iter().enumerate() {
+        // the slot is the hash of its id less one, hashed on the EVM
+        let padded = [id.as_bytes().to_vec(), vec![0; 32 - id.len()]].concat();
+        let hashed = format!(
+            "{} 0x00 mstore {:#x} 0x00 sha3 0x01 swap1 sub",
+            U256::from_be_bytes(&padded).to_hex(),
+            id.len()
+        );
+        let slot = run(&dir, file, &format!("[{}]", name), &[]).unwrap()[0];
+        assert_eq!(run(&dir, file, &hashed, &[]), ok(&[slot]), "{}", name);
+
+        // and the slot a proxy reads its address from
+        let mut evm = Evm::new();
+        evm.sstore(addr(0xc0de), slot, n(0x1e5 + i as u64));
+        let read = format!("[{}] sload", name);
+        assert_eq!(
+            step(&mut evm, &dir, file, &read, &[]),
+            ok(&[n(0x1e5 + i as u64)])
+        );
+    }
+}
+
+#[test]
+fn blobs() {
+    let dir = common::temp_dir("blobs");
+    let generator = Generator::new().with_evm_version(EvmVersion::Cancun);
+    generator.write_to(&dir).unwrap();
+    let file = "libblob.huff";
+    let n = U256::from_u64;
+
+    let mut evm = Evm::new();
+    evm.block.blob_hashes = vec![n(0x01b0), n(0x01b1)];
+    evm.block.blob_basefee = n(7);
+    let call = |evm: &mut Evm, invocation: &str| step(evm, &dir, file, invocation, &[]);
+
+    assert_eq!(call(&mut evm, "BLOBHASH_AT(0x01)"), ok(&[n(0x01b1)]));
+    assert_eq!(call(&mut evm, "BLOBHASH_AT(0x02)"), ok(&[U256::ZERO]));
+    assert_eq!(call(&mut evm, "BLOB_BASEFEE()"), ok(&[n(7)]));
+    assert_eq!(call(&mut evm, "REQUIRE_BLOB_COUNT(0x02)"), ok(&[]));
+    for count in ["0x00", "0x01", "0x03"] {
+        let invocation = format!("REQUIRE_BLOB_COUNT({})", count);
+        assert_eq!(call(&mut evm, &invocation), revert("InvalidBlobCount"));
+    }
+    evm.block.blob_hashes.clear();
+    assert_eq!(call(&mut evm, "REQUIRE_BLOB_COUNT(0x00)"), ok(&[]));
+    assert_eq!(
+        call(&mut evm, "REQUIRE_BLOB_COUNT(0x01)"),
+        revert("InvalidBlobCount")
+    );
+
+    // a point evaluation precompile verifying only the input laid out from the arguments and a
+    // commitment of `0xcc` and proof of `0xdd` bytes, returning the blob size and modulus
+    fn point_evaluation(input: &[u8]) -> (bool, Vec<u8>) {
+        let mut expected = [0x01b0, 2, 3]
+            .map(|word| U256::from_u64(word).to_be_bytes())
+            .concat();
+        expected.extend([0xcc; 48]);
+        expected.extend([0xdd; 48]);
+        let modulus = "0x73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000001";
+        let output = [U256::from_u64(0x1000), U256::from_hex(modulus).unwrap()];
+        match input == expected {
+            true => (true, output.map(|word| word.to_be_bytes()).concat()),
+            false => (false, Vec::new()),
+        }
+    }
+    let evaluate = |evm: &mut Evm, y: u64| {
+        let data = [vec![0; 4], vec![0xcc; 48], vec![0xdd; 48]].concat();
+        let body = format!("0x04 {:#x} 0x02 0x01b0 POINT_EVALUATION(0x200)", y);
+        let (program, code) = compile(&dir, file, &body);
+        let outcome = transact(evm, code, &data);
+        result(&program, &body, outcome)
+    };
+    // a call to the empty precompile address succeeds without returning the modulus
+    assert_eq!(evaluate(&mut evm, 3), revert("InvalidPointEvaluation"));
+    evm.precompiles.insert(addr(0x0a), point_evaluation);
+    assert_eq!(evaluate(&mut evm, 3), ok(&[]));
+    assert_eq!(evaluate(&mut evm, 4), revert("InvalidPointEvaluation"));
+}
+
+#[test]
+fn bytes_casts() {
+    let dir = generate("bytes_casts");
+    let file = "libbytescast.huff";
+
+    for size in 1..=32u32 {
+        let mask = U256::MAX.shl(256 - 8 * size);
+        let cast = |name: &str, value: U256| run(&dir, file, &format!("{}()", name), &[value]);
+        let (to, safe) = (
+            format!("TO_BYTES{}", size),
+            format!("SAFE_TO_BYTES{}", size),
+        );
+
+        assert_eq!(
+            run(&dir, file, &format!("BYTES{}_MASK()", size), &[]),
+            ok(&[mask])
+        );
+        assert_eq!(cast(&to, U256::MAX), ok(&[mask]), "{}(MAX)", to);
+        assert_eq!(cast(&safe, mask), ok(&[mask]), "{}(mask)", safe);
+        assert_eq!(cast(&safe, U256::ZERO), ok(&[U256::ZERO]), "{}(0)", safe);
+        if size < 32 {
+            // the highest and lowest bytes below the type are each dirty
+            let below = U256::ONE.shl(255 - 8 * size);
+            for dirty in [below, U256::ONE] {
+                assert_eq!(cast(&to, mask | dirty), ok(&[mask]), "{}", to);
+                let cast = cast(&safe, mask | dirty);
+                assert_eq!(cast, revert("DirtyBytes"), "{}({})", safe, dirty.to_hex());
+            }
+        }
+    }
+}
+
+#[test]
+fn calldata_loads() {
+    let dir = generate("calldata_loads");
+    let file = "libcalldata.huff";
+    // a selector followed by `words`
+    let load = |invocation: &str, words: &[U256]| {
+        let data = words.iter().fold(vec![0; 4], |mut data, word| {
+            data.extend(word.to_be_bytes());
+            data
+        });
+        let (program, outcome) = execute(&dir, file, invocation, &data);
+        result(&program, invocation, outcome)
+    };
+
+    for size in INT_SIZES.step_by(8) {
+        let max = max(size);
+        let words = [max, max.wrapping_add(U256::ONE)];
+        let invocation = |offset: u64| format!("LOAD_U{}({:#04x})", size, offset);
+        assert_eq!(load(&invocation(0x04), &words), ok(&[max]), "{}", size);
+        if size < 256 {
+            let dirty = load(&invocation(0x24), &words);
+            assert_eq!(dirty, revert("DirtyBits"), "{}", size);
+        }
+        // the word extends a byte past the end of calldata
+        assert_eq!(
+            load(&invocation(0x25), &words),
+            revert("OutOfBounds"),
+            "{}",
+            size
+        );
+    }
+
+    let address = max(160);
+    assert_eq!(load("LOAD_ADDRESS(0x04)", &[address]), ok(&[address]));
+    let dirty = address.wrapping_add(U256::ONE);
+    assert_eq!(load("LOAD_ADDRESS(0x04)", &[dirty]), revert("DirtyBits"));
+    assert_eq!(load("LOAD_ADDRESS(0x04)", &[]), revert("OutOfBounds"));
+    assert_eq!(load("LOAD_BOOL(0x04)", &[U256::ONE]), ok(&[U256::ONE]));
+    assert_eq!(load("LOAD_BOOL(0x04)", &[U256::ZERO]), ok(&[U256::ZERO]));
+    assert_eq!(
+        load("LOAD_BOOL(0x04)", &[U256::from_u64(2)]),
+        revert("DirtyBits")
+    );
+}
+
+#[test]
+fn escrows() {
+    let dir = generate("escrow");
+    let mut evm = Evm::new();
+    let payee = addr(0xbeef);
+    let word = evm::addr_to_u256(&payee);
+    evm.account(addr(common::CALLER)).balance = U256::from_u64(1_000);
+
+    // credits the value of the call to the payee
+    let body = format!("{} ESCROW_DEPOSIT(0x03)", word.to_hex());
+    let (program, deposit) = compile(&dir, "libescrow.huff", &body);
+    evm.set_code(addr(0xc0de), deposit);
+    for value in [300, 400] {
+        let outcome = evm.transact(Call {
+            caller: addr(common::CALLER),
+            address: addr(0xc0de),
+            code_address: addr(0xc0de),
+            value: U256::from_u64(value),
+            data: vec![],
+            gas: 30_000_000,
+            is_static: false,
+            transfer: true,
+        });
+        assert_eq!(result(&program, "ESCROW_DEPOSIT", outcome), ok(&[]));
+    }
+    let escrow =
+        |evm: &mut Evm, invocation: &str| step(evm, &dir, "libescrow.huff", invocation, &[word]);
+    assert_eq!(evm.balance(addr(0xc0de)), U256::from_u64(700));
+    assert_eq!(
+        escrow(&mut evm, "ESCROW_BALANCE_OF(0x03)"),
+        ok(&[U256::from_u64(700)])
+    );
+
+    // withdraws the entire balance, to the payee only
+    assert_eq!(escrow(&mut evm, "ESCROW_WITHDRAW(0x03)"), ok(&[]));
+    assert_eq!(evm.balance(payee), U256::from_u64(700));
+    assert_eq!(evm.balance(addr(0xc0de)), U256::ZERO);
+    assert_eq!(
+        escrow(&mut evm, "ESCROW_BALANCE_OF(0x03)"),
+        ok(&[U256::ZERO])
+    );
+    assert_eq!(escrow(&mut evm, "ESCROW_WITHDRAW(0x03)"), ok(&[]));
+    assert_eq!(evm.balance(payee), U256::from_u64(700));
+}
+
+#[test]
+fn rlp_and_checksums() {
+    let dir = generate("rlp");
+    let encode = |body: &str| {
+        let (_, outcome) = execute(&dir, "librlp.huff", &format!("{} 0x00 return", body), &[]);
+        assert_eq!(outcome.halt, Halt::Return, "{}", body);
+        hex(&outcome.output)
+    };
+    // stores `bytes` from `ptr`, a word at a time
+    let store = |ptr: usize, bytes: &[u8]| {
+        let mut bytes = bytes.to_vec();
+        bytes.resize(bytes.len().div_ceil(32) * 32, 0);
+        let words = bytes.chunks(32).enumerate();
+        let stores = words.map(|(i, word)| format!("0x{} {:#x} mstore", hex(word), ptr + i * 32));
+        stores.collect::<Vec<String>>().join(" ")
+    };
+
+    // the examples of the RLP specification
+    let uint = |value: U256| encode(&format!("0x00 {} RLP_ENCODE_UINT()", value.to_hex()));
+    assert_eq!(uint(U256::ZERO), "80");
+    assert_eq!(uint(U256::from_u64(0x0f)), "0f");
+    assert_eq!(uint(U256::from_u64(0x7f)), "7f");
+    assert_eq!(uint(U256::from_u64(0x80)), "8180");
+    assert_eq!(uint(U256::from_u64(0x0400)), "820400");
+    assert_eq!(uint(U256::MAX), format!("a0{}", "ff".repeat(32)));
+
+    let bytes = |payload: &[u8]| {
+        let len = payload.len();
+        encode(&format!(
+            "{} 0x00 {:#x} 0x100 RLP_ENCODE_BYTES()",
+            store(0x100, payload),
+            len
+        ))
+    };
+    assert_eq!(bytes(b""), "80");
+    assert_eq!(bytes(b"\x00"), "00");
+    assert_eq!(bytes(b"\x80"), "8180");
+    assert_eq!(bytes(b"dog"), "83646f67");
+    let lorem = b"Lorem ipsum dolor sit amet, consectetur adipisicing elit";
+    assert_eq!(bytes(lorem), format!("b838{}", hex(lorem)));
+
+    let cat_dog = format!(
+        "{} {} 0x00 0x04 0x140 0x04 0x100 RLP_ENCODE_LIST_2()",
+        store(0x100, &[0x83, b'c', b'a', b't']),
+        store(0x140, &[0x83, b'd', b'o', b'g']),
+    );
+    assert_eq!(encode(&cat_dog), "c88363617483646f67");
+    let words = format!(
+        "{} {} 0x00 0x21 0x140 0x21 0x100 RLP_ENCODE_LIST_2()",
+        store(0x100, &[[0xa0].as_slice(), &[0x11; 32]].concat()),
+        store(0x140, &[[0xa0].as_slice(), &[0x22; 32]].concat()),
+    );
+    let items = format!("a0{}a0{}", "11".repeat(32), "22".repeat(32));
+    assert_eq!(encode(&words), format!("f842{}", items));
+
+    // the examples of EIP-55
+    let checksums = [
+        "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed",
+        "0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359",
+        "0xdbF03B407c01E7cD3CBea99509d93f8DDDC8C6FB",
+        "0xD1220A0cf47c7B9Be7A2E6BA89F429762e7b9aDb",
+    ];
+    for checksum in checksums {
+        let address = checksum.to_lowercase();
+        let string = |invocation: &str| {
+            let body = format!("0x00 {} {} 0x2a 0x00 return", address, invocation);
+            let (_, outcome) = execute(&dir, "libchecksum.huff", &body, &[]);
+            String::from_utf8(outcome.output).unwrap()
+        };
+        assert_eq!(string("ADDRESS_TO_CHECKSUM_STRING()"), checksum);
+        assert_eq!(string("ADDRESS_TO_HEX_STRING()"), address);
+    }
+}
+
+#[test]
+fn foundry_suites() {
+    let dir = generate("suites");
+    let out = dir.join("test");
+    fs::create_dir_all(&out).unwrap();
+    let suite = testgen::render(&dir.join("libsafemath.huff"), &out, &cancun()).unwrap();
+    let names = suite
+        .iter()
+        .map(|(name, _)| name.as_str())
+        .collect::<Vec<&str>>();
+    assert_eq!(names, ["SafemathHarness.huff", "Safemath.t.sol"]);
+    let again = testgen::render(&dir.join("libsafemath.huff"), &out, &cancun()).unwrap();
+    assert_eq!(suite, again, "suites are reproducible");
+
+    // the harness dispatches to each macro with its arguments from calldata
+    let program = Program::parse_str(&suite[0].1, &out).unwrap();
+    let mut evm = Evm::new();
+    evm.set_code(
+        addr(0x4af),
+        profile::compile(program, "MAIN", &cancun()).unwrap(),
+    );
+    let mut call = |signature: &str, a: u64, b: u64| {
+        let args = [
+            U256::from_u64(a).to_be_bytes(),
+            U256::from_u64(b).to_be_bytes(),
+        ]
+        .concat();
+        let data = [&huff::selector(signature)[..], &args].concat();
+        let outcome = common::call(&mut evm, addr(0x4af), &data);
+        (outcome.halt, hex(&outcome.output))
+    };
+    assert_eq!(
+        call("SAFE_ADD_U8(uint256,uint256)", 0xfe, 0x01).1,
+        format!("{:064x}", 0xff)
+    );
+    assert_eq!(
+        call("SAFE_ADD_U8(uint256,uint256)", 0xff, 0x01),
+        (Halt::Revert, "35278d12".into())
+    );
+
+    let test = &suite[1].1;
+    assert!(test.contains("harness = HuffDeployer.deploy(\"../"));
+    assert!(test.contains("function test_SAFE_ADD_U8() public {"));
+    assert!(test.contains(&format!(
+        "bytes4(0x{}), uint256(0xff), uint256(0x1)), false, hex\"35278d12\");",
+        hex(&huff::selector("SAFE_ADD_U8(uint256,uint256)"))
+    )));
+    assert!(test.contains("function testFuzz_SAFE_ADD_U8(uint256[2] memory values"));
+    let contracts = common::solidity::check(test).unwrap();
+    assert_eq!(contracts[0].name, "SafemathTest");
+    assert_eq!(contracts[0].bases, ["Test"]);
+    let tested = contracts[0]
+        .functions
+        .iter()
+        .filter(|f| f.starts_with("test"))
+        .count();
+    assert_eq!(tested, test.matches("    function test").count());
+}
+
+#[test]
+fn benchmarks() {
+    let dir = generate("bench");
+    // a baseline checking the sum against an operand, and one not checking it at all
+    let checked = "#define macro SAFE_ADD() = takes (2) returns (1) {\n\
+                       dup2 add dup1 dup3 gt iszero ok jumpi 0x00 dup1 revert ok: swap1 pop\n\
+                   }\n";
+    fs::write(dir.join("checked.huff"), checked).unwrap();
+    let wrapping = "#define macro SAFE_ADD() = takes (2) returns (1) { add }\n";
+    fs::write(dir.join("wrapping.huff"), wrapping).unwrap();
+    fs::copy(
+        common::manifest("bench/Checked.sol"),
+        dir.join("Checked.sol"),
+    )
+    .unwrap();
+
+    let config = |baselines: &[(&str, &str)]| {
+        let mut src = format!(
+            "[[operation]]\nname = \"checked add\"\n\
+             inputs = [[1, 2], [\"{}\", 1], [\"0x{}\", 1]]\n\
+             libhuff = {{ file = \"{}\", macro = \"SAFE_ADD_U256\" }}\n",
+            "0xfffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffe",
+            "f".repeat(64),
+            dir.join("libsafemath.huff").display(),
+        );
+        for (name, file) in baselines {
+            let source = match file.ends_with(".sol") {
+                true => {
+                    "contract = \"Checked\"\nfunction = \"add(uint256,uint256)\"\n\
+                         solidity"
+                }
+                false => "macro = \"SAFE_ADD\"\nfile",
+            };
+            let file = dir.join(file).display().to_string();
+            let baseline = format!("name = \"{}\"\n{} = \"{}\"\n", name, source, file);
+            src.push_str(&format!("[[operation.baseline]]\n{}", baseline));
+        }
+        bench::parse(&config::parse(&src).unwrap()).unwrap()
+    };
+
+    let operations = config(&[("checked", "checked.huff"), ("missing", "missing.huff")]);
+    assert_eq!(operations[0].inputs[2], [U256::MAX, U256::ONE]);
+    let measurements = bench::measure(&operations, &cancun()).unwrap();
+    let costs = measurements
+        .iter()
+        .map(|m| m.cost.clone())
+        .collect::<Vec<_>>();
+    let (size, gas) = costs[0].clone().unwrap();
+    let (checked_size, checked_gas) = costs[1].clone().unwrap();
+    assert!(costs[2]
+        .as_ref()
+        .unwrap_err()
+        .contains("missing.huff: No such file"));
+
+    // the deployed code of the library is that of the macro between its loads and stores, run
+    // on the embedded EVM for each input
+    let main = "0x24 calldataload 0x04 calldataload SAFE_ADD_U256() 0x00 mstore 0x20 0x00 return";
+    let program = wrap(&dir.join("libsafemath.huff"), main).unwrap();
+    assert_eq!(
+        size,
+        profile::compile(program, "MAIN", &cancun()).unwrap().len()
+    );
+    assert!(gas > 0 && checked_size < size);
+
+    let report = bench::markdown(&measurements);
+    let delta = |a: usize, b: usize| a as i64 - b as i64;
+    let deltas = (
+        delta(checked_size, size),
+        delta(checked_gas as usize, gas as usize),
+    );
+    let library = format!("| checked add | libhuff | {} | {} | - | - |\n", size, gas);
+    assert!(report.contains(&library), "{}", report);
+    let (bytes, used) = (checked_size, checked_gas);
+    let checked = format!(
+        "| checked | {} | {} | {:+} | {:+} |\n",
+        bytes, used, deltas.0, deltas.1
+    );
+    assert!(report.contains(&checked), "{}", report);
+    assert!(report.contains("\nSkipped:\n\n- missing of `checked add`: "));
+
+    // baselines must agree with the library on every input
+    let error = bench::measure(&config(&[("wrapping", "wrapping.huff")]), &cancun()).unwrap_err();
+    let max = format!("0x{}", "f".repeat(64));
+    assert_eq!(
+        error,
+        format!(
+            "wrapping of `checked add` differs from libhuff on [{}, 0x1]",
+            max
+        )
+    );
+
+    // Solidity baselines are compiled with `solc`, if one is installed
+    let measurements = bench::measure(&config(&[("solidity", "Checked.sol")]), &cancun()).unwrap();
+    match &measurements[1].cost {
+        Ok((size, _)) => assert!(*size > 0),
+        Err(e) => assert!(e.ends_with("not found") || e.contains("failed"), "{}", e),
+    }
+
+    // the shipped benchmarks run, and their Solidity baselines parse
+    let src = fs::read_to_string(common::manifest("bench.toml")).unwrap();
+    let operations = bench::parse(&config::parse(&src).unwrap()).unwrap();
+    let names = operations
+        .iter()
+        .map(|operation| operation.name.as_str())
+        .collect::<Vec<_>>();
+    let shipped = [
+        "checked add",
+        "checked mul",
+        "wad multiplication",
+        "wad division",
+    ];
+    assert_eq!(names, [&shipped[..], &["cast to u8"]].concat());
+    for measurement in bench::measure(&operations, &cancun()).unwrap() {
+        assert!(measurement.implementation != "libhuff" || measurement.cost.is_ok());
+    }
+    for file in ["bench/Checked.sol", "bench/Solady.sol"] {
+        common::solidity::check(&fs::read_to_string(common::manifest(file)).unwrap()).unwrap();
+    }
+}
+
+#[test]
+fn documentation() {
+    let dir = generate("docs");
+    let files = [dir.join("libfixedpoint.huff"), dir.join("libcmp.huff")];
+    let files = files.iter().map(PathBuf::as_path).collect::<Vec<&Path>>();
+    let book = docs::render(&files, &cancun()).unwrap();
+    let names = book
+        .iter()
+        .map(|(name, _)| name.as_str())
+        .collect::<Vec<&str>>();
+    assert_eq!(
+        names,
+        [
+            "book.toml",
+            "src/SUMMARY.md",
+            "src/libfixedpoint.md",
+            "src/libcmp.md"
+        ]
+    );
+
+    let summary = "# Summary\n\n- [Fixed Point Library](libfixedpoint.md)\n";
+    assert_eq!(
+        book[1].1,
+        format!("{}- [Comparison Library](libcmp.md)\n", summary)
+    );
+
+    // macros with template arguments are not measured
+    let page = &book[2].1;
+    assert!(page.starts_with("# Fixed Point Library\n\nProvides checked multiplication"));
+    assert!(page.contains("| [`MUL_SCALED(scale)`](#mul_scaled) | 2 | 1 | - | - |\n"));
+    let signature = "#define macro DIV_WAD() = takes (2) returns (1)";
+    assert!(page.contains(&format!("### `DIV_WAD`\n\n```huff\n{}\n```", signature)));
+    assert!(page.contains("- Reverts: `DivByZero()`, `Overflow()`\n"));
+    assert!(page.contains("#### Usage\n\n```huff\n#define macro MAIN()"));
+
+    let page = &book[3].1;
+    assert_eq!(page.matches("| [`MIN_U").count(), 32);
+    let stack = "- Stack: `[a, b]` to `[min]`\n";
+    assert!(page.contains(&format!(
+        "{}\nPushes the smaller of two 8 bit values.",
+        stack
+    )));
+    assert!(page.contains("- @post `result <= a`\n"));
+}
+
+/// Parses the library `file` with a `MAIN` of `body`.
+fn wrap(file: &Path, body: &str) -> Result<Program, huff::Error> {
+    let name = file.file_name().unwrap().to_str().unwrap();
+    let main = format!(
+        "#define macro MAIN() = takes (0) returns (0) {{ {} }}\n",
+        body
+    );
+    let src = format!("#include \"{}\"\n{}", name, main);
+    Program::parse_str(&src, file.parent().unwrap())
+}
+
+#[test]
+fn layouts() {
+    let dir = generate("layouts");
+    Generator::new()
+        .with_layout(Layout::Type)
+        .write_to(&dir)
+        .unwrap();
+
+    // each file of a type compiles on its own
+    for library in Library::ALL.iter().filter(|library| library.is_sized()) {
+        let types = fs::read_dir(dir.join(library.name())).unwrap();
+        let mut files = types
+            .map(|entry| entry.unwrap().path())
+            .collect::<Vec<PathBuf>>();
+        files.sort();
+        // the common items and a file per type, unsigned and signed for the bounds of `libconstants`
+        let types = if *library == Library::Constants {
+            64
+        } else {
+            32
+        };
+        assert_eq!(files.len(), types + 1, "{}", library.name());
+
+        for file in files {
+            let program = wrap(&file, "").unwrap();
+            let src = fs::read_to_string(&file).unwrap();
+            let mut macros = program
+                .macros
+                .iter()
+                .filter(|(name, definition)| definition.params.is_empty() && *name != "MAIN")
+                .map(|(name, _)| name)
+                .filter(|name| src.contains(&format!("#define macro {}(", name)))
+                .collect::<Vec<&String>>();
+            macros.sort();
+            for name in macros {
+                let invocation = format!("{}()", name);
+                let program = wrap(&file, &invocation).unwrap();
+                profile::compile(program, "MAIN", &cancun())
+                    .unwrap_or_else(|e| panic!("{}: {}: {}", file.display(), name, e));
+            }
+        }
+    }
+
+    // the index of a split library stands in for it
+    let cast = |dir: &Path| run(dir, "libcmp.huff", "CLAMP_U8()", &[U256::from_u64(0x1ff)]);
+    assert_eq!(cast(&dir), ok(&[U256::from_u64(0xff)]));
+    let u8 = fs::read_to_string(dir.join("cast/u8.huff")).unwrap();
+    assert!(u8.contains("//! # Casting Library, U8\n"));
+    assert!(u8.contains("#include \"common.huff\"\n\n/// ## U8 Mask\n"));
+    assert!(!u8.contains("U16"));
+    let safemath = fs::read_to_string(dir.join("safemath/u8.huff")).unwrap();
+    assert!(safemath.contains("#include \"common.huff\"\n#include \"../cast/u8.huff\"\n\n"));
+
+    let files = Generator::new()
+        .with_layout(Layout::Bundle)
+        .render()
+        .unwrap();
+    let bundle = &files["libhuff.huff"];
+    // of each generated library, which include the hand-written ones
+    assert_eq!(bundle.matches("#include").count(), files.len() - 2);
+    fs::write(dir.join("libhuff.huff"), bundle).unwrap();
+    assert!(wrap(&dir.join("libhuff.huff"), "").is_ok());
+}
+
+#[test]
+fn configuration() {
+    let config = config::parse(concat!(
+        "evm_version = \"paris\"\n",
+        "\n",
+        "[generate]\n",
+        "libraries = [\"cast\", \"libsafemath\"]\n",
+        "sizes = [16, 8]\n",
+        "revert = \"panic\"\n",
+        "prefix = \"LH_\"\n",
+    ))
+    .unwrap();
+    let generator = Generator::from_config(config).unwrap();
+    assert_eq!(generator.libraries(), [Library::Cast, Library::SafeMath]);
+    assert_eq!(generator.options().sizes(), [8, 16]);
+
+    let dir = generate("configuration");
+    generator.write_to(&dir).unwrap();
+    let panic = "0x4e487b71".to_string() + &"0".repeat(62) + "11";
+    assert_eq!(
+        run(&dir, "libcast.huff", "LH_TO_U8()", &[max(16)]),
+        Err(panic)
+    );
+    let add = |invocation| run(&dir, "libsafemath.huff", invocation, &[max(8), U256::ONE]);
+    assert_eq!(add("LH_SAFE_ADD_U8()"), revert("Overflow"));
+    assert_eq!(add("LH_SAFE_ADD_U16()"), ok(&[U256::from_u64(0x100)]));
+
+    // every generated macro is prefixed, where it is defined, invoked and documented
+    let safemath = fs::read_to_string(dir.join("libsafemath.huff")).unwrap();
+    assert!(safemath.contains("LH_TO_U8()") && !safemath.contains(" TO_U8()"));
+    assert!(safemath.contains("///     LH_SAFE_ADD_U8()\n"));
+    assert!(!safemath.contains("#define macro SAFE"));
+
+    let invalid = |src: &str| Generator::from_config(config::parse(src).unwrap()).unwrap_err();
+    assert_eq!(
+        invalid("[generate]\nlibraries = [\"casts\"]").to_string(),
+        "unknown library `casts`"
+    );
+    let sizes = Generator::from_config(config::parse("[generate]\nsizes = [12]").unwrap());
+    let sizes = sizes.unwrap();
+    let message = "invalid size `12`, expected a multiple of 8 from 8 to 256";
+    assert_eq!(sizes.render().unwrap_err().to_string(), message);
+    let diagnostic = &sizes.diagnostics()[0];
+    assert_eq!(
+        (diagnostic.severity, diagnostic.message.as_str()),
+        (Severity::Error, message)
+    );
+    assert_eq!(
+        diagnostic.clone().locate("[generate]\nsizes = [12]").line,
+        Some(2)
+    );
+
+    // the default sizes warn of nothing, though they include 256 and 32
+    assert!(Generator::new().diagnostics().is_empty());
+    let warnings = Generator::new().with_sizes(&[8, 32, 256]).diagnostics();
+    assert!(warnings
+        .iter()
+        .all(|warning| warning.severity == Severity::Warning));
+    assert_eq!(
+        warnings
+            .iter()
+            .map(|warning| warning.message.as_str())
+            .collect::<Vec<_>>(),
+        [
+            "the checked casts of size 256 never revert, as every word fits",
+            "`MINI_TO_U32` is no smaller than `TO_U32` for the targeted EVM version",
+        ],
+    );
+    assert_eq!(
+        invalid("[generate]\nprefix = \"0x\"").to_string(),
+        "`0x` is not a valid macro prefix"
+    );
+}
+
+#[test]
+fn naming() {
+    let config = config::parse("[generate]\nnaming = \"huffmate\"\n").unwrap();
+    let generator = Generator::from_config(config).unwrap();
+    let dir = generate("naming");
+    generator.write_to(&dir).unwrap();
+
+    // libraries move to huffmate's layout, and their includes follow them
+    let files = generator.render().unwrap();
+    assert!(files.contains_key("math/SafeMath.huff") && files.contains_key("utils/Casting.huff"));
+    assert!(!files.contains_key("libsafemath.huff"));
+    assert!(files["math/SafeMath.huff"].contains("\n#include \"../utils/Casting.huff\"\n"));
+    assert!(files["utils/Calls.huff"].contains("\n#include \"Revert.huff\"\n"));
+    // as do the includes of the usage examples, from the output directory
+    assert!(files["auth/Auth.huff"].contains("\n/// #include \"auth/Auth.huff\"\n"));
+    assert!(!files
+        .values()
+        .any(|contents| contents.contains("#include \"libauth.huff\"")));
+
+    let add = run(
+        &dir,
+        "math/SafeMath.huff",
+        "SAFE_ADD()",
+        &[max(256), U256::ONE],
+    );
+    assert_eq!(add, revert("Overflow"));
+    let add = run(
+        &dir,
+        "math/SafeMath.huff",
+        "SAFE_ADD_U8()",
+        &[max(8), U256::ZERO],
+    );
+    assert_eq!(add, ok(&[max(8)]));
+    let guard = |code: &str| run(&dir, "utils/Guard.huff", code, &[]);
+    assert_eq!(guard("LOCK(0x01) UNLOCK(0x01) LOCK(0x01)"), ok(&[]));
+    assert_eq!(guard("LOCK(0x01) LOCK(0x01)"), revert("Reentrancy"));
+
+    let bundle = Generator::new()
+        .with_naming(Huffmate)
+        .with_layout(Layout::Bundle);
+    assert!(bundle.render().unwrap()["libhuff.huff"].contains("#include \"math/SafeMath.huff\"\n"));
+
+    // conventions are pluggable, but may not give two items the same name
+    #[derive(Debug)]
+    struct Narrow;
+    impl Naming for Narrow {
+        fn name(&self) -> &str {
+            "narrow"
+        }
+
+        fn macro_name(&self, name: &str) -> Option<String> {
+            (name == "SAFE_ADD_U16").then(|| "SAFE_ADD_U8".to_string())
+        }
+    }
+    let narrow = Generator::new()
+        .with_library(Library::Cmp)
+        .with_naming(Narrow);
+    assert_eq!(
+        narrow.render().unwrap_err().to_string(),
+        "naming `narrow` renames both `SAFE_ADD_U16` and `SAFE_ADD_U8` to `SAFE_ADD_U8`"
+    );
+
+    // configured conventions apply to every library alike
+    let config = config::parse(concat!(
+        "[generate.naming]\n",
+        "case = \"camel\"\n",
+        "cast_prefix = \"CAST\"\n",
+        "error_suffix = \"Error\"\n",
+    ))
+    .unwrap();
+    let convention = Generator::from_config(config).unwrap();
+    let files = convention.render().unwrap();
+    // names are renamed as identifiers, in code spans and usage examples, realigning the stack
+    // comments, while the case leaves the macros it would name after an opcode
+    assert!(files["librandom.huff"].contains("#define macro PREVRANDAO() = takes (0) returns (1)"));
+    assert!(files["libcast.huff"].contains("\n/// ## Overflow Error\n"));
+    assert!(files["libcast.huff"].contains("\n//! - `castTypename` - Downcasts"));
+    assert!(
+        files["libcast.huff"].contains("\n    u8Mask()                // [mask, value, value]\n")
+    );
+    assert!(files["libsafemath.huff"].contains("\n///     safeAddU8()\n"));
+    let dir = generate("naming-convention");
+    let libraries = [Library::Cast, Library::SafeMath];
+    convention
+        .with_libraries(&libraries)
+        .write_to(&dir)
+        .unwrap();
+    let cast = |invocation| run(&dir, "libcast.huff", invocation, &[max(16)]);
+    assert_eq!(cast("castU8()"), revert("OverflowError"));
+    assert_eq!(cast("unsafeCastU8()"), ok(&[max(8)]));
+    assert_eq!(cast("miniCastU32()"), ok(&[max(16)]));
+    let safemath = fs::read_to_string(dir.join("libsafemath.huff")).unwrap();
+    assert!(safemath.contains("#define macro safeAddU8() = takes (2) returns (1) {\n"));
+    assert!(safemath.contains(" castU8()") && !safemath.contains("TO_U8"));
+    assert!(safemath.contains("#define error UnderflowError()"));
+
+    let invalid = config::parse("[generate]\nnaming = \"solady\"").unwrap();
+    assert_eq!(
+        Generator::from_config(invalid).unwrap_err().to_string(),
+        "`generate.naming` must be one of \"libhuff\", \"huffmate\", or a table"
+    );
+}
+
+#[test]
+fn provenance() {
+    let generator = Generator::new().with_library(Library::Cmp).with_sizes(&[8]);
+    let files = generator.render().unwrap();
+    assert_eq!(files, generator.render().unwrap());
+
+    let header = files["libcmp.huff"].lines().take(4).collect::<Vec<&str>>();
+    assert_eq!(
+        header[0],
+        format!("// @generated by huffgen {}", env!("CARGO_PKG_VERSION"))
+    );
+    assert_eq!(
+        header[1],
+        format!("// @library libcmp.huff {}", env!("CARGO_PKG_VERSION"))
+    );
+    assert!(header[2].starts_with("// @config 0x") && header[2].len() == 77);
+    assert_eq!(header[3], "");
+
+    // the config hash follows the inputs
+    let hash = |generator: Generator| {
+        let files = generator.render().unwrap();
+        files["libcmp.huff"].lines().nth(2).unwrap().to_string()
+    };
+    let paris = config::parse("evm_version = \"paris\"").unwrap();
+    assert_ne!(
+        hash(generator.clone()),
+        hash(generator.clone().with_sizes(&[16]))
+    );
+    assert_ne!(
+        hash(generator.clone()),
+        hash(generator.clone().with_config(paris))
+    );
+
+    let stamped = generator.clone().with_timestamp(1710338135);
+    let contents = &stamped.render().unwrap()["libcmp.huff"];
+    assert_eq!(
+        contents.lines().nth(3),
+        Some("// @timestamp 2024-03-13T13:55:35Z")
+    );
+
+    // timestamps are not checked
+    let dir = common::temp_dir("provenance");
+    generator.clone().with_timestamp(0).write_to(&dir).unwrap();
+    assert!(stamped.check(&dir).unwrap().is_empty());
+    // both `libcmp.huff` and the `libcast.huff` it includes follow the sizes
+    assert_eq!(stamped.with_sizes(&[16]).check(&dir).unwrap().len(), 2);
+}
+
+#[test]
+fn evm_versions() {
+    let render = |version| Generator::new().with_evm_version(version).render().unwrap();
+    let (paris, shanghai, cancun) = (
+        render(EvmVersion::Paris),
+        render(EvmVersion::Shanghai),
+        render(EvmVersion::Cancun),
+    );
+
+    assert!(!paris.contains_key("libblob.huff") && !shanghai.contains_key("libblob.huff"));
+    assert!(cancun.contains_key("libblob.huff"));
+
+    // two byte `0x00` pushes make the literal the smallest mask of 40 bits before Shanghai
+    let mini_mask = "#define macro MINI_U40_MASK() = takes (0) returns (1) {";
+    assert!(paris["libcast.huff"].contains(&format!("{} 0xffffffffff }}", mini_mask)));
+    assert!(shanghai["libcast.huff"].contains(&format!("{} 0x00 not 0xd8 shr }}", mini_mask)));
+}
+
+#[test]
+fn dispatchers() {
+    let mut src = String::from("[generate]\nlibraries = [\"dispatchers\"]\n");
+    for strategy in ["linear", "binary", "jump_table"] {
+        src.push_str(&format!(
+            concat!(
+                "\n[[dispatcher]]\nname = \"{}\"\nstrategy = \"{}\"\nfunctions = [\n",
+                "    {{ signature = \"transfer(address,uint256)\", macro = \"TRANSFER\" }},\n",
+                "    {{ signature = \"balanceOf(address)\", macro = \"BALANCE_OF\" }},\n",
+                "    {{ signature = \"approve(address,uint256)\", macro = \"APPROVE\" }},\n",
+                "    {{ signature = \"totalSupply()\", macro = \"TOTAL_SUPPLY\" }},\n",
+                "]\n",
+            ),
+            strategy.to_uppercase(),
+            strategy,
+        ));
+    }
+
+    // the function macros are defined by the contract, not the generated library
+    let generator = Generator::from_config(config::parse(&src).unwrap()).unwrap();
+    let dir = generate("dispatchers");
+    generator.write_to(&dir).unwrap();
+    let functions = concat!(
+        "#include \"libdispatchers.huff\"\n",
+        "#define macro TRANSFER() = takes (0) returns (0) { 0x01 stop }\n",
+        "#define macro BALANCE_OF() = takes (0) returns (0) { 0x02 stop }\n",
+        "#define macro APPROVE() = takes (0) returns (0) { 0x03 stop }\n",
+        "#define macro TOTAL_SUPPLY() = takes (0) returns (0) { 0x04 stop }\n",
+    );
+    fs::write(dir.join("functions.huff"), functions).unwrap();
+
+    let calls = [
+        ("transfer(address,uint256)", Some(1)),
+        ("balanceOf(address)", Some(2)),
+        ("approve(address,uint256)", Some(3)),
+        ("totalSupply()", Some(4)),
+        ("allowance(address,address)", None),
+    ];
+    for strategy in ["LINEAR", "BINARY", "JUMP_TABLE"] {
+        let body = format!("DISPATCH_{}() 0x00 0x00 revert", strategy);
+        let (_, code) = compile(&dir, "functions.huff", &body);
+        for (signature, function) in calls {
+            let mut data = huff::selector(signature).to_vec();
+            data.extend([0; 64]);
+            let outcome = transact(&mut Evm::new(), code.clone(), &data);
+            match function {
+                Some(function) => {
+                    assert_eq!(outcome.halt, Halt::Stop, "{} {}", strategy, signature);
+                    assert_eq!(outcome.stack, [U256::from_u64(function)]);
+                }
+                None => assert_eq!(outcome.halt, Halt::Revert, "{} {}", strategy, signature),
+            }
+        }
+    }
+}
+
+#[test]
+fn dispatcher_ether() {
+    let mut src = String::from("[generate]\nlibraries = [\"dispatchers\"]\n");
+    for strategy in ["linear", "binary", "jump_table"] {
+        src.push_str(&format!(
+            concat!(
+                "\n[[dispatcher]]\nname = \"{}\"\nstrategy = \"{}\"\n",
+                "ether = \"reject\"\nreceive = \"RECEIVE\"\nfunctions = [\n",
+                "    {{ signature = \"a()\", macro = \"A\" }},\n",
+                "    {{ signature = \"b()\", macro = \"B\", ether = \"payable\" }},\n",
+                "]\n",
+            ),
+            strategy.to_uppercase(),
+            strategy,
+        ));
+    }
+    src.push_str(concat!(
+        "\n[[dispatcher]]\nname = \"ONLY\"\nether = \"receive_only\"\n",
+        "functions = [{ signature = \"a()\", macro = \"A\" }]\n",
+    ));
+
+    let generator = Generator::from_config(config::parse(&src).unwrap()).unwrap();
+    let dir = generate("dispatcher-ether");
+    generator.write_to(&dir).unwrap();
+    let functions = concat!(
+        "#include \"libdispatchers.huff\"\n",
+        "#define macro A() = takes (0) returns (0) { 0x01 stop }\n",
+        "#define macro B() = takes (0) returns (0) { 0x02 stop }\n",
+        "#define macro RECEIVE() = takes (0) returns (0) { 0x03 stop }\n",
+    );
+    fs::write(dir.join("functions.huff"), functions).unwrap();
+
+    let rejected = Err(hex(&huff::selector("EtherNotAccepted()")));
+    let (a, b) = (huff::selector("a()"), huff::selector("b()"));
+    let n = U256::from_u64;
+
+    // each function rejects or accepts ether by its own policy, and the receive macro takes empty
+    // calldata whatever they are
+    for strategy in ["LINEAR", "BINARY", "JUMP_TABLE"] {
+        let body = format!("DISPATCH_{}() 0x00 0x00 revert", strategy);
+        let (_, code) = compile(&dir, "functions.huff", &body);
+        assert_eq!(send(&code, &a, 0), Ok(vec![n(1)]), "{}", strategy);
+        assert_eq!(send(&code, &a, 5), rejected, "{}", strategy);
+        assert_eq!(send(&code, &b, 5), Ok(vec![n(2)]), "{}", strategy);
+        assert_eq!(send(&code, &[], 5), Ok(vec![n(3)]), "{}", strategy);
+    }
+
+    let (_, code) = compile(&dir, "functions.huff", "DISPATCH_ONLY() 0x00 0x00 revert");
+    assert_eq!(send(&code, &[], 5), Ok(vec![]));
+    assert_eq!(send(&code, &a, 0), Ok(vec![n(1)]));
+    assert_eq!(send(&code, &a, 5), rejected);
+
+    let libdispatchers = fs::read_to_string(dir.join("libdispatchers.huff")).unwrap();
+    assert!(libdispatchers.contains("/// | `0x0dbe671f` | `a()` | `A` | `reject` |\n"));
+    assert!(libdispatchers.contains("/// | `0x4df7e3d0` | `b()` | `B` | `payable` |\n"));
+    assert!(libdispatchers
+        .contains("Empty calldata takes the receive path, which evaluates `RECEIVE`."));
+}
+
+#[test]
+fn abi_dispatcher_ether() {
+    let dir = common::temp_dir("abi-dispatcher");
+    let abi = concat!(
+        "[{\"type\": \"function\", \"name\": \"a\", \"stateMutability\": \"nonpayable\"},\n",
+        " {\"type\": \"function\", \"name\": \"b\", \"stateMutability\": \"payable\"},\n",
+        " {\"type\": \"receive\", \"stateMutability\": \"payable\"}]\n",
+    );
+    let main = huffgen::abi::render_dispatcher(abi, "C", "linear", &cancun()).unwrap();
+    let contract = format!(
+        "{}\n{}\n{}\n{}\n",
+        main,
+        "#define macro A() = takes (0) returns (0) { 0x01 stop }",
+        "#define macro B() = takes (0) returns (0) { 0x02 stop }",
+        "#define macro RECEIVE() = takes (0) returns (0) { 0x03 stop }",
+    );
+    let program = Program::parse_str(&contract, &dir).unwrap();
+    let code = profile::compile(program, "MAIN", &cancun()).unwrap();
+
+    // the payable function does not make the others payable, nor drop the receive function
+    let n = U256::from_u64;
+    let (a, b) = (huff::selector("a()"), huff::selector("b()"));
+    assert_eq!(send(&code, &a, 0), Ok(vec![n(1)]));
+    assert_eq!(
+        send(&code, &a, 5),
+        Err(hex(&huff::selector("EtherNotAccepted()")))
+    );
+    assert_eq!(send(&code, &b, 5), Ok(vec![n(2)]));
+    assert_eq!(send(&code, &[], 5), Ok(vec![n(3)]));
+}
+
+#[test]
+fn yul_target() {
+    let generator = Generator::new()
+        .with_target(Target::Yul)
+        .with_sizes(&[8, 256]);
+    let files = generator.render().unwrap();
+    assert_eq!(files.keys().collect::<Vec<_>>(), ["libcast.yul"]);
+
+    let yul = &files["libcast.yul"];
+    assert!(yul.contains("// @library libcast.yul "));
+    assert!(yul.contains("function toU8(value) -> result {\n    if gt(value, 0xff)"));
+    assert!(yul.contains("function toU8x3(a, b, c) -> result, bResult, cResult {"));
+    assert!(yul.contains("function unsafeToAddress(value) -> result {"));
+    assert!(!yul.contains("function toU16("));
+
+    // the conditions of the functions are those of the macros, in the names of the functions
+    let huff = Generator::new().with_sizes(&[8, 256]).render().unwrap();
+    let (pre, post) = ("/// @pre value <= 0xff\n", "/// @post result == value\n");
+    assert!(huff["libcast.huff"].contains(&format!("{}{}#define macro TO_U8()", pre, post)));
+    assert!(yul.contains(&format!("{}{}function toU8(", pre, post)));
+    assert!(yul.contains("/// @post maskedValue == value & 0xff\nfunction tryToU8("));
+
+    let error = |generator: Generator| generator.render().unwrap_err().to_string();
+    let yul = || Generator::new().with_target(Target::Yul);
+    let safemath = error(yul().with_library(Library::SafeMath));
+    assert_eq!(safemath, "`libsafemath.huff` has no yul target");
+    assert_eq!(
+        error(yul().with_layout(Layout::Bundle)),
+        "the yul target has no `bundle` layout"
+    );
+}
+
+#[test]
+fn summaries() {
+    let dir = common::temp_dir("summaries");
+    let generator = Generator::new().with_sizes(&[8, 32]);
+    let summaries = generator.write_summarized(&dir).unwrap();
+
+    // libraries render in parallel, into the same files in the same order
+    let files = generator.render().unwrap();
+    let paths = summaries
+        .iter()
+        .map(|file| file.path.clone())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        paths,
+        files.keys().map(|name| dir.join(name)).collect::<Vec<_>>()
+    );
+    for file in &summaries {
+        let contents = fs::read_to_string(&file.path).unwrap();
+        assert_eq!(file.bytes, contents.len());
+        let macros = contents.matches("\n#define macro ").count()
+            + contents.matches("\n#define fn ").count();
+        assert_eq!(file.definitions, macros, "{}", file.path.display());
+    }
+    assert_eq!(generator.render().unwrap(), files);
+}
+
+#[test]
+fn empty_directories() {
+    let dir = common::temp_dir("empty");
+
+    // any selection generates alongside the libraries it includes, generated or hand-written
+    let selections: [&[&str]; 4] = [
+        &[],
+        &["--lib", "payment"],
+        &["--lib", "escrow"],
+        &["--lib", "account", "--lib", "royalty"],
+    ];
+    for (i, selection) in selections.into_iter().enumerate() {
+        let out = format!("out{}", i);
+        let args = [&["generate", "--no-timestamp", "--out", &out], selection].concat();
+        let (status, stderr) = huffgen(&dir, &args);
+        assert_eq!(status, Some(0), "{:?}: {}", args, stderr);
+
+        for entry in fs::read_dir(dir.join(&out)).unwrap() {
+            let name = entry.unwrap().file_name().into_string().unwrap();
+            let src = format!("#include \"{}\"\n", name);
+            Program::parse_str(&src, &dir.join(&out)).unwrap();
+        }
+    }
+    // differential tests are only written when asked for, wherever they are asked for
+    assert!(!dir.join("differential").exists());
+    let args = [
+        "generate",
+        "--lib",
+        "cast",
+        "--out",
+        "out4",
+        "--differential",
+        "tests/cast",
+    ];
+    assert_eq!(huffgen(&dir, &args), (Some(0), String::new()));
+    let harness = fs::read_to_string(dir.join("tests/cast/CastHarness.huff")).unwrap();
+    assert!(harness.contains("#include \"../../out4/libcast.huff\"\n"));
+    let program = Program::parse_str(&harness, &dir.join("tests/cast")).unwrap();
+    assert!(profile::compile(program, "MAIN", &cancun()).is_ok());
+
+    for (out, file) in [
+        ("out1", "libmapping.huff"),
+        ("out1", "libreentrancy.huff"),
+        ("out2", "libpayment.huff"),
+        ("out3", "libkeccak.huff"),
+        ("out3", "libsignature.huff"),
+    ] {
+        assert!(dir.join(out).join(file).exists(), "{}/{}", out, file);
+    }
+
+    // as does each layout of the huffmate naming, nested in directories
+    let huffmate = common::temp_dir("empty-huffmate");
+    fs::write(
+        huffmate.join("libhuff.toml"),
+        "[generate]\nnaming = \"huffmate\"\n",
+    )
+    .unwrap();
+    let (status, stderr) = huffgen(&huffmate, &["generate", "--no-timestamp", "--out", "out"]);
+    assert_eq!(status, Some(0), "{}", stderr);
+    let payment = huffmate.join("out/utils/Payment.huff");
+    assert!(fs::read_to_string(&payment)
+        .unwrap()
+        .contains("#include \"../libreentrancy.huff\"\n"));
+    assert!(wrap(&payment, "").is_ok());
+
+    // and libraries using opcodes the profile denies fail before any file is written
+    let denied = common::temp_dir("empty-denied");
+    fs::write(
+        denied.join("libhuff.toml"),
+        "[opcodes]\ndeny = [\"caller\"]\n",
+    )
+    .unwrap();
+    let args = [
+        "generate",
+        "--no-timestamp",
+        "--deny-warnings",
+        "--out",
+        "out",
+    ];
+    let (status, stderr) = huffgen(&denied, &args);
+    assert_eq!(status, Some(1));
+    assert!(stderr.contains("uses `caller`"), "{}", stderr);
+    // failing with the reason why after the warnings
+    let reason = stderr.lines().last().unwrap();
+    let warnings = stderr.matches("warning: ").count();
+    let expected = format!("error: aborting due to {} warnings denied by", warnings);
+    assert_eq!(reason, format!("{} `--deny-warnings`", expected));
+    assert!(!denied.join("out").exists());
+}
+
+#[test]
+fn switches() {
+    let src = concat!(
+        "[generate]\n",
+        "libraries = [\"dispatchers\"]\n",
+        "\n",
+        "[[switch]]\n",
+        "name = \"Op\"\n",
+        "default = \"UNKNOWN\"\n",
+        "cases = [\n",
+        "    { value = 1, macro = \"ONE\" },\n",
+        "    { value = 2, macro = \"TWO\" },\n",
+        "    { value = 4, macro = \"FOUR\" },\n",
+        "]\n",
+        "\n",
+        "[[switch]]\n",
+        "name = \"Sparse\"\n",
+        "cases = [{ value = 65536, macro = \"ONE\" }, { value = 536870912, macro = \"TWO\" }]\n",
+    );
+    let generator = Generator::from_config(config::parse(src).unwrap()).unwrap();
+    let dir = generate("switches");
+    generator.write_to(&dir).unwrap();
+    let cases = concat!(
+        "#include \"libdispatchers.huff\"\n",
+        "#define macro ONE() = takes (0) returns (1) { 0x01 }\n",
+        "#define macro TWO() = takes (0) returns (1) { 0x02 }\n",
+        "#define macro FOUR() = takes (0) returns (1) { 0x04 }\n",
+        "#define macro UNKNOWN() = takes (0) returns (1) { 0xff }\n",
+    );
+    fs::write(dir.join("cases.huff"), cases).unwrap();
+
+    // the close values take a table of their range, checking only its bounds
+    let libdispatchers = fs::read_to_string(dir.join("libdispatchers.huff")).unwrap();
+    assert!(libdispatchers.contains("The `dense` jump table is indexed by `value - 0x01`"));
+    assert!(libdispatchers.contains("    case_0 case_1 default case_2\n"));
+    let op = |value: U256| run(&dir, "cases.huff", "SWITCH_Op()", &[value]);
+    let word = U256::from_u64;
+    assert_eq!(op(word(1)), ok(&[word(1)]));
+    assert_eq!(op(word(2)), ok(&[word(2)]));
+    assert_eq!(op(word(4)), ok(&[word(4)]));
+    for value in [U256::ZERO, word(3), word(5), max(256)] {
+        assert_eq!(op(value), ok(&[word(0xff)]));
+    }
+
+    // the distant values take a table indexed by their bits, checking the value of the case
+    assert!(libdispatchers.contains("The `indexed` jump table is indexed by `(value >> 16) % 2`"));
+    let sparse = |value: U256| run(&dir, "cases.huff", "SWITCH_Sparse()", &[value]);
+    assert_eq!(sparse(word(65536)), ok(&[word(1)]));
+    assert_eq!(sparse(word(536870912)), ok(&[word(2)]));
+    for value in [U256::ZERO, word(65537), word(196608), max(256)] {
+        assert_eq!(sparse(value), ok(&[]));
+    }
+
+    let invalid = |cases: &str| {
+        let src = format!("[[switch]]\nname = \"Op\"\ncases = [{}]", cases);
+        let generator = Generator::from_config(config::parse(&src).unwrap()).unwrap();
+        Library::Dispatchers
+            .render(generator.options())
+            .unwrap_err()
+            .to_string()
+    };
+    assert_eq!(
+        invalid("{ value = 1, macro = \"ONE\" }, { value = 1, macro = \"TWO\" }"),
+        "duplicate value 1 in switch `Op`"
+    );
+    assert_eq!(
+        invalid("{ value = 4294967296, macro = \"ONE\" }"),
+        "case of switch `Op` requires a `value` below 2^32"
+    );
+}
+
+#[test]
+fn assemble_deployments() {
+    let dir = common::temp_dir("assemble");
+    let src = "#define error Nope()\n\
+               #define macro CONSTRUCTOR() = takes (0) returns (0) { 0x2a 0x00 sstore }\n\
+               #define macro GET() = takes (0) returns (0) {\n\
+                   0x00 sload 0x00 mstore 0x20 0x00 return\n\
+               }\n\
+               #define macro FAIL() = takes (0) returns (0) {\n\
+                   __ERROR(Nope) 0x00 mstore 0x04 0x00 revert\n\
+               }\n";
+    fs::write(dir.join("lib.huff"), src).unwrap();
+    let assemble = |args: &[&str]| {
+        let output = std::process::Command::new(env!("CARGO_BIN_EXE_huffgen"))
+            .arg("assemble")
+            .args(args)
+            .current_dir(&dir)
+            .output()
+            .unwrap();
+        String::from_utf8(output.stdout).unwrap()
+    };
+
+    // the deployment runs the constructor, then returns the runtime code
+    let runtime = assemble(&["lib.huff", "GET"]);
+    let initcode = assemble(&["lib.huff", "GET", "--deploy"]);
+    let bytes = |hex: &str| -> Vec<u8> {
+        let hex = hex.trim().trim_start_matches("0x");
+        let byte = |i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap();
+        (0..hex.len()).step_by(2).map(byte).collect()
+    };
+    let mut evm = Evm::new();
+    let (outcome, address) = evm.create(addr(common::CALLER), &bytes(&initcode), 1_000_000);
+    assert_eq!(outcome.halt, Halt::Return);
+    assert_eq!(evm.account(address).code, bytes(&runtime));
+    assert_eq!(evm.sload(address, U256::ZERO), U256::from_u64(0x2a));
+
+    // and `--call` deploys it on the embedded EVM and calls it
+    let deployed = format!("address: 0x{}\n", hex(&address));
+    let stored = format!("returndata: 0x{:064x}\n", 0x2a);
+    let report = assemble(&["lib.huff", "GET", "--call", "0x"]);
+    assert!(
+        report.starts_with(&format!("{}stack: []\n{}gas: ", deployed, stored)),
+        "{}",
+        report
+    );
+    let report = assemble(&["lib.huff", "FAIL", "--call", "0x12"]);
+    assert!(
+        report.starts_with(&format!("{}reverted: Nope()\n", deployed)),
+        "{}",
+        report
+    );
+
+    // a contract defining its own `MAIN` assembles and lists from it, and a call halting with an
+    // exception fails
+    let contract = "#define macro MAIN() = takes (0) returns (0) {\n    pop\n}\n";
+    fs::write(dir.join("main.huff"), contract).unwrap();
+    assert_eq!(assemble(&["main.huff", "MAIN"]), "0x50\n");
+    assert_eq!(
+        huffgen(&dir, &["listing", "main.huff", "MAIN"]),
+        (Some(0), String::new())
+    );
+    let (status, _) = huffgen(&dir, &["assemble", "main.huff", "MAIN", "--call", "0x"]);
+    assert_eq!(status, Some(1));
+    assert_eq!(
+        huffgen(&dir, &["assemble", "lib.huff", "GET", "--call", "0x"]).0,
+        Some(0)
+    );
+}
+
+#[test]
+fn compile_errors() {
+    let error = |body: &str| {
+        let src = format!(
+            "#define table T {{ 0x{} }}\n\
+             #define macro MAIN() = takes (0) returns (0) {{\n    {}\n}}\n",
+            "00".repeat(0x10000),
+            body
+        );
+        Program::parse_str(&src, Path::new("."))
+            .and_then(|program| huff::compile(&program, "MAIN", true))
+            .unwrap_err()
+            .to_string()
+    };
+
+    // literals and sizes too large for their encoding fail rather than panic or truncate
+    let takes = "#define macro A() = takes (0xfffffffffffffffffffff) returns (0) {}\n";
+    assert_eq!(
+        Program::parse_str(takes, Path::new("."))
+            .unwrap_err()
+            .to_string(),
+        "./<input>:1: number too large: Hex(\"fffffffffffffffffffff\")"
+    );
+    assert_eq!(
+        error(&format!("__RIGHTPAD(\"{}\")", "ff".repeat(33))),
+        "`__RIGHTPAD` of 33 bytes, more than a word"
+    );
+    assert_eq!(
+        error(&format!("0x{}", "ff".repeat(33))),
+        format!("hex literal 0x{} overflows 256 bits", "ff".repeat(33))
+    );
+    assert_eq!(
+        error("__tablesize(T)"),
+        "table `T` of 65536 bytes is too large for `__tablesize`"
+    );
+}
+
+#[test]
+fn command_errors() {
+    let dir = common::temp_dir("commands");
+    fs::write(dir.join("libhuff.toml"), "evm_version = \"london\"\n").unwrap();
+    fs::write(dir.join("lib.huff"), "").unwrap();
+    let abi = "[{\"type\": \"function\", \"name\": \"f\", \"inputs\": []}]";
+    fs::write(dir.join("abi.json"), abi).unwrap();
+
+    // an invalid config fails each command with its error, located in the config
+    let commands: [&[&str]; 8] = [
+        &["generate", "--no-timestamp"],
+        &["assemble", "lib.huff", "A"],
+        &["listing", "lib.huff"],
+        &["dispatcher", "--abi", "abi.json"],
+        &["report", "lib.huff"],
+        &["gen-tests", "lib.huff", "--out", "test"],
+        &["docs", "lib.huff", "--out", "book"],
+        &["repl", "lib.huff"],
+    ];
+    let error = "`evm_version` must be one of \"paris\", \"shanghai\", \"cancun\"";
+    for args in commands {
+        let expected = (
+            Some(1),
+            format!("error: {}\n  --> libhuff.toml:1 `evm_version`\n", error),
+        );
+        assert_eq!(huffgen(&dir, args), expected, "{:?}", args);
+    }
+
+    // as do paths that cannot be read or written
+    fs::remove_file(dir.join("libhuff.toml")).unwrap();
+    let layout = "slot = 0x00\nfields = [{ name = \"a\", bits = 8 }]\n";
+    fs::write(dir.join("layout.toml"), layout).unwrap();
+    let missing = "No such file or directory (os error 2)";
+    assert_eq!(
+        huffgen(&dir, &["pack", "--layout", "missing.toml"]),
+        (Some(1), format!("error: missing.toml: {}\n", missing)),
+    );
+    assert_eq!(
+        huffgen(
+            &dir,
+            &[
+                "pack",
+                "--layout",
+                "layout.toml",
+                "--out",
+                "missing/lib.huff"
+            ]
+        ),
+        (Some(1), format!("error: missing/lib.huff: {}\n", missing)),
+    );
+    assert_eq!(
+        huffgen(&dir, &["dispatcher", "--abi", "missing.json"]),
+        (Some(1), format!("error: missing.json: {}\n", missing)),
+    );
+    assert_eq!(
+        huffgen(
+            &dir,
+            &[
+                "dispatcher",
+                "--abi",
+                "abi.json",
+                "--out",
+                "missing/main.huff"
+            ]
+        ),
+        (Some(1), format!("error: missing/main.huff: {}\n", missing)),
+    );
+    fs::write(dir.join("out"), "").unwrap();
+    let library = "#define macro A() = takes (0) returns (1) { 0x01 }\n";
+    fs::write(dir.join("lib.huff"), library).unwrap();
+    assert_eq!(
+        huffgen(&dir, &["gen-tests", "lib.huff", "--out", "out"]),
+        (
+            Some(1),
+            "error: out/Harness.huff: File exists (os error 17)\n".to_string()
+        ),
+    );
+    let docs = format!("error: src: {}, give the libraries to document\n", missing);
+    assert_eq!(huffgen(&dir, &["docs"]), (Some(1), docs));
+}
+
+/// Runs the `huffgen` binary with `args` in `dir`, returning its exit code and standard error.
+fn huffgen(dir: &Path, args: &[&str]) -> (Option<i32>, String) {
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_huffgen"))
+        .args(args)
+        .current_dir(dir)
+        .stdin(std::process::Stdio::null())
+        .output()
+        .unwrap();
+    (
+        output.status.code(),
+        String::from_utf8(output.stderr).unwrap(),
+    )
+}
@@ -0,0 +1,260 @@
+//! Checks structural invariants of the libraries generated for a selection of configs: every macro
+//! their API documents is defined, every mask is as wide as its type, and `MINI_` casts are only
+//! emitted from 32 bits.
+
+mod common;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use huffgen::evm::{addr, Evm, Halt};
+use huffgen::huff::Program;
+use huffgen::profile::{self, Profile};
+use huffgen::u256::U256;
+use huffgen::{config, Generator};
+
+/// The configs checked, all of them generating every library.
+const CONFIGS: [&str; 4] = [
+    "",
+    "evm_version = \"paris\"",
+    "evm_version = \"cancun\"",
+    concat!(
+        "[generate]\nsizes = [8, 16, 24, 32, 40]\n\n",
+        "[[flags]]\nname = \"Market\"\nflags = [{ name = \"PAUSED\" }]",
+    ),
+];
+
+/// Words of the API lists standing for the names of the generated items.
+const PLACEHOLDERS: [&str; 10] = [
+    "TYPENAME",
+    "BYTESNAME",
+    "UN",
+    "IN",
+    "N",
+    "T",
+    "D",
+    "FLAGS",
+    "FLAG",
+    "UNIT",
+];
+
+/// A config with its generated libraries, written to a temporary directory.
+struct Generated {
+    config: config::Table,
+    dir: PathBuf,
+    files: Vec<(String, Program)>,
+}
+
+fn generate(name: &str, index: usize, src: &str) -> Generated {
+    let config = config::parse(src).unwrap();
+    let dir = common::temp_dir(&format!("{}-{}", name, index));
+
+    let files = Generator::from_config(config.clone())
+        .unwrap()
+        .render()
+        .unwrap();
+    for (file, contents) in &files {
+        fs::write(dir.join(file), contents).unwrap();
+    }
+    let files = files
+        .iter()
+        .map(|(file, contents)| {
+            let program = Program::parse_str(contents, &dir)
+                .unwrap_or_else(|e| panic!("{} does not parse: {}", file, e.msg));
+            (file.clone(), program)
+        })
+        .collect();
+
+    Generated { config, dir, files }
+}
+
+/// Generates every config of `CONFIGS`.
+fn generated(name: &str) -> Vec<Generated> {
+    CONFIGS
+        .iter()
+        .enumerate()
+        .map(|(index, src)| generate(name, index, src))
+        .collect()
+}
+
+/// The names of the `## API` list of `contents`.
+fn api(contents: &str) -> Vec<&str> {
+    contents
+        .lines()
+        .filter_map(|line| line.strip_prefix("//! - `"))
+        .filter_map(|line| line.split('`').next())
+        .collect()
+}
+
+/// Whether `name` is an instance of the documented `pattern`, its placeholder words standing for
+/// any word, and its words ending in a single letter placeholder for any number.
+fn instance(pattern: &str, name: &str) -> bool {
+    let patterns = pattern.split('_').collect::<Vec<&str>>();
+    let words = name.split('_').collect::<Vec<&str>>();
+
+    patterns.len() == words.len()
+        && patterns.iter().zip(&words).all(|(pattern, word)| {
+            if pattern == word || PLACEHOLDERS.contains(pattern) {
+                return !word.is_empty();
+            }
+            match pattern.split_at(pattern.len() - 1) {
+                (prefix, letter) if !prefix.is_empty() && PLACEHOLDERS.contains(&letter) => word
+                    .strip_prefix(prefix)
+                    .is_some_and(|n| !n.is_empty() && n.bytes().all(|b| b.is_ascii_digit())),
+                _ => false,
+            }
+        })
+}
+
+/// Runs the macro `name` of `program`, taking no arguments, returning its single output.
+fn output(program: &Program, profile: &Profile, name: &str) -> U256 {
+    let mut program = program.clone();
+    let src = format!(
+        "#define macro MAIN() = takes (0) returns (0) {{ {}() }}",
+        name
+    );
+    let main = Program::parse_str(&src, Path::new("."))
+        .unwrap()
+        .macros
+        .remove("MAIN")
+        .unwrap();
+    program.macros.insert("MAIN".to_string(), main);
+
+    let code = profile::compile(program, "MAIN", profile)
+        .unwrap_or_else(|e| panic!("{} does not compile: {}", name, e.msg));
+    let mut evm = Evm::new();
+    evm.set_code(addr(0xc0de), code);
+    let outcome = common::call(&mut evm, addr(0xc0de), &[]);
+
+    assert_eq!(outcome.halt, Halt::Stop, "{} halts", name);
+    assert_eq!(outcome.stack.len(), 1, "{} returns one word", name);
+    outcome.stack[0]
+}
+
+/// The number of `ff` bytes of the mask `name`, and whether they are leading rather than trailing,
+/// if it is the mask of a type.
+fn mask_bytes(name: &str) -> Option<(usize, bool)> {
+    let bits = |size: &str| size.parse::<usize>().ok().filter(|bits| bits % 8 == 0);
+
+    if name == "ADDRESS_MASK" {
+        return Some((20, false));
+    }
+    let name = name.strip_suffix("_MASK")?;
+    let name = name.strip_prefix("MINI_").unwrap_or(name);
+    if let Some(size) = name.strip_prefix("BYTES") {
+        return size.parse().ok().map(|bytes| (bytes, true));
+    }
+    name.strip_prefix('U')
+        .and_then(bits)
+        .map(|bits| (bits / 8, false))
+}
+
+#[test]
+fn documented_macros_exist() {
+    for generated in generated("api") {
+        for (file, program) in &generated.files {
+            let contents = fs::read_to_string(generated.dir.join(file)).unwrap();
+            let names = program
+                .macros
+                .keys()
+                .chain(program.constants.keys())
+                .chain(program.tables.keys())
+                .collect::<Vec<&String>>();
+
+            for pattern in api(&contents) {
+                // flags are only generated for the entries of the config
+                let optional =
+                    pattern.starts_with("FLAGS_") && !generated.config.contains_key("flags");
+                assert!(
+                    optional || names.iter().any(|name| instance(pattern, name)),
+                    "`{}` of {} is not defined with {:?}",
+                    pattern,
+                    file,
+                    config::canonical(&generated.config),
+                );
+            }
+        }
+        fs::remove_dir_all(&generated.dir).unwrap();
+    }
+}
+
+#[test]
+fn masks_are_as_wide_as_their_type() {
+    for generated in generated("masks") {
+        let profile = Profile::load(&generated.config).unwrap();
+        let mut masks = 0;
+
+        for (file, program) in &generated.files {
+            let contents = fs::read_to_string(generated.dir.join(file)).unwrap();
+            for name in program.macros.keys() {
+                let Some((bytes, left)) = mask_bytes(name) else {
+                    continue;
+                };
+                if !contents.contains(&format!("#define macro {}(", name)) {
+                    continue;
+                }
+
+                let mask = output(program, &profile, name).to_be_bytes();
+                let expected = if left { 0..bytes } else { 32 - bytes..32 };
+                for (index, byte) in mask.iter().enumerate() {
+                    let ff = if expected.contains(&index) {
+                        0xff
+                    } else {
+                        0x00
+                    };
+                    assert_eq!(*byte, ff, "byte {} of {} of {}", index, name, file);
+                }
+                masks += 1;
+            }
+        }
+
+        assert!(
+            masks > 0,
+            "no masks with {:?}",
+            config::canonical(&generated.config)
+        );
+        fs::remove_dir_all(&generated.dir).unwrap();
+    }
+}
+
+#[test]
+fn mini_casts_are_from_32_bits() {
+    for generated in generated("mini") {
+        let generator = Generator::from_config(generated.config.clone()).unwrap();
+        let sizes = generator.options().sizes().to_vec();
+        let (_, cast) = generated
+            .files
+            .iter()
+            .find(|(file, _)| file == "libcast.huff")
+            .unwrap();
+
+        for size in sizes {
+            for name in ["MINI_U{}_MASK", "MINI_TO_U{}", "UNSAFE_MINI_TO_U{}"] {
+                let name = name.replace("{}", &size.to_string());
+                assert_eq!(
+                    cast.macros.contains_key(&name),
+                    size >= 32,
+                    "{} is generated",
+                    name
+                );
+            }
+        }
+
+        for (file, program) in &generated.files {
+            for name in program
+                .macros
+                .keys()
+                .filter(|name| name.starts_with("MINI_U"))
+            {
+                let bits = name["MINI_U".len()..].split('_').next().unwrap();
+                assert!(
+                    bits.parse::<u16>().unwrap() >= 32,
+                    "{} of {} is generated",
+                    name,
+                    file
+                );
+            }
+        }
+        fs::remove_dir_all(&generated.dir).unwrap();
+    }
+}
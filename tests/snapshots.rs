@@ -0,0 +1,124 @@
+//! Compares the libraries generated for a selection of configs to their golden files in
+//! `tests/snapshots`, byte for byte, but for the provenance header.
+//!
+//! After an intended change to the output, rewrite the golden files with
+//! `UPDATE_SNAPSHOTS=1 cargo test --test snapshots` and review their diff.
+
+mod common;
+
+use std::fs;
+
+use huffgen::{diff, EvmVersion, Generator, Library, OverflowRevert};
+
+/// The snapshots, by golden file name, with the generator of their library.
+fn snapshots() -> Vec<(&'static str, Generator)> {
+    let library = |library| Generator::new().with_library(library);
+
+    vec![
+        (
+            "cast.huff",
+            library(Library::Cast).with_sizes(&[8, 32, 40, 256]),
+        ),
+        (
+            "cast-paris.huff",
+            library(Library::Cast)
+                .with_sizes(&[8, 32, 40, 256])
+                .with_evm_version(EvmVersion::Paris),
+        ),
+        (
+            "cast-panic.huff",
+            library(Library::Cast)
+                .with_sizes(&[8])
+                .with_overflow_revert(OverflowRevert::Panic),
+        ),
+        (
+            "signed.huff",
+            library(Library::Signed).with_sizes(&[8, 256]),
+        ),
+        (
+            "safemath.huff",
+            library(Library::SafeMath).with_sizes(&[8, 256]),
+        ),
+        (
+            "wrapping.huff",
+            library(Library::Wrapping).with_sizes(&[8, 256]),
+        ),
+        ("cmp.huff", library(Library::Cmp).with_sizes(&[8])),
+        (
+            "bytescast.huff",
+            library(Library::BytesCast).with_sizes(&[8, 256]),
+        ),
+        (
+            "calldata.huff",
+            library(Library::Calldata).with_sizes(&[8, 256]),
+        ),
+        ("bits.huff", library(Library::Bits)),
+        ("guard.huff", library(Library::Guard)),
+        (
+            "guard-cancun.huff",
+            library(Library::Guard).with_evm_version(EvmVersion::Cancun),
+        ),
+    ]
+}
+
+/// The file of the only library `generator` selects, without its provenance header or the
+/// libraries it includes.
+fn render(generator: &Generator) -> String {
+    let [library] = generator.libraries() else {
+        panic!("snapshots are of a single library");
+    };
+    let files = generator.render().unwrap();
+
+    files[&library.file_name()]
+        .split_inclusive('\n')
+        .skip_while(|line| line.starts_with("// @"))
+        .collect()
+}
+
+#[test]
+fn golden_files() {
+    let dir = common::manifest("tests/snapshots");
+    let update = std::env::var_os("UPDATE_SNAPSHOTS").is_some();
+
+    let mut stale = Vec::new();
+    for (name, generator) in snapshots() {
+        let path = dir.join(name);
+        let contents = render(&generator);
+        if update {
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(&path, &contents).unwrap();
+            continue;
+        }
+
+        let golden = fs::read_to_string(&path).unwrap_or_default();
+        stale.extend(diff::unified(
+            &format!("tests/snapshots/{}", name),
+            &golden,
+            &contents,
+        ));
+    }
+
+    assert!(
+        stale.is_empty(),
+        "{}\nthe output differs from the snapshots, rerun with `UPDATE_SNAPSHOTS=1` if intended",
+        stale.concat(),
+    );
+}
+
+#[test]
+fn every_golden_file_is_a_snapshot() {
+    let dir = common::manifest("tests/snapshots");
+    let names = snapshots()
+        .iter()
+        .map(|(name, _)| name.to_string())
+        .collect::<Vec<String>>();
+
+    for entry in fs::read_dir(dir).unwrap() {
+        let name = entry.unwrap().file_name().into_string().unwrap();
+        assert!(
+            names.contains(&name),
+            "`tests/snapshots/{}` is not a snapshot",
+            name
+        );
+    }
+}